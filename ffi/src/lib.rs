@@ -0,0 +1,8 @@
+/*!
+Static library with `extern "C"` functions of [pcics::ffi]
+
+`pcics` is `no_std` and has no panic handler, this crate links it with `std` to build
+`libpcics_ffi.a`: `cargo build -p pcics-ffi --release`.
+*/
+
+pub use pcics::ffi::*;
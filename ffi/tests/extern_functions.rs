@@ -0,0 +1,16 @@
+use pcics_ffi::*;
+
+const DATA: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../tests/data/device/8086_2030/config"
+));
+
+#[test]
+fn header_summary() {
+    let mut header = HeaderSummary::default();
+    let rc = unsafe { pcics_header_summary(DATA.as_ptr(), DATA.len(), &mut header) };
+    assert_eq!(0, rc);
+    assert_eq!((0x8086, 0x2030), (header.vendor_id, header.device_id));
+    let rc = unsafe { pcics_header_summary(core::ptr::null(), 0, &mut header) };
+    assert_eq!(-1, rc);
+}
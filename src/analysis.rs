@@ -0,0 +1,36 @@
+/*!
+## Cross-register and cross-device analysis
+
+Parsers in [capabilities](crate::capabilities) and
+[extended_capabilities](crate::extended_capabilities) decode registers of a single function.
+Many configuration rules, however, depend on several registers or on both link partners. This
+module collects such checks.
+
+- [Configuration space byte budget](budget)
+- [Duplicated capabilities](duplicates)
+- [Interrupt Message Numbers](interrupts)
+- [L1 PM Substates](l1_substates)
+- [Lanes](lanes)
+- [Root Complex Link Declaration addresses](link_declaration)
+- [Latency Tolerance Reporting and OBFF](ltr)
+- [Max_Payload_Size and Max_Read_Request_Size](payload)
+- [Multicast with ACS](multicast)
+- [Precision Time Measurement path](ptm)
+- [Routing IDs](routing)
+- [Tag field width](tags)
+- [TLP Processing Hints](tph)
+*/
+
+pub mod budget;
+pub mod duplicates;
+pub mod interrupts;
+pub mod l1_substates;
+pub mod lanes;
+pub mod link_declaration;
+pub mod ltr;
+pub mod multicast;
+pub mod payload;
+pub mod ptm;
+pub mod routing;
+pub mod tags;
+pub mod tph;
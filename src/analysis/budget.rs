@@ -0,0 +1,429 @@
+/*!
+# Configuration space byte budget
+
+Capabilities are placed anywhere in the 192 bytes of device dependent region, extended
+capabilities anywhere in the 3840 bytes of extended configuration space. Before a new structure
+is injected with [ConfigSpaceEditor](crate::ConfigSpaceEditor) or a synthetic image builder
+one needs to know which bytes are already taken.
+
+[ByteBudget] marks every DWORD covered by a structure of the list as used and counts DWORDs
+claimed by more than one structure as overlapped. There is no common length field, so the size
+of a structure is estimated from its ID:
+- mandatory registers of the decoded structure
+- registers selected by header fields: MSI 64-bit Address and Per-Vector Masking, PCI Express
+  Capability Version 2 registers, Virtual Channel resources, Resizable BAR entries
+- length fields of Vendor Specific, Vendor-Specific Extended and Designated Vendor-Specific
+  Extended Capabilities
+- header only for unknown or reserved IDs
+
+Variable-length tables of other structures are not counted, so the budget is a lower bound of
+used space. Iteration stops when a structure is visited again (looped list).
+
+## Examples
+
+```rust
+# use pcics::analysis::budget::*;
+# use pcics::{ConfigOffset, ConfigurationSpace};
+let mut data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_9dc8/config"
+))
+.to_vec();
+// Power Management (50h) -> Vendor Specific (80h) -> MSI (60h)
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let budget = ByteBudget::from(cs.capabilities());
+assert_eq!(192, budget.used() + budget.free());
+assert_eq!(0, budget.overlapped());
+// First hole of 0x10 bytes starts right after the header
+assert_eq!(Some(ConfigOffset(0x40)), budget.first_fit(0x10));
+
+// Budget of a list kept for iteration
+let capabilities = cs.capabilities();
+assert_eq!(budget, ByteBudget::from(&capabilities));
+assert_eq!(3, capabilities.count());
+
+// MSI moved to 54h overlaps Power Management Interface
+data[0x81] = 0x54;
+data.copy_within(0x60..0x6a, 0x54);
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let budget = ByteBudget::from(cs.capabilities());
+assert_eq!(4, budget.overlapped());
+assert!(budget.is_overlapped(ConfigOffset(0x54)));
+```
+*/
+
+use core::{fmt, ops::Range};
+
+use crate::{
+    capabilities::{
+        message_signaled_interrups::{
+            MSI_32BIT_PVM_SIZE, MSI_32BIT_SIZE, MSI_64BIT_PVM_SIZE, MSI_64BIT_SIZE,
+        },
+        *,
+    },
+    extended_capabilities::{self as ecaps, ExtendedCapability},
+    header::HeaderType,
+    ConfigOffset, ExtendedCapabilities, DDR_OFFSET, ECS_OFFSET, EXTENDED_CONFIG_SPACE_SIZE,
+};
+
+/// DWORDs in the whole configuration space
+const DWORDS: usize = EXTENDED_CONFIG_SPACE_SIZE / 4;
+
+/// DWORD set over the whole configuration space
+type Bitmap = [u64; DWORDS / 64];
+
+fn bit(bitmap: &[u64], n: usize) -> bool {
+    bitmap
+        .get(n / 64)
+        .is_some_and(|word| word & (1 << (n % 64)) != 0)
+}
+
+fn set_bit(bitmap: &mut [u64], n: usize) {
+    if let Some(word) = bitmap.get_mut(n / 64) {
+        *word |= 1 << (n % 64);
+    }
+}
+
+/// Configuration space region holding a capabilities list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// Device dependent region (40h-FFh), Capabilities List
+    DeviceDependent,
+    /// Extended configuration space (100h-FFFh), Extended Capabilities List
+    Extended,
+}
+
+impl Region {
+    /// Absolute offsets of the region
+    pub const fn range(self) -> Range<usize> {
+        match self {
+            Self::DeviceDependent => DDR_OFFSET..ECS_OFFSET,
+            Self::Extended => ECS_OFFSET..EXTENDED_CONFIG_SPACE_SIZE,
+        }
+    }
+    /// Region length in bytes
+    pub const fn size(self) -> usize {
+        let Range { start, end } = self.range();
+        end - start
+    }
+}
+
+/// Space used by a capabilities list, see [module](self) documentation
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ByteBudget {
+    pub region: Region,
+    used: Bitmap,
+    overlapped: Bitmap,
+}
+
+impl ByteBudget {
+    /// Region without structures
+    pub const fn new(region: Region) -> Self {
+        Self {
+            region,
+            used: [0; DWORDS / 64],
+            overlapped: [0; DWORDS / 64],
+        }
+    }
+    /// Marks `size` bytes at `offset` as used by one structure, bytes outside of the region are
+    /// ignored
+    pub fn insert(&mut self, offset: ConfigOffset, size: usize) {
+        let Range { start, end } = self.region.range();
+        let offset = offset.get() as usize;
+        let first = offset.max(start) / 4;
+        let last = offset.saturating_add(size.max(1)).min(end).div_ceil(4);
+        for dword in first..last {
+            if bit(&self.used, dword) {
+                set_bit(&mut self.overlapped, dword);
+            }
+            set_bit(&mut self.used, dword);
+        }
+    }
+    /// Bytes used by structures
+    pub fn used(&self) -> usize {
+        self.count(&self.used)
+    }
+    /// Bytes not used by any structure
+    pub fn free(&self) -> usize {
+        self.region.size() - self.used()
+    }
+    /// Bytes claimed by more than one structure
+    pub fn overlapped(&self) -> usize {
+        self.count(&self.overlapped)
+    }
+    /// DWORD at `offset` is used by a structure
+    pub fn is_used(&self, offset: ConfigOffset) -> bool {
+        self.region.range().contains(&(offset.get() as usize))
+            && bit(&self.used, offset.get() as usize / 4)
+    }
+    /// DWORD at `offset` is claimed by more than one structure
+    pub fn is_overlapped(&self, offset: ConfigOffset) -> bool {
+        self.region.range().contains(&(offset.get() as usize))
+            && bit(&self.overlapped, offset.get() as usize / 4)
+    }
+    /// Lowest DWORD aligned offset with `size` free bytes
+    pub fn first_fit(&self, size: usize) -> Option<ConfigOffset> {
+        let Range { start, end } = self.region.range();
+        let needed = size.max(1).div_ceil(4);
+        let mut hole = start / 4;
+        for dword in start / 4..end / 4 {
+            if bit(&self.used, dword) {
+                hole = dword + 1;
+            } else if dword + 1 - hole == needed {
+                return Some(ConfigOffset((hole * 4) as u16));
+            }
+        }
+        None
+    }
+    fn count(&self, bitmap: &Bitmap) -> usize {
+        let Range { start, end } = self.region.range();
+        (start / 4..end / 4)
+            .filter(|&dword| bit(bitmap, dword))
+            .count()
+            * 4
+    }
+}
+
+impl fmt::Debug for ByteBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ByteBudget")
+            .field("region", &self.region)
+            .field("length", &self.region.size())
+            .field("used", &self.used())
+            .field("free", &self.free())
+            .field("overlapped", &self.overlapped())
+            .finish()
+    }
+}
+
+impl From<Capabilities<'_>> for ByteBudget {
+    fn from(capabilities: Capabilities<'_>) -> Self {
+        Self::from(&capabilities)
+    }
+}
+
+impl From<&Capabilities<'_>> for ByteBudget {
+    fn from(capabilities: &Capabilities<'_>) -> Self {
+        let capabilities = *capabilities;
+        let (data, header) = capabilities.parts();
+        let mut budget = Self::new(Region::DeviceDependent);
+        let mut visited = [0; EXTENDED_CONFIG_SPACE_SIZE / 64];
+        for (offset, CapabilityHeader { id, .. }) in capabilities.offsets() {
+            if bit(&visited, offset.get() as usize) {
+                break;
+            }
+            set_bit(&mut visited, offset.get() as usize);
+            let ddr = offset.ddr_relative().unwrap_or_default();
+            let byte = |n: usize| data.get(ddr + n).copied().unwrap_or_default();
+            let size = match id {
+                0x01 => PowerManagementInterface::MIN_SIZE,
+                0x02 => AcceleratedGraphicsPort::MIN_SIZE,
+                0x03 => VitalProductData::MIN_SIZE,
+                0x04 => SlotIdentification::MIN_SIZE,
+                // 64 bit Address Capable, Per-Vector Masking Capable
+                0x05 => match (byte(2) & 0x80 != 0, byte(3) & 0x01 != 0) {
+                    (false, false) => MSI_32BIT_SIZE,
+                    (true, false) => MSI_64BIT_SIZE,
+                    (false, true) => MSI_32BIT_PVM_SIZE,
+                    (true, true) => MSI_64BIT_PVM_SIZE,
+                },
+                0x07 if matches!(header.header_type, HeaderType::Bridge(_)) => PciXBridge::MIN_SIZE,
+                0x07 => PciX::MIN_SIZE,
+                // Capability Length counts the capability header too
+                0x09 => (byte(2) as usize).saturating_sub(Capability::HEADER_SIZE),
+                0x0a => DebugPort::MIN_SIZE,
+                0x0c => PciHotPlug::MIN_SIZE,
+                0x0d => BridgeSubsystemVendorId::MIN_SIZE,
+                // Registers after Root Status are defined by Capability Version 2
+                0x10 if byte(2) & 0x0f >= 2 => PciExpress::SIZE,
+                0x10 => PciExpress::MIN_SIZE,
+                0x11 => MsiX::MIN_SIZE,
+                0x12 => Sata::MIN_SIZE,
+                0x13 => AdvancedFeatures::MIN_SIZE,
+                0x14 => EnhancedAllocation::MIN_SIZE,
+                0x15 => FlatteningPortalBridge::MIN_SIZE,
+                _ => 0,
+            };
+            budget.insert(offset, Capability::HEADER_SIZE + size);
+        }
+        budget
+    }
+}
+
+impl From<ExtendedCapabilities<'_>> for ByteBudget {
+    fn from(ecaps: ExtendedCapabilities<'_>) -> Self {
+        Self::from(&ecaps)
+    }
+}
+
+impl From<&ExtendedCapabilities<'_>> for ByteBudget {
+    fn from(ecaps: &ExtendedCapabilities<'_>) -> Self {
+        let ecaps = *ecaps;
+        let data = ecaps.data();
+        let mut budget = Self::new(Region::Extended);
+        let mut visited = [0; EXTENDED_CONFIG_SPACE_SIZE / 64];
+        for (offset, header) in ecaps.headers() {
+            if bit(&visited, offset.get() as usize) {
+                break;
+            }
+            set_bit(&mut visited, offset.get() as usize);
+            let ecs = offset.ecs_relative().unwrap_or_default();
+            let dword = |n: usize| {
+                data.get(ecs + n..ecs + n + 4)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u32::from_le_bytes)
+                    .unwrap_or_default()
+            };
+            // Structures decoded from the data after the header
+            let data_size = match header.extended_capability_id {
+                0x0001 => ecaps::AdvancedErrorReporting::MIN_SIZE,
+                // Port VC Capability Register 1 and VC Resource registers of VC0 and extended VCs
+                0x0002 | 0x0008 | 0x0009 => {
+                    ecaps::VirtualChannel::MIN_SIZE + 0x0c * (1 + (dword(4) & 0b111) as usize)
+                }
+                0x0003 => ecaps::DeviceSerialNumber::MIN_SIZE,
+                0x0004 => ecaps::PowerBudgeting::MIN_SIZE,
+                0x0005 => ecaps::RootComplexLinkDeclaration::MIN_SIZE,
+                0x0006 => ecaps::RootComplexInternalLinkControl::MIN_SIZE,
+                0x0007 => ecaps::RootComplexEventCollectorEndpointAssociation::MIN_SIZE,
+                0x000d => ecaps::AccessControlServices::MIN_SIZE,
+                0x000e => ecaps::AlternativeRoutingIdInterpretation::MIN_SIZE,
+                0x000f => ecaps::AddressTranslationServices::MIN_SIZE,
+                0x0010 => ecaps::SingleRootIoVirtualization::MIN_SIZE,
+                0x0011 => ecaps::MultiRootIoVirtualization::MIN_SIZE,
+                0x0013 => ecaps::PageRequestInterface::MIN_SIZE,
+                0x0017 => ecaps::TphRequester::MIN_SIZE,
+                0x0018 => ecaps::LatencyToleranceReporting::MIN_SIZE,
+                0x0019 => ecaps::SecondaryPciExpress::MIN_SIZE,
+                0x001b => ecaps::ProcessAddressSpaceId::MIN_SIZE,
+                0x001d => ecaps::DownstreamPortContainment::MIN_SIZE,
+                0x001e => ecaps::L1PmSubstates::MIN_SIZE,
+                0x001f => ecaps::PrecisionTimeMeasurement::MIN_SIZE,
+                0x0025 => ecaps::DataLinkFeature::MIN_SIZE,
+                0x0026 => ecaps::PhysicalLayer16GTps::MIN_SIZE,
+                0x0027 => ecaps::LaneMarginingAtTheReceiver::MIN_SIZE,
+                0x0029 => ecaps::NativePcieEnclosureManagement::MIN_SIZE,
+                0x002a => ecaps::PhysicalLayer32GTps::MIN_SIZE,
+                0x002e => ecaps::DataObjectExchange::MIN_SIZE,
+                _ => 0,
+            };
+            // Structures decoded with the header
+            let size = match header.extended_capability_id {
+                0x000a => ecaps::RootComplexRegisterBlockHeader::MIN_SIZE,
+                // VSEC Length and DVSEC Length count the header too
+                0x000b | 0x0023 => (dword(4) >> 20) as usize,
+                0x000c => ecaps::ConfigurationAccessCorrelation::MIN_SIZE,
+                0x0012 => ecaps::Multicast::MIN_SIZE,
+                // Number of Resizable BARs in the first Control register
+                0x0015 | 0x0024 => {
+                    let bars = (dword(8) >> 5 & 0b111).max(1) as usize;
+                    ExtendedCapability::HEADER_SIZE + bars * ecaps::ResizableBar::ENTRY_SIZE
+                }
+                0x0016 => ecaps::DynamicPowerAllocation::MIN_SIZE,
+                0x001a => ecaps::ProtocolMultiplexing::MIN_SIZE,
+                0x001c => ecaps::LnRequester::MIN_SIZE,
+                0x0020 => ecaps::PciExpressOverMphy::MIN_SIZE,
+                0x0021 => ecaps::FrsQueuing::MIN_SIZE,
+                0x0022 => ecaps::ReadinessTimeReporting::MIN_SIZE,
+                _ => ExtendedCapability::HEADER_SIZE + data_size,
+            };
+            budget.insert(offset, size.max(ExtendedCapability::HEADER_SIZE));
+        }
+        budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{synthetic, ConfigurationSpace, DDR_LENGTH, ECS_LENGTH};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn insert() {
+        let mut budget = ByteBudget::new(Region::DeviceDependent);
+        assert_eq!(DDR_LENGTH, budget.free());
+        budget.insert(ConfigOffset(0x40), 0x0a);
+        assert_eq!(12, budget.used());
+        budget.insert(ConfigOffset(0x48), 0x08);
+        assert_eq!((16, 4), (budget.used(), budget.overlapped()));
+        assert!(budget.is_overlapped(ConfigOffset(0x4b)));
+        assert!(!budget.is_overlapped(ConfigOffset(0x4c)));
+        assert_eq!(Some(ConfigOffset(0x50)), budget.first_fit(0xb0));
+        assert_eq!(None, budget.first_fit(0xb1));
+        // Header and extended configuration space bytes are out of the region
+        budget.insert(ConfigOffset(0x3c), 0x08);
+        budget.insert(ConfigOffset(0xfc), 0x08);
+        assert_eq!((20, 8), (budget.used(), budget.overlapped()));
+        assert!(!budget.is_used(ConfigOffset(0x3c)));
+        assert!(!budget.is_used(ConfigOffset(0x100)));
+    }
+
+    #[test]
+    fn capabilities() {
+        // MSI-X at 0x80 (12 bytes) -> PCI Express v2 at 0x40 (60 bytes)
+        let data = synthetic::endpoint_with_msix(1);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let budget = ByteBudget::from(cs.capabilities());
+        let sample = "ByteBudget { region: DeviceDependent, length: 192, used: 72, free: 120, \
+            overlapped: 0 }";
+        assert_eq!(sample, format!("{:?}", budget));
+        assert_eq!(Some(ConfigOffset(0x7c)), budget.first_fit(4));
+        assert_eq!(Some(ConfigOffset(0x8c)), budget.first_fit(8));
+
+        // Looped list is counted once
+        let mut data = data;
+        data[synthetic::INTERRUPT_PTR as usize + 1] = synthetic::PCIE_PTR;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(budget, ByteBudget::from(cs.capabilities()));
+    }
+
+    #[test]
+    fn extended_capabilities() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let budget = ByteBudget::from(cs.extended_capabilities().unwrap());
+        assert_eq!(ECS_LENGTH, budget.used() + budget.free());
+        assert_eq!(0, budget.overlapped());
+        assert!(budget.is_used(ConfigOffset(0x100)));
+        let mut ecaps = cs.extended_capabilities().unwrap();
+        assert_eq!(budget, ByteBudget::from(&ecaps));
+        assert!(ecaps.all(|ecap| ecap.is_ok()));
+
+        // Vendor-Specific at 100h claiming 0x50 bytes
+        data[0x100..0x102].copy_from_slice(&0x000bu16.to_le_bytes());
+        data[0x104..0x108].copy_from_slice(&(0x50u32 << 20).to_le_bytes());
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = ByteBudget::from(cs.extended_capabilities().unwrap());
+        assert!(result.overlapped() > 0);
+        assert!(result.is_overlapped(ConfigOffset(0x148)));
+    }
+
+    #[test]
+    fn synthetic_extended_capabilities() {
+        let functions = [
+            (
+                synthetic::endpoint_with_extended_capabilities(),
+                synthetic::ENDPOINT_EXTENDED_CAPABILITIES.as_slice(),
+            ),
+            (
+                synthetic::root_port_with_extended_capabilities(),
+                synthetic::ROOT_PORT_EXTENDED_CAPABILITIES.as_slice(),
+            ),
+        ];
+        for (data, ecaps) in functions {
+            let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+            let budget = ByteBudget::from(cs.extended_capabilities().unwrap());
+            assert_eq!(0, budget.overlapped());
+            // Registers after the header are counted for every decoded capability
+            for &(offset, id) in ecaps {
+                let offset = ConfigOffset(offset + ExtendedCapability::HEADER_SIZE as u16);
+                assert!(
+                    budget.is_used(offset),
+                    "{:04x} registers are not counted",
+                    id
+                );
+            }
+        }
+    }
+}
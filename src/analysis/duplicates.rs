@@ -0,0 +1,247 @@
+/*!
+# Duplicated capabilities
+
+Capability lists are plain linked lists, nothing prevents a function from linking two
+structures with the same ID. [Capabilities](crate::Capabilities) and
+[ExtendedCapabilities](crate::ExtendedCapabilities) return every structure they find, so a
+consumer looking for "the" Power Management Capability silently gets the first or the last one.
+
+Most capabilities must be implemented at most once per function. Exceptions are the structures
+defined to be repeated:
+- Vendor Specific (09h) and HyperTransport (08h) Capabilities
+- Vendor-Specific (000Bh) and Designated Vendor-Specific (0023h) Extended Capabilities
+
+[Duplicates] reports every other repeated ID together with the offset of its first occurrence,
+so tools can flag the firmware bug.
+
+## Examples
+
+```rust
+# use pcics::analysis::duplicates::*;
+# use pcics::{ConfigOffset, ConfigurationSpace};
+let mut data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_9dc8/config"
+))
+.to_vec();
+// Power Management (50h) -> Vendor Specific (80h) -> MSI (60h), MSI is replaced with PM
+data[0x60] = 0x01;
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let result = Duplicates::capabilities(&cs).collect::<Vec<_>>();
+let sample = vec![Duplicate {
+    id: 0x01,
+    first: ConfigOffset(0x50),
+    offset: ConfigOffset(0x60),
+}];
+assert_eq!(sample, result);
+```
+*/
+
+use crate::{
+    capabilities::{CapabilityHeader, CapabilityOffsets},
+    extended_capabilities::{ExtendedCapabilityHeader, ExtendedCapabilityHeaders},
+    ConfigOffset, ConfigurationSpace, ExtendedCapabilities,
+};
+
+/// Header of a capabilities list entry
+pub trait ListEntry: Copy {
+    /// Capability ID type
+    type Id: Copy + PartialEq;
+    /// Capability ID
+    fn id(&self) -> Self::Id;
+    /// Structures with this ID may be repeated in the list
+    fn may_repeat(&self) -> bool;
+}
+
+impl ListEntry for CapabilityHeader {
+    type Id = u8;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+    fn may_repeat(&self) -> bool {
+        // HyperTransport, Vendor Specific
+        matches!(self.id, 0x08 | 0x09)
+    }
+}
+
+impl ListEntry for ExtendedCapabilityHeader {
+    type Id = u16;
+
+    fn id(&self) -> Self::Id {
+        self.extended_capability_id
+    }
+    fn may_repeat(&self) -> bool {
+        // Vendor-Specific, Designated Vendor-Specific
+        matches!(self.extended_capability_id, 0x000b | 0x0023)
+    }
+}
+
+/// Capability ID found at more than one offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Duplicate<T> {
+    /// Capability ID
+    pub id: T,
+    /// Offset of the first structure with this ID in list order
+    pub first: ConfigOffset,
+    /// Offset of the repeated structure
+    pub offset: ConfigOffset,
+}
+
+/// An iterator through repeated capabilities of a list, see [module](self) documentation
+///
+/// Every repetition is reported. Iteration stops when a structure is visited again (looped
+/// list), it is not a duplicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicates<I> {
+    list: I,
+    iter: I,
+    /// Number of entries taken from `iter`
+    position: usize,
+}
+
+impl<I> Duplicates<I>
+where
+    I: Clone,
+{
+    /// Repeated entries of `list`
+    pub fn new(list: I) -> Self {
+        Self {
+            iter: list.clone(),
+            list,
+            position: 0,
+        }
+    }
+}
+
+impl<'a> Duplicates<CapabilityOffsets<'a>> {
+    /// Repeated capabilities of the Capabilities List
+    pub fn capabilities(cs: &'a ConfigurationSpace) -> Self {
+        Self::new(cs.capabilities().offsets())
+    }
+}
+
+impl<'a> Duplicates<ExtendedCapabilityHeaders<'a>> {
+    /// Repeated extended capabilities, the iterator is empty if there is no extended
+    /// configuration space
+    pub fn extended_capabilities(cs: &ConfigurationSpace<'a>) -> Self {
+        let ecaps = cs
+            .extended_capabilities()
+            .unwrap_or_else(|| ExtendedCapabilities::new(&[]));
+        Self::new(ecaps.headers())
+    }
+}
+
+impl<I, T> Iterator for Duplicates<I>
+where
+    I: Iterator<Item = (ConfigOffset, T)> + Clone,
+    T: ListEntry,
+{
+    type Item = Duplicate<T::Id>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (offset, entry) in self.iter.by_ref() {
+            let previous = self.list.clone().take(self.position);
+            self.position += 1;
+            let mut first = None;
+            for (o, e) in previous {
+                if o == offset {
+                    // Looped list, the rest are repeated entries
+                    self.iter.by_ref().for_each(drop);
+                    return None;
+                }
+                if first.is_none() && e.id() == entry.id() {
+                    first = Some(o);
+                }
+            }
+            if let Some(first) = first.filter(|_| !entry.may_repeat()) {
+                return Some(Duplicate {
+                    id: entry.id(),
+                    first,
+                    offset,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capabilities::CapabilityKind, synthetic};
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    fn capability(data: &mut [u8], ptr: u8, id: u8, next: u8) {
+        data[ptr as usize] = id;
+        data[ptr as usize + 1] = next;
+    }
+
+    #[test]
+    fn capabilities() {
+        // MSI-X -> PM -> PM -> Vendor Specific -> Vendor Specific
+        let mut data = synthetic::endpoint_with_msix(1);
+        capability(&mut data, synthetic::INTERRUPT_PTR, 0x11, 0x90);
+        capability(&mut data, 0x90, 0x01, 0xa0);
+        capability(&mut data, 0xa0, 0x01, 0xb0);
+        capability(&mut data, 0xb0, 0x09, 0xc0);
+        capability(&mut data, 0xc0, 0x09, 0x00);
+        data[0xb2] = 3;
+        data[0xc2] = 3;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+
+        // Both Power Management Capabilities are decoded
+        let pm = cs
+            .capabilities()
+            .filter_map(|cap| {
+                let cap = cap.unwrap();
+                matches!(cap.kind, CapabilityKind::PowerManagementInterface(_))
+                    .then_some(cap.pointer)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![ConfigOffset(0x90), ConfigOffset(0xa0)], pm);
+
+        let result = Duplicates::capabilities(&cs).collect::<Vec<_>>();
+        let sample = vec![Duplicate {
+            id: 0x01,
+            first: ConfigOffset(0x90),
+            offset: ConfigOffset(0xa0),
+        }];
+        assert_eq!(sample, result);
+
+        // Last Vendor Specific points back to the first PM
+        data[0xc1] = 0x90;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(sample, Duplicates::capabilities(&cs).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn extended_capabilities() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, Duplicates::extended_capabilities(&cs).next());
+
+        // LTR and TPH Requester become AER, PTM and Multicast become Vendor-Specific
+        for (offset, id) in [
+            (0x1b0, 0x0001u16),
+            (0x1b8, 0x0001),
+            (0x1e8, 0x000b),
+            (0x1f8, 0x000b),
+        ] {
+            data[offset..offset + 2].copy_from_slice(&id.to_le_bytes());
+        }
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = Duplicates::extended_capabilities(&cs).collect::<Vec<_>>();
+        let duplicate = |offset| Duplicate {
+            id: 0x0001,
+            first: ConfigOffset(0x100),
+            offset: ConfigOffset(offset),
+        };
+        assert_eq!(vec![duplicate(0x1b0), duplicate(0x1b8)], result);
+
+        // Conventional configuration space only
+        let cs = ConfigurationSpace::try_from(&data[..0x100]).unwrap();
+        assert_eq!(None, Duplicates::extended_capabilities(&cs).next());
+    }
+}
@@ -0,0 +1,205 @@
+/*!
+# Interrupt Message Numbers
+
+Several capabilities report which MSI or MSI-X vector is used for their interrupts, e.g.
+Advanced Error Interrupt Message Number of the AER Root Error Status register. The field refers
+to:
+- the MSI-X Table entry if MSI-X is enabled or MSI is not implemented, it must be less than
+  MSI-X Table Size
+- the offset from the base MSI Message Data otherwise, it must be less than the number of
+  vectors allocated by Multiple Message Enable
+
+[InterruptVectors] selects the mechanism, [AdvancedErrorInterrupt] checks the AER field
+against it.
+
+## Examples
+
+```rust
+# use pcics::analysis::interrupts::*;
+# use pcics::capabilities::MsiX;
+// MSI-X Table has 4 entries
+let msi_x = MsiX::from([0x03, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30, 0x00, 0x00]);
+let vectors = InterruptVectors::new(None, Some(&msi_x)).unwrap();
+assert_eq!(InterruptVectors::MsiX(4), vectors);
+assert!(vectors.contains(3));
+assert!(!vectors.contains(4));
+```
+*/
+
+use crate::{
+    capabilities::{pci_express::DeviceType, CapabilityKind, MessageSignaledInterrups, MsiX},
+    extended_capabilities::{AdvancedErrorReporting, ExtendedCapabilityKind},
+    ConfigurationSpace,
+};
+
+/// Vectors the Interrupt Message Number fields of a Function refer to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptVectors {
+    /// Number of MSI vectors allocated
+    Msi(u8),
+    /// Number of MSI-X Table entries
+    MsiX(u16),
+}
+
+impl InterruptVectors {
+    /// Vectors of MSI-X if it is enabled or MSI is not implemented, vectors of MSI otherwise.
+    /// `None` if neither is implemented.
+    pub fn new(msi: Option<&MessageSignaledInterrups>, msi_x: Option<&MsiX>) -> Option<Self> {
+        match (msi, msi_x.map(|msi_x| &msi_x.message_control)) {
+            (_, Some(mc)) if mc.msi_x_enable || msi.is_none() => {
+                // Table Size is encoded as N-1
+                Some(Self::MsiX(mc.table_size + 1))
+            }
+            (Some(msi), _) => Some(Self::Msi(
+                msi.message_control
+                    .multiple_message_enable
+                    .number_of_vectors(),
+            )),
+            (None, _) => None,
+        }
+    }
+    /// Vectors of MSI and MSI-X Capabilities of the Function
+    pub fn from_configuration_space(cs: &ConfigurationSpace) -> Option<Self> {
+        let (mut msi, mut msi_x) = (None, None);
+        for cap in cs.capabilities().flatten() {
+            match cap.kind {
+                CapabilityKind::MessageSignaledInterrups(v) => msi = Some(v),
+                CapabilityKind::MsiX(v) => msi_x = Some(v),
+                _ => (),
+            }
+        }
+        Self::new(msi.as_ref(), msi_x.as_ref())
+    }
+    /// Number of vectors
+    pub fn count(&self) -> u16 {
+        match *self {
+            Self::Msi(n) => n as u16,
+            Self::MsiX(n) => n,
+        }
+    }
+    /// `message_number` refers to an available vector
+    pub fn contains(&self, message_number: u16) -> bool {
+        message_number < self.count()
+    }
+}
+
+/// Advanced Error Interrupt Message Number of a Root Port or Root Complex Event Collector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdvancedErrorInterrupt {
+    /// Advanced Error Interrupt Message Number
+    pub message_number: u8,
+    /// Vectors the message number refers to
+    pub vectors: Option<InterruptVectors>,
+}
+
+impl AdvancedErrorInterrupt {
+    /// `None` if `aer` has no Root Error Status register
+    pub fn new(aer: &AdvancedErrorReporting, vectors: Option<InterruptVectors>) -> Option<Self> {
+        let status = aer.root_error_status.as_ref()?;
+        Some(Self {
+            message_number: status.advanced_error_interrupt_message_number,
+            vectors,
+        })
+    }
+    /// `None` if the Function is not a Root Port or Root Complex Event Collector with AER
+    /// Capability, extended capabilities parsing errors are skipped
+    ///
+    /// Root Error Status register is decoded whenever AER data is long enough, so the device
+    /// type is taken from the PCI Express Capability.
+    pub fn from_configuration_space(cs: &ConfigurationSpace) -> Option<Self> {
+        cs.capabilities()
+            .find_map(|cap| match cap.ok()?.kind {
+                CapabilityKind::PciExpress(pcie) => Some(pcie.device_type),
+                _ => None,
+            })
+            .filter(|device_type| {
+                matches!(
+                    device_type,
+                    DeviceType::RootPort { .. } | DeviceType::RootComplexEventCollector { .. }
+                )
+            })?;
+        let aer = cs
+            .extended_capabilities()?
+            .find_map(|ecap| match ecap.ok()?.kind {
+                ExtendedCapabilityKind::AdvancedErrorReporting(aer) => Some(aer),
+                _ => None,
+            })?;
+        Self::new(&aer, InterruptVectors::from_configuration_space(cs))
+    }
+    /// Message number refers to a vector the Function does not have, or the Function has
+    /// neither MSI nor MSI-X
+    pub fn is_out_of_range(&self) -> bool {
+        !self
+            .vectors
+            .is_some_and(|vectors| vectors.contains(self.message_number as u16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic;
+    use pretty_assertions::assert_eq;
+
+    fn msi(message_control: u16) -> MessageSignaledInterrups {
+        let mut data = [0u8; MessageSignaledInterrups::MIN_SIZE];
+        data[..2].copy_from_slice(&message_control.to_le_bytes());
+        MessageSignaledInterrups::try_from(data.as_slice()).unwrap()
+    }
+
+    fn msi_x(message_control: u16) -> MsiX {
+        let mut data = [0; MsiX::SIZE];
+        data[..2].copy_from_slice(&message_control.to_le_bytes());
+        MsiX::from(data)
+    }
+
+    #[test]
+    fn interrupt_vectors() {
+        assert_eq!(None, InterruptVectors::new(None, None));
+        // Multiple Message Capable 8, Multiple Message Enable 4
+        let msi = msi(0b010 << 4 | 0b011 << 1);
+        assert_eq!(
+            Some(InterruptVectors::Msi(4)),
+            InterruptVectors::new(Some(&msi), None)
+        );
+        // MSI-X with 16 entries is disabled
+        assert_eq!(
+            Some(InterruptVectors::Msi(4)),
+            InterruptVectors::new(Some(&msi), Some(&msi_x(15)))
+        );
+        assert_eq!(
+            Some(InterruptVectors::MsiX(16)),
+            InterruptVectors::new(Some(&msi), Some(&msi_x(15 | 1 << 15)))
+        );
+        assert_eq!(
+            Some(InterruptVectors::MsiX(16)),
+            InterruptVectors::new(None, Some(&msi_x(15)))
+        );
+    }
+
+    #[test]
+    fn advanced_error_interrupt() {
+        // Root Port with single MSI vector and Interrupt Message Number 0
+        let mut data = synthetic::root_port_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = AdvancedErrorInterrupt::from_configuration_space(&cs).unwrap();
+        let sample = AdvancedErrorInterrupt {
+            message_number: 0,
+            vectors: Some(InterruptVectors::Msi(1)),
+        };
+        assert_eq!(sample, result);
+        assert!(!result.is_out_of_range());
+
+        // Root Error Status: Advanced Error Interrupt Message Number 1
+        data[0x130 + 3] = 1 << 3;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = AdvancedErrorInterrupt::from_configuration_space(&cs).unwrap();
+        assert_eq!(1, result.message_number);
+        assert!(result.is_out_of_range());
+
+        // Endpoint AER has no Root Error Status register
+        let data = synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, AdvancedErrorInterrupt::from_configuration_space(&cs));
+    }
+}
@@ -0,0 +1,269 @@
+/*!
+# L1 PM Substates
+
+L1.1 and L1.2 are substates of the L1 Link state, the Link gets there either from ASPM L1 or
+from PCI-PM L1 entered when the Function leaves D0. A substate can be enabled only if both
+components on the Link implement the
+[L1 PM Substates Capability](crate::extended_capabilities::l1_pm_substates) and report the
+substate as supported. ASPM substates additionally take effect only while ASPM L1 is supported
+and enabled in Link Control of both components, PCI-PM L1 needs no enable.
+
+[L1Substate::check] tells whether enabling a substate on the Link is valid, [ValidL1Substates]
+collects the result for every substate.
+
+## Examples
+
+```rust
+# use pcics::analysis::l1_substates::*;
+# use pcics::capabilities::pci_express::Link;
+# use pcics::extended_capabilities::L1PmSubstates;
+// All substates supported, ASPM L1 supported
+let l1_pm_substates = L1PmSubstates::try_from([0x1f, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0].as_slice()).unwrap();
+let caps = 0b10 << 10;
+// Root Port has ASPM L1 enabled, Endpoint has ASPM disabled
+let root_port = Link::new(caps, 0b10, 0);
+let endpoint = Link::new(caps, 0b00, 0);
+let upstream = L1SubstatesComponent::new(&root_port, Some(&l1_pm_substates));
+let downstream = L1SubstatesComponent::new(&endpoint, Some(&l1_pm_substates));
+
+assert_eq!(Ok(()), L1Substate::PciPmL1_2.check(upstream, downstream));
+assert_eq!(
+    Err(L1SubstateError::AspmL1Disabled { component: LinkComponent::Downstream }),
+    L1Substate::AspmL1_1.check(upstream, downstream)
+);
+let sample = ValidL1Substates {
+    aspm_l1_1: false,
+    aspm_l1_2: false,
+    pci_pm_l1_1: true,
+    pci_pm_l1_2: true,
+};
+assert_eq!(sample, ValidL1Substates::new(upstream, downstream));
+```
+*/
+
+use snafu::prelude::*;
+
+use crate::{
+    capabilities::pci_express::{ActiveStatePowerManagement, Link},
+    extended_capabilities::l1_pm_substates::L1PmSubstates,
+};
+
+/// Component on one end of the Link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1SubstatesComponent<'a> {
+    /// Link registers of the PCI Express Capability
+    pub link: &'a Link,
+    /// L1 PM Substates Capability, `None` if not implemented
+    pub l1_pm_substates: Option<&'a L1PmSubstates>,
+}
+
+impl<'a> L1SubstatesComponent<'a> {
+    pub fn new(link: &'a Link, l1_pm_substates: Option<&'a L1PmSubstates>) -> Self {
+        Self {
+            link,
+            l1_pm_substates,
+        }
+    }
+}
+
+/// Component of the Link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkComponent {
+    /// Downstream Port, e.g. Root Port
+    Upstream,
+    /// Upstream Port of the component below, e.g. Endpoint
+    Downstream,
+}
+
+impl core::fmt::Display for LinkComponent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Upstream => write!(f, "upstream component"),
+            Self::Downstream => write!(f, "downstream component"),
+        }
+    }
+}
+
+/// Reasons a substate can not be enabled on the Link
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum L1SubstateError {
+    #[snafu(display("{component} has no L1 PM Substates Capability"))]
+    NoCapability { component: LinkComponent },
+    #[snafu(display("{component} does not support the substate"))]
+    Unsupported { component: LinkComponent },
+    #[snafu(display("{component} does not support ASPM L1"))]
+    AspmL1Unsupported { component: LinkComponent },
+    #[snafu(display("{component} has ASPM L1 disabled"))]
+    AspmL1Disabled { component: LinkComponent },
+}
+
+/// L1 PM Substate
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1Substate {
+    AspmL1_1,
+    AspmL1_2,
+    PciPmL1_1,
+    PciPmL1_2,
+}
+
+impl L1Substate {
+    /// Checks whether the substate may be enabled on the Link between `upstream` and
+    /// `downstream` components
+    pub fn check(
+        self,
+        upstream: L1SubstatesComponent,
+        downstream: L1SubstatesComponent,
+    ) -> Result<(), L1SubstateError> {
+        for (component, partner) in [
+            (LinkComponent::Upstream, upstream),
+            (LinkComponent::Downstream, downstream),
+        ] {
+            let caps = &partner
+                .l1_pm_substates
+                .context(NoCapabilitySnafu { component })?
+                .l1_pm_substates_capabilities;
+            let supported = match self {
+                Self::AspmL1_1 => caps.aspm_l1_1_supported,
+                Self::AspmL1_2 => caps.aspm_l1_2_supported,
+                Self::PciPmL1_1 => caps.pci_pm_l1_1_supported,
+                Self::PciPmL1_2 => caps.pci_pm_l1_2_supported,
+            };
+            ensure!(
+                caps.l1_pm_substates_supported && supported,
+                UnsupportedSnafu { component }
+            );
+            if matches!(self, Self::AspmL1_1 | Self::AspmL1_2) {
+                let link = partner.link;
+                ensure!(
+                    has_l1(&link.capabilities.active_state_power_management_support),
+                    AspmL1UnsupportedSnafu { component }
+                );
+                ensure!(
+                    has_l1(&link.control.active_state_power_management_control),
+                    AspmL1DisabledSnafu { component }
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Substates which may be enabled on the Link
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ValidL1Substates {
+    pub aspm_l1_1: bool,
+    pub aspm_l1_2: bool,
+    pub pci_pm_l1_1: bool,
+    pub pci_pm_l1_2: bool,
+}
+
+impl ValidL1Substates {
+    pub fn new(upstream: L1SubstatesComponent, downstream: L1SubstatesComponent) -> Self {
+        let valid = |substate: L1Substate| substate.check(upstream, downstream).is_ok();
+        Self {
+            aspm_l1_1: valid(L1Substate::AspmL1_1),
+            aspm_l1_2: valid(L1Substate::AspmL1_2),
+            pci_pm_l1_1: valid(L1Substate::PciPmL1_1),
+            pci_pm_l1_2: valid(L1Substate::PciPmL1_2),
+        }
+    }
+}
+
+fn has_l1(aspm: &ActiveStatePowerManagement) -> bool {
+    matches!(
+        aspm,
+        ActiveStatePowerManagement::L1 | ActiveStatePowerManagement::L0sAndL1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Link Capabilities: ASPM Support
+    const ASPM_L0S: u32 = 0b01 << 10;
+    const ASPM_L0S_L1: u32 = 0b11 << 10;
+    // Link Control: ASPM Control
+    const ASPM_L1_EN: u16 = 0b10;
+    const ASPM_L0S_L1_EN: u16 = 0b11;
+
+    fn l1_pm_substates(capabilities: u32) -> L1PmSubstates {
+        let mut data = [0; 12];
+        data[..4].copy_from_slice(&capabilities.to_le_bytes());
+        data.as_slice().try_into().unwrap()
+    }
+
+    #[test]
+    fn aspm() {
+        let all = l1_pm_substates(0x1f);
+        let root_port = Link::new(ASPM_L0S_L1, ASPM_L0S_L1_EN, 0);
+        let endpoint = Link::new(ASPM_L0S_L1, ASPM_L1_EN, 0);
+        let upstream = L1SubstatesComponent::new(&root_port, Some(&all));
+        let downstream = L1SubstatesComponent::new(&endpoint, Some(&all));
+        let sample = ValidL1Substates {
+            aspm_l1_1: true,
+            aspm_l1_2: true,
+            pci_pm_l1_1: true,
+            pci_pm_l1_2: true,
+        };
+        assert_eq!(sample, ValidL1Substates::new(upstream, downstream));
+
+        // ASPM L1 is not supported by the Root Port
+        let root_port = Link::new(ASPM_L0S, 0, 0);
+        let upstream = L1SubstatesComponent::new(&root_port, Some(&all));
+        assert_eq!(
+            Err(L1SubstateError::AspmL1Unsupported {
+                component: LinkComponent::Upstream
+            }),
+            L1Substate::AspmL1_2.check(upstream, downstream)
+        );
+        assert_eq!(Ok(()), L1Substate::PciPmL1_1.check(upstream, downstream));
+    }
+
+    #[test]
+    fn capabilities() {
+        let link = Link::new(ASPM_L0S_L1, ASPM_L1_EN, 0);
+        // ASPM L1.1 and PCI-PM L1.1 only
+        let l1_1 = l1_pm_substates(0x1a);
+        let upstream = L1SubstatesComponent::new(&link, Some(&l1_1));
+        let all = l1_pm_substates(0x1f);
+        let downstream = L1SubstatesComponent::new(&link, Some(&all));
+        let sample = ValidL1Substates {
+            aspm_l1_1: true,
+            pci_pm_l1_1: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, ValidL1Substates::new(upstream, downstream));
+        assert_eq!(
+            Err(L1SubstateError::Unsupported {
+                component: LinkComponent::Upstream
+            }),
+            L1Substate::AspmL1_2.check(upstream, downstream)
+        );
+
+        // L1 PM Substates Supported is clear
+        let not_supported = l1_pm_substates(0x0f);
+        let downstream = L1SubstatesComponent::new(&link, Some(&not_supported));
+        assert_eq!(
+            Err(L1SubstateError::Unsupported {
+                component: LinkComponent::Downstream
+            }),
+            L1Substate::AspmL1_1.check(upstream, downstream)
+        );
+
+        let downstream = L1SubstatesComponent::new(&link, None);
+        assert_eq!(
+            Err(L1SubstateError::NoCapability {
+                component: LinkComponent::Downstream
+            }),
+            L1Substate::PciPmL1_1.check(upstream, downstream)
+        );
+        assert_eq!(
+            ValidL1Substates::default(),
+            ValidL1Substates::new(upstream, downstream)
+        );
+    }
+}
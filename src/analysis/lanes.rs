@@ -0,0 +1,262 @@
+/*!
+# Lanes
+
+Per-Lane registers of a Link are spread over several extended capabilities, each of them
+sized by Maximum Link Width:
+- [Secondary PCI Express](crate::extended_capabilities::secondary_pci_express) holds Lane
+  Error Status bits and 8.0 GT/s Lane Equalization Control registers
+- [Physical Layer 16.0, 32.0 and 64.0 GT/s](crate::extended_capabilities::physical_layer)
+  Capabilities hold Lane Equalization Control registers of their data rates
+
+[Lanes] joins them by Lane number, so a single [Lane] describes everything known about one
+Lane of the Link. Registers not implemented by the Function are `None`.
+
+## Examples
+
+```rust
+# use pcics::analysis::lanes::*;
+# use pcics::capabilities::pci_express::{LinkWidth, TransmitterPreset};
+# use pcics::extended_capabilities::SecondaryPciExpress;
+// Lane Error at Lane 1, 8.0 GT/s Downstream Port Transmitter Preset P4 on both Lanes
+let data = [0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x04, 0x00, 0x04, 0x00];
+let spe = SecondaryPciExpress::try_from(data.as_slice()).unwrap();
+
+let lanes = Lanes::new(LinkWidth::X2)
+    .secondary_pci_express(&spe)
+    .collect::<Vec<_>>();
+assert_eq!(2, lanes.len());
+let lane = &lanes[1];
+assert_eq!(1, lane.number);
+assert_eq!(Some(true), lane.error);
+let control = lane.equalization_8gt.as_ref().unwrap();
+assert_eq!(TransmitterPreset::P4, control.downstream_port_transmitter_preset);
+assert_eq!(None, lane.equalization_16gt);
+```
+*/
+
+use core::ops::Range;
+
+use crate::{
+    capabilities::{pci_express::LinkWidth, CapabilityKind},
+    extended_capabilities::{
+        physical_layer::{self, LaneEqualizationControls, PhysicalLayer},
+        secondary_pci_express::{self, EqualizationControlLanes},
+        ExtendedCapabilityKind, SecondaryPciExpress,
+    },
+    ConfigurationSpace,
+};
+
+/// Per-Lane registers of a single Lane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lane {
+    /// Lane number
+    pub number: u8,
+    /// Lane Error Status bit
+    pub error: Option<bool>,
+    /// 8.0 GT/s Lane Equalization Control
+    pub equalization_8gt: Option<secondary_pci_express::LaneEqualizationControl>,
+    /// 16.0 GT/s Lane Equalization Control
+    pub equalization_16gt: Option<physical_layer::LaneEqualizationControl>,
+    /// 32.0 GT/s Lane Equalization Control
+    pub equalization_32gt: Option<physical_layer::LaneEqualizationControl>,
+    /// 64.0 GT/s Lane Equalization Control
+    pub equalization_64gt: Option<physical_layer::LaneEqualizationControl>,
+}
+
+/// An iterator through [Lane]s of a Link, see [module](self) documentation
+#[derive(Debug, Clone)]
+pub struct Lanes<'a> {
+    link_width: LinkWidth,
+    numbers: Range<u8>,
+    lane_error_status: Option<u32>,
+    equalization_8gt: Option<EqualizationControlLanes<'a>>,
+    equalization_16gt: Option<LaneEqualizationControls<'a>>,
+    equalization_32gt: Option<LaneEqualizationControls<'a>>,
+    equalization_64gt: Option<LaneEqualizationControls<'a>>,
+}
+
+impl<'a> Lanes<'a> {
+    /// Lanes of a Link with `link_width` Maximum Link Width without per-Lane registers
+    pub fn new(link_width: LinkWidth) -> Self {
+        Self {
+            link_width,
+            numbers: 0..link_width.value(),
+            lane_error_status: None,
+            equalization_8gt: None,
+            equalization_16gt: None,
+            equalization_32gt: None,
+            equalization_64gt: None,
+        }
+    }
+    /// Adds Lane Error Status and 8.0 GT/s Lane Equalization Control registers
+    pub fn secondary_pci_express(self, spe: &SecondaryPciExpress<'a>) -> Self {
+        Self {
+            lane_error_status: Some(spe.lane_error_status.0),
+            equalization_8gt: Some(spe.equalization_control_lanes(self.link_width)),
+            ..self
+        }
+    }
+    /// Adds Lane Equalization Control registers of the `phy` data rate, data rates other than
+    /// 16.0, 32.0 and 64.0 GT/s are ignored
+    pub fn physical_layer<P: PhysicalLayer<'a>>(mut self, phy: &P) -> Self {
+        let lanes = Some(phy.equalization_control_lanes(self.link_width));
+        match P::DATA_RATE {
+            16 => self.equalization_16gt = lanes,
+            32 => self.equalization_32gt = lanes,
+            64 => self.equalization_64gt = lanes,
+            _ => (),
+        }
+        self
+    }
+    /// Lanes of the Link described by PCI Express Capability, `None` if the Function has no
+    /// Link, extended capabilities parsing errors are skipped
+    ///
+    /// Physical Layer 64.0 GT/s Capability is not decoded yet and is not included.
+    pub fn from_configuration_space(cs: &'a ConfigurationSpace) -> Option<Self> {
+        let link_width = cs.capabilities().find_map(|cap| match cap.ok()?.kind {
+            CapabilityKind::PciExpress(pcie) => pcie
+                .device_type
+                .link()
+                .map(|link| link.capabilities.maximum_link_width),
+            _ => None,
+        })?;
+        let mut lanes = Self::new(link_width);
+        let ecaps = cs.extended_capabilities().into_iter().flatten();
+        for ecap in ecaps.flatten() {
+            match ecap.kind {
+                ExtendedCapabilityKind::SecondaryPciExpress(spe) => {
+                    lanes = lanes.secondary_pci_express(&spe);
+                }
+                ExtendedCapabilityKind::PhysicalLayer16GTps(phy) => {
+                    lanes = lanes.physical_layer(&phy);
+                }
+                ExtendedCapabilityKind::PhysicalLayer32GTps(phy) => {
+                    lanes = lanes.physical_layer(&phy);
+                }
+                _ => (),
+            }
+        }
+        Some(lanes)
+    }
+}
+
+impl<'a> Iterator for Lanes<'a> {
+    type Item = Lane;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let number = self.numbers.next()?;
+        let control = |iter: &mut Option<LaneEqualizationControls>| {
+            iter.as_mut()?.next().map(|(_, control)| control)
+        };
+        Some(Lane {
+            number,
+            error: self
+                .lane_error_status
+                .and_then(|status| status.checked_shr(number as u32))
+                .map(|status| status & 1 != 0),
+            equalization_8gt: self.equalization_8gt.as_mut().and_then(Iterator::next),
+            equalization_16gt: control(&mut self.equalization_16gt),
+            equalization_32gt: control(&mut self.equalization_32gt),
+            equalization_64gt: control(&mut self.equalization_64gt),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capabilities::pci_express::TransmitterPreset, synthetic};
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    /// Physical Layer 32.0 GT/s stand-in
+    struct Sample<'a>(&'a [u8]);
+
+    impl<'a> PhysicalLayer<'a> for Sample<'a> {
+        const DATA_RATE: u8 = 32;
+
+        fn equalization_status(&self) -> physical_layer::EqualizationStatus {
+            0.into()
+        }
+        fn lane_equalization_control_data(&self) -> &'a [u8] {
+            self.0
+        }
+    }
+
+    #[test]
+    fn lanes() {
+        // Lane Errors at Lanes 1 and 31, 8.0 GT/s Equalization Control for 2 Lanes
+        let data = [
+            0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x80, 0x01, 0x00, 0x02, 0x00,
+        ];
+        let spe = SecondaryPciExpress::try_from(data.as_slice()).unwrap();
+        let phy = Sample(&[0x33; 32]);
+        let result = Lanes::new(LinkWidth::X32)
+            .secondary_pci_express(&spe)
+            .physical_layer(&phy)
+            .map(|lane| {
+                (
+                    lane.number,
+                    lane.error,
+                    lane.equalization_8gt
+                        .map(|c| c.downstream_port_transmitter_preset),
+                    lane.equalization_32gt
+                        .map(|c| c.upstream_port_transmitter_preset),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(32, result.len());
+        let (p1, p2, p3) = (
+            Some(TransmitterPreset::P1),
+            Some(TransmitterPreset::P2),
+            Some(TransmitterPreset::P3),
+        );
+        assert_eq!((0, Some(false), p1, p3.clone()), result[0]);
+        assert_eq!((1, Some(true), p2, p3.clone()), result[1]);
+        assert_eq!((2, Some(false), None, p3.clone()), result[2]);
+        assert_eq!((31, Some(true), None, p3), result[31]);
+
+        let lane = Lanes::new(LinkWidth::X1).next().unwrap();
+        let sample = Lane {
+            number: 0,
+            error: None,
+            equalization_8gt: None,
+            equalization_16gt: None,
+            equalization_32gt: None,
+            equalization_64gt: None,
+        };
+        assert_eq!(sample, lane);
+    }
+
+    #[test]
+    fn from_configuration_space() {
+        let data = synthetic::root_port_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let lanes = Lanes::from_configuration_space(&cs)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(4, lanes.len());
+        let control = lanes[3].equalization_8gt.as_ref().unwrap();
+        assert_eq!(
+            TransmitterPreset::P4,
+            control.downstream_port_transmitter_preset
+        );
+        assert!(lanes.iter().all(|lane| lane.error == Some(false)));
+        assert!(lanes.iter().all(|lane| {
+            [&lane.equalization_16gt, &lane.equalization_32gt]
+                .iter()
+                .all(|control| {
+                    let control = control.as_ref().unwrap();
+                    control.downstream_port_transmitter_preset == TransmitterPreset::P4
+                        && control.upstream_port_transmitter_preset == TransmitterPreset::P4
+                })
+        }));
+        assert!(lanes.iter().all(|lane| lane.equalization_64gt.is_none()));
+
+        // Root Complex Integrated Endpoint has no Link
+        let mut data = synthetic::endpoint_with_msix(1);
+        data[synthetic::PCIE_PTR as usize + 2] = 0x92;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert!(Lanes::from_configuration_space(&cs).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+/*!
+# Root Complex Link Declaration addresses
+
+Link Entries of the [Root Complex Link Declaration
+Capability](crate::extended_capabilities::root_complex_link_declaration) point to other Root
+Complex elements. Memory-Mapped Space links hold the base address of the target RCRB, which
+is 4 KiB aligned, so bits 11:0 of the Link Address are reserved and must be zero. A non-zero
+value there is a root complex firmware bug.
+
+[MisalignedLinks] reports every valid Link Entry with such an address, Link Entries missing
+from the data are skipped.
+
+## Examples
+
+```rust
+# use pcics::analysis::link_declaration::*;
+# use pcics::ConfigOffset;
+# use pcics::extended_capabilities::root_complex_link_declaration::MisalignedAddress;
+let mut data = vec![0u8; 0x1000];
+// Root Complex Link Declaration at 100h with single Memory-Mapped Space Link Entry
+data[0x100..0x120].copy_from_slice(&[
+    0x05, 0x00, 0x01, 0x00, // Extended Capability Header
+    0x00, 0x01, 0x01, 0x03, // Element Self Description
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Reserved
+    0x01, 0x00, 0x01, 0x00, // Link Entry 1 Description
+    0x00, 0x00, 0x00, 0x00, // Link Entry 1 Reserved
+    0x80, 0x90, 0xd1, 0xfe, // Link Entry 1 Address First DWORD
+    0x00, 0x00, 0x00, 0x00, // Link Entry 1 Address Second DWORD
+]);
+let ecs = &data[0x100..];
+let result = MisalignedLinks::new(pcics::ExtendedCapabilities::new(ecs)).collect::<Vec<_>>();
+let sample = vec![MisalignedLink {
+    offset: ConfigOffset(0x100),
+    entry: 0,
+    error: MisalignedAddress { address: 0xfed19080 },
+}];
+assert_eq!(sample, result);
+```
+*/
+
+use core::iter::Enumerate;
+
+use crate::{
+    extended_capabilities::{
+        root_complex_link_declaration::{LinkEntries, MisalignedAddress},
+        ExtendedCapabilityKind,
+    },
+    ConfigOffset, ConfigurationSpace, ExtendedCapabilities,
+};
+
+/// Link Entry with the address not aligned to 4 KiB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MisalignedLink {
+    /// Root Complex Link Declaration Capability offset
+    pub offset: ConfigOffset,
+    /// Link Entry index, starting from 0
+    pub entry: usize,
+    pub error: MisalignedAddress,
+}
+
+/// An iterator through misaligned addresses of valid Link Entries of all Root Complex Link
+/// Declaration Capabilities, see [module](self) documentation
+#[derive(Debug, Clone)]
+pub struct MisalignedLinks<'a> {
+    ecaps: ExtendedCapabilities<'a>,
+    entries: Option<(ConfigOffset, Enumerate<LinkEntries<'a>>)>,
+}
+
+impl<'a> MisalignedLinks<'a> {
+    pub fn new(ecaps: ExtendedCapabilities<'a>) -> Self {
+        Self {
+            ecaps,
+            entries: None,
+        }
+    }
+    /// Misaligned links of the function, the iterator is empty if there is no extended
+    /// configuration space
+    pub fn from_configuration_space(cs: &ConfigurationSpace<'a>) -> Self {
+        Self::new(
+            cs.extended_capabilities()
+                .unwrap_or_else(|| ExtendedCapabilities::new(&[])),
+        )
+    }
+}
+
+impl Iterator for MisalignedLinks<'_> {
+    type Item = MisalignedLink;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((offset, entries)) = &mut self.entries {
+                for (entry, link) in entries.by_ref() {
+                    let link = match link {
+                        Ok(link) if link.link_description.link_valid => link,
+                        _ => continue,
+                    };
+                    if let Err(error) = link.link_address.base_address() {
+                        return Some(MisalignedLink {
+                            offset: *offset,
+                            entry,
+                            error,
+                        });
+                    }
+                }
+            }
+            self.entries = self.ecaps.by_ref().find_map(|ecap| {
+                let ecap = ecap.ok()?;
+                match ecap.kind {
+                    ExtendedCapabilityKind::RootComplexLinkDeclaration(rcld) => {
+                        Some((ecap.offset, rcld.link_entries.enumerate()))
+                    }
+                    _ => None,
+                }
+            });
+            self.entries.as_ref()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const RCLD: [u8; 0x30] = [
+        0x05, 0x00, 0x01, 0x00, // Extended Capability Header
+        0x00, 0x02, 0x01, 0x03, // Element Self Description, 2 Link Entries
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Reserved
+        0x00, 0x00, 0x01, 0x00, // Link Entry 1 Description, not valid
+        0x00, 0x00, 0x00, 0x00, // Link Entry 1 Reserved
+        0x01, 0x00, 0x00, 0x00, // Link Entry 1 Address First DWORD
+        0x00, 0x00, 0x00, 0x00, // Link Entry 1 Address Second DWORD
+        0x01, 0x00, 0x02, 0x00, // Link Entry 2 Description
+        0x00, 0x00, 0x00, 0x00, // Link Entry 2 Reserved
+        0x00, 0xa0, 0xd1, 0xfe, // Link Entry 2 Address First DWORD
+        0x00, 0x00, 0x00, 0x00, // Link Entry 2 Address Second DWORD
+    ];
+
+    #[test]
+    fn misaligned_links() {
+        let mut data = vec![0u8; 0x1000];
+        data[0x100..0x130].copy_from_slice(&RCLD);
+        data[0x103] = 0x14;
+        data[0x140..0x170].copy_from_slice(&RCLD);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(0, MisalignedLinks::from_configuration_space(&cs).count());
+
+        data[0x168] = 0x10;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = MisalignedLinks::from_configuration_space(&cs).collect::<Vec<_>>();
+        let sample = vec![MisalignedLink {
+            offset: ConfigOffset(0x140),
+            entry: 1,
+            error: MisalignedAddress {
+                address: 0xfed1a010,
+            },
+        }];
+        assert_eq!(sample, result);
+
+        // Conventional PCI function
+        let cs = ConfigurationSpace::try_from(&data[..0x100]).unwrap();
+        assert_eq!(0, MisalignedLinks::from_configuration_space(&cs).count());
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+# Latency Tolerance Reporting and OBFF
+
+LTR Messages travel upstream, Optimized Buffer Flush/Fill (OBFF) indications travel downstream.
+Both mechanisms work only if every Port on the path supports and enables them:
+- LTR Mechanism Enable must not be set unless LTR Mechanism Supported is set, and software must
+  not enable LTR in a Function unless the Port above it has LTR enabled. Linux skips enabling
+  LTR on a device when the upstream bridge has it disabled.
+- OBFF Enable must select a signaling mechanism reported in OBFF Supported. With Message
+  signaling the OBFF Messages are sent by the Port above, so it must support and enable OBFF
+  Message signaling too.
+
+[LtrObffInconsistencies] checks a Function against the Device Capabilities 2 / Control 2
+registers of the Port above it (Root Port or Switch Downstream Port).
+
+## Examples
+
+```rust
+# use pcics::analysis::ltr::*;
+# use pcics::capabilities::pci_express::Device2;
+// Endpoint supports and enables LTR and OBFF Message signaling (Variation A)
+let device_2 = Device2::new(1 << 11 | 0b01 << 18, 1 << 10 | 0b01 << 13, 0);
+// Root Port supports LTR and OBFF WAKE# signaling only, LTR is disabled
+let upstream_2 = Device2::new(1 << 11 | 0b10 << 18, 0, 0);
+
+let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+assert!(result.upstream_ltr_disabled);
+assert!(result.upstream_obff_unsupported);
+assert!(!result.is_consistent());
+```
+*/
+
+use crate::capabilities::pci_express::{Device2, Obff, ObffEnable, PciExpress};
+
+/// LTR and OBFF settings not backed by capabilities of the Function or the Port above it
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LtrObffInconsistencies {
+    /// LTR Mechanism Enable is set, but LTR Mechanism Supported is not
+    pub ltr_unsupported: bool,
+    /// LTR is enabled, but the Port above does not support LTR
+    pub upstream_ltr_unsupported: bool,
+    /// LTR is enabled, but the Port above has LTR Mechanism Enable clear
+    pub upstream_ltr_disabled: bool,
+    /// OBFF Enable selects a signaling mechanism not reported in OBFF Supported
+    pub obff_unsupported: bool,
+    /// OBFF Enable selects a signaling mechanism the Port above does not support
+    pub upstream_obff_unsupported: bool,
+    /// OBFF Message signaling is enabled, but the Port above has OBFF disabled
+    pub upstream_obff_disabled: bool,
+}
+
+impl LtrObffInconsistencies {
+    /// Checks `device_2` Device Capabilities 2 / Control 2 registers of a Function against
+    /// `upstream_2` registers of the Port above it
+    ///
+    /// Upstream checks are skipped if `upstream_2` is unknown.
+    pub fn new(device_2: &Device2, upstream_2: Option<&Device2>) -> Self {
+        let ltr_enabled = device_2.control.ltr_mechanism_enable;
+        let obff_enable = &device_2.control.obff_enable;
+        let obff_enabled = *obff_enable != ObffEnable::Disabled;
+        let message_signaling = matches!(
+            obff_enable,
+            ObffEnable::MessageSignalingA | ObffEnable::MessageSignalingB
+        );
+        Self {
+            ltr_unsupported: ltr_enabled && !device_2.capabilities.ltr_mechanism_supported,
+            upstream_ltr_unsupported: ltr_enabled
+                && upstream_2.is_some_and(|u| !u.capabilities.ltr_mechanism_supported),
+            upstream_ltr_disabled: ltr_enabled
+                && upstream_2.is_some_and(|u| !u.control.ltr_mechanism_enable),
+            obff_unsupported: !is_obff_supported(
+                &device_2.capabilities.obff_supported,
+                obff_enable,
+            ),
+            upstream_obff_unsupported: obff_enabled
+                && upstream_2.is_some_and(|u| {
+                    !is_obff_supported(&u.capabilities.obff_supported, obff_enable)
+                }),
+            upstream_obff_disabled: message_signaling
+                && upstream_2.is_some_and(|u| u.control.obff_enable == ObffEnable::Disabled),
+        }
+    }
+    /// Checks `device` PCI Express Capability against `upstream` Capability of the Port above
+    /// it, `None` if `device` has no Device Capabilities 2 / Control 2 registers
+    pub fn from_link_partners(device: &PciExpress, upstream: &PciExpress) -> Option<Self> {
+        let device_2 = device.device_2.as_ref()?;
+        Some(Self::new(device_2, upstream.device_2.as_ref()))
+    }
+    /// No inconsistencies found
+    pub fn is_consistent(&self) -> bool {
+        *self == Default::default()
+    }
+}
+
+/// `enable` signaling mechanism is reported in `supported`
+fn is_obff_supported(supported: &Obff, enable: &ObffEnable) -> bool {
+    match enable {
+        ObffEnable::Disabled => true,
+        ObffEnable::MessageSignalingA | ObffEnable::MessageSignalingB => {
+            matches!(supported, Obff::Message | Obff::WakeAndMessage)
+        }
+        ObffEnable::WakeSignaling => matches!(supported, Obff::Wake | Obff::WakeAndMessage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Device Capabilities 2: LTR Mechanism Supported, OBFF Supported
+    const LTR: u32 = 1 << 11;
+    const OBFF_MSG: u32 = 0b01 << 18;
+    const OBFF_WAKE: u32 = 0b10 << 18;
+    const OBFF_BOTH: u32 = 0b11 << 18;
+    // Device Control 2: LTR Mechanism Enable, OBFF Enable
+    const LTR_EN: u16 = 1 << 10;
+    const OBFF_MSG_A_EN: u16 = 0b01 << 13;
+    const OBFF_MSG_B_EN: u16 = 0b10 << 13;
+    const OBFF_WAKE_EN: u16 = 0b11 << 13;
+
+    #[test]
+    fn disabled() {
+        let device_2 = Device2::new(0, 0, 0);
+        let upstream_2 = Device2::new(0, 0, 0);
+        assert!(LtrObffInconsistencies::new(&device_2, None).is_consistent());
+        assert!(LtrObffInconsistencies::new(&device_2, Some(&upstream_2)).is_consistent());
+    }
+
+    #[test]
+    fn ltr() {
+        let device_2 = Device2::new(LTR, LTR_EN, 0);
+        let upstream_2 = Device2::new(LTR, LTR_EN, 0);
+        let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+        assert!(result.is_consistent());
+
+        let device_2 = Device2::new(0, LTR_EN, 0);
+        let upstream_2 = Device2::new(0, 0, 0);
+        let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+        let sample = LtrObffInconsistencies {
+            ltr_unsupported: true,
+            upstream_ltr_unsupported: true,
+            upstream_ltr_disabled: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+
+        // Unknown Port above
+        let result = LtrObffInconsistencies::new(&device_2, None);
+        let sample = LtrObffInconsistencies {
+            ltr_unsupported: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn obff() {
+        let device_2 = Device2::new(OBFF_BOTH, OBFF_MSG_B_EN, 0);
+        let upstream_2 = Device2::new(OBFF_MSG, OBFF_MSG_A_EN, 0);
+        let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+        assert!(result.is_consistent());
+
+        // WAKE# signaling is a sideband signal, OBFF Enable of the Port above does not matter
+        let device_2 = Device2::new(OBFF_WAKE, OBFF_WAKE_EN, 0);
+        let upstream_2 = Device2::new(OBFF_BOTH, 0, 0);
+        let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+        assert!(result.is_consistent());
+
+        let device_2 = Device2::new(OBFF_WAKE, OBFF_MSG_A_EN, 0);
+        let upstream_2 = Device2::new(OBFF_WAKE, 0, 0);
+        let result = LtrObffInconsistencies::new(&device_2, Some(&upstream_2));
+        let sample = LtrObffInconsistencies {
+            obff_unsupported: true,
+            upstream_obff_unsupported: true,
+            upstream_obff_disabled: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+    }
+}
@@ -0,0 +1,233 @@
+/*!
+# Multicast with ACS
+
+Multicast TLPs are routed by the Multicast Capability settings, so Access Control Services
+which isolate peer-to-peer traffic do not catch them by default. The specification
+implementation notes list the settings needed to keep both working:
+
+- MC_Overlay rewrites the address of forwarded TLPs, which invalidates their ECRC. Without
+  ECRC regeneration the Port strips ECRC, so the TLPs lose end-to-end protection.
+- ACS P2P Request Redirect does not redirect Multicast TLPs, with MC_Overlay enabled they go
+  straight to the overlaid peer address.
+- Multicast TLPs with Untranslated Addresses bypass the Translation Agent, ACS Translation
+  Blocking and P2P Request Redirect only keep isolation if MC_Block_Untranslated blocks the
+  received groups.
+
+[MulticastComponent::advisories] reports these and plain programming errors of an enabled
+Multicast Capability.
+
+## Examples
+
+```rust
+# use pcics::analysis::multicast::*;
+# use pcics::extended_capabilities::{AccessControlServices, Multicast};
+let mut data = [0u8; Multicast::SIZE];
+// MC_Max_Group 8, MC_Num_Group 4, MC_Enable
+data[4..8].copy_from_slice(&[0x07, 0x00, 0x03, 0x80]);
+// MC_Index_Position 12
+data[0x08] = 12;
+// Groups 0 and 1 received
+data[0x10] = 0b11;
+// Overlay 4 KB window
+data[0x28] = 12;
+let multicast = Multicast::try_from(data.as_slice()).unwrap();
+// ACS P2P Request Redirect supported and enabled
+let acs = AccessControlServices::try_from([0x04, 0x00, 0x04, 0x00].as_slice()).unwrap();
+
+let component = MulticastComponent::new(&multicast, Some(&acs));
+let sample = [
+    MulticastAdvisory::OverlayEcrc,
+    MulticastAdvisory::OverlayRedirect,
+    MulticastAdvisory::UntranslatedNotBlocked { groups: 0b11 },
+];
+assert!(component.advisories().eq(sample));
+```
+*/
+
+use snafu::prelude::*;
+
+use crate::extended_capabilities::{AccessControlServices, Multicast};
+
+/// Lowest MC_Index_Position keeping Multicast Group numbers above the 4 KB page offset
+pub const MIN_INDEX_POSITION: u8 = 12;
+
+/// MC_Overlay_Size values below this disable the overlay
+pub const MIN_OVERLAY_SIZE: u8 = 6;
+
+/// Multicast Capability of a Port or Function with its ACS Capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MulticastComponent<'a> {
+    pub multicast: &'a Multicast,
+    /// ACS Capability, `None` if not implemented
+    pub acs: Option<&'a AccessControlServices<'a>>,
+}
+
+impl<'a> MulticastComponent<'a> {
+    pub fn new(multicast: &'a Multicast, acs: Option<&'a AccessControlServices<'a>>) -> Self {
+        Self { multicast, acs }
+    }
+    /// MC_Overlay is in effect
+    pub fn is_overlay_enabled(&self) -> bool {
+        matches!(
+            &self.multicast.mc_overlay_bar,
+            Some(bar) if bar.mc_overlay_size >= MIN_OVERLAY_SIZE
+        )
+    }
+    /// Bit vector of the configured Multicast Groups
+    pub fn groups(&self) -> u64 {
+        let num = self.multicast.multicast_control.mc_num_group as u32 + 1;
+        u64::MAX >> (u64::BITS - num)
+    }
+    /// An iterator through configuration issues, empty while Multicast is disabled
+    pub fn advisories(&self) -> MulticastAdvisories<'a> {
+        MulticastAdvisories {
+            component: *self,
+            index: 0,
+        }
+    }
+}
+
+/// Multicast configuration which breaks Multicast or ACS isolation
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MulticastAdvisory {
+    #[snafu(display("MC_Num_Group ({num}) exceeds MC_Max_Group ({max})"))]
+    NumGroup { num: u8, max: u8 },
+    #[snafu(display(
+        "MC_Index_Position ({position}) is below {MIN_INDEX_POSITION}, behavior is undefined"
+    ))]
+    IndexPosition { position: u8 },
+    #[snafu(display("MC_Overlay is enabled without ECRC regeneration, ECRC is stripped"))]
+    OverlayEcrc,
+    #[snafu(display(
+        "MC_Overlay is enabled with ACS P2P Request Redirect, Multicast TLPs are not redirected"
+    ))]
+    OverlayRedirect,
+    #[snafu(display(
+        "ACS isolation is enabled, but groups {groups:#x} accept Untranslated Addresses"
+    ))]
+    UntranslatedNotBlocked { groups: u64 },
+}
+
+/// An iterator through [MulticastAdvisory]s of a [MulticastComponent]
+#[derive(Debug, Clone)]
+pub struct MulticastAdvisories<'a> {
+    component: MulticastComponent<'a>,
+    index: usize,
+}
+
+impl<'a> Iterator for MulticastAdvisories<'a> {
+    type Item = MulticastAdvisory;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let component = &self.component;
+        let mc = component.multicast;
+        if !mc.multicast_control.mc_enable {
+            return None;
+        }
+        let num = mc.multicast_control.mc_num_group;
+        let max = mc.multicast_capability.mc_max_group;
+        let position = mc.mc_base_address.mc_index_position;
+        let overlay = component.is_overlay_enabled();
+        let acs = component.acs.map(|acs| &acs.acs_control);
+        let redirect = matches!(acs, Some(ctl) if ctl.acs_p2p_request_redirect_enable);
+        let blocking = matches!(acs, Some(ctl) if ctl.acs_translation_blocking_enable);
+        let untranslated =
+            mc.mc_receive & !mc.mc_block_all & !mc.mc_block_untranslated & component.groups();
+        loop {
+            self.index += 1;
+            let advisory = match self.index {
+                1 if num > max => MulticastAdvisory::NumGroup { num, max },
+                2 if position < MIN_INDEX_POSITION => MulticastAdvisory::IndexPosition { position },
+                3 if overlay && !mc.multicast_capability.mc_ecrc_regeneration_supported => {
+                    MulticastAdvisory::OverlayEcrc
+                }
+                4 if overlay && redirect => MulticastAdvisory::OverlayRedirect,
+                5 if (redirect || blocking) && untranslated != 0 => {
+                    MulticastAdvisory::UntranslatedNotBlocked {
+                        groups: untranslated,
+                    }
+                }
+                1..=5 => continue,
+                _ => return None,
+            };
+            return Some(advisory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    fn multicast(capability: u16, control: u16, index_position: u8, overlay_size: u8) -> Multicast {
+        let mut data = [0u8; Multicast::SIZE];
+        data[4..6].copy_from_slice(&capability.to_le_bytes());
+        data[6..8].copy_from_slice(&control.to_le_bytes());
+        data[0x08] = index_position;
+        // Groups 0 to 3 received, group 0 blocked, group 1 blocked if untranslated
+        data[0x10] = 0b1111;
+        data[0x18] = 0b0001;
+        data[0x20] = 0b0010;
+        data[0x28] = overlay_size;
+        Multicast::try_from(data.as_slice()).unwrap()
+    }
+
+    // ACS Capability with V, B, R, C and U supported
+    fn acs(control: u8) -> [u8; 4] {
+        [0x1f, 0x00, control, 0x00]
+    }
+
+    #[test]
+    fn disabled() {
+        // MC_Num_Group 64 with MC_Max_Group 1
+        let mc = multicast(0x0000, 0x003f, 0, 0);
+        let component = MulticastComponent::new(&mc, None);
+        assert_eq!(None, component.advisories().next());
+    }
+
+    #[test]
+    fn programming() {
+        // MC_Max_Group 2, MC_Num_Group 3, ECRC regeneration, overlay disabled
+        let mc = multicast(0x8001, 0x8002, 6, 5);
+        let component = MulticastComponent::new(&mc, None);
+        assert!(!component.is_overlay_enabled());
+        assert_eq!(0b111, component.groups());
+        let sample = vec![
+            MulticastAdvisory::NumGroup { num: 2, max: 1 },
+            MulticastAdvisory::IndexPosition { position: 6 },
+        ];
+        assert_eq!(sample, component.advisories().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn acs_isolation() {
+        // MC_Max_Group 64, MC_Num_Group 64, ECRC regeneration, overlay enabled
+        let mc = multicast(0x803f, 0x803f, 12, 6);
+        assert_eq!(u64::MAX, MulticastComponent::new(&mc, None).groups());
+
+        // Translation Blocking
+        let data = acs(0b0010);
+        let blocking = AccessControlServices::try_from(data.as_slice()).unwrap();
+        let component = MulticastComponent::new(&mc, Some(&blocking));
+        let sample = vec![MulticastAdvisory::UntranslatedNotBlocked { groups: 0b1100 }];
+        assert_eq!(sample, component.advisories().collect::<Vec<_>>());
+
+        // P2P Request Redirect
+        let data = acs(0b0100);
+        let redirect = AccessControlServices::try_from(data.as_slice()).unwrap();
+        let component = MulticastComponent::new(&mc, Some(&redirect));
+        let sample = vec![
+            MulticastAdvisory::OverlayRedirect,
+            MulticastAdvisory::UntranslatedNotBlocked { groups: 0b1100 },
+        ];
+        assert_eq!(sample, component.advisories().collect::<Vec<_>>());
+
+        // Isolation disabled
+        let data = acs(0b0001);
+        let none = AccessControlServices::try_from(data.as_slice()).unwrap();
+        let component = MulticastComponent::new(&mc, Some(&none));
+        assert_eq!(None, component.advisories().next());
+    }
+}
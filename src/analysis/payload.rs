@@ -0,0 +1,208 @@
+/*!
+# Max_Payload_Size and Max_Read_Request_Size on a path
+
+A component must not send TLPs with payload larger than its Max_Payload_Size, and a receiver
+treats TLPs above its own Max_Payload_Size as Malformed. DMA through a path where any
+component has Max_Payload_Size above what another one accepts ends with errors reported by
+AER, so the largest safe Max_Payload_Size of a path is the smallest Max_Payload_Size Supported
+of its components.
+
+Read Requests larger than Max_Payload_Size are legal, the Completer splits Completions. Still,
+some devices mishandle split Completions, so [PayloadPolicy] also reports Max_Read_Request_Size
+above the path Max_Payload_Size, as the Linux `pcie_bus_perf` policy does.
+
+## Examples
+
+```rust
+# use pcics::analysis::payload::*;
+# use pcics::capabilities::pci_express::{Device, MaxSize};
+// Max_Payload_Size Supported 512 bytes, Max_Payload_Size 256 bytes, Max_Read_Request_Size 512
+let endpoint = Device::new(0b010, 0b010_0_0_0_0_001_0_0000, 0);
+// Max_Payload_Size Supported 256 bytes, Max_Payload_Size 128 bytes
+let root_port = Device::new(0b001, 0, 0);
+let path = [&endpoint, &root_port];
+
+let policy = PayloadPolicy::new(&path).unwrap();
+assert_eq!(MaxSize::B256, policy.max_payload_size);
+let sample = [
+    PayloadViolation::ReadRequestSizeAbove { index: 0, size: MaxSize::B512 },
+    PayloadViolation::PayloadSizeBelow { index: 1, size: MaxSize::B128 },
+];
+assert!(policy.violations().eq(sample));
+```
+*/
+
+use core::cmp::Ordering;
+
+use crate::capabilities::pci_express::{Device, MaxSize};
+
+/// Max_Payload_Size policy of the path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadPolicy<'a> {
+    path: &'a [&'a Device],
+    /// Largest Max_Payload_Size supported by every component of the path
+    pub max_payload_size: MaxSize,
+}
+
+impl<'a> PayloadPolicy<'a> {
+    /// Policy of the `path` of Device registers of every component, e.g. the Endpoint, Switch
+    /// Upstream and Downstream Ports and the Root Port
+    ///
+    /// `None` if no component reports a defined Max_Payload_Size Supported.
+    pub fn new(path: &'a [&'a Device]) -> Option<Self> {
+        let max_payload_size = path
+            .iter()
+            .map(|device| device.capabilities.max_payload_size_supported)
+            .filter(|size| size.bytes().is_some())
+            .min_by_key(|size| size.bytes())?;
+        Some(Self {
+            path,
+            max_payload_size,
+        })
+    }
+    /// Control values of the path components violating the policy
+    pub fn violations(&self) -> PayloadViolations<'a> {
+        PayloadViolations {
+            path: self.path.iter().enumerate(),
+            max_payload_size: self.max_payload_size,
+            pending: None,
+        }
+    }
+    /// No component violates the policy
+    pub fn is_consistent(&self) -> bool {
+        self.violations().next().is_none()
+    }
+}
+
+/// Control value violating [PayloadPolicy]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadViolation {
+    /// Reserved Max_Payload_Size Supported, Max_Payload_Size or Max_Read_Request_Size encoding
+    Reserved { index: usize },
+    /// Max_Payload_Size is above the path Max_Payload_Size, sent payloads may be rejected as
+    /// Malformed TLPs
+    PayloadSizeAbove { index: usize, size: MaxSize },
+    /// Max_Payload_Size is below the path Max_Payload_Size, received payloads may be rejected as
+    /// Malformed TLPs
+    PayloadSizeBelow { index: usize, size: MaxSize },
+    /// Max_Read_Request_Size is above the path Max_Payload_Size
+    ReadRequestSizeAbove { index: usize, size: MaxSize },
+}
+
+/// Iterator over [PayloadViolation]s in path order, see [PayloadPolicy::violations]
+#[derive(Debug, Clone)]
+pub struct PayloadViolations<'a> {
+    path: core::iter::Enumerate<core::slice::Iter<'a, &'a Device>>,
+    max_payload_size: MaxSize,
+    pending: Option<PayloadViolation>,
+}
+
+impl<'a> Iterator for PayloadViolations<'a> {
+    type Item = PayloadViolation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(violation) = self.pending.take() {
+            return Some(violation);
+        }
+        let max = self.max_payload_size.bytes();
+        loop {
+            let (index, device) = self.path.next()?;
+            let payload = device.control.max_payload_size;
+            let read_request = device.control.max_read_request_size;
+            if device
+                .capabilities
+                .max_payload_size_supported
+                .bytes()
+                .is_none()
+                || payload.bytes().is_none()
+                || read_request.bytes().is_none()
+            {
+                return Some(PayloadViolation::Reserved { index });
+            }
+            let payload_violation = match payload.bytes().cmp(&max) {
+                Ordering::Greater => Some(PayloadViolation::PayloadSizeAbove {
+                    index,
+                    size: payload,
+                }),
+                Ordering::Less => Some(PayloadViolation::PayloadSizeBelow {
+                    index,
+                    size: payload,
+                }),
+                Ordering::Equal => None,
+            };
+            let read_request_violation =
+                (read_request.bytes() > max).then_some(PayloadViolation::ReadRequestSizeAbove {
+                    index,
+                    size: read_request,
+                });
+            match (payload_violation, read_request_violation) {
+                (Some(violation), pending) => {
+                    self.pending = pending;
+                    return Some(violation);
+                }
+                (None, Some(violation)) => return Some(violation),
+                (None, None) => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    // Device Control: Max_Payload_Size and Max_Read_Request_Size
+    fn control(payload: u16, read_request: u16) -> u16 {
+        payload << 5 | read_request << 12
+    }
+
+    #[test]
+    fn consistent() {
+        let endpoint = Device::new(0b011, control(0b001, 0b001), 0);
+        let switch = Device::new(0b001, control(0b001, 0b000), 0);
+        let root_port = Device::new(0b010, control(0b001, 0b000), 0);
+        let path = [&endpoint, &switch, &switch, &root_port];
+        let policy = PayloadPolicy::new(&path).unwrap();
+        assert_eq!(MaxSize::B256, policy.max_payload_size);
+        assert!(policy.is_consistent());
+    }
+
+    #[test]
+    fn violations() {
+        // Max_Payload_Size and Max_Read_Request_Size 1024 bytes
+        let endpoint = Device::new(0b011, control(0b011, 0b011), 0);
+        let switch = Device::new(0b001, control(0b000, 0b000), 0);
+        // Reserved Max_Payload_Size
+        let root_port = Device::new(0b001, control(0b110, 0b000), 0);
+        let path = [&endpoint, &switch, &root_port];
+        let policy = PayloadPolicy::new(&path).unwrap();
+        assert_eq!(MaxSize::B256, policy.max_payload_size);
+        let result = policy.violations().collect::<Vec<_>>();
+        let sample = vec![
+            PayloadViolation::PayloadSizeAbove {
+                index: 0,
+                size: MaxSize::B1024,
+            },
+            PayloadViolation::ReadRequestSizeAbove {
+                index: 0,
+                size: MaxSize::B1024,
+            },
+            PayloadViolation::PayloadSizeBelow {
+                index: 1,
+                size: MaxSize::B128,
+            },
+            PayloadViolation::Reserved { index: 2 },
+        ];
+        assert_eq!(sample, result);
+        assert!(!policy.is_consistent());
+    }
+
+    #[test]
+    fn reserved_supported() {
+        let device = Device::new(0b111, 0, 0);
+        assert_eq!(None, PayloadPolicy::new(&[&device]));
+        assert_eq!(None, PayloadPolicy::new(&[]));
+    }
+}
@@ -0,0 +1,285 @@
+/*!
+# Precision Time Measurement path
+
+A PTM Requester gets the time from the PTM Responder above it, which is a PTM Root or forwards
+the request further upstream, so PTM works only if every component on the path up to a PTM
+Root implements the [PTM Capability](crate::extended_capabilities::precision_time_measurement).
+Switch Downstream Ports have no PTM Capability, the Upstream Port of the Switch handles PTM
+for the whole Switch.
+
+[PtmPath] follows the Linux rules of granting PTM:
+- a component with PTM Root Capable set is the PTM Root if there is no PTM capable component
+  above it, so the furthest upstream Time Source is selected
+- other components may enable PTM only if the component above has PTM enabled
+- Effective Granularity is the largest Local Clock Granularity of the PTM Root and all
+  intervening Time Sources, or unknown (0) if the PTM Root reports 0. Granularity of the
+  Function itself counts only if the Function is the PTM Root
+
+Additionally the Function must be PTM Requester capable and every component above it must be
+PTM Responder capable.
+
+## Examples
+
+```rust
+# use pcics::analysis::ptm::*;
+# use pcics::capabilities::pci_express::{DeviceType, Link, Root, Slot};
+# use pcics::extended_capabilities::PrecisionTimeMeasurement;
+let ptm = |capability: u32| {
+    let mut data = [0; 8];
+    data[..4].copy_from_slice(&capability.to_le_bytes());
+    PrecisionTimeMeasurement::try_from(data.as_slice()).unwrap()
+};
+let endpoint = DeviceType::Endpoint { link: Link::new(0, 0, 0), link_2: None };
+let root_port = DeviceType::RootPort {
+    link: Link::new(0, 0, 0),
+    link_2: None,
+    slot: Slot::new(0, 0, 0),
+    slot_2: None,
+    root: Root::new(0, 0, 0),
+};
+// Requester with 4 ns clock below Root capable Responder with 16 ns clock
+let (requester, root) = (ptm(0x0401), ptm(0x1006));
+let function = PtmComponent::new(&endpoint, Some(&requester));
+let upstream = [PtmComponent::new(&root_port, Some(&root))];
+
+let result = PtmPath::new(function, &upstream).unwrap();
+assert_eq!(PtmRoot::Upstream(0), result.root);
+assert_eq!(16, result.effective_granularity);
+
+// Root Port without PTM Capability
+let upstream = [PtmComponent::new(&root_port, None)];
+let result = PtmPath::new(function, &upstream);
+assert_eq!(Err(PtmPathError::UpstreamNoCapability { index: 0 }), result);
+```
+*/
+
+use snafu::prelude::*;
+
+use crate::{
+    capabilities::pci_express::DeviceType,
+    extended_capabilities::precision_time_measurement::{PrecisionTimeMeasurement, PtmCapability},
+};
+
+/// PCI Express Function on the PTM path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtmComponent<'a> {
+    /// Device/Port Type of PCI Express Capability
+    pub device_type: &'a DeviceType,
+    /// PTM Capability, `None` if not implemented
+    pub ptm: Option<&'a PrecisionTimeMeasurement>,
+}
+
+impl<'a> PtmComponent<'a> {
+    pub fn new(device_type: &'a DeviceType, ptm: Option<&'a PrecisionTimeMeasurement>) -> Self {
+        Self { device_type, ptm }
+    }
+    fn capability(&self) -> Option<&'a PtmCapability> {
+        self.ptm.map(|ptm| &ptm.ptm_capability)
+    }
+}
+
+/// Reasons PTM can not be enabled in the Function
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PtmPathError {
+    #[snafu(display("Function has no PTM Capability"))]
+    NoCapability,
+    #[snafu(display("Function is not PTM Requester capable"))]
+    NotRequester,
+    #[snafu(display("upstream component {index} has no PTM Capability"))]
+    UpstreamNoCapability { index: usize },
+    #[snafu(display("upstream component {index} is not PTM Responder capable"))]
+    NotResponder { index: usize },
+    #[snafu(display("there is no PTM Root capable component on the path"))]
+    NoRoot,
+}
+
+/// Component selected as the PTM Root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtmRoot {
+    /// The Function itself
+    Function,
+    /// Index in the upstream components
+    Upstream(usize),
+}
+
+/// Path of PTM Messages from the Function to the PTM Root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtmPath {
+    pub root: PtmRoot,
+    /// Effective Granularity in ns, 0 if unknown
+    pub effective_granularity: u8,
+}
+
+impl PtmPath {
+    /// Checks whether PTM can be enabled in the `function`
+    ///
+    /// `upstream` components are ordered from the Port above the Function up to the Root Port.
+    /// Switch Downstream Ports are skipped, they may be omitted.
+    pub fn new(function: PtmComponent, upstream: &[PtmComponent]) -> Result<Self, PtmPathError> {
+        let cap = function.capability().context(NoCapabilitySnafu)?;
+        ensure!(cap.ptm_requester_capable, NotRequesterSnafu);
+        let mut above = upstream
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !matches!(c.device_type, DeviceType::DownstreamPort { .. }));
+        // Walk up until a component without PTM capable component above it
+        let (mut root, mut root_cap) = (PtmRoot::Function, cap);
+        loop {
+            match above.next() {
+                Some((index, component)) => match component.capability() {
+                    Some(cap) => {
+                        ensure!(cap.ptm_responder_capable, NotResponderSnafu { index });
+                        (root, root_cap) = (PtmRoot::Upstream(index), cap);
+                    }
+                    None if root_cap.ptm_root_capable => break,
+                    None => return UpstreamNoCapabilitySnafu { index }.fail(),
+                },
+                None if root_cap.ptm_root_capable => break,
+                None => return NoRootSnafu.fail(),
+            }
+        }
+        // Granularity propagates down from the PTM Root to the Time Source above the Function
+        let granularity = match root {
+            PtmRoot::Function => cap.local_clock_granularity,
+            PtmRoot::Upstream(index) => upstream
+                .get(..=index)
+                .unwrap_or_default()
+                .iter()
+                .rev()
+                .filter_map(PtmComponent::capability)
+                .map(|cap| cap.local_clock_granularity)
+                .reduce(|above, own| if above == 0 { 0 } else { above.max(own) })
+                .unwrap_or_default(),
+        };
+        Ok(Self {
+            root,
+            effective_granularity: granularity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::pci_express::{Link, Slot};
+    use pretty_assertions::assert_eq;
+
+    fn ptm(
+        requester: bool,
+        responder: bool,
+        root: bool,
+        granularity: u8,
+    ) -> PrecisionTimeMeasurement {
+        let dword = requester as u32
+            | (responder as u32) << 1
+            | (root as u32) << 2
+            | (granularity as u32) << 8;
+        let mut data = [0; 8];
+        data[..4].copy_from_slice(&dword.to_le_bytes());
+        data.as_slice().try_into().unwrap()
+    }
+
+    fn upstream_port() -> DeviceType {
+        DeviceType::UpstreamPort {
+            link: Link::new(0, 0, 0),
+            link_2: None,
+        }
+    }
+
+    #[test]
+    fn switch() {
+        let endpoint = DeviceType::Endpoint {
+            link: Link::new(0, 0, 0),
+            link_2: None,
+        };
+        let downstream = DeviceType::DownstreamPort {
+            link: Link::new(0, 0, 0),
+            link_2: None,
+            slot: Slot::new(0, 0, 0),
+            slot_2: None,
+        };
+        let upstream = upstream_port();
+        let requester = ptm(true, false, false, 0);
+        // Switch is Root capable, but the Root Port above is selected
+        let switch = ptm(true, true, true, 8);
+        let root_port = ptm(false, true, true, 4);
+        let function = PtmComponent::new(&endpoint, Some(&requester));
+        let path = [
+            PtmComponent::new(&downstream, None),
+            PtmComponent::new(&upstream, Some(&switch)),
+            PtmComponent::new(&upstream, Some(&root_port)),
+        ];
+        let sample = PtmPath {
+            root: PtmRoot::Upstream(2),
+            effective_granularity: 8,
+        };
+        assert_eq!(Ok(sample), PtmPath::new(function, &path));
+
+        // Root Port granularity is unknown
+        let root_port = ptm(false, true, true, 0);
+        let path = [
+            PtmComponent::new(&upstream, Some(&switch)),
+            PtmComponent::new(&upstream, Some(&root_port)),
+        ];
+        let result = PtmPath::new(function, &path).unwrap();
+        assert_eq!(0, result.effective_granularity);
+
+        // Without the Root Port PTM Capability the Switch is the PTM Root
+        let path = [PtmComponent::new(&upstream, Some(&switch))];
+        let sample = PtmPath {
+            root: PtmRoot::Upstream(0),
+            effective_granularity: 8,
+        };
+        assert_eq!(Ok(sample), PtmPath::new(function, &path));
+
+        // Coarser clock of the Requester does not count
+        let requester = ptm(true, false, false, 32);
+        let root_port = ptm(false, true, true, 16);
+        let function = PtmComponent::new(&endpoint, Some(&requester));
+        let path = [PtmComponent::new(&upstream, Some(&root_port))];
+        let result = PtmPath::new(function, &path).unwrap();
+        assert_eq!(16, result.effective_granularity);
+    }
+
+    #[test]
+    fn function_is_root() {
+        let device_type = upstream_port();
+        let function = ptm(true, true, true, 32);
+        let function = PtmComponent::new(&device_type, Some(&function));
+        let sample = PtmPath {
+            root: PtmRoot::Function,
+            effective_granularity: 32,
+        };
+        assert_eq!(Ok(sample), PtmPath::new(function, &[]));
+    }
+
+    #[test]
+    fn errors() {
+        let device_type = upstream_port();
+        let requester = ptm(true, false, false, 0);
+        let function = PtmComponent::new(&device_type, Some(&requester));
+        let responder = ptm(false, true, false, 0);
+        let not_responder = ptm(true, false, true, 0);
+
+        assert_eq!(
+            Err(PtmPathError::NoCapability),
+            PtmPath::new(PtmComponent::new(&device_type, None), &[])
+        );
+        assert_eq!(
+            Err(PtmPathError::NotRequester),
+            PtmPath::new(PtmComponent::new(&device_type, Some(&responder)), &[])
+        );
+        assert_eq!(Err(PtmPathError::NoRoot), PtmPath::new(function, &[]));
+        let path = [PtmComponent::new(&device_type, Some(&responder))];
+        assert_eq!(Err(PtmPathError::NoRoot), PtmPath::new(function, &path));
+        let path = [
+            PtmComponent::new(&device_type, Some(&responder)),
+            PtmComponent::new(&device_type, Some(&not_responder)),
+        ];
+        assert_eq!(
+            Err(PtmPathError::NotResponder { index: 1 }),
+            PtmPath::new(function, &path)
+        );
+    }
+}
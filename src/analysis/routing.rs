@@ -0,0 +1,168 @@
+/*!
+# Routing IDs
+
+A Device consumes Routing IDs for its Physical Functions and, with SR-IOV, for Virtual
+Functions of each Physical Function:
+- Physical Functions are addressed by a 3-bit Function Number, or by an 8-bit Function Number
+  in an ARI Device, so a multi-function Device reserves 8 or 256 Routing IDs
+- Routing ID of the n-th VF is the Routing ID of its PF + First VF Offset + (n - 1) * VF Stride,
+  it may belong to one of the following Bus Numbers
+
+[RoutingIdUsage] combines Header Type, ARI and SR-IOV Capabilities of a Physical Function to
+get the number of Routing IDs and Bus Numbers to allocate for it.
+
+First VF Offset and VF Stride depend on NumVFs and ARI Capable Hierarchy values. The Routing
+IDs for TotalVFs are computed with the current First VF Offset and VF Stride, so NumVFs should
+be set to TotalVFs before the registers are read.
+
+## Examples
+
+```rust
+# use pcics::{analysis::routing::*, ConfigurationSpace};
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let usage = RoutingIdUsage::from_configuration_space(&cs);
+assert_eq!(1, usage.function_numbers);
+assert_eq!(None, usage.total_vfs);
+assert_eq!(1, usage.routing_ids());
+assert_eq!(1, usage.bus_numbers(0x0000));
+```
+*/
+
+use crate::{
+    extended_capabilities::{
+        AlternativeRoutingIdInterpretation, ExtendedCapabilityKind, SingleRootIoVirtualization,
+    },
+    header::Header,
+    ConfigurationSpace,
+};
+
+/// Routing IDs consumed by a Physical Function and its Virtual Functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingIdUsage {
+    /// Function Numbers of the Device: 1 for a single-function Device, 8 for a multi-function
+    /// Device and 256 for a multi-function ARI Device
+    pub function_numbers: u16,
+    /// Virtual Functions Routing IDs with TotalVFs enabled
+    pub total_vfs: Option<VfRoutingIds>,
+    /// Virtual Functions Routing IDs with NumVFs currently enabled
+    pub num_vfs: Option<VfRoutingIds>,
+}
+
+/// Routing IDs of Virtual Functions as offsets from the Physical Function Routing ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VfRoutingIds {
+    /// Number of Virtual Functions
+    pub count: u16,
+    /// Offset of the first VF
+    pub first: u32,
+    /// Offset of the last VF
+    pub last: u32,
+}
+
+impl VfRoutingIds {
+    /// Routing IDs of `count` VFs, `None` if there are no VFs
+    pub fn new(count: u16, first_vf_offset: u16, vf_stride: u16) -> Option<Self> {
+        let n = count.checked_sub(1)?;
+        let first = first_vf_offset as u32;
+        Some(Self {
+            count,
+            first,
+            last: first + n as u32 * vf_stride as u32,
+        })
+    }
+}
+
+impl RoutingIdUsage {
+    pub fn new(
+        header: &Header,
+        ari: Option<&AlternativeRoutingIdInterpretation>,
+        sriov: Option<&SingleRootIoVirtualization>,
+    ) -> Self {
+        let function_numbers = match (header.is_multi_function, ari.is_some()) {
+            (false, _) => 1,
+            (true, false) => 8,
+            (true, true) => 256,
+        };
+        let vfs = |count| {
+            sriov.and_then(|sriov| VfRoutingIds::new(count, sriov.first_vf_offset, sriov.vf_stride))
+        };
+        let num_vfs = sriov
+            .filter(|sriov| sriov.sriov_control.vf_enable)
+            .and_then(|sriov| vfs(sriov.num_vfs));
+        Self {
+            function_numbers,
+            total_vfs: sriov.and_then(|sriov| vfs(sriov.total_vfs)),
+            num_vfs,
+        }
+    }
+    /// Routing IDs usage of the Function, extended capabilities parsing errors are skipped
+    pub fn from_configuration_space(cs: &ConfigurationSpace) -> Self {
+        let (mut ari, mut sriov) = (None, None);
+        let ecaps = cs.extended_capabilities().into_iter().flatten();
+        for ecap in ecaps.flatten() {
+            match ecap.kind {
+                ExtendedCapabilityKind::AlternativeRoutingIdInterpretation(v) => ari = Some(v),
+                ExtendedCapabilityKind::SingleRootIoVirtualization(v) => sriov = Some(v),
+                _ => (),
+            }
+        }
+        Self::new(&cs.header, ari.as_ref(), sriov.as_ref())
+    }
+    /// Number of consecutive Routing IDs starting from Function 0 of the Device, reserved for
+    /// all Physical Functions and TotalVFs of this Physical Function
+    pub fn routing_ids(&self) -> u32 {
+        let pf = self.function_numbers as u32;
+        let vf = self.total_vfs.map_or(0, |vfs| vfs.last + 1);
+        pf.max(vf)
+    }
+    /// Number of Bus Numbers spanned by the Physical Function with `routing_id` and its TotalVFs
+    pub fn bus_numbers(&self, routing_id: u16) -> u16 {
+        let first = routing_id as u32;
+        let last = first + self.total_vfs.map_or(0, |vfs| vfs.last);
+        ((last >> 8) - (first >> 8) + 1) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn vf_routing_ids() {
+        assert_eq!(None, VfRoutingIds::new(0, 1, 1));
+        let sample = VfRoutingIds {
+            count: 64,
+            first: 0x180,
+            last: 0x180 + 63 * 2,
+        };
+        assert_eq!(Some(sample), VfRoutingIds::new(64, 0x180, 2));
+    }
+
+    #[test]
+    fn sriov_endpoint() {
+        let data = synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = RoutingIdUsage::from_configuration_space(&cs);
+        // TotalVFs 8, First VF Offset 80h, VF Stride 1, VFs are not enabled
+        let sample = RoutingIdUsage {
+            function_numbers: 1,
+            total_vfs: Some(VfRoutingIds {
+                count: 8,
+                first: 0x80,
+                last: 0x87,
+            }),
+            num_vfs: None,
+        };
+        assert_eq!(sample, result);
+        assert_eq!(0x88, result.routing_ids());
+        assert_eq!(1, result.bus_numbers(0x0100));
+        // Function 7Ch on bus 1: VFs end at 02:03.3
+        assert_eq!(2, result.bus_numbers(0x017c));
+    }
+}
@@ -0,0 +1,221 @@
+/*!
+# Tag field width
+
+The Tag field of a Non-Posted Request identifies outstanding requests of a Requester. Its
+width depends on both sides of a transaction:
+- 5-bit Tags are always usable
+- 8-bit Tags are usable if the Requester supports them ([ExtendedTagFieldSupported]),
+  Completers are required to handle 8-bit Tags regardless of their own settings
+- 10-bit Tags are usable if the Requester supports 10-Bit Tag Requester and the Completer
+  (or the link partner forwarding requests to it) supports 10-Bit Tag Completer
+
+[TagWidthReport] describes one direction of a link, call [TagWidthReport::new] twice with
+swapped arguments to check both link partners.
+
+## Examples
+
+```rust
+# use pcics::capabilities::pci_express::*;
+# use pcics::analysis::tags::*;
+let requester = Device::new(1 << 5, 1 << 8, 0);
+let requester_2 = Device2::new(0b11 << 16, 1 << 12, 0);
+// Link partner without 10-Bit Tag Completer Supported
+let completer_2 = Device2::new(0, 0, 0);
+
+let report = TagWidthReport::new(
+    (&requester, Some(&requester_2)),
+    Some(&completer_2),
+);
+assert_eq!(TagWidth::Eight, report.max_usable);
+assert_eq!(TagWidth::Ten, report.enabled);
+assert!(report.inconsistencies.ten_bit_completer_unsupported);
+assert!(!report.is_consistent());
+```
+*/
+
+use crate::capabilities::pci_express::{Device, Device2, ExtendedTagFieldSupported, PciExpress};
+
+/// Tag field width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TagWidth {
+    /// 5-bit Tag field
+    Five,
+    /// 8-bit Tag field
+    Eight,
+    /// 10-bit Tag field
+    Ten,
+}
+
+impl TagWidth {
+    /// Tag field width in bits
+    pub fn bits(&self) -> u8 {
+        match self {
+            Self::Five => 5,
+            Self::Eight => 8,
+            Self::Ten => 10,
+        }
+    }
+    /// Maximum number of outstanding Non-Posted Requests per Function (without Phantom
+    /// Functions). 10-bit Tag values with both upper bits clear are not permitted.
+    pub fn max_outstanding(&self) -> u16 {
+        match self {
+            Self::Five => 32,
+            Self::Eight => 256,
+            Self::Ten => 768,
+        }
+    }
+}
+
+/// Tag field width usable by a Requester and its consistency with current control registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagWidthReport {
+    /// Maximum width supported by both Requester and Completer
+    pub max_usable: TagWidth,
+    /// Width currently enabled on Requester
+    pub enabled: TagWidth,
+    pub inconsistencies: TagInconsistencies,
+}
+
+/// Control register settings not backed by capabilities
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagInconsistencies {
+    /// Extended Tag Field Enable is set, but Requester supports only 5-bit Tags
+    pub extended_tag_unsupported: bool,
+    /// 10-Bit Tag Requester Enable is set, but Requester does not support it
+    pub ten_bit_requester_unsupported: bool,
+    /// 10-Bit Tag Requester Enable is set, but link partner does not support 10-Bit Tag
+    /// Completer
+    pub ten_bit_completer_unsupported: bool,
+}
+
+impl TagWidthReport {
+    /// Tag width for Requests sent from `requester` to link partner with `completer_2` Device
+    /// Capabilities 2 / Control 2 registers (absent on PCI Express Capability version 1)
+    pub fn new(
+        (requester, requester_2): (&Device, Option<&Device2>),
+        completer_2: Option<&Device2>,
+    ) -> Self {
+        let extended_tag_supported = matches!(
+            requester.capabilities.extended_tag_field_supported,
+            ExtendedTagFieldSupported::Eight
+        );
+        let ten_bit_requester_supported =
+            requester_2.is_some_and(|d| d.capabilities.support_10bit_tag_requester);
+        let ten_bit_requester_enabled =
+            requester_2.is_some_and(|d| d.control.enable_10bit_tag_requester);
+        let ten_bit_completer_supported =
+            completer_2.is_some_and(|d| d.capabilities.support_10bit_tag_completer);
+
+        let max_usable = if ten_bit_requester_supported && ten_bit_completer_supported {
+            TagWidth::Ten
+        } else if extended_tag_supported {
+            TagWidth::Eight
+        } else {
+            TagWidth::Five
+        };
+        let enabled = if ten_bit_requester_enabled {
+            TagWidth::Ten
+        } else if requester.control.extended_tag_field_enable {
+            TagWidth::Eight
+        } else {
+            TagWidth::Five
+        };
+        let inconsistencies = TagInconsistencies {
+            extended_tag_unsupported: requester.control.extended_tag_field_enable
+                && !extended_tag_supported,
+            ten_bit_requester_unsupported: ten_bit_requester_enabled
+                && !ten_bit_requester_supported,
+            ten_bit_completer_unsupported: ten_bit_requester_enabled
+                && !ten_bit_completer_supported,
+        };
+        Self {
+            max_usable,
+            enabled,
+            inconsistencies,
+        }
+    }
+    /// Tag width for Requests sent from `requester` to `completer` link partner
+    pub fn from_link_partners(requester: &PciExpress, completer: &PciExpress) -> Self {
+        Self::new(
+            (&requester.device, requester.device_2.as_ref()),
+            completer.device_2.as_ref(),
+        )
+    }
+    /// Enabled width is not wider than usable and no inconsistencies found
+    pub fn is_consistent(&self) -> bool {
+        self.enabled <= self.max_usable && self.inconsistencies == Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // Device Capabilities: Extended Tag Field Supported
+    const ETFS: u32 = 1 << 5;
+    // Device Control: Extended Tag Field Enable
+    const ETFE: u16 = 1 << 8;
+    // Device Capabilities 2: 10-Bit Tag Completer/Requester Supported
+    const TEN_CPL: u32 = 1 << 16;
+    const TEN_REQ: u32 = 1 << 17;
+    // Device Control 2: 10-Bit Tag Requester Enable
+    const TEN_REQ_EN: u16 = 1 << 12;
+
+    #[test]
+    fn legacy_devices() {
+        let requester = Device::new(0, 0, 0);
+        let result = TagWidthReport::new((&requester, None), None);
+        let sample = TagWidthReport {
+            max_usable: TagWidth::Five,
+            enabled: TagWidth::Five,
+            inconsistencies: Default::default(),
+        };
+        assert_eq!(sample, result);
+        assert!(result.is_consistent());
+    }
+
+    #[test]
+    fn extended_tag_without_support() {
+        let requester = Device::new(0, ETFE, 0);
+        let result = TagWidthReport::new((&requester, None), None);
+        assert_eq!(TagWidth::Five, result.max_usable);
+        assert_eq!(TagWidth::Eight, result.enabled);
+        assert!(result.inconsistencies.extended_tag_unsupported);
+        assert!(!result.is_consistent());
+    }
+
+    #[test]
+    fn ten_bit_tags() {
+        let requester = Device::new(ETFS, ETFE, 0);
+        let requester_2 = Device2::new(TEN_CPL | TEN_REQ, TEN_REQ_EN, 0);
+        let completer_2 = Device2::new(TEN_CPL, 0, 0);
+        let result = TagWidthReport::new((&requester, Some(&requester_2)), Some(&completer_2));
+        let sample = TagWidthReport {
+            max_usable: TagWidth::Ten,
+            enabled: TagWidth::Ten,
+            inconsistencies: Default::default(),
+        };
+        assert_eq!(sample, result);
+        assert!(result.is_consistent());
+
+        // Reverse direction: completer is not capable of 10-bit requests
+        let result = TagWidthReport::new((&requester, Some(&completer_2)), Some(&requester_2));
+        assert_eq!(TagWidth::Eight, result.max_usable);
+        assert_eq!(TagWidth::Eight, result.enabled);
+        assert!(result.is_consistent());
+    }
+
+    #[test]
+    fn ten_bit_requester_enabled_without_support() {
+        let requester = Device::new(ETFS, 0, 0);
+        let requester_2 = Device2::new(0, TEN_REQ_EN, 0);
+        let result = TagWidthReport::new((&requester, Some(&requester_2)), None);
+        let sample = TagInconsistencies {
+            extended_tag_unsupported: false,
+            ten_bit_requester_unsupported: true,
+            ten_bit_completer_unsupported: true,
+        };
+        assert_eq!(sample, result.inconsistencies);
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+# TLP Processing Hints
+
+TPH Requester settings are spread over several structures:
+- [TPH Requester Capability](crate::extended_capabilities::tph_requester) holds supported ST
+  Modes, the ST Table location and ST Mode Select / TPH Requester Enable controls
+- [MSI-X](crate::capabilities::msi_x) Table may hold the Steering Tags instead of the TPH
+  Requester Capability
+- Device Capabilities 2 of the Completer (usually the Root Port) reports TPH Completer support
+
+[TphInconsistencies] encodes the rules between them that software and hardware must not
+violate.
+
+## Examples
+
+```rust
+# use pcics::analysis::tph::*;
+# use pcics::capabilities::MsiX;
+# use pcics::extended_capabilities::TphRequester;
+// Interrupt Vector Mode selected, ST Table is located in the MSI-X Table with 8 entries
+let tph = TphRequester::try_from([0x03, 0x04, 0x07, 0x00, 0x01, 0x01, 0x00, 0x00].as_slice())
+    .unwrap();
+// MSI-X Table has only 4 entries
+let msi_x = MsiX::from([0x03, 0x80, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30, 0x00, 0x00]);
+
+let result = TphInconsistencies::new(&tph, Some(&msi_x), None);
+assert!(result.st_table_size_mismatch);
+assert!(!result.is_consistent());
+```
+*/
+
+use crate::{
+    capabilities::{
+        pci_express::{Device2, TphCompleter},
+        CapabilityKind, MsiX,
+    },
+    extended_capabilities::{
+        tph_requester::{StModeSelect, StTable, TphRequesterEnable},
+        ExtendedCapabilityKind, TphRequester,
+    },
+    ConfigurationSpace,
+};
+
+/// Combinations of TPH Requester, MSI-X and TPH Completer registers not permitted by the
+/// specification
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TphInconsistencies {
+    /// No ST Mode Supported must be set
+    pub no_st_mode_unsupported: bool,
+    /// ST Mode Select has a reserved value
+    pub st_mode_reserved: bool,
+    /// ST Mode Select selects a mode not reported as supported
+    pub st_mode_unsupported: bool,
+    /// Interrupt Vector Mode is selected, but there is no ST Table to look the Steering Tags up
+    pub interrupt_vector_mode_without_st_table: bool,
+    /// ST Table Location has a reserved value
+    pub st_table_location_reserved: bool,
+    /// ST Table is located in the MSI-X Table, but the Function has no MSI-X Capability
+    pub st_table_without_msi_x: bool,
+    /// ST Table is located in the MSI-X Table, but ST Table Size does not match MSI-X Table Size
+    pub st_table_size_mismatch: bool,
+    /// TPH Requester Enable has a reserved value
+    pub requester_enable_reserved: bool,
+    /// Extended TPH is permitted, but Extended TPH Requester is not supported
+    pub extended_tph_requester_unsupported: bool,
+    /// TPH is permitted, but the Completer does not support TPH
+    pub tph_completer_unsupported: bool,
+    /// Extended TPH is permitted, but the Completer does not support Extended TPH
+    pub extended_tph_completer_unsupported: bool,
+}
+
+impl TphInconsistencies {
+    /// Checks `requester` against MSI-X Capability of the same Function and `completer_2`
+    /// Device Capabilities 2 / Control 2 registers of the Completer
+    ///
+    /// Completer checks are skipped if `completer_2` is unknown.
+    pub fn new(
+        requester: &TphRequester,
+        msi_x: Option<&MsiX>,
+        completer_2: Option<&Device2>,
+    ) -> Self {
+        let cap = &requester.tph_requester_capability;
+        let control = &requester.tph_requester_control;
+        let st_mode_unsupported = match control.st_mode_select {
+            StModeSelect::NoStMode => !cap.no_st_mode_supported,
+            StModeSelect::InterruptVectorMode => !cap.interrupt_vector_mode_supported,
+            StModeSelect::DeviceSpecificMode => !cap.device_specific_mode_supported,
+            StModeSelect::Reserved(_) => false,
+        };
+        let msi_x_table_size = match cap.st_table {
+            StTable::MsiXTable { size } => Some(size),
+            _ => None,
+        };
+        let (tph_permitted, extended_tph_permitted) = match control.tph_requester_enable {
+            TphRequesterEnable::NotPermitted | TphRequesterEnable::Reserved => (false, false),
+            TphRequesterEnable::TphPermitted => (true, false),
+            TphRequesterEnable::TphAndExtendedTphPermitted => (true, true),
+        };
+        let completer = completer_2.map(|d| &d.capabilities.tph_completer_supported);
+        Self {
+            no_st_mode_unsupported: !cap.no_st_mode_supported,
+            st_mode_reserved: matches!(control.st_mode_select, StModeSelect::Reserved(_)),
+            st_mode_unsupported,
+            interrupt_vector_mode_without_st_table: control.st_mode_select
+                == StModeSelect::InterruptVectorMode
+                && matches!(cap.st_table, StTable::NotPresent | StTable::Reserved),
+            st_table_location_reserved: cap.st_table == StTable::Reserved,
+            st_table_without_msi_x: msi_x_table_size.is_some() && msi_x.is_none(),
+            st_table_size_mismatch: msi_x_table_size
+                .zip(msi_x)
+                .is_some_and(|(size, msi_x)| size != msi_x.message_control.table_size),
+            requester_enable_reserved: control.tph_requester_enable == TphRequesterEnable::Reserved,
+            extended_tph_requester_unsupported: extended_tph_permitted
+                && !cap.extended_tph_requester_supported,
+            tph_completer_unsupported: tph_permitted
+                && matches!(
+                    completer,
+                    Some(TphCompleter::NotSupported | TphCompleter::Reserved)
+                ),
+            extended_tph_completer_unsupported: extended_tph_permitted
+                && completer.is_some_and(|c| *c != TphCompleter::TphAndExtendedTph),
+        }
+    }
+    /// Checks TPH Requester Capability of the Function against its MSI-X Capability, `None` if
+    /// there is no TPH Requester Capability
+    pub fn from_configuration_space(cs: &ConfigurationSpace) -> Option<Self> {
+        let requester = cs
+            .extended_capabilities()?
+            .find_map(|ecap| match ecap.ok()?.kind {
+                ExtendedCapabilityKind::TphRequester(tph) => Some(tph),
+                _ => None,
+            })?;
+        let msi_x = cs.capabilities().find_map(|cap| match cap.ok()?.kind {
+            CapabilityKind::MsiX(msi_x) => Some(msi_x),
+            _ => None,
+        });
+        Some(Self::new(&requester, msi_x.as_ref(), None))
+    }
+    /// No inconsistencies found
+    pub fn is_consistent(&self) -> bool {
+        *self == Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic;
+    use pretty_assertions::assert_eq;
+
+    // TPH Requester Capability: No ST Mode, Interrupt Vector Mode, Extended TPH Requester
+    // Supported
+    const NO_ST: u32 = 1 << 0;
+    const IV: u32 = 1 << 1;
+    const EXT: u32 = 1 << 8;
+    // ST Table Location: MSI-X Table
+    const IN_MSI_X: u32 = 0b10 << 9;
+    // TPH Requester Control: Interrupt Vector Mode, TPH and Extended TPH permitted
+    const IV_MODE: u32 = 0b001;
+    const TPH_EN: u32 = 0b01 << 8;
+    const EXT_EN: u32 = 0b11 << 8;
+
+    fn requester(capability: u32, control: u32) -> [u8; 8] {
+        let mut data = [0; 8];
+        data[..4].copy_from_slice(&capability.to_le_bytes());
+        data[4..].copy_from_slice(&control.to_le_bytes());
+        data
+    }
+
+    fn msi_x(table_size: u16) -> MsiX {
+        let mut data = [0; MsiX::SIZE];
+        data[..2].copy_from_slice(&table_size.to_le_bytes());
+        MsiX::from(data)
+    }
+
+    #[test]
+    fn interrupt_vector_mode() {
+        let data = requester(NO_ST | IV | IN_MSI_X | 7 << 16, IV_MODE | TPH_EN);
+        let tph = TphRequester::try_from(data.as_slice()).unwrap();
+        let completer_2 = Device2::new(0b01 << 12, 0, 0);
+        let result = TphInconsistencies::new(&tph, Some(&msi_x(7)), Some(&completer_2));
+        assert!(result.is_consistent());
+
+        let result = TphInconsistencies::new(&tph, None, None);
+        let sample = TphInconsistencies {
+            st_table_without_msi_x: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+
+        // Interrupt Vector Mode is not supported and ST Table is not present
+        let data = requester(NO_ST, IV_MODE);
+        let tph = TphRequester::try_from(data.as_slice()).unwrap();
+        let result = TphInconsistencies::new(&tph, Some(&msi_x(7)), None);
+        let sample = TphInconsistencies {
+            st_mode_unsupported: true,
+            interrupt_vector_mode_without_st_table: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn requester_enable() {
+        let data = requester(0, 0b111 | EXT_EN);
+        let tph = TphRequester::try_from(data.as_slice()).unwrap();
+        let completer_2 = Device2::new(0b01 << 12, 0, 0);
+        let result = TphInconsistencies::new(&tph, None, Some(&completer_2));
+        let sample = TphInconsistencies {
+            no_st_mode_unsupported: true,
+            st_mode_reserved: true,
+            extended_tph_requester_unsupported: true,
+            extended_tph_completer_unsupported: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+
+        let data = requester(NO_ST | EXT, 0b10 << 8);
+        let tph = TphRequester::try_from(data.as_slice()).unwrap();
+        let result = TphInconsistencies::new(&tph, None, Some(&Device2::new(0, 0, 0)));
+        let sample = TphInconsistencies {
+            requester_enable_reserved: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+
+        let data = requester(NO_ST | EXT, EXT_EN);
+        let tph = TphRequester::try_from(data.as_slice()).unwrap();
+        let result = TphInconsistencies::new(&tph, None, Some(&Device2::new(0, 0, 0)));
+        let sample = TphInconsistencies {
+            tph_completer_unsupported: true,
+            extended_tph_completer_unsupported: true,
+            ..Default::default()
+        };
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn configuration_space() {
+        let data = synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(
+            Some(TphInconsistencies::default()),
+            TphInconsistencies::from_configuration_space(&cs)
+        );
+
+        let data = synthetic::root_port_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, TphInconsistencies::from_configuration_space(&cs));
+    }
+}
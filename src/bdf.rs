@@ -0,0 +1,166 @@
+/*!
+## Bus, Device and Function numbers
+
+Bridge headers and function addresses hold several bus numbers next to each other.
+[Bus], [Device] and [Function] keep them apart from other `u8` registers: a
+[Device] is always in `0..=31` and a [Function] in `0..=7`, so a value taken from a
+wrong field is rejected on construction instead of silently truncated.
+
+## Examples
+
+```rust
+# use pcics::bdf::{Bus, Device, Function};
+let bus = Bus(0x3a);
+let device = Device::new(0x1f).unwrap();
+let function = Function::try_from(7).unwrap();
+assert_eq!("3a:1f.7", format!("{}:{}.{}", bus, device, function));
+
+// Function number does not fit into 3 bits
+assert_eq!(None, Function::new(8));
+assert!(Device::try_from(0x20).is_err());
+```
+*/
+
+use core::fmt;
+
+use snafu::prelude::*;
+
+/// Bus Number
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bus(pub u8);
+
+impl Bus {
+    pub const fn new(bus: u8) -> Self {
+        Self(bus)
+    }
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// Device Number, `0..=31`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Device(u8);
+
+impl Device {
+    pub const MAX: u8 = 31;
+    /// `None` if `device` is greater than [MAX](Self::MAX)
+    pub const fn new(device: u8) -> Option<Self> {
+        if device <= Self::MAX {
+            Some(Self(device))
+        } else {
+            None
+        }
+    }
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// Function Number, `0..=7`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Function(u8);
+
+impl Function {
+    pub const MAX: u8 = 7;
+    /// `None` if `function` is greater than [MAX](Self::MAX)
+    pub const fn new(function: u8) -> Option<Self> {
+        if function <= Self::MAX {
+            Some(Self(function))
+        } else {
+            None
+        }
+    }
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+/// Device or Function Number is out of range
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BdfError {
+    #[snafu(display("device number {value} should not be greater than {}", Device::MAX))]
+    Device { value: u8 },
+    #[snafu(display("function number {value} should not be greater than {}", Function::MAX))]
+    Function { value: u8 },
+}
+
+impl From<u8> for Bus {
+    fn from(bus: u8) -> Self {
+        Self(bus)
+    }
+}
+
+impl TryFrom<u8> for Device {
+    type Error = BdfError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).context(DeviceSnafu { value })
+    }
+}
+
+impl TryFrom<u8> for Function {
+    type Error = BdfError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).context(FunctionSnafu { value })
+    }
+}
+
+impl From<Bus> for u8 {
+    fn from(bus: Bus) -> Self {
+        bus.0
+    }
+}
+
+impl From<Device> for u8 {
+    fn from(device: Device) -> Self {
+        device.0
+    }
+}
+
+impl From<Function> for u8 {
+    fn from(function: Function) -> Self {
+        function.0
+    }
+}
+
+impl fmt::Display for Bus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn validation() {
+        assert_eq!(Some(31), Device::new(31).map(Device::get));
+        assert_eq!(None, Device::new(32));
+        assert_eq!(Some(7), Function::new(7).map(u8::from));
+        assert_eq!(None, Function::new(0xff));
+        assert_eq!(
+            "device number 32 should not be greater than 31",
+            Device::try_from(32).unwrap_err().to_string()
+        );
+        assert_eq!(Err(BdfError::Function { value: 8 }), Function::try_from(8));
+        assert!(Bus(0x10) < Bus::from(0x11));
+    }
+}
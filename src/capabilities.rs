@@ -43,7 +43,7 @@ Capabilities: [a8] SATA HBA v1.0 BAR4 Offset=00000004
 
 pcics capabilities:
 ```rust
-# use pcics::Header;
+# use pcics::{ConfigOffset, Header};
 # use pcics::capabilities::{
 #     Capabilities,
 #     Capability,
@@ -81,7 +81,7 @@ let result = Capabilities::new(&device_dependent_region, &header)
 
 let sample = vec![
     Ok(Capability {
-        pointer: 0x80,
+        pointer: ConfigOffset(0x80),
         kind: CapabilityKind::MessageSignaledInterrups(MessageSignaledInterrups {
             message_control: msi::MessageControl {
                 msi_enable: true,
@@ -91,6 +91,7 @@ let sample = vec![
                 a_64_bit_address_capable: false,
                 extended_message_data_capable: false,
                 extended_message_data_enable: false,
+                raw: 0x0001,
             },
             message_address: msi::MessageAddress::Dword(0xfee00358),
             message_data: 0x0000,
@@ -100,7 +101,7 @@ let sample = vec![
         })
     }),
     Ok(Capability {
-        pointer: 0x70,
+        pointer: ConfigOffset(0x70),
         kind: CapabilityKind::PowerManagementInterface(PowerManagementInterface {
             capabilities: pmi::Capabilities {
                 version: 0b11,
@@ -135,7 +136,7 @@ let sample = vec![
         })
     }),
     Ok(Capability {
-        pointer: 0xa8,
+        pointer: ConfigOffset(0xa8),
         kind: CapabilityKind::Sata(Sata {
             revision: sata::Revision { major: 1, minor: 0 },
             bar_offset: sata::BarOffset(0x00000004),
@@ -146,11 +147,58 @@ let sample = vec![
 
 assert_eq!(sample, result);
 ```
+
+## Slice length
+
+Capability structures are parsed from a slice starting right after the Capability ID and Next
+Capability Pointer bytes. Slices longer than a structure are accepted, trailing bytes are
+ignored. Every structure has a `MIN_SIZE` constant, shorter slices are always rejected and the
+error states how many bytes were required. Some structures have optional or variable-length
+parts, so `MIN_SIZE` is a lower bound and actual register values may require more data.
+Structures of a bounded size also have a `SIZE` constant, the size with all optional registers
+present. It equals `MIN_SIZE` for fixed-size structures.
+
+```rust
+# use pcics::capabilities::SlotIdentification;
+let data = [0x22, 0x02, 0xff];
+assert!(SlotIdentification::try_from(&data[..SlotIdentification::MIN_SIZE]).is_ok());
+
+let result = SlotIdentification::try_from(&data[..1]).unwrap_err();
+assert_eq!(SlotIdentification::MIN_SIZE, result.size);
+assert_eq!("Slot Identification (2 bytes)", result.to_string());
+assert_eq!(SlotIdentification::SIZE, SlotIdentification::MIN_SIZE);
+```
+
+## Strict parsing
+
+Reserved encodings are decoded permissively into `Reserved` variants, so a scan of many
+functions is not interrupted by a single odd register. Conformance tools may turn them into
+errors with [Capabilities::strict], see [CapabilityKind::reserved_encoding] for checked fields.
+
+```rust
+# use pcics::{capabilities::CapabilityError, ConfigOffset, ConfigurationSpace};
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let mut data = data.to_vec();
+// PCI Express Device Control: Max_Payload_Size 110b
+data[0x98] = data[0x98] & !0xe0 | 0xc0;
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+assert!(cs.capabilities().all(|cap| cap.is_ok()));
+
+let result = cs.capabilities().strict().find_map(Result::err);
+let sample = CapabilityError::ReservedEncoding {
+    ptr: ConfigOffset(0x90),
+    field: "Max_Payload_Size",
+};
+assert_eq!(Some(sample), result);
+```
 */
 
 use snafu::prelude::*;
 
-use super::DDR_OFFSET;
+use super::{ConfigOffset, DDR_LENGTH};
 use crate::header::{Header, HeaderType};
 
 // 01h PCI Power Management Interface
@@ -192,7 +240,9 @@ pub mod pci_x;
 pub use pci_x::{PciX, PciXBridge};
 
 // 08h HyperTransport
+#[cfg(feature = "hypertransport")]
 pub mod hypertransport;
+#[cfg(feature = "hypertransport")]
 pub use hypertransport::Hypertransport;
 
 // 09h Vendor Specific
@@ -214,16 +264,7 @@ pub mod compact_pci_resource_control {
 pub use compact_pci_resource_control::CompactPciResourceControl;
 
 // 0Ch PCI Hot-Plug
-pub mod pci_hot_plug {
-    /*!
-    # PCI Hot-Plug
-
-    Indicates that the associated device conforms to the Standard Hot-Plug
-    Controller model
-    */
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct PciHotPlug;
-}
+pub mod pci_hot_plug;
 pub use pci_hot_plug::PciHotPlug;
 
 // 0Dh PCI Bridge Subsystem Vendor ID
@@ -274,62 +315,173 @@ pub use enhanced_allocation::EnhancedAllocation;
 pub mod flattening_portal_bridge;
 pub use flattening_portal_bridge::FlatteningPortalBridge;
 
+/// Largest `MIN_SIZE` of the capability structures, a buffer of this size after the capability
+/// header fits mandatory registers of any decoded capability
+pub const MAX_MIN_SIZE: usize = crate::max_size(&[
+    PowerManagementInterface::MIN_SIZE,
+    AcceleratedGraphicsPort::MIN_SIZE,
+    VitalProductData::MIN_SIZE,
+    SlotIdentification::MIN_SIZE,
+    MessageSignaledInterrups::MIN_SIZE,
+    PciX::MIN_SIZE,
+    PciXBridge::MIN_SIZE,
+    #[cfg(feature = "hypertransport")]
+    Hypertransport::MIN_SIZE,
+    VendorSpecific::MIN_SIZE,
+    DebugPort::MIN_SIZE,
+    PciHotPlug::MIN_SIZE,
+    BridgeSubsystemVendorId::MIN_SIZE,
+    PciExpress::MIN_SIZE,
+    MsiX::MIN_SIZE,
+    Sata::MIN_SIZE,
+    AdvancedFeatures::MIN_SIZE,
+    EnhancedAllocation::MIN_SIZE,
+    FlatteningPortalBridge::MIN_SIZE,
+]);
+const _: () = assert!(Capability::HEADER_SIZE + MAX_MIN_SIZE <= DDR_LENGTH);
+
 /// Capability parsing error
+///
+/// Variants caused by short data carry capability pointer and number of bytes available after
+/// the capability header, see [CapabilityError::offset], [CapabilityError::needed] and
+/// [CapabilityError::available]
 #[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
 pub enum CapabilityError {
     #[snafu(display("capabilities pointer should be greater than 0x40"))]
     Pointer,
-    #[snafu(display("[{ptr:02x}] capability header is not available"))]
-    Header { ptr: u8 },
-    #[snafu(display("[{ptr:02x}] {source} data read error"))]
+    /// More than `limit` capabilities in the list, the list is terminated
+    #[snafu(display("[{ptr}] capabilities list is longer than {limit} entries"))]
+    Limit { ptr: ConfigOffset, limit: usize },
+    /// Capability decoded by a [strict](Capabilities::strict) iterator has a reserved or
+    /// contradictory encoding in `field`
+    #[snafu(display("[{ptr}] {field} has a reserved or contradictory encoding"))]
+    ReservedEncoding {
+        ptr: ConfigOffset,
+        field: &'static str,
+    },
+    #[snafu(display(
+        "[{ptr}] capability header is not available ({available} of {} bytes)",
+        Capability::HEADER_SIZE
+    ))]
+    Header { ptr: ConfigOffset, available: usize },
+    #[snafu(display("[{ptr}] {source} data read error ({available} bytes available)"))]
     Data {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: CapabilityDataError,
     },
-    #[snafu(display("[{ptr:02x}] PCI Express error: {source}"))]
+    #[snafu(display("[{ptr}] PCI Express error: {source} ({available} bytes available)"))]
     PciExpress {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: pci_express::PciExpressError,
     },
-    #[snafu(display("[{ptr:02x}] HyperTransport: {source}"))]
+    #[cfg(feature = "hypertransport")]
+    #[snafu(display("[{ptr}] HyperTransport: {source} ({available} bytes available)"))]
     Hypertransport {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: hypertransport::HypertransportError,
     },
-    #[snafu(display("[{ptr:02x}] Vendor Specific error: {source}"))]
+    #[snafu(display("[{ptr}] Vendor Specific error: {source} ({available} bytes available)"))]
     VendorSpecific {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: vendor_specific::VendorSpecificError,
     },
-    #[snafu(display("[{ptr:02x}] MSI error: {source}"))]
+    #[snafu(display("[{ptr}] MSI error: {source} ({available} bytes available)"))]
     MessageSignaledInterrups {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: message_signaled_interrups::MessageSignaledInterrupsError,
     },
-    #[snafu(display("[{ptr:02x}] PCI-X error: {source}"))]
+    #[snafu(display("[{ptr}] PCI-X error: {source} ({available} bytes available)"))]
     PciX {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: pci_x::PciXError,
     },
-    #[snafu(display("[{ptr:02x}] PCI-X Bridge error: {source}"))]
+    #[snafu(display("[{ptr}] PCI-X Bridge error: {source} ({available} bytes available)"))]
     PciXBridge {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: pci_x::PciXBridgeError,
     },
-    #[snafu(display("[{ptr:02x}] Enhanced Allocation error: {source}"))]
+    #[snafu(display("[{ptr}] Enhanced Allocation error: {source} ({available} bytes available)"))]
     EnhancedAllocation {
-        ptr: u8,
+        ptr: ConfigOffset,
+        available: usize,
         source: enhanced_allocation::EnhancedAllocationError,
     },
 }
+impl CapabilityError {
+    /// Absolute offset of the failed read: capability header for [CapabilityError::Header],
+    /// capability data (pointer + [Capability::HEADER_SIZE]) otherwise
+    pub fn offset(&self) -> Option<ConfigOffset> {
+        match self {
+            Self::Pointer => None,
+            Self::Header { ptr, .. }
+            | Self::Limit { ptr, .. }
+            | Self::ReservedEncoding { ptr, .. } => Some(*ptr),
+            Self::Data { ptr, .. }
+            | Self::PciExpress { ptr, .. }
+            | Self::VendorSpecific { ptr, .. }
+            | Self::MessageSignaledInterrups { ptr, .. }
+            | Self::PciX { ptr, .. }
+            | Self::PciXBridge { ptr, .. }
+            | Self::EnhancedAllocation { ptr, .. } => Some(*ptr + Capability::HEADER_SIZE as u16),
+            #[cfg(feature = "hypertransport")]
+            Self::Hypertransport { ptr, .. } => Some(*ptr + Capability::HEADER_SIZE as u16),
+        }
+    }
+    /// Number of bytes (after capability header) at [offset](Self::offset) required to parse
+    /// the structure, if the error is caused by short data
+    ///
+    /// Capability errors resolve it through `needed` of their source error, which describes
+    /// the part of the structure that failed to read.
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::Pointer | Self::Limit { .. } | Self::ReservedEncoding { .. } => None,
+            Self::Header { .. } => Some(Capability::HEADER_SIZE),
+            Self::Data { source, .. } => Some(source.size),
+            Self::PciExpress { source, .. } => source.needed(),
+            #[cfg(feature = "hypertransport")]
+            Self::Hypertransport { source, .. } => source.needed(),
+            Self::VendorSpecific { source, .. } => source.needed(),
+            Self::MessageSignaledInterrups { source, .. } => source.needed(),
+            Self::PciX { source, .. } => source.needed(),
+            Self::PciXBridge { source, .. } => source.needed(),
+            Self::EnhancedAllocation { source, .. } => source.needed(),
+        }
+    }
+    /// Number of bytes available at [offset](Self::offset)
+    pub fn available(&self) -> Option<usize> {
+        match self {
+            Self::Pointer | Self::Limit { .. } | Self::ReservedEncoding { .. } => None,
+            Self::Header { available, .. }
+            | Self::Data { available, .. }
+            | Self::PciExpress { available, .. }
+            | Self::VendorSpecific { available, .. }
+            | Self::MessageSignaledInterrups { available, .. }
+            | Self::PciX { available, .. }
+            | Self::PciXBridge { available, .. }
+            | Self::EnhancedAllocation { available, .. } => Some(*available),
+            #[cfg(feature = "hypertransport")]
+            Self::Hypertransport { available, .. } => Some(*available),
+        }
+    }
+}
 
 
 /// Common error for reading capability data
 #[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
 #[snafu(display("{name} ({size} bytes)"))]
 pub struct CapabilityDataError {
-    name: &'static str,
-    size: usize,
+    /// Capability name
+    pub name: &'static str,
+    /// Required number of bytes
+    pub size: usize,
 }
 
 /// An iterator through *Capabilities List*
@@ -337,15 +489,79 @@ pub struct CapabilityDataError {
 /// Used to point to a linked list of new capabilities implemented by this device. This
 /// register is only valid if the “Capabilities List” bit in the [crate::header::Status] Register is set. If
 /// implemented, the bottom two bits are reserved and should be set to 00b.
+///
+/// At most [MAX_ENTRIES](Self::MAX_ENTRIES) capabilities are yielded, a longer list ends with
+/// [CapabilityError::Limit]. Every structure is decoded in place from the device dependent
+/// region, so both entries and bytes visited are bounded on looped or adversarial lists.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Capabilities<'a> {
     data: &'a [u8],
     header: &'a Header,
     pointer: u8,
+    limit: usize,
+    remaining: usize,
+    strict: bool,
 }
 impl<'a> Capabilities<'a> {
+    /// Capabilities are DWORD aligned, so a list without loops has at most as many entries as
+    /// there are DWORDs in device dependent region
+    pub const MAX_ENTRIES: usize = DDR_LENGTH / 4;
     pub fn new(data: &'a [u8], header: &'a Header) -> Self {
-        Self { data, header, pointer: header.capabilities_pointer }
+        Self {
+            data,
+            header,
+            pointer: header.capabilities_pointer,
+            limit: Self::MAX_ENTRIES,
+            remaining: Self::MAX_ENTRIES,
+            strict: false,
+        }
+    }
+    /// Yield at most `limit` capabilities before [CapabilityError::Limit], `limit` is capped by
+    /// [MAX_ENTRIES](Self::MAX_ENTRIES)
+    pub fn with_limit(self, limit: usize) -> Self {
+        let limit = limit.min(Self::MAX_ENTRIES);
+        Self {
+            limit,
+            remaining: limit,
+            ..self
+        }
+    }
+    /// Yield [CapabilityError::ReservedEncoding] instead of capabilities with a
+    /// [reserved encoding](CapabilityKind::reserved_encoding)
+    pub fn strict(self) -> Self {
+        Self {
+            strict: true,
+            ..self
+        }
+    }
+    /// An iterator through capability headers only, capability bodies are not decoded
+    pub fn offsets(self) -> CapabilityOffsets<'a> {
+        CapabilityOffsets {
+            data: self.data,
+            pointer: self.pointer,
+            remaining: Self::MAX_ENTRIES,
+        }
+    }
+    /// End of the structure at `pointer`: offset of the next capability in address order, or
+    /// end of device dependent region
+    ///
+    /// Capabilities have no common length field, this is the upper bound of a structure size.
+    pub fn structure_end(self, pointer: ConfigOffset) -> ConfigOffset {
+        self.offsets()
+            .map(|(offset, _)| offset)
+            .filter(|&offset| offset > pointer)
+            .min()
+            .unwrap_or(ConfigOffset::ECS)
+    }
+    /// Decodes the single capability at `pointer`, usually taken from [offsets](Self::offsets),
+    /// without walking the list
+    pub(crate) fn parse_at(self, pointer: ConfigOffset) -> CapabilityResult<'a> {
+        let mut pointer = u8::try_from(pointer.get()).unwrap_or(0);
+        parse_cap(self.data, &mut pointer, self.header)
+    }
+    /// Device dependent region and header the list was created with
+    pub(crate) fn parts(self) -> (&'a [u8], &'a Header) {
+        (self.data, self.header)
     }
 }
 impl<'a> Iterator for Capabilities<'a> {
@@ -353,106 +569,182 @@ impl<'a> Iterator for Capabilities<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         // Stop iterating if next pointer is null
-        (self.pointer != 0).then(|| parse_cap(self.data, &mut self.pointer, self.header))
+        if self.pointer == 0 {
+            return None;
+        }
+        if self.remaining == 0 {
+            let ptr = ConfigOffset::from(self.pointer);
+            self.pointer = 0;
+            return Some(Err(CapabilityError::Limit {
+                ptr,
+                limit: self.limit,
+            }));
+        }
+        self.remaining -= 1;
+        match parse_cap(self.data, &mut self.pointer, self.header) {
+            Ok(cap) if self.strict => match cap.kind.reserved_encoding() {
+                Some(field) => Some(Err(CapabilityError::ReservedEncoding {
+                    ptr: cap.pointer,
+                    field,
+                })),
+                None => Some(Ok(cap)),
+            },
+            v => Some(v),
+        }
     }
 }
 
+/// An iterator through *Capabilities List* headers
+///
+/// Yields capability offsets with their [CapabilityHeader]. Unlike [Capabilities] iteration
+/// stops silently on invalid pointer or truncated header, and after as many entries as there
+/// are DWORDs in device dependent region, so looped lists are finite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityOffsets<'a> {
+    data: &'a [u8],
+    pointer: u8,
+    remaining: usize,
+}
+impl<'a> Iterator for CapabilityOffsets<'a> {
+    type Item = (ConfigOffset, CapabilityHeader);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pointer = ConfigOffset::from(self.pointer);
+        let offset = pointer.ddr_relative().filter(|_| self.remaining > 0)?;
+        self.pointer = 0;
+        let (id, next) = match self.data.get(offset..)? {
+            [id, next, ..] => (*id, *next),
+            _ => return None,
+        };
+        self.pointer = next;
+        self.remaining -= 1;
+        Some((pointer, CapabilityHeader { id, next }))
+    }
+}
+
+/// Capability ID and pointer to the next capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityHeader {
+    /// Capability ID assigned by the PCI-SIG
+    pub id: u8,
+    /// Next capability pointer, 00h ends the list
+    pub next: u8,
+}
+
 type CapabilityResult<'a> = Result<Capability<'a>, CapabilityError>;
 fn parse_cap<'a>(bytes: &'a [u8], pointer: &mut u8, header: &'a Header) -> CapabilityResult<'a> {
-    let ptr = *pointer;
     // Capability data resides in Device dependent region (starts from 0x40)
-    let offset = (*pointer as usize).checked_sub(DDR_OFFSET).ok_or_else(|| {
+    let offset = ConfigOffset::from(*pointer).ddr_relative().ok_or_else(|| {
         *pointer = 0;
         CapabilityError::Pointer
     })?;
-    let (id, cap_data) = if let Some([id, next, rest @ ..]) = bytes.get(offset..) {
+    parse_cap_at(bytes.get(offset..).unwrap_or_default(), pointer, header)
+}
+
+/// Decodes the capability at valid `pointer` from `bytes` starting at the capability header
+pub(crate) fn parse_cap_at<'a>(
+    bytes: &'a [u8],
+    pointer: &mut u8,
+    header: &'a Header,
+) -> CapabilityResult<'a> {
+    let ptr = *pointer;
+    let (id, cap_data) = if let [id, next, rest @ ..] = bytes {
         *pointer = *next;
         (*id, rest)
     } else {
-        return Err(CapabilityError::Header { ptr });
+        *pointer = 0;
+        return Err(CapabilityError::Header {
+            ptr: ptr.into(),
+            available: bytes.len(),
+        });
     };
+    let available = cap_data.len();
     use CapabilityKind as Kind;
     let kind = match id {
         0x00 => Kind::NullCapability,
         0x01 => cap_data
             .try_into()
             .map(Kind::PowerManagementInterface)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x02 => cap_data
             .try_into()
             .map(Kind::AcceleratedGraphicsPort)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x03 => cap_data
             .try_into()
             .map(Kind::VitalProductData)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x04 => cap_data
             .try_into()
             .map(Kind::SlotIdentification)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x05 => cap_data
             .try_into()
             .map(Kind::MessageSignaledInterrups)
-            .context(MessageSignaledInterrupsSnafu { ptr })?,
+            .context(MessageSignaledInterrupsSnafu { ptr, available })?,
         0x06 => Kind::CompactPciHotSwap(CompactPciHotSwap),
         0x07 => {
             if matches!(header.header_type, HeaderType::Bridge(_)) {
                 cap_data
                     .try_into()
                     .map(Kind::PciXBridge)
-                    .context(PciXBridgeSnafu { ptr })?
+                    .context(PciXBridgeSnafu { ptr, available })?
             } else {
                 cap_data
                     .try_into()
                     .map(Kind::PciX)
-                    .context(PciXSnafu { ptr })?
+                    .context(PciXSnafu { ptr, available })?
             }
         }
+        #[cfg(feature = "hypertransport")]
         0x08 => cap_data
             .try_into()
             .map(Kind::Hypertransport)
-            .context(HypertransportSnafu { ptr })?,
+            .context(HypertransportSnafu { ptr, available })?,
         0x09 => VendorSpecific::try_new(cap_data, header)
             .map(Kind::VendorSpecific)
-            .context(VendorSpecificSnafu { ptr })?,
+            .context(VendorSpecificSnafu { ptr, available })?,
         0x0a => cap_data
             .try_into()
             .map(Kind::DebugPort)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x0b => Kind::CompactPciResourceControl(CompactPciResourceControl),
-        0x0c => Kind::PciHotPlug(PciHotPlug),
+        0x0c => cap_data
+            .try_into()
+            .map(Kind::PciHotPlug)
+            .context(DataSnafu { ptr, available })?,
         0x0d => cap_data
             .try_into()
             .map(Kind::BridgeSubsystemVendorId)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x0e => Kind::Agp8x(Agp8x),
         0x0f => Kind::SecureDevice(SecureDevice),
         0x10 => cap_data
             .try_into()
             .map(Kind::PciExpress)
-            .context(PciExpressSnafu { ptr })?,
+            .context(PciExpressSnafu { ptr, available })?,
         0x11 => cap_data
             .try_into()
             .map(Kind::MsiX)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x12 => cap_data
             .try_into()
             .map(Kind::Sata)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x13 => cap_data
             .try_into()
             .map(Kind::AdvancedFeatures)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         0x14 => EnhancedAllocation::try_new(cap_data, header)
             .map(Kind::EnhancedAllocation)
-            .context(EnhancedAllocationSnafu { ptr })?,
+            .context(EnhancedAllocationSnafu { ptr, available })?,
         0x15 => cap_data
             .try_into()
             .map(Kind::FlatteningPortalBridge)
-            .context(DataSnafu { ptr })?,
+            .context(DataSnafu { ptr, available })?,
         v => Kind::Reserved(v),
     };
-    Ok(Capability { pointer: ptr, kind })
+    Ok(Capability { pointer: ptr.into(), kind })
 }
 
 
@@ -462,17 +754,23 @@ fn parse_cap<'a>(bytes: &'a [u8], pointer: &mut u8, header: &'a Header) -> Capab
 /// Capability structure
 #[derive(Debug, PartialEq, Eq)]
 pub struct Capability<'a> {
-    pub pointer: u8,
+    /// Capability offset in configuration space
+    pub pointer: ConfigOffset,
     pub kind: CapabilityKind<'a>,
 }
 impl<'a> Capability<'a> {
     /// Each capability in the capability list consists of an 8-bit ID field assigned by the PCI
     /// SIG, an 8 bit pointer in configuration space to the next capability.
     pub const HEADER_SIZE: usize = 2;
+    /// Capability ID, see [CapabilityKind::kind_id]
+    pub fn id(&self) -> u8 {
+        self.kind.kind_id()
+    }
 }
 
 /// Capability ID assigned by the PCI-SIG
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CapabilityKind<'a> {
     /// Null Capability (00h)
     ///
@@ -487,6 +785,7 @@ pub enum CapabilityKind<'a> {
     CompactPciHotSwap(CompactPciHotSwap),
     PciX(PciX),
     PciXBridge(PciXBridge),
+    #[cfg(feature = "hypertransport")]
     Hypertransport(Hypertransport),
     VendorSpecific(VendorSpecific<'a>),
     DebugPort(DebugPort),
@@ -504,10 +803,122 @@ pub enum CapabilityKind<'a> {
     Reserved(u8),
 }
 
+impl<'a> CapabilityKind<'a> {
+    /// Capability ID of the variant, `_` arms of matches on this non-exhaustive enum may
+    /// dispatch on it
+    pub fn kind_id(&self) -> u8 {
+        match *self {
+            Self::NullCapability => 0x00,
+            Self::PowerManagementInterface(_) => 0x01,
+            Self::AcceleratedGraphicsPort(_) => 0x02,
+            Self::VitalProductData(_) => 0x03,
+            Self::SlotIdentification(_) => 0x04,
+            Self::MessageSignaledInterrups(_) => 0x05,
+            Self::CompactPciHotSwap(_) => 0x06,
+            Self::PciX(_) | Self::PciXBridge(_) => 0x07,
+            #[cfg(feature = "hypertransport")]
+            Self::Hypertransport(_) => 0x08,
+            Self::VendorSpecific(_) => 0x09,
+            Self::DebugPort(_) => 0x0a,
+            Self::CompactPciResourceControl(_) => 0x0b,
+            Self::PciHotPlug(_) => 0x0c,
+            Self::BridgeSubsystemVendorId(_) => 0x0d,
+            Self::Agp8x(_) => 0x0e,
+            Self::SecureDevice(_) => 0x0f,
+            Self::PciExpress(_) => 0x10,
+            Self::MsiX(_) => 0x11,
+            Self::Sata(_) => 0x12,
+            Self::AdvancedFeatures(_) => 0x13,
+            Self::EnhancedAllocation(_) => 0x14,
+            Self::FlatteningPortalBridge(_) => 0x15,
+            Self::Reserved(v) => v,
+        }
+    }
+    /// Capability ID is defined by the specification
+    ///
+    /// IDs of decoders disabled by cargo features (e.g. `hypertransport`) are known too, though
+    /// they are parsed as [Reserved](Self::Reserved).
+    pub fn is_known_id(id: u8) -> bool {
+        id <= 0x15
+    }
+    /// Name of the first field with a reserved or contradictory encoding
+    ///
+    /// [Unknown](Self::is_known_id) Capability ID, Power Management Data Select, MSI-X Table and
+    /// PBA BIRs, PCI Express Device/Port Type, Max_Payload_Size, Max_Read_Request_Size, link
+    /// speeds and widths are checked. Current Link Speed and Negotiated Link Width are contradictory if they exceed
+    /// Max Link Speed and Maximum Link Width, they are not checked while the Link is down.
+    /// Nonzero Captured Slot Power Limit is contradictory outside of Upstream Ports.
+    pub fn reserved_encoding(&self) -> Option<&'static str> {
+        use msi_x::Bir;
+        use pci_express::{DeviceType, LinkSpeed, LinkWidth, MaxSize};
+        use power_management_interface::DataSelect;
+        let is_reserved_size = |size| matches!(size, MaxSize::Reserved0 | MaxSize::Reserved1);
+        match self {
+            Self::Reserved(id) => (!Self::is_known_id(*id)).then_some("Capability ID"),
+            Self::PowerManagementInterface(pm) => {
+                matches!(pm.control.data_select, DataSelect::Reserved(_)).then_some("Data Select")
+            }
+            Self::MsiX(msix) => {
+                if matches!(msix.table.bir, Bir::Reserved(_)) {
+                    Some("Table BIR")
+                } else if matches!(msix.pending_bit_array.bir, Bir::Reserved(_)) {
+                    Some("PBA BIR")
+                } else {
+                    None
+                }
+            }
+            Self::PciExpress(pcie) => {
+                let device = &pcie.device;
+                if matches!(pcie.device_type, DeviceType::Reserved { .. }) {
+                    return Some("Device/Port Type");
+                }
+                if is_reserved_size(device.capabilities.max_payload_size_supported) {
+                    return Some("Max_Payload_Size Supported");
+                }
+                if is_reserved_size(device.control.max_payload_size) {
+                    return Some("Max_Payload_Size");
+                }
+                if is_reserved_size(device.control.max_read_request_size) {
+                    return Some("Max_Read_Request_Size");
+                }
+                if pcie.has_stray_captured_slot_power_limit() {
+                    return Some("Captured Slot Power Limit");
+                }
+                let link = pcie.device_type.link()?;
+                let (max_speed, max_width) = (
+                    link.capabilities.max_link_speed,
+                    link.capabilities.maximum_link_width,
+                );
+                let (speed, width) = (
+                    link.status.current_link_speed,
+                    link.status.negotiated_link_width,
+                );
+                let reserved_speed =
+                    |speed| matches!(speed, LinkSpeed::Reserved(_) | LinkSpeed::RateRsvdp);
+                if reserved_speed(max_speed) {
+                    Some("Max Link Speed")
+                } else if matches!(max_width, LinkWidth::Reserved(_)) {
+                    Some("Maximum Link Width")
+                } else if width == LinkWidth::Reserved(0) {
+                    // Link is down
+                    None
+                } else if reserved_speed(speed) || speed > max_speed {
+                    Some("Current Link Speed")
+                } else if matches!(width, LinkWidth::Reserved(_)) || width > max_width {
+                    Some("Negotiated Link Width")
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ECS_OFFSET;
+    use crate::{DDR_OFFSET, ECS_OFFSET};
     use pretty_assertions::assert_eq;
     use std::prelude::v1::*;
 
@@ -528,19 +939,19 @@ mod tests {
         let result = Capabilities::new(ddr, &header).collect::<Vec<_>>();
         let sample = vec![
             Ok(Capability {
-                pointer: 0x50,
+                pointer: ConfigOffset(0x50),
                 kind: CapabilityKind::PowerManagementInterface(
                     data[0x50 + 2 .. ].try_into().unwrap(),
                 ),
             }),
             Ok(Capability {
-                pointer: 0x80,
+                pointer: ConfigOffset(0x80),
                 kind: VendorSpecific::try_new(&data[(0x80 + 2)..], &header)
                     .map(CapabilityKind::VendorSpecific)
                     .unwrap(),
             }),
             Ok(Capability {
-                pointer: 0x60,
+                pointer: ConfigOffset(0x60),
                 kind: CapabilityKind::MessageSignaledInterrups(
                     data[(0x60 + 2)..].try_into().unwrap(),
                 ),
@@ -548,4 +959,318 @@ mod tests {
         ];
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn offsets() {
+        let mut data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_9dc8/config"
+        ))
+        .to_vec();
+        let header = data.as_slice().try_into().unwrap();
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let result = Capabilities::new(ddr, &header)
+            .offsets()
+            .map(|(pointer, CapabilityHeader { id, next })| (pointer.get(), id, next))
+            .collect::<Vec<_>>();
+        let sample = vec![(0x50, 0x01, 0x80), (0x80, 0x09, 0x60), (0x60, 0x05, 0x00)];
+        assert_eq!(sample, result);
+
+        // MSI points back to Power Management
+        data[0x61] = 0x50;
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let count = Capabilities::new(ddr, &header).offsets().count();
+        assert_eq!(DDR_LENGTH / 4, count);
+    }
+
+    #[test]
+    fn structure_end() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_9dc8/config"
+        ));
+        let header = data.as_slice().try_into().unwrap();
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let caps = Capabilities::new(ddr, &header);
+        // Power Management (50h) is followed by MSI (60h) in address order
+        assert_eq!(ConfigOffset(0x60), caps.structure_end(ConfigOffset(0x50)));
+        let end = caps.structure_end(ConfigOffset(0x80));
+        assert_eq!(ConfigOffset::ECS, end);
+        // Vendor Specific Information: Len=14
+        let payload = VendorSpecific::bounded_payload(&data[0x82..], ConfigOffset(0x80), end);
+        assert_eq!(0x14 - 3, payload.unwrap().len());
+    }
+
+    #[test]
+    fn kind_id() {
+        let data = crate::synthetic::root_port(true);
+        let header = data.as_slice().try_into().unwrap();
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let result = Capabilities::new(ddr, &header)
+            .map(|cap| cap.unwrap().id())
+            .collect::<Vec<_>>();
+        let sample = Capabilities::new(ddr, &header)
+            .offsets()
+            .map(|(_, CapabilityHeader { id, .. })| id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![0x10, 0x05], result);
+        assert_eq!(sample, result);
+        let bridge = PciXBridge::try_from([0u8; PciXBridge::MIN_SIZE].as_slice()).unwrap();
+        assert_eq!(0x07, CapabilityKind::PciXBridge(bridge).kind_id());
+        assert_eq!(0x42, CapabilityKind::Reserved(0x42).kind_id());
+    }
+
+    #[cfg(not(feature = "hypertransport"))]
+    #[test]
+    fn disabled_decoder() {
+        let mut data = crate::synthetic::endpoint_with_msix(1);
+        // MSI-X Capability ID replaced with HyperTransport
+        data[crate::synthetic::INTERRUPT_PTR as usize] = 0x08;
+        let header = data.as_slice().try_into().unwrap();
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let result = Capabilities::new(ddr, &header)
+            .map(|cap| cap.unwrap().kind)
+            .collect::<Vec<_>>();
+        assert_eq!(Some(&CapabilityKind::Reserved(0x08)), result.last());
+    }
+
+    macro_rules! assert_min_size {
+        ($t:ty) => {
+            assert_min_size!($t, [0u8; <$t>::MIN_SIZE])
+        };
+        ($t:ty, $data:expr) => {{
+            let data = $data;
+            assert!(<$t>::try_from(&data[..]).is_ok(), "{}", stringify!($t));
+            assert!(
+                <$t>::try_from(&data[..data.len() - 1]).is_err(),
+                "{}",
+                stringify!($t)
+            );
+        }};
+    }
+
+    #[test]
+    fn min_size() {
+        assert_min_size!(PowerManagementInterface);
+        assert_min_size!(AcceleratedGraphicsPort);
+        assert_min_size!(VitalProductData);
+        assert_min_size!(SlotIdentification);
+        assert_min_size!(MessageSignaledInterrups);
+        assert_min_size!(PciX);
+        assert_min_size!(PciXBridge);
+        #[cfg(feature = "hypertransport")]
+        {
+            let mut data = [0u8; Hypertransport::MIN_SIZE];
+            // Reserved-Host capability type does not have additional registers
+            data[1] = 0b01001 << 3;
+            assert_min_size!(Hypertransport, data);
+        }
+        assert_min_size!(DebugPort);
+        assert_min_size!(BridgeSubsystemVendorId);
+        assert_min_size!(PciExpress);
+        assert_min_size!(MsiX);
+        assert_min_size!(Sata);
+        assert_min_size!(AdvancedFeatures);
+        assert_min_size!(FlatteningPortalBridge);
+
+        let header: Header = [0u8; 0x40].as_slice().try_into().unwrap();
+        // Length byte counts Capability ID and Next Pointer
+        let data = [3];
+        assert!(VendorSpecific::try_new(&data[..VendorSpecific::MIN_SIZE], &header).is_ok());
+        assert!(VendorSpecific::try_new(&data[..0], &header).is_err());
+        let data = [0u8; EnhancedAllocation::MIN_SIZE];
+        assert!(EnhancedAllocation::try_new(&data, &header).is_ok());
+        assert!(EnhancedAllocation::try_new(&data[..1], &header).is_err());
+    }
+
+    macro_rules! assert_size {
+        ($($t:ty),+ $(,)?) => {$(
+            assert!(<$t>::SIZE >= <$t>::MIN_SIZE, "{}", stringify!($t));
+            assert!(<$t>::try_from([0u8; <$t>::SIZE].as_slice()).is_ok(), "{}", stringify!($t));
+        )+};
+    }
+
+    #[test]
+    fn size() {
+        assert_size!(
+            PowerManagementInterface,
+            VitalProductData,
+            SlotIdentification,
+            DebugPort,
+            PciHotPlug,
+            BridgeSubsystemVendorId,
+            PciExpress,
+            MsiX,
+            Sata,
+            AdvancedFeatures,
+            FlatteningPortalBridge,
+        );
+        assert_eq!(PciExpress::MIN_SIZE + 0x20, PciExpress::SIZE);
+    }
+
+    #[test]
+    fn data_error_size() {
+        let result = PowerManagementInterface::try_from([0u8; 5].as_slice());
+        let sample = Err(CapabilityDataError {
+            name: "Power Management Interface",
+            size: 6,
+        });
+        assert_eq!(sample, result);
+        assert_eq!(
+            "can't read mandatory registers (26 bytes) from slice",
+            PciExpress::try_from([0u8; 25].as_slice())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn truncated_capability_error() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_9dc8/config"
+        ));
+        let mut header: Header = data.as_slice().try_into().unwrap();
+        let mut ddr = [0u8; ECS_OFFSET - DDR_OFFSET];
+        // PCI Express capability at the very end of the DDR
+        ddr[0xf0 - DDR_OFFSET] = 0x10;
+        header.capabilities_pointer = 0xf0;
+        let result = Capabilities::new(&ddr, &header).next().unwrap().unwrap_err();
+        let sample = CapabilityError::PciExpress {
+            ptr: ConfigOffset(0xf0),
+            available: 14,
+            source: pci_express::PciExpressError::RequiredBytesSlice,
+        };
+        assert_eq!(sample, result);
+        assert_eq!(Some(ConfigOffset(0xf2)), result.offset());
+        assert_eq!(Some(PciExpress::MIN_SIZE), result.needed());
+        assert_eq!(Some(14), result.available());
+
+        header.capabilities_pointer = 0xff;
+        let result = Capabilities::new(&ddr, &header).next().unwrap().unwrap_err();
+        assert_eq!(
+            (Some(ConfigOffset(0xff)), Some(2), Some(1)),
+            (result.offset(), result.needed(), result.available())
+        );
+        assert_eq!(
+            "[ff] capability header is not available (1 of 2 bytes)",
+            result.to_string()
+        );
+        // Truncated header ends the list
+        assert_eq!(1, Capabilities::new(&ddr, &header).count());
+    }
+
+    #[test]
+    fn looped_list_limit() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_9dc8/config"
+        ));
+        let mut header: Header = data.as_slice().try_into().unwrap();
+        let mut ddr = [0u8; ECS_OFFSET - DDR_OFFSET];
+        // Null capability points to itself
+        ddr[0x40 - DDR_OFFSET..0x42 - DDR_OFFSET].copy_from_slice(&[0x00, 0x40]);
+        header.capabilities_pointer = 0x40;
+        let result = Capabilities::new(&ddr, &header).collect::<Vec<_>>();
+        assert_eq!(Capabilities::MAX_ENTRIES + 1, result.len());
+        let sample = Some(&Err(CapabilityError::Limit {
+            ptr: ConfigOffset(0x40),
+            limit: 48,
+        }));
+        assert_eq!(sample, result.last());
+        assert_eq!(
+            "[40] capabilities list is longer than 48 entries",
+            result.last().unwrap().as_ref().unwrap_err().to_string()
+        );
+
+        let mut caps = Capabilities::new(&ddr, &header).with_limit(2);
+        assert!(caps.next().unwrap().is_ok());
+        assert!(caps.next().unwrap().is_ok());
+        let error = caps.next().unwrap().unwrap_err();
+        assert_eq!(
+            (Some(ConfigOffset(0x40)), None, None),
+            (error.offset(), error.needed(), error.available())
+        );
+        assert_eq!(None, caps.next());
+
+        // Limit is reached only if the list continues
+        ddr[0x41 - DDR_OFFSET] = 0x00;
+        let result = Capabilities::new(&ddr, &header).with_limit(1);
+        assert!(result.map(|cap| cap.is_ok()).eq([true]));
+    }
+
+    #[test]
+    fn strict() {
+        let mut data = crate::synthetic::root_port(false);
+        let header = data.as_slice().try_into().unwrap();
+        let strict = |data: &[u8]| {
+            let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+            Capabilities::new(ddr, &header)
+                .strict()
+                .map(|cap| cap.map(|cap| cap.pointer.get()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![Ok(0x40), Ok(0x80)], strict(&data));
+
+        // Link Status: x8 Link of x4 Port
+        data[0x52] = 0x83;
+        let error = CapabilityError::ReservedEncoding {
+            ptr: ConfigOffset(0x40),
+            field: "Negotiated Link Width",
+        };
+        assert_eq!(
+            "[40] Negotiated Link Width has a reserved or contradictory encoding",
+            error.to_string()
+        );
+        assert_eq!(vec![Err(error), Ok(0x80)], strict(&data));
+        // Link Speeds encoded as Supported Link Speeds Vector bit 6 (0111b) are reserved
+        data[0x52] = 0x47;
+        let error = CapabilityError::ReservedEncoding {
+            ptr: ConfigOffset(0x40),
+            field: "Current Link Speed",
+        };
+        assert_eq!(vec![Err(error), Ok(0x80)], strict(&data));
+        data[0x4c] = 0x47;
+        let error = CapabilityError::ReservedEncoding {
+            ptr: ConfigOffset(0x40),
+            field: "Max Link Speed",
+        };
+        assert_eq!(vec![Err(error), Ok(0x80)], strict(&data));
+        data[0x4c] = 0x43;
+        // Link is down
+        data[0x52] = 0x01;
+        assert_eq!(vec![Ok(0x40), Ok(0x80)], strict(&data));
+        // Root Port does not receive Set_Slot_Power_Limit
+        data[0x46] |= 25 << 2;
+        let error = CapabilityError::ReservedEncoding {
+            ptr: ConfigOffset(0x40),
+            field: "Captured Slot Power Limit",
+        };
+        assert_eq!(vec![Err(error), Ok(0x80)], strict(&data));
+        data[0x46] &= 0b11;
+        // Unassigned Capability ID is kept by permissive iterator
+        data[0x80] = 0x7f;
+        let error = CapabilityError::ReservedEncoding {
+            ptr: ConfigOffset(0x80),
+            field: "Capability ID",
+        };
+        assert_eq!(vec![Ok(0x40), Err(error)], strict(&data));
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        assert!(Capabilities::new(ddr, &header).all(|cap| cap.is_ok()));
+    }
+
+    #[cfg(not(feature = "hypertransport"))]
+    #[test]
+    fn strict_without_decoder() {
+        let mut data = crate::synthetic::root_port(false);
+        // HyperTransport Capability is parsed as Reserved, but its ID is known
+        data[0x80] = 0x08;
+        let header = data.as_slice().try_into().unwrap();
+        let ddr = &data[DDR_OFFSET..ECS_OFFSET];
+        let result = Capabilities::new(ddr, &header)
+            .strict()
+            .map(|cap| cap.map(|cap| cap.kind))
+            .nth(1);
+        assert_eq!(Some(Ok(CapabilityKind::Reserved(0x08))), result);
+    }
 }
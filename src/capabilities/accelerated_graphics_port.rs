@@ -120,6 +120,9 @@ pub struct AcceleratedGraphicsPort {
     pub isochronous_command: Option<IsochronousCommand>,
 }
 
+impl AcceleratedGraphicsPort {
+    pub const MIN_SIZE: usize = 2 + 4 + 4;
+}
 impl TryFrom<&[u8]> for AcceleratedGraphicsPort {
     type Error = CapabilityDataError;
 
@@ -129,7 +132,7 @@ impl TryFrom<&[u8]> for AcceleratedGraphicsPort {
             tail,
         } = P3(slice).try_into().map_err(|_| CapabilityDataError {
             name: "AGP",
-            size: 10,
+            size: Self::MIN_SIZE,
         })?;
         let status: Status = From::<u32>::from(status);
 
@@ -50,6 +50,10 @@ pub struct AdvancedFeatures {
     pub control: Control,
     pub status: Status,
 }
+impl AdvancedFeatures {
+    pub const SIZE: usize = 1 + 1 + 1 + 1;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for AdvancedFeatures {
     type Error = CapabilityDataError;
 
@@ -59,7 +63,7 @@ impl TryFrom<&[u8]> for AdvancedFeatures {
             ..
         } = P4(slice).try_into().map_err(|_| CapabilityDataError {
             name: "Advanced Features",
-            size: 4,
+            size: Self::MIN_SIZE,
         })?;
         let Lsb((cap_tp, function_level_reset, ())) = P3::<u8, 1, 1, 6>(capabilities).into();
         let Lsb((initiate_flr, ())) = P2::<u8, 1, 7>(control).into();
@@ -29,6 +29,7 @@ pub struct BridgeSubsystemVendorId {
 }
 impl BridgeSubsystemVendorId {
     pub const SIZE: usize = 2 + 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl From<[u8; BridgeSubsystemVendorId::SIZE]> for BridgeSubsystemVendorId {
@@ -49,7 +50,7 @@ impl<'a> TryFrom<&'a [u8]> for BridgeSubsystemVendorId {
             .and_then(|slice| <[u8; Self::SIZE]>::try_from(slice).ok())
             .ok_or(CapabilityDataError {
                 name: "Bridge Subsystem Vendor ID",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })
             .map(Self::from)
     }
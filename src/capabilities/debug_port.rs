@@ -1,21 +1,28 @@
 /*!
 Debug port
 
+EHCI Debug Port location: the Debug Port registers are located at
+[offset](DebugPort::offset) within the memory space mapped by the Base Address Register
+selected by [bar_number](DebugPort::bar_number).
+
 # Examples
 
 Capability value: `Debug port: BAR=1 offset=00a0`
 
 ```rust
-# use pcics::capabilities::DebugPort;
+# use pcics::{capabilities::DebugPort, ConfigOffset};
 let data = [0x0a, 0x98, 0xa0, 0x20];
-let dp = data[2..].try_into().unwrap();
+let dp: DebugPort = data[2..].try_into().unwrap();
 assert_eq!(DebugPort { offset: 0x00a0, bar_number: 1 }, dp);
+assert_eq!(Some(ConfigOffset(0x10)), dp.bar_register());
+assert_eq!(Some(0), dp.bar_index());
 ```
 */
 
 use heterob::{endianness::LeBytesTryInto, Seq};
 
 use super::CapabilityDataError;
+use crate::ConfigOffset;
 
 /// Debug port
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +33,23 @@ pub struct DebugPort {
     /// registers
     pub bar_number: u8,
 }
+impl DebugPort {
+    pub const SIZE: usize = 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+
+    /// Index of the Base Address Register (0 - 5), `None` for reserved BAR# values
+    pub fn bar_index(&self) -> Option<usize> {
+        match self.bar_number {
+            n @ 1..=6 => Some(n as usize - 1),
+            _ => None,
+        }
+    }
+    /// Configuration space offset of the Base Address Register (10h - 24h)
+    pub fn bar_register(&self) -> Option<ConfigOffset> {
+        self.bar_index()
+            .map(|i| ConfigOffset::new(0x10) + 4 * i as u16)
+    }
+}
 impl TryFrom<&[u8]> for DebugPort {
     type Error = CapabilityDataError;
 
@@ -33,7 +57,7 @@ impl TryFrom<&[u8]> for DebugPort {
         let Seq { head, .. }: Seq<u16, _> =
             slice.le_bytes_try_into().map_err(|_| CapabilityDataError {
                 name: "Debug port",
-                size: 2,
+                size: Self::MIN_SIZE,
             })?;
         Ok(DebugPort {
             offset: head & 0x1fff,
@@ -41,3 +65,41 @@ impl TryFrom<&[u8]> for DebugPort {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bar_location() {
+        let dp: DebugPort = [0x00, 0xc0].as_slice().try_into().unwrap();
+        assert_eq!(
+            DebugPort {
+                offset: 0,
+                bar_number: 6
+            },
+            dp
+        );
+        assert_eq!(Some(5), dp.bar_index());
+        assert_eq!(Some(ConfigOffset(0x24)), dp.bar_register());
+
+        let dp: DebugPort = [0xff, 0x1f].as_slice().try_into().unwrap();
+        assert_eq!(
+            DebugPort {
+                offset: 0x1fff,
+                bar_number: 0
+            },
+            dp
+        );
+        assert_eq!(None, dp.bar_register());
+        assert_eq!(
+            None,
+            DebugPort {
+                offset: 0,
+                bar_number: 7
+            }
+            .bar_index()
+        );
+    }
+}
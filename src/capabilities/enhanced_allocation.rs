@@ -42,7 +42,7 @@ let sample = EnhancedAllocationEntry {
 };
 let mut ea = EnhancedAllocation::try_new(&data[2..], &header).unwrap();
 let result = ea.entries.next();
-assert_eq!(Some(sample), result);
+assert_eq!(Some(Ok(sample)), result);
 ```
 */
 
@@ -58,11 +58,30 @@ use snafu::Snafu;
 
 /// Enhanced Allocation Errors
 #[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
 pub enum EnhancedAllocationError {
-    #[snafu(display("number of entries is unreadable"))]
+    #[snafu(display(
+        "number of entries ({} bytes) is unreadable",
+        EnhancedAllocation::MIN_SIZE
+    ))]
     NumEntries,
-    #[snafu(display("second DW for Type 1 is unreadable"))]
+    #[snafu(display(
+        "second DW for Type 1 is unreadable ({} bytes)",
+        EnhancedAllocation::TYPE_1_MIN_SIZE
+    ))]
     Type1SecondDw,
+    #[snafu(display("entry {index} is unreadable"))]
+    Entry { index: u8 },
+}
+impl EnhancedAllocationError {
+    /// Num Entries, or with the second DW for Type 1 functions; entry errors are not size related
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::NumEntries => Some(EnhancedAllocation::MIN_SIZE),
+            Self::Type1SecondDw => Some(EnhancedAllocation::TYPE_1_MIN_SIZE),
+            Self::Entry { .. } => None,
+        }
+    }
 }
 
 /// Enhanced Allocation (EA) Capability
@@ -74,6 +93,10 @@ pub struct EnhancedAllocation<'a> {
 }
 
 impl<'a> EnhancedAllocation<'a> {
+    /// Num Entries and reserved byte
+    pub const MIN_SIZE: usize = 2;
+    /// Type 1 functions have Fixed Secondary/Subordinate Bus Numbers DW
+    pub const TYPE_1_MIN_SIZE: usize = Self::MIN_SIZE + 4;
     pub fn try_new(slice: &'a [u8], header: &'a Header) -> Result<Self, EnhancedAllocationError> {
         if let [num_entries, _, slice @ ..] = slice {
             let num_entries = *num_entries & 0x3f;
@@ -88,7 +111,7 @@ impl<'a> EnhancedAllocation<'a> {
                         entries: EnhancedAllocationEntries::new(slice, num_entries),
                     })
                 } else {
-                    Err(EnhancedAllocationError::NumEntries)
+                    Err(EnhancedAllocationError::Type1SecondDw)
                 }
             } else {
                 Ok(Self {
@@ -108,27 +131,42 @@ impl<'a> EnhancedAllocation<'a> {
 pub struct EnhancedAllocationEntries<'a> {
     data: &'a [u8],
     count: u8,
+    index: u8,
 }
 
 impl<'a> EnhancedAllocationEntries<'a> {
     pub fn new(data: &'a [u8], count: u8) -> Self {
-        Self { data, count }
+        Self {
+            data,
+            count,
+            index: 0,
+        }
     }
 }
 
 impl<'a> Iterator for EnhancedAllocationEntries<'a> {
-    type Item = EnhancedAllocationEntry;
+    type Item = Result<EnhancedAllocationEntry, EnhancedAllocationError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.count == 0 {
             return None;
         }
+        let result = self.entry();
+        // Entries after the truncated one are not readable either
+        self.count = if result.is_ok() { self.count - 1 } else { 0 };
+        self.index += 1;
+        Some(result)
+    }
+}
+
+impl<'a> EnhancedAllocationEntries<'a> {
+    fn entry(&mut self) -> Result<EnhancedAllocationEntry, EnhancedAllocationError> {
+        let error = EnhancedAllocationError::Entry { index: self.index };
         let Seq {
             head: Le((first_dw, base_lo, max_offset_lo)),
             tail: mut _slice,
-        } = P3(self.data).try_into().ok()?;
+        } = P3(self.data).try_into().map_err(|_| error.clone())?;
         let _: (u32, u32) = (base_lo, max_offset_lo);
-        self.count -= 1;
 
         let Lsb((
             entry_size,
@@ -147,7 +185,7 @@ impl<'a> Iterator for EnhancedAllocationEntries<'a> {
         // and must always be interpreted as 00b.
         let base_lo = base_lo & !0b11;
         let base = if is_base_64 {
-            let Seq { head, tail } = _slice.le_bytes_try_into().ok()?;
+            let Seq { head, tail } = _slice.le_bytes_try_into().map_err(|_| error.clone())?;
             let _: u32 = head;
             _slice = tail;
             let base_hi = (head as u64) << 32;
@@ -161,7 +199,7 @@ impl<'a> Iterator for EnhancedAllocationEntries<'a> {
         // field, and must always be interpreted as 11b.
         let max_offset_lo = max_offset_lo | 0b11;
         let max_offset = if is_max_offset_64 {
-            let Seq { head, tail } = _slice.le_bytes_try_into().ok()?;
+            let Seq { head, tail } = _slice.le_bytes_try_into().map_err(|_| error)?;
             let _: u32 = head;
             _slice = tail;
             let max_offset_hi = (head as u64) << 32;
@@ -177,10 +215,11 @@ impl<'a> Iterator for EnhancedAllocationEntries<'a> {
         } else {
             // according to entry size field
             let next_entry_start = (entry_size as usize + 1) * 4;
-            self.data = self.data.get(next_entry_start..)?;
+            // Entry Size past the end of data is reported by the next entry
+            self.data = self.data.get(next_entry_start..).unwrap_or_default();
         }
 
-        Some(EnhancedAllocationEntry {
+        Ok(EnhancedAllocationEntry {
             entry_size,
             bar_equivalent_indicator: From::<u8>::from(bar_equivalent_indicator),
             primary_properties: From::<u8>::from(primary_properties),
@@ -349,3 +388,42 @@ pub enum ResourceRangeAddress {
     U32(u32),
     U64(u64),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn truncated_entries() {
+        // Entry Size 2, 32-bit Base and MaxOffset
+        let entry = [
+            0x02, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0xfe, 0xfc, 0x0f, 0x00, 0x00,
+        ];
+        let sample = EnhancedAllocationEntry {
+            entry_size: 2,
+            bar_equivalent_indicator: BarEquivalentIndicator::Location10h,
+            primary_properties: ResourceDefinition::MemorySpaceNonPrefetchable,
+            secondary_properties: ResourceDefinition::MemorySpaceNonPrefetchable,
+            writable: false,
+            enable: true,
+            base: ResourceRangeAddress::U32(0xfe00_0000),
+            max_offset: ResourceRangeAddress::U32(0x0000_0fff),
+        };
+        // Num Entries is larger than the data
+        let result = EnhancedAllocationEntries::new(&entry, 3).collect::<Vec<_>>();
+        let error = EnhancedAllocationError::Entry { index: 1 };
+        assert_eq!(vec![Ok(sample.clone()), Err(error)], result);
+
+        // Entry Size past the end of data does not hide the entry
+        let mut data = entry;
+        data[0] = 0x04;
+        let result = EnhancedAllocationEntries::new(&data, 1).next();
+        let sample = EnhancedAllocationEntry {
+            entry_size: 4,
+            ..sample
+        };
+        assert_eq!(Some(Ok(sample)), result);
+    }
+}
@@ -96,6 +96,7 @@ pub struct FlatteningPortalBridge {
 
 impl FlatteningPortalBridge {
     pub const SIZE: usize = 2 + 8 * 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl TryFrom<&[u8]> for FlatteningPortalBridge {
@@ -107,7 +108,7 @@ impl TryFrom<&[u8]> for FlatteningPortalBridge {
             .map(|Seq { head, .. }| From::<[u8; Self::SIZE]>::from(head))
             .map_err(|_| CapabilityDataError {
                 name: "Flattening Portal Bridge",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })
     }
 }
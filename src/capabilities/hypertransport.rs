@@ -6,7 +6,7 @@
 //! Capability types:
 //! - [x] [Slave or Primary Interface](SlaveOrPrimaryInterface)
 //! - [x] [Host or Secondary Interface](HostOrSecondaryInterface)
-//! - [ ] Switch
+//! - [x] [Switch](Switch)
 //! - [ ] Reserved-Host
 //! - [ ] Interrupt Discovery and Configuration
 //! - [x] [Revision ID](RevisionId)
@@ -26,19 +26,34 @@
 use heterob::{bit_numbering::Lsb, endianness::Le, P10, P11, P13, P16, P17, P2, P3, P5, P6, P8};
 use snafu::Snafu;
 
+use crate::register::register;
+
 /// HyperTransport errors
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum HypertransportError {
+    #[snafu(display("command register ({} bytes) is unreadable", Hypertransport::MIN_SIZE))]
     CapabilityType,
+    #[snafu(display(
+        "Slave/Primary Interface block ({} bytes) is unreadable",
+        SlaveOrPrimaryInterface::SIZE
+    ))]
     SlaveOrPrimaryInterface,
+    #[snafu(display(
+        "Host/Secondary Interface block ({} bytes) is unreadable",
+        HostOrSecondaryInterface::SIZE
+    ))]
     HostOrSecondaryInterface,
+    #[snafu(display("Switch block ({} bytes) is unreadable", Switch::SIZE))]
     Switch,
     ReservedHost,
     InterruptDiscoveryAndConfiguration,
+    #[snafu(display("Revision ID ({} bytes) is unreadable", RevisionId::SIZE))]
     RevisionId,
     UnitIdClumping,
     ExtendedConfigurationSpaceAccess,
     AddressMapping,
+    #[snafu(display("MSI Mapping block ({} bytes) is unreadable", MsiMapping::SIZE))]
     MsiMapping,
     DirectRoute,
     VCSet,
@@ -48,6 +63,20 @@ pub enum HypertransportError {
     PowerManagement,
     HighNodeCount,
 }
+impl HypertransportError {
+    /// Command register, or the register block of the Capability Type
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::CapabilityType => Some(Hypertransport::MIN_SIZE),
+            Self::SlaveOrPrimaryInterface => Some(SlaveOrPrimaryInterface::SIZE),
+            Self::HostOrSecondaryInterface => Some(HostOrSecondaryInterface::SIZE),
+            Self::Switch => Some(Switch::SIZE),
+            Self::RevisionId => Some(RevisionId::SIZE),
+            Self::MsiMapping => Some(MsiMapping::SIZE),
+            _ => None,
+        }
+    }
+}
 
 /// The layout of the capabilities block is determined by the value in the Capability Type field in
 /// the Command register
@@ -92,13 +121,16 @@ pub enum Hypertransport {
     /// Reserved
     Reserved(u8),
 }
+impl Hypertransport {
+    pub const MIN_SIZE: usize = 2;
+}
 impl<'a> TryFrom<&'a [u8]> for Hypertransport {
     type Error = HypertransportError;
 
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
         let cmd = slice
-            .get(..2)
-            .and_then(|slice| <[u8; 2]>::try_from(slice).ok())
+            .get(..Self::MIN_SIZE)
+            .and_then(|slice| <[u8; Self::MIN_SIZE]>::try_from(slice).ok())
             .map(u16::from_le_bytes)
             .ok_or(HypertransportError::CapabilityType)?;
         // For the primary and secondary interface capability blocks,
@@ -117,7 +149,11 @@ impl<'a> TryFrom<&'a [u8]> for Hypertransport {
                 .and_then(|slice| <[u8; HostOrSecondaryInterface::SIZE]>::try_from(slice).ok())
                 .map(|data| Self::HostOrSecondaryInterface(data.into()))
                 .ok_or(HypertransportError::HostOrSecondaryInterface)?,
-            0b01000 => Self::Switch(Switch {}),
+            0b01000 => slice
+                .get(..Switch::SIZE)
+                .and_then(|slice| <[u8; Switch::SIZE]>::try_from(slice).ok())
+                .map(|data| Self::Switch(data.into()))
+                .ok_or(HypertransportError::Switch)?,
             0b01001 => Self::ReservedHost(ReservedHost {}),
             0b10000 => {
                 Self::InterruptDiscoveryAndConfiguration(InterruptDiscoveryAndConfiguration {})
@@ -142,7 +178,7 @@ impl<'a> TryFrom<&'a [u8]> for Hypertransport {
             0b11011 => Self::FunctionLevelExtension(FunctionLevelExtension {}),
             0b11100 => Self::PowerManagement(PowerManagement {}),
             0b11101 => Self::HighNodeCount(HighNodeCount {}),
-            v => Self::Reserved(v as u8),
+            v => Self::Reserved(v),
         })
     }
 }
@@ -429,9 +465,10 @@ impl From<u16> for LinkConfiguration {
 
 /// Indicate the physical width of the incoming side of the HyperTransport link implemented by this
 /// device. Unganged links indicate a maximum width of 8 bits.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum LinkWidth {
     /// 8 bits
+    #[default]
     Width8bits,
     /// 16 bits
     Width16bits,
@@ -459,11 +496,6 @@ impl From<u8> for LinkWidth {
         }
     }
 }
-impl Default for LinkWidth {
-    fn default() -> Self {
-        Self::Width8bits
-    }
-}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RevisionId {
@@ -473,22 +505,21 @@ pub struct RevisionId {
 impl RevisionId {
     pub const SIZE: usize = 1;
 }
-impl From<u8> for RevisionId {
-    fn from(byte: u8) -> Self {
-        let Lsb((minor, major)) = P2::<_, 5, 3>(byte).into();
-        Self { minor, major }
-    }
-}
+register!(RevisionId: u8 {
+    minor[0..5],
+    major[5..8],
+});
 impl<'a> From<&'a RevisionId> for u8 {
     fn from(data: &'a RevisionId) -> Self {
-        (data.major << 5) | (data.minor & 0b11111)
+        data.clone().into()
     }
 }
 
 /// The Link Frequency register specifies the operating frequency of the link’s transmitter
 /// clock—the data rate is twice this value.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum LinkFrequency {
+    #[default]
     Rate200MHz,
     Rate300MHz,
     Rate400MHz,
@@ -538,11 +569,6 @@ impl LinkFrequency {
         }
     }
 }
-impl Default for LinkFrequency {
-    fn default() -> Self {
-        Self::Rate200MHz
-    }
-}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinkError {
@@ -870,8 +896,76 @@ impl From<u16> for HostOrSecondaryCommand {
     }
 }
 
+/// Switch Capability
+///
+/// Controls a HyperTransport switch: which port takes transactions not claimed by any other
+/// port, which ports are visible to software and what traffic the performance counter counts.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Switch {}
+pub struct Switch {
+    /// Command
+    pub command: SwitchCommand,
+    /// Downstream Port Bitmap, bit per port, set for ports leading away from the host
+    pub downstream_ports: u32,
+    /// Hide Port Bitmap, bit per port, set for ports hidden from enumeration
+    pub hidden_ports: u32,
+    /// Performance Counter Control
+    pub performance_counter_control: PerformanceCounterControl,
+    /// Performance Counter
+    pub performance_counter: u32,
+}
+impl Switch {
+    pub const SIZE: usize = 2 + 4 * 4;
+}
+impl From<[u8; Self::SIZE]> for Switch {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
+        let Le((
+            command,
+            downstream_ports,
+            hidden_ports,
+            performance_counter_control,
+            performance_counter,
+        )) = P5(bytes).into();
+        Self {
+            command: From::<u16>::from(command),
+            downstream_ports,
+            hidden_ports,
+            performance_counter_control: From::<u32>::from(performance_counter_control),
+            performance_counter,
+        }
+    }
+}
+
+/// Switch Command register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchCommand {
+    /// Default Port, takes requests not claimed by any other port (subtractive decode)
+    pub default_port: u8,
+    /// Default Port Enable
+    pub default_port_enable: bool,
+}
+register!(SwitchCommand: u16 {
+    default_port[0..5],
+    default_port_enable[5],
+});
+
+/// Switch Performance Counter Control register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerformanceCounterControl {
+    /// Port Select, port which traffic is counted
+    pub port: u8,
+    /// Count Incoming, count packets received by the port instead of sent
+    pub count_incoming: bool,
+    /// Counter Overflow
+    pub overflow: bool,
+    /// Counter Enable
+    pub enable: bool,
+}
+register!(PerformanceCounterControl: u32 {
+    port[0..5],
+    count_incoming[5],
+    overflow[30],
+    enable[31],
+});
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ReservedHost {}
@@ -1276,4 +1370,39 @@ mod tests {
 
         assert_eq!(0x11da173400000000, result.base_address());
     }
+
+    #[test]
+    fn switch() {
+        // Default Port 2, ports 1-3 downstream, port 3 hidden, counting port 1 incoming
+        let data = [
+            0x08, 0x00, 0x22, 0x40, // +00h
+            0x0e, 0x00, 0x00, 0x00, // +04h
+            0x08, 0x00, 0x00, 0x00, // +08h
+            0x21, 0x00, 0x00, 0x80, // +0Ch
+            0x10, 0x27, 0x00, 0x00, // +10h
+        ];
+        let result = Hypertransport::try_from(&data[2..]).unwrap();
+        let sample = Hypertransport::Switch(Switch {
+            command: SwitchCommand {
+                default_port: 2,
+                default_port_enable: true,
+            },
+            downstream_ports: 0b1110,
+            hidden_ports: 0b1000,
+            performance_counter_control: PerformanceCounterControl {
+                port: 1,
+                count_incoming: true,
+                overflow: false,
+                enable: true,
+            },
+            performance_counter: 10_000,
+        });
+        assert_eq!(sample, result);
+        assert_eq!(0b01000, u8::from(&result));
+
+        assert_eq!(
+            Err(HypertransportError::Switch),
+            Hypertransport::try_from(&data[2..data.len() - 1])
+        );
+    }
 }
@@ -20,13 +20,34 @@ pub const MSI_64BIT_PVM_SIZE: usize = 2 + 4 + 4 + 2 + 2 + 4 + 4;
 
 /// MSI Errors
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MessageSignaledInterrupsError {
+    #[snafu(display(
+        "Message Control register ({} bytes) is unreadable",
+        MessageControl::SIZE
+    ))]
     MessageControl,
+    #[snafu(display("32-bit structure ({MSI_32BIT_SIZE} bytes) is unreadable"))]
     Size32bit,
+    #[snafu(display("64-bit structure ({MSI_64BIT_SIZE} bytes) is unreadable"))]
     Size64bit,
+    #[snafu(display("32-bit structure with PVM ({MSI_32BIT_PVM_SIZE} bytes) is unreadable"))]
     Size32bitPerVector,
+    #[snafu(display("64-bit structure with PVM ({MSI_64BIT_PVM_SIZE} bytes) is unreadable"))]
     Size64bitPerVector,
 }
+impl MessageSignaledInterrupsError {
+    /// Message Control, or the whole structure for its addressing and masking variant
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::MessageControl => MessageControl::SIZE,
+            Self::Size32bit => MSI_32BIT_SIZE,
+            Self::Size64bit => MSI_64BIT_SIZE,
+            Self::Size32bitPerVector => MSI_32BIT_PVM_SIZE,
+            Self::Size64bitPerVector => MSI_64BIT_PVM_SIZE,
+        })
+    }
+}
 
 /// To request service, an MSI function writes the contents of the Message Data register to the
 /// address specified by the contents of the Message Address register (and, optionally, the Message
@@ -46,6 +67,10 @@ pub struct MessageSignaledInterrups {
     /// For each Pending bit that is set, the function has a pending associated message
     pub pending_bits: Option<u32>,
 }
+impl MessageSignaledInterrups {
+    /// 32-bit Message Address without Per-Vector Masking is the shortest layout
+    pub const MIN_SIZE: usize = MSI_32BIT_SIZE;
+}
 impl<'a> TryFrom<&'a [u8]> for MessageSignaledInterrups {
     type Error = MessageSignaledInterrupsError;
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
@@ -56,25 +81,12 @@ impl<'a> TryFrom<&'a [u8]> for MessageSignaledInterrups {
                 Some(u16::from_le_bytes(bytes))
             })
             .ok_or(MessageSignaledInterrupsError::MessageControl)?;
-        let Lsb((
-            msi_enable,
-            mmc,
-            mme,
-            a_64_bit_address_capable,
-            per_vector_masking_capable,
-            extended_message_data_capable,
-            extended_message_data_enable,
-            (),
-        )) = P8::<_, 1, 3, 3, 1, 1, 1, 1, 5>(mc).into();
-        let message_control = MessageControl {
-            msi_enable,
-            multiple_message_capable: MultipleMessage(mmc),
-            multiple_message_enable: MultipleMessage(mme),
+        let message_control = MessageControl::from(mc);
+        let MessageControl {
             a_64_bit_address_capable,
             per_vector_masking_capable,
-            extended_message_data_capable,
-            extended_message_data_enable,
-        };
+            ..
+        } = message_control;
         let msi = match (a_64_bit_address_capable, per_vector_masking_capable) {
             (false, false) => {
                 let Le((addr, message_data, extended_message_data)) = slice
@@ -160,7 +172,11 @@ impl<'a> TryFrom<&'a [u8]> for MessageSignaledInterrups {
 }
 
 /// Provides system software control over MSI.
-#[derive(Default, Debug, PartialEq, Eq)]
+///
+/// PCI Local Bus 3.0 defines bits 8:0 only, Extended Message Data ECN adds bits 10:9. Set
+/// reserved bits usually mean the register is read at a wrong offset, see
+/// [MessageControl::warnings].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MessageControl {
     pub msi_enable: bool,
     pub multiple_message_capable: MultipleMessage,
@@ -169,9 +185,101 @@ pub struct MessageControl {
     pub per_vector_masking_capable: bool,
     pub extended_message_data_capable: bool,
     pub extended_message_data_enable: bool,
+    /// Register value as read
+    pub raw: u16,
 }
 impl MessageControl {
     pub const SIZE: usize = 2;
+    /// Reserved bits of the current specification
+    pub const RESERVED: u16 = 0xf800;
+    /// Reserved bits before Extended Message Data ECN
+    pub const RESERVED_PCI_3_0: u16 = 0xfe00;
+    /// Set bits of the register reserved by the current specification
+    pub fn reserved_bits(&self) -> u16 {
+        self.raw & Self::RESERVED
+    }
+    /// An iterator through inconsistencies of the register value
+    pub fn warnings(&self) -> MessageControlWarnings {
+        MessageControlWarnings {
+            message_control: *self,
+            index: 0,
+        }
+    }
+}
+impl From<u16> for MessageControl {
+    fn from(word: u16) -> Self {
+        let Lsb((
+            msi_enable,
+            mmc,
+            mme,
+            a_64_bit_address_capable,
+            per_vector_masking_capable,
+            extended_message_data_capable,
+            extended_message_data_enable,
+            (),
+        )) = P8::<_, 1, 3, 3, 1, 1, 1, 1, 5>(word).into();
+        Self {
+            msi_enable,
+            multiple_message_capable: MultipleMessage(mmc),
+            multiple_message_enable: MultipleMessage(mme),
+            a_64_bit_address_capable,
+            per_vector_masking_capable,
+            extended_message_data_capable,
+            extended_message_data_enable,
+            raw: word,
+        }
+    }
+}
+
+/// Message Control value which is not expected from a conforming function
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageControlWarning {
+    #[snafu(display("reserved bits {bits:#06x} are set"))]
+    ReservedBits { bits: u16 },
+    #[snafu(display("Extended Message Data Enable is set, but the function is not capable"))]
+    ExtendedMessageDataEnable,
+    #[snafu(display("Multiple Message Capable has reserved encoding {value:#05b}"))]
+    MultipleMessageCapable { value: u8 },
+    #[snafu(display(
+        "Multiple Message Enable ({enabled:#05b}) exceeds Multiple Message Capable ({capable:#05b})"
+    ))]
+    MultipleMessageEnable { enabled: u8, capable: u8 },
+}
+
+/// An iterator through [MessageControlWarning]s of a [MessageControl]
+#[derive(Debug, Clone)]
+pub struct MessageControlWarnings {
+    message_control: MessageControl,
+    index: usize,
+}
+impl Iterator for MessageControlWarnings {
+    type Item = MessageControlWarning;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mc = &self.message_control;
+        let MultipleMessage(capable) = mc.multiple_message_capable;
+        let MultipleMessage(enabled) = mc.multiple_message_enable;
+        loop {
+            self.index += 1;
+            let warning = match self.index {
+                1 if mc.reserved_bits() != 0 => MessageControlWarning::ReservedBits {
+                    bits: mc.reserved_bits(),
+                },
+                2 if mc.extended_message_data_enable && !mc.extended_message_data_capable => {
+                    MessageControlWarning::ExtendedMessageDataEnable
+                }
+                3 if capable > MultipleMessage::MAX => {
+                    MessageControlWarning::MultipleMessageCapable { value: capable }
+                }
+                4 if enabled > capable => {
+                    MessageControlWarning::MultipleMessageEnable { enabled, capable }
+                }
+                1..=4 => continue,
+                _ => return None,
+            };
+            return Some(warning);
+        }
+    }
 }
 
 /// System-specified message address
@@ -188,9 +296,11 @@ impl Default for MessageAddress {
 
 /// The number of requested vectors must be aligned to a power of two (if a function requires three
 /// vectors, it requests four by initializing this field to “010”).
-#[derive(Default, Debug, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MultipleMessage(pub u8);
 impl MultipleMessage {
+    /// Largest defined encoding, 32 vectors
+    pub const MAX: u8 = 0b101;
     pub fn number_of_vectors(&self) -> u8 {
         1 << self.0
     }
@@ -200,11 +310,12 @@ impl MultipleMessage {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
 
     #[test]
     fn message_address_32bit() {
         let mut data =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k")).clone();
+            *include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k"));
         let control = 0b0_0000_0000u16.to_le_bytes();
         data[2] = control[0];
         data[3] = control[1];
@@ -218,6 +329,7 @@ mod tests {
                 a_64_bit_address_capable: false,
                 extended_message_data_capable: false,
                 extended_message_data_enable: false,
+                raw: 0x0000,
             },
             message_address: MessageAddress::Dword(0x95f8e4dc),
             message_data: 0xcb86,
@@ -231,7 +343,7 @@ mod tests {
     #[test]
     fn message_address_64bit() {
         let mut data =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k")).clone();
+            *include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k"));
         let control = 0b0_1000_0000u16.to_le_bytes();
         data[2] = control[0];
         data[3] = control[1];
@@ -245,6 +357,7 @@ mod tests {
                 a_64_bit_address_capable: true,
                 extended_message_data_capable: false,
                 extended_message_data_enable: false,
+                raw: 0x0080,
             },
             message_address: MessageAddress::Qword(0x1A87CB8695F8E4DC),
             message_data: 0x5eb6,
@@ -258,7 +371,7 @@ mod tests {
     #[test]
     fn message_address_32bit_per_vector_masking() {
         let mut data =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k")).clone();
+            *include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k"));
         let control = 0b1_0000_0000u16.to_le_bytes();
         data[2] = control[0];
         data[3] = control[1];
@@ -272,6 +385,7 @@ mod tests {
                 a_64_bit_address_capable: false,
                 extended_message_data_capable: false,
                 extended_message_data_enable: false,
+                raw: 0x0100,
             },
             message_address: MessageAddress::Dword(0x95f8e4dc),
             message_data: 0xcb86,
@@ -285,7 +399,7 @@ mod tests {
     #[test]
     fn message_address_64bit_per_vector_masking() {
         let mut data =
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k")).clone();
+            *include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/random/4k"));
         let control = 0b1_1000_0000u16.to_le_bytes();
         data[2] = control[0];
         data[3] = control[1];
@@ -299,6 +413,7 @@ mod tests {
                 a_64_bit_address_capable: true,
                 extended_message_data_capable: false,
                 extended_message_data_enable: false,
+                raw: 0x0180,
             },
             message_address: MessageAddress::Qword(0x1A87CB8695F8E4DC),
             message_data: 0x5eb6,
@@ -308,4 +423,33 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn message_control_warnings() {
+        let mc = MessageControl::from(0b1_1010_0101);
+        assert_eq!(0, mc.warnings().count());
+
+        // Extended Message Data is defined by the ECN, bits are not reserved
+        let mc = MessageControl::from(0b110_0000_0000);
+        assert_eq!(0, mc.reserved_bits());
+        assert_eq!(0, mc.warnings().count());
+
+        let mc = MessageControl::from(0b1000_0100_0011_1110);
+        assert_eq!(0x8000, mc.reserved_bits());
+        let result = mc.warnings().collect::<Vec<_>>();
+        let sample = [
+            MessageControlWarning::ReservedBits { bits: 0x8000 },
+            MessageControlWarning::ExtendedMessageDataEnable,
+            MessageControlWarning::MultipleMessageCapable { value: 0b111 },
+            MessageControlWarning::MultipleMessageEnable {
+                enabled: 0b011,
+                capable: 0b010,
+            },
+        ];
+        assert_eq!(sample[..3], result[..]);
+
+        let mc = MessageControl::from(0b0011_0100);
+        let result = mc.warnings().collect::<Vec<_>>();
+        assert_eq!(sample[3..], result[..]);
+    }
 }
@@ -22,6 +22,7 @@ pub struct MsiX {
 }
 impl MsiX {
     pub const SIZE: usize = 2 + 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 impl From<[u8; MsiX::SIZE]> for MsiX {
     fn from(bytes: [u8; MsiX::SIZE]) -> Self {
@@ -41,7 +42,7 @@ impl<'a> TryFrom<&'a [u8]> for MsiX {
             .and_then(|slice| <[u8; Self::SIZE]>::try_from(slice).ok())
             .ok_or(CapabilityDataError {
                 name: "MSI-X",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })
             .map(Self::from)
     }
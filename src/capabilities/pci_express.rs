@@ -213,28 +213,60 @@ let sample = PciExpress {
             unsupported_request_detected: true,
             aux_power_detected: false,
             transactions_pending: false,
+            emergency_power_reduction_detected: false,
         },
     },
     device_2: None,
+    register_blocks: RegisterBlocks {
+        root: RegisterBlock::NotApplicable,
+        device_2: RegisterBlock::NotApplicable,
+        link_2: RegisterBlock::NotApplicable,
+        slot_2: RegisterBlock::NotApplicable,
+    },
 };
 assert_eq!(sample, result);
+
+// Registers encode back, Root and version 2 registers are not implemented and stay zero
+let bytes = <[u8; PciExpress::SIZE]>::from(&sample);
+assert_eq!(data[2..], bytes[..data.len() - 2]);
+assert!(bytes[data.len() - 2..].iter().all(|&b| b == 0));
 ```
 */
 
-use core::ops::Range;
+use core::{cmp::Ordering, fmt, ops::Range};
 
-use heterob::{
-    bit_numbering::LsbInto, endianness::Le, P10, P12, P14, P2, P21, P3, P4, P6, P7, P8, P9,
-};
+use heterob::{bit_numbering::LsbInto, endianness::Le, P10, P12, P14, P3, P6};
 use snafu::prelude::*;
 
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor},
+    register::{flags, impl_u8_field, register},
+    ConfigOffset,
+};
+
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum PciExpressError {
-    #[snafu(display("can't read mandatory (0x1a) bytes from slice"))]
+    #[snafu(display(
+        "can't read mandatory registers ({} bytes) from slice",
+        PciExpress::MIN_SIZE
+    ))]
     RequiredBytesSlice,
-    #[snafu(display("can't read root bytes from slice"))]
+    #[snafu(display(
+        "can't read root registers ({} bytes) from slice",
+        PciExpress::ROOT_SIZE
+    ))]
     RootBytesSlice,
 }
+impl PciExpressError {
+    /// Mandatory registers, or with Root registers for Root Ports and Root Complex Event Collectors
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::RequiredBytesSlice => PciExpress::MIN_SIZE,
+            Self::RootBytesSlice => PciExpress::ROOT_SIZE,
+        })
+    }
+}
 
 /// PCI Express Capability Structure
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -251,20 +283,205 @@ pub struct PciExpress {
     pub tcs_routing_support: bool,
     pub device: Device,
     pub device_2: Option<Device2>,
+    /// Optional register blocks, tells blocks missing from the data from not implemented ones
+    pub register_blocks: RegisterBlocks,
 }
 impl PciExpress {
     pub const SIZE: usize = 0x3c - super::Capability::HEADER_SIZE;
+    /// PCI Express Capabilities, Device, Link and Slot registers
+    pub const MIN_SIZE: usize = 0x1a;
+    /// Mandatory registers with Root registers, required for Root Ports and Root Complex Event
+    /// Collectors
+    pub const ROOT_SIZE: usize = Self::MIN_SIZE + 8;
+    /// Captured Slot Power Limit, `None` if the Function is not associated with an
+    /// [Upstream Port](DeviceType::receives_set_slot_power_limit)
+    ///
+    /// ```rust
+    /// # use pcics::capabilities::pci_express::*;
+    /// // Endpoint with 25 W limit
+    /// let mut data = [0u8; PciExpress::SIZE];
+    /// data[0] = 0x02;
+    /// data[2..6].copy_from_slice(&(25u32 << 18).to_le_bytes());
+    /// let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+    /// let watts = pcie.captured_slot_power_limit().and_then(SlotPowerLimit::watts);
+    /// assert_eq!(Some(25.0), watts);
+    /// assert!(!pcie.has_stray_captured_slot_power_limit());
+    ///
+    /// // Same limit on a Root Port is meaningless
+    /// data[0] = 0x42;
+    /// let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+    /// assert_eq!(None, pcie.captured_slot_power_limit());
+    /// assert!(pcie.has_stray_captured_slot_power_limit());
+    /// ```
+    pub fn captured_slot_power_limit(&self) -> Option<&SlotPowerLimit> {
+        self.device_type
+            .receives_set_slot_power_limit()
+            .then_some(&self.device.capabilities.captured_slot_power_limit)
+    }
+    /// Nonzero Captured Slot Power Limit in a Function with a known Device/Port Type not
+    /// associated with an Upstream Port
+    pub fn has_stray_captured_slot_power_limit(&self) -> bool {
+        let cspl = &self.device.capabilities.captured_slot_power_limit;
+        let nonzero = cspl.value != 0 || cspl.scale_encoding() != 0;
+        let known = !matches!(self.device_type, DeviceType::Reserved { .. });
+        nonzero && known && !self.device_type.receives_set_slot_power_limit()
+    }
+    /// Whether the `register` is implemented by the Device/Port Type and the Capability
+    /// Version
+    ///
+    /// Implemented registers missing from the data ([RegisterBlock::Truncated]) count as
+    /// implemented.
+    pub fn implements(&self, register: Register) -> bool {
+        let applicable = |block: RegisterBlock| block != RegisterBlock::NotApplicable;
+        let blocks = &self.register_blocks;
+        match register {
+            Register::PciExpressCapabilities
+            | Register::DeviceCapabilities
+            | Register::DeviceControl
+            | Register::DeviceStatus => true,
+            Register::LinkCapabilities | Register::LinkControl | Register::LinkStatus => {
+                self.device_type.link().is_some()
+            }
+            Register::SlotCapabilities | Register::SlotControl | Register::SlotStatus => {
+                self.device_type.slot().is_some()
+            }
+            Register::RootControl | Register::RootCapabilities | Register::RootStatus => {
+                applicable(blocks.root)
+            }
+            Register::DeviceCapabilities2 | Register::DeviceControl2 | Register::DeviceStatus2 => {
+                applicable(blocks.device_2)
+            }
+            Register::LinkCapabilities2 | Register::LinkControl2 | Register::LinkStatus2 => {
+                applicable(blocks.link_2)
+            }
+            Register::SlotCapabilities2 | Register::SlotControl2 | Register::SlotStatus2 => {
+                applicable(blocks.slot_2)
+            }
+        }
+    }
+    /// Offset of the `register` in the configuration space of the Function for the Capability
+    /// at `pointer`, `None` if the register is not [implemented](Self::implements)
+    ///
+    /// ```rust
+    /// # use pcics::{capabilities::pci_express::*, ConfigOffset};
+    /// // Capability Version 2 Root Complex Integrated Endpoint at 40h
+    /// let mut data = [0u8; PciExpress::SIZE];
+    /// data[0] = 0x92;
+    /// let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+    /// let pointer = ConfigOffset(0x40);
+    /// assert_eq!(
+    ///     Some(ConfigOffset(0x68)),
+    ///     pcie.absolute_offset_of(pointer, Register::DeviceControl2)
+    /// );
+    /// // There is no Link
+    /// assert_eq!(None, pcie.absolute_offset_of(pointer, Register::LinkControl2));
+    /// ```
+    pub fn absolute_offset_of(
+        &self,
+        pointer: ConfigOffset,
+        register: Register,
+    ) -> Option<ConfigOffset> {
+        self.implements(register)
+            .then(|| pointer + register.offset())
+    }
+    /// Emergency Power Reduction State, `None` if Device 2 registers are not implemented
+    pub fn emergency_power_reduction(&self) -> Option<EmergencyPowerReductionStatus> {
+        let device_2 = self.device_2.as_ref()?;
+        Some(EmergencyPowerReductionStatus {
+            supported: device_2
+                .capabilities
+                .emergency_power_reduction_supported
+                .clone(),
+            initialization_required: device_2
+                .capabilities
+                .emergency_power_reduction_initialization_required,
+            requested: device_2.control.emergency_power_reduction_request,
+            detected: self.device.status.emergency_power_reduction_detected,
+        })
+    }
+    /// Sets control registers to the reset values, capabilities and status registers are kept
+    ///
+    /// Device models may start from a parsed image of the hardware initialized registers and
+    /// get a compliant baseline of the software controlled ones.
+    ///
+    /// ```rust
+    /// # use pcics::capabilities::pci_express::*;
+    /// let data = include_bytes!(concat!(
+    ///     env!("CARGO_MANIFEST_DIR"),
+    ///     "/tests/data/device/8086_2030/config"
+    /// ));
+    /// let mut pcie = PciExpress::try_from(&data[0x92..0xcc]).unwrap();
+    /// pcie.reset_controls();
+    /// assert_eq!(DeviceControl::default(), pcie.device.control);
+    /// assert_eq!(MaxSize::B128, pcie.device.control.max_payload_size);
+    /// let link = pcie.device_type.link().unwrap();
+    /// let link_2 = pcie.device_type.link_2().unwrap();
+    /// assert_eq!(link.capabilities.max_link_speed, link_2.control.target_link_speed);
+    /// ```
+    pub fn reset_controls(&mut self) {
+        self.device.control = Default::default();
+        if let Some(device_2) = &mut self.device_2 {
+            device_2.control = Default::default();
+        }
+        let (link, link_2, slot, root) = match &mut self.device_type {
+            DeviceType::Endpoint { link, link_2 }
+            | DeviceType::LegacyEndpoint { link, link_2 }
+            | DeviceType::UpstreamPort { link, link_2 }
+            | DeviceType::PcieToPciBridge { link, link_2 } => (Some(link), link_2, None, None),
+            DeviceType::RootComplexIntegratedEndpoint => (None, &mut None, None, None),
+            DeviceType::RootComplexEventCollector { root } => (None, &mut None, None, Some(root)),
+            DeviceType::RootPort {
+                link,
+                link_2,
+                slot,
+                root,
+                ..
+            } => (Some(link), link_2, Some(slot), Some(root)),
+            DeviceType::DownstreamPort {
+                link, link_2, slot, ..
+            }
+            | DeviceType::PciToPcieBridge {
+                link, link_2, slot, ..
+            } => (Some(link), link_2, Some(slot), None),
+            DeviceType::Reserved {
+                link,
+                link_2,
+                slot,
+                root,
+                ..
+            } => (Some(link), link_2, Some(slot), root.as_mut()),
+        };
+        if let Some(link) = link {
+            link.control = Default::default();
+            if let Some(link_2) = link_2 {
+                link_2.control = LinkControl2::new_spec_default(link.capabilities.max_link_speed);
+            }
+        }
+        if let Some(slot) = slot {
+            slot.control = Default::default();
+        }
+        if let Some(root) = root {
+            // No NFM Subtree Below This Root Port is hardware initialized
+            root.control = RootControl {
+                no_nfm_subtree_below_this_root_port: root
+                    .control
+                    .no_nfm_subtree_below_this_root_port,
+                ..Default::default()
+            };
+        }
+    }
 }
 impl<'a> TryFrom<&'a [u8]> for PciExpress {
     type Error = PciExpressError;
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
         // The PCI Express Capabilities, Device Capabilities, Device Status, and Device Control
         // registers are required for all PCI Express device Functions
-        let (start, end) = (0, 26);
+        let (start, end) = (0, Self::MIN_SIZE);
         let required_bytes = slice
             .get(start..end)
-            .and_then(|slice| <[u8; 26]>::try_from(slice).ok())
+            .and_then(|slice| <[u8; Self::MIN_SIZE]>::try_from(slice).ok())
             .ok_or(PciExpressError::RequiredBytesSlice)?;
+        let register_blocks = RegisterBlocks::try_from(slice)?;
         let Le((
             caps,
             dev_caps,
@@ -334,14 +551,268 @@ impl<'a> TryFrom<&'a [u8]> for PciExpress {
             tcs_routing_support,
             device,
             device_2,
+            register_blocks,
         })
     }
 }
+impl From<&PciExpress> for [u8; PciExpress::SIZE] {
+    /// Encodes registers as [PciExpress::try_from] expects them, registers not implemented by
+    /// the Function and reserved bits are zero
+    fn from(pcie: &PciExpress) -> Self {
+        let mut bytes = [0; PciExpress::SIZE];
+        let mut set = |register: Register, value: u32| {
+            let start = usize::from(register.offset()) - super::Capability::HEADER_SIZE;
+            let width = register.descriptor().width.bytes();
+            let register = bytes.get_mut(start..start + width).unwrap_or_default();
+            for (byte, value) in register.iter_mut().zip(value.to_le_bytes()) {
+                *byte = value;
+            }
+        };
+        let caps = u16::from(pcie.version & 0xf)
+            | u16::from(pcie.device_type.id() & 0xf) << 4
+            | u16::from(pcie.slot_implemented) << 8
+            | u16::from(pcie.interrupt_message_number & 0x1f) << 9
+            | u16::from(pcie.tcs_routing_support) << 14;
+        set(Register::PciExpressCapabilities, caps.into());
+        let Device {
+            capabilities,
+            control,
+            status,
+        } = pcie.device.clone();
+        set(Register::DeviceCapabilities, capabilities.into());
+        set(Register::DeviceControl, u16::from(control).into());
+        set(Register::DeviceStatus, u16::from(status).into());
+        if let Some(Link {
+            capabilities,
+            control,
+            status,
+        }) = pcie.device_type.link().cloned()
+        {
+            set(Register::LinkCapabilities, capabilities.into());
+            set(Register::LinkControl, u16::from(control).into());
+            set(Register::LinkStatus, u16::from(status).into());
+        }
+        if let Some(Slot {
+            capabilities,
+            control,
+            status,
+        }) = pcie.device_type.slot().cloned()
+        {
+            set(Register::SlotCapabilities, capabilities.into());
+            set(Register::SlotControl, u16::from(control).into());
+            set(Register::SlotStatus, u16::from(status).into());
+        }
+        if let Some(Root {
+            control,
+            capabilities,
+            status,
+        }) = pcie.device_type.root().cloned()
+        {
+            set(Register::RootControl, u16::from(control).into());
+            set(Register::RootCapabilities, u16::from(capabilities).into());
+            set(Register::RootStatus, status.into());
+        }
+        if let Some(Device2 {
+            capabilities,
+            control,
+            status,
+        }) = pcie.device_2.clone()
+        {
+            set(Register::DeviceCapabilities2, capabilities.into());
+            set(Register::DeviceControl2, u16::from(control).into());
+            set(Register::DeviceStatus2, u16::from(status).into());
+        }
+        if let Some(Link2 {
+            capabilities,
+            control,
+            status,
+        }) = pcie.device_type.link_2().cloned()
+        {
+            set(Register::LinkCapabilities2, capabilities.into());
+            set(Register::LinkControl2, u16::from(control).into());
+            set(Register::LinkStatus2, u16::from(status).into());
+        }
+        if let Some(Slot2 {
+            capabilities,
+            control,
+            status,
+        }) = pcie.device_type.slot_2().cloned()
+        {
+            set(Register::SlotCapabilities2, capabilities.into());
+            set(Register::SlotControl2, u16::from(control).into());
+            set(Register::SlotStatus2, u16::from(status).into());
+        }
+        bytes
+    }
+}
+
+/// Presence of an optional register block of the PCI Express Capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterBlock {
+    /// Registers are decoded
+    Present,
+    /// Registers are not implemented by the Capability Version or the Device/Port Type
+    NotApplicable,
+    /// Registers are implemented, but the data ends before them
+    Truncated,
+}
+impl RegisterBlock {
+    fn new(applicable: bool, slice: &[u8], end: usize) -> Self {
+        if !applicable {
+            Self::NotApplicable
+        } else if slice.len() < end {
+            Self::Truncated
+        } else {
+            Self::Present
+        }
+    }
+}
+
+/// Optional register blocks of the PCI Express Capability
+///
+/// [PciExpress] decodes a missing block as `None` both if the block is not implemented and if
+/// the data ends before it, [RegisterBlocks] kept in [PciExpress::register_blocks] tells these
+/// cases apart.
+///
+/// ```rust
+/// # use pcics::capabilities::pci_express::*;
+/// // Capability Version 2 Endpoint, data ends after Device 2 registers
+/// let mut data = [0u8; PciExpress::ROOT_SIZE + 8];
+/// data[0] = 0x02;
+/// let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+/// let blocks = RegisterBlocks::try_from(data.as_slice()).unwrap();
+/// assert!(pcie.device_2.is_some());
+/// assert_eq!(RegisterBlock::Present, blocks.device_2);
+/// assert_eq!(RegisterBlock::NotApplicable, blocks.root);
+/// assert_eq!(RegisterBlock::NotApplicable, blocks.slot_2);
+/// assert!(matches!(pcie.device_type, DeviceType::Endpoint { link_2: None, .. }));
+/// assert_eq!(RegisterBlock::Truncated, blocks.link_2);
+/// assert!(blocks.is_truncated());
+/// assert_eq!(blocks, pcie.register_blocks);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterBlocks {
+    /// Root Capabilities, Root Control and Root Status
+    pub root: RegisterBlock,
+    /// Device Capabilities 2, Device Control 2 and Device Status 2
+    pub device_2: RegisterBlock,
+    /// Link Capabilities 2, Link Control 2 and Link Status 2
+    pub link_2: RegisterBlock,
+    /// Slot Capabilities 2, Slot Control 2 and Slot Status 2
+    pub slot_2: RegisterBlock,
+}
+impl RegisterBlocks {
+    /// Some implemented register block is missing from the data
+    pub fn is_truncated(&self) -> bool {
+        [self.root, self.device_2, self.link_2, self.slot_2].contains(&RegisterBlock::Truncated)
+    }
+}
+impl<'a> TryFrom<&'a [u8]> for RegisterBlocks {
+    type Error = PciExpressError;
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        ensure!(slice.len() >= PciExpress::MIN_SIZE, RequiredBytesSliceSnafu);
+        let caps = match *slice {
+            [lo, hi, ..] => u16::from_le_bytes([lo, hi]),
+            _ => return RequiredBytesSliceSnafu.fail(),
+        };
+        let (version, device_type) = ((caps & 0xf) as u8, (caps >> 4 & 0xf) as u8);
+        // Reserved Device/Port Types keep all registers
+        let (has_root, has_link, has_slot) = match device_type {
+            0b0000 | 0b0001 | 0b0101 | 0b0111 => (false, true, false),
+            0b0100 => (true, true, true),
+            0b0110 | 0b1000 => (false, true, true),
+            0b1001 => (false, false, false),
+            0b1010 => (true, false, false),
+            _ => (true, true, true),
+        };
+        let v2 = version > 1;
+        let end = PciExpress::ROOT_SIZE;
+        Ok(Self {
+            root: RegisterBlock::new(has_root, slice, end),
+            device_2: RegisterBlock::new(v2, slice, end + 8),
+            link_2: RegisterBlock::new(v2 && has_link, slice, end + 16),
+            slot_2: RegisterBlock::new(v2 && has_slot, slice, end + 24),
+        })
+    }
+}
+
+/// Register of the PCI Express Capability
+///
+/// Locates a register for consumers doing configuration accesses on their own, see
+/// [PciExpress::absolute_offset_of].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    PciExpressCapabilities,
+    DeviceCapabilities,
+    DeviceControl,
+    DeviceStatus,
+    LinkCapabilities,
+    LinkControl,
+    LinkStatus,
+    SlotCapabilities,
+    SlotControl,
+    SlotStatus,
+    RootControl,
+    RootCapabilities,
+    RootStatus,
+    DeviceCapabilities2,
+    DeviceControl2,
+    DeviceStatus2,
+    LinkCapabilities2,
+    LinkControl2,
+    LinkStatus2,
+    SlotCapabilities2,
+    SlotControl2,
+    SlotStatus2,
+}
+impl Register {
+    /// Register location relative to the PCI Express Capability with its access rules
+    pub const fn descriptor(self) -> RegisterDescriptor {
+        use AccessWidth::{Dword, Word};
+        let new = RegisterDescriptor::new;
+        match self {
+            Self::PciExpressCapabilities => {
+                new("PCI Express Capabilities", 0x02, Word).read_only(u32::MAX)
+            }
+            Self::DeviceCapabilities => new("Device Capabilities", 0x04, Dword).read_only(u32::MAX),
+            Self::DeviceControl => DeviceControl::REGISTER,
+            Self::DeviceStatus => DeviceStatus::REGISTER,
+            Self::LinkCapabilities => new("Link Capabilities", 0x0c, Dword).read_only(u32::MAX),
+            Self::LinkControl => LinkControl::REGISTER,
+            Self::LinkStatus => LinkStatus::REGISTER,
+            Self::SlotCapabilities => new("Slot Capabilities", 0x14, Dword).read_only(u32::MAX),
+            Self::SlotControl => SlotControl::REGISTER,
+            Self::SlotStatus => SlotStatus::REGISTER,
+            Self::RootControl => RootControl::REGISTER,
+            Self::RootCapabilities => new("Root Capabilities", 0x1e, Word).read_only(u32::MAX),
+            Self::RootStatus => RootStatus::REGISTER,
+            Self::DeviceCapabilities2 => {
+                new("Device Capabilities 2", 0x24, Dword).read_only(u32::MAX)
+            }
+            Self::DeviceControl2 => DeviceControl2::REGISTER,
+            Self::DeviceStatus2 => new("Device Status 2", 0x2a, Word),
+            Self::LinkCapabilities2 => new("Link Capabilities 2", 0x2c, Dword).read_only(u32::MAX),
+            Self::LinkControl2 => LinkControl2::REGISTER,
+            // Link Equalization Request 8.0 GT/s and DRS Message Received
+            Self::LinkStatus2 => new("Link Status 2", 0x32, Word)
+                .read_only(0x73df)
+                .rw1c(0x8020),
+            Self::SlotCapabilities2 => new("Slot Capabilities 2", 0x34, Dword).read_only(u32::MAX),
+            Self::SlotControl2 => new("Slot Control 2", 0x38, Word),
+            Self::SlotStatus2 => new("Slot Status 2", 0x3a, Word),
+        }
+    }
+    /// Offset relative to the PCI Express Capability
+    pub const fn offset(self) -> u16 {
+        self.descriptor().offset
+    }
+}
 
 type DeviceTypeArgs = (u8, Link, Slot, Option<Root>, Option<Link2>, Option<Slot2>);
 
 /// Indicates the specific type of this PCI Express Function
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DeviceType {
     /// PCI Express Endpoint
     Endpoint { link: Link, link_2: Option<Link2> },
@@ -388,6 +859,21 @@ pub enum DeviceType {
     },
 }
 impl DeviceType {
+    /// Device/Port Type field encoding
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::Endpoint { .. } => 0b0000,
+            Self::LegacyEndpoint { .. } => 0b0001,
+            Self::RootPort { .. } => 0b0100,
+            Self::UpstreamPort { .. } => 0b0101,
+            Self::DownstreamPort { .. } => 0b0110,
+            Self::PcieToPciBridge { .. } => 0b0111,
+            Self::PciToPcieBridge { .. } => 0b1000,
+            Self::RootComplexIntegratedEndpoint => 0b1001,
+            Self::RootComplexEventCollector { .. } => 0b1010,
+            Self::Reserved { id, .. } => *id,
+        }
+    }
     pub fn is_endpoint(&self) -> bool {
         matches!(
             self,
@@ -410,6 +896,91 @@ impl DeviceType {
                 | DeviceType::PciToPcieBridge { .. }
         )
     }
+    /// Function is associated with an Upstream Port, the only one capturing Slot Power Limit
+    /// from Set_Slot_Power_Limit Messages
+    pub fn receives_set_slot_power_limit(&self) -> bool {
+        matches!(
+            self,
+            DeviceType::Endpoint { .. }
+                | DeviceType::LegacyEndpoint { .. }
+                | DeviceType::UpstreamPort { .. }
+                | DeviceType::PcieToPciBridge { .. }
+        )
+    }
+    /// Link Capabilities, Control and Status registers, `None` for Functions without a Link
+    pub fn link(&self) -> Option<&Link> {
+        match self {
+            Self::Endpoint { link, .. }
+            | Self::LegacyEndpoint { link, .. }
+            | Self::RootPort { link, .. }
+            | Self::UpstreamPort { link, .. }
+            | Self::DownstreamPort { link, .. }
+            | Self::PcieToPciBridge { link, .. }
+            | Self::PciToPcieBridge { link, .. }
+            | Self::Reserved { link, .. } => Some(link),
+            Self::RootComplexIntegratedEndpoint | Self::RootComplexEventCollector { .. } => None,
+        }
+    }
+    /// Slot Capabilities, Control and Status registers, `None` for Functions without a Slot
+    pub fn slot(&self) -> Option<&Slot> {
+        match self {
+            Self::RootPort { slot, .. }
+            | Self::DownstreamPort { slot, .. }
+            | Self::PciToPcieBridge { slot, .. }
+            | Self::Reserved { slot, .. } => Some(slot),
+            _ => None,
+        }
+    }
+    /// Root Control, Capabilities and Status registers, `None` for Functions without them
+    pub fn root(&self) -> Option<&Root> {
+        match self {
+            Self::RootPort { root, .. } | Self::RootComplexEventCollector { root } => Some(root),
+            Self::Reserved { root, .. } => root.as_ref(),
+            _ => None,
+        }
+    }
+    /// Link Capabilities 2, Control 2 and Status 2 registers, `None` if not implemented or
+    /// missing from the data
+    pub fn link_2(&self) -> Option<&Link2> {
+        match self {
+            Self::Endpoint { link_2, .. }
+            | Self::LegacyEndpoint { link_2, .. }
+            | Self::RootPort { link_2, .. }
+            | Self::UpstreamPort { link_2, .. }
+            | Self::DownstreamPort { link_2, .. }
+            | Self::PcieToPciBridge { link_2, .. }
+            | Self::PciToPcieBridge { link_2, .. }
+            | Self::Reserved { link_2, .. } => link_2.as_ref(),
+            Self::RootComplexIntegratedEndpoint | Self::RootComplexEventCollector { .. } => None,
+        }
+    }
+    /// Slot Capabilities 2, Control 2 and Status 2 registers, `None` if not implemented or
+    /// missing from the data
+    pub fn slot_2(&self) -> Option<&Slot2> {
+        match self {
+            Self::RootPort { slot_2, .. }
+            | Self::DownstreamPort { slot_2, .. }
+            | Self::PciToPcieBridge { slot_2, .. }
+            | Self::Reserved { slot_2, .. } => slot_2.as_ref(),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Endpoint { .. } => write!(f, "PCI Express Endpoint"),
+            Self::LegacyEndpoint { .. } => write!(f, "Legacy PCI Express Endpoint"),
+            Self::RootComplexIntegratedEndpoint => write!(f, "Root Complex Integrated Endpoint"),
+            Self::RootComplexEventCollector { .. } => write!(f, "Root Complex Event Collector"),
+            Self::RootPort { .. } => write!(f, "Root Port of PCI Express Root Complex"),
+            Self::UpstreamPort { .. } => write!(f, "Upstream Port of PCI Express Switch"),
+            Self::DownstreamPort { .. } => write!(f, "Downstream Port of PCI Express Switch"),
+            Self::PcieToPciBridge { .. } => write!(f, "PCI Express to PCI/PCI-X Bridge"),
+            Self::PciToPcieBridge { .. } => write!(f, "PCI/PCI-X to PCI Express Bridge"),
+            Self::Reserved { id, .. } => write!(f, "Reserved ({:04b})", id),
+        }
+    }
 }
 impl TryFrom<DeviceTypeArgs> for DeviceType {
     type Error = PciExpressError;
@@ -541,6 +1112,24 @@ impl From<u32> for DeviceCapabilities {
         }
     }
 }
+impl From<DeviceCapabilities> for u32 {
+    fn from(caps: DeviceCapabilities) -> Self {
+        let cspl = &caps.captured_slot_power_limit;
+        let (cspl_value, cspl_scale) = (cspl.value, cspl.scale_encoding());
+        u32::from(u8::from(caps.max_payload_size_supported))
+            | u32::from(u8::from(caps.phantom_functions_supported)) << 3
+            | u32::from(bool::from(caps.extended_tag_field_supported)) << 5
+            | u32::from(u8::from(caps.endpoint_l0s_acceptable_latency)) << 6
+            | u32::from(u8::from(caps.endpoint_l1_acceptable_latency)) << 9
+            | u32::from(caps.attention_button_present) << 12
+            | u32::from(caps.attention_indicator_present) << 13
+            | u32::from(caps.power_indicator_present) << 14
+            | u32::from(caps.role_based_error_reporting) << 15
+            | u32::from(cspl_value) << 18
+            | u32::from(cspl_scale) << 26
+            | u32::from(caps.function_level_reset_capability) << 28
+    }
+}
 
 /// Max_Payload_Size Supported / Max_Payload_Size / Max_Read_Request_Size
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -577,6 +1166,46 @@ impl From<u8> for MaxSize {
         }
     }
 }
+impl From<MaxSize> for u8 {
+    fn from(size: MaxSize) -> Self {
+        size as u8
+    }
+}
+impl_u8_field!(MaxSize);
+impl MaxSize {
+    /// Size in bytes, `None` for reserved encodings
+    pub fn bytes(&self) -> Option<u16> {
+        match self {
+            Self::Reserved0 | Self::Reserved1 => None,
+            size => Some(128 << u8::from(*size)),
+        }
+    }
+    /// Encoding of `bytes` size, `None` if it is not a power of two from 128 to 4096
+    pub fn try_from_bytes(bytes: u16) -> Option<Self> {
+        let size = match bytes {
+            128 => Self::B128,
+            256 => Self::B256,
+            512 => Self::B512,
+            1024 => Self::B1024,
+            2048 => Self::B2048,
+            4096 => Self::B4096,
+            _ => return None,
+        };
+        Some(size)
+    }
+}
+
+/// Max_Payload_Size or Max_Read_Request_Size can't be set
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MaxSizeError {
+    #[snafu(display("{size:?} is a reserved encoding"))]
+    Reserved { size: MaxSize },
+    #[snafu(display(
+        "Max_Payload_Size {size:?} exceeds Max_Payload_Size Supported {supported:?}"
+    ))]
+    Unsupported { size: MaxSize, supported: MaxSize },
+}
 
 /// Support for use of unclaimed Function Numbers to extend the number of outstanding transactions
 /// allowed by logically combining unclaimed Function Numbers (called Phantom Functions) with the
@@ -604,6 +1233,11 @@ impl From<u8> for PhantomFunctionsSupported {
         }
     }
 }
+impl From<PhantomFunctionsSupported> for u8 {
+    fn from(data: PhantomFunctionsSupported) -> Self {
+        data as u8
+    }
+}
 
 /// Maximum supported size of the Tag field as a Requester
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -622,6 +1256,11 @@ impl From<bool> for ExtendedTagFieldSupported {
         }
     }
 }
+impl From<ExtendedTagFieldSupported> for bool {
+    fn from(data: ExtendedTagFieldSupported) -> Self {
+        data == ExtendedTagFieldSupported::Eight
+    }
+}
 
 /// Acceptable total latency that an Endpoint can withstand due to the transition from L0s state to
 /// the L0 state
@@ -659,6 +1298,27 @@ impl From<u8> for EndpointL0sAcceptableLatency {
         }
     }
 }
+impl From<EndpointL0sAcceptableLatency> for u8 {
+    fn from(data: EndpointL0sAcceptableLatency) -> Self {
+        data as u8
+    }
+}
+
+impl EndpointL0sAcceptableLatency {
+    /// Acceptable latency in nanoseconds, `None` if there is no limit
+    pub fn max_ns(&self) -> Option<u32> {
+        match self {
+            Self::Max64ns => Some(64),
+            Self::Max128ns => Some(128),
+            Self::Max256ns => Some(256),
+            Self::Max512ns => Some(512),
+            Self::Max1us => Some(1_000),
+            Self::Max2us => Some(2_000),
+            Self::Max4us => Some(4_000),
+            Self::NoLimit => None,
+        }
+    }
+}
 
 /// Aacceptable latency that an Endpoint can withstand due to the transition from L1 state to the
 /// L0 state
@@ -696,9 +1356,43 @@ impl From<u8> for EndpointL1AcceptableLatency {
         }
     }
 }
+impl From<EndpointL1AcceptableLatency> for u8 {
+    fn from(data: EndpointL1AcceptableLatency) -> Self {
+        data as u8
+    }
+}
+
+impl EndpointL1AcceptableLatency {
+    /// Acceptable latency in nanoseconds, `None` if there is no limit
+    pub fn max_ns(&self) -> Option<u32> {
+        match self {
+            Self::Max1us => Some(1_000),
+            Self::Max2us => Some(2_000),
+            Self::Max4us => Some(4_000),
+            Self::Max8us => Some(8_000),
+            Self::Max16us => Some(16_000),
+            Self::Max32us => Some(32_000),
+            Self::Max64us => Some(64_000),
+            Self::NoLimit => None,
+        }
+    }
+}
 
 /// Slot Power Limit (Captured)
 /// Specifies the upper limit on power available/supplied to the adapter
+///
+/// With 1.0x scale values above EFh are alternative encodings: F0h is 250 W, F1h is 275 W,
+/// F2h is 300 W, F3h to FFh are reserved for limits above 300 W.
+///
+/// ```rust
+/// # use pcics::capabilities::pci_express::SlotPowerLimit;
+/// let spl = SlotPowerLimit::from_watts(275.0).unwrap();
+/// assert_eq!((0xf1, 0b00), (spl.value, spl.scale_encoding()));
+/// assert_eq!(Some(275.0), spl.watts());
+///
+/// let spl = SlotPowerLimit::from_watts(7.5).unwrap();
+/// assert_eq!((75, 0b01), (spl.value, spl.scale_encoding()));
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct SlotPowerLimit {
     /// Slot Power Limit Value
@@ -719,16 +1413,72 @@ impl SlotPowerLimit {
             _ => unreachable!(),
         }
     }
+    /// Alternative 1.0x scale encodings of 250 W, 275 W and 300 W
+    const ALTERNATIVE_WATTS: [u16; 3] = [250, 275, 300];
+    /// Limit closest to `watts` without exceeding it
+    ///
+    /// The coarsest scale is preferred if several (value, scale) pairs encode the same limit,
+    /// limits above 300 W are clamped to 300 W. Returns `None` for negative or NaN `watts`.
+    pub fn from_watts(watts: f32) -> Option<Self> {
+        if watts.is_nan() || watts < 0.0 {
+            return None;
+        }
+        let milliwatts = (watts * 1000.0) as u32;
+        // (value, scale encoding, milliwatts) triples with the 1.0x scale first
+        let regular = [1000, 100, 10, 1]
+            .into_iter()
+            .zip(0u8..)
+            .map(|(unit, scale)| {
+                let max = if scale == 0b00 { 0xef } else { 0xff };
+                let value = (milliwatts / unit).min(max);
+                (value as u8, scale, value * unit)
+            });
+        let alternative = Self::ALTERNATIVE_WATTS
+            .into_iter()
+            .zip(0xf0u8..)
+            .map(|(w, value)| (value, 0b00, u32::from(w) * 1000))
+            .filter(|&(_, _, mw)| mw <= milliwatts);
+        let best = alternative
+            .chain(regular)
+            .reduce(|best, pair| if pair.2 > best.2 { pair } else { best });
+        let (value, scale, _) = best?;
+        Some(Self::new(value, scale))
+    }
+    /// Slot Power Limit Scale field encoding
+    pub fn scale_encoding(&self) -> u8 {
+        if self.scale >= 1.0 {
+            0b00
+        } else if self.scale >= 0.1 {
+            0b01
+        } else if self.scale >= 0.01 {
+            0b10
+        } else {
+            0b11
+        }
+    }
+    /// Power limit in watts, `None` for reserved encodings above 300 W
+    pub fn watts(&self) -> Option<f32> {
+        match (self.scale_encoding(), self.value) {
+            (0b00, value @ 0xf0..) => Self::ALTERNATIVE_WATTS
+                .get(usize::from(value - 0xf0))
+                .map(|&w| f32::from(w)),
+            (scale, value) => {
+                let divisor = [1.0, 10.0, 100.0, 1000.0].get(usize::from(scale))?;
+                Some(f32::from(value) / divisor)
+            }
+        }
+    }
 }
 impl Eq for SlotPowerLimit {}
+/// Reserved encodings are converted to 300 W, the lower bound of the limit
 impl From<SlotPowerLimit> for f32 {
     fn from(cspl: SlotPowerLimit) -> Self {
-        cspl.scale * cspl.value as f32
+        f32::from(&cspl)
     }
 }
-impl<'a> From<&'a SlotPowerLimit> for f32 {
+impl From<&SlotPowerLimit> for f32 {
     fn from(cspl: &SlotPowerLimit) -> Self {
-        cspl.scale * cspl.value as f32
+        cspl.watts().unwrap_or(300.0)
     }
 }
 
@@ -764,36 +1514,55 @@ pub struct DeviceControl {
     /// - All pub others: Reserved
     pub bcre_or_flreset: bool,
 }
-impl From<u16> for DeviceControl {
-    fn from(word: u16) -> Self {
-        let (
-            correctable_error_reporting_enable,
-            non_fatal_error_reporting_enable,
-            fatal_error_reporting_enable,
-            unsupported_request_reporting_enable,
-            enable_relaxed_ordering,
-            mps,
-            extended_tag_field_enable,
-            phantom_functions_enable,
-            aux_power_pm_enable,
-            enable_no_snoop,
-            mrrs,
-            bcre_or_flreset,
-        ) = P12::<_, 1, 1, 1, 1, 1, 3, 1, 1, 1, 1, 3, 1>(word).lsb_into();
-        Self {
-            correctable_error_reporting_enable,
-            non_fatal_error_reporting_enable,
-            fatal_error_reporting_enable,
-            unsupported_request_reporting_enable,
-            enable_relaxed_ordering,
-            max_payload_size: From::<u8>::from(mps),
-            extended_tag_field_enable,
-            phantom_functions_enable,
-            aux_power_pm_enable,
-            enable_no_snoop,
-            max_read_request_size: From::<u8>::from(mrrs),
-            bcre_or_flreset,
-        }
+register!(DeviceControl: u16 {
+    correctable_error_reporting_enable[0],
+    non_fatal_error_reporting_enable[1],
+    fatal_error_reporting_enable[2],
+    unsupported_request_reporting_enable[3],
+    enable_relaxed_ordering[4],
+    max_payload_size[5..8],
+    extended_tag_field_enable[8],
+    phantom_functions_enable[9],
+    aux_power_pm_enable[10],
+    enable_no_snoop[11],
+    max_read_request_size[12..15],
+    bcre_or_flreset[15],
+});
+impl Default for DeviceControl {
+    /// Reset value: Relaxed Ordering and No Snoop enabled, Max_Payload_Size 128 bytes,
+    /// Max_Read_Request_Size 512 bytes
+    ///
+    /// Extended Tag Field Enable default is implementation specific, it is cleared.
+    fn default() -> Self {
+        Self::from(0x2810)
+    }
+}
+impl DeviceControl {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Device Control", 0x08, AccessWidth::Word);
+    /// Sets Max_Payload_Size, refuses reserved encodings and sizes above `supported`
+    /// Max_Payload_Size Supported of Device Capabilities
+    pub fn set_max_payload_size(
+        &mut self,
+        size: MaxSize,
+        supported: MaxSize,
+    ) -> Result<(), MaxSizeError> {
+        let bytes = size.bytes().context(ReservedSnafu { size })?;
+        ensure!(
+            supported
+                .bytes()
+                .is_some_and(|supported| bytes <= supported),
+            UnsupportedSnafu { size, supported }
+        );
+        self.max_payload_size = size;
+        Ok(())
+    }
+    /// Sets Max_Read_Request_Size, refuses reserved encodings
+    pub fn set_max_read_request_size(&mut self, size: MaxSize) -> Result<(), MaxSizeError> {
+        size.bytes().context(ReservedSnafu { size })?;
+        self.max_read_request_size = size;
+        Ok(())
     }
 }
 
@@ -817,27 +1586,39 @@ pub struct DeviceStatus {
     /// - Root and Switch pub Ports: indicates that a Port has issued Non-Posted Requests on its own
     ///   behalf (using the Port’s own Requester ID) which have not been completed
     pub transactions_pending: bool,
-}
-impl From<u16> for DeviceStatus {
-    fn from(word: u16) -> Self {
-        let (
-            correctable_error_detected,
-            non_fatal_error_detected,
-            fatal_error_detected,
-            unsupported_request_detected,
-            aux_power_detected,
-            transactions_pending,
-            (),
-        ) = P7::<_, 1, 1, 1, 1, 1, 1, 10>(word).lsb_into();
-        Self {
-            correctable_error_detected,
-            non_fatal_error_detected,
-            fatal_error_detected,
-            unsupported_request_detected,
-            aux_power_detected,
-            transactions_pending,
-        }
+    /// Emergency Power Reduction Detected
+    ///
+    /// Function is in the Emergency Power Reduction State
+    pub emergency_power_reduction_detected: bool,
+}
+register!(DeviceStatus: u16 {
+    correctable_error_detected[0],
+    non_fatal_error_detected[1],
+    fatal_error_detected[2],
+    unsupported_request_detected[3],
+    aux_power_detected[4],
+    transactions_pending[5],
+    emergency_power_reduction_detected[6],
+});
+flags!(
+    /// Error detected bits as `(field name, value)` pairs
+    DeviceStatus::error_bits {
+        correctable_error_detected,
+        non_fatal_error_detected,
+        fatal_error_detected,
+        unsupported_request_detected,
     }
+);
+impl DeviceStatus {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Device Status", 0x0a, AccessWidth::Word)
+            .read_only(0x0030)
+            .rw1c(0x004f);
+    /// Emergency Power Reduction Detected bit of Device Status register
+    ///
+    /// Bit is RW1C, writing this value clears it without affecting error status bits
+    pub const EMERGENCY_POWER_REDUCTION_DETECTED: u16 = 1 << 6;
 }
 
 /// The Link Capabilities, Link Status, and Link Control registers are required for all Root Ports,
@@ -880,37 +1661,19 @@ pub struct LinkCapabilities {
     /// Port Number
     pub port_number: u8,
 }
-impl From<u32> for LinkCapabilities {
-    fn from(dword: u32) -> Self {
-        let (
-            mls,
-            mlw,
-            aspms,
-            l0s_lat,
-            l1_lat,
-            clock_power_management,
-            surprise_down_error_reporting_capable,
-            data_link_layer_link_active_reporting_capable,
-            link_bandwidth_notification_capability,
-            aspm_optionality_compliance,
-            (),
-            port_number,
-        ) = P12::<_, 4, 6, 2, 3, 3, 1, 1, 1, 1, 1, 1, 8>(dword).lsb_into();
-        Self {
-            max_link_speed: From::<u8>::from(mls),
-            maximum_link_width: From::<u8>::from(mlw),
-            active_state_power_management_support: From::<u8>::from(aspms),
-            l0s_exit_latency: From::<u8>::from(l0s_lat),
-            l1_exit_latency: From::<u8>::from(l1_lat),
-            clock_power_management,
-            surprise_down_error_reporting_capable,
-            data_link_layer_link_active_reporting_capable,
-            link_bandwidth_notification_capability,
-            aspm_optionality_compliance,
-            port_number,
-        }
-    }
-}
+register!(LinkCapabilities: u32 {
+    max_link_speed[0..4],
+    maximum_link_width[4..10],
+    active_state_power_management_support[10..12],
+    l0s_exit_latency[12..15],
+    l1_exit_latency[15..18],
+    clock_power_management[18],
+    surprise_down_error_reporting_capable[19],
+    data_link_layer_link_active_reporting_capable[20],
+    link_bandwidth_notification_capability[21],
+    aspm_optionality_compliance[22],
+    port_number[24..32],
+});
 
 /// Max/Current/Target Link Speed
 /// Speeds should be taken from [SupportedLinkSpeedsVector]
@@ -961,9 +1724,14 @@ impl From<LinkSpeed> for u8 {
         }
     }
 }
+impl_u8_field!(LinkSpeed);
 
 /// Maximum/Negotiated Link Width
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Widths are ordered by number of lanes. Reserved encodings do not describe any real width,
+/// so they are ordered below [X1](LinkWidth::X1) (by encoding among themselves), that is
+/// comparison like `width >= LinkWidth::X8` is always false for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LinkWidth {
     Reserved(u8),
     X1,
@@ -974,6 +1742,24 @@ pub enum LinkWidth {
     X16,
     X32,
 }
+impl LinkWidth {
+    /// Number of lanes, encoding for Reserved values
+    pub fn value(&self) -> u8 {
+        (*self).into()
+    }
+}
+impl PartialOrd for LinkWidth {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for LinkWidth {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |width: &Self| (!matches!(width, Self::Reserved(_)), width.value());
+        key(self).cmp(&key(other))
+    }
+}
+/// Link Width field is encoded as number of lanes
 impl From<u8> for LinkWidth {
     fn from(byte: u8) -> Self {
         match byte {
@@ -1002,6 +1788,7 @@ impl From<LinkWidth> for u8 {
         }
     }
 }
+impl_u8_field!(LinkWidth);
 
 /// Active State Power Management (ASPM) Support/Control
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1022,6 +1809,31 @@ impl From<u8> for ActiveStatePowerManagement {
         }
     }
 }
+impl From<ActiveStatePowerManagement> for u8 {
+    fn from(aspm: ActiveStatePowerManagement) -> Self {
+        match aspm {
+            ActiveStatePowerManagement::NoAspm => 0b00,
+            ActiveStatePowerManagement::L0s => 0b01,
+            ActiveStatePowerManagement::L1 => 0b10,
+            ActiveStatePowerManagement::L0sAndL1 => 0b11,
+        }
+    }
+}
+impl_u8_field!(ActiveStatePowerManagement);
+impl ActiveStatePowerManagement {
+    /// L0s is supported or enabled
+    pub fn l0s(&self) -> bool {
+        matches!(self, Self::L0s | Self::L0sAndL1)
+    }
+    /// L1 is supported or enabled
+    pub fn l1(&self) -> bool {
+        matches!(self, Self::L1 | Self::L0sAndL1)
+    }
+    /// All states enabled by `control` are present in this ASPM Support value
+    pub fn supports(&self, control: &Self) -> bool {
+        (self.l0s() || !control.l0s()) && (self.l1() || !control.l1())
+    }
+}
 
 /// L0s Exit Latency
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1058,6 +1870,28 @@ impl From<u8> for L0sExitLatency {
         }
     }
 }
+impl From<L0sExitLatency> for u8 {
+    fn from(latency: L0sExitLatency) -> Self {
+        latency as u8
+    }
+}
+impl_u8_field!(L0sExitLatency);
+
+impl L0sExitLatency {
+    /// Upper bound of exit latency in nanoseconds, `None` if it is unbounded
+    pub fn max_ns(&self) -> Option<u32> {
+        match self {
+            Self::Lt64ns => Some(64),
+            Self::Ge64nsAndLt128ns => Some(128),
+            Self::Ge128nsAndLt256ns => Some(256),
+            Self::Ge256nsAndLt512ns => Some(512),
+            Self::Ge512nsAndLt1us => Some(1_000),
+            Self::Ge1usAndLt2us => Some(2_000),
+            Self::Ge2usAndLt4us => Some(4_000),
+            Self::Gt4ns => None,
+        }
+    }
+}
 
 /// L1 Exit Latency
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1094,6 +1928,94 @@ impl From<u8> for L1ExitLatency {
         }
     }
 }
+impl From<L1ExitLatency> for u8 {
+    fn from(latency: L1ExitLatency) -> Self {
+        latency as u8
+    }
+}
+impl_u8_field!(L1ExitLatency);
+impl L1ExitLatency {
+    /// Upper bound of exit latency in nanoseconds, `None` if it is unbounded
+    pub fn max_ns(&self) -> Option<u32> {
+        match self {
+            Self::Lt1us => Some(1_000),
+            Self::Ge1usAndLt2us => Some(2_000),
+            Self::Ge2usAndLt4us => Some(4_000),
+            Self::Ge4usAndLt8us => Some(8_000),
+            Self::Ge8usAndLt16us => Some(16_000),
+            Self::Ge16usAndLt32us => Some(32_000),
+            Self::Ge32usAndLt64us => Some(64_000),
+            Self::Gt64ns => None,
+        }
+    }
+}
+
+/// ASPM states which exit latency is within Endpoint tolerance
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AspmLatency {
+    /// L0s can be enabled on every Link of the path
+    pub l0s: bool,
+    /// L1 can be enabled on every Link of the path
+    pub l1: bool,
+}
+
+impl AspmLatency {
+    /// Each Switch on the path adds up to 1 µs to L1 exit latency
+    pub const L1_SWITCH_LATENCY_NS: u32 = 1_000;
+
+    /// Compares exit latencies of Links between Endpoint and Root Port to the Endpoint
+    /// acceptable latencies
+    ///
+    /// `path` contains both ends of every Link starting from the Endpoint: Upstream Port of
+    /// the lower component and Downstream Port of the upper one. L0s exit latency is checked
+    /// for every Link separately, L1 exit latencies of all Links are accumulated with
+    /// [Switch](Self::L1_SWITCH_LATENCY_NS) latency.
+    ///
+    /// Exit latencies reported in Link Capabilities depend on Common Clock Configuration, so
+    /// both ends of every Link should have the same configuration and Link should be retrained
+    /// after it was changed.
+    pub fn check(
+        endpoint: &DeviceCapabilities,
+        path: &[(&Link, &Link)],
+    ) -> Result<Self, AspmLatencyError> {
+        let l0s_acceptable = endpoint.endpoint_l0s_acceptable_latency.max_ns();
+        let l1_acceptable = endpoint.endpoint_l1_acceptable_latency.max_ns();
+        let within = |latency: Option<u32>, acceptable: Option<u32>| match (latency, acceptable) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(latency), Some(acceptable)) => latency <= acceptable,
+        };
+        let mut result = Self {
+            l0s: true,
+            l1: true,
+        };
+        let mut switch_latency = 0;
+        for (link, (lower, upper)) in path.iter().enumerate() {
+            if lower.control.common_clock_configuration != upper.control.common_clock_configuration
+            {
+                return Err(AspmLatencyError::CommonClockConfiguration { link });
+            }
+            let (lower, upper) = (&lower.capabilities, &upper.capabilities);
+            result.l0s &= within(lower.l0s_exit_latency.max_ns(), l0s_acceptable)
+                && within(upper.l0s_exit_latency.max_ns(), l0s_acceptable);
+            let l1 = lower
+                .l1_exit_latency
+                .max_ns()
+                .zip(upper.l1_exit_latency.max_ns())
+                .map(|(lower, upper)| lower.max(upper) + switch_latency);
+            result.l1 &= within(l1, l1_acceptable);
+            switch_latency += Self::L1_SWITCH_LATENCY_NS;
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AspmLatencyError {
+    #[snafu(display("Common Clock Configuration of Link {link} ends differs"))]
+    CommonClockConfiguration { link: usize },
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinkControl {
@@ -1118,37 +2040,146 @@ pub struct LinkControl {
     /// Link Autonomous Bandwidth Interrupt Enable
     pub link_autonomous_bandwidth_interrupt_enable: bool,
 }
-impl From<u16> for LinkControl {
-    fn from(word: u16) -> Self {
-        let (
-            aspmc,
-            (),
-            rcb,
-            link_disable,
-            retrain_link,
-            common_clock_configuration,
-            extended_synch,
-            enable_clock_power_management,
-            hardware_autonomous_width_disable,
-            link_bandwidth_management_interrupt_enable,
-            link_autonomous_bandwidth_interrupt_enable,
-            (),
-        ) = P12::<_, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 4>(word).lsb_into();
-        Self {
-            active_state_power_management_control: From::<u8>::from(aspmc),
-            read_completion_boundary: From::<bool>::from(rcb),
-            link_disable,
-            retrain_link,
-            common_clock_configuration,
-            extended_synch,
-            enable_clock_power_management,
-            hardware_autonomous_width_disable,
-            link_bandwidth_management_interrupt_enable,
-            link_autonomous_bandwidth_interrupt_enable,
-        }
+impl LinkControl {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Link Control", 0x10, AccessWidth::Word);
+}
+register!(LinkControl: u16 {
+    active_state_power_management_control[0..2],
+    read_completion_boundary[3],
+    link_disable[4],
+    retrain_link[5],
+    common_clock_configuration[6],
+    extended_synch[7],
+    enable_clock_power_management[8],
+    hardware_autonomous_width_disable[9],
+    link_bandwidth_management_interrupt_enable[10],
+    link_autonomous_bandwidth_interrupt_enable[11],
+});
+impl Default for LinkControl {
+    /// Reset value, all fields are cleared
+    fn default() -> Self {
+        Self::from(0)
     }
 }
 
+/// Link Control field not applicable to a Device/Port Type
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LinkControlError {
+    #[snafu(display(
+        "{field:?} is reserved or read-only for Device/Port Type {device_type:#06b}"
+    ))]
+    NotApplicable {
+        field: LinkControlField,
+        device_type: u8,
+    },
+}
+
+/// [LinkControl] register fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkControlField {
+    ActiveStatePowerManagementControl,
+    ReadCompletionBoundary,
+    LinkDisable,
+    RetrainLink,
+    CommonClockConfiguration,
+    ExtendedSynch,
+    EnableClockPowerManagement,
+    HardwareAutonomousWidthDisable,
+    LinkBandwidthManagementInterruptEnable,
+    LinkAutonomousBandwidthInterruptEnable,
+}
+impl LinkControlField {
+    /// Field may be programmed by software in a Function of `device_type`
+    ///
+    /// Functions without a Link (Root Complex Integrated Endpoint and Event Collector) and
+    /// Reserved types have no applicable fields. Read Completion Boundary is hardwired in Root
+    /// Ports and reserved in Switch Ports. Link Disable, Retrain Link and the bandwidth
+    /// interrupt enables apply only to Downstream Ports, Enable Clock Power Management only to
+    /// Upstream Ports.
+    pub fn is_applicable(&self, device_type: &DeviceType) -> bool {
+        use DeviceType as T;
+        let has_link = matches!(
+            device_type,
+            T::Endpoint { .. }
+                | T::LegacyEndpoint { .. }
+                | T::RootPort { .. }
+                | T::UpstreamPort { .. }
+                | T::DownstreamPort { .. }
+                | T::PcieToPciBridge { .. }
+                | T::PciToPcieBridge { .. }
+        );
+        has_link
+            && match self {
+                Self::ReadCompletionBoundary => !matches!(
+                    device_type,
+                    T::RootPort { .. } | T::UpstreamPort { .. } | T::DownstreamPort { .. }
+                ),
+                Self::LinkDisable
+                | Self::RetrainLink
+                | Self::LinkBandwidthManagementInterruptEnable
+                | Self::LinkAutonomousBandwidthInterruptEnable => device_type.is_downstream_port(),
+                Self::EnableClockPowerManagement => !device_type.is_downstream_port(),
+                Self::ActiveStatePowerManagementControl
+                | Self::CommonClockConfiguration
+                | Self::ExtendedSynch
+                | Self::HardwareAutonomousWidthDisable => true,
+            }
+    }
+    fn check(self, device_type: &DeviceType) -> Result<(), LinkControlError> {
+        ensure!(
+            self.is_applicable(device_type),
+            NotApplicableSnafu {
+                field: self,
+                device_type: device_type.id(),
+            }
+        );
+        Ok(())
+    }
+}
+
+macro_rules! link_control_setters {
+    ($($setter:ident: $field:ident: $t:ty => $variant:ident),+ $(,)?) => {
+        impl LinkControl {$(
+            #[doc = concat!(
+                "Sets [", stringify!($field), "](Self::", stringify!($field), "), fails if ",
+                "the field is not applicable to `device_type`, see ",
+                "[LinkControlField::is_applicable]"
+            )]
+            pub fn $setter(
+                &mut self,
+                value: $t,
+                device_type: &DeviceType,
+            ) -> Result<(), LinkControlError> {
+                LinkControlField::$variant.check(device_type)?;
+                self.$field = value;
+                Ok(())
+            }
+        )+}
+    };
+}
+
+link_control_setters!(
+    set_active_state_power_management_control: active_state_power_management_control:
+        ActiveStatePowerManagement => ActiveStatePowerManagementControl,
+    set_read_completion_boundary: read_completion_boundary:
+        ReadCompletionBoundary => ReadCompletionBoundary,
+    set_link_disable: link_disable: bool => LinkDisable,
+    set_retrain_link: retrain_link: bool => RetrainLink,
+    set_common_clock_configuration: common_clock_configuration: bool => CommonClockConfiguration,
+    set_extended_synch: extended_synch: bool => ExtendedSynch,
+    set_enable_clock_power_management: enable_clock_power_management:
+        bool => EnableClockPowerManagement,
+    set_hardware_autonomous_width_disable: hardware_autonomous_width_disable:
+        bool => HardwareAutonomousWidthDisable,
+    set_link_bandwidth_management_interrupt_enable: link_bandwidth_management_interrupt_enable:
+        bool => LinkBandwidthManagementInterruptEnable,
+    set_link_autonomous_bandwidth_interrupt_enable: link_autonomous_bandwidth_interrupt_enable:
+        bool => LinkAutonomousBandwidthInterruptEnable,
+);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReadCompletionBoundary {
     B64 = 64,
@@ -1163,6 +2194,17 @@ impl From<bool> for ReadCompletionBoundary {
         }
     }
 }
+impl From<u8> for ReadCompletionBoundary {
+    fn from(byte: u8) -> Self {
+        (byte != 0).into()
+    }
+}
+impl From<ReadCompletionBoundary> for u8 {
+    fn from(rcb: ReadCompletionBoundary) -> Self {
+        (rcb == ReadCompletionBoundary::B128) as u8
+    }
+}
+impl_u8_field!(ReadCompletionBoundary);
 
 /// The Link Status register provides information about PCI Express Link specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1184,30 +2226,23 @@ pub struct LinkStatus {
     /// Link Autonomous Bandwidth Status
     pub link_autonomous_bandwidth_status: bool,
 }
-impl From<u16> for LinkStatus {
-    fn from(word: u16) -> Self {
-        let (
-            cls,
-            nlw,
-            link_training_error,
-            link_training,
-            slot_clock_configuration,
-            data_link_layer_link_active,
-            link_bandwidth_management_status,
-            link_autonomous_bandwidth_status,
-        ) = P8::<_, 4, 6, 1, 1, 1, 1, 1, 1>(word).lsb_into();
-        Self {
-            current_link_speed: From::<u8>::from(cls),
-            negotiated_link_width: From::<u8>::from(nlw),
-            link_training_error,
-            link_training,
-            slot_clock_configuration,
-            data_link_layer_link_active,
-            link_bandwidth_management_status,
-            link_autonomous_bandwidth_status,
-        }
-    }
-}
+impl LinkStatus {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Link Status", 0x12, AccessWidth::Word)
+            .read_only(0x3fff)
+            .rw1c(0xc000);
+}
+register!(LinkStatus: u16 {
+    current_link_speed[0..4],
+    negotiated_link_width[4..10],
+    link_training_error[10],
+    link_training[11],
+    slot_clock_configuration[12],
+    data_link_layer_link_active[13],
+    link_bandwidth_management_status[14],
+    link_autonomous_bandwidth_status[15],
+});
 
 /// Slot Capabilities, Slot Status, and Slot Control registers are required for Switch Downstream
 /// and Root Ports if a slot is implemented on the Port (indicated by the Slot Implemented bit in
@@ -1285,6 +2320,23 @@ impl From<u32> for SlotCapabilities {
         }
     }
 }
+impl From<SlotCapabilities> for u32 {
+    fn from(caps: SlotCapabilities) -> Self {
+        let spl = &caps.slot_power_limit;
+        u32::from(caps.attention_button_present)
+            | u32::from(caps.power_controller_present) << 1
+            | u32::from(caps.mrl_sensor_present) << 2
+            | u32::from(caps.attention_indicator_present) << 3
+            | u32::from(caps.power_indicator_present) << 4
+            | u32::from(caps.hot_plug_surprise) << 5
+            | u32::from(caps.hot_plug_capable) << 6
+            | u32::from(spl.value) << 7
+            | u32::from(spl.scale_encoding()) << 15
+            | u32::from(caps.electromechanical_interlock_present) << 17
+            | u32::from(caps.no_command_completed_support) << 18
+            | u32::from(caps.physical_slot_number & 0x1fff) << 19
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SlotControl {
@@ -1311,35 +2363,33 @@ pub struct SlotControl {
     /// Data Link Layer State Changed Enable
     pub data_link_layer_state_changed_enable: bool,
 }
-impl From<u16> for SlotControl {
-    fn from(word: u16) -> Self {
-        let (
-            attention_button_pressed_enable,
-            power_fault_detected_enable,
-            mrl_sensor_changed_enable,
-            presence_detect_changed_enable,
-            command_completed_interrupt_enable,
-            hot_plug_interrupt_enable,
-            aic,
-            pic,
-            power_controller_control,
-            electromechanical_interlock_control,
-            data_link_layer_state_changed_enable,
-            (),
-        ) = P12::<_, 1, 1, 1, 1, 1, 1, 2, 2, 1, 1, 1, 3>(word).lsb_into();
-        Self {
-            attention_button_pressed_enable,
-            power_fault_detected_enable,
-            mrl_sensor_changed_enable,
-            presence_detect_changed_enable,
-            command_completed_interrupt_enable,
-            hot_plug_interrupt_enable,
-            attention_indicator_control: From::<u8>::from(aic),
-            power_indicator_control: From::<u8>::from(pic),
-            power_controller_control,
-            electromechanical_interlock_control,
-            data_link_layer_state_changed_enable,
-        }
+impl SlotControl {
+    /// Register location relative to the PCI Express Capability
+    ///
+    /// Every write is a Hot-Plug command, so the register is written as a whole
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Slot Control", 0x18, AccessWidth::Word)
+            .min_write(AccessWidth::Word);
+}
+register!(SlotControl: u16 {
+    attention_button_pressed_enable[0],
+    power_fault_detected_enable[1],
+    mrl_sensor_changed_enable[2],
+    presence_detect_changed_enable[3],
+    command_completed_interrupt_enable[4],
+    hot_plug_interrupt_enable[5],
+    attention_indicator_control[6..8],
+    power_indicator_control[8..10],
+    power_controller_control[10],
+    electromechanical_interlock_control[11],
+    data_link_layer_state_changed_enable[12],
+});
+impl Default for SlotControl {
+    /// Reset value: indicators are Off and the Power Controller is On
+    ///
+    /// Indicator and Power Controller Control defaults are implementation specific.
+    fn default() -> Self {
+        Self::from(0x03c0)
     }
 }
 
@@ -1362,6 +2412,17 @@ impl From<u8> for IndicatorControl {
         }
     }
 }
+impl From<IndicatorControl> for u8 {
+    fn from(data: IndicatorControl) -> Self {
+        match data {
+            IndicatorControl::Reserved => 0b00,
+            IndicatorControl::On => 0b01,
+            IndicatorControl::Blink => 0b10,
+            IndicatorControl::Off => 0b11,
+        }
+    }
+}
+impl_u8_field!(IndicatorControl);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SlotStatus {
@@ -1384,30 +2445,143 @@ pub struct SlotStatus {
     /// Data Link Layer State Changed
     pub data_link_layer_state_changed: bool,
 }
-impl From<u16> for SlotStatus {
-    fn from(word: u16) -> Self {
-        let (
-            attention_button_pressed,
-            power_fault_detected,
-            mrl_sensor_changed,
-            presence_detect_changed,
-            command_completed,
-            mrl_sensor_state,
-            presence_detect_state,
-            electromechanical_interlock_status,
-            data_link_layer_state_changed,
-            (),
-        ) = P10::<_, 1, 1, 1, 1, 1, 1, 1, 1, 1, 7>(word).lsb_into();
+register!(SlotStatus: u16 {
+    attention_button_pressed[0],
+    power_fault_detected[1],
+    mrl_sensor_changed[2],
+    presence_detect_changed[3],
+    command_completed[4],
+    mrl_sensor_state[5],
+    presence_detect_state[6],
+    electromechanical_interlock_status[7],
+    data_link_layer_state_changed[8],
+});
+flags!(
+    /// Event and state bits as `(field name, value)` pairs
+    SlotStatus::bits {
+        attention_button_pressed,
+        power_fault_detected,
+        mrl_sensor_changed,
+        presence_detect_changed,
+        command_completed,
+        mrl_sensor_state,
+        presence_detect_state,
+        electromechanical_interlock_status,
+        data_link_layer_state_changed,
+    }
+);
+
+impl SlotStatus {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Slot Status", 0x1a, AccessWidth::Word)
+            .read_only(0x00e0)
+            .rw1c(0x011f);
+    /// Command Completed bit of Slot Status register
+    ///
+    /// Bit is RW1C, writing this value clears it before the next Hot-Plug command
+    pub const COMMAND_COMPLETED: u16 = 1 << 4;
+}
+
+/// Slot Control write is not allowed, previous Hot-Plug command is not completed
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[snafu(display("previous Slot Control command is pending until {deadline} ms"))]
+pub struct SlotCommandError {
+    /// Time (ms) after which the previous command is assumed completed
+    pub deadline: u64,
+}
+
+/// Next step of a [SlotCommandSequence]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotCommandStep {
+    /// Slot Control write is allowed
+    Ready,
+    /// Command Completed is set, next Slot Control write is allowed after clearing it with
+    /// [SlotStatus::COMMAND_COMPLETED]
+    Completed,
+    /// Command Completed was not set in time, the command is assumed completed and next Slot
+    /// Control write is allowed
+    TimedOut,
+    /// Poll Slot Status register until Command Completed is set or `deadline` (ms) passes
+    Poll { deadline: u64 },
+}
+
+/// Sequencing of Slot Control writes (Hot-Plug commands)
+///
+/// If the Port supports Command Completed notification, software must wait for the Command
+/// Completed bit after each Slot Control write before writing it again. A command is assumed
+/// completed if the bit is not set within [TIMEOUT](Self::TIMEOUT) milliseconds. Ports with No
+/// Command Completed Support accept writes at any time.
+///
+/// The sequence does no I/O, the caller reads and writes the registers and supplies the time in
+/// milliseconds from any monotonic clock.
+///
+/// ```rust
+/// # use pcics::capabilities::pci_express::*;
+/// let mut seq = SlotCommandSequence::new(&SlotCapabilities::from(0));
+/// assert_eq!(SlotCommandStep::Ready, seq.step());
+///
+/// // Write Slot Control at 100 ms
+/// assert_eq!(Ok(SlotCommandStep::Poll { deadline: 1100 }), seq.write(100));
+/// assert!(seq.write(200).is_err());
+/// // Slot Status read at 150 ms
+/// assert_eq!(
+///     SlotCommandStep::Poll { deadline: 1100 },
+///     seq.poll(&SlotStatus::from(0), 150)
+/// );
+/// // Command Completed is set at 160 ms, clear it before the next write
+/// let status = SlotStatus::from(SlotStatus::COMMAND_COMPLETED);
+/// assert_eq!(SlotCommandStep::Completed, seq.poll(&status, 160));
+/// assert_eq!(SlotCommandStep::Ready, seq.step());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotCommandSequence {
+    no_command_completed_support: bool,
+    deadline: Option<u64>,
+}
+impl SlotCommandSequence {
+    /// Time in milliseconds to wait for Command Completed
+    pub const TIMEOUT: u64 = 1000;
+    /// Sequence for a Port with `capabilities` Slot Capabilities
+    pub fn new(capabilities: &SlotCapabilities) -> Self {
         Self {
-            attention_button_pressed,
-            power_fault_detected,
-            mrl_sensor_changed,
-            presence_detect_changed,
-            command_completed,
-            mrl_sensor_state,
-            presence_detect_state,
-            electromechanical_interlock_status,
-            data_link_layer_state_changed,
+            no_command_completed_support: capabilities.no_command_completed_support,
+            deadline: None,
+        }
+    }
+    /// Current step without reading Slot Status
+    pub fn step(&self) -> SlotCommandStep {
+        match self.deadline {
+            Some(deadline) => SlotCommandStep::Poll { deadline },
+            None => SlotCommandStep::Ready,
+        }
+    }
+    /// Records Slot Control write at `now`, fails if previous command is pending
+    ///
+    /// Returns [SlotCommandStep::Poll] if the Port notifies of command completion,
+    /// [SlotCommandStep::Ready] otherwise.
+    pub fn write(&mut self, now: u64) -> Result<SlotCommandStep, SlotCommandError> {
+        if let Some(deadline) = self.deadline {
+            return SlotCommandSnafu { deadline }.fail();
+        }
+        if !self.no_command_completed_support {
+            self.deadline = Some(now.saturating_add(Self::TIMEOUT));
+        }
+        Ok(self.step())
+    }
+    /// Updates pending command with `status` Slot Status read at `now`
+    pub fn poll(&mut self, status: &SlotStatus, now: u64) -> SlotCommandStep {
+        match self.deadline {
+            None => SlotCommandStep::Ready,
+            Some(_) if status.command_completed => {
+                self.deadline = None;
+                SlotCommandStep::Completed
+            }
+            Some(deadline) if now >= deadline => {
+                self.deadline = None;
+                SlotCommandStep::TimedOut
+            }
+            Some(deadline) => SlotCommandStep::Poll { deadline },
         }
     }
 }
@@ -1443,24 +2617,40 @@ pub struct RootControl {
     pub pme_interrupt_enable: bool,
     /// CRS Software Visibility Enable
     pub crs_software_visibility_enable: bool,
-}
-impl From<u16> for RootControl {
-    fn from(word: u16) -> Self {
-        let (
-            system_error_on_correctable_error_enable,
-            system_error_on_non_fatal_error_enable,
-            system_error_on_fatal_error_enable,
-            pme_interrupt_enable,
-            crs_software_visibility_enable,
-            (),
-        ) = P6::<_, 1, 1, 1, 1, 1, 11>(word).lsb_into();
-        Self {
-            system_error_on_correctable_error_enable,
-            system_error_on_non_fatal_error_enable,
-            system_error_on_fatal_error_enable,
-            pme_interrupt_enable,
-            crs_software_visibility_enable,
-        }
+    /// No NFM Subtree Below This Root Port
+    ///
+    /// Indicates that there is no Non-Flit Mode Device in the hierarchy below this Root Port
+    pub no_nfm_subtree_below_this_root_port: bool,
+}
+impl RootControl {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Root Control", 0x1c, AccessWidth::Word);
+    /// System Error on Correctable Error Enable bit of Root Control register
+    pub const SYSTEM_ERROR_ON_CORRECTABLE_ERROR_ENABLE: u16 = 1 << 0;
+    /// System Error on Non-Fatal Error Enable bit of Root Control register
+    pub const SYSTEM_ERROR_ON_NON_FATAL_ERROR_ENABLE: u16 = 1 << 1;
+    /// System Error on Fatal Error Enable bit of Root Control register
+    pub const SYSTEM_ERROR_ON_FATAL_ERROR_ENABLE: u16 = 1 << 2;
+    /// PME Interrupt Enable bit of Root Control register
+    pub const PME_INTERRUPT_ENABLE: u16 = 1 << 3;
+    /// CRS Software Visibility Enable bit of Root Control register
+    pub const CRS_SOFTWARE_VISIBILITY_ENABLE: u16 = 1 << 4;
+    /// No NFM Subtree Below This Root Port bit of Root Control register (RO)
+    pub const NO_NFM_SUBTREE_BELOW_THIS_ROOT_PORT: u16 = 1 << 5;
+}
+register!(RootControl: u16 {
+    system_error_on_correctable_error_enable[0],
+    system_error_on_non_fatal_error_enable[1],
+    system_error_on_fatal_error_enable[2],
+    pme_interrupt_enable[3],
+    crs_software_visibility_enable[4],
+    no_nfm_subtree_below_this_root_port[5],
+});
+impl Default for RootControl {
+    /// Reset value, all fields are cleared
+    fn default() -> Self {
+        Self::from(0)
     }
 }
 
@@ -1470,14 +2660,13 @@ pub struct RootCapabilities {
     /// CRS Software Visibility
     pub crs_software_visibility: bool,
 }
-impl From<u16> for RootCapabilities {
-    fn from(word: u16) -> Self {
-        let (crs_software_visibility, ()) = P2::<_, 1, 15>(word).lsb_into();
-        Self {
-            crs_software_visibility,
-        }
-    }
+impl RootCapabilities {
+    /// CRS Software Visibility bit of Root Capabilities register
+    pub const CRS_SOFTWARE_VISIBILITY: u16 = 1 << 0;
 }
+register!(RootCapabilities: u16 {
+    crs_software_visibility[0],
+});
 
 /// The Root Status register provides information about PCI Express device specific parameters
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1489,17 +2678,24 @@ pub struct RootStatus {
     /// PME Pending
     pub pme_pending: bool,
 }
-impl From<u32> for RootStatus {
-    fn from(dword: u32) -> Self {
-        let (pme_requester_id, pme_status, pme_pending, ()) =
-            P4::<_, 16, 1, 1, 14>(dword).lsb_into();
-        Self {
-            pme_requester_id,
-            pme_status,
-            pme_pending,
-        }
-    }
-}
+impl RootStatus {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Root Status", 0x20, AccessWidth::Dword)
+            .read_only(0x0002_ffff)
+            .rw1c(0x0001_0000);
+    /// PME Status bit of Root Status register
+    ///
+    /// Bit is RW1C, writing this value clears it and allows the next PME to be reported
+    pub const PME_STATUS: u32 = 1 << 16;
+    /// PME Pending bit of Root Status register (RO)
+    pub const PME_PENDING: u32 = 1 << 17;
+}
+register!(RootStatus: u32 {
+    pme_requester_id[0..16],
+    pme_status[16],
+    pme_pending[17],
+});
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Device2 {
@@ -1560,56 +2756,28 @@ pub struct DeviceCapabilities2 {
     /// FRS Supported
     pub frs_supported: bool,
 }
-impl From<u32> for DeviceCapabilities2 {
-    fn from(dword: u32) -> Self {
-        let (
-            ctrs,
-            completion_timeout_disable_supported,
-            ari_forwarding_supported,
-            atomic_op_routing_supported,
-            u32_atomicop_completer_supported,
-            u64_atomicop_completer_supported,
-            u128_cas_completer_supported,
-            no_ro_enabled_pr_pr_passing,
-            ltr_mechanism_supported,
-            tcs,
-            lsc,
-            support_10bit_tag_completer,
-            support_10bit_tag_requester,
-            os,
-            extended_fmt_field_supported,
-            end_end_tlp_prefix_supported,
-            meetp,
-            eprs,
-            emergency_power_reduction_initialization_required,
-            (),
-            frs_supported,
-        ) = P21::<_, 4, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 1, 1, 2, 1, 1, 2, 2, 1, 4, 1>(dword)
-            .lsb_into();
-        Self {
-            completion_timeout_ranges_supported: From::<u8>::from(ctrs),
-            completion_timeout_disable_supported,
-            ari_forwarding_supported,
-            atomic_op_routing_supported,
-            u32_atomicop_completer_supported,
-            u64_atomicop_completer_supported,
-            u128_cas_completer_supported,
-            no_ro_enabled_pr_pr_passing,
-            ltr_mechanism_supported,
-            tph_completer_supported: From::<u8>::from(tcs),
-            ln_system_cls: From::<u8>::from(lsc),
-            support_10bit_tag_completer,
-            support_10bit_tag_requester,
-            obff_supported: From::<u8>::from(os),
-            extended_fmt_field_supported,
-            end_end_tlp_prefix_supported,
-            max_end_end_tlp_prefixes: From::<u8>::from(meetp),
-            emergency_power_reduction_supported: From::<u8>::from(eprs),
-            emergency_power_reduction_initialization_required,
-            frs_supported,
-        }
-    }
-}
+register!(DeviceCapabilities2: u32 {
+    completion_timeout_ranges_supported[0..4],
+    completion_timeout_disable_supported[4],
+    ari_forwarding_supported[5],
+    atomic_op_routing_supported[6],
+    u32_atomicop_completer_supported[7],
+    u64_atomicop_completer_supported[8],
+    u128_cas_completer_supported[9],
+    no_ro_enabled_pr_pr_passing[10],
+    ltr_mechanism_supported[11],
+    tph_completer_supported[12..14],
+    ln_system_cls[14..16],
+    support_10bit_tag_completer[16],
+    support_10bit_tag_requester[17],
+    obff_supported[18..20],
+    extended_fmt_field_supported[20],
+    end_end_tlp_prefix_supported[21],
+    max_end_end_tlp_prefixes[22..24],
+    emergency_power_reduction_supported[24..26],
+    emergency_power_reduction_initialization_required[26],
+    frs_supported[31],
+});
 
 /// Indicates device Function support for the optional Completion Timeout programmability mechanism
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1670,6 +2838,7 @@ impl From<CompletionTimeoutRanges> for u8 {
         }
     }
 }
+impl_u8_field!(CompletionTimeoutRanges);
 
 /// Value indicates Completer support for TPH or Extended TPH
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1704,6 +2873,7 @@ impl From<TphCompleter> for u8 {
         }
     }
 }
+impl_u8_field!(TphCompleter);
 
 /// Indicates if the Root Port or RCRB supports LN protocol as an LN Completer, and if so, what
 /// cacheline size is in effect
@@ -1739,6 +2909,7 @@ impl From<LnSystemCls> for u8 {
         }
     }
 }
+impl_u8_field!(LnSystemCls);
 
 /// Indicates if OBFF is supported and, if so, what signaling mechanism is used
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1773,6 +2944,7 @@ impl From<Obff> for u8 {
         }
     }
 }
+impl_u8_field!(Obff);
 
 /// Indicates the maximum number of End-End TLP Prefixes supported by this Function
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1807,6 +2979,7 @@ impl From<MaxEndEndTlpPrefixes> for u8 {
         }
     }
 }
+impl_u8_field!(MaxEndEndTlpPrefixes);
 
 /// Indicates support level of the optional Emergency Power Reduction State feature
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1843,6 +3016,71 @@ impl From<EmergencyPowerReduction> for u8 {
         }
     }
 }
+impl_u8_field!(EmergencyPowerReduction);
+impl EmergencyPowerReduction {
+    /// Emergency Power Reduction State is supported by the Function
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            self,
+            Self::DeviceSpecific | Self::FormFactorOrDeviceSpecific
+        )
+    }
+}
+
+/// Emergency Power Reduction State of the Function combined from Device Capabilities 2, Device
+/// Control 2 and Device Status registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmergencyPowerReductionStatus {
+    /// Emergency Power Reduction Supported
+    pub supported: EmergencyPowerReduction,
+    /// Emergency Power Reduction Initialization Required
+    pub initialization_required: bool,
+    /// Emergency Power Reduction Request
+    pub requested: bool,
+    /// Emergency Power Reduction Detected
+    pub detected: bool,
+}
+impl EmergencyPowerReductionStatus {
+    /// Function is in the Emergency Power Reduction State
+    pub fn is_active(&self) -> bool {
+        self.supported.is_supported() && self.detected
+    }
+    /// Emergency Power Reduction Request can be issued through Device Control 2 register
+    pub fn can_request(&self) -> bool {
+        self.supported.is_supported() && !self.requested
+    }
+    /// Checks that register values are consistent and the Function is ready for use
+    ///
+    /// Functions with Initialization Required set need complete device driver initialization
+    /// upon exit from the Emergency Power Reduction State. Software clears Emergency Power
+    /// Reduction Detected after initialization, so set bit on not requested Function means
+    /// initialization is still pending. Functions supporting the Form Factor mechanism may enter
+    /// the state without a request, so the check is limited to Device Specific triggering.
+    pub fn validate(&self) -> Result<(), EmergencyPowerReductionError> {
+        if !self.supported.is_supported() && (self.requested || self.detected) {
+            return Err(EmergencyPowerReductionError::NotSupported {
+                requested: self.requested,
+                detected: self.detected,
+            });
+        }
+        let device_specific = self.supported == EmergencyPowerReduction::DeviceSpecific;
+        if device_specific && self.initialization_required && self.detected && !self.requested {
+            return Err(EmergencyPowerReductionError::InitializationPending);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmergencyPowerReductionError {
+    #[snafu(display(
+        "Emergency Power Reduction State is not supported (requested: {requested}, detected: {detected})"
+    ))]
+    NotSupported { requested: bool, detected: bool },
+    #[snafu(display("Function was not initialized after Emergency Power Reduction State exit"))]
+    InitializationPending,
+}
 
 /// Device Control 2 Register
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1872,36 +3110,39 @@ pub struct DeviceControl2 {
     /// End-End TLP Prefix Blocking
     pub end_end_tlp_prefix_blocking: EndEndTlpPrefixBlocking,
 }
-impl From<u16> for DeviceControl2 {
-    fn from(word: u16) -> Self {
-        let (
-            ctv,
-            completion_timeout_disable,
-            ari_forwarding_enable,
-            atomic_op_requester_enable,
-            atomic_op_egress_blocking,
-            ido_request_enable,
-            ido_completion_enable,
-            ltr_mechanism_enable,
-            emergency_power_reduction_request,
-            enable_10bit_tag_requester,
-            oe,
-            eetpd,
-        ) = P12::<_, 4, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 1>(word).lsb_into();
-        Self {
-            completion_timeout_value: From::<u8>::from(ctv),
-            completion_timeout_disable,
-            ari_forwarding_enable,
-            atomic_op_requester_enable,
-            atomic_op_egress_blocking,
-            ido_request_enable,
-            ido_completion_enable,
-            ltr_mechanism_enable,
-            emergency_power_reduction_request,
-            enable_10bit_tag_requester,
-            obff_enable: From::<u8>::from(oe),
-            end_end_tlp_prefix_blocking: From::<bool>::from(eetpd),
-        }
+register!(DeviceControl2: u16 {
+    completion_timeout_value[0..4],
+    completion_timeout_disable[4],
+    ari_forwarding_enable[5],
+    atomic_op_requester_enable[6],
+    atomic_op_egress_blocking[7],
+    ido_request_enable[8],
+    ido_completion_enable[9],
+    ltr_mechanism_enable[10],
+    emergency_power_reduction_request[11],
+    enable_10bit_tag_requester[12],
+    obff_enable[13..15],
+    end_end_tlp_prefix_blocking[15],
+});
+impl Default for DeviceControl2 {
+    /// Reset value: default Completion Timeout range, all other fields are cleared
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+impl DeviceControl2 {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Device Control 2", 0x28, AccessWidth::Word);
+    /// Emergency Power Reduction Request bit of Device Control 2 register
+    pub const EMERGENCY_POWER_REDUCTION_REQUEST: u16 = 1 << 11;
+    /// Sets Emergency Power Reduction Request in Device Control 2 register `value`
+    pub fn request_emergency_power_reduction(value: &mut u16) {
+        *value |= Self::EMERGENCY_POWER_REDUCTION_REQUEST;
+    }
+    /// Clears Emergency Power Reduction Request in Device Control 2 register `value`
+    pub fn release_emergency_power_reduction(value: &mut u16) {
+        *value &= !Self::EMERGENCY_POWER_REDUCTION_REQUEST;
     }
 }
 
@@ -1959,6 +3200,7 @@ impl From<CompletionTimeoutValue> for u8 {
         }
     }
 }
+impl_u8_field!(CompletionTimeoutValue);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ObffEnable {
@@ -1992,6 +3234,7 @@ impl From<ObffEnable> for u8 {
         }
     }
 }
+impl_u8_field!(ObffEnable);
 
 /// Device Status 2 Register is a placeholder
 /// There are no capabilities that require this register
@@ -2002,6 +3245,11 @@ impl From<u16> for DeviceStatus2 {
         Self {}
     }
 }
+impl From<DeviceStatus2> for u16 {
+    fn from(_: DeviceStatus2) -> Self {
+        0
+    }
+}
 
 /// Controls whether the routing function is permitted to forward TLPs containing an End-End TLP
 /// Prefix
@@ -2029,6 +3277,17 @@ impl From<EndEndTlpPrefixBlocking> for bool {
         }
     }
 }
+impl From<u8> for EndEndTlpPrefixBlocking {
+    fn from(byte: u8) -> Self {
+        (byte != 0).into()
+    }
+}
+impl From<EndEndTlpPrefixBlocking> for u8 {
+    fn from(data: EndEndTlpPrefixBlocking) -> Self {
+        bool::from(data) as u8
+    }
+}
+impl_u8_field!(EndEndTlpPrefixBlocking);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Link2 {
@@ -2064,30 +3323,15 @@ pub struct LinkCapabilities2 {
     /// DRS Supported
     pub drs_supported: bool,
 }
-impl From<u32> for LinkCapabilities2 {
-    fn from(dword: u32) -> Self {
-        let (
-            (),
-            slsv,
-            crosslink_supported,
-            lsog,
-            lsor,
-            retimer_presence_detect_supported,
-            two_retimers_presence_detect_supported,
-            (),
-            drs_supported,
-        ) = P9::<_, 1, 7, 1, 7, 7, 1, 1, 6, 1>(dword).lsb_into();
-        Self {
-            supported_link_speeds_vector: From::<u8>::from(slsv),
-            crosslink_supported,
-            lower_skp_os_generation_supported_speeds_vector: From::<u8>::from(lsog),
-            lower_skp_os_reception_supported_speeds_vector: From::<u8>::from(lsor),
-            retimer_presence_detect_supported,
-            two_retimers_presence_detect_supported,
-            drs_supported,
-        }
-    }
-}
+register!(LinkCapabilities2: u32 {
+    supported_link_speeds_vector[1..8],
+    crosslink_supported[8],
+    lower_skp_os_generation_supported_speeds_vector[9..16],
+    lower_skp_os_reception_supported_speeds_vector[16..23],
+    retimer_presence_detect_supported[23],
+    two_retimers_presence_detect_supported[24],
+    drs_supported[31],
+});
 
 /// Indicates the supported Link speed(s) of the associated Port
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2107,28 +3351,16 @@ pub struct SupportedLinkSpeedsVector {
     /// Reserved
     pub reserved: bool,
 }
-impl From<u8> for SupportedLinkSpeedsVector {
-    fn from(byte: u8) -> Self {
-        let (
-            speed_2_5_gtps,
-            speed_5_0_gtps,
-            speed_8_0_gtps,
-            speed_16_0_gtps,
-            speed_32_0_gtps,
-            speed_64_0_gtps,
-            reserved,
-        ) = P7::<_, 1, 1, 1, 1, 1, 1, 1>(byte).lsb_into();
-        Self {
-            speed_2_5_gtps,
-            speed_5_0_gtps,
-            speed_8_0_gtps,
-            speed_16_0_gtps,
-            speed_32_0_gtps,
-            speed_64_0_gtps,
-            reserved,
-        }
-    }
-}
+register!(SupportedLinkSpeedsVector: u8 {
+    speed_2_5_gtps[0],
+    speed_5_0_gtps[1],
+    speed_8_0_gtps[2],
+    speed_16_0_gtps[3],
+    speed_32_0_gtps[4],
+    speed_64_0_gtps[5],
+    reserved[6],
+});
+impl_u8_field!(SupportedLinkSpeedsVector);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinkControl2 {
@@ -2148,30 +3380,30 @@ pub struct LinkControl2 {
     /// Compliance Preset/De-emphasis
     pub compliance_preset_or_de_emphasis: CompliancePresetOrDeEmphasis,
 }
-impl From<u16> for LinkControl2 {
-    fn from(word: u16) -> Self {
-        let (
-            tls,
-            enter_compliance,
-            hardware_autonomous_speed_disable,
-            sde,
-            tm,
-            enter_modified_compliance,
-            compliance_sos,
-            cpode,
-        ) = P8::<_, 4, 1, 1, 1, 3, 1, 1, 4>(word).lsb_into();
+impl LinkControl2 {
+    /// Register location relative to the PCI Express Capability
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Link Control 2", 0x30, AccessWidth::Word);
+    /// Reset value for the Link with `max_link_speed` of Link Capabilities
+    ///
+    /// Target Link Speed defaults to Max Link Speed, all other fields are cleared.
+    pub fn new_spec_default(max_link_speed: LinkSpeed) -> Self {
         Self {
-            target_link_speed: From::<u8>::from(tls),
-            enter_compliance,
-            hardware_autonomous_speed_disable,
-            selectable_de_emphasis: From::<bool>::from(sde),
-            transmit_margin: From::<u8>::from(tm),
-            enter_modified_compliance,
-            compliance_sos,
-            compliance_preset_or_de_emphasis: From::<u8>::from(cpode),
+            target_link_speed: max_link_speed,
+            ..Self::from(0)
         }
     }
 }
+register!(LinkControl2: u16 {
+    target_link_speed[0..4],
+    enter_compliance[4],
+    hardware_autonomous_speed_disable[5],
+    selectable_de_emphasis[6],
+    transmit_margin[7..10],
+    enter_modified_compliance[10],
+    compliance_sos[11],
+    compliance_preset_or_de_emphasis[12..16],
+});
 
 /// Selectable De-emphasis
 ///
@@ -2190,6 +3422,17 @@ impl From<bool> for DeEmphasis {
         }
     }
 }
+impl From<u8> for DeEmphasis {
+    fn from(byte: u8) -> Self {
+        (byte != 0).into()
+    }
+}
+impl From<DeEmphasis> for u8 {
+    fn from(data: DeEmphasis) -> Self {
+        (data == DeEmphasis::Minus3_5dB) as u8
+    }
+}
+impl_u8_field!(DeEmphasis);
 
 /// Controls the value of the nondeemphasized voltage level at the Transmitter pins
 ///
@@ -2201,6 +3444,12 @@ impl From<u8> for TransmitMargin {
         Self(byte)
     }
 }
+impl From<TransmitMargin> for u8 {
+    fn from(data: TransmitMargin) -> Self {
+        data.0
+    }
+}
+impl_u8_field!(TransmitMargin);
 
 /// Compliance Preset/De-emphasis
 ///
@@ -2217,6 +3466,12 @@ impl From<u8> for CompliancePresetOrDeEmphasis {
         Self(byte)
     }
 }
+impl From<CompliancePresetOrDeEmphasis> for u8 {
+    fn from(data: CompliancePresetOrDeEmphasis) -> Self {
+        data.0
+    }
+}
+impl_u8_field!(CompliancePresetOrDeEmphasis);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinkStatus2 {
@@ -2243,37 +3498,19 @@ pub struct LinkStatus2 {
     /// DRS Message Received
     pub drs_message_received: bool,
 }
-impl From<u16> for LinkStatus2 {
-    fn from(word: u16) -> Self {
-        let (
-            cdel,
-            equalization_complete,
-            equalization_phase_1_successful,
-            equalization_phase_2_successful,
-            equalization_phase_3_successful,
-            link_equalization_request,
-            retimer_presence_detected,
-            two_retimers_presence_detected,
-            cr,
-            (),
-            dcp,
-            drs_message_received,
-        ) = P12::<_, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 3, 1>(word).lsb_into();
-        Self {
-            current_de_emphasis_level: From::<bool>::from(cdel),
-            equalization_complete,
-            equalization_phase_1_successful,
-            equalization_phase_2_successful,
-            equalization_phase_3_successful,
-            link_equalization_request,
-            retimer_presence_detected,
-            two_retimers_presence_detected,
-            crosslink_resolution: From::<u8>::from(cr),
-            downstream_component_presence: From::<u8>::from(dcp),
-            drs_message_received,
-        }
-    }
-}
+register!(LinkStatus2: u16 {
+    current_de_emphasis_level[0],
+    equalization_complete[1],
+    equalization_phase_1_successful[2],
+    equalization_phase_2_successful[3],
+    equalization_phase_3_successful[4],
+    link_equalization_request[5],
+    retimer_presence_detected[6],
+    two_retimers_presence_detected[7],
+    crosslink_resolution[8..10],
+    downstream_component_presence[12..15],
+    drs_message_received[15],
+});
 
 /// Indicates the state of the Crosslink negotiation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -2298,6 +3535,12 @@ impl From<u8> for CrosslinkResolution {
         }
     }
 }
+impl From<CrosslinkResolution> for u8 {
+    fn from(data: CrosslinkResolution) -> Self {
+        data as u8
+    }
+}
+impl_u8_field!(CrosslinkResolution);
 
 /// Indicates the presence and DRS status for the Downstream Component, if any, connected to the
 /// Link
@@ -2328,6 +3571,19 @@ impl From<u8> for DownstreamComponentPresence {
         }
     }
 }
+impl From<DownstreamComponentPresence> for u8 {
+    fn from(data: DownstreamComponentPresence) -> Self {
+        match data {
+            DownstreamComponentPresence::DownNotDetermined => 0b000,
+            DownstreamComponentPresence::DownNotPresent => 0b001,
+            DownstreamComponentPresence::DownPresent => 0b010,
+            DownstreamComponentPresence::UpPresent => 0b100,
+            DownstreamComponentPresence::UpPresentAndDrsReceived => 0b101,
+            DownstreamComponentPresence::Reserved(v) => v,
+        }
+    }
+}
+impl_u8_field!(DownstreamComponentPresence);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Slot2 {
@@ -2355,6 +3611,11 @@ impl From<u32> for SlotCapabilities2 {
         Self {}
     }
 }
+impl From<SlotCapabilities2> for u32 {
+    fn from(_: SlotCapabilities2) -> Self {
+        0
+    }
+}
 
 /// Slot Control 2 Register
 ///
@@ -2366,6 +3627,11 @@ impl From<u16> for SlotControl2 {
         Self {}
     }
 }
+impl From<SlotControl2> for u16 {
+    fn from(_: SlotControl2) -> Self {
+        0
+    }
+}
 
 /// Slot Status 2 Register
 ///
@@ -2377,6 +3643,11 @@ impl From<u16> for SlotStatus2 {
         Self {}
     }
 }
+impl From<SlotStatus2> for u16 {
+    fn from(_: SlotStatus2) -> Self {
+        0
+    }
+}
 
 /// Transmitter Preset
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2412,6 +3683,25 @@ impl From<u8> for TransmitterPreset {
         }
     }
 }
+impl From<TransmitterPreset> for u8 {
+    fn from(preset: TransmitterPreset) -> Self {
+        match preset {
+            TransmitterPreset::P0 => 0b0000,
+            TransmitterPreset::P1 => 0b0001,
+            TransmitterPreset::P2 => 0b0010,
+            TransmitterPreset::P3 => 0b0011,
+            TransmitterPreset::P4 => 0b0100,
+            TransmitterPreset::P5 => 0b0101,
+            TransmitterPreset::P6 => 0b0110,
+            TransmitterPreset::P7 => 0b0111,
+            TransmitterPreset::P8 => 0b1000,
+            TransmitterPreset::P9 => 0b1001,
+            TransmitterPreset::P10 => 0b1010,
+            TransmitterPreset::Reserved(v) => v,
+        }
+    }
+}
+impl_u8_field!(TransmitterPreset);
 
 /// Receiver Preset Hint
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2443,7 +3733,9 @@ impl From<u8> for ReceiverPresetHint {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config_write::{FieldAccess, FieldRef};
     use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
 
     #[test]
     fn endpoint() {
@@ -2623,6 +3915,7 @@ mod tests {
                     unsupported_request_detected: true,
                     aux_power_detected: false,
                     transactions_pending: false,
+                    emergency_power_reduction_detected: false,
                 },
             },
             device_2: Some(Device2 {
@@ -2664,7 +3957,582 @@ mod tests {
                 },
                 status: DeviceStatus2 {},
             }),
+            register_blocks: RegisterBlocks {
+                root: RegisterBlock::NotApplicable,
+                device_2: RegisterBlock::Present,
+                link_2: RegisterBlock::Present,
+                slot_2: RegisterBlock::NotApplicable,
+            },
+        };
+        assert_eq!(sample, result);
+        assert_eq!(data[2..], <[u8; PciExpress::SIZE]>::from(&result));
+    }
+
+    #[test]
+    fn device_type_id() {
+        for id in 0..16 {
+            let root = Some(Root::new(0, 0, 0));
+            let args = (id, Link::new(0, 0, 0), Slot::new(0, 0, 0), root, None, None);
+            let device_type = DeviceType::try_from(args).unwrap();
+            assert_eq!(id, device_type.id());
+        }
+    }
+
+    #[test]
+    fn register_blocks() {
+        use RegisterBlock::*;
+        let blocks = |caps: u16, len| {
+            let mut data = [0u8; PciExpress::SIZE];
+            data[..2].copy_from_slice(&caps.to_le_bytes());
+            let RegisterBlocks {
+                root,
+                device_2,
+                link_2,
+                slot_2,
+            } = RegisterBlocks::try_from(&data[..len]).unwrap();
+            [root, device_2, link_2, slot_2]
+        };
+        // Version 1 Root Port has no Device 2, Link 2 and Slot 2 registers
+        let result = blocks(0x0041, PciExpress::ROOT_SIZE);
+        assert_eq!(
+            [Present, NotApplicable, NotApplicable, NotApplicable],
+            result
+        );
+        let result = blocks(0x0041, PciExpress::MIN_SIZE);
+        assert_eq!(
+            [Truncated, NotApplicable, NotApplicable, NotApplicable],
+            result
+        );
+        // Version 2 Root Port
+        let result = blocks(0x0042, PciExpress::SIZE);
+        assert_eq!([Present; 4], result);
+        let result = blocks(0x0042, PciExpress::SIZE - 1);
+        assert_eq!([Present, Present, Present, Truncated], result);
+        // Version 2 Root Complex Event Collector has no Link and Slot
+        let result = blocks(0x00a2, PciExpress::ROOT_SIZE + 4);
+        assert_eq!([Present, Truncated, NotApplicable, NotApplicable], result);
+        // Version 2 Upstream Port
+        let result = blocks(0x0052, PciExpress::MIN_SIZE);
+        assert_eq!([NotApplicable, Truncated, Truncated, NotApplicable], result);
+
+        assert_eq!(
+            Err(PciExpressError::RequiredBytesSlice),
+            RegisterBlocks::try_from([0u8; PciExpress::MIN_SIZE - 1].as_slice())
+        );
+    }
+
+    #[test]
+    fn slot_command_sequence() {
+        let pending = SlotStatus::from(0);
+        let completed = SlotStatus::from(SlotStatus::COMMAND_COMPLETED);
+        assert!(completed.command_completed);
+
+        let mut seq = SlotCommandSequence::new(&SlotCapabilities::from(0));
+        assert_eq!(SlotCommandStep::Ready, seq.poll(&completed, 0));
+        let poll = SlotCommandStep::Poll { deadline: 1010 };
+        assert_eq!(Ok(poll), seq.write(10));
+        assert_eq!(Err(SlotCommandError { deadline: 1010 }), seq.write(20));
+        assert_eq!(poll, seq.poll(&pending, 1009));
+        assert_eq!(SlotCommandStep::TimedOut, seq.poll(&pending, 1010));
+        assert_eq!(SlotCommandStep::Ready, seq.step());
+        assert!(seq.write(1020).is_ok());
+        assert_eq!(SlotCommandStep::Completed, seq.poll(&completed, 1030));
+        assert_eq!(SlotCommandStep::Ready, seq.poll(&completed, 1040));
+
+        // No Command Completed Support
+        let mut seq = SlotCommandSequence::new(&SlotCapabilities::from(1 << 18));
+        assert_eq!(Ok(SlotCommandStep::Ready), seq.write(0));
+        assert_eq!(Ok(SlotCommandStep::Ready), seq.write(0));
+        assert_eq!(SlotCommandStep::Ready, seq.poll(&pending, 0));
+    }
+
+    #[test]
+    fn device_type_display() {
+        let link = Link::new(0, 0, 0);
+        let result = [
+            DeviceType::UpstreamPort {
+                link: link.clone(),
+                link_2: None,
+            }
+            .to_string(),
+            DeviceType::RootComplexIntegratedEndpoint.to_string(),
+            DeviceType::Reserved {
+                id: 0b1111,
+                link,
+                link_2: None,
+                slot: Slot::new(0, 0, 0),
+                slot_2: None,
+                root: None,
+            }
+            .to_string(),
+        ];
+        let sample = [
+            "Upstream Port of PCI Express Switch",
+            "Root Complex Integrated Endpoint",
+            "Reserved (1111)",
+        ];
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn emergency_power_reduction() {
+        // Device Status, Device Capabilities 2 and Device Control 2 in configuration space
+        const DEV_STA: usize = 0x4a;
+        const DEV_CAP2: usize = 0x67;
+        const DEV_CTL2: usize = 0x68;
+        let mut data = crate::synthetic::endpoint_with_msix(1);
+        let epr = |data: &[u8]| {
+            PciExpress::try_from(&data[0x42..])
+                .unwrap()
+                .emergency_power_reduction()
+                .unwrap()
+        };
+
+        let result = epr(&data);
+        assert_eq!(EmergencyPowerReduction::NotSupported, result.supported);
+        assert!(!result.can_request());
+        assert_eq!(Ok(()), result.validate(), "Not supported");
+
+        // Device Specific mechanism, Initialization Required
+        data[DEV_CAP2] |= 0b101;
+        let mut ctl2 = u16::from_le_bytes([data[DEV_CTL2], data[DEV_CTL2 + 1]]);
+        assert!(epr(&data).can_request());
+        DeviceControl2::request_emergency_power_reduction(&mut ctl2);
+        data[DEV_CTL2..DEV_CTL2 + 2].copy_from_slice(&ctl2.to_le_bytes());
+        data[DEV_STA] |= DeviceStatus::EMERGENCY_POWER_REDUCTION_DETECTED as u8;
+        let result = epr(&data);
+        assert!(result.requested && result.is_active() && !result.can_request());
+        assert_eq!(Ok(()), result.validate(), "Requested");
+
+        DeviceControl2::release_emergency_power_reduction(&mut ctl2);
+        data[DEV_CTL2..DEV_CTL2 + 2].copy_from_slice(&ctl2.to_le_bytes());
+        assert_eq!(
+            Err(EmergencyPowerReductionError::InitializationPending),
+            epr(&data).validate(),
+            "Released"
+        );
+
+        // Form Factor mechanism triggers the state without request
+        data[DEV_CAP2] ^= 0b011;
+        let result = epr(&data);
+        assert_eq!(
+            EmergencyPowerReduction::FormFactorOrDeviceSpecific,
+            result.supported
+        );
+        assert!(result.is_active() && !result.requested);
+        assert_eq!(Ok(()), result.validate(), "Form Factor triggered");
+
+        data[DEV_CAP2] &= !0b111;
+        assert_eq!(
+            Err(EmergencyPowerReductionError::NotSupported {
+                requested: false,
+                detected: true
+            }),
+            epr(&data).validate(),
+            "Detected on not supported"
+        );
+    }
+
+    #[test]
+    fn aspm_latency() {
+        // L0s 512 ns, L1 2 µs
+        let endpoint = DeviceCapabilities::from(0b001 << 9 | 0b011 << 6);
+        // L0s 256-512 ns, L1 1-2 µs
+        let port = |common_clock: bool| {
+            Link::new(0b011 << 12 | 0b001 << 15, (common_clock as u16) << 6, 0)
+        };
+        let (sw_up, sw_down, root) = (port(true), port(true), port(true));
+        let aspm = |l0s, l1| Ok(AspmLatency { l0s, l1 });
+
+        let result = AspmLatency::check(&endpoint, &[(&port(true), &sw_down)]);
+        assert_eq!(aspm(true, true), result, "Single link");
+
+        let path = [(&port(true), &sw_down), (&sw_up, &root)];
+        let result = AspmLatency::check(&endpoint, &path);
+        assert_eq!(aspm(true, false), result, "L1: 2 + 1 µs");
+
+        let endpoint = DeviceCapabilities::from(0b001 << 6);
+        let result = AspmLatency::check(&endpoint, &[(&port(true), &sw_down)]);
+        assert_eq!(aspm(false, false), result, "Strict");
+
+        let endpoint = DeviceCapabilities::from(0b111 << 9 | 0b111 << 6);
+        let result = AspmLatency::check(&endpoint, &[(&port(true), &sw_down)]);
+        assert_eq!(aspm(true, true), result, "No limit");
+
+        let path = [(&port(true), &sw_down), (&sw_up, &port(false))];
+        let result = AspmLatency::check(&endpoint, &path);
+        let sample = Err(AspmLatencyError::CommonClockConfiguration { link: 1 });
+        assert_eq!(sample, result, "Common clock differs");
+    }
+
+    #[test]
+    fn link_width_order() {
+        assert!(LinkWidth::X16 > LinkWidth::X12);
+        assert!(LinkWidth::X12 > LinkWidth::X8);
+        assert!(LinkWidth::Reserved(0x3f) < LinkWidth::X1);
+        assert!(LinkWidth::Reserved(0) < LinkWidth::Reserved(3));
+        let widths = [4, 32, 1, 0x3f].map(LinkWidth::from);
+        assert_eq!(Some(&LinkWidth::X32), widths.iter().max());
+        assert_eq!(8, LinkWidth::from(8).value());
+    }
+
+    #[test]
+    fn max_size_bytes() {
+        for bytes in [128, 256, 512, 1024, 2048, 4096] {
+            let size = MaxSize::try_from_bytes(bytes).unwrap();
+            assert_eq!(Some(bytes), size.bytes());
+            assert_eq!(size, MaxSize::from(u8::from(size)));
+        }
+        assert_eq!(None, MaxSize::from(0b110).bytes());
+        assert_eq!(None, MaxSize::from(0b111).bytes());
+        assert_eq!(None, MaxSize::try_from_bytes(0));
+        assert_eq!(None, MaxSize::try_from_bytes(8192));
+    }
+
+    #[test]
+    fn device_control_max_size_setters() {
+        let mut control = DeviceControl::from(0x2810);
+        assert_eq!(0x2810, u16::from(control.clone()));
+
+        assert_eq!(
+            Ok(()),
+            control.set_max_payload_size(MaxSize::B256, MaxSize::B512)
+        );
+        assert_eq!(
+            Err(MaxSizeError::Unsupported {
+                size: MaxSize::B1024,
+                supported: MaxSize::B512
+            }),
+            control.set_max_payload_size(MaxSize::B1024, MaxSize::B512)
+        );
+        assert_eq!(
+            Err(MaxSizeError::Reserved {
+                size: MaxSize::Reserved0
+            }),
+            control.set_max_payload_size(MaxSize::Reserved0, MaxSize::B4096)
+        );
+        assert_eq!(Ok(()), control.set_max_read_request_size(MaxSize::B4096));
+        assert_eq!(
+            Err(MaxSizeError::Reserved {
+                size: MaxSize::Reserved1
+            }),
+            control.set_max_read_request_size(MaxSize::Reserved1)
+        );
+        assert_eq!(0x5830, u16::from(control));
+    }
+
+    #[test]
+    fn link_control_setters() {
+        let device_type = |id| {
+            let root = Some(Root::new(0, 0, 0));
+            let args = (id, Link::new(0, 0, 0), Slot::new(0, 0, 0), root, None, None);
+            DeviceType::try_from(args).unwrap()
         };
+        let (endpoint, root_port, rciep) = (
+            device_type(0b0000),
+            device_type(0b0100),
+            device_type(0b1001),
+        );
+        let mut control = LinkControl::from(0);
+
+        assert_eq!(Ok(()), control.set_retrain_link(true, &root_port));
+        assert_eq!(
+            Err(LinkControlError::NotApplicable {
+                field: LinkControlField::RetrainLink,
+                device_type: 0b0000,
+            }),
+            control.set_retrain_link(false, &endpoint)
+        );
+        assert!(control
+            .set_read_completion_boundary(ReadCompletionBoundary::B128, &root_port)
+            .is_err());
+        assert_eq!(
+            Ok(()),
+            control.set_read_completion_boundary(ReadCompletionBoundary::B128, &endpoint)
+        );
+        assert!(control
+            .set_enable_clock_power_management(true, &root_port)
+            .is_err());
+        assert!(control
+            .set_common_clock_configuration(true, &rciep)
+            .is_err());
+        assert_eq!(
+            Ok(()),
+            control.set_active_state_power_management_control(
+                ActiveStatePowerManagement::L1,
+                &endpoint
+            )
+        );
+        // ASPM L1, RCB 128 bytes, Retrain Link
+        assert_eq!(0b10_1010, u16::from(control.clone()));
+        assert_eq!(control, LinkControl::from(0xf000 | 0b10_1010));
+    }
+
+    #[test]
+    fn root_registers() {
+        let control = RootControl::from(0xffff);
+        assert!(control.no_nfm_subtree_below_this_root_port);
+        assert_eq!(0x003f, u16::from(control));
+        let mut control = RootControl::from(RootControl::NO_NFM_SUBTREE_BELOW_THIS_ROOT_PORT);
+        assert!(!control.pme_interrupt_enable);
+        control.pme_interrupt_enable = true;
+        assert_eq!(0b10_1000, u16::from(control));
+
+        let capabilities = RootCapabilities::from(0xffff);
+        let value: u16 = capabilities.into();
+        assert_eq!(RootCapabilities::CRS_SOFTWARE_VISIBILITY, value);
+
+        let status = RootStatus::from(0xffff_0100);
+        assert!(status.pme_status && status.pme_pending);
+        let value: u32 = status.into();
+        assert_eq!(
+            RootStatus::PME_STATUS | RootStatus::PME_PENDING | 0x0100,
+            value
+        );
+    }
+
+    #[test]
+    fn status_bits() {
+        let status = DeviceStatus::from(0x0035);
+        let sample = vec![
+            ("correctable_error_detected", true),
+            ("non_fatal_error_detected", false),
+            ("fatal_error_detected", true),
+            ("unsupported_request_detected", false),
+        ];
+        assert_eq!(sample, status.error_bits().collect::<Vec<_>>());
+
+        let status = SlotStatus::from(0x0140);
+        let result = status
+            .bits()
+            .filter_map(|(name, value)| value.then_some(name))
+            .collect::<Vec<_>>();
+        let sample = vec!["presence_detect_state", "data_link_layer_state_changed"];
         assert_eq!(sample, result);
+        assert_eq!(9, status.bits().count());
+    }
+
+    #[test]
+    fn slot_power_limit_round_trip() {
+        for value in 0xe0..=0xffu8 {
+            for scale in 0..4u32 {
+                let dword = u32::from(value) << 7 | scale << 15 | 0x1234 << 19;
+                let caps = SlotCapabilities::from(dword);
+                assert_eq!(dword, u32::from(caps));
+                let dword = u32::from(value) << 18 | scale << 26 | 1 << 28;
+                let caps = DeviceCapabilities::from(dword);
+                assert_eq!(dword, u32::from(caps));
+            }
+        }
+        let watts = [0xef, 0xf0, 0xf1, 0xf2, 0xf3, 0xff]
+            .map(|value| SlotPowerLimit::new(value, 0b00).watts());
+        let sample = [
+            Some(239.0),
+            Some(250.0),
+            Some(275.0),
+            Some(300.0),
+            None,
+            None,
+        ];
+        assert_eq!(sample, watts);
+        assert_eq!(300.0, f32::from(SlotPowerLimit::new(0xf3, 0b00)));
+        assert_eq!(Some(0.255), SlotPowerLimit::new(0xff, 0b11).watts());
+    }
+
+    #[test]
+    fn slot_power_limit_from_watts() {
+        let watts = [
+            0.0, 0.0006, 0.0015, 0.2555, 2.5605, 7.5, 25.0, 25.555, 75.0, 239.0, 249.9, 250.0,
+            280.0, 300.0, 600.0,
+        ];
+        let result = watts.map(|watts| {
+            let spl = SlotPowerLimit::from_watts(watts).unwrap();
+            (spl.value, spl.scale_encoding())
+        });
+        let sample = [
+            (0, 0b00),
+            (0, 0b00),
+            (1, 0b11),
+            (255, 0b11),
+            (255, 0b10),
+            (75, 0b01),
+            (25, 0b00),
+            (255, 0b01),
+            (75, 0b00),
+            (0xef, 0b00),
+            (0xef, 0b00),
+            (0xf0, 0b00),
+            (0xf1, 0b00),
+            (0xf2, 0b00),
+            (0xf2, 0b00),
+        ];
+        assert_eq!(sample, result);
+        // Sub-milliwatt fractions are truncated, the limit never exceeds requested watts
+        for watts in watts {
+            let limit = SlotPowerLimit::from_watts(watts).unwrap().watts().unwrap();
+            assert!(limit <= watts, "{limit} W > {watts} W");
+        }
+        assert_eq!(None, SlotPowerLimit::from_watts(-1.0));
+        assert_eq!(None, SlotPowerLimit::from_watts(f32::NAN));
+    }
+
+    #[test]
+    fn reset_values() {
+        let control = DeviceControl::default();
+        assert_eq!(0x2810, u16::from(control.clone()));
+        assert!(control.enable_relaxed_ordering && control.enable_no_snoop);
+        assert_eq!(
+            (MaxSize::B128, MaxSize::B512),
+            (control.max_payload_size, control.max_read_request_size)
+        );
+        let control = SlotControl::default();
+        assert_eq!(
+            (IndicatorControl::Off, IndicatorControl::Off, false),
+            (
+                control.attention_indicator_control,
+                control.power_indicator_control,
+                control.power_controller_control
+            )
+        );
+        assert_eq!(0, u16::from(LinkControl::default()));
+        assert_eq!(0, u16::from(RootControl::default()));
+        assert_eq!(0, u16::from(DeviceControl2::default()));
+        assert_eq!(
+            0x0004,
+            u16::from(LinkControl2::new_spec_default(LinkSpeed::Rate16GTps))
+        );
+    }
+
+    #[test]
+    fn reset_controls() {
+        // Root Port, Link 2 implemented
+        let mut data = [0xffu8; PciExpress::SIZE];
+        data[0] = 0x42;
+        // Max Link Speed 8.0 GT/s
+        data[0x0a] = 0x03;
+        let mut pcie = PciExpress::try_from(data.as_slice()).unwrap();
+        assert!(matches!(pcie.device_type, DeviceType::RootPort { .. }));
+        let mut sample = pcie.clone();
+        pcie.reset_controls();
+        sample.device.control = DeviceControl::from(0x2810);
+        sample.device_2.as_mut().unwrap().control = DeviceControl2::from(0);
+        if let DeviceType::RootPort {
+            link,
+            link_2,
+            slot,
+            root,
+            ..
+        } = &mut sample.device_type
+        {
+            link.control = LinkControl::from(0);
+            link_2.as_mut().unwrap().control = LinkControl2::from(0x0003);
+            slot.control = SlotControl::from(0x03c0);
+            root.control = RootControl::from(RootControl::NO_NFM_SUBTREE_BELOW_THIS_ROOT_PORT);
+        }
+        assert_eq!(sample, pcie);
+    }
+
+    #[test]
+    fn round_trip() {
+        // Defined bits of every register
+        let masks = [
+            (Register::DeviceCapabilities, 0x1ffc_ffff),
+            (Register::DeviceControl, 0xffff),
+            (Register::DeviceStatus, 0x007f),
+            (Register::LinkCapabilities, 0xff7f_ffff),
+            (Register::LinkControl, 0x0ffb),
+            (Register::LinkStatus, 0xffff),
+            (Register::SlotCapabilities, 0xffff_ffff),
+            (Register::SlotControl, 0x1fff),
+            (Register::SlotStatus, 0x01ff),
+            (Register::RootControl, 0x003f),
+            (Register::RootCapabilities, 0x0001),
+            (Register::RootStatus, 0x0003_ffff),
+            (Register::DeviceCapabilities2, 0x87ff_ffff),
+            (Register::DeviceControl2, 0xffff),
+            (Register::LinkCapabilities2, 0x81ff_fffe),
+            (Register::LinkControl2, 0xffff),
+            (Register::LinkStatus2, 0xf3ff),
+        ];
+        for pattern in [0x5a5a_5a5au32, 0xa5a5_a5a5, 0xffff_ffff] {
+            // Capability Version 2 Root Port
+            let mut data = [0u8; PciExpress::SIZE];
+            data[..2].copy_from_slice(&(pattern as u16 & 0x7f00 | 0x42).to_le_bytes());
+            for (register, mask) in masks {
+                let start = register.offset() as usize - 2;
+                let width = register.descriptor().width.bytes();
+                data[start..start + width]
+                    .copy_from_slice(&(pattern & mask).to_le_bytes()[..width]);
+            }
+            let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+            assert_eq!(data, <[u8; PciExpress::SIZE]>::from(&pcie));
+        }
+
+        // Registers not implemented by the Function are zero
+        let mut data = [0xffu8; PciExpress::SIZE];
+        // Capability Version 1 Root Complex Integrated Endpoint
+        data[..2].copy_from_slice(&0x0091u16.to_le_bytes());
+        let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+        let mut sample = [0u8; PciExpress::SIZE];
+        sample[..2].copy_from_slice(&0x0091u16.to_le_bytes());
+        sample[2..10].copy_from_slice(&[0xff, 0xff, 0xfc, 0x1f, 0xff, 0xff, 0x7f, 0x00]);
+        assert_eq!(sample, <[u8; PciExpress::SIZE]>::from(&pcie));
+    }
+
+    #[test]
+    fn register_offsets() {
+        let pointer = ConfigOffset(0x40);
+        // Capability Version 2 Root Port, data ends before Slot 2 registers
+        let mut data = [0u8; PciExpress::ROOT_SIZE + 16];
+        data[0] = 0x42;
+        let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+        assert_eq!(RegisterBlock::Truncated, pcie.register_blocks.slot_2);
+        let result = [
+            Register::PciExpressCapabilities,
+            Register::SlotStatus,
+            Register::RootStatus,
+            Register::SlotControl2,
+        ]
+        .map(|register| pcie.absolute_offset_of(pointer, register));
+        let sample = [
+            Some(ConfigOffset(0x42)),
+            Some(ConfigOffset(0x5a)),
+            Some(ConfigOffset(0x60)),
+            Some(ConfigOffset(0x78)),
+        ];
+        assert_eq!(sample, result);
+
+        // Capability Version 2 Upstream Port has no Slot and Root registers
+        let mut data = [0u8; PciExpress::SIZE];
+        data[0] = 0x52;
+        let pcie = PciExpress::try_from(data.as_slice()).unwrap();
+        let result = [
+            Register::LinkStatus,
+            Register::SlotControl,
+            Register::RootControl,
+            Register::LinkControl2,
+            Register::SlotStatus2,
+        ]
+        .map(|register| pcie.absolute_offset_of(pointer, register));
+        let sample = [
+            Some(ConfigOffset(0x52)),
+            None,
+            None,
+            Some(ConfigOffset(0x70)),
+            None,
+        ];
+        assert_eq!(sample, result);
+
+        // Max Link Speed is read-only
+        let field = FieldRef::new(pointer, Register::LinkCapabilities.descriptor(), 0, 4);
+        assert_eq!(FieldAccess::ReadOnly, field.access);
+        let field = FieldRef::new(pointer, Register::LinkStatus2.descriptor(), 15, 1);
+        assert_eq!(FieldAccess::Rw1c, field.access);
+
+        // Writing back the read value keeps pending Link Status 2 events
+        let register = Register::LinkStatus2.descriptor();
+        assert_eq!(None, register.write(pointer, 0x8020, 0x8020));
+        let write = register.write(pointer, 0x8020, 0x0020).unwrap();
+        assert_eq!((ConfigOffset(0x73), 0x80), (write.offset, write.value));
     }
 }
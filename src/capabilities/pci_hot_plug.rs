@@ -0,0 +1,440 @@
+/*!
+# PCI Hot-Plug
+
+Indicates that the associated device conforms to the Standard Hot-Plug Controller (SHPC)
+model. The SHPC Working Register Set is not mapped to configuration space directly, the
+capability gives access to one DWORD of it at a time: the DWORD selected by *DWORD Select* is
+readable through *DWORD Data*.
+
+## Struct diagram
+[PciHotPlug]
+- [WorkingRegister]
+  - [SlotsAvailable1]
+  - [SlotsAvailable2]
+  - [SlotConfiguration]
+  - [SlotRegister]
+    - [SlotState]
+    - [IndicatorState]
+    - [SlotEvents]
+
+## Examples
+
+DWORD Select points to the Slot Configuration register
+
+```rust
+# use pcics::capabilities::pci_hot_plug::*;
+let data = [0x0c, 0x00, 0x03, 0x00, 0x02, 0x01, 0x05, 0xa0];
+let result: PciHotPlug = data[2..].try_into().unwrap();
+let sample = PciHotPlug {
+    dword_select: 3,
+    pending: false,
+    dword_data: 0xa0050102,
+};
+assert_eq!(sample, result);
+
+let slot_configuration = SlotConfiguration {
+    number_of_slots_implemented: 2,
+    first_device_number: 1,
+    physical_slot_number: 5,
+    physical_slot_number_up: true,
+    mrl_sensor_implemented: false,
+    attention_button_implemented: true,
+};
+assert_eq!(
+    WorkingRegister::SlotConfiguration(slot_configuration),
+    result.working_register()
+);
+```
+*/
+
+use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P16, P2, P3, P6, P8, P9};
+
+use super::CapabilityDataError;
+
+/// PCI Hot-Plug (SHPC)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PciHotPlug {
+    /// Selects the DWORD of the SHPC Working Register Set accessible through DWORD Data
+    pub dword_select: u8,
+    /// SHPC has a pending interrupt
+    pub pending: bool,
+    /// Value of the selected DWORD of the SHPC Working Register Set
+    pub dword_data: u32,
+}
+impl PciHotPlug {
+    pub const SIZE: usize = 1 + 1 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+
+    /// Decode [dword_data](Self::dword_data) according to [dword_select](Self::dword_select)
+    pub fn working_register(&self) -> WorkingRegister {
+        WorkingRegister::new(self.dword_select, self.dword_data)
+    }
+}
+impl TryFrom<&[u8]> for PciHotPlug {
+    type Error = CapabilityDataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((dword_select, pending, dword_data)),
+            ..
+        } = P3(slice).try_into().map_err(|_| CapabilityDataError {
+            name: "PCI Hot-Plug",
+            size: Self::MIN_SIZE,
+        })?;
+        let Lsb(((), pending)) = P2::<u8, 7, 1>(pending).into();
+        Ok(Self {
+            dword_select,
+            pending,
+            dword_data,
+        })
+    }
+}
+
+/// DWORD of the SHPC Working Register Set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkingRegister {
+    /// Offset of the memory mapped SHPC Working Register Set from the BAR0 base
+    BaseOffset(u32),
+    SlotsAvailable1(SlotsAvailable1),
+    SlotsAvailable2(SlotsAvailable2),
+    SlotConfiguration(SlotConfiguration),
+    /// Slot register of the slot with logical number (starts from 1)
+    Slot {
+        number: u8,
+        register: SlotRegister,
+    },
+    /// Controller registers without dedicated decoder (Secondary Bus Configuration, Command,
+    /// Interrupt Locator, SERR Locator, Controller SERR-INT Enable) and reserved DWORDs
+    Other(u32),
+}
+impl WorkingRegister {
+    /// DWORD index of the first slot register
+    pub const SLOT_1: u8 = 0x09;
+    /// Maximum number of slots controlled by SHPC
+    pub const MAX_SLOTS: u8 = 31;
+
+    pub fn new(dword_select: u8, dword_data: u32) -> Self {
+        match dword_select {
+            0x00 => Self::BaseOffset(dword_data),
+            0x01 => Self::SlotsAvailable1(dword_data.into()),
+            0x02 => Self::SlotsAvailable2(dword_data.into()),
+            0x03 => Self::SlotConfiguration(dword_data.into()),
+            n if (Self::SLOT_1..Self::SLOT_1 + Self::MAX_SLOTS).contains(&n) => Self::Slot {
+                number: n - Self::SLOT_1 + 1,
+                register: dword_data.into(),
+            },
+            _ => Self::Other(dword_data),
+        }
+    }
+}
+
+/// Number of slots that can be enabled at the given bus frequency and mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotsAvailable1 {
+    /// Conventional PCI 33 MHz
+    pub conventional_33mhz: u8,
+    /// PCI-X 66 MHz
+    pub pci_x_66mhz: u8,
+    /// PCI-X 100 MHz
+    pub pci_x_100mhz: u8,
+    /// PCI-X 133 MHz
+    pub pci_x_133mhz: u8,
+}
+impl From<u32> for SlotsAvailable1 {
+    fn from(dword: u32) -> Self {
+        let Lsb((conventional_33mhz, (), pci_x_66mhz, (), pci_x_100mhz, (), pci_x_133mhz, ())) =
+            P8::<u32, 5, 3, 5, 3, 5, 3, 5, 3>(dword).into();
+        Self {
+            conventional_33mhz,
+            pci_x_66mhz,
+            pci_x_100mhz,
+            pci_x_133mhz,
+        }
+    }
+}
+
+/// Number of slots that can be enabled at the given bus frequency and mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotsAvailable2 {
+    /// Conventional PCI 66 MHz
+    pub conventional_66mhz: u8,
+    /// PCI-X 266 at 66 MHz
+    pub pci_x_266_66mhz: u8,
+    /// PCI-X 266 at 100 MHz
+    pub pci_x_266_100mhz: u8,
+    /// PCI-X 266 at 133 MHz
+    pub pci_x_266_133mhz: u8,
+    /// PCI-X 533 at 66 MHz
+    pub pci_x_533_66mhz: u8,
+    /// PCI-X 533 at 100 MHz
+    pub pci_x_533_100mhz: u8,
+    /// PCI-X 533 at 133 MHz
+    pub pci_x_533_133mhz: u8,
+}
+impl From<u32> for SlotsAvailable2 {
+    fn from(dword: u32) -> Self {
+        let Lsb((
+            conventional_66mhz,
+            (),
+            pci_x_266_66mhz,
+            pci_x_266_100mhz,
+            pci_x_266_133mhz,
+            pci_x_533_66mhz,
+            pci_x_533_100mhz,
+            pci_x_533_133mhz,
+        )) = P8::<u32, 5, 3, 4, 4, 4, 4, 4, 4>(dword).into();
+        Self {
+            conventional_66mhz,
+            pci_x_266_66mhz,
+            pci_x_266_100mhz,
+            pci_x_266_133mhz,
+            pci_x_533_66mhz,
+            pci_x_533_100mhz,
+            pci_x_533_133mhz,
+        }
+    }
+}
+
+/// Slot Configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotConfiguration {
+    /// Number of Slots Implemented (NSI)
+    pub number_of_slots_implemented: u8,
+    /// First Device Number (FDN) assigned to the first hot-plug slot
+    pub first_device_number: u8,
+    /// Physical Slot Number (PSN) of the first hot-plug slot
+    pub physical_slot_number: u16,
+    /// Physical Slot Number Up/Down: physical slot numbers increase (`true`) or decrease
+    /// with device number
+    pub physical_slot_number_up: bool,
+    /// MRL Sensor Implemented (MRLSI)
+    pub mrl_sensor_implemented: bool,
+    /// Attention Button Implemented (ABI)
+    pub attention_button_implemented: bool,
+}
+impl From<u32> for SlotConfiguration {
+    fn from(dword: u32) -> Self {
+        let Lsb((
+            number_of_slots_implemented,
+            (),
+            first_device_number,
+            (),
+            physical_slot_number,
+            (),
+            physical_slot_number_up,
+            mrl_sensor_implemented,
+            attention_button_implemented,
+        )) = P9::<u32, 5, 3, 5, 3, 11, 2, 1, 1, 1>(dword).into();
+        Self {
+            number_of_slots_implemented,
+            first_device_number,
+            physical_slot_number,
+            physical_slot_number_up,
+            mrl_sensor_implemented,
+            attention_button_implemented,
+        }
+    }
+}
+
+/// Logical Slot register: slot status, event latches and event masks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotRegister {
+    pub slot_state: SlotState,
+    /// Power Indicator State
+    pub power_indicator_state: IndicatorState,
+    /// Attention Indicator State
+    pub attention_indicator_state: IndicatorState,
+    /// Power Fault detected
+    pub power_fault: bool,
+    /// Attention Button is pressed
+    pub attention_button: bool,
+    /// MRL is open
+    pub mrl_sensor: bool,
+    /// Card in slot is 66 MHz capable (M66EN pin)
+    pub m66en: bool,
+    /// PRSNT1# and PRSNT2# pins state, `0b11` means the slot is empty
+    pub prsnt: u8,
+    /// PCIXCAP pins state
+    pub pcixcap: u8,
+    /// Latched events
+    pub detected: SlotEvents,
+    /// Masked interrupts
+    pub interrupt_mask: SlotEvents,
+    /// MRL Sensor change SERR mask
+    pub mrl_sensor_serr_mask: bool,
+    /// Connected Power Fault SERR mask
+    pub connected_power_fault_serr_mask: bool,
+}
+impl From<u32> for SlotRegister {
+    fn from(dword: u32) -> Self {
+        let Lsb((
+            slot_state,
+            power_indicator_state,
+            attention_indicator_state,
+            power_fault,
+            attention_button,
+            mrl_sensor,
+            m66en,
+            prsnt,
+            pcixcap,
+            (),
+            detected,
+            (),
+            interrupt_mask,
+            mrl_sensor_serr_mask,
+            connected_power_fault_serr_mask,
+            (),
+        )) = P16::<u32, 2, 2, 2, 1, 1, 1, 1, 2, 3, 1, 5, 3, 5, 1, 1, 1>(dword).into();
+        Self {
+            slot_state: From::<u8>::from(slot_state),
+            power_indicator_state: From::<u8>::from(power_indicator_state),
+            attention_indicator_state: From::<u8>::from(attention_indicator_state),
+            power_fault,
+            attention_button,
+            mrl_sensor,
+            m66en,
+            prsnt,
+            pcixcap,
+            detected: From::<u8>::from(detected),
+            interrupt_mask: From::<u8>::from(interrupt_mask),
+            mrl_sensor_serr_mask,
+            connected_power_fault_serr_mask,
+        }
+    }
+}
+
+/// Slot State
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState {
+    Reserved,
+    /// Powered Only
+    PoweredOnly,
+    Enabled,
+    Disabled,
+}
+impl From<u8> for SlotState {
+    fn from(byte: u8) -> Self {
+        match byte & 0b11 {
+            0b01 => Self::PoweredOnly,
+            0b10 => Self::Enabled,
+            0b11 => Self::Disabled,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+/// Power / Attention Indicator State
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndicatorState {
+    Reserved,
+    On,
+    Blink,
+    Off,
+}
+impl From<u8> for IndicatorState {
+    fn from(byte: u8) -> Self {
+        match byte & 0b11 {
+            0b01 => Self::On,
+            0b10 => Self::Blink,
+            0b11 => Self::Off,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+/// Slot events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotEvents {
+    /// Card Presence Change
+    pub card_presence_change: bool,
+    /// Isolated Power Fault
+    pub isolated_power_fault: bool,
+    /// Attention Button press
+    pub attention_button: bool,
+    /// MRL Sensor change
+    pub mrl_sensor_change: bool,
+    /// Connected Power Fault
+    pub connected_power_fault: bool,
+}
+impl From<u8> for SlotEvents {
+    fn from(byte: u8) -> Self {
+        let Lsb((
+            card_presence_change,
+            isolated_power_fault,
+            attention_button,
+            mrl_sensor_change,
+            connected_power_fault,
+            (),
+        )) = P6::<u8, 1, 1, 1, 1, 1, 3>(byte).into();
+        Self {
+            card_presence_change,
+            isolated_power_fault,
+            attention_button,
+            mrl_sensor_change,
+            connected_power_fault,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn slot_register() {
+        // Slot 2: enabled, power indicator on, attention indicator off, card present,
+        // attention button press latched, all interrupts masked
+        let result = WorkingRegister::new(0x0a, 0x1f_04_00_36);
+        let sample = WorkingRegister::Slot {
+            number: 2,
+            register: SlotRegister {
+                slot_state: SlotState::Enabled,
+                power_indicator_state: IndicatorState::On,
+                attention_indicator_state: IndicatorState::Off,
+                power_fault: false,
+                attention_button: false,
+                mrl_sensor: false,
+                m66en: false,
+                prsnt: 0,
+                pcixcap: 0,
+                detected: SlotEvents {
+                    card_presence_change: false,
+                    isolated_power_fault: false,
+                    attention_button: true,
+                    mrl_sensor_change: false,
+                    connected_power_fault: false,
+                },
+                interrupt_mask: SlotEvents {
+                    card_presence_change: true,
+                    isolated_power_fault: true,
+                    attention_button: true,
+                    mrl_sensor_change: true,
+                    connected_power_fault: true,
+                },
+                mrl_sensor_serr_mask: false,
+                connected_power_fault_serr_mask: false,
+            },
+        };
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn working_register_set_bounds() {
+        assert_eq!(WorkingRegister::Other(1), WorkingRegister::new(0x08, 1));
+        assert!(matches!(
+            WorkingRegister::new(0x27, 0),
+            WorkingRegister::Slot { number: 31, .. }
+        ));
+        assert_eq!(WorkingRegister::Other(1), WorkingRegister::new(0x28, 1));
+    }
+
+    #[test]
+    fn pending() {
+        let data = [0x00, 0x80, 0x00, 0x00, 0x00, 0x00];
+        let result: PciHotPlug = data.as_slice().try_into().unwrap();
+        assert!(result.pending);
+        assert_eq!(WorkingRegister::BaseOffset(0), result.working_register());
+        assert!(PciHotPlug::try_from(&data[..5]).is_err());
+    }
+}
@@ -169,14 +169,33 @@ use snafu::Snafu;
 
 /// PCI-X Errors
 #[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
 pub enum PciXError {
-    #[snafu(display("command and status registers are unreadable"))]
+    #[snafu(display(
+        "command and status registers ({} bytes) are unreadable",
+        PciX::MIN_SIZE
+    ))]
     CommandAndStatus,
-    #[snafu(display("Mode 2 ECC registres unreadable"))]
+    #[snafu(display(
+        "Mode 2 ECC registers unreadable ({} bytes)",
+        PciX::MIN_SIZE + Ecc::SIZE
+    ))]
     EccMode2Only,
-    #[snafu(display("Mode 1/2 ECC registres unreadable"))]
+    #[snafu(display(
+        "Mode 1/2 ECC registers unreadable ({} bytes)",
+        PciX::MIN_SIZE + Ecc::SIZE
+    ))]
     EccMode1OrMode2,
 }
+impl PciXError {
+    /// Command and Status registers, with ECC registers if ECC is supported
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::CommandAndStatus => PciX::MIN_SIZE,
+            Self::EccMode2Only | Self::EccMode1OrMode2 => PciX::MIN_SIZE + Ecc::SIZE,
+        })
+    }
+}
 
 /// Type 00h Configuration Space header PCI-X Capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -186,6 +205,9 @@ pub struct PciX {
     pub ecc: Ecc,
 }
 
+impl PciX {
+    pub const MIN_SIZE: usize = 2 + 4;
+}
 impl TryFrom<&[u8]> for PciX {
     type Error = PciXError;
 
@@ -421,6 +443,10 @@ pub enum Ecc {
     },
     Reserved,
 }
+impl Ecc {
+    /// ECC Control and Status, First Address, Second Address and Attribute registers
+    pub const SIZE: usize = 4 * 4;
+}
 
 /// Provides information about detected ECC errors
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -558,14 +584,35 @@ impl From<u8> for Syndrome {
 
 /// PCI-X Bridge Errors
 #[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
 pub enum PciXBridgeError {
-    #[snafu(display("statuses and split trx control registers are unreadable"))]
+    #[snafu(display(
+        "statuses and split trx control registers ({} bytes) are unreadable",
+        PciXBridge::MIN_SIZE
+    ))]
     Mandatory,
-    #[snafu(display("Mode 2 ECC registres unreadable"))]
+    #[snafu(display(
+        "Mode 2 ECC registers unreadable ({} bytes)",
+        PciXBridge::MIN_SIZE + Ecc::SIZE
+    ))]
     BridgeEccMode2Only,
-    #[snafu(display("Mode 1/2 ECC registres unreadable"))]
+    #[snafu(display(
+        "Mode 1/2 ECC registers unreadable ({} bytes)",
+        PciXBridge::MIN_SIZE + Ecc::SIZE
+    ))]
     BridgeEccMode1OrMode2,
 }
+impl PciXBridgeError {
+    /// Mandatory bridge registers, with ECC registers if ECC is supported
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::Mandatory => PciXBridge::MIN_SIZE,
+            Self::BridgeEccMode2Only | Self::BridgeEccMode1OrMode2 => {
+                PciXBridge::MIN_SIZE + Ecc::SIZE
+            }
+        })
+    }
+}
 
 /// Type 01h Configuration Space header (Bridge) PCI-X Capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -577,6 +624,9 @@ pub struct PciXBridge {
     pub ecc: Ecc,
 }
 
+impl PciXBridge {
+    pub const MIN_SIZE: usize = 2 + 4 + 2 + 2 + 2 + 2;
+}
 impl TryFrom<&[u8]> for PciXBridge {
     type Error = PciXBridgeError;
 
@@ -14,12 +14,14 @@ a PCI device. It is fully documented in the PCI Power Management Interface Speci
   - [DataSelect]
   - [DataScale]
 - [Bridge]
+- [Data]
+  - [PowerConsumption]
 
 ## Examples
 
 > Power Management version 3
-Flags: PMEClk- DSI- D1- D2- AuxCurrent=0mA PME(D0-,D1-,D2-,D3hot-,D3cold-)
-Status: D0 NoSoftRst+ PME-Enable- DSel=0 DScale=0 PME-
+> Flags: PMEClk- DSI- D1- D2- AuxCurrent=0mA PME(D0-,D1-,D2-,D3hot-,D3cold-)
+> Status: D0 NoSoftRst+ PME-Enable- DSel=0 DScale=0 PME-
 
 ```rust
 # use pcics::capabilities::power_management_interface::*;
@@ -59,6 +61,20 @@ let sample = PowerManagementInterface {
 };
 assert_eq!(sample, result);
 ```
+
+Data register reporting 2.5 W consumed by the common logic of a multi-function device:
+
+```rust
+# use pcics::capabilities::power_management_interface::*;
+let data = [0x03, 0x00, 0x00, 0x30, 0x00, 0x19];
+let pm: PowerManagementInterface = data.as_slice().try_into().unwrap();
+let sample = PowerConsumption {
+    state: None,
+    watts: 2.5,
+    is_common_logic: true,
+};
+assert_eq!(Some(sample), pm.power_consumption());
+```
 */
 
 use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P12, P3, P4, P8};
@@ -74,6 +90,8 @@ pub struct PowerManagementInterface {
     pub data: u8,
 }
 impl PowerManagementInterface {
+    pub const SIZE: usize = 2 + 2 + 1 + 1;
+    pub const MIN_SIZE: usize = Self::SIZE;
     pub fn data(&self) -> Option<Data> {
         if self.data == 0 {
             None
@@ -85,6 +103,11 @@ impl PowerManagementInterface {
             })
         }
     }
+    /// Power consumption reported through the [Data] register, see
+    /// [Data::power_consumption]
+    pub fn power_consumption(&self) -> Option<PowerConsumption> {
+        self.data().and_then(|data| data.power_consumption())
+    }
 }
 impl TryFrom<&[u8]> for PowerManagementInterface {
     type Error = CapabilityDataError;
@@ -95,7 +118,7 @@ impl TryFrom<&[u8]> for PowerManagementInterface {
             ..
         } = P4(slice).try_into().map_err(|_| CapabilityDataError {
             name: "Power Management Interface",
-            size: 6,
+            size: Self::MIN_SIZE,
         })?;
         let Lsb((
             version,
@@ -248,8 +271,9 @@ pub struct Control {
 }
 
 /// Current power state.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum PowerState {
+    #[default]
     D0,
     D1,
     D2,
@@ -291,6 +315,43 @@ pub struct Data {
     pub select: DataSelect,
     pub scale: DataScale,
 }
+impl Data {
+    /// Value in watts, `None` if the scale is unknown or the data select value is reserved
+    pub fn watts(&self) -> Option<f32> {
+        if let DataSelect::Reserved(_) = self.select {
+            return None;
+        }
+        self.scale
+            .divisor()
+            .map(|divisor| self.value as f32 / divisor as f32)
+    }
+    /// Power consumed in a power state or by the common logic
+    ///
+    /// `None` for power dissipation values, reserved data select values and unknown scale.
+    /// Common logic power consumption is reported only by function 0 of a multi-function
+    /// device, other functions report only their own power consumption.
+    pub fn power_consumption(&self) -> Option<PowerConsumption> {
+        let (state, is_common_logic) = match self.select {
+            DataSelect::CommonLogic => (None, true),
+            select => (Some(select.consumed_power_state()?), false),
+        };
+        Some(PowerConsumption {
+            state,
+            watts: self.watts()?,
+            is_common_logic,
+        })
+    }
+}
+
+/// Power consumed by the function in a power state or by the multi-function device common logic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerConsumption {
+    /// Power state, `None` for the common logic
+    pub state: Option<PowerState>,
+    pub watts: f32,
+    /// Power consumed by the logic shared by all functions of a multi-function device
+    pub is_common_logic: bool,
+}
 
 /// Used to select which data is to be reported through the [Data] register and [DataScale].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -316,6 +377,28 @@ pub enum DataSelect {
     /// TBD
     Reserved(u8),
 }
+impl DataSelect {
+    /// Power state of the D0-D3 Power Consumed values
+    pub fn consumed_power_state(&self) -> Option<PowerState> {
+        match self {
+            Self::PowerConsumedD0 => Some(PowerState::D0),
+            Self::PowerConsumedD1 => Some(PowerState::D1),
+            Self::PowerConsumedD2 => Some(PowerState::D2),
+            Self::PowerConsumedD3 => Some(PowerState::D3Hot),
+            _ => None,
+        }
+    }
+    /// Power state of the D0-D3 Power Dissipated values
+    pub fn dissipated_power_state(&self) -> Option<PowerState> {
+        match self {
+            Self::PowerDissipatedD0 => Some(PowerState::D0),
+            Self::PowerDissipatedD1 => Some(PowerState::D1),
+            Self::PowerDissipatedD2 => Some(PowerState::D2),
+            Self::PowerDissipatedD3 => Some(PowerState::D3Hot),
+            _ => None,
+        }
+    }
+}
 impl From<u8> for DataSelect {
     fn from(byte: u8) -> Self {
         match byte {
@@ -360,6 +443,17 @@ pub enum DataScale {
     /// 0.001x
     Thousandth,
 }
+impl DataScale {
+    /// Divisor to get watts from the [Data] register value, `None` if the scale is unknown
+    pub fn divisor(&self) -> Option<u16> {
+        match self {
+            Self::Unknown => None,
+            Self::Tenth => Some(10),
+            Self::Hundredth => Some(100),
+            Self::Thousandth => Some(1000),
+        }
+    }
+}
 impl From<u8> for DataScale {
     fn from(byte: u8) -> Self {
         match byte {
@@ -419,4 +513,37 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn power_consumption() {
+        let data = |select: u8, scale: u8, value| Data {
+            value,
+            select: select.into(),
+            scale: scale.into(),
+        };
+        let sample = PowerConsumption {
+            state: Some(PowerState::D3Hot),
+            watts: 0.2,
+            is_common_logic: false,
+        };
+        assert_eq!(Some(sample), data(3, 0b10, 20).power_consumption());
+        let sample = PowerConsumption {
+            state: None,
+            watts: 0.25,
+            is_common_logic: true,
+        };
+        assert_eq!(Some(sample), data(8, 0b11, 250).power_consumption());
+        // Power dissipation
+        let result = data(5, 0b01, 15);
+        assert_eq!(
+            (None, Some(1.5)),
+            (result.power_consumption(), result.watts())
+        );
+        assert_eq!(Some(PowerState::D1), result.select.dissipated_power_state());
+        // Unknown scale
+        assert_eq!(None, data(0, 0b00, 15).power_consumption());
+        // Reserved data select
+        let result = data(9, 0b01, 15);
+        assert_eq!((None, None), (result.power_consumption(), result.watts()));
+    }
 }
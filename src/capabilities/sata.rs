@@ -39,6 +39,10 @@ pub struct Sata {
     /// BAR Location
     pub bar_location: BarLocation,
 }
+impl Sata {
+    pub const SIZE: usize = 1 + 1 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for Sata {
     type Error = CapabilityDataError;
 
@@ -48,7 +52,7 @@ impl TryFrom<&[u8]> for Sata {
             ..
         } = P3(slice).try_into().map_err(|_| CapabilityDataError {
             name: "Serial ATA",
-            size: 6,
+            size: Self::MIN_SIZE,
         })?;
         let _: u8 = rsvd;
         let Lsb((minor, major)) = P2::<u8, 4, 4>(revision).into();
@@ -37,6 +37,10 @@ pub struct SlotIdentification {
     /// Contains the physical chassis number for the slots on this bridge’s secondary interface
     pub chassis_number: u8,
 }
+impl SlotIdentification {
+    pub const SIZE: usize = 1 + 1;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl<'a> TryFrom<&'a [u8]> for SlotIdentification {
     type Error = CapabilityDataError;
 
@@ -46,7 +50,7 @@ impl<'a> TryFrom<&'a [u8]> for SlotIdentification {
             ..
         } = P2(slice).try_into().map_err(|_| CapabilityDataError {
             name: "Slot Identification",
-            size: 2,
+            size: Self::MIN_SIZE,
         })?;
         let Lsb((expansion_slots_provided, first_in_chassis, ())) =
             P3::<u8, 5, 1, 2>(expansion_slot).into();
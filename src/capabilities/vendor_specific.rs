@@ -8,6 +8,9 @@ An example vendor specific usage is a device that is configured in the final
 manufacturing steps as either a 32-bit or 64-bit PCI agent and the Vendor Specific capability
 structure tells the device driver which features the device supports.
 
+[VendorSpecific::bounded_payload] returns data clipped to the length byte and checks that the
+structure does not run into the next capability.
+
 ## Struct diagram
 <pre>
 <a href="enum.VendorSpecific.html">VendorSpecific</a>
@@ -48,9 +51,10 @@ assert_eq!(sample, result);
 use snafu::prelude::*;
 use heterob::{endianness::Le, P5};
 
-use crate::header::Header;
+use crate::{header::Header, ConfigOffset};
 
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum VendorSpecificError {
     #[snafu(display("length byte is unreadable"))]
     LengthUnreadable,
@@ -58,8 +62,27 @@ pub enum VendorSpecificError {
     Length { val: u8 },
     #[snafu(display("unable to get {size} bytes data"))]
     Data { size: usize },
-    #[snafu(display("Virtio size shold be > 12"))]
+    #[snafu(display("Virtio structure ({} bytes) is unreadable", Virtio::MIN_SIZE))]
     Virtio,
+    #[snafu(display("capability at {pointer} with length {val} crosses the structure end {end}"))]
+    Overlap {
+        pointer: ConfigOffset,
+        val: u8,
+        end: ConfigOffset,
+    },
+}
+impl VendorSpecificError {
+    /// Capability Length byte or the length it declares, including Virtio structure
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::LengthUnreadable => Some(VendorSpecific::MIN_SIZE),
+            Self::Length { .. } => None,
+            Self::Data { size } => Some(*size),
+            // Virtio structure follows the length byte
+            Self::Virtio => Some(VendorSpecific::MIN_SIZE + Virtio::MIN_SIZE),
+            Self::Overlap { .. } => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -71,7 +94,19 @@ pub enum VendorSpecific<'a> {
 }
 
 impl<'a> VendorSpecific<'a> {
+    /// Capability Length byte
+    pub const MIN_SIZE: usize = 1;
     pub fn try_new(slice: &'a [u8], header: &'a Header) -> Result<Self, VendorSpecificError> {
+        let slice = Self::payload(slice)?;
+        let result = match (header.vendor_id, header.device_id) {
+            (0x1af4, 0x1000..=0x107f) => slice.try_into().map(Self::Virtio)?,
+            _ => Self::Unspecified(slice),
+        };
+        Ok(result)
+    }
+    /// Vendor-specific data clipped to the length byte, `slice` starts after the capability
+    /// header
+    pub fn payload(slice: &'a [u8]) -> Result<&'a [u8], VendorSpecificError> {
         let size: usize = slice
             .first()
             .ok_or(VendorSpecificError::LengthUnreadable)
@@ -81,14 +116,24 @@ impl<'a> VendorSpecific<'a> {
                     .ok_or(VendorSpecificError::Length { val: *l })
             })?
             .into();
-        let slice = slice
-            .get(1..size)
-            .ok_or(VendorSpecificError::Data { size })?;
-        let result = match (header.vendor_id, header.device_id) {
-            (0x1af4, 0x1000..=0x107f) => slice.try_into().map(Self::Virtio)?,
-            _ => Self::Unspecified(slice),
-        };
-        Ok(result)
+        slice.get(1..size).ok_or(VendorSpecificError::Data { size })
+    }
+    /// [Payload](Self::payload) of the capability located at `pointer`, the structure must not
+    /// cross `end`
+    ///
+    /// `end` is usually the next structure offset, see
+    /// [Capabilities::structure_end](super::Capabilities::structure_end).
+    pub fn bounded_payload(
+        slice: &'a [u8],
+        pointer: ConfigOffset,
+        end: ConfigOffset,
+    ) -> Result<&'a [u8], VendorSpecificError> {
+        let val = *slice.first().ok_or(VendorSpecificError::LengthUnreadable)?;
+        ensure!(
+            pointer.checked_add(val.into()).is_some_and(|e| e <= end),
+            OverlapSnafu { pointer, val, end }
+        );
+        Self::payload(slice)
     }
 }
 
@@ -117,6 +162,7 @@ impl Virtio {
     /// Offset within bar: `[u32]`
     /// Length of the structure, in bytes: `[u32]`
     pub const SIZE: usize = 1 + 1 + 3 + 4 + 4; // 13 bytes
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 impl<'a> TryFrom<&'a [u8]> for Virtio {
     type Error = VendorSpecificError;
@@ -169,4 +215,35 @@ mod tests {
         };
         assert_eq!(sample, vc);
     }
+
+    #[test]
+    fn payload() {
+        // Vendor Specific at 50h with length 8, next capability at 58h
+        let data = [0x08, 0x01, 0x02, 0x03, 0x04, 0x05, 0xff, 0xff];
+        let (pointer, end) = (ConfigOffset(0x50), ConfigOffset(0x58));
+        let sample = [0x01, 0x02, 0x03, 0x04, 0x05].as_slice();
+        assert_eq!(Ok(sample), VendorSpecific::payload(&data));
+        assert_eq!(
+            Ok(sample),
+            VendorSpecific::bounded_payload(&data, pointer, end)
+        );
+
+        let end = ConfigOffset(0x54);
+        assert_eq!(
+            Err(VendorSpecificError::Overlap {
+                pointer,
+                val: 8,
+                end
+            }),
+            VendorSpecific::bounded_payload(&data, pointer, end)
+        );
+        assert_eq!(
+            Err(VendorSpecificError::Data { size: 6 }),
+            VendorSpecific::payload(&data[..4])
+        );
+        assert_eq!(
+            Err(VendorSpecificError::LengthUnreadable),
+            VendorSpecific::bounded_payload(&[], pointer, end)
+        );
+    }
 }
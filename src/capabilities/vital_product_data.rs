@@ -20,11 +20,45 @@ let sample = VitalProductData {
 };
 assert_eq!(sample, result);
 ```
+
+## VPD access
+
+VPD is accessed one DWORD at a time through the VPD Address and VPD Data registers, the F
+flag of VPD Address register signals completion. [VpdTransaction] computes the writes starting
+an access and interprets the registers read while polling:
+
+```rust
+# use pcics::capabilities::vital_product_data::*;
+# use pcics::config_write::{AccessWidth, RegisterWrite};
+# use pcics::ConfigOffset;
+// VPD Capability at 58h
+let pointer = ConfigOffset(0x58);
+let read = VpdTransaction::read(0x0100).unwrap();
+let writes = read.start(pointer).collect::<Vec<_>>();
+let sample = vec![RegisterWrite {
+    offset: ConfigOffset(0x5a),
+    width: AccessWidth::Word,
+    value: 0x0100,
+}];
+assert_eq!(sample, writes);
+
+// F flag is not set yet
+let vpd: VitalProductData = [0x00, 0x01, 0x00, 0x00, 0x00, 0x00].as_slice().try_into().unwrap();
+assert_eq!(VpdStatus::Pending, read.poll(&vpd));
+// Data is transferred
+let vpd: VitalProductData = [0x00, 0x81, 0x82, 0x10, 0x00, 0x4c].as_slice().try_into().unwrap();
+assert_eq!(VpdStatus::Read(0x4c001082), read.poll(&vpd));
+```
 */
 
 use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P2};
+use snafu::prelude::*;
 
 use super::CapabilityDataError;
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor, RegisterWrite},
+    ConfigOffset,
+};
 
 /// Vital Product Data
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -37,6 +71,17 @@ pub struct VitalProductData {
     /// VPD Data
     pub vpd_data: u32,
 }
+impl VitalProductData {
+    pub const SIZE: usize = 2 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// VPD Address register, the address and the F flag are written together
+    pub const ADDRESS_REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("VPD Address", 0x02, AccessWidth::Word)
+            .min_write(AccessWidth::Word);
+    /// VPD Data register
+    pub const DATA_REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("VPD Data", 0x04, AccessWidth::Dword).min_write(AccessWidth::Dword);
+}
 impl TryFrom<&[u8]> for VitalProductData {
     type Error = CapabilityDataError;
 
@@ -46,7 +91,7 @@ impl TryFrom<&[u8]> for VitalProductData {
             ..
         } = P2(slice).try_into().map_err(|_| CapabilityDataError {
             name: "Vital Product Data",
-            size: 6,
+            size: Self::MIN_SIZE,
         })?;
         let Lsb((vpd_address, transfer_completed)) = P2::<u16, 15, 1>(word).into();
         Ok(Self {
@@ -56,3 +101,142 @@ impl TryFrom<&[u8]> for VitalProductData {
         })
     }
 }
+
+/// VPD address is not DWORD aligned or does not fit 15 bits
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("VPD address {address:#x} is not valid"))]
+pub struct InvalidVpdAddress {
+    pub address: u16,
+}
+
+/// Single DWORD access to VPD
+///
+/// To read, software writes the address with F flag clear, the function sets the flag when VPD
+/// Data holds the value. To write, software writes VPD Data, then the address with F flag set,
+/// the function clears the flag when the value is stored. VPD Address and VPD Data must be read
+/// in this order while polling, so the data is read after the flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VpdTransaction {
+    Read { address: u16 },
+    Write { address: u16, data: u32 },
+}
+
+impl VpdTransaction {
+    /// Largest VPD address
+    pub const ADDRESS_MAX: u16 = 0x7ffc;
+    pub fn read(address: u16) -> Result<Self, InvalidVpdAddress> {
+        Self::check(address).map(|address| Self::Read { address })
+    }
+    pub fn write(address: u16, data: u32) -> Result<Self, InvalidVpdAddress> {
+        Self::check(address).map(|address| Self::Write { address, data })
+    }
+    fn check(address: u16) -> Result<u16, InvalidVpdAddress> {
+        ensure!(
+            address & 0b11 == 0 && address <= Self::ADDRESS_MAX,
+            InvalidVpdAddressSnafu { address }
+        );
+        Ok(address)
+    }
+    /// DWORD-aligned byte address of the access
+    pub fn address(&self) -> u16 {
+        match *self {
+            Self::Read { address } | Self::Write { address, .. } => address,
+        }
+    }
+    /// Writes starting the access to VPD Capability at `pointer`, in order
+    pub fn start(&self, pointer: ConfigOffset) -> impl Iterator<Item = RegisterWrite> {
+        let (data, flag) = match *self {
+            Self::Read { .. } => (None, 0),
+            Self::Write { data, .. } => (Some(data), 1 << 15),
+        };
+        let data = data.map(|value| RegisterWrite {
+            offset: pointer + VitalProductData::DATA_REGISTER.offset,
+            width: AccessWidth::Dword,
+            value,
+        });
+        let address = RegisterWrite {
+            offset: pointer + VitalProductData::ADDRESS_REGISTER.offset,
+            width: AccessWidth::Word,
+            value: (self.address() | flag) as u32,
+        };
+        data.into_iter().chain(Some(address))
+    }
+    /// Interprets VPD Capability registers read while polling
+    pub fn poll(&self, vpd: &VitalProductData) -> VpdStatus {
+        if vpd.vpd_address != self.address() {
+            return VpdStatus::AddressMismatch {
+                address: vpd.vpd_address,
+            };
+        }
+        match self {
+            Self::Read { .. } if vpd.transfer_completed => VpdStatus::Read(vpd.vpd_data),
+            Self::Write { .. } if !vpd.transfer_completed => VpdStatus::Written,
+            _ => VpdStatus::Pending,
+        }
+    }
+}
+
+/// State of a [VpdTransaction]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VpdStatus {
+    /// F flag is not changed yet
+    Pending,
+    /// Read is completed with the data
+    Read(u32),
+    /// Write is completed
+    Written,
+    /// VPD Address register holds other address, VPD is accessed by someone else
+    AddressMismatch { address: u16 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    fn vpd(word: u16, vpd_data: u32) -> VitalProductData {
+        let mut data = [0; VitalProductData::SIZE];
+        data[..2].copy_from_slice(&word.to_le_bytes());
+        data[2..].copy_from_slice(&vpd_data.to_le_bytes());
+        data.as_slice().try_into().unwrap()
+    }
+
+    #[test]
+    fn write_transaction() {
+        let write = VpdTransaction::write(0x0ffc, 0xdeadbeef).unwrap();
+        let result = write.start(ConfigOffset(0x40)).collect::<Vec<_>>();
+        let sample = vec![
+            RegisterWrite {
+                offset: ConfigOffset(0x44),
+                width: AccessWidth::Dword,
+                value: 0xdeadbeef,
+            },
+            RegisterWrite {
+                offset: ConfigOffset(0x42),
+                width: AccessWidth::Word,
+                value: 0x8ffc,
+            },
+        ];
+        assert_eq!(sample, result);
+        assert_eq!(VpdStatus::Pending, write.poll(&vpd(0x8ffc, 0xdeadbeef)));
+        assert_eq!(VpdStatus::Written, write.poll(&vpd(0x0ffc, 0xdeadbeef)));
+        assert_eq!(
+            VpdStatus::AddressMismatch { address: 0x0100 },
+            write.poll(&vpd(0x0100, 0))
+        );
+    }
+
+    #[test]
+    fn invalid_address() {
+        assert_eq!(
+            Err(InvalidVpdAddress { address: 0x0102 }),
+            VpdTransaction::read(0x0102)
+        );
+        assert_eq!(
+            Err(InvalidVpdAddress { address: 0x8000 }),
+            VpdTransaction::write(0x8000, 0)
+        );
+        assert!(VpdTransaction::read(VpdTransaction::ADDRESS_MAX).is_ok());
+    }
+}
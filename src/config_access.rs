@@ -0,0 +1,418 @@
+/*!
+## Configuration space access
+
+[ConfigAccess] abstracts the way configuration space registers are read, so functions behind
+ECAM MMIO, I/O ports (`CF8h`/`CFCh`) or any other mechanism can be parsed. Byte slices
+implement [ConfigAccess] and remain the default way to parse configuration space.
+
+There are two frontends for other mechanisms:
+- [ConfigReader] reads the predefined header, walks the capabilities lists by their headers and
+  reads only the registers of listed structures, one structure at a time. Decoded structures
+  borrow a small window and are passed to a closure.
+- [ConfigurationSpace::read](crate::ConfigurationSpace::read) copies the present regions into
+  a caller-provided 4 KiB buffer and runs the slice parsers on the copy: extended
+  configuration space is skipped, and not read at all, if the function does not implement it.
+
+## Examples
+
+Legacy I/O port mechanism provides only the first 256 bytes with dword reads:
+
+```rust
+# use pcics::{ConfigAccess, ConfigurationSpace};
+struct PortIo<'a>(&'a [u8]);
+
+impl ConfigAccess for PortIo<'_> {
+    fn read_u8(&self, offset: u16) -> Option<u8> {
+        let dword = self.read_u32(offset & !3)?;
+        Some(dword.to_le_bytes()[offset as usize & 3])
+    }
+    fn read_u32(&self, offset: u16) -> Option<u32> {
+        // outl(0xcf8, address); inl(0xcfc)
+        let data = self.0.get(offset as usize..offset as usize + 4)?;
+        (offset < 0x100).then(|| u32::from_le_bytes(data.try_into().unwrap()))
+    }
+}
+
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let mut buffer = [0; ConfigurationSpace::PCIE_SIZE];
+let cs = ConfigurationSpace::read(&PortIo(data), &mut buffer).unwrap();
+assert_eq!(0x2030, cs.header.device_id);
+assert_eq!(ConfigurationSpace::PCI_SIZE, cs.data().len());
+assert_eq!(4, cs.capabilities().count());
+```
+
+[ConfigReader] needs no configuration space buffer, extended capabilities larger than the
+window are decoded from truncated data:
+
+```rust
+# use pcics::{config_access::ConfigReader, ConfigAccess};
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let reader = ConfigReader::new(data.as_slice()).unwrap();
+assert_eq!(0x2030, reader.header().device_id);
+let mut ids = vec![];
+reader.for_each_capability(|cap| ids.push(cap.unwrap().kind.kind_id() as u16));
+let mut window = [0; 256];
+let present = reader.for_each_extended_capability(&mut window, |ecap| {
+    ids.push(ecap.unwrap().id());
+});
+assert!(present);
+assert_eq!(
+    vec![0x0d, 0x05, 0x10, 0x01, 0x000b, 0x000d, 0x0001, 0x000b, 0x0019, 0x000b, 0x000b, 0x000b],
+    ids
+);
+```
+*/
+
+use core::array::TryFromSliceError;
+
+use crate::{
+    capabilities::{parse_cap_at, Capability, CapabilityError},
+    extended_capabilities::{
+        parse_ecap_at, ExtendedCapability, ExtendedCapabilityError, ExtendedCapabilityHeader,
+    },
+    Capabilities, ConfigOffset, ExtendedCapabilities, Header, DDR_LENGTH, DDR_OFFSET, ECS_OFFSET,
+    EXTENDED_CONFIG_SPACE_SIZE,
+};
+
+/// Configuration space registers reader
+///
+/// Only [read_u8](ConfigAccess::read_u8) is required, wider reads are composed of it by
+/// default. Mechanisms with native 16/32-bit accesses should override them.
+pub trait ConfigAccess {
+    /// Byte at `offset`, `None` if `offset` is out of accessible configuration space
+    fn read_u8(&self, offset: u16) -> Option<u8>;
+    /// Little-endian word at `offset`
+    fn read_u16(&self, offset: u16) -> Option<u16> {
+        let lo = self.read_u8(offset)?;
+        let hi = self.read_u8(offset.checked_add(1)?)?;
+        Some(u16::from_le_bytes([lo, hi]))
+    }
+    /// Little-endian dword at `offset`
+    fn read_u32(&self, offset: u16) -> Option<u32> {
+        let lo = self.read_u16(offset)?;
+        let hi = self.read_u16(offset.checked_add(2)?)?;
+        Some(lo as u32 | (hi as u32) << 16)
+    }
+    /// Fills `buffer` with registers starting from dword aligned `offset` using dword reads,
+    /// returns number of bytes read
+    fn read_into(&self, offset: u16, buffer: &mut [u8]) -> usize {
+        let mut len = 0;
+        for chunk in buffer.chunks_mut(4) {
+            let dword = (offset as usize + len)
+                .try_into()
+                .ok()
+                .and_then(|offset| self.read_u32(offset));
+            match dword {
+                Some(dword) if chunk.len() == 4 => chunk.copy_from_slice(&dword.to_le_bytes()),
+                _ => break,
+            }
+            len += 4;
+        }
+        len
+    }
+}
+
+impl ConfigAccess for [u8] {
+    fn read_u8(&self, offset: u16) -> Option<u8> {
+        self.get(offset as usize).copied()
+    }
+}
+
+impl<T: ConfigAccess + ?Sized> ConfigAccess for &T {
+    fn read_u8(&self, offset: u16) -> Option<u8> {
+        (**self).read_u8(offset)
+    }
+    fn read_u16(&self, offset: u16) -> Option<u16> {
+        (**self).read_u16(offset)
+    }
+    fn read_u32(&self, offset: u16) -> Option<u32> {
+        (**self).read_u32(offset)
+    }
+}
+
+/// Parsers reading registers through [ConfigAccess] on demand, see [module](self)
+/// documentation
+///
+/// The header is read once. Capabilities lists are walked by their headers first, then every
+/// listed structure is read from its offset up to the next listed structure in address order
+/// (as bounded by [Capabilities::structure_end] and [ExtendedCapabilities::structure_end]) and
+/// decoded from that window. Registers outside of the header and listed structures are never
+/// read. Lists are limited, reported and ended like [Capabilities] and [ExtendedCapabilities].
+#[derive(Debug, Clone)]
+pub struct ConfigReader<'a, A: ?Sized> {
+    access: &'a A,
+    header: Header,
+}
+
+impl<'a, A: ConfigAccess + ?Sized> ConfigReader<'a, A> {
+    /// Reads and parses the predefined header
+    pub fn new(access: &'a A) -> Result<Self, TryFromSliceError> {
+        let mut buffer = [0; DDR_OFFSET];
+        let len = access.read_into(0, &mut buffer);
+        let header = buffer.get(..len).unwrap_or_default().try_into()?;
+        Ok(Self { access, header })
+    }
+    /// Predefined header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+    /// Configuration space access the reader was created with
+    pub fn access(&self) -> &'a A {
+        self.access
+    }
+    /// Calls `f` with every capability of *Capabilities List*
+    pub fn for_each_capability<F>(&self, mut f: F)
+    where
+        F: FnMut(Result<Capability<'_>, CapabilityError>),
+    {
+        const MAX_ENTRIES: usize = Capabilities::MAX_ENTRIES;
+        let mut offsets = [0u16; MAX_ENTRIES];
+        let mut count = 0;
+        let mut pointer = self.header.capabilities_pointer;
+        for slot in offsets.iter_mut() {
+            if ConfigOffset::from(pointer).ddr_relative().is_none() {
+                break;
+            }
+            *slot = pointer.into();
+            count += 1;
+            match self.access.read_u8(u16::from(pointer) + 1) {
+                Some(next) => pointer = next,
+                None => break,
+            }
+        }
+        let offsets = offsets.get(..count).unwrap_or_default();
+
+        let mut window = [0; DDR_LENGTH];
+        let mut pointer = self.header.capabilities_pointer;
+        let mut remaining = MAX_ENTRIES;
+        while pointer != 0 {
+            if remaining == 0 {
+                let ptr = ConfigOffset::from(pointer);
+                f(Err(CapabilityError::Limit {
+                    ptr,
+                    limit: MAX_ENTRIES,
+                }));
+                break;
+            }
+            remaining -= 1;
+            if ConfigOffset::from(pointer).ddr_relative().is_none() {
+                f(Err(CapabilityError::Pointer));
+                break;
+            }
+            let start = u16::from(pointer);
+            let end = structure_end(offsets, start, ECS_OFFSET as u16);
+            let bytes = self.read_window(start, end, &mut window);
+            f(parse_cap_at(bytes, &mut pointer, &self.header));
+        }
+    }
+    /// Calls `f` with every extended capability of *Extended Capabilities List*, returns
+    /// `false` if extended configuration space is not implemented
+    ///
+    /// Extended configuration space is implemented if its first DWORD is accessible and is
+    /// neither 0 nor all ones. Extended capabilities are read into `window`, the part of a
+    /// structure beyond the window length is not read.
+    pub fn for_each_extended_capability<F>(&self, window: &mut [u8], mut f: F) -> bool
+    where
+        F: FnMut(Result<ExtendedCapability<'_>, ExtendedCapabilityError>),
+    {
+        const MAX_ENTRIES: usize = ExtendedCapabilities::MAX_ENTRIES;
+        let first = ConfigOffset::ECS.get();
+        if !matches!(self.access.read_u32(first), Some(dword) if dword != 0 && dword != u32::MAX) {
+            return false;
+        }
+        let mut offsets = [0u16; MAX_ENTRIES];
+        let mut count = 0;
+        let mut offset = first;
+        for slot in offsets.iter_mut() {
+            if ConfigOffset(offset).ecs_relative().is_none() {
+                break;
+            }
+            match self.access.read_u32(offset).filter(|&dword| dword != 0) {
+                Some(dword) => {
+                    *slot = offset;
+                    count += 1;
+                    offset = ExtendedCapabilityHeader::from(dword).next_capability_offset;
+                }
+                None => break,
+            }
+        }
+        let offsets = offsets.get(..count).unwrap_or_default();
+
+        let mut next = first;
+        let mut last = 0;
+        let mut remaining = MAX_ENTRIES;
+        while next != 0 {
+            if ConfigOffset(next).ecs_relative().is_none() {
+                f(Err(ExtendedCapabilityError::Offset {
+                    offset: ConfigOffset(last),
+                    next,
+                }));
+                break;
+            }
+            if remaining == 0 {
+                f(Err(ExtendedCapabilityError::Limit {
+                    offset: ConfigOffset(next),
+                    limit: MAX_ENTRIES,
+                }));
+                break;
+            }
+            remaining -= 1;
+            last = next;
+            let end = structure_end(offsets, next, EXTENDED_CONFIG_SPACE_SIZE as u16);
+            let bytes = self.read_window(next, end, window);
+            match parse_ecap_at(bytes, &mut next) {
+                Err(ExtendedCapabilityError::EmptyHeader { .. }) => break,
+                result => f(result),
+            }
+        }
+        true
+    }
+    /// Reads registers from `start` to `end` with DWORD accesses into `window`, returns bytes
+    /// starting at `start`
+    fn read_window<'w>(&self, start: u16, end: u16, window: &'w mut [u8]) -> &'w [u8] {
+        let aligned = start & !3;
+        let len = usize::from(end.saturating_add(3) & !3)
+            .saturating_sub(aligned.into())
+            .min(window.len());
+        let read = match window.get_mut(..len) {
+            Some(buffer) => self.access.read_into(aligned, buffer),
+            None => 0,
+        };
+        let end = read.min(usize::from(end.saturating_sub(aligned)));
+        window
+            .get(usize::from(start - aligned)..end)
+            .unwrap_or_default()
+    }
+}
+
+/// Offset of the first listed structure after `offset`, or `region_end`
+fn structure_end(offsets: &[u16], offset: u16, region_end: u16) -> u16 {
+    offsets
+        .iter()
+        .copied()
+        .filter(|&next| next > offset)
+        .min()
+        .unwrap_or(region_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigurationSpace;
+    use core::cell::RefCell;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn slice() {
+        let data = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+        assert_eq!(Some(0x11), data.read_u8(1));
+        assert_eq!(Some(0x2211), data.read_u16(1));
+        assert_eq!(Some(0x55443322), data.read_u32(2));
+        assert_eq!(None, data.read_u32(3));
+
+        let mut buffer = [0; 8];
+        assert_eq!(4, data.as_slice().read_into(0, &mut buffer));
+        assert_eq!([0x00, 0x11, 0x22, 0x33, 0, 0, 0, 0], buffer);
+    }
+
+    /// Slice access recording which bytes were read
+    struct Recorder<'a> {
+        data: &'a [u8],
+        read: RefCell<Vec<bool>>,
+    }
+
+    impl<'a> Recorder<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            let read = RefCell::new(vec![false; data.len()]);
+            Self { data, read }
+        }
+    }
+
+    impl ConfigAccess for Recorder<'_> {
+        fn read_u8(&self, offset: u16) -> Option<u8> {
+            let value = self.data.read_u8(offset)?;
+            self.read.borrow_mut()[offset as usize] = true;
+            Some(value)
+        }
+    }
+
+    #[test]
+    fn config_reader() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let access = Recorder::new(data);
+        let reader = ConfigReader::new(&access).unwrap();
+        assert_eq!(&cs.header, reader.header());
+
+        let mut result = vec![];
+        reader.for_each_capability(|cap| result.push(format!("{:?}", cap)));
+        let sample = cs
+            .capabilities()
+            .map(|cap| format!("{:?}", cap))
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+
+        // Some structures keep the data up to the region end, only the window is read here
+        let key = |ecap: Result<ExtendedCapability, _>| {
+            ecap.map(|ecap| (ecap.offset, ecap.id(), ecap.version))
+        };
+        let mut result = vec![];
+        let mut window = [0; ECS_OFFSET];
+        assert!(reader.for_each_extended_capability(&mut window, |ecap| result.push(key(ecap))));
+        let sample = cs
+            .extended_capabilities()
+            .unwrap()
+            .map(key)
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+
+        // Registers after the last extended capability at 300h are not read
+        let read = access.read.borrow();
+        assert!(read[..0x40].iter().all(|&b| b));
+        assert!(read[0x300..0x310].iter().all(|&b| b));
+        assert!(!read[0x400..].iter().any(|&b| b));
+    }
+
+    #[test]
+    fn config_reader_limits() {
+        // Conventional function: extended configuration space reads as all ones
+        let mut data = crate::synthetic::endpoint_with_msix(1).to_vec();
+        data[ECS_OFFSET..].fill(0xff);
+        let reader = ConfigReader::new(data.as_slice()).unwrap();
+        let mut window = [0; 64];
+        assert!(!reader.for_each_extended_capability(&mut window, |_| unreachable!()));
+
+        // Looped list ends with Limit error
+        let mut data = crate::synthetic::endpoint_with_msix(1).to_vec();
+        data[crate::synthetic::INTERRUPT_PTR as usize + 1] = crate::synthetic::PCIE_PTR;
+        let reader = ConfigReader::new(data.as_slice()).unwrap();
+        let mut result = vec![];
+        reader.for_each_capability(|cap| result.push(cap.map(|cap| cap.pointer)));
+        assert_eq!(Capabilities::MAX_ENTRIES + 1, result.len());
+        assert!(matches!(
+            result.last(),
+            Some(Err(CapabilityError::Limit { .. }))
+        ));
+
+        // VSEC longer than the window is truncated
+        let mut data = [0; EXTENDED_CONFIG_SPACE_SIZE];
+        data[0x100..0x108].copy_from_slice(&[0x0b, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x10]);
+        let reader = ConfigReader::new(data.as_slice()).unwrap();
+        let mut result = vec![];
+        reader.for_each_extended_capability(&mut window, |ecap| {
+            result.push(format!("{:?}", ecap.unwrap().kind))
+        });
+        assert_eq!(1, result.len());
+        assert!(result[0].contains("Incomplete"), "{}", result[0]);
+    }
+}
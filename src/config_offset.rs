@@ -0,0 +1,136 @@
+/*!
+## Configuration space offsets
+
+Capabilities and extended capabilities are linked through offsets relative to the start of
+the function configuration space. Parsers in this crate, on the other hand, work on
+sub-slices: device dependent region starts at [DDR_OFFSET](crate::DDR_OFFSET) and extended
+configuration space starts at [ECS_OFFSET](crate::ECS_OFFSET). [ConfigOffset] is always an
+absolute offset, conversion to region relative index is explicit.
+
+## Examples
+
+```rust
+# use pcics::ConfigOffset;
+let offset = ConfigOffset::from(0x148u16);
+assert_eq!(Some(0x48), offset.ecs_relative());
+assert_eq!(None, offset.ddr_relative());
+assert_eq!(ConfigOffset(0x14c), offset + 4);
+assert_eq!("148", format!("{}", offset));
+```
+*/
+
+use core::{fmt, ops::Add};
+
+use crate::{DDR_OFFSET, ECS_OFFSET};
+
+/// Absolute offset in function configuration space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConfigOffset(pub u16);
+
+impl ConfigOffset {
+    /// Device dependent region start
+    pub const DDR: Self = Self(DDR_OFFSET as u16);
+    /// Extended configuration space start
+    pub const ECS: Self = Self(ECS_OFFSET as u16);
+
+    pub const fn new(offset: u16) -> Self {
+        Self(offset)
+    }
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+    /// Offset is located in extended configuration space
+    pub const fn is_extended(self) -> bool {
+        self.0 as usize >= ECS_OFFSET
+    }
+    /// Index in device dependent region slice (i.e. `data[DDR_OFFSET..ECS_OFFSET]`)
+    pub fn ddr_relative(self) -> Option<usize> {
+        (self.0 as usize)
+            .checked_sub(DDR_OFFSET)
+            .filter(|_| !self.is_extended())
+    }
+    /// Index in extended configuration space slice (i.e. `data[ECS_OFFSET..]`)
+    pub fn ecs_relative(self) -> Option<usize> {
+        (self.0 as usize).checked_sub(ECS_OFFSET)
+    }
+    pub fn checked_add(self, rhs: u16) -> Option<Self> {
+        self.0.checked_add(rhs).map(Self)
+    }
+}
+
+/// Saturates at FFFFh, which is out of any configuration space, use
+/// [checked_add](ConfigOffset::checked_add) to detect the overflow
+impl Add<u16> for ConfigOffset {
+    type Output = Self;
+
+    fn add(self, rhs: u16) -> Self::Output {
+        Self(self.0.saturating_add(rhs))
+    }
+}
+
+impl From<u8> for ConfigOffset {
+    fn from(offset: u8) -> Self {
+        Self(offset as u16)
+    }
+}
+impl From<u16> for ConfigOffset {
+    fn from(offset: u16) -> Self {
+        Self(offset)
+    }
+}
+impl From<ConfigOffset> for u16 {
+    fn from(offset: ConfigOffset) -> Self {
+        offset.0
+    }
+}
+impl From<ConfigOffset> for usize {
+    fn from(offset: ConfigOffset) -> Self {
+        offset.0 as usize
+    }
+}
+
+impl fmt::Display for ConfigOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+impl fmt::LowerHex for ConfigOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+impl fmt::UpperHex for ConfigOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn relative() {
+        assert_eq!(Some(0x10), ConfigOffset(0x50).ddr_relative());
+        assert_eq!(None, ConfigOffset(0x50).ecs_relative());
+        assert_eq!(None, ConfigOffset(0x30).ddr_relative());
+        assert_eq!(Some(0), ConfigOffset::ECS.ecs_relative());
+        assert_eq!(None, ConfigOffset::ECS.ddr_relative());
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(ConfigOffset(0x104), ConfigOffset::ECS + 4);
+        assert_eq!(ConfigOffset(u16::MAX), ConfigOffset(0xfffe) + 4);
+        assert_eq!(None, ConfigOffset(0xfffe).checked_add(4));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("50", ConfigOffset(0x50).to_string());
+        assert_eq!("1d0", ConfigOffset(0x1d0).to_string());
+        assert_eq!("0x01D0", format!("{:#06X}", ConfigOffset(0x1d0)));
+    }
+}
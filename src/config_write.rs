@@ -0,0 +1,494 @@
+/*!
+## Configuration space writes
+
+Configuration writes change whole bytes, words or dwords, but not every register tolerates
+every width. Some registers must be written as a whole, e.g. each write to Slot Control
+register is a Hot-Plug command, so a byte write would issue a command with the other byte
+undefined. Status registers hold RW1C bits, writing back the value just read clears every
+pending status.
+
+[RegisterDescriptor] holds the location of a register, the narrowest write it accepts, its
+read-only and RW1C bits. [RegisterDescriptor::write] computes the minimal naturally aligned write changing
+the register from the current value to the desired one. Descriptors are associated constants
+of the register types, e.g.
+[SlotControl::REGISTER](crate::capabilities::pci_express::SlotControl::REGISTER).
+
+Configuration space images are edited field by field: [FieldRef] locates a bit field of a
+register and tells how software may access it, [patch_field] replaces the field value in a raw
+buffer. Fields covering read-only or RW1C bits of the register are refused, [patch_field_forced] writes them anyway, e.g.
+to forge an image for tests.
+
+## Examples
+
+```rust
+# use pcics::{capabilities::pci_express::*, config_write::*, ConfigOffset};
+// PCI Express Capability at 40h
+let base = ConfigOffset(0x40);
+// Link Disable is in the low byte of Link Control
+let write = LinkControl::REGISTER.write(base, 0x0040, 0x0050).unwrap();
+let sample = RegisterWrite {
+    offset: ConfigOffset(0x50),
+    width: AccessWidth::Byte,
+    value: 0x50,
+};
+assert_eq!(sample, write);
+
+// Power Indicator Control is in the low byte of Slot Control, but the register is written
+// as a whole
+let write = SlotControl::REGISTER.write(base, 0x03c0, 0x0340).unwrap();
+let sample = RegisterWrite {
+    offset: ConfigOffset(0x58),
+    width: AccessWidth::Word,
+    value: 0x0340,
+};
+assert_eq!(sample, write);
+
+// Link Bandwidth Management Status is cleared, Link Autonomous Bandwidth Status is kept
+let write = LinkStatus::REGISTER.write(base, 0xc011, 0x8011).unwrap();
+let sample = RegisterWrite {
+    offset: ConfigOffset(0x53),
+    width: AccessWidth::Byte,
+    value: 0x40,
+};
+assert_eq!(sample, write);
+
+// Patch ASPM Control of Link Control in an image
+let mut data = [0u8; 0x60];
+let aspm_control = FieldRef::new(base, LinkControl::REGISTER, 0, 2);
+patch_field(&mut data, aspm_control, 0b10).unwrap();
+assert_eq!(0x02, data[0x50]);
+// Link Bandwidth Management Status is RW1C
+let lbms = FieldRef::new(base, LinkStatus::REGISTER, 14, 1);
+assert_eq!(FieldAccess::Rw1c, lbms.access);
+assert_eq!(
+    Err(PatchFieldError::Rw1c { name: "Link Status" }),
+    patch_field(&mut data, lbms, 1)
+);
+// Current Link Speed is read-only
+let speed = FieldRef::new(base, LinkStatus::REGISTER, 0, 4);
+assert_eq!(FieldAccess::ReadOnly, speed.access);
+assert!(patch_field(&mut data, speed, 3).is_err());
+patch_field_forced(&mut data, speed, 3).unwrap();
+assert_eq!(0x03, data[0x52]);
+```
+*/
+
+use snafu::prelude::*;
+
+use crate::ConfigOffset;
+
+/// Width of a configuration space access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccessWidth {
+    Byte,
+    Word,
+    Dword,
+}
+
+impl AccessWidth {
+    /// Access size in bytes
+    pub const fn bytes(self) -> usize {
+        match self {
+            Self::Byte => 1,
+            Self::Word => 2,
+            Self::Dword => 4,
+        }
+    }
+    const fn mask(self) -> u32 {
+        match self {
+            Self::Byte => 0xff,
+            Self::Word => 0xffff,
+            Self::Dword => 0xffff_ffff,
+        }
+    }
+    const fn wider(self) -> Option<Self> {
+        match self {
+            Self::Byte => Some(Self::Word),
+            Self::Word => Some(Self::Dword),
+            Self::Dword => None,
+        }
+    }
+}
+
+/// Location and access rules of a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterDescriptor {
+    /// Register name as in the specification
+    pub name: &'static str,
+    /// Offset from the start of the header or the capability structure
+    pub offset: u16,
+    /// Register size
+    pub width: AccessWidth,
+    /// Narrowest write the register accepts
+    pub min_write: AccessWidth,
+    /// Bits not changed by writes, including hardware initialized ones
+    pub read_only: u32,
+    /// Bits cleared by writing 1
+    pub rw1c: u32,
+}
+
+impl RegisterDescriptor {
+    /// Register accepting writes of any width without read-only and RW1C bits
+    pub const fn new(name: &'static str, offset: u16, width: AccessWidth) -> Self {
+        Self {
+            name,
+            offset,
+            width,
+            min_write: AccessWidth::Byte,
+            read_only: 0,
+            rw1c: 0,
+        }
+    }
+    pub const fn min_write(self, min_write: AccessWidth) -> Self {
+        Self { min_write, ..self }
+    }
+    pub const fn read_only(self, read_only: u32) -> Self {
+        Self { read_only, ..self }
+    }
+    pub const fn rw1c(self, rw1c: u32) -> Self {
+        Self { rw1c, ..self }
+    }
+    /// Minimal write changing the register of the structure at `base` from `current` to
+    /// `desired` value, `None` if there is nothing to change or the register offset overflows
+    ///
+    /// RW1C bits are set in the written value only where a set bit should become clear, so
+    /// other pending status bits are not lost. Desired RW1C bits that are clear now and
+    /// desired read-only bits are ignored.
+    pub fn write(&self, base: ConfigOffset, current: u32, desired: u32) -> Option<RegisterWrite> {
+        let mask = self.width.mask();
+        let current = current & mask;
+        let desired = desired & mask & !self.read_only | current & self.read_only;
+        // RW1C bits can not be set by software
+        let changed = (current ^ desired) & !(self.rw1c & desired);
+        if changed == 0 {
+            return None;
+        }
+        let value = desired & !self.rw1c | current & !desired & self.rw1c;
+        let first = changed.trailing_zeros() / 8;
+        let last = (u32::BITS - 1 - changed.leading_zeros()) / 8;
+        // Naturally aligned access covering all changed bytes
+        let mut width = self.min_write;
+        let start = loop {
+            let start = first & !(width.bytes() as u32 - 1);
+            if last < start + width.bytes() as u32 {
+                break start;
+            }
+            width = width.wider()?;
+        };
+        Some(RegisterWrite {
+            offset: base.checked_add(self.offset)?.checked_add(start as u16)?,
+            width,
+            value: value >> (start * 8) & width.mask(),
+        })
+    }
+}
+
+/// Single configuration space write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterWrite {
+    /// Absolute offset, aligned to the access width
+    pub offset: ConfigOffset,
+    pub width: AccessWidth,
+    pub value: u32,
+}
+
+/// Software access to a register field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldAccess {
+    ReadOnly,
+    ReadWrite,
+    /// Cleared by writing 1
+    Rw1c,
+}
+
+/// Bit field of a register of the structure at `base`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldRef {
+    /// Absolute offset of the header or the capability structure
+    pub base: ConfigOffset,
+    pub register: RegisterDescriptor,
+    /// Least significant bit of the field
+    pub shift: u8,
+    /// Field size in bits
+    pub width: u8,
+    pub access: FieldAccess,
+}
+
+impl FieldRef {
+    /// Field of `width` bits starting at bit `shift`
+    ///
+    /// Access is RW1C if any field bit is a RW1C bit of the register, read-only if any field bit
+    /// is a read-only bit, read-write otherwise.
+    pub const fn new(
+        base: ConfigOffset,
+        register: RegisterDescriptor,
+        shift: u8,
+        width: u8,
+    ) -> Self {
+        let field = Self {
+            base,
+            register,
+            shift,
+            width,
+            access: FieldAccess::ReadWrite,
+        };
+        if field.mask() & register.rw1c != 0 {
+            field.access(FieldAccess::Rw1c)
+        } else if field.mask() & register.read_only != 0 {
+            field.access(FieldAccess::ReadOnly)
+        } else {
+            field
+        }
+    }
+    pub const fn access(self, access: FieldAccess) -> Self {
+        Self { access, ..self }
+    }
+    /// Field bits in the register, 0 if the field is empty or does not fit in the register
+    pub const fn mask(&self) -> u32 {
+        let (shift, width) = (self.shift as usize, self.width as usize);
+        if width == 0 || shift + width > self.register.width.bytes() * 8 {
+            0
+        } else {
+            u32::MAX >> (32 - width) << shift
+        }
+    }
+}
+
+/// Field can not be patched
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchFieldError {
+    #[snafu(display("{name}: {width} bits at bit {shift} do not fit in the register"))]
+    InvalidBits {
+        name: &'static str,
+        shift: u8,
+        width: u8,
+    },
+    #[snafu(display("{name}: value {value:#x} does not fit in {width} bits"))]
+    ValueTooWide {
+        name: &'static str,
+        value: u64,
+        width: u8,
+    },
+    #[snafu(display("{name}: offset {offset:#x} is out of {len} bytes buffer"))]
+    OutOfBounds {
+        name: &'static str,
+        offset: usize,
+        len: usize,
+    },
+    #[snafu(display("{name}: field is read-only"))]
+    ReadOnly { name: &'static str },
+    #[snafu(display("{name}: field is RW1C"))]
+    Rw1c { name: &'static str },
+}
+
+/// Replaces the field value in the configuration space image `buffer`, other bits of the
+/// register are kept
+///
+/// Read-only and RW1C fields are refused.
+pub fn patch_field(buffer: &mut [u8], field: FieldRef, value: u64) -> Result<(), PatchFieldError> {
+    patch(buffer, field, value, false)
+}
+
+/// Replaces the field value in the configuration space image `buffer` regardless of the field
+/// access
+pub fn patch_field_forced(
+    buffer: &mut [u8],
+    field: FieldRef,
+    value: u64,
+) -> Result<(), PatchFieldError> {
+    patch(buffer, field, value, true)
+}
+
+fn patch(
+    buffer: &mut [u8],
+    field: FieldRef,
+    value: u64,
+    force: bool,
+) -> Result<(), PatchFieldError> {
+    let FieldRef {
+        base,
+        register,
+        shift,
+        width,
+        access,
+    } = field;
+    let name = register.name;
+    let mask = field.mask();
+    ensure!(mask != 0, InvalidBitsSnafu { name, shift, width });
+    ensure!(
+        value <= (mask >> shift) as u64,
+        ValueTooWideSnafu { name, value, width }
+    );
+    if !force {
+        match access {
+            FieldAccess::ReadOnly => return ReadOnlySnafu { name }.fail(),
+            FieldAccess::Rw1c => return Rw1cSnafu { name }.fail(),
+            FieldAccess::ReadWrite => (),
+        }
+    }
+    let offset = base.get() as usize + register.offset as usize;
+    let len = buffer.len();
+    let bytes = buffer
+        .get_mut(offset..offset + register.width.bytes())
+        .context(OutOfBoundsSnafu { name, offset, len })?;
+    let current = bytes.iter().rev().fold(0u32, |acc, &b| acc << 8 | b as u32);
+    let patched = current & !mask | (value as u32) << shift;
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (patched >> (i * 8)) as u8;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const REG: RegisterDescriptor = RegisterDescriptor::new("Test", 0x10, AccessWidth::Dword);
+
+    fn write(offset: u16, width: AccessWidth, value: u32) -> Option<RegisterWrite> {
+        Some(RegisterWrite {
+            offset: ConfigOffset(offset),
+            width,
+            value,
+        })
+    }
+
+    #[test]
+    fn minimal_write() {
+        let base = ConfigOffset(0x100);
+        assert_eq!(None, REG.write(base, 0x1234_5678, 0x1234_5678));
+        assert_eq!(
+            write(0x112, AccessWidth::Byte, 0xaa),
+            REG.write(base, 0x1234_5678, 0x12aa_5678)
+        );
+        // Bytes 1 and 2 are not a naturally aligned word
+        assert_eq!(
+            write(0x110, AccessWidth::Dword, 0x12aa_bb78),
+            REG.write(base, 0x1234_5678, 0x12aa_bb78)
+        );
+        assert_eq!(
+            write(0x112, AccessWidth::Word, 0xaabb),
+            REG.write(base, 0x1234_5678, 0xaabb_5678)
+        );
+        // Bits above the register width are ignored
+        let reg = RegisterDescriptor::new("Word", 0x02, AccessWidth::Word);
+        assert_eq!(None, reg.write(base, 0x1_0000, 0));
+        let reg = reg.min_write(AccessWidth::Word);
+        assert_eq!(
+            write(0x102, AccessWidth::Word, 0x0101),
+            reg.write(base, 0x0001, 0x0101)
+        );
+    }
+
+    #[test]
+    fn rw1c() {
+        let reg = REG.rw1c(0xffff_0000);
+        // Status bits are kept while control bits are written
+        assert_eq!(
+            write(0x10, AccessWidth::Byte, 0x01),
+            reg.write(ConfigOffset(0), 0x0003_0000, 0x0003_0001)
+        );
+        // Only the bit to become clear is written as 1
+        assert_eq!(
+            write(0x12, AccessWidth::Byte, 0x02),
+            reg.write(ConfigOffset(0), 0x0003_0000, 0x0001_0000)
+        );
+        // Clear bit can not be set
+        assert_eq!(None, reg.write(ConfigOffset(0), 0x0001_0000, 0x0003_0000));
+    }
+
+    #[test]
+    fn offset_overflow() {
+        assert_eq!(None, REG.write(ConfigOffset(0xfff0), 0, 1));
+    }
+
+    #[test]
+    fn read_only() {
+        let reg = REG.read_only(0x0000_ff00).rw1c(0xff00_0000);
+        // Read-only bits are never written
+        assert_eq!(None, reg.write(ConfigOffset(0), 0x0000_1200, 0x0000_3400));
+        assert_eq!(
+            write(0x10, AccessWidth::Byte, 0x56),
+            reg.write(ConfigOffset(0), 0x0000_1200, 0x0000_3456)
+        );
+        let access = [(0, 8), (4, 8), (8, 8), (20, 8)]
+            .map(|(shift, width)| FieldRef::new(ConfigOffset(0), reg, shift, width).access);
+        let sample = [
+            FieldAccess::ReadWrite,
+            FieldAccess::ReadOnly,
+            FieldAccess::ReadOnly,
+            FieldAccess::Rw1c,
+        ];
+        assert_eq!(sample, access);
+        let mut data = [0u8; 0x14];
+        let field = FieldRef::new(ConfigOffset(0), reg, 8, 4);
+        assert_eq!(
+            Err(PatchFieldError::ReadOnly { name: "Test" }),
+            patch_field(&mut data, field, 1)
+        );
+    }
+    #[test]
+    fn patch() {
+        let base = ConfigOffset(0x100);
+        let mut data = [0x5au8; 0x120];
+        let field = FieldRef::new(base, REG, 12, 8);
+        assert_eq!(0x000f_f000, field.mask());
+        patch_field(&mut data, field, 0x3c).unwrap();
+        assert_eq!([0x5a, 0x5a, 0x5a, 0x5a], data[0x10c..0x110]);
+        assert_eq!([0x5a, 0xca, 0x53, 0x5a], data[0x110..0x114]);
+        assert_eq!(
+            Err(PatchFieldError::ValueTooWide {
+                name: "Test",
+                value: 0x100,
+                width: 8
+            }),
+            patch_field(&mut data, field, 0x100)
+        );
+        // Field ends after the register
+        let field = FieldRef::new(base, REG, 24, 9);
+        assert_eq!(0, field.mask());
+        assert_eq!(
+            Err(PatchFieldError::InvalidBits {
+                name: "Test",
+                shift: 24,
+                width: 9
+            }),
+            patch_field(&mut data, field, 0)
+        );
+        // Register ends after the buffer
+        let field = FieldRef::new(ConfigOffset(0x110), REG, 0, 32);
+        assert_eq!(
+            Err(PatchFieldError::OutOfBounds {
+                name: "Test",
+                offset: 0x120,
+                len: 0x120
+            }),
+            patch_field(&mut data, field, 0)
+        );
+    }
+
+    #[test]
+    fn patch_access() {
+        let reg = REG.rw1c(0xffff_0000);
+        let mut data = [0u8; 0x14];
+        // Field overlapping RW1C bits
+        let field = FieldRef::new(ConfigOffset(0), reg, 8, 16);
+        assert_eq!(FieldAccess::Rw1c, field.access);
+        assert_eq!(
+            Err(PatchFieldError::Rw1c { name: "Test" }),
+            patch_field(&mut data, field, 0x1234)
+        );
+        patch_field_forced(&mut data, field, 0x1234).unwrap();
+        assert_eq!([0x00, 0x34, 0x12, 0x00], data[0x10..]);
+
+        let field = FieldRef::new(ConfigOffset(0), reg, 0, 8).access(FieldAccess::ReadOnly);
+        assert_eq!(
+            Err(PatchFieldError::ReadOnly { name: "Test" }),
+            patch_field(&mut data, field, 1)
+        );
+        patch_field_forced(&mut data, field, 0xff).unwrap();
+        assert_eq!([0xff, 0x34, 0x12, 0x00], data[0x10..]);
+    }
+}
@@ -0,0 +1,822 @@
+/*!
+## Function configuration space
+
+[ConfigurationSpace] ties together the three parts of a single function configuration space:
+the [predefined header](crate::header), the [capabilities](crate::capabilities) list located
+in the device dependent region and the [extended capabilities](crate::extended_capabilities)
+list located in the PCI Express extended configuration space.
+
+Conventional PCI functions expose only 256 bytes of configuration space, so the extended
+configuration space is available only if the data slice is long enough.
+
+## Examples
+
+```rust
+# use pcics::ConfigurationSpace;
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+assert_eq!((0x8086, 0x2030), (cs.header.vendor_id, cs.header.device_id));
+assert_eq!(4, cs.capabilities().count());
+assert_eq!(8, cs.extended_capabilities().unwrap().count());
+```
+
+[ConfigurationSpace::parse_report] walks both lists and collects statistics suitable for
+aggregation over many functions:
+
+```rust
+# use pcics::ConfigurationSpace;
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let report = ConfigurationSpace::try_from(data.as_slice()).unwrap().parse_report();
+assert_eq!(4, report.capabilities.parsed);
+assert_eq!(8, report.extended_capabilities.unwrap().parsed);
+assert!(report.is_complete());
+```
+
+Functions in low power states may return 00h or FFh for some registers. With
+[ConfigurationSpace::parse_report_in] the caller annotates the assumed power state, so regions
+that might be affected are marked and not reported as failures:
+
+```rust
+# use pcics::ConfigurationSpace;
+# use pcics::capabilities::power_management_interface::PowerState;
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let mut data = data.to_vec();
+// Extended configuration space reads as all ones
+data[0x100..].fill(0xff);
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let report = cs.parse_report_in(PowerState::D3Hot);
+assert!(!report.is_complete());
+assert!(report.extended_capabilities.unwrap().power_state_suspect);
+assert!(!report.has_unexplained_failures());
+assert!(cs.parse_report().has_unexplained_failures());
+```
+
+Fleet tooling aggregating many functions may attach [FunctionAddress] to the configuration
+space. [ConfigurationSpace::located] prefixes any displayable value, e.g. an error or an
+analysis finding, with the full segment/bus/device/function address:
+
+```rust
+# use pcics::{configuration_space::FunctionAddress, ConfigOffset, ConfigurationSpace};
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let cs = ConfigurationSpace::try_from(data.as_slice())
+    .unwrap()
+    .with_address(FunctionAddress::new(1, 0x17, 0x00, 0).unwrap());
+assert_eq!("0001:17:00.0 8086:2030", format!("{}", cs));
+assert_eq!("0001:17:00.0 148", format!("{}", cs.located(ConfigOffset(0x148))));
+```
+
+Interactive tools polling a few registers re-read only the written range.
+[ConfigurationSpace::refresh_range] decodes just the structures overlapping it:
+
+```rust
+# use pcics::{configuration_space::RefreshedStructure, ConfigOffset, ConfigurationSpace};
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let mut cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+// Advanced Error Reporting: Uncorrectable Error Status
+let mut polled = data.to_vec();
+polled[0x14c] = 0x10;
+let refreshed = cs.refresh_range(&polled, ConfigOffset(0x14c), 4).unwrap();
+assert!(!refreshed.header);
+let ids = refreshed
+    .map(|s| match s {
+        RefreshedStructure::ExtendedCapability(ecap) => ecap.unwrap().id(),
+        _ => unreachable!(),
+    })
+    .collect::<Vec<_>>();
+assert_eq!(vec![0x0001], ids);
+```
+*/
+
+use core::{array::TryFromSliceError, fmt, ops::Range};
+
+use crate::{
+    analysis::budget::ByteBudget,
+    bdf::{Bus, Device, Function},
+    capabilities::{
+        power_management_interface::PowerState, Capability, CapabilityError, CapabilityKind,
+        CapabilityOffsets,
+    },
+    extended_capabilities::{
+        ExtendedCapability, ExtendedCapabilityError, ExtendedCapabilityHeaders,
+        ExtendedCapabilityKind, SingleRootIoVirtualization,
+    },
+    header::{HeaderType, Normal},
+    Capabilities, ConfigAccess, ConfigOffset, ExtendedCapabilities, Header, CONFIG_SPACE_SIZE,
+    DDR_OFFSET, ECS_OFFSET, EXTENDED_CONFIG_SPACE_SIZE,
+};
+
+/// Parsed header with access to the raw data of a single function configuration space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationSpace<'a> {
+    pub header: Header,
+    data: &'a [u8],
+    address: Option<FunctionAddress>,
+}
+
+impl<'a> ConfigurationSpace<'a> {
+    /// Conventional PCI configuration space size
+    pub const PCI_SIZE: usize = CONFIG_SPACE_SIZE;
+    /// PCI Express configuration space size
+    pub const PCIE_SIZE: usize = EXTENDED_CONFIG_SPACE_SIZE;
+
+    /// Reads configuration space through `access` into `buffer` and parses it
+    ///
+    /// Conventional configuration space is always read, extended configuration space is read
+    /// only if its first dword is accessible and is neither 0 nor all ones. Data ends at the
+    /// first register that is not accessible. The rest of `buffer` is zeroed, the returned
+    /// configuration space borrows the copy. [ConfigReader](crate::config_access::ConfigReader)
+    /// parses through `access` without the copy.
+    pub fn read<A: ConfigAccess + ?Sized>(
+        access: &A,
+        buffer: &'a mut [u8; ConfigurationSpace::PCIE_SIZE],
+    ) -> Result<Self, TryFromSliceError> {
+        buffer.fill(0);
+        let mut len = access.read_into(0, &mut buffer[..ECS_OFFSET]);
+        let ecs_present = matches!(
+            access.read_u32(ECS_OFFSET as u16),
+            Some(dword) if dword != 0 && dword != u32::MAX
+        );
+        if len == ECS_OFFSET && ecs_present {
+            len += access.read_into(ECS_OFFSET as u16, &mut buffer[ECS_OFFSET..]);
+        }
+        Self::try_from(buffer.get(..len).unwrap_or_default())
+    }
+    /// Attaches segment/bus/device/function identity of the function
+    #[must_use]
+    pub fn with_address(self, address: FunctionAddress) -> Self {
+        Self {
+            address: Some(address),
+            ..self
+        }
+    }
+    /// Segment/bus/device/function identity if it was attached
+    pub fn address(&self) -> Option<FunctionAddress> {
+        self.address
+    }
+    /// Wraps `value` so it is displayed prefixed with the function address, if any
+    pub fn located<T>(&self, value: T) -> Located<T> {
+        Located {
+            address: self.address,
+            value,
+        }
+    }
+    /// Raw configuration space data
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+    /// Device dependent region, empty if data is shorter than 0x40 bytes
+    pub fn device_dependent_region(&self) -> &'a [u8] {
+        let end = self.data.len().min(ECS_OFFSET);
+        self.data.get(DDR_OFFSET..end).unwrap_or_default()
+    }
+    /// Extended configuration space if data is longer than 0x100 bytes
+    pub fn extended_configuration_space(&self) -> Option<&'a [u8]> {
+        self.data.get(ECS_OFFSET..).filter(|ecs| !ecs.is_empty())
+    }
+    /// An iterator through *Capabilities List*
+    pub fn capabilities(&self) -> Capabilities<'_> {
+        Capabilities::new(self.device_dependent_region(), &self.header)
+    }
+    /// An iterator through *Extended Capabilities List* if extended configuration space is
+    /// available
+    pub fn extended_capabilities(&self) -> Option<ExtendedCapabilities<'a>> {
+        self.extended_configuration_space()
+            .map(ExtendedCapabilities::new)
+    }
+    /// Replaces data with `data` after a write of `bytes` bytes at `offset` and decodes only
+    /// the structures overlapping the written range
+    ///
+    /// The header is parsed again only if the range overlaps it. Overlapping capabilities and
+    /// extended capabilities are found by walking the list headers, their extents are bounded
+    /// as in [Capabilities::structure_end] and [ExtendedCapabilities::structure_end]. A write
+    /// to the Capabilities Pointer or to a Next Capability pointer may relink the following
+    /// structures, which are not yielded again.
+    pub fn refresh_range(
+        &mut self,
+        data: &'a [u8],
+        offset: ConfigOffset,
+        bytes: usize,
+    ) -> Result<Refreshed<'_>, TryFromSliceError> {
+        let start = usize::from(offset);
+        let range = start..start.saturating_add(bytes);
+        let header = range.start < DDR_OFFSET && !range.is_empty();
+        if header {
+            self.header = data.try_into()?;
+        }
+        self.data = data;
+        let capabilities = self.capabilities();
+        let extended_capabilities = self.extended_capabilities();
+        Ok(Refreshed {
+            header,
+            range,
+            capabilities,
+            capability_offsets: capabilities.offsets(),
+            extended_capabilities,
+            extended_capability_headers: extended_capabilities.map(ExtendedCapabilities::headers),
+        })
+    }
+    /// Classifies the function as an SR-IOV Physical Function, Virtual Function or neither
+    ///
+    /// A PF has the SR-IOV extended capability. A VF is recognized by the header: Vendor ID
+    /// and Device ID read as FFFFh and all Base Address Registers are zero, VF BARs are
+    /// located in the PF's SR-IOV capability. A function that is not present reads as all
+    /// ones, so its header type is not decoded as a Type 0 header.
+    pub fn sriov_role(&self) -> SriovRole {
+        let sriov = self.extended_capabilities().and_then(|mut ecaps| {
+            ecaps.find_map(|ecap| match ecap.ok()?.kind {
+                ExtendedCapabilityKind::SingleRootIoVirtualization(sriov) => Some(sriov),
+                _ => None,
+            })
+        });
+        if let Some(sriov) = sriov {
+            return SriovRole::PhysicalFunction(sriov);
+        }
+        let Header {
+            vendor_id,
+            device_id,
+            ref header_type,
+            ..
+        } = self.header;
+        match header_type {
+            HeaderType::Normal(Normal { base_addresses, .. })
+                if vendor_id == 0xffff
+                    && device_id == 0xffff
+                    && base_addresses.orig().iter().all(|&bar| bar == 0) =>
+            {
+                SriovRole::VirtualFunction
+            }
+            _ => SriovRole::Regular,
+        }
+    }
+    /// Walks capabilities lists and counts decoded, unknown and broken capabilities
+    ///
+    /// Covered bytes are estimated by [ByteBudget], offsets and IDs of the first
+    /// [RegionReport::UNKNOWN_IDS] unknown capabilities of each list are recorded.
+    pub fn parse_report(&self) -> ParseReport {
+        self.parse_report_in(PowerState::D0)
+    }
+    /// [parse_report](Self::parse_report) of a function assumed to be in `power_state`
+    ///
+    /// Outside of D0 regions with failures or filled with 00h or FFh are marked as
+    /// [power_state_suspect](RegionReport::power_state_suspect).
+    pub fn parse_report_in(&self, power_state: PowerState) -> ParseReport {
+        let mut capabilities = RegionReport::new(ByteBudget::from(self.capabilities()));
+        // Iterator terminates a list longer than the region can hold, it is looped
+        for cap in self.capabilities() {
+            if matches!(cap, Err(CapabilityError::Limit { .. })) {
+                capabilities.looped = true;
+                break;
+            }
+            capabilities.walked += 1;
+            match cap {
+                Ok(cap) if matches!(cap.kind, CapabilityKind::Reserved(_)) => {
+                    capabilities.insert_unknown(cap.pointer, cap.id().into())
+                }
+                Ok(_) => capabilities.parsed += 1,
+                Err(_) => capabilities.errors += 1,
+            }
+        }
+        let extended_capabilities = self.extended_configuration_space().map(|ecs| {
+            let ecaps = ExtendedCapabilities::new(ecs);
+            let mut report = RegionReport::new(ByteBudget::from(ecaps));
+            for ecap in ecaps {
+                if matches!(ecap, Err(ExtendedCapabilityError::Limit { .. })) {
+                    report.looped = true;
+                    break;
+                }
+                report.walked += 1;
+                match ecap {
+                    Ok(ecap)
+                        if ecap.kind.is_placeholder()
+                            || matches!(ecap.kind, ExtendedCapabilityKind::Reserved(_)) =>
+                    {
+                        report.insert_unknown(ecap.offset, ecap.id())
+                    }
+                    Ok(_) => report.parsed += 1,
+                    Err(_) => report.errors += 1,
+                }
+            }
+            report.check_power_state(power_state, ecs);
+            report
+        });
+        capabilities.check_power_state(power_state, self.device_dependent_region());
+        ParseReport {
+            header_bytes: self.data.len().min(DDR_OFFSET),
+            power_state,
+            capabilities,
+            extended_capabilities,
+        }
+    }
+}
+
+/// SR-IOV role of a function, see [ConfigurationSpace::sriov_role]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SriovRole {
+    /// Physical Function with its SR-IOV capability
+    PhysicalFunction(SingleRootIoVirtualization),
+    /// Virtual Function, its Vendor ID and Device ID are in the PF's header and SR-IOV
+    /// capability
+    VirtualFunction,
+    /// Function not associated with SR-IOV
+    Regular,
+}
+
+/// Structures decoded again by [ConfigurationSpace::refresh_range]
+#[derive(Debug, PartialEq, Eq)]
+pub enum RefreshedStructure<'a> {
+    Capability(Result<Capability<'a>, CapabilityError>),
+    ExtendedCapability(Result<ExtendedCapability<'a>, ExtendedCapabilityError>),
+}
+
+/// An iterator through capabilities and extended capabilities overlapping the range refreshed
+/// by [ConfigurationSpace::refresh_range]
+#[derive(Debug, Clone)]
+pub struct Refreshed<'a> {
+    /// Predefined header overlaps the range and was parsed again
+    pub header: bool,
+    range: Range<usize>,
+    capabilities: Capabilities<'a>,
+    capability_offsets: CapabilityOffsets<'a>,
+    extended_capabilities: Option<ExtendedCapabilities<'a>>,
+    extended_capability_headers: Option<ExtendedCapabilityHeaders<'a>>,
+}
+
+impl<'a> Refreshed<'a> {
+    /// Structure at `offset` ending at `end()` overlaps the range
+    fn overlaps(&self, offset: ConfigOffset, end: impl FnOnce() -> ConfigOffset) -> bool {
+        usize::from(offset) < self.range.end && usize::from(end()) > self.range.start
+    }
+}
+
+impl<'a> Iterator for Refreshed<'a> {
+    type Item = RefreshedStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let caps = self.capabilities;
+        while let Some((pointer, _)) = self.capability_offsets.next() {
+            if self.overlaps(pointer, || caps.structure_end(pointer)) {
+                return Some(RefreshedStructure::Capability(caps.parse_at(pointer)));
+            }
+        }
+        let ecaps = self.extended_capabilities?;
+        while let Some((offset, _)) = self.extended_capability_headers.as_mut()?.next() {
+            if self.overlaps(offset, || ecaps.structure_end(offset)) {
+                return Some(RefreshedStructure::ExtendedCapability(
+                    ecaps.parse_at(offset),
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Parse statistics of a single function configuration space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Predefined header bytes
+    pub header_bytes: usize,
+    /// Power state the function was assumed to be in
+    pub power_state: PowerState,
+    /// Capabilities List in the device dependent region
+    pub capabilities: RegionReport,
+    /// Extended Capabilities List, `None` if extended configuration space is not available
+    pub extended_capabilities: Option<RegionReport>,
+}
+
+impl ParseReport {
+    /// All capabilities in both lists are decoded
+    pub fn is_complete(&self) -> bool {
+        self.capabilities.is_complete()
+            && self
+                .extended_capabilities
+                .is_none_or(|report| report.is_complete())
+    }
+    /// Some capabilities are not decoded and it can not be explained by the assumed power state
+    pub fn has_unexplained_failures(&self) -> bool {
+        let unexplained =
+            |report: &RegionReport| !report.is_complete() && !report.power_state_suspect;
+        unexplained(&self.capabilities)
+            || self.extended_capabilities.is_some_and(|r| unexplained(&r))
+    }
+}
+
+/// Capabilities list statistics of a configuration space region
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RegionReport {
+    /// Region bytes covered by walked structures, a lower bound estimated by [ByteBudget]
+    pub bytes: usize,
+    /// Capabilities list entries visited
+    pub walked: usize,
+    /// Decoded capabilities
+    pub parsed: usize,
+    /// Capabilities with reserved IDs or without decoder
+    pub unknown: usize,
+    /// Offsets and IDs of the first [UNKNOWN_IDS](Self::UNKNOWN_IDS) unknown capabilities
+    pub unknown_ids: [Option<(ConfigOffset, u16)>; Self::UNKNOWN_IDS],
+    /// Capabilities failed to parse
+    pub errors: usize,
+    /// List walk was stopped because it is longer than the region can hold
+    pub looped: bool,
+    /// Region content may be invalid because the function is not in D0
+    pub power_state_suspect: bool,
+}
+
+impl RegionReport {
+    /// Maximum number of recorded [unknown_ids](Self::unknown_ids)
+    pub const UNKNOWN_IDS: usize = 8;
+    fn new(budget: ByteBudget) -> Self {
+        Self {
+            bytes: budget.used(),
+            ..Default::default()
+        }
+    }
+    /// Counts unknown capability and records its offset and ID while there is room
+    fn insert_unknown(&mut self, offset: ConfigOffset, id: u16) {
+        if let Some(slot) = self.unknown_ids.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some((offset, id));
+        }
+        self.unknown += 1;
+    }
+    /// Recorded offsets and IDs of unknown capabilities
+    pub fn unknown_ids(&self) -> impl Iterator<Item = (ConfigOffset, u16)> + '_ {
+        self.unknown_ids.iter().map_while(|&slot| slot)
+    }
+    /// Marks incomplete or uniformly 00h / FFh filled region outside of D0
+    fn check_power_state(&mut self, power_state: PowerState, region: &[u8]) {
+        let uniform = |byte| !region.is_empty() && region.iter().all(|&b| b == byte);
+        self.power_state_suspect = power_state != PowerState::D0
+            && (!self.is_complete() || uniform(0x00) || uniform(0xff));
+    }
+    /// No unknown or broken capabilities were met
+    pub fn is_complete(&self) -> bool {
+        self.unknown == 0 && self.errors == 0 && !self.looped
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for ConfigurationSpace<'a> {
+    type Error = TryFromSliceError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let header = data.try_into()?;
+        Ok(Self {
+            header,
+            data,
+            address: None,
+        })
+    }
+}
+
+impl fmt::Display for ConfigurationSpace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Header {
+            vendor_id,
+            device_id,
+            ..
+        } = self.header;
+        write!(
+            f,
+            "{}",
+            self.located(format_args!("{:04x}:{:04x}", vendor_id, device_id))
+        )
+    }
+}
+
+/// PCI Segment Group (domain), Bus, Device and Function numbers of a function
+///
+/// Displayed in the `ssss:bb:dd.f` form used by Linux
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FunctionAddress {
+    /// PCI Segment Group Number
+    pub segment: u16,
+    /// Bus Number
+    pub bus: Bus,
+    /// Device Number
+    pub device: Device,
+    /// Function Number
+    pub function: Function,
+}
+
+impl FunctionAddress {
+    /// `None` if `device` or `function` is out of range
+    pub const fn new(segment: u16, bus: u8, device: u8, function: u8) -> Option<Self> {
+        match (Device::new(device), Function::new(function)) {
+            (Some(device), Some(function)) => {
+                Some(Self::from_bdf(segment, Bus(bus), device, function))
+            }
+            _ => None,
+        }
+    }
+    /// Address from typed Bus, Device and Function numbers
+    pub const fn from_bdf(segment: u16, bus: Bus, device: Device, function: Function) -> Self {
+        Self {
+            segment,
+            bus,
+            device,
+            function,
+        }
+    }
+    /// Requester / Completer ID of the function in the PCI Segment Group
+    pub const fn routing_id(&self) -> u16 {
+        (self.bus.get() as u16) << 8 | (self.device.get() as u16) << 3 | self.function.get() as u16
+    }
+    /// Address of the function with `routing_id` in the same PCI Segment Group
+    pub const fn from_routing_id(segment: u16, routing_id: u16) -> Self {
+        let device = Device::new((routing_id >> 3) as u8 & 0x1f);
+        let function = Function::new(routing_id as u8 & 0x07);
+        match (device, function) {
+            (Some(device), Some(function)) => {
+                Self::from_bdf(segment, Bus((routing_id >> 8) as u8), device, function)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for FunctionAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04x}:{}:{}.{}",
+            self.segment, self.bus, self.device, self.function
+        )
+    }
+}
+
+/// Value tied to a function, displayed prefixed with the function address if it is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Located<T> {
+    pub address: Option<FunctionAddress>,
+    pub value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Located<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.address {
+            Some(address) => write!(f, "{} {}", address, self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DDR_LENGTH;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn sriov_role() {
+        let pf = crate::synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(pf.as_slice()).unwrap();
+        let vf_device_id = match cs.sriov_role() {
+            SriovRole::PhysicalFunction(sriov) => Some(sriov.vf_device_id),
+            _ => None,
+        };
+        assert_eq!(Some(crate::synthetic::VF_DEVICE_ID), vf_device_id);
+
+        // Regular function, no extended configuration space
+        let cs = ConfigurationSpace::try_from(&pf[..ECS_OFFSET]).unwrap();
+        assert_eq!(SriovRole::Regular, cs.sriov_role());
+
+        // VF: no IDs and BARs
+        let mut vf = pf;
+        vf[..4].fill(0xff);
+        vf[0x10..0x28].fill(0);
+        vf[0x100..].fill(0);
+        let cs = ConfigurationSpace::try_from(vf.as_slice()).unwrap();
+        assert_eq!(SriovRole::VirtualFunction, cs.sriov_role());
+
+        // BAR is implemented
+        vf[0x10] = 0x04;
+        let cs = ConfigurationSpace::try_from(vf.as_slice()).unwrap();
+        assert_eq!(SriovRole::Regular, cs.sriov_role());
+
+        // Function is not present
+        let absent = [0xff; ECS_OFFSET];
+        let cs = ConfigurationSpace::try_from(absent.as_slice()).unwrap();
+        assert_eq!(SriovRole::Regular, cs.sriov_role());
+    }
+
+    #[test]
+    fn refresh_range() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let mut polled = data.to_vec();
+        // Command: Bus Master Enable cleared
+        polled[0x04] &= !0x04;
+        let mut cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let offsets = |refreshed: Refreshed| {
+            refreshed
+                .map(|s| match s {
+                    RefreshedStructure::Capability(cap) => cap.unwrap().pointer.get(),
+                    RefreshedStructure::ExtendedCapability(ecap) => ecap.unwrap().offset.get(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let refreshed = cs.refresh_range(&polled, ConfigOffset(0x04), 2).unwrap();
+        assert!(refreshed.header);
+        assert_eq!(Vec::<u16>::new(), offsets(refreshed));
+        assert!(!cs.header.command.bus_master);
+
+        // PCI Express Link Status
+        let result = offsets(cs.refresh_range(&polled, ConfigOffset(0xa2), 2).unwrap());
+        assert_eq!(vec![0x90], result);
+        // Spans PCI Express and Power Management capabilities
+        let result = offsets(cs.refresh_range(&polled, ConfigOffset(0xde), 4).unwrap());
+        assert_eq!(vec![0x90, 0xe0], result);
+        // Spans last capability and first extended capability
+        let result = offsets(cs.refresh_range(&polled, ConfigOffset(0xfc), 8).unwrap());
+        assert_eq!(vec![0xe0, 0x100], result);
+        // Extended capabilities are in list order
+        let result = offsets(
+            cs.refresh_range(&polled, ConfigOffset(0x2f0), 0x20)
+                .unwrap(),
+        );
+        assert_eq!(vec![0x298, 0x300], result);
+        // Empty range
+        let refreshed = cs.refresh_range(&polled, ConfigOffset(0x00), 0).unwrap();
+        assert!(!refreshed.header);
+        assert_eq!(0, refreshed.count());
+        // Conventional PCI data
+        let result = offsets(
+            cs.refresh_range(&polled[..0x100], ConfigOffset(0xfc), 8)
+                .unwrap(),
+        );
+        assert_eq!(vec![0xe0], result);
+        assert!(cs.extended_capabilities().is_none());
+    }
+
+    #[test]
+    fn conventional_pci() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_9dc8/config"
+        ));
+        let cs = ConfigurationSpace::try_from(&data[..ECS_OFFSET]).unwrap();
+        assert_eq!(3, cs.capabilities().count());
+        assert!(cs.extended_capabilities().is_none());
+    }
+
+    #[test]
+    fn too_short() {
+        assert!(ConfigurationSpace::try_from([0u8; 0x3f].as_slice()).is_err());
+        let cs = ConfigurationSpace::try_from([0u8; 0x40].as_slice()).unwrap();
+        assert!(cs.device_dependent_region().is_empty());
+    }
+
+    /// Accessor counting registers reads
+    struct Counting<'a>(&'a [u8], core::cell::Cell<usize>);
+
+    impl ConfigAccess for Counting<'_> {
+        fn read_u8(&self, offset: u16) -> Option<u8> {
+            self.1.set(self.1.get() + 1);
+            self.0.read_u8(offset)
+        }
+    }
+
+    #[test]
+    fn read() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let mut buffer = [0xff; ConfigurationSpace::PCIE_SIZE];
+        let cs = ConfigurationSpace::read(data.as_slice(), &mut buffer).unwrap();
+        assert_eq!(ConfigurationSpace::try_from(data.as_slice()).unwrap(), cs);
+
+        // Conventional PCI function without extended configuration space
+        let data = crate::synthetic::root_port(false);
+        let access = Counting(&data, Default::default());
+        let cs = ConfigurationSpace::read(&access, &mut buffer).unwrap();
+        assert_eq!(ECS_OFFSET, cs.data().len());
+        assert_eq!(ECS_OFFSET + 4, access.1.get());
+
+        assert!(ConfigurationSpace::read(&data[..0x3f], &mut buffer).is_err());
+    }
+
+    #[test]
+    fn parse_report() {
+        let data = crate::synthetic::endpoint_with_extended_capabilities();
+        let report = ConfigurationSpace::try_from(data.as_slice())
+            .unwrap()
+            .parse_report();
+        assert_eq!(
+            ParseReport {
+                header_bytes: DDR_OFFSET,
+                power_state: PowerState::D0,
+                capabilities: RegionReport {
+                    bytes: 72,
+                    walked: 2,
+                    parsed: 2,
+                    ..Default::default()
+                },
+                extended_capabilities: Some(RegionReport {
+                    bytes: 300,
+                    walked: 16,
+                    parsed: 16,
+                    ..Default::default()
+                }),
+            },
+            report
+        );
+        assert!(report.is_complete());
+
+        // MSI-X points to itself, reserved extended capability ID
+        let mut data = crate::synthetic::endpoint_with_msix(1);
+        data[0x81] = 0x80;
+        data[ECS_OFFSET..ECS_OFFSET + 4].copy_from_slice(&[0xfe, 0xff, 0x01, 0x00]);
+        let report = ConfigurationSpace::try_from(data.as_slice())
+            .unwrap()
+            .parse_report();
+        assert_eq!(DDR_LENGTH / 4, report.capabilities.walked);
+        assert!(report.capabilities.looped);
+        let ecaps = report.extended_capabilities.unwrap();
+        assert_eq!((1, 1, 0), (ecaps.walked, ecaps.unknown, ecaps.parsed));
+        assert_eq!(
+            vec![(ConfigOffset(0x100), 0xfffe)],
+            ecaps.unknown_ids().collect::<Vec<_>>()
+        );
+        assert_eq!(ExtendedCapability::HEADER_SIZE, ecaps.bytes);
+        assert!(!report.is_complete());
+        assert!(report.has_unexplained_failures());
+    }
+
+    #[test]
+    fn parse_report_in_low_power_state() {
+        let data = crate::synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let report = cs.parse_report_in(PowerState::D3Hot);
+        assert!(report.is_complete());
+        assert!(!report.capabilities.power_state_suspect);
+        assert!(!report.extended_capabilities.unwrap().power_state_suspect);
+
+        // Extended configuration space reads as zeroes: empty list is not an error
+        let mut data = data;
+        data[ECS_OFFSET..].fill(0);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let report = cs.parse_report_in(PowerState::D3Hot);
+        let ecaps = report.extended_capabilities.unwrap();
+        assert_eq!((0, true), (ecaps.walked, ecaps.power_state_suspect));
+        assert!(report.is_complete());
+        let ecaps = cs.parse_report().extended_capabilities.unwrap();
+        assert!(!ecaps.power_state_suspect);
+
+        // Broken capabilities list in D0 is a failure
+        data[0x41] = 0x3c;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert!(cs.parse_report().has_unexplained_failures());
+        let report = cs.parse_report_in(PowerState::D1);
+        assert!(report.capabilities.power_state_suspect);
+        assert!(!report.has_unexplained_failures());
+    }
+
+    #[test]
+    fn function_address() {
+        let address = FunctionAddress::new(0x10, 0x3a, 0x1f, 7).unwrap();
+        assert_eq!(None, FunctionAddress::new(0x10, 0x3a, 0x20, 7));
+        assert_eq!(None, FunctionAddress::new(0x10, 0x3a, 0x1f, 8));
+        assert_eq!("0010:3a:1f.7", address.to_string());
+        assert_eq!(0x3aff, address.routing_id());
+        assert_eq!(address, FunctionAddress::from_routing_id(0x10, 0x3aff));
+
+        let data = crate::synthetic::endpoint_with_extended_capabilities();
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, cs.address());
+        let id = format!("{:04x}:{:04x}", cs.header.vendor_id, cs.header.device_id);
+        assert_eq!(id, cs.to_string());
+        assert_eq!("1d0", cs.located(ConfigOffset(0x1d0)).to_string());
+
+        let cs = cs.with_address(address);
+        assert_eq!(Some(address), cs.address());
+        assert_eq!(format!("0010:3a:1f.7 {}", id), cs.to_string());
+        // MSI capability truncated by the end of the device dependent region
+        let mut data = data;
+        data[0x34] = 0xfc;
+        data[0xfc..0x100].copy_from_slice(&[0x05, 0x00, 0x00, 0x00]);
+        let cs = ConfigurationSpace::try_from(data.as_slice())
+            .unwrap()
+            .with_address(address);
+        let error = cs.capabilities().next().unwrap().unwrap_err();
+        let sample = format!("0010:3a:1f.7 {}", error);
+        assert_eq!(sample, cs.located(error).to_string());
+    }
+}
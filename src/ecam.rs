@@ -0,0 +1,253 @@
+/*!
+## Enhanced Configuration Access Mechanism
+
+ECAM maps configuration space of every function in a PCI Segment Group into a flat memory
+region. The address of a function configuration space is computed from its location in the
+hierarchy:
+
+| Memory address bits | Field             |
+|---------------------|-------------------|
+| 27:20               | Bus Number        |
+| 19:15               | Device Number     |
+| 14:12               | Function Number   |
+| 11:0                | Register offset   |
+
+[Ecam] walks through such region (e.g. obtained by mapping `/dev/mem` or from a VFIO
+container) and yields every present function. Memory mapped region without a slice is read
+through `mmio` module (`ecam-mmio` feature). Functions returning `0xFFFF` as Vendor ID are
+treated as absent. Functions 1-7 are scanned only if function 0 is a multi-function device.
+
+## Examples
+
+```rust
+# use pcics::ecam::*;
+// Region for buses 0x10-0x11
+let mut region = vec![0xffu8; 2 * Ecam::BUS_SIZE];
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+// 11:02.0
+let offset = Ecam::offset(0x10, 0x11, 0x02, 0).unwrap();
+region[offset..offset + Ecam::FUNCTION_SIZE].copy_from_slice(data);
+
+let result = Ecam::new(&region, 0, 0x10..=0x11)
+    .map(|f| (f.segment, f.bus, f.device, f.function, f.configuration_space.header.device_id))
+    .collect::<Vec<_>>();
+assert_eq!(vec![(0, 0x11, 0x02, 0, 0x2030)], result);
+```
+*/
+
+#[cfg(feature = "ecam-mmio")]
+pub mod mmio;
+
+use core::ops::RangeInclusive;
+
+use crate::configuration_space::{ConfigurationSpace, FunctionAddress};
+
+/// An iterator through all present functions in ECAM region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ecam<'a> {
+    data: &'a [u8],
+    segment: u16,
+    start_bus: u8,
+    end_bus: u8,
+    /// Next bus/device/function to scan
+    position: Option<(u8, u8, u8)>,
+}
+
+impl<'a> Ecam<'a> {
+    /// Each function has 4 KiB configuration space
+    pub const FUNCTION_SIZE: usize = 1 << 12;
+    /// Each device has up to 8 functions
+    pub const DEVICE_SIZE: usize = Self::FUNCTION_SIZE << 3;
+    /// Each bus has up to 32 devices
+    pub const BUS_SIZE: usize = Self::DEVICE_SIZE << 5;
+
+    /// Creates iterator over ECAM region `data` which is mapped starting from the first bus in
+    /// `buses` range. Buses not covered by `data` are not scanned.
+    pub fn new(data: &'a [u8], segment: u16, buses: RangeInclusive<u8>) -> Self {
+        let (start_bus, end_bus) = buses.into_inner();
+        Self {
+            data,
+            segment,
+            start_bus,
+            end_bus,
+            position: (start_bus <= end_bus).then_some((start_bus, 0, 0)),
+        }
+    }
+    /// Function configuration space offset relative to ECAM region mapped from `start_bus`,
+    /// `None` if `bus` is below `start_bus` or device/function number is out of range
+    pub const fn offset(start_bus: u8, bus: u8, device: u8, function: u8) -> Option<usize> {
+        match bus.checked_sub(start_bus) {
+            Some(bus) if device <= 31 && function <= 7 => Some(
+                bus as usize * Self::BUS_SIZE
+                    + device as usize * Self::DEVICE_SIZE
+                    + function as usize * Self::FUNCTION_SIZE,
+            ),
+            _ => None,
+        }
+    }
+    /// Raw configuration space of the function if it is covered by ECAM region
+    pub fn function_data(&self, bus: u8, device: u8, function: u8) -> Option<&'a [u8]> {
+        if bus > self.end_bus {
+            return None;
+        }
+        let offset = Self::offset(self.start_bus, bus, device, function)?;
+        self.data.get(offset..offset + Self::FUNCTION_SIZE)
+    }
+    fn advance(&mut self, skip_functions: bool) {
+        self.position = self.position.and_then(|(bus, device, function)| {
+            if function < 7 && !skip_functions {
+                Some((bus, device, function + 1))
+            } else if device < 31 {
+                Some((bus, device + 1, 0))
+            } else if bus < self.end_bus {
+                Some((bus + 1, 0, 0))
+            } else {
+                None
+            }
+        });
+    }
+}
+
+impl<'a> Iterator for Ecam<'a> {
+    type Item = EcamFunction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((bus, device, function)) = self.position {
+            let address = FunctionAddress::new(self.segment, bus, device, function);
+            let (data, address) = match (self.function_data(bus, device, function), address) {
+                (Some(data), Some(address)) => (data, address),
+                _ => {
+                    // Rest of buses are not mapped
+                    self.position = None;
+                    break;
+                }
+            };
+            let configuration_space = ConfigurationSpace::try_from(data)
+                .ok()
+                .filter(|cs| cs.header.vendor_id != 0xFFFF)
+                .map(|cs| cs.with_address(address));
+            // Non-existent or single function device at function 0 means there are no more
+            // functions at this device
+            let skip_functions = function == 0
+                && !configuration_space
+                    .as_ref()
+                    .map(|cs| cs.header.is_multi_function)
+                    .unwrap_or(false);
+            self.advance(skip_functions);
+            if let Some(configuration_space) = configuration_space {
+                return Some(EcamFunction {
+                    segment: self.segment,
+                    bus,
+                    device,
+                    function,
+                    configuration_space,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Present function found in ECAM region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcamFunction<'a> {
+    /// PCI Segment Group Number
+    pub segment: u16,
+    /// Bus Number
+    pub bus: u8,
+    /// Device Number
+    pub device: u8,
+    /// Function Number
+    pub function: u8,
+    /// Configuration space with attached [address](Self::address)
+    pub configuration_space: ConfigurationSpace<'a>,
+}
+
+impl EcamFunction<'_> {
+    /// Segment/bus/device/function identity of the function, `None` if device or function
+    /// number is out of range
+    pub fn address(&self) -> Option<FunctionAddress> {
+        FunctionAddress::new(self.segment, self.bus, self.device, self.function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const DATA: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+
+    fn place(region: &mut [u8], device: u8, function: u8, is_multi_function: bool) {
+        let offset = Ecam::offset(0, 0, device, function).unwrap();
+        let cs = &mut region[offset..offset + Ecam::FUNCTION_SIZE];
+        cs.copy_from_slice(DATA);
+        if is_multi_function {
+            cs[0x0e] |= 0x80;
+        } else {
+            cs[0x0e] &= !0x80;
+        }
+    }
+
+    #[test]
+    fn multi_function() {
+        let mut region = vec![0xff; Ecam::BUS_SIZE];
+        place(&mut region, 0, 0, true);
+        place(&mut region, 0, 3, true);
+        // Single function device: function 1 should not be scanned
+        place(&mut region, 5, 0, false);
+        place(&mut region, 5, 1, false);
+        place(&mut region, 31, 0, false);
+        let result = Ecam::new(&region, 1, 0..=0)
+            .map(|f| (f.segment, f.bus, f.device, f.function))
+            .collect::<Vec<_>>();
+        let sample = vec![(1, 0, 0, 0), (1, 0, 0, 3), (1, 0, 5, 0), (1, 0, 31, 0)];
+        assert_eq!(sample, result);
+        let result = Ecam::new(&region, 1, 0..=0)
+            .map(|f| (f.address(), f.configuration_space.address()))
+            .last()
+            .unwrap();
+        let address = FunctionAddress::new(1, 0, 31, 0);
+        assert_eq!((address, address), result);
+    }
+
+    #[test]
+    fn region_shorter_than_bus_range() {
+        let mut region = vec![0xff; Ecam::BUS_SIZE + Ecam::FUNCTION_SIZE];
+        place(&mut region, 0, 0, false);
+        region[Ecam::BUS_SIZE..].copy_from_slice(DATA);
+        let result = Ecam::new(&region, 0, 0..=0xff)
+            .map(|f| (f.bus, f.device, f.function))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(0, 0, 0), (1, 0, 0)], result);
+    }
+
+    #[test]
+    fn offset() {
+        assert_eq!(
+            Some(Ecam::BUS_SIZE + 0x1a000),
+            Ecam::offset(0x10, 0x11, 3, 2)
+        );
+        assert_eq!(Some(0), Ecam::offset(0xff, 0xff, 0, 0));
+        assert_eq!(None, Ecam::offset(0x10, 0x0f, 0, 0));
+        assert_eq!(None, Ecam::offset(0, 0, 32, 0));
+        assert_eq!(None, Ecam::offset(0, 0, 0, 8));
+        let ecam = Ecam::new(DATA, 0, 0x10..=0x11);
+        assert_eq!(None, ecam.function_data(0x0f, 0, 0));
+        assert_eq!(None, ecam.function_data(0x12, 0, 0));
+    }
+
+    #[test]
+    fn empty_range() {
+        #[allow(clippy::reversed_empty_ranges)]
+        let mut ecam = Ecam::new(DATA, 0, 1..=0);
+        assert_eq!(None, ecam.next());
+    }
+}
@@ -0,0 +1,152 @@
+/*!
+## Memory mapped ECAM access
+
+[Ecam](super::Ecam) needs the ECAM region as a byte slice. A kernel or firmware enumerating
+functions directly reads the region through volatile accesses instead: configuration registers
+may have side effects and must not be cached, merged or reordered by the compiler.
+
+[MmioEcam] wraps a caller-provided pointer to the mapped region and gives a [ConfigAccess]
+for every function in it. All unsafe code is confined to [MmioEcam::new], where the caller
+guarantees the mapping, and to the aligned dword reads bounded by the bus range. Registers are
+read with 32-bit accesses only, which every ECAM implementation supports.
+
+This module is enabled by `ecam-mmio` feature.
+
+## Examples
+
+Enumeration of bus 0, a buffer stands in for the region mapped from MCFG table base address:
+
+```rust
+# use pcics::{ecam::{mmio::MmioEcam, Ecam}, ConfigAccess, ConfigurationSpace};
+# let mut region = vec![0xffff_ffffu32; Ecam::BUS_SIZE / 4];
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+# for (dword, bytes) in region.iter_mut().zip(data.chunks(4)) {
+#     *dword = u32::from_le_bytes(bytes.try_into().unwrap());
+# }
+# let base = region.as_ptr() as usize;
+// SAFETY: `base` is the virtual address of uncached mapping of bus 0 ECAM region, it stays
+// mapped while `ecam` is alive
+let ecam = unsafe { MmioEcam::new(base as *const u32, 0..=0) };
+let mut buffer = [0; ConfigurationSpace::PCIE_SIZE];
+let mut found = vec![];
+for device in 0..32 {
+    for function in 0..8 {
+        let access = ecam.function(0, device, function).unwrap();
+        if access.read_u16(0) == Some(0xffff) {
+            // Functions 1-7 may be sparse, absent function 0 means absent device
+            if function == 0 {
+                break;
+            }
+            continue;
+        }
+        let cs = ConfigurationSpace::read(&access, &mut buffer).unwrap();
+        found.push((device, function, cs.header.device_id));
+        if function == 0 && !cs.header.is_multi_function {
+            break;
+        }
+    }
+}
+assert_eq!(vec![(0, 0, 0x2030)], found);
+```
+*/
+
+use core::{marker::PhantomData, ops::RangeInclusive, ptr};
+
+use super::Ecam;
+use crate::ConfigAccess;
+
+/// ECAM region mapped into the address space
+#[derive(Debug)]
+pub struct MmioEcam {
+    base: *const u32,
+    start_bus: u8,
+    end_bus: u8,
+}
+
+impl MmioEcam {
+    /// ECAM region of `buses` mapped at `base`
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 4-byte aligned and point to the ECAM region of the first bus in `buses`.
+    /// The whole region of `buses` ([Ecam::BUS_SIZE] bytes per bus) must stay mapped and
+    /// readable, preferably as uncached device memory, while [MmioEcam] and its functions are
+    /// alive.
+    pub unsafe fn new(base: *const u32, buses: RangeInclusive<u8>) -> Self {
+        let (start_bus, end_bus) = buses.into_inner();
+        Self {
+            base,
+            start_bus,
+            end_bus,
+        }
+    }
+    /// Configuration space access of the function, `None` if it is outside of the region
+    pub fn function(&self, bus: u8, device: u8, function: u8) -> Option<MmioFunction<'_>> {
+        if bus > self.end_bus {
+            return None;
+        }
+        let offset = Ecam::offset(self.start_bus, bus, device, function)?;
+        Some(MmioFunction {
+            // Offset is within the region guaranteed by the constructor
+            base: self.base.wrapping_add(offset / 4),
+            _ecam: PhantomData,
+        })
+    }
+}
+
+/// Configuration space of a single function in [MmioEcam] region
+#[derive(Debug, Clone, Copy)]
+pub struct MmioFunction<'a> {
+    base: *const u32,
+    _ecam: PhantomData<&'a MmioEcam>,
+}
+
+impl ConfigAccess for MmioFunction<'_> {
+    fn read_u8(&self, offset: u16) -> Option<u8> {
+        let dword = self.read_u32(offset & !3)?;
+        Some((dword >> ((offset & 3) * 8)) as u8)
+    }
+    fn read_u32(&self, offset: u16) -> Option<u32> {
+        let offset = offset as usize;
+        if offset & 3 != 0 || offset >= Ecam::FUNCTION_SIZE {
+            return None;
+        }
+        // SAFETY: aligned dword inside the function configuration space, which is mapped
+        // according to MmioEcam::new contract
+        let dword = unsafe { ptr::read_volatile(self.base.add(offset / 4)) };
+        Some(u32::from_le(dword))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn function_bounds() {
+        // Two buses, 1 KiB of function 21:00.1 is filled with a pattern
+        let mut region = vec![0u32; 2 * Ecam::BUS_SIZE / 4];
+        let start = Ecam::offset(0x20, 0x21, 0, 1).unwrap() / 4;
+        for (i, dword) in region[start..start + 0x100].iter_mut().enumerate() {
+            *dword = u32::from_le_bytes([i as u8, 0x11, 0x22, 0x33]);
+        }
+        let ecam = unsafe { MmioEcam::new(region.as_ptr(), 0x20..=0x21) };
+        assert!(ecam.function(0x1f, 0, 0).is_none());
+        assert!(ecam.function(0x22, 0, 0).is_none());
+        assert!(ecam.function(0x21, 32, 0).is_none());
+        assert!(ecam.function(0x21, 0, 8).is_none());
+
+        let function = ecam.function(0x21, 0, 1).unwrap();
+        assert_eq!(Some(0x3322_1102), function.read_u32(0x08));
+        assert_eq!(Some(0x22), function.read_u8(0x0a));
+        assert_eq!(Some(0x0333), function.read_u16(0x0b));
+        assert_eq!(None, function.read_u32(0x0a));
+        assert_eq!(Some(0), function.read_u32(0xffc));
+        assert_eq!(None, function.read_u8(0x1000));
+    }
+}
@@ -0,0 +1,406 @@
+/*!
+## Configuration space editor
+
+[ConfigSpaceEditor] modifies a configuration space image in place, e.g. to mask features of a
+function passed through to a guest. Hidden capabilities are unlinked from their lists and their
+registers are zeroed.
+
+Capability structures have no common size field, so the hidden structure is assumed to span up
+to the next structure of the same list in address order, or up to the end of the list region.
+
+Some guests refuse newer versions of extended capabilities. Capability Version can be
+renumbered keeping the Capability ID and the Next Capability Offset, Advanced Error Reporting
+can be turned into version 1 structure without version 2 features and TLP Prefix Log.
+
+## Examples
+
+```rust
+# use pcics::{ConfigSpaceEditor, ConfigurationSpace};
+let mut data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+))
+.to_vec();
+// Hide Advanced Error Reporting
+let hidden = ConfigSpaceEditor::new(&mut data).hide_extended_capability(0x0001);
+assert_eq!(1, hidden);
+
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let ids = cs
+    .extended_capabilities()
+    .unwrap()
+    .map(|ecap| ecap.unwrap().id())
+    .collect::<Vec<_>>();
+assert_eq!(vec![0x000b, 0x000d, 0x000b, 0x0019, 0x000b, 0x000b, 0x000b], ids);
+
+// Secondary PCI Express as version 2
+let renumbered = ConfigSpaceEditor::new(&mut data).set_extended_capability_version(0x0019, 2);
+assert_eq!(1, renumbered);
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let ecap = cs.extended_capabilities().unwrap().nth(3).unwrap().unwrap();
+assert_eq!((0x0019, 2), (ecap.id(), ecap.version));
+```
+*/
+
+use core::ops::Deref;
+
+use crate::{
+    capabilities::Capability, extended_capabilities::ExtendedCapabilityHeader, DDR_LENGTH,
+    DDR_OFFSET, ECS_LENGTH, ECS_OFFSET,
+};
+
+/// Maximum number of capabilities lists entries, every DWORD visited once
+const MAX_CAPS: usize = DDR_LENGTH / 4;
+const MAX_ECAPS: usize = ECS_LENGTH / 4;
+
+/// Header Type register offset
+const HEADER_TYPE: usize = 0x0e;
+/// Status register offset and Capabilities List bit
+const STATUS: usize = 0x06;
+const CAPABILITIES_LIST: u8 = 1 << 4;
+
+/// Advanced Error Reporting Capability ID and registers offsets
+const AER_ID: u16 = 0x0001;
+const AER_CAPS_AND_CONTROL: usize = 0x18;
+const AER_TLP_PREFIX_LOG: usize = 0x38;
+const AER_TLP_PREFIX_LOG_END: usize = 0x48;
+/// Advanced Error Capabilities and Control bits introduced by version 2
+const AER_V2_BITS: u16 = 0b1111 << 9;
+
+/// In-place editor of a configuration space image
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConfigSpaceEditor<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> ConfigSpaceEditor<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self {
+        Self { data }
+    }
+    /// Edited configuration space data
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+    /// Unlinks all capabilities with `id` from the Capabilities List and zeroes them, returns
+    /// number of hidden capabilities
+    ///
+    /// If the list becomes empty, Capabilities List bit of Status register is cleared.
+    pub fn hide_capability(&mut self, id: u8) -> usize {
+        // Capabilities Pointer location depends on Header Type
+        let mut link = match self.data.get(HEADER_TYPE).map(|ht| ht & 0x7f) {
+            Some(0x02) => 0x14,
+            Some(_) => 0x34,
+            None => return 0,
+        };
+        let end = self.data.len().min(ECS_OFFSET);
+        let offsets = self.capabilities(link, end);
+        let mut hidden = 0;
+        for &ptr in offsets.iter() {
+            // Listed capabilities headers are within the data
+            if self.data.get(ptr) != Some(&id) {
+                link = ptr + 1;
+                continue;
+            }
+            let next_ptr = self.data.get(ptr + 1).copied().unwrap_or(0);
+            if let Some(byte) = self.data.get_mut(link) {
+                *byte = next_ptr;
+            }
+            let next = offsets.iter().copied().filter(|&p| p > ptr).min();
+            if let Some(bytes) = self.data.get_mut(ptr..next.unwrap_or(end)) {
+                bytes.fill(0);
+            }
+            hidden += 1;
+        }
+        if hidden > 0 && hidden == offsets.len() {
+            if let Some(status) = self.data.get_mut(STATUS) {
+                *status &= !CAPABILITIES_LIST;
+            }
+        }
+        hidden
+    }
+    /// Unlinks all extended capabilities with `id` from the Extended Capabilities List and
+    /// zeroes them, returns number of hidden extended capabilities
+    ///
+    /// The first extended capability at 100h can not be unlinked, its header is replaced with
+    /// Capability ID 0000h and Capability Version 0h pointing to the next capability.
+    pub fn hide_extended_capability(&mut self, id: u16) -> usize {
+        let offsets = self.extended_capabilities();
+        let mut hidden = 0;
+        let mut prev = None;
+        for &offset in offsets.iter() {
+            let header = self.ecap_header(offset);
+            if header.extended_capability_id != id {
+                prev = Some(offset);
+                continue;
+            }
+            let next = header.next_capability_offset as u32;
+            let end = offsets
+                .iter()
+                .copied()
+                .filter(|&o| o > offset)
+                .min()
+                .unwrap_or(self.data.len());
+            let start = match prev {
+                Some(prev) => {
+                    let dword = self.ecap_dword(prev) & 0x000f_ffff | next << 20;
+                    self.set_ecap_dword(prev, dword);
+                    offset
+                }
+                None => {
+                    self.set_ecap_dword(offset, next << 20);
+                    prev = Some(offset);
+                    offset + ExtendedCapabilityHeader::SIZE
+                }
+            };
+            if let Some(bytes) = self.data.get_mut(start..end) {
+                bytes.fill(0);
+            }
+            hidden += 1;
+        }
+        hidden
+    }
+    /// Sets Capability Version of all extended capabilities with `id`, keeping Capability ID and
+    /// Next Capability Offset, returns number of renumbered extended capabilities
+    pub fn set_extended_capability_version(&mut self, id: u16, version: u8) -> usize {
+        let offsets = self.extended_capabilities();
+        let mut renumbered = 0;
+        for &offset in offsets.iter() {
+            let header = self.ecap_header(offset);
+            if header.extended_capability_id != id {
+                continue;
+            }
+            let header = ExtendedCapabilityHeader {
+                capability_version: version,
+                ..header
+            };
+            self.set_ecap_dword(offset, header.into());
+            renumbered += 1;
+        }
+        renumbered
+    }
+    /// Turns all Advanced Error Reporting capabilities into version 1 structures, returns
+    /// number of downgraded capabilities
+    ///
+    /// Multiple Header Recording, TLP Prefix Log Present and Completion Timeout Prefix/Header
+    /// Log Capable bits are cleared and TLP Prefix Log is zeroed.
+    pub fn downgrade_advanced_error_reporting(&mut self) -> usize {
+        let offsets = self.extended_capabilities();
+        let downgraded = self.set_extended_capability_version(AER_ID, 1);
+        for &offset in offsets.iter() {
+            if self.ecap_header(offset).extended_capability_id != AER_ID {
+                continue;
+            }
+            let caps_and_control = offset + AER_CAPS_AND_CONTROL;
+            if let Some(bytes) = self.data.get_mut(caps_and_control..caps_and_control + 2) {
+                let mut word = [0; 2];
+                word.copy_from_slice(bytes);
+                let word = u16::from_le_bytes(word) & !AER_V2_BITS;
+                bytes.copy_from_slice(&word.to_le_bytes());
+            }
+            // TLP Prefix Log may be absent, it never overlaps the next structure
+            let end = offsets
+                .iter()
+                .copied()
+                .filter(|&o| o > offset)
+                .min()
+                .unwrap_or(self.data.len())
+                .min(offset + AER_TLP_PREFIX_LOG_END);
+            if let Some(bytes) = self.data.get_mut(offset + AER_TLP_PREFIX_LOG..end) {
+                bytes.fill(0);
+            }
+        }
+        downgraded
+    }
+    /// Capabilities List offsets starting from pointer at `link`
+    fn capabilities(&self, mut link: usize, end: usize) -> Offsets<MAX_CAPS> {
+        let mut offsets = Offsets::default();
+        while let Some(&ptr) = self.data.get(link) {
+            // Bottom two bits are reserved
+            let ptr = (ptr & !0b11) as usize;
+            if ptr < DDR_OFFSET || ptr + Capability::HEADER_SIZE > end || !offsets.push(ptr) {
+                break;
+            }
+            link = ptr + 1;
+        }
+        offsets
+    }
+    /// Extended Capabilities List offsets
+    fn extended_capabilities(&self) -> Offsets<MAX_ECAPS> {
+        let mut offsets = Offsets::default();
+        let mut offset = ECS_OFFSET;
+        while offset >= ECS_OFFSET
+            && offset + ExtendedCapabilityHeader::SIZE <= self.data.len()
+            && self.ecap_dword(offset) != 0
+            && offsets.push(offset)
+        {
+            offset = self.ecap_header(offset).next_capability_offset as usize & !0b11;
+        }
+        offsets
+    }
+    /// Header dword at `offset`, zero past the end of data
+    fn ecap_dword(&self, offset: usize) -> u32 {
+        self.data
+            .get(offset..offset + ExtendedCapabilityHeader::SIZE)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .unwrap_or(0)
+    }
+    fn set_ecap_dword(&mut self, offset: usize, dword: u32) {
+        if let Some(bytes) = self
+            .data
+            .get_mut(offset..offset + ExtendedCapabilityHeader::SIZE)
+        {
+            bytes.copy_from_slice(&dword.to_le_bytes());
+        }
+    }
+    fn ecap_header(&self, offset: usize) -> ExtendedCapabilityHeader {
+        self.ecap_dword(offset).into()
+    }
+}
+
+/// Fixed capacity list of unique offsets
+#[derive(Debug)]
+struct Offsets<const N: usize> {
+    data: [usize; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Offsets<N> {
+    fn default() -> Self {
+        Self {
+            data: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Offsets<N> {
+    /// Appends `offset`, returns `false` if it is already listed or capacity is exceeded
+    fn push(&mut self, offset: usize) -> bool {
+        if self.contains(&offset) {
+            return false;
+        }
+        match self.data.get_mut(self.len) {
+            Some(slot) => {
+                *slot = offset;
+                self.len += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<const N: usize> Deref for Offsets<N> {
+    type Target = [usize];
+
+    fn deref(&self) -> &Self::Target {
+        self.data.get(..self.len).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{synthetic, ConfigurationSpace};
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    fn ids(data: &[u8]) -> (Vec<u8>, Vec<u16>) {
+        let cs = ConfigurationSpace::try_from(data).unwrap();
+        let caps = cs
+            .capabilities()
+            .map(|cap| data[u16::from(cap.unwrap().pointer) as usize])
+            .collect();
+        let ecaps = cs
+            .extended_capabilities()
+            .unwrap()
+            .map(|ecap| ecap.unwrap().id())
+            .collect();
+        (caps, ecaps)
+    }
+
+    #[test]
+    fn hide_capability() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        let mut editor = ConfigSpaceEditor::new(&mut data);
+        assert_eq!(0, editor.hide_capability(0x05));
+        // PCI Express is the first
+        assert_eq!(1, editor.hide_capability(0x10));
+        assert_eq!(synthetic::INTERRUPT_PTR, data[0x34]);
+        assert!(data[0x40..0x80].iter().all(|&b| b == 0));
+        assert_eq!(vec![0x11], ids(&data).0);
+
+        let mut editor = ConfigSpaceEditor::new(&mut data);
+        assert_eq!(1, editor.hide_capability(0x11));
+        assert_eq!(0, data[0x34]);
+        assert_eq!(0, data[STATUS] & CAPABILITIES_LIST);
+        assert!(data[0x40..0x100].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn hide_extended_capability() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        let mut editor = ConfigSpaceEditor::new(&mut data);
+        // SR-IOV in the middle of the list
+        assert_eq!(1, editor.hide_extended_capability(0x0010));
+        // AER is the first
+        assert_eq!(1, editor.hide_extended_capability(0x0001));
+        // Last one
+        let (last_offset, last_id) = *synthetic::ENDPOINT_EXTENDED_CAPABILITIES.last().unwrap();
+        assert_eq!(1, editor.hide_extended_capability(last_id));
+        let sample = synthetic::ENDPOINT_EXTENDED_CAPABILITIES
+            .iter()
+            .map(|&(_, id)| id)
+            .filter(|id| ![0x0010, last_id].contains(id))
+            .map(|id| if id == 0x0001 { 0 } else { id })
+            .collect::<Vec<_>>();
+        assert_eq!(sample, ids(&data).1);
+        // AER body and whole SR-IOV are zeroed, AER header links to ARI
+        assert_eq!([0x00, 0x00, 0x80, 0x14], data[0x100..0x104]);
+        assert!(data[0x104..0x148].iter().all(|&b| b == 0));
+        assert!(data[0x170..0x1b0].iter().all(|&b| b == 0));
+        assert!(data[last_offset as usize..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn extended_capability_version() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        let before = ids(&data);
+        let mut editor = ConfigSpaceEditor::new(&mut data);
+        assert_eq!(0, editor.set_extended_capability_version(0x0002, 1));
+        assert_eq!(1, editor.set_extended_capability_version(0x0018, 3));
+        // Capability ID and Next Capability Offset are kept
+        assert_eq!([0x18, 0x00, 0x83, 0x1b], data[0x1b0..0x1b4]);
+        assert_eq!(before, ids(&data));
+    }
+
+    #[test]
+    fn downgrade_advanced_error_reporting() {
+        let mut data = synthetic::endpoint_with_extended_capabilities();
+        // TLP Prefix Log Present, Multiple Header Recording Capable, ECRC Generation Capable
+        data[0x118..0x11a].copy_from_slice(&0x0a20u16.to_le_bytes());
+        data[0x138..0x148].fill(0xff);
+        let before = ids(&data);
+        let mut editor = ConfigSpaceEditor::new(&mut data);
+        assert_eq!(1, editor.downgrade_advanced_error_reporting());
+        assert_eq!([0x01, 0x00, 0x81, 0x14], data[0x100..0x104]);
+        assert_eq!([0x20, 0x00], data[0x118..0x11a]);
+        assert!(data[0x138..0x148].iter().all(|&b| b == 0));
+        // ARI is not touched
+        assert_eq!([0x0e, 0x00, 0x01, 0x15], data[0x148..0x14c]);
+        assert_eq!(before, ids(&data));
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let aer = cs.extended_capabilities().unwrap().next().unwrap().unwrap();
+        assert_eq!(1, aer.version);
+    }
+
+    #[test]
+    fn looped_list() {
+        let mut data = synthetic::endpoint_with_msix(1);
+        // MSI-X points to PCI Express
+        data[0x81] = synthetic::PCIE_PTR;
+        assert_eq!(1, ConfigSpaceEditor::new(&mut data).hide_capability(0x11));
+        assert_eq!(0x40, data[0x41]);
+    }
+}
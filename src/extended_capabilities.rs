@@ -24,7 +24,7 @@ Extended Capabilities list:
 - [x] [Alternative Routing-ID Interpretation (ARI)](alternative_routing_id_interpolation) (000Eh)
 - [x] [Address Translation Services (ATS)](address_translation_services) (000Fh)
 - [x] [Single Root I/O Virtualization (SR-IOV)](single_root_io_virtualization) (0010h)
-- [ ] [Multi-Root I/O Virtualization (MR-IOV)](multi_root_io_virtualization) (0011h)
+- [x] [Multi-Root I/O Virtualization (MR-IOV)](multi_root_io_virtualization) (0011h)
 - [x] [Multicast](multicast) (0012h)
 - [x] [Page Request Interface (PRI)](page_request_interface) (0013h)
 - [x] [Reserved for AMD](reserved_for_amd) (0014h)
@@ -44,16 +44,16 @@ Extended Capabilities list:
 - [x] [Readiness Time Reporting](readiness_time_reporting) (0022h)
 - [x] [Designated Vendor-Specific Extended Capability](designated_vendor_specific_extended_capability) (0023h)
 - [x] [VF Resizable BAR](vf_resizable_bar) (0024h)
-- [ ] [Data Link Feature](data_link_feature) (0025h)
-- [ ] [Physical Layer 16.0 GT/s](physical_layer_16_gtps) (0026h)
-- [ ] [Lane Margining at the Receiver](lane_margining_at_the_receiver) (0027h)
+- [x] [Data Link Feature](data_link_feature) (0025h)
+- [x] [Physical Layer 16.0 GT/s](physical_layer_16_gtps) (0026h)
+- [x] [Lane Margining at the Receiver](lane_margining_at_the_receiver) (0027h)
 - [ ] [Hierarchy ID](hierarchy_id) (0028h)
-- [ ] [Native PCIe Enclosure Management (NPEM)](native_pcie_enclosure_management) (0029h)
-- [ ] [Physical Layer 32.0 GT/s](physical_layer_32_gtps) (002Ah)
+- [x] [Native PCIe Enclosure Management (NPEM)](native_pcie_enclosure_management) (0029h)
+- [x] [Physical Layer 32.0 GT/s](physical_layer_32_gtps) (002Ah)
 - [ ] [Alternate Protocol](alternate_protocol) (002Bh)
 - [ ] [System Firmware Intermediary (SFI)](system_firmware_intermediary) (002Ch)
 - [ ] [Shadow Functions](shadow_functions) (002Dh)
-- [ ] [Data Object Exchange](data_object_exchange) (002Eh)
+- [x] [Data Object Exchange](data_object_exchange) (002Eh)
 - [ ] [Device 3](device_3) (002Fh)
 - [ ] [Integrity and Data Encryption (IDE)](integrity_and_data_encryption) (0030h)
 - [ ] [Physical Layer 64.0 GT/s Capability](physical_layer_64_gtps) (0031h)
@@ -63,6 +63,8 @@ Extended Capabilities list:
 
 Others Reserved
 
+Physical Layer 16.0, 32.0 and 64.0 GT/s Capabilities share [physical_layer] registers.
+
 ## Example
 lspci out:
 ```plaintext
@@ -70,6 +72,7 @@ Capabilities: [100 v1] Device Serial Number 11-22-33-44-55-66-77-88
 ```
 pcics extended capabilities:
 ```rust
+# use pcics::ConfigOffset;
 # use pcics::extended_capabilities::{
 #     DeviceSerialNumber,
 #     ExtendedCapabilities,
@@ -86,7 +89,7 @@ let result = ExtendedCapabilities::new(&data)
 let sample = vec![
     ExtendedCapability {
         version: 1,
-        offset: 0x100,
+        offset: ConfigOffset(0x100),
         kind: ExtendedCapabilityKind::DeviceSerialNumber(DeviceSerialNumber {
             lower_dword: 0x88776655,
             upper_dword: 0x44332211,
@@ -94,6 +97,55 @@ let sample = vec![
     },
 ];
 ```
+
+## Slice length
+
+Most structures are parsed from a slice starting right after the Extended Capability Header,
+some of them (documented on their `MIN_SIZE`) need the slice with the header. Slices longer
+than a structure are accepted, trailing bytes are ignored. Every structure has a `MIN_SIZE`
+constant, shorter slices are always rejected and the error states how many bytes were
+required. Structures with optional or variable-length parts may require more data than
+`MIN_SIZE`, depending on actual register values.
+Structures of a bounded size also have a `SIZE` constant, measured the same way as their
+`MIN_SIZE`: the size with all optional registers present. It equals `MIN_SIZE` for fixed-size
+structures.
+
+```rust
+# use pcics::extended_capabilities::LatencyToleranceReporting;
+let data = [0x00, 0x10, 0x00, 0x10, 0xff];
+assert!(LatencyToleranceReporting::try_from(&data[..]).is_ok());
+
+let result = LatencyToleranceReporting::try_from(&data[..3]).unwrap_err();
+assert_eq!(LatencyToleranceReporting::MIN_SIZE, result.size);
+assert_eq!("Latency Tolerance Reporting (4 bytes)", result.to_string());
+assert_eq!(LatencyToleranceReporting::SIZE, LatencyToleranceReporting::MIN_SIZE);
+```
+
+## Strict parsing
+
+Like [Capabilities::strict](crate::Capabilities::strict), [ExtendedCapabilities::strict]
+reports extended capabilities with a
+[reserved encoding](ExtendedCapabilityKind::reserved_encoding) as errors:
+
+```rust
+# use pcics::{extended_capabilities::ExtendedCapabilityError, ConfigOffset, ConfigurationSpace};
+# let data = include_bytes!(concat!(
+#     env!("CARGO_MANIFEST_DIR"),
+#     "/tests/data/device/8086_2030/config"
+# ));
+let mut data = data.to_vec();
+// Extended Capability ID 00FFh is not assigned
+data[0x100] = 0xff;
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+assert!(cs.extended_capabilities().unwrap().all(|ecap| ecap.is_ok()));
+
+let result = cs.extended_capabilities().unwrap().strict().next();
+let sample = ExtendedCapabilityError::ReservedEncoding {
+    offset: ConfigOffset(0x100),
+    field: "Capability ID",
+};
+assert_eq!(Some(Err(sample)), result);
+```
 */
 
 
@@ -101,111 +153,359 @@ let sample = vec![
 use heterob::{P3, bit_numbering::{LsbInto, Lsb}, endianness::FromLeBytes};
 use snafu::prelude::*;
 
-use super::ECS_OFFSET;
+use super::{ConfigOffset, ECS_LENGTH, EXTENDED_CONFIG_SPACE_SIZE};
 
 /// Extended Capability Header length in bytes
 pub const ECH_BYTES: usize = 4;
 
 
 /// Extended capability parsing error
+///
+/// Variants caused by short data carry the number of bytes available after the Extended
+/// Capability Header, see [ExtendedCapabilityError::offset], [ExtendedCapabilityError::needed]
+/// and [ExtendedCapabilityError::available]
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ExtendedCapabilityError {
-    #[snafu(display("extended capability offset should be greater than 0xFF"))]
-    Offset,
-    #[snafu(display("[{offset:03x}] extended capability header shorter than u32"))]
-    Header { offset: u16 },
-    #[snafu(display("[{offset:03x}] extended capability has empty header"))]
-    EmptyHeader { offset: u16 },
-    #[snafu(display("[{offset:03x}] {source} data read error"))]
-    Data { offset: u16, source: ExtendedCapabilityDataError },
-    #[snafu(display("[{offset:03x}] Root Complex Link Declaration error: {source}"))]
+    /// Next Capability Offset of the capability at `offset` points into PCI Configuration Space,
+    /// the list is terminated
+    #[snafu(display("[{offset}] next capability offset {next:#x} should be greater than 0xFF"))]
+    Offset { offset: ConfigOffset, next: u16 },
+    /// More than `limit` extended capabilities in the list, the list is terminated
+    #[snafu(display("[{offset}] extended capabilities list is longer than {limit} entries"))]
+    Limit { offset: ConfigOffset, limit: usize },
+    /// Extended capability decoded by a [strict](ExtendedCapabilities::strict) iterator has a
+    /// reserved or contradictory encoding in `field`
+    #[snafu(display("[{offset}] {field} has a reserved or contradictory encoding"))]
+    ReservedEncoding { offset: ConfigOffset, field: &'static str },
+    #[snafu(display("[{offset}] extended capability header shorter than u32 ({available} bytes)"))]
+    Header { offset: ConfigOffset, available: usize },
+    #[snafu(display("[{offset}] extended capability has empty header"))]
+    EmptyHeader { offset: ConfigOffset },
+    #[snafu(display("[{offset}] {source} data read error ({available} bytes available)"))]
+    Data {
+        offset: ConfigOffset,
+        /// Bytes after the Extended Capability Header required by the structure
+        needed: usize,
+        available: usize,
+        source: ExtendedCapabilityDataError,
+    },
+    #[snafu(display("[{offset}] Root Complex Link Declaration error: {source} ({available} bytes available)"))]
     RootComplexLinkDeclaration {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: root_complex_link_declaration::RootComplexLinkDeclarationError,
     },
-    #[snafu(display("[{offset:03x}] Multi-Function Virtual Channel error: {source}"))]
-    MultifunctionVirtualChannel {
-        offset: u16,
-        source: multifunction_virtual_channel::MultifunctionVirtualChannelError,
-    },
-    #[snafu(display("[{offset:03x}] Single Root I/O Virtualization error: {source}"))]
+    #[snafu(display("[{offset}] Single Root I/O Virtualization error: {source} ({available} bytes available)"))]
     SingleRootIoVirtualization {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: single_root_io_virtualization::SingleRootIoVirtualizationError,
     },
-    #[snafu(display("[{offset:03x}] Advanced Error Reporting error: {source}"))]
+    #[snafu(display("[{offset}] Advanced Error Reporting error: {source} ({available} bytes available)"))]
     AdvancedErrorReporting {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: advanced_error_reporting::AdvancedErrorReportingError,
     },
-    #[snafu(display("[{offset:03x}] Downstream Port Containment error: {source}"))]
+    #[snafu(display("[{offset}] Downstream Port Containment error: {source} ({available} bytes available)"))]
     DownstreamPortContainment {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: downstream_port_containment::DownstreamPortContainmentError,
     },
-    #[snafu(display("[{offset:03x}] Resizable BAR error: {source}"))]
+    #[snafu(display("[{offset}] Resizable BAR error: {source} ({available} bytes available)"))]
     ResizableBar {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: resizable_bar::ResizableBarError,
     },
-    #[snafu(display("[{offset:03x}] Dynamic Power Allocation error: {source}"))]
+    #[snafu(display("[{offset}] Dynamic Power Allocation error: {source} ({available} bytes available)"))]
     DynamicPowerAllocation {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: dynamic_power_allocation::DynamicPowerAllocationError,
     },
-    #[snafu(display("[{offset:03x}] Protocol Multiplexing error: {source}"))]
+    #[snafu(display("[{offset}] Protocol Multiplexing error: {source} ({available} bytes available)"))]
     ProtocolMultiplexing {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: protocol_multiplexing::ProtocolMultiplexingError,
     },
-    #[snafu(display("[{offset:03x}] Designated Vendor-Specific Extended Capabilities error: {source}"))]
+    #[snafu(display("[{offset}] Designated Vendor-Specific Extended Capabilities error: {source} ({available} bytes available)"))]
     DesignatedVendorSpecificExtendedCapability {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: designated_vendor_specific_extended_capability::DesignatedVendorSpecificExtendedCapabilityError,
     },
-    #[snafu(display("[{offset:03x}] VF Resizable BAR error: {source}"))]
+    #[snafu(display("[{offset}] VF Resizable BAR error: {source} ({available} bytes available)"))]
     VfResizableBar {
-        offset: u16,
+        offset: ConfigOffset,
+        available: usize,
         source: vf_resizable_bar::VfResizableBarError,
     },
 }
+impl ExtendedCapabilityError {
+    /// Absolute offset of the failed read: extended capability for
+    /// [ExtendedCapabilityError::Header], data after the Extended Capability Header for short
+    /// data variants
+    pub fn offset(&self) -> ConfigOffset {
+        match self {
+            Self::Offset { offset, .. }
+            | Self::Limit { offset, .. }
+            | Self::ReservedEncoding { offset, .. }
+            | Self::Header { offset, .. }
+            | Self::EmptyHeader { offset } => *offset,
+            Self::Data { offset, .. }
+            | Self::RootComplexLinkDeclaration { offset, .. }
+            | Self::SingleRootIoVirtualization { offset, .. }
+            | Self::AdvancedErrorReporting { offset, .. }
+            | Self::DownstreamPortContainment { offset, .. }
+            | Self::ResizableBar { offset, .. }
+            | Self::DynamicPowerAllocation { offset, .. }
+            | Self::ProtocolMultiplexing { offset, .. }
+            | Self::DesignatedVendorSpecificExtendedCapability { offset, .. }
+            | Self::VfResizableBar { offset, .. } => *offset + ECH_BYTES as u16,
+        }
+    }
+    /// Number of bytes at [offset](Self::offset) required to parse structure
+    pub fn needed(&self) -> Option<usize> {
+        // Decoders of these structures are given the Extended Capability Header too
+        let with_header = |needed: Option<usize>| needed.map(|n| n.saturating_sub(ECH_BYTES));
+        match self {
+            Self::Offset { .. }
+            | Self::Limit { .. }
+            | Self::ReservedEncoding { .. }
+            | Self::EmptyHeader { .. }
+            | Self::SingleRootIoVirtualization { .. } => None,
+            Self::Header { .. } => Some(ECH_BYTES),
+            Self::Data { needed, .. } => Some(*needed),
+            Self::RootComplexLinkDeclaration { source, .. } => source.needed(),
+            Self::AdvancedErrorReporting { source, .. } => source.needed(),
+            Self::DownstreamPortContainment { source, .. } => source.needed(),
+            Self::ResizableBar { source, .. } => with_header(source.needed()),
+            Self::DynamicPowerAllocation { source, .. } => with_header(source.needed()),
+            Self::ProtocolMultiplexing { source, .. } => with_header(source.needed()),
+            Self::DesignatedVendorSpecificExtendedCapability { source, .. } => {
+                with_header(source.needed())
+            }
+            Self::VfResizableBar { source, .. } => with_header(source.needed()),
+        }
+    }
+    /// Number of bytes available at [offset](Self::offset)
+    pub fn available(&self) -> Option<usize> {
+        match self {
+            Self::Offset { .. }
+            | Self::Limit { .. }
+            | Self::ReservedEncoding { .. }
+            | Self::EmptyHeader { .. } => None,
+            Self::Header { available, .. }
+            | Self::Data { available, .. }
+            | Self::RootComplexLinkDeclaration { available, .. }
+            | Self::SingleRootIoVirtualization { available, .. }
+            | Self::AdvancedErrorReporting { available, .. }
+            | Self::DownstreamPortContainment { available, .. }
+            | Self::ResizableBar { available, .. }
+            | Self::DynamicPowerAllocation { available, .. }
+            | Self::ProtocolMultiplexing { available, .. }
+            | Self::DesignatedVendorSpecificExtendedCapability { available, .. }
+            | Self::VfResizableBar { available, .. } => Some(*available),
+        }
+    }
+}
 
 
 /// Common error for reading capability data
 #[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
 #[snafu(display("{name} ({size} bytes)"))]
 pub struct ExtendedCapabilityDataError {
-    name: &'static str,
-    size: usize,
+    /// Extended capability name
+    pub name: &'static str,
+    /// Required number of bytes
+    pub size: usize,
 }
 
 /// An iterator through *Extended Capabilities List*
+///
+/// At most [MAX_ENTRIES](Self::MAX_ENTRIES) extended capabilities are yielded, a longer list
+/// ends with [ExtendedCapabilityError::Limit]. Every structure is decoded in place from the
+/// extended configuration space, so both entries and bytes visited are bounded on looped or
+/// adversarial lists.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ExtendedCapabilities<'a> {
     /// Extended Configuration Space
     ecs: &'a [u8],
     /// PCI Configuration Space offset
     next_capability_offset: u16,
+    /// Offset of the last visited capability
+    offset: u16,
+    /// Maximum number of yielded capabilities
+    limit: usize,
+    /// Number of capabilities left to yield
+    remaining: usize,
+    /// Capabilities with reserved encodings are yielded as errors
+    strict: bool,
 }
 impl<'a> ExtendedCapabilities<'a> {
+    /// Extended capabilities are DWORD aligned, so a list without loops has at most as many
+    /// entries as there are DWORDs in extended configuration space
+    pub const MAX_ENTRIES: usize = ECS_LENGTH / ECH_BYTES;
     pub fn new(ecs: &'a [u8]) -> Self {
-        Self { ecs, next_capability_offset: ECS_OFFSET as u16 }
+        Self {
+            ecs,
+            next_capability_offset: ConfigOffset::ECS.get(),
+            offset: 0,
+            limit: Self::MAX_ENTRIES,
+            remaining: Self::MAX_ENTRIES,
+            strict: false,
+        }
+    }
+    /// Yield at most `limit` extended capabilities before [ExtendedCapabilityError::Limit],
+    /// `limit` is capped by [MAX_ENTRIES](Self::MAX_ENTRIES)
+    pub fn with_limit(self, limit: usize) -> Self {
+        let limit = limit.min(Self::MAX_ENTRIES);
+        Self {
+            limit,
+            remaining: limit,
+            ..self
+        }
+    }
+    /// Yield [ExtendedCapabilityError::ReservedEncoding] instead of extended capabilities with
+    /// a [reserved encoding](ExtendedCapabilityKind::reserved_encoding)
+    pub fn strict(self) -> Self {
+        Self {
+            strict: true,
+            ..self
+        }
+    }
+    /// An iterator through extended capability headers only, capability bodies are not decoded
+    pub fn headers(self) -> ExtendedCapabilityHeaders<'a> {
+        ExtendedCapabilityHeaders {
+            ecs: self.ecs,
+            next_capability_offset: self.next_capability_offset,
+            remaining: Self::MAX_ENTRIES,
+        }
+    }
+    /// End of the structure at `offset`: offset of the next extended capability in address
+    /// order, or end of extended configuration space
+    pub fn structure_end(self, offset: ConfigOffset) -> ConfigOffset {
+        self.headers()
+            .map(|(next, _)| next)
+            .filter(|&next| next > offset)
+            .min()
+            .unwrap_or(ConfigOffset(EXTENDED_CONFIG_SPACE_SIZE as u16))
+    }
+    /// Decodes the single extended capability at `offset`, taken from
+    /// [headers](Self::headers), without walking the list
+    pub(crate) fn parse_at(self, offset: ConfigOffset) -> ExtendedCapabilityResult<'a> {
+        if offset.ecs_relative().is_none() {
+            return Err(ExtendedCapabilityError::Header {
+                offset,
+                available: 0,
+            });
+        }
+        let mut next_capability_offset = offset.get();
+        parse_ecap(self.ecs, &mut next_capability_offset)
+    }
+    /// An iterator through Designated Vendor-Specific Extended Capabilities with their offsets
+    ///
+    /// Capabilities failed to decode are skipped.
+    pub fn dvsecs(self) -> Dvsecs<'a> {
+        Dvsecs { ecaps: self }
+    }
+    /// Extended configuration space the list was created with
+    pub(crate) fn data(self) -> &'a [u8] {
+        self.ecs
     }
 }
 impl<'a> Iterator for ExtendedCapabilities<'a> {
     type Item = ExtendedCapabilityResult<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.next_capability_offset == 0 {
+        let next = self.next_capability_offset;
+        if next == 0 {
             return None;
         }
+        // Non-zero offset into PCI Configuration Space is reported once and ends the list
+        if ConfigOffset(next).ecs_relative().is_none() {
+            self.next_capability_offset = 0;
+            let offset = ConfigOffset(self.offset);
+            return Some(Err(ExtendedCapabilityError::Offset { offset, next }));
+        }
+        if self.remaining == 0 {
+            self.next_capability_offset = 0;
+            let offset = ConfigOffset(next);
+            let limit = self.limit;
+            return Some(Err(ExtendedCapabilityError::Limit { offset, limit }));
+        }
+        self.remaining -= 1;
+        self.offset = next;
         match parse_ecap(self.ecs, &mut self.next_capability_offset) {
             Err(ExtendedCapabilityError::EmptyHeader { .. }) => None,
+            Ok(ecap) if self.strict => match ecap.kind.reserved_encoding() {
+                Some(field) => Some(Err(ExtendedCapabilityError::ReservedEncoding {
+                    offset: ecap.offset,
+                    field,
+                })),
+                None => Some(Ok(ecap)),
+            },
             v => Some(v),
         }
     }
 }
 
+/// An iterator through [DesignatedVendorSpecificExtendedCapability]s, see
+/// [ExtendedCapabilities::dvsecs]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dvsecs<'a> {
+    ecaps: ExtendedCapabilities<'a>,
+}
+impl<'a> Iterator for Dvsecs<'a> {
+    type Item = (ConfigOffset, DesignatedVendorSpecificExtendedCapability<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ecaps.find_map(|ecap| match ecap {
+            Ok(ExtendedCapability {
+                kind: ExtendedCapabilityKind::DesignatedVendorSpecificExtendedCapability(dvsec),
+                offset,
+                ..
+            }) => Some((offset, dvsec)),
+            _ => None,
+        })
+    }
+}
+
+/// An iterator through *Extended Capabilities List* headers
+///
+/// Yields extended capability offsets with their [ExtendedCapabilityHeader]. Unlike
+/// [ExtendedCapabilities] iteration stops silently on invalid offset or truncated header, and
+/// after as many entries as there are DWORDs in extended configuration space, so looped lists
+/// are finite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedCapabilityHeaders<'a> {
+    ecs: &'a [u8],
+    next_capability_offset: u16,
+    remaining: usize,
+}
+impl<'a> Iterator for ExtendedCapabilityHeaders<'a> {
+    type Item = (ConfigOffset, ExtendedCapabilityHeader);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = ConfigOffset(self.next_capability_offset);
+        let ecs_offset = offset.ecs_relative().filter(|_| self.remaining > 0)?;
+        self.next_capability_offset = 0;
+        let dword = self
+            .ecs
+            .get(ecs_offset..ecs_offset + ECH_BYTES)
+            .map(|slice| u32::from_le_bytes(slice.try_into().unwrap()))
+            .filter(|&dword| dword != 0)?;
+        let header = ExtendedCapabilityHeader::from(dword);
+        self.next_capability_offset = header.next_capability_offset;
+        self.remaining -= 1;
+        Some((offset, header))
+    }
+}
+
 /// PCI Express Extended Capability Header
 ///
 /// All PCI Express Extended Capabilities must begin with a PCI Express
@@ -244,6 +544,14 @@ impl From<u32> for ExtendedCapabilityHeader {
     }
 }
 
+impl From<ExtendedCapabilityHeader> for u32 {
+    fn from(header: ExtendedCapabilityHeader) -> Self {
+        header.extended_capability_id as u32
+            | (header.capability_version as u32 & 0xf) << 16
+            | (header.next_capability_offset as u32 & 0xfff) << 20
+    }
+}
+
 impl ExtendedCapabilityHeader {
     /// Extended Capability Header is DWORD
     pub const SIZE: usize = 4;
@@ -261,28 +569,56 @@ type ExtendedCapabilityResult<'a> = Result<ExtendedCapability<'a>, ExtendedCapab
 fn parse_ecap<'a>(
     bytes: &'a [u8],
     next_capability_offset: &mut u16,
+) -> ExtendedCapabilityResult<'a> {
+    // Offset is checked by the iterator
+    let ecs_offset = *next_capability_offset as usize - ConfigOffset::ECS.get() as usize;
+    parse_ecap_at(
+        bytes.get(ecs_offset..).unwrap_or_default(),
+        next_capability_offset,
+    )
+}
+
+/// Decodes the extended capability at valid `next_capability_offset` from `bytes` starting at
+/// the extended capability header
+pub(crate) fn parse_ecap_at<'a>(
+    bytes: &'a [u8],
+    next_capability_offset: &mut u16,
 ) -> ExtendedCapabilityResult<'a> {
     let offset = *next_capability_offset;
-    let ecs_offset = (offset as usize).checked_sub(ECS_OFFSET).ok_or_else(|| {
-        *next_capability_offset = 0;
-        ExtendedCapabilityError::Offset
-    })?;
-    let ecap_data_offset = ecs_offset + ECH_BYTES;
     let dword = &bytes
-        .get(ecs_offset..ecap_data_offset)
+        .get(..ECH_BYTES)
         // We can use unwrap on already length checked slice
         .map(|slice| u32::from_le_bytes(slice.try_into().unwrap()))
         .ok_or_else(|| {
             *next_capability_offset = 0;
-            ExtendedCapabilityError::Header { offset }
+            ExtendedCapabilityError::Header {
+                offset: offset.into(),
+                available: bytes.len(),
+            }
         })?;
     if *dword == 0 {
-        return Err(ExtendedCapabilityError::EmptyHeader { offset });
+        return Err(ExtendedCapabilityError::EmptyHeader { offset: offset.into() });
     }
     let (id, version, next_cap_offset) = P3::<_, 16, 4, 12>(*dword).lsb_into();
     *next_capability_offset = next_cap_offset;
 
-    let ecap_data = &bytes[ecap_data_offset..];
+    let ecap_bytes = bytes;
+    // Offset is within the header read above
+    let ecap_data = bytes.get(ECH_BYTES..).unwrap_or_default();
+    let available = ecap_data.len();
+    // Some structures are decoded with the header, their sizes include it
+    let data = |source: ExtendedCapabilityDataError| ExtendedCapabilityError::Data {
+        offset: offset.into(),
+        needed: source.size,
+        available,
+        source,
+    };
+    let data_with_header = |source: ExtendedCapabilityDataError| ExtendedCapabilityError::Data {
+        offset: offset.into(),
+        needed: source.size.saturating_sub(ECH_BYTES),
+        available,
+        source,
+    };
 
     use ExtendedCapabilityKind as Kind;
     let kind = match id {
@@ -290,156 +626,182 @@ fn parse_ecap<'a>(
         0x0001 => ecap_data
             .try_into()
             .map(Kind::AdvancedErrorReporting)
-            .context(AdvancedErrorReportingSnafu { offset })?,
+            .context(AdvancedErrorReportingSnafu { offset, available })?,
         0x0002 => ecap_data
             .try_into()
             .map(Kind::VirtualChannel)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0003 => ecap_data
             .try_into()
             .map(Kind::DeviceSerialNumber)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0004 => ecap_data
             .try_into()
             .map(Kind::PowerBudgeting)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0005 => ecap_data
             .try_into()
             .map(Kind::RootComplexLinkDeclaration)
-            .context(RootComplexLinkDeclarationSnafu { offset })?,
+            .context(RootComplexLinkDeclarationSnafu { offset, available })?,
         0x0006 => ecap_data
             .try_into()
             .map(Kind::RootComplexInternalLinkControl)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0007 => ecap_data
             .try_into()
             .map(Kind::RootComplexEventCollectorEndpointAssociation)
-            .context(DataSnafu { offset })?,
-        // MFVC use data with PCI Express Extended Capability Header for simpler calculations
-        0x0008 => bytes
+            .map_err(data)?,
+        #[cfg(feature = "mfvc")]
+        0x0008 => ecap_data
             .try_into()
             .map(Kind::MultifunctionVirtualChannel)
-            .context(MultifunctionVirtualChannelSnafu { offset })?,
+            .map_err(data)?,
         0x0009 => ecap_data
             .try_into()
             .map(Kind::VirtualChannelMfvcPresent)
-            .context(DataSnafu { offset })?,
-        0x000A => bytes
+            .map_err(data)?,
+        0x000A => ecap_bytes
             .try_into()
             .map(Kind::RootComplexRegisterBlockHeader)
-            .context(DataSnafu { offset })?,
+            .map_err(data_with_header)?,
         0x000B => ecap_data
             .try_into()
             .map(Kind::VendorSpecificExtendedCapability)
-            .context(DataSnafu { offset })?,
-        0x000C => bytes
+            .map_err(data)?,
+        0x000C => ecap_bytes
             .try_into()
             .map(Kind::ConfigurationAccessCorrelation)
-            .context(DataSnafu { offset })?,
+            .map_err(data_with_header)?,
         0x000D => ecap_data
             .try_into()
             .map(Kind::AccessControlServices)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x000E => ecap_data
             .try_into()
             .map(Kind::AlternativeRoutingIdInterpretation)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x000F => ecap_data
             .try_into()
             .map(Kind::AddressTranslationServices)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0010 => ecap_data
             .try_into()
             .map(Kind::SingleRootIoVirtualization)
-            .context(DataSnafu { offset })?,
-        0x0011 => Kind::MultiRootIoVirtualization(MultiRootIoVirtualization),
-        0x0012 => bytes
+            .map_err(data)?,
+        0x0011 => ecap_data
+            .try_into()
+            .map(Kind::MultiRootIoVirtualization)
+            .map_err(data)?,
+        0x0012 => ecap_bytes
             .try_into()
             .map(Kind::Multicast)
-            .context(DataSnafu { offset })?,
+            .map_err(data_with_header)?,
         0x0013 => ecap_data
             .try_into()
             .map(Kind::PageRequestInterface)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0014 => Kind::ReservedForAmd(ReservedForAmd),
-        0x0015 => bytes
+        0x0015 => ecap_bytes
             .try_into()
             .map(Kind::ResizableBar)
-            .context(ResizableBarSnafu { offset })?,
-        0x0016 => bytes
+            .context(ResizableBarSnafu { offset, available })?,
+        0x0016 => ecap_bytes
             .try_into()
             .map(Kind::DynamicPowerAllocation)
-            .context(DynamicPowerAllocationSnafu { offset })?,
-        0x0017 => ecap_data
-            .try_into()
-            .map(Kind::TphRequester)
-            .context(DataSnafu { offset })?,
+            .context(DynamicPowerAllocationSnafu { offset, available })?,
+        0x0017 => ecap_data.try_into().map(Kind::TphRequester).map_err(data)?,
         0x0018 => ecap_data
             .try_into()
             .map(Kind::LatencyToleranceReporting)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x0019 => ecap_data
             .try_into()
             .map(Kind::SecondaryPciExpress)
-            .context(DataSnafu { offset })?,
-        0x001A => bytes
+            .map_err(data)?,
+        0x001A => ecap_bytes
             .try_into()
             .map(Kind::ProtocolMultiplexing)
-            .context(ProtocolMultiplexingSnafu { offset })?,
+            .context(ProtocolMultiplexingSnafu { offset, available })?,
         0x001B => ecap_data
             .try_into()
             .map(Kind::ProcessAddressSpaceId)
-            .context(DataSnafu { offset })?,
-        0x001C => bytes
+            .map_err(data)?,
+        0x001C => ecap_bytes
             .try_into()
             .map(Kind::LnRequester)
-            .context(DataSnafu { offset })?,
+            .map_err(data_with_header)?,
         0x001D => ecap_data
             .try_into()
             .map(Kind::DownstreamPortContainment)
-            .context(DownstreamPortContainmentSnafu { offset })?,
+            .context(DownstreamPortContainmentSnafu { offset, available })?,
         0x001E => ecap_data
             .try_into()
             .map(Kind::L1PmSubstates)
-            .context(DataSnafu { offset })?,
+            .map_err(data)?,
         0x001F => ecap_data
             .try_into()
             .map(Kind::PrecisionTimeMeasurement)
-            .context(DataSnafu { offset })?,
-        0x0020 => bytes
+            .map_err(data)?,
+        0x0020 => ecap_bytes
             .try_into()
             .map(Kind::PciExpressOverMphy)
-            .context(DataSnafu { offset })?,
-        0x0021 => bytes
+            .map_err(data_with_header)?,
+        0x0021 => ecap_bytes
             .try_into()
             .map(Kind::FrsQueuing)
-            .context(DataSnafu { offset })?,
-        0x0022 => bytes
+            .map_err(data_with_header)?,
+        0x0022 => ecap_bytes
             .try_into()
             .map(Kind::ReadinessTimeReporting)
-            .context(DataSnafu { offset })?,
-        0x0023 => bytes
+            .map_err(data_with_header)?,
+        0x0023 => ecap_bytes
             .try_into()
             .map(Kind::DesignatedVendorSpecificExtendedCapability)
-            .context(DesignatedVendorSpecificExtendedCapabilitySnafu { offset })?,
-        0x0024 => bytes
+            .context(DesignatedVendorSpecificExtendedCapabilitySnafu { offset, available })?,
+        0x0024 => ecap_bytes
             .try_into()
             .map(Kind::VfResizableBar)
-            .context(VfResizableBarSnafu { offset })?,
-        0x0025 => Kind::DataLinkFeature(DataLinkFeature),
-        0x0026 => Kind::PhysicalLayer16GTps(PhysicalLayer16GTps),
-        0x0027 => Kind::LaneMarginingAtTheReceiver(LaneMarginingAtTheReceiver),
+            .context(VfResizableBarSnafu { offset, available })?,
+        0x0025 => ecap_data
+            .try_into()
+            .map(Kind::DataLinkFeature)
+            .map_err(data)?,
+        0x0026 => ecap_data
+            .try_into()
+            .map(Kind::PhysicalLayer16GTps)
+            .map_err(data)?,
+        0x0027 => ecap_data
+            .try_into()
+            .map(Kind::LaneMarginingAtTheReceiver)
+            .map_err(data)?,
         0x0028 => Kind::HierarchyId(HierarchyId),
-        0x0029 => Kind::NativePcieEnclosureManagement(NativePcieEnclosureManagement),
-        0x002A => Kind::PhysicalLayer32GTps(PhysicalLayer32GTps),
+        0x0029 => ecap_data
+            .try_into()
+            .map(Kind::NativePcieEnclosureManagement)
+            .map_err(data)?,
+        0x002A => ecap_data
+            .try_into()
+            .map(Kind::PhysicalLayer32GTps)
+            .map_err(data)?,
         0x002B => Kind::AlternateProtocol(AlternateProtocol),
         0x002C => Kind::SystemFirmwareIntermediary(SystemFirmwareIntermediary),
+        0x002D => Kind::ShadowFunctions(ShadowFunctions),
+        0x002E => ecap_data
+            .try_into()
+            .map(Kind::DataObjectExchange)
+            .map_err(data)?,
+        0x002F => Kind::Device3(Device3),
+        0x0030 => Kind::IntegrityAndDataEncryption(IntegrityAndDataEncryption),
+        0x0031 => Kind::PhysicalLayer64GTps(PhysicalLayer64GTps),
+        0x0032 => Kind::FlitLogging(FlitLogging),
+        0x0033 => Kind::FlitPerformanceMeasurement(FlitPerformanceMeasurement),
+        0x0034 => Kind::FlitErrorInjection(FlitErrorInjection),
         v => Kind::Reserved(v),
     };
     Ok(ExtendedCapability {
         kind,
         version,
-        offset,
+        offset: offset.into(),
     })
 }
 
@@ -448,73 +810,25 @@ fn parse_ecap<'a>(
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExtendedCapability<'a> {
     pub kind: ExtendedCapabilityKind<'a>,
+    /// Capability Version
+    ///
+    /// Version 0 reported by some devices is kept as is, the structure is decoded by the
+    /// Capability ID regardless of the version.
     pub version: u8,
-    pub offset: u16,
+    /// Extended capability offset in configuration space
+    pub offset: ConfigOffset,
 }
 impl<'a> ExtendedCapability<'a> {
     /// Extended Capability Header length in bytes
     pub const HEADER_SIZE: usize = 4;
+    /// Extended Capability ID, see [ExtendedCapabilityKind::kind_id]
     pub fn id(&self) -> u16 {
-        match self.kind {
-            ExtendedCapabilityKind::Null => 0x0000,
-            ExtendedCapabilityKind::AdvancedErrorReporting(_) => 0x0001,
-            ExtendedCapabilityKind::VirtualChannel(_) => 0x0002,
-            ExtendedCapabilityKind::DeviceSerialNumber(_) => 0x0003,
-            ExtendedCapabilityKind::PowerBudgeting(_) => 0x0004,
-            ExtendedCapabilityKind::RootComplexLinkDeclaration(_) => 0x0005,
-            ExtendedCapabilityKind::RootComplexInternalLinkControl(_) => 0x0006,
-            ExtendedCapabilityKind::RootComplexEventCollectorEndpointAssociation(_) => 0x0007,
-            ExtendedCapabilityKind::MultifunctionVirtualChannel(_) => 0x0008,
-            ExtendedCapabilityKind::VirtualChannelMfvcPresent(_) => 0x0009,
-            ExtendedCapabilityKind::RootComplexRegisterBlockHeader(_) => 0x000A,
-            ExtendedCapabilityKind::VendorSpecificExtendedCapability(_) => 0x000B,
-            ExtendedCapabilityKind::ConfigurationAccessCorrelation(_) => 0x000C,
-            ExtendedCapabilityKind::AccessControlServices(_) => 0x000D,
-            ExtendedCapabilityKind::AlternativeRoutingIdInterpretation(_) => 0x000E,
-            ExtendedCapabilityKind::AddressTranslationServices(_) => 0x000F,
-            ExtendedCapabilityKind::SingleRootIoVirtualization(_) => 0x0010,
-            ExtendedCapabilityKind::MultiRootIoVirtualization(_) => 0x0011,
-            ExtendedCapabilityKind::Multicast(_) => 0x0012,
-            ExtendedCapabilityKind::PageRequestInterface(_) => 0x0013,
-            ExtendedCapabilityKind::ReservedForAmd(_) => 0x0014,
-            ExtendedCapabilityKind::ResizableBar(_) => 0x0015,
-            ExtendedCapabilityKind::DynamicPowerAllocation(_) => 0x0016,
-            ExtendedCapabilityKind::TphRequester(_) => 0x0017,
-            ExtendedCapabilityKind::LatencyToleranceReporting(_) => 0x0018,
-            ExtendedCapabilityKind::SecondaryPciExpress(_) => 0x0019,
-            ExtendedCapabilityKind::ProtocolMultiplexing(_) => 0x001A,
-            ExtendedCapabilityKind::ProcessAddressSpaceId(_) => 0x001B,
-            ExtendedCapabilityKind::LnRequester(_) => 0x001C,
-            ExtendedCapabilityKind::DownstreamPortContainment(_) => 0x001D,
-            ExtendedCapabilityKind::L1PmSubstates(_) => 0x001E,
-            ExtendedCapabilityKind::PrecisionTimeMeasurement(_) => 0x001F,
-            ExtendedCapabilityKind::PciExpressOverMphy(_) => 0x0020,
-            ExtendedCapabilityKind::FrsQueuing(_) => 0x0021,
-            ExtendedCapabilityKind::ReadinessTimeReporting(_) => 0x0022,
-            ExtendedCapabilityKind::DesignatedVendorSpecificExtendedCapability(_) => 0x0023,
-            ExtendedCapabilityKind::VfResizableBar(_) => 0x0024,
-            ExtendedCapabilityKind::DataLinkFeature(_) => 0x0025,
-            ExtendedCapabilityKind::PhysicalLayer16GTps(_) => 0x0026,
-            ExtendedCapabilityKind::LaneMarginingAtTheReceiver(_) => 0x0027,
-            ExtendedCapabilityKind::HierarchyId(_) => 0x0028,
-            ExtendedCapabilityKind::NativePcieEnclosureManagement(_) => 0x0029,
-            ExtendedCapabilityKind::PhysicalLayer32GTps(_) => 0x002A,
-            ExtendedCapabilityKind::AlternateProtocol(_) => 0x002B,
-            ExtendedCapabilityKind::SystemFirmwareIntermediary(_) => 0x002C,
-            ExtendedCapabilityKind::ShadowFunctions(_) => 0x002D,
-            ExtendedCapabilityKind::DataObjectExchange(_) => 0x002E,
-            ExtendedCapabilityKind::Device3(_) => 0x002F,
-            ExtendedCapabilityKind::IntegrityAndDataEncryption(_) => 0x0030,
-            ExtendedCapabilityKind::PhysicalLayer64GTps(_) => 0x0031,
-            ExtendedCapabilityKind::FlitLogging(_) => 0x0032,
-            ExtendedCapabilityKind::FlitPerformanceMeasurement(_) => 0x0033,
-            ExtendedCapabilityKind::FlitErrorInjection(_) => 0x0034,
-            ExtendedCapabilityKind::Reserved(v) => v,
-        }
+        self.kind.kind_id()
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ExtendedCapabilityKind<'a> {
     /// Null Capability – This capability contains no registers other than those in the Extended
     /// Capability Header
@@ -534,6 +848,7 @@ pub enum ExtendedCapabilityKind<'a> {
     /// Root Complex Event Collector Endpoint Association
     RootComplexEventCollectorEndpointAssociation(RootComplexEventCollectorEndpointAssociation),
     /// Multi-Function Virtual Channel (MFVC)
+    #[cfg(feature = "mfvc")]
     MultifunctionVirtualChannel(MultifunctionVirtualChannel<'a>),
     /// Virtual Channel (VC) – used if an MFVC Extended Cap structure is present in the device
     VirtualChannelMfvcPresent(VirtualChannel<'a>),
@@ -596,15 +911,15 @@ pub enum ExtendedCapabilityKind<'a> {
     /// Data Link Feature
     DataLinkFeature(DataLinkFeature),
     /// Physical Layer 16.0 GT/s
-    PhysicalLayer16GTps(PhysicalLayer16GTps),
+    PhysicalLayer16GTps(PhysicalLayer16GTps<'a>),
     /// Lane Margining at the Receiver
-    LaneMarginingAtTheReceiver(LaneMarginingAtTheReceiver),
+    LaneMarginingAtTheReceiver(LaneMarginingAtTheReceiver<'a>),
     /// Hierarchy ID
     HierarchyId(HierarchyId),
     /// Native PCIe Enclosure Management (NPEM)
     NativePcieEnclosureManagement(NativePcieEnclosureManagement),
     /// Physical Layer 32.0 GT/s
-    PhysicalLayer32GTps(PhysicalLayer32GTps),
+    PhysicalLayer32GTps(PhysicalLayer32GTps<'a>),
     /// Alternate Protocol
     AlternateProtocol(AlternateProtocol),
     /// System Firmware Intermediary (SFI)
@@ -628,6 +943,139 @@ pub enum ExtendedCapabilityKind<'a> {
     Reserved(u16),
 }
 
+impl<'a> ExtendedCapabilityKind<'a> {
+    /// Extended Capability ID of the variant, `_` arms of matches on this non-exhaustive enum
+    /// may dispatch on it
+    pub fn kind_id(&self) -> u16 {
+        match *self {
+            Self::Null => 0x0000,
+            Self::AdvancedErrorReporting(_) => 0x0001,
+            Self::VirtualChannel(_) => 0x0002,
+            Self::DeviceSerialNumber(_) => 0x0003,
+            Self::PowerBudgeting(_) => 0x0004,
+            Self::RootComplexLinkDeclaration(_) => 0x0005,
+            Self::RootComplexInternalLinkControl(_) => 0x0006,
+            Self::RootComplexEventCollectorEndpointAssociation(_) => 0x0007,
+            #[cfg(feature = "mfvc")]
+            Self::MultifunctionVirtualChannel(_) => 0x0008,
+            Self::VirtualChannelMfvcPresent(_) => 0x0009,
+            Self::RootComplexRegisterBlockHeader(_) => 0x000A,
+            Self::VendorSpecificExtendedCapability(_) => 0x000B,
+            Self::ConfigurationAccessCorrelation(_) => 0x000C,
+            Self::AccessControlServices(_) => 0x000D,
+            Self::AlternativeRoutingIdInterpretation(_) => 0x000E,
+            Self::AddressTranslationServices(_) => 0x000F,
+            Self::SingleRootIoVirtualization(_) => 0x0010,
+            Self::MultiRootIoVirtualization(_) => 0x0011,
+            Self::Multicast(_) => 0x0012,
+            Self::PageRequestInterface(_) => 0x0013,
+            Self::ReservedForAmd(_) => 0x0014,
+            Self::ResizableBar(_) => 0x0015,
+            Self::DynamicPowerAllocation(_) => 0x0016,
+            Self::TphRequester(_) => 0x0017,
+            Self::LatencyToleranceReporting(_) => 0x0018,
+            Self::SecondaryPciExpress(_) => 0x0019,
+            Self::ProtocolMultiplexing(_) => 0x001A,
+            Self::ProcessAddressSpaceId(_) => 0x001B,
+            Self::LnRequester(_) => 0x001C,
+            Self::DownstreamPortContainment(_) => 0x001D,
+            Self::L1PmSubstates(_) => 0x001E,
+            Self::PrecisionTimeMeasurement(_) => 0x001F,
+            Self::PciExpressOverMphy(_) => 0x0020,
+            Self::FrsQueuing(_) => 0x0021,
+            Self::ReadinessTimeReporting(_) => 0x0022,
+            Self::DesignatedVendorSpecificExtendedCapability(_) => 0x0023,
+            Self::VfResizableBar(_) => 0x0024,
+            Self::DataLinkFeature(_) => 0x0025,
+            Self::PhysicalLayer16GTps(_) => 0x0026,
+            Self::LaneMarginingAtTheReceiver(_) => 0x0027,
+            Self::HierarchyId(_) => 0x0028,
+            Self::NativePcieEnclosureManagement(_) => 0x0029,
+            Self::PhysicalLayer32GTps(_) => 0x002A,
+            Self::AlternateProtocol(_) => 0x002B,
+            Self::SystemFirmwareIntermediary(_) => 0x002C,
+            Self::ShadowFunctions(_) => 0x002D,
+            Self::DataObjectExchange(_) => 0x002E,
+            Self::Device3(_) => 0x002F,
+            Self::IntegrityAndDataEncryption(_) => 0x0030,
+            Self::PhysicalLayer64GTps(_) => 0x0031,
+            Self::FlitLogging(_) => 0x0032,
+            Self::FlitPerformanceMeasurement(_) => 0x0033,
+            Self::FlitErrorInjection(_) => 0x0034,
+            Self::Reserved(v) => v,
+        }
+    }
+    /// Extended capability ID is known, but its structure is not decoded
+    pub fn is_placeholder(&self) -> bool {
+        matches!(
+            self,
+            Self::ReservedForAmd(_)
+                | Self::HierarchyId(_)
+                | Self::AlternateProtocol(_)
+                | Self::SystemFirmwareIntermediary(_)
+                | Self::ShadowFunctions(_)
+                | Self::Device3(_)
+                | Self::IntegrityAndDataEncryption(_)
+                | Self::PhysicalLayer64GTps(_)
+                | Self::FlitLogging(_)
+                | Self::FlitPerformanceMeasurement(_)
+                | Self::FlitErrorInjection(_)
+        )
+    }
+    /// Extended Capability ID is defined by the specification
+    ///
+    /// IDs of decoders disabled by cargo features (e.g. `mfvc`) are known too, though they are
+    /// parsed as [Reserved](Self::Reserved).
+    pub fn is_known_id(id: u16) -> bool {
+        id <= 0x0034
+    }
+    /// Name of the first field with a reserved or contradictory encoding
+    ///
+    /// [Unknown](Self::is_known_id) Extended Capability ID, L1 PM Substates T_POWER_ON Scales,
+    /// DPC Trigger Enable, DPC Trigger Reason of a triggered DPC and valid Readiness Time scales
+    /// are checked. L1 PM Substates support bits are contradictory if L1 PM Substates Supported
+    /// is not set.
+    pub fn reserved_encoding(&self) -> Option<&'static str> {
+        use downstream_port_containment::{DpcTrigger, DpcTriggerReason};
+        use l1_pm_substates::PortTPowerOnScale;
+        match self {
+            Self::Reserved(id) => (!Self::is_known_id(*id)).then_some("Capability ID"),
+            Self::L1PmSubstates(l1pm) => {
+                let caps = &l1pm.l1_pm_substates_capabilities;
+                let any_supported = caps.pci_pm_l1_2_supported
+                    || caps.pci_pm_l1_1_supported
+                    || caps.aspm_l1_2_supported
+                    || caps.aspm_l1_1_supported;
+                if caps.port_t_power_on.scale == PortTPowerOnScale::Reserved {
+                    Some("Port T_POWER_ON Scale")
+                } else if l1pm.l1_pm_substates_control_2.t_power_on.scale
+                    == PortTPowerOnScale::Reserved
+                {
+                    Some("T_POWER_ON Scale")
+                } else if any_supported && !caps.l1_pm_substates_supported {
+                    Some("L1 PM Substates Supported")
+                } else {
+                    None
+                }
+            }
+            Self::DownstreamPortContainment(dpc) => {
+                let status = &dpc.dpc_status;
+                if dpc.dpc_control.dpc_trigger_enable == DpcTrigger::Reserved {
+                    Some("DPC Trigger Enable")
+                } else if status.dpc_trigger_status
+                    && matches!(status.dpc_trigger_reason, DpcTriggerReason::Reserved(_))
+                {
+                    Some("DPC Trigger Reason")
+                } else {
+                    None
+                }
+            }
+            Self::ReadinessTimeReporting(rtr) => rtr.reserved_scale(),
+            _ => None,
+        }
+    }
+}
+
 
 // 0001h Advanced Error Reporting (AER)
 pub mod advanced_error_reporting;
@@ -658,7 +1106,9 @@ pub mod root_complex_event_collector_endpoint_association;
 pub use root_complex_event_collector_endpoint_association::RootComplexEventCollectorEndpointAssociation;
 
 // 0008h Multi-Function Virtual Channel (MFVC)
+#[cfg(feature = "mfvc")]
 pub mod multifunction_virtual_channel;
+#[cfg(feature = "mfvc")]
 pub use multifunction_virtual_channel::MultifunctionVirtualChannel;
 
 // 000Ah Root Complex Register Block (RCRB) Header
@@ -692,10 +1142,7 @@ pub use single_root_io_virtualization::SingleRootIoVirtualization;
 
 // 0011h Multi-Root I/O Virtualization (MR-IOV)
 // defined in the Multi-Root I/O Virtualization and Sharing Specification
-pub mod multi_root_io_virtualization {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct MultiRootIoVirtualization;
-}
+pub mod multi_root_io_virtualization;
 pub use multi_root_io_virtualization::MultiRootIoVirtualization;
 
 // 0012h Multicast
@@ -774,47 +1221,22 @@ pub mod designated_vendor_specific_extended_capability;
 pub use designated_vendor_specific_extended_capability::DesignatedVendorSpecificExtendedCapability;
 
 // 0024h VF Resizable BAR
-pub mod vf_resizable_bar {
-    /*!
-    # VF Resizable BAR
-
-    The VF Resizable BAR capability permits hardware to communicate the
-    resource sizes that are acceptable for operation via the VF Resizable BAR Capability and Control
-    registers and system software to communicate the optimal size back to the hardware via the VF
-    BAR Size field of the VF Resizable BAR Control register.
-
-    ## Struct diagram
-
-    VF Resizable BAR has same fields as [ResizableBar](super::ResizableBar)
-    */
-
-    /// VF Resizable BAR
-    pub type VfResizableBar<'a> = super::ResizableBar<'a>;
-
-    /// VF Resizable BAR Error
-    pub type VfResizableBarError = super::resizable_bar::ResizableBarError;
-}
+pub mod vf_resizable_bar;
 pub use vf_resizable_bar::VfResizableBar;
 
 // 0025h Data Link Feature
-pub mod data_link_feature {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct DataLinkFeature;
-}
+pub mod data_link_feature;
 pub use data_link_feature::DataLinkFeature;
 
+// Physical Layer 16.0, 32.0 and 64.0 GT/s common registers
+pub mod physical_layer;
+
 // 0026h Physical Layer 16.0 GT/s
-pub mod physical_layer_16_gtps {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct PhysicalLayer16GTps;
-}
+pub mod physical_layer_16_gtps;
 pub use physical_layer_16_gtps::PhysicalLayer16GTps;
 
 // 0027h Lane Margining at the Receiver
-pub mod lane_margining_at_the_receiver {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct LaneMarginingAtTheReceiver;
-}
+pub mod lane_margining_at_the_receiver;
 pub use lane_margining_at_the_receiver::LaneMarginingAtTheReceiver;
 
 // 0028h Hierarchy ID
@@ -825,17 +1247,11 @@ pub mod hierarchy_id {
 pub use hierarchy_id::HierarchyId;
 
 // 0029h Native PCIe Enclosure Management (NPEM)
-pub mod native_pcie_enclosure_management {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct NativePcieEnclosureManagement;
-}
+pub mod native_pcie_enclosure_management;
 pub use native_pcie_enclosure_management::NativePcieEnclosureManagement;
 
 // 002Ah Physical Layer 32.0 GT/s
-pub mod physical_layer_32_gtps {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct PhysicalLayer32GTps;
-}
+pub mod physical_layer_32_gtps;
 pub use physical_layer_32_gtps::PhysicalLayer32GTps;
 
 // 002Bh Alternate Protocol
@@ -860,10 +1276,7 @@ pub mod shadow_functions {
 pub use shadow_functions::ShadowFunctions;
 
 // 002Eh Data Object Exchange
-pub mod data_object_exchange {
-    #[derive(Debug, Clone, PartialEq, Eq)]
-    pub struct DataObjectExchange;
-}
+pub mod data_object_exchange;
 pub use data_object_exchange::DataObjectExchange;
 
 // 002Fh Device 3
@@ -908,9 +1321,56 @@ pub mod flit_error_injection {
 }
 pub use flit_error_injection::FlitErrorInjection;
 
+/// Largest `MIN_SIZE` of the extended capability structures, a buffer of this size after the
+/// extended capability header fits mandatory registers of any decoded extended capability
+pub const MAX_MIN_SIZE: usize = crate::max_size(&[
+    AdvancedErrorReporting::MIN_SIZE,
+    VirtualChannel::MIN_SIZE,
+    DeviceSerialNumber::MIN_SIZE,
+    PowerBudgeting::MIN_SIZE,
+    RootComplexLinkDeclaration::MIN_SIZE,
+    RootComplexInternalLinkControl::MIN_SIZE,
+    RootComplexEventCollectorEndpointAssociation::MIN_SIZE,
+    #[cfg(feature = "mfvc")]
+    MultifunctionVirtualChannel::MIN_SIZE,
+    RootComplexRegisterBlockHeader::MIN_SIZE,
+    VendorSpecificExtendedCapability::MIN_SIZE,
+    ConfigurationAccessCorrelation::MIN_SIZE,
+    AccessControlServices::MIN_SIZE,
+    AlternativeRoutingIdInterpretation::MIN_SIZE,
+    AddressTranslationServices::MIN_SIZE,
+    SingleRootIoVirtualization::MIN_SIZE,
+    MultiRootIoVirtualization::MIN_SIZE,
+    Multicast::MIN_SIZE,
+    PageRequestInterface::MIN_SIZE,
+    ResizableBar::MIN_SIZE,
+    DynamicPowerAllocation::MIN_SIZE,
+    TphRequester::MIN_SIZE,
+    LatencyToleranceReporting::MIN_SIZE,
+    SecondaryPciExpress::MIN_SIZE,
+    ProtocolMultiplexing::MIN_SIZE,
+    ProcessAddressSpaceId::MIN_SIZE,
+    LnRequester::MIN_SIZE,
+    DownstreamPortContainment::MIN_SIZE,
+    L1PmSubstates::MIN_SIZE,
+    PrecisionTimeMeasurement::MIN_SIZE,
+    PciExpressOverMphy::MIN_SIZE,
+    FrsQueuing::MIN_SIZE,
+    ReadinessTimeReporting::MIN_SIZE,
+    DesignatedVendorSpecificExtendedCapability::MIN_SIZE,
+    DataLinkFeature::MIN_SIZE,
+    PhysicalLayer16GTps::MIN_SIZE,
+    LaneMarginingAtTheReceiver::MIN_SIZE,
+    NativePcieEnclosureManagement::MIN_SIZE,
+    PhysicalLayer32GTps::MIN_SIZE,
+    DataObjectExchange::MIN_SIZE,
+]);
+const _: () = assert!(ExtendedCapability::HEADER_SIZE + MAX_MIN_SIZE <= ECS_LENGTH);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ECS_OFFSET;
     use pretty_assertions::assert_eq;
     use std::prelude::v1::*;
 
@@ -931,18 +1391,419 @@ mod tests {
         // Capabilities: [300] Vendor Specific Information: ID=0008 Rev=0 Len=038 <?>
         let ecaps = ExtendedCapabilities::new(DATA[ECS_OFFSET..].try_into().unwrap());
         let sample = vec![
-            Ok((0x100, 0x000b)),
-            Ok((0x110, 0x000d)),
-            Ok((0x148, 0x0001)),
-            Ok((0x1d0, 0x000b)),
-            Ok((0x250, 0x0019)),
-            Ok((0x280, 0x000b)),
-            Ok((0x298, 0x000b)),
-            Ok((0x300, 0x000b)),
+            Ok((ConfigOffset(0x100), 0x000b)),
+            Ok((ConfigOffset(0x110), 0x000d)),
+            Ok((ConfigOffset(0x148), 0x0001)),
+            Ok((ConfigOffset(0x1d0), 0x000b)),
+            Ok((ConfigOffset(0x250), 0x0019)),
+            Ok((ConfigOffset(0x280), 0x000b)),
+            Ok((ConfigOffset(0x298), 0x000b)),
+            Ok((ConfigOffset(0x300), 0x000b)),
         ];
         let result = ecaps
             .map(|ecap| ecap.map(|ecap| (ecap.offset, ecap.id())))
             .collect::<Vec<_>>();
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn offset_into_pci_configuration_space() {
+        // Device Serial Number version 0 points to 40h
+        let ecs = [
+            0x03, 0x00, 0x00, 0x04, 0xfe, 0x85, 0x54, 0x45, 0x00, 0x50, 0x56, 0xff,
+        ];
+        let mut ecaps = ExtendedCapabilities::new(&ecs);
+        let dsn = ecaps.next().unwrap().unwrap();
+        assert_eq!(
+            (ConfigOffset(0x100), 0x0003, 0),
+            (dsn.offset, dsn.id(), dsn.version)
+        );
+        let error = ecaps.next().unwrap().unwrap_err();
+        let sample = ExtendedCapabilityError::Offset {
+            offset: ConfigOffset(0x100),
+            next: 0x40,
+        };
+        assert_eq!(sample, error);
+        assert_eq!(None, ecaps.next());
+    }
+
+    #[test]
+    fn looped_list_limit() {
+        // Null Capability points to itself
+        let ecs = [0x00, 0x00, 0x01, 0x10];
+        let result = ExtendedCapabilities::new(&ecs).collect::<Vec<_>>();
+        assert_eq!(ExtendedCapabilities::MAX_ENTRIES + 1, result.len());
+        let sample = Some(&Err(ExtendedCapabilityError::Limit {
+            offset: ConfigOffset(0x100),
+            limit: 960,
+        }));
+        assert_eq!(sample, result.last());
+
+        let mut ecaps = ExtendedCapabilities::new(&ecs).with_limit(1);
+        assert!(ecaps.next().unwrap().is_ok());
+        assert_eq!(
+            "[100] extended capabilities list is longer than 1 entries",
+            ecaps.next().unwrap().unwrap_err().to_string()
+        );
+        assert_eq!(None, ecaps.next());
+    }
+
+    #[test]
+    fn truncated_extended_capability_error() {
+        let first = |ecs: &[u8]| ExtendedCapabilities::new(ecs).next().unwrap().unwrap_err();
+        let sizes = |e: &ExtendedCapabilityError| (e.offset(), e.needed(), e.available());
+        let mut ecs = [0u8; 0x10];
+        // Advanced Error Reporting at 100h
+        ecs[..4].copy_from_slice(&[0x01, 0x00, 0x01, 0x00]);
+        let result = first(&ecs);
+        let sample = ExtendedCapabilityError::AdvancedErrorReporting {
+            offset: ConfigOffset(0x100),
+            available: 12,
+            source: advanced_error_reporting::AdvancedErrorReportingError::Common,
+        };
+        assert_eq!(sample, result);
+        let needed = Some(AdvancedErrorReporting::MIN_SIZE);
+        assert_eq!((ConfigOffset(0x104), needed, Some(12)), sizes(&result));
+
+        // Device Serial Number
+        ecs[0] = 0x03;
+        let result = first(&ecs[..8]);
+        assert_eq!((ConfigOffset(0x104), Some(8), Some(4)), sizes(&result));
+        assert_eq!(
+            "[100] Device Serial Number (8 bytes) data read error (4 bytes available)",
+            result.to_string()
+        );
+        // FRS Queuing is decoded with the header
+        ecs[0] = 0x21;
+        let result = first(&ecs[..8]);
+        assert_eq!((ConfigOffset(0x104), Some(12), Some(4)), sizes(&result));
+        // VF Resizable BAR
+        ecs[0] = 0x24;
+        let result = first(&ecs[..6]);
+        assert_eq!((ConfigOffset(0x104), Some(8), Some(2)), sizes(&result));
+
+        let result = first(&ecs[..2]);
+        assert_eq!((ConfigOffset(0x100), Some(4), Some(2)), sizes(&result));
+    }
+
+    #[test]
+    fn strict() {
+        let mut data = crate::synthetic::root_port_with_extended_capabilities();
+        let strict = |data: &[u8]| {
+            ExtendedCapabilities::new(&data[ECS_OFFSET..])
+                .strict()
+                .filter_map(|ecap| ecap.err())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(Vec::<ExtendedCapabilityError>::new(), strict(&data));
+
+        // DPC Control: DPC Trigger Enable 11b
+        data[0x16e] |= 0b11;
+        // L1 PM Substates Capabilities: L1 PM Substates Supported is cleared
+        data[0x194] &= !0x10;
+        let sample = vec![
+            ExtendedCapabilityError::ReservedEncoding {
+                offset: ConfigOffset(0x168),
+                field: "DPC Trigger Enable",
+            },
+            ExtendedCapabilityError::ReservedEncoding {
+                offset: ConfigOffset(0x190),
+                field: "L1 PM Substates Supported",
+            },
+        ];
+        assert_eq!(sample, strict(&data));
+        let ecs = &data[ECS_OFFSET..];
+        assert!(ExtendedCapabilities::new(ecs).all(|ecap| ecap.is_ok()));
+
+        // Known IDs without decoders are not reserved encodings
+        for id in [
+            0x0014, 0x0028, 0x002b, 0x002c, 0x002d, 0x002f, 0x0030, 0x0031, 0x0032, 0x0033, 0x0034,
+        ] {
+            let ecs = [id as u8, 0x00, 0x01, 0x00];
+            let ecap = ExtendedCapabilities::new(&ecs)
+                .strict()
+                .next()
+                .unwrap()
+                .unwrap();
+            assert!(ecap.kind.is_placeholder(), "{:04x}", id);
+            assert_eq!(id, ecap.id());
+        }
+        let ecs = [0x35, 0x00, 0x01, 0x00];
+        let result = ExtendedCapabilities::new(&ecs).strict().next().unwrap();
+        let error = ExtendedCapabilityError::ReservedEncoding {
+            offset: ConfigOffset(0x100),
+            field: "Capability ID",
+        };
+        assert_eq!(Err(error), result);
+    }
+
+    #[cfg(not(feature = "mfvc"))]
+    #[test]
+    fn strict_without_decoder() {
+        // MFVC Extended Capability is parsed as Reserved, but its ID is known
+        let ecs = [0x08, 0x00, 0x01, 0x00];
+        let result = ExtendedCapabilities::new(&ecs)
+            .strict()
+            .map(|ecap| ecap.map(|ecap| ecap.kind))
+            .next();
+        assert_eq!(Some(Ok(ExtendedCapabilityKind::Reserved(0x0008))), result);
+    }
+
+    #[test]
+    fn headers() {
+        let ecs = &DATA[ECS_OFFSET..];
+        let result = ExtendedCapabilities::new(ecs)
+            .headers()
+            .map(|(offset, header)| (offset, header.extended_capability_id))
+            .collect::<Vec<_>>();
+        let sample = ExtendedCapabilities::new(ecs)
+            .map(|ecap| ecap.map(|ecap| (ecap.offset, ecap.id())).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sample, result);
+        let (_, header) = ExtendedCapabilities::new(ecs).headers().nth(2).unwrap();
+        let sample = ExtendedCapabilityHeader {
+            extended_capability_id: 0x0001,
+            capability_version: 1,
+            next_capability_offset: 0x1d0,
+        };
+        assert_eq!(sample, header);
+        assert_eq!(0x1d01_0001, u32::from(header));
+        // Header round-trip keeps version and next offset
+        for (offset, header) in ExtendedCapabilities::new(ecs).headers() {
+            let dword = u32::from(header);
+            let ecs_offset = offset.ecs_relative().unwrap();
+            assert_eq!(
+                &dword.to_le_bytes(),
+                &ecs[ecs_offset..ecs_offset + ECH_BYTES]
+            );
+            assert_eq!(header, ExtendedCapabilityHeader::from(dword));
+        }
+
+        // Self-referencing capability at 100h
+        let ecs = [0x0b, 0x00, 0x01, 0x10];
+        let count = ExtendedCapabilities::new(&ecs).headers().count();
+        assert_eq!(ECS_LENGTH / ECH_BYTES, count);
+    }
+
+    macro_rules! assert_min_size {
+        ($t:ty) => {
+            assert_min_size!($t, [0u8; <$t>::MIN_SIZE])
+        };
+        ($t:ty, $data:expr) => {{
+            let data = $data;
+            assert!(<$t>::try_from(&data[..]).is_ok(), "{}", stringify!($t));
+            assert!(
+                <$t>::try_from(&data[..data.len() - 1]).is_err(),
+                "{}",
+                stringify!($t)
+            );
+        }};
+    }
+
+    #[test]
+    fn min_size() {
+        assert_min_size!(AdvancedErrorReporting);
+        assert_min_size!(VirtualChannel);
+        assert_min_size!(DeviceSerialNumber);
+        assert_min_size!(PowerBudgeting);
+        let mut data = [0u8; RootComplexLinkDeclaration::MIN_SIZE];
+        // Number of Link Entries
+        data[1] = 1;
+        assert_min_size!(RootComplexLinkDeclaration, data);
+        assert_min_size!(RootComplexInternalLinkControl);
+        assert_min_size!(RootComplexEventCollectorEndpointAssociation);
+        #[cfg(feature = "mfvc")]
+        assert_min_size!(MultifunctionVirtualChannel);
+        assert_min_size!(RootComplexRegisterBlockHeader);
+        assert_min_size!(VendorSpecificExtendedCapability);
+        assert_min_size!(ConfigurationAccessCorrelation);
+        assert_min_size!(AccessControlServices);
+        assert_min_size!(AlternativeRoutingIdInterpretation);
+        assert_min_size!(AddressTranslationServices);
+        assert_min_size!(SingleRootIoVirtualization);
+        assert_min_size!(MultiRootIoVirtualization);
+        assert_min_size!(Multicast);
+        assert_min_size!(PageRequestInterface);
+        let mut data = [0u8; ResizableBar::MIN_SIZE];
+        // Number of Resizable BARs
+        data[8] = 1 << 5;
+        assert_min_size!(ResizableBar, data);
+        assert_min_size!(DynamicPowerAllocation);
+        assert_min_size!(TphRequester);
+        assert_min_size!(LatencyToleranceReporting);
+        assert_min_size!(SecondaryPciExpress);
+        assert_min_size!(ProtocolMultiplexing);
+        assert_min_size!(ProcessAddressSpaceId);
+        assert_min_size!(LnRequester);
+        assert_min_size!(DownstreamPortContainment);
+        assert_min_size!(L1PmSubstates);
+        assert_min_size!(PrecisionTimeMeasurement);
+        assert_min_size!(PciExpressOverMphy);
+        assert_min_size!(FrsQueuing);
+        assert_min_size!(ReadinessTimeReporting);
+        assert_min_size!(DataLinkFeature);
+        assert_min_size!(PhysicalLayer16GTps);
+        assert_min_size!(LaneMarginingAtTheReceiver);
+        assert_min_size!(NativePcieEnclosureManagement);
+        assert_min_size!(PhysicalLayer32GTps);
+        assert_min_size!(DataObjectExchange);
+        let mut data = [0u8; DesignatedVendorSpecificExtendedCapability::MIN_SIZE];
+        // DVSEC Length
+        data[6] = (DesignatedVendorSpecificExtendedCapability::MIN_SIZE as u8) << 4;
+        assert_min_size!(DesignatedVendorSpecificExtendedCapability, data);
+    }
+
+    macro_rules! assert_size {
+        ($($t:ty),+ $(,)?) => {$(
+            assert!(<$t>::SIZE >= <$t>::MIN_SIZE, "{}", stringify!($t));
+            assert!(<$t>::try_from([0u8; <$t>::SIZE].as_slice()).is_ok(), "{}", stringify!($t));
+        )+};
+    }
+
+    #[test]
+    fn size() {
+        assert_size!(
+            DeviceSerialNumber,
+            PowerBudgeting,
+            RootComplexInternalLinkControl,
+            RootComplexEventCollectorEndpointAssociation,
+            RootComplexRegisterBlockHeader,
+            ConfigurationAccessCorrelation,
+            AlternativeRoutingIdInterpretation,
+            AddressTranslationServices,
+            SingleRootIoVirtualization,
+            MultiRootIoVirtualization,
+            Multicast,
+            PageRequestInterface,
+            LatencyToleranceReporting,
+            ProcessAddressSpaceId,
+            LnRequester,
+            L1PmSubstates,
+            PrecisionTimeMeasurement,
+            PciExpressOverMphy,
+            FrsQueuing,
+            ReadinessTimeReporting,
+            DataLinkFeature,
+            NativePcieEnclosureManagement,
+        );
+        assert_eq!(
+            SingleRootIoVirtualization::BYTES,
+            SingleRootIoVirtualization::SIZE
+        );
+    }
+
+    #[test]
+    fn data_error_size() {
+        let result = LatencyToleranceReporting::try_from([0u8; 3].as_slice());
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Latency Tolerance Reporting",
+            size: 4,
+        });
+        assert_eq!(sample, result);
+        let result = VirtualChannel::try_from([0u8; 11].as_slice());
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Virtual Channel",
+            size: 12,
+        });
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn structure_with_header_not_at_ecs_start() {
+        let data = [
+            0x03, 0x00, 0x01, 0x11, 0x88, 0x77, 0x66, 0x55, // 0x100
+            0x44, 0x33, 0x22, 0x11, 0x00, 0x00, 0x00, 0x00, // 0x108
+            0x0c, 0x00, 0x01, 0x00, 0x00, 0x11, 0x22, 0x33, // 0x110
+        ];
+        let result = ExtendedCapabilities::new(&data)
+            .map(|ecap| ecap.map(|ecap| ecap.kind))
+            .last();
+        let sample = Some(Ok(ExtendedCapabilityKind::ConfigurationAccessCorrelation(
+            ConfigurationAccessCorrelation {
+                device_correlation: 0x33221100,
+            },
+        )));
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn dvsecs() {
+        let mut data = [0u8; 0x40];
+        // DVSEC at 100h, vendor 8086h, ID 0023h, 12 bytes
+        data[0x00..0x0c].copy_from_slice(&[
+            0x23, 0x00, 0x01, 0x11, 0x86, 0x80, 0xc1, 0x00, 0x23, 0x00, 0xaa, 0xbb,
+        ]);
+        // Device Serial Number at 110h
+        data[0x10..0x1c].copy_from_slice(&[
+            0x03, 0x00, 0x01, 0x12, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11,
+        ]);
+        // DVSEC at 120h, vendor 1234h, ID 0001h, length exceeds data
+        data[0x20..0x2a]
+            .copy_from_slice(&[0x23, 0x00, 0x01, 0x13, 0x34, 0x12, 0x01, 0x40, 0x01, 0x00]);
+        // DVSEC at 130h, vendor 1234h, ID 0002h, 10 bytes
+        data[0x30..0x3a]
+            .copy_from_slice(&[0x23, 0x00, 0x01, 0x00, 0x34, 0x12, 0xa1, 0x00, 0x02, 0x00]);
+        let result = ExtendedCapabilities::new(&data)
+            .dvsecs()
+            .map(|(offset, dvsec)| (offset, dvsec.payload()))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            (ConfigOffset(0x100), Some([0xaa, 0xbb].as_slice())),
+            (ConfigOffset(0x130), Some([].as_slice())),
+        ];
+        assert_eq!(sample, result);
+        let (offset, _) = ExtendedCapabilities::new(&data)
+            .dvsecs()
+            .find(|(_, dvsec)| dvsec.is(0x1234, 0x0002))
+            .unwrap();
+        assert_eq!(ConfigOffset(0x130), offset);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_owned() {
+        use resizable_bar::OwnedResizableBar;
+        use tph_requester::{OwnedStTable, OwnedTphRequester};
+        use virtual_channel::OwnedVirtualChannel;
+
+        let (mut rebar, mut tph): (Option<OwnedResizableBar>, Option<OwnedTphRequester>) =
+            (None, None);
+        {
+            let data = crate::synthetic::endpoint_with_extended_capabilities().to_vec();
+            for ecap in ExtendedCapabilities::new(&data[ECS_OFFSET..]) {
+                match ecap.unwrap().kind {
+                    ExtendedCapabilityKind::ResizableBar(v) => rebar = Some(v.into_owned()),
+                    ExtendedCapabilityKind::TphRequester(v) => tph = Some(v.into_owned()),
+                    _ => (),
+                }
+            }
+        }
+        let rebar = rebar.unwrap();
+        let entry = rebar.as_borrowed().next().unwrap().unwrap();
+        assert_eq!(8, entry.control.bar_size);
+        assert_eq!(1, rebar.as_borrowed().count());
+        let tph = tph.unwrap();
+        assert_eq!(
+            OwnedStTable::NotPresent,
+            tph.tph_requester_capability.st_table
+        );
+        let borrowed = tph.as_borrowed();
+        assert!(borrowed.tph_requester_capability.no_st_mode_supported);
+
+        let vc: OwnedVirtualChannel = {
+            let mut data = vec![0u8; 0x1c];
+            // VC Arbitration Table Offset 1, WRR 32 phases
+            data[7] = 0x01;
+            data[8] = 0b010;
+            data[0x0c] = 0x35;
+            VirtualChannel::try_from(data.as_slice())
+                .unwrap()
+                .into_owned()
+        };
+        let table = vc.as_borrowed().vc_arbitration_table().unwrap();
+        assert_eq!(
+            vec![5, 3, 0, 0],
+            table.take(4).map(|entry| entry.vc_id).collect::<Vec<_>>()
+        );
+    }
 }
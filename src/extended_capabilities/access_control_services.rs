@@ -63,6 +63,7 @@ pub struct AccessControlServices<'a> {
 }
 
 impl<'a> AccessControlServices<'a> {
+    pub const MIN_SIZE: usize = 2 + 2;
     pub fn egress_control_vectors(&self) -> EgressControlVectors<'a> {
         let size = self.acs_capability.egress_control_vector_size as usize;
         let end = size / (u32::BITS as usize) * ECV_BYTES;
@@ -81,7 +82,7 @@ impl<'a> TryFrom<&'a [u8]> for AccessControlServices<'a> {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Access Control Services",
-                size: 4,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             data: tail,
@@ -43,6 +43,10 @@ pub struct AddressTranslationServices {
     /// ATS Control
     pub ats_control: AtsControl,
 }
+impl AddressTranslationServices {
+    pub const SIZE: usize = 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for AddressTranslationServices {
     type Error = ExtendedCapabilityDataError;
 
@@ -54,7 +58,7 @@ impl TryFrom<&[u8]> for AddressTranslationServices {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Address Translation Services",
-                size: 4,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             ats_capability: From::<u16>::from(ats_capability),
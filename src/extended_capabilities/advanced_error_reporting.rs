@@ -69,6 +69,12 @@ let sample = AdvancedErrorReporting {
         atomicop_egress_blocked_status: false,
         tlp_prefix_blocked_error_status: false,
         poisoned_tlp_egress_blocked_status: false,
+        dmwr_request_egress_blocked_status: false,
+        ide_check_failed_status: false,
+        misrouted_ide_tlp_status: false,
+        pcrc_check_failed_status: false,
+        tlp_translation_egress_blocked_status: false,
+        reserved: 0,
     },
     uncorrectable_error_mask: UncorrectableError {
         link_training_error: false,
@@ -89,6 +95,12 @@ let sample = AdvancedErrorReporting {
         atomicop_egress_blocked_status: false,
         tlp_prefix_blocked_error_status: false,
         poisoned_tlp_egress_blocked_status: false,
+        dmwr_request_egress_blocked_status: false,
+        ide_check_failed_status: false,
+        misrouted_ide_tlp_status: false,
+        pcrc_check_failed_status: false,
+        tlp_translation_egress_blocked_status: false,
+        reserved: 0,
     },
     uncorrectable_error_severity: UncorrectableError {
         link_training_error: true,
@@ -109,6 +121,12 @@ let sample = AdvancedErrorReporting {
         atomicop_egress_blocked_status: false,
         tlp_prefix_blocked_error_status: false,
         poisoned_tlp_egress_blocked_status: false,
+        dmwr_request_egress_blocked_status: false,
+        ide_check_failed_status: false,
+        misrouted_ide_tlp_status: false,
+        pcrc_check_failed_status: false,
+        tlp_translation_egress_blocked_status: false,
+        reserved: 0,
     },
     correctable_error_status: CorrectableError {
         receiver_error_status: false,
@@ -119,6 +137,7 @@ let sample = AdvancedErrorReporting {
         advisory_non_fatal_error_status: false,
         corrected_internal_error_status: false,
         header_log_overflow_status: false,
+        reserved: 0,
     },
     correctable_error_mask: CorrectableError {
         receiver_error_status: true,
@@ -129,6 +148,7 @@ let sample = AdvancedErrorReporting {
         advisory_non_fatal_error_status: true,
         corrected_internal_error_status: false,
         header_log_overflow_status: false,
+        reserved: 0,
     },
     advanced_error_capabilities_and_control: AdvancedErrorCapabilitiesAndControl {
         first_error_pointer: 0x00,
@@ -165,19 +185,82 @@ let sample = AdvancedErrorReporting {
 };
 assert_eq!(sample, result);
 ```
+
+Status registers are RW1C, an error daemon acknowledges only the errors it has handled, e.g.
+Non-Fatal errors, leaving the rest for the next pass:
+
+```rust
+# use pcics::extended_capabilities::advanced_error_reporting::*;
+# use pcics::{config_write::*, ConfigOffset};
+// Completion Timeout (Non-Fatal) and Malformed TLP (Fatal) are pending
+let status = UncorrectableError::from(0x0004_4000);
+let severity = UncorrectableError::from(0x0006_2030);
+let non_fatal = u32::from(status.non_fatal(&severity));
+assert_eq!(0x0000_4000, non_fatal);
+
+// AER at 100h
+let current = u32::from(status);
+let write = AdvancedErrorReporting::UNCORRECTABLE_ERROR_STATUS
+    .write(ConfigOffset(0x100), current, current & !non_fatal)
+    .unwrap();
+let sample = RegisterWrite {
+    offset: ConfigOffset(0x105),
+    width: AccessWidth::Byte,
+    value: 0x40,
+};
+assert_eq!(sample, write);
+```
+
+First Error Pointer is meaningful only while the pointed status bit is set:
+
+```rust
+# use pcics::extended_capabilities::advanced_error_reporting::*;
+let mut data = [0u8; AdvancedErrorReporting::MIN_SIZE];
+// Malformed TLP Status, First Error Pointer is 18
+data[0..4].copy_from_slice(&0x0004_0000u32.to_le_bytes());
+data[0x14] = 18;
+let aer = AdvancedErrorReporting::try_from(data.as_slice()).unwrap();
+assert_eq!(Some(UncorrectableErrorKind::MalformedTlp), aer.first_error());
+
+// Status bit was cleared
+data[0..4].fill(0);
+let aer = AdvancedErrorReporting::try_from(data.as_slice()).unwrap();
+assert_eq!(None, aer.first_error());
+```
 */
 
-use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P10, P11, P2, P21, P4, P7, P9};
+use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P10, P2, P4, P7};
 use snafu::Snafu;
 
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor},
+    register::{flags, register},
+};
+
 /// Advanced Error Reporting Error
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum AdvancedErrorReportingError {
-    #[snafu(display("can't read common registres"))]
+    #[snafu(display(
+        "can't read common registers ({} bytes)",
+        AdvancedErrorReporting::MIN_SIZE
+    ))]
     Common,
-    #[snafu(display("can't read TLP Prefix Log"))]
+    #[snafu(display(
+        "can't read TLP Prefix Log ({} bytes)",
+        AdvancedErrorReporting::FULL_SIZE
+    ))]
     TlpPrefixLog,
 }
+impl AdvancedErrorReportingError {
+    /// Bytes after the Extended Capability Header needed by the failed read
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::Common => AdvancedErrorReporting::MIN_SIZE,
+            Self::TlpPrefixLog => AdvancedErrorReporting::FULL_SIZE,
+        })
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AdvancedErrorReporting {
@@ -206,6 +289,7 @@ pub struct AdvancedErrorReporting {
 impl AdvancedErrorReporting {
     /// Common registers size (exists) on any device type
     pub const COMMON_SIZE: usize = 0x2c - 0x04;
+    pub const MIN_SIZE: usize = Self::COMMON_SIZE;
     // Root Ports and Root Complex Event Collectors registres size
     pub const RP_AND_RCEC_SIZE: usize = 0x38 - 0x04;
     /// Full size with TLP Prefix Log register
@@ -214,6 +298,33 @@ impl AdvancedErrorReporting {
     pub const HEADER_LOG_SIZE: usize = 4 * 4;
     /// TLP Prefix Log register size (4 x u32)
     pub const TLP_PREFIX_LOG_SIZE: usize = 4 * 4;
+    /// Uncorrectable Error Status register, all status bits are RW1C
+    pub const UNCORRECTABLE_ERROR_STATUS: RegisterDescriptor =
+        RegisterDescriptor::new("Uncorrectable Error Status", 0x04, AccessWidth::Dword).rw1c(!0);
+    /// Uncorrectable Error Mask register, masked errors are neither logged in the Header Log
+    /// nor reported
+    pub const UNCORRECTABLE_ERROR_MASK: RegisterDescriptor =
+        RegisterDescriptor::new("Uncorrectable Error Mask", 0x08, AccessWidth::Dword);
+    /// Uncorrectable Error Severity register
+    pub const UNCORRECTABLE_ERROR_SEVERITY: RegisterDescriptor =
+        RegisterDescriptor::new("Uncorrectable Error Severity", 0x0c, AccessWidth::Dword);
+    /// Correctable Error Status register, all status bits are RW1C
+    pub const CORRECTABLE_ERROR_STATUS: RegisterDescriptor =
+        RegisterDescriptor::new("Correctable Error Status", 0x10, AccessWidth::Dword).rw1c(!0);
+    /// Correctable Error Mask register
+    pub const CORRECTABLE_ERROR_MASK: RegisterDescriptor =
+        RegisterDescriptor::new("Correctable Error Mask", 0x14, AccessWidth::Dword);
+    /// Root Error Status register, bits 6:0 are RW1C
+    pub const ROOT_ERROR_STATUS: RegisterDescriptor =
+        RegisterDescriptor::new("Root Error Status", 0x30, AccessWidth::Dword).rw1c(0x7f);
+    /// Uncorrectable error pointed by First Error Pointer
+    ///
+    /// `None` if the corresponding Uncorrectable Error Status bit is not set, the pointer is
+    /// undefined then.
+    pub fn first_error(&self) -> Option<UncorrectableErrorKind> {
+        let kind = self.advanced_error_capabilities_and_control.first_error();
+        self.uncorrectable_error_status.is_set(kind).then_some(kind)
+    }
 }
 impl TryFrom<&[u8]> for AdvancedErrorReporting {
     type Error = AdvancedErrorReportingError;
@@ -329,51 +440,178 @@ pub struct UncorrectableError {
     pub tlp_prefix_blocked_error_status: bool,
     /// Poisoned TLP Egress Blocked Status (Optional)
     pub poisoned_tlp_egress_blocked_status: bool,
+    /// DMWr Request Egress Blocked Status (Optional)
+    pub dmwr_request_egress_blocked_status: bool,
+    /// IDE Check Failed Status (Optional)
+    pub ide_check_failed_status: bool,
+    /// Misrouted IDE TLP Status (Optional)
+    pub misrouted_ide_tlp_status: bool,
+    /// PCRC_CHECK_FAILED Status (Optional)
+    pub pcrc_check_failed_status: bool,
+    /// TLP Translation Egress Blocked Status (Optional)
+    pub tlp_translation_egress_blocked_status: bool,
+    /// Reserved bits as read, some implementations report chip specific errors there
+    pub reserved: u32,
 }
-impl From<u32> for UncorrectableError {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            link_training_error,
-            (),
-            data_link_protocol_error_status,
-            surprise_down_error_status,
-            (),
-            poisoned_tlp_received_status,
-            flow_control_protocol_error_status,
-            completion_timeout_status,
-            completer_abort_status,
-            unexpected_completion_status,
-            receiver_overflow_status,
-            malformed_tlp_status,
-            ecrc_error_status,
-            unsupported_request_error_status,
-            acs_violation_status,
-            uncorrectable_internal_error_status,
-            mc_blocked_tlp_status,
-            atomicop_egress_blocked_status,
-            tlp_prefix_blocked_error_status,
-            poisoned_tlp_egress_blocked_status,
-            (),
-        )) = P21::<_, 1, 3, 1, 1, 6, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 5>(dword).into();
-        Self {
-            link_training_error,
-            data_link_protocol_error_status,
-            surprise_down_error_status,
-            poisoned_tlp_received_status,
-            flow_control_protocol_error_status,
-            completion_timeout_status,
-            completer_abort_status,
-            unexpected_completion_status,
-            receiver_overflow_status,
-            malformed_tlp_status,
-            ecrc_error_status,
-            unsupported_request_error_status,
-            acs_violation_status,
-            uncorrectable_internal_error_status,
-            mc_blocked_tlp_status,
-            atomicop_egress_blocked_status,
-            tlp_prefix_blocked_error_status,
-            poisoned_tlp_egress_blocked_status,
+register!(UncorrectableError: u32 {
+    link_training_error[0],
+    data_link_protocol_error_status[4],
+    surprise_down_error_status[5],
+    poisoned_tlp_received_status[12],
+    flow_control_protocol_error_status[13],
+    completion_timeout_status[14],
+    completer_abort_status[15],
+    unexpected_completion_status[16],
+    receiver_overflow_status[17],
+    malformed_tlp_status[18],
+    ecrc_error_status[19],
+    unsupported_request_error_status[20],
+    acs_violation_status[21],
+    uncorrectable_internal_error_status[22],
+    mc_blocked_tlp_status[23],
+    atomicop_egress_blocked_status[24],
+    tlp_prefix_blocked_error_status[25],
+    poisoned_tlp_egress_blocked_status[26],
+    dmwr_request_egress_blocked_status[27],
+    ide_check_failed_status[28],
+    misrouted_ide_tlp_status[29],
+    pcrc_check_failed_status[30],
+    tlp_translation_egress_blocked_status[31],
+}, reserved);
+flags!(
+    /// Error bits as `(field name, value)` pairs, reserved bits are not included
+    UncorrectableError::error_bits {
+        link_training_error,
+        data_link_protocol_error_status,
+        surprise_down_error_status,
+        poisoned_tlp_received_status,
+        flow_control_protocol_error_status,
+        completion_timeout_status,
+        completer_abort_status,
+        unexpected_completion_status,
+        receiver_overflow_status,
+        malformed_tlp_status,
+        ecrc_error_status,
+        unsupported_request_error_status,
+        acs_violation_status,
+        uncorrectable_internal_error_status,
+        mc_blocked_tlp_status,
+        atomicop_egress_blocked_status,
+        tlp_prefix_blocked_error_status,
+        poisoned_tlp_egress_blocked_status,
+        dmwr_request_egress_blocked_status,
+        ide_check_failed_status,
+        misrouted_ide_tlp_status,
+        pcrc_check_failed_status,
+        tlp_translation_egress_blocked_status,
+    }
+);
+
+impl UncorrectableError {
+    /// Errors of `self` reported as Fatal by Uncorrectable Error Severity register `severity`
+    pub fn fatal(&self, severity: &Self) -> Self {
+        Self::from(u32::from(self.clone()) & u32::from(severity.clone()))
+    }
+    /// Errors of `self` reported as Non-Fatal by Uncorrectable Error Severity register
+    /// `severity`
+    pub fn non_fatal(&self, severity: &Self) -> Self {
+        Self::from(u32::from(self.clone()) & !u32::from(severity.clone()))
+    }
+    /// Bit of `kind` is set, reserved bits are checked as well
+    pub fn is_set(&self, kind: UncorrectableErrorKind) -> bool {
+        u32::from(self.clone())
+            .checked_shr(u8::from(kind).into())
+            .is_some_and(|bits| bits & 1 != 0)
+    }
+}
+
+/// Bit of [UncorrectableError] registers, First Error Pointer values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UncorrectableErrorKind {
+    LinkTrainingError,
+    DataLinkProtocolError,
+    SurpriseDownError,
+    PoisonedTlpReceived,
+    FlowControlProtocolError,
+    CompletionTimeout,
+    CompleterAbort,
+    UnexpectedCompletion,
+    ReceiverOverflow,
+    MalformedTlp,
+    EcrcError,
+    UnsupportedRequestError,
+    AcsViolation,
+    UncorrectableInternalError,
+    McBlockedTlp,
+    AtomicOpEgressBlocked,
+    TlpPrefixBlockedError,
+    PoisonedTlpEgressBlocked,
+    DmwrRequestEgressBlocked,
+    IdeCheckFailed,
+    MisroutedIdeTlp,
+    PcrcCheckFailed,
+    TlpTranslationEgressBlocked,
+    /// Bit position without defined error
+    Reserved(u8),
+}
+impl From<u8> for UncorrectableErrorKind {
+    fn from(bit: u8) -> Self {
+        match bit {
+            0 => Self::LinkTrainingError,
+            4 => Self::DataLinkProtocolError,
+            5 => Self::SurpriseDownError,
+            12 => Self::PoisonedTlpReceived,
+            13 => Self::FlowControlProtocolError,
+            14 => Self::CompletionTimeout,
+            15 => Self::CompleterAbort,
+            16 => Self::UnexpectedCompletion,
+            17 => Self::ReceiverOverflow,
+            18 => Self::MalformedTlp,
+            19 => Self::EcrcError,
+            20 => Self::UnsupportedRequestError,
+            21 => Self::AcsViolation,
+            22 => Self::UncorrectableInternalError,
+            23 => Self::McBlockedTlp,
+            24 => Self::AtomicOpEgressBlocked,
+            25 => Self::TlpPrefixBlockedError,
+            26 => Self::PoisonedTlpEgressBlocked,
+            27 => Self::DmwrRequestEgressBlocked,
+            28 => Self::IdeCheckFailed,
+            29 => Self::MisroutedIdeTlp,
+            30 => Self::PcrcCheckFailed,
+            31 => Self::TlpTranslationEgressBlocked,
+            v => Self::Reserved(v),
+        }
+    }
+}
+impl From<UncorrectableErrorKind> for u8 {
+    fn from(kind: UncorrectableErrorKind) -> Self {
+        use UncorrectableErrorKind as Kind;
+        match kind {
+            Kind::LinkTrainingError => 0,
+            Kind::DataLinkProtocolError => 4,
+            Kind::SurpriseDownError => 5,
+            Kind::PoisonedTlpReceived => 12,
+            Kind::FlowControlProtocolError => 13,
+            Kind::CompletionTimeout => 14,
+            Kind::CompleterAbort => 15,
+            Kind::UnexpectedCompletion => 16,
+            Kind::ReceiverOverflow => 17,
+            Kind::MalformedTlp => 18,
+            Kind::EcrcError => 19,
+            Kind::UnsupportedRequestError => 20,
+            Kind::AcsViolation => 21,
+            Kind::UncorrectableInternalError => 22,
+            Kind::McBlockedTlp => 23,
+            Kind::AtomicOpEgressBlocked => 24,
+            Kind::TlpPrefixBlockedError => 25,
+            Kind::PoisonedTlpEgressBlocked => 26,
+            Kind::DmwrRequestEgressBlocked => 27,
+            Kind::IdeCheckFailed => 28,
+            Kind::MisroutedIdeTlp => 29,
+            Kind::PcrcCheckFailed => 30,
+            Kind::TlpTranslationEgressBlocked => 31,
+            Kind::Reserved(v) => v,
         }
     }
 }
@@ -397,34 +635,32 @@ pub struct CorrectableError {
     pub corrected_internal_error_status: bool,
     /// Header Log Overflow Status
     pub header_log_overflow_status: bool,
+    /// Reserved bits as read, some implementations report chip specific errors there
+    pub reserved: u32,
 }
-impl From<u32> for CorrectableError {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            receiver_error_status,
-            (),
-            bad_tlp_status,
-            bad_dllp_status,
-            replay_num_rollover_status,
-            (),
-            replay_timer_timeout_status,
-            advisory_non_fatal_error_status,
-            corrected_internal_error_status,
-            header_log_overflow_status,
-            (),
-        )) = P11::<_, 1, 5, 1, 1, 1, 3, 1, 1, 1, 1, 16>(dword).into();
-        Self {
-            receiver_error_status,
-            bad_tlp_status,
-            bad_dllp_status,
-            replay_num_rollover_status,
-            replay_timer_timeout_status,
-            advisory_non_fatal_error_status,
-            corrected_internal_error_status,
-            header_log_overflow_status,
-        }
+register!(CorrectableError: u32 {
+    receiver_error_status[0],
+    bad_tlp_status[6],
+    bad_dllp_status[7],
+    replay_num_rollover_status[8],
+    replay_timer_timeout_status[12],
+    advisory_non_fatal_error_status[13],
+    corrected_internal_error_status[14],
+    header_log_overflow_status[15],
+}, reserved);
+flags!(
+    /// Error bits as `(field name, value)` pairs, reserved bits are not included
+    CorrectableError::error_bits {
+        receiver_error_status,
+        bad_tlp_status,
+        bad_dllp_status,
+        replay_num_rollover_status,
+        replay_timer_timeout_status,
+        advisory_non_fatal_error_status,
+        corrected_internal_error_status,
+        header_log_overflow_status,
     }
-}
+);
 
 /// Advanced Error Capabilities and Control Register
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -448,6 +684,15 @@ pub struct AdvancedErrorCapabilitiesAndControl {
     /// Completion Timeout Prefix/Header Log Capable
     pub completion_timeout_prefix_or_header_log_capable: bool,
 }
+impl AdvancedErrorCapabilitiesAndControl {
+    /// [First Error Pointer](Self::first_error_pointer) as an uncorrectable error
+    ///
+    /// The pointer is valid only if the bit is set in Uncorrectable Error Status, see
+    /// [AdvancedErrorReporting::first_error].
+    pub fn first_error(&self) -> UncorrectableErrorKind {
+        self.first_error_pointer.into()
+    }
+}
 
 impl From<u32> for AdvancedErrorCapabilitiesAndControl {
     fn from(dword: u32) -> Self {
@@ -529,35 +774,34 @@ pub struct RootErrorStatus {
     pub non_fatal_error_messages_received: bool,
     /// Fatal Error Messages Received
     pub fatal_error_messages_received: bool,
-    /// Advanced Error Interrupt Message Number
+    /// Advanced Error Interrupt Message Number, the MSI or MSI-X vector used for the interrupt
+    /// messages associated with this register, see
+    /// [analysis::interrupts](crate::analysis::interrupts)
     pub advanced_error_interrupt_message_number: u8,
 }
 
-impl From<u32> for RootErrorStatus {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            err_cor_received,
-            multiple_err_cor_received,
-            err_fatal_or_nonfatal_received,
-            multiple_err_fatal_or_nonfatal_received,
-            first_uncorrectable_fatal,
-            non_fatal_error_messages_received,
-            fatal_error_messages_received,
-            (),
-            advanced_error_interrupt_message_number,
-        )) = P9::<_, 1, 1, 1, 1, 1, 1, 1, 20, 5>(dword).into();
-        Self {
-            err_cor_received,
-            multiple_err_cor_received,
-            err_fatal_or_nonfatal_received,
-            multiple_err_fatal_or_nonfatal_received,
-            first_uncorrectable_fatal,
-            non_fatal_error_messages_received,
-            fatal_error_messages_received,
-            advanced_error_interrupt_message_number,
-        }
+register!(RootErrorStatus: u32 {
+    err_cor_received[0],
+    multiple_err_cor_received[1],
+    err_fatal_or_nonfatal_received[2],
+    multiple_err_fatal_or_nonfatal_received[3],
+    first_uncorrectable_fatal[4],
+    non_fatal_error_messages_received[5],
+    fatal_error_messages_received[6],
+    advanced_error_interrupt_message_number[27..32],
+});
+flags!(
+    /// Error Message received bits as `(field name, value)` pairs
+    RootErrorStatus::error_bits {
+        err_cor_received,
+        multiple_err_cor_received,
+        err_fatal_or_nonfatal_received,
+        multiple_err_fatal_or_nonfatal_received,
+        first_uncorrectable_fatal,
+        non_fatal_error_messages_received,
+        fatal_error_messages_received,
     }
-}
+);
 
 /// The Error Source Identification register identifies the source (Requester ID) of first
 /// correctable and uncorrectable (Non-fatal/Fatal) errors reported in the Root Error Status
@@ -579,6 +823,7 @@ pub struct TlpPrefixLog(pub [u32; 4]);
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
 
     #[test]
     fn uncorrectable_error_status() {
@@ -603,10 +848,82 @@ mod tests {
             atomicop_egress_blocked_status: false,
             tlp_prefix_blocked_error_status: false,
             poisoned_tlp_egress_blocked_status: false,
+            dmwr_request_egress_blocked_status: false,
+            ide_check_failed_status: false,
+            misrouted_ide_tlp_status: false,
+            pcrc_check_failed_status: false,
+            tlp_translation_egress_blocked_status: false,
+            reserved: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
 
+    #[test]
+    fn uncorrectable_error_classes() {
+        // IDE Check Failed, TLP Translation Egress Blocked and chip specific bit 8
+        let dword = 0x9000_0100;
+        let status = UncorrectableError::from(dword);
+        assert!(status.ide_check_failed_status);
+        assert!(status.tlp_translation_egress_blocked_status);
+        assert_eq!(0x0000_0100, status.reserved);
+        assert_eq!(dword, u32::from(status.clone()));
+
+        let severity = UncorrectableError::from(0x1000_0000);
+        assert_eq!(0x1000_0000, u32::from(status.fatal(&severity)));
+        assert_eq!(0x8000_0100, u32::from(status.non_fatal(&severity)));
+    }
+
+    #[test]
+    fn error_bits() {
+        let status = UncorrectableError::from(0x9000_0110);
+        assert_eq!(23, status.error_bits().count());
+        let result = status
+            .error_bits()
+            .filter_map(|(name, value)| value.then_some(name))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            "data_link_protocol_error_status",
+            "ide_check_failed_status",
+            "tlp_translation_egress_blocked_status",
+        ];
+        assert_eq!(sample, result);
+
+        let status = CorrectableError::from(0x0000_2001);
+        let result = status.error_bits().collect::<Vec<_>>();
+        assert_eq!(8, result.len());
+        assert_eq!(("receiver_error_status", true), result[0]);
+        assert_eq!(("advisory_non_fatal_error_status", true), result[5]);
+
+        let status = RootErrorStatus::from(0xf800_0004);
+        let result = status
+            .error_bits()
+            .filter_map(|(name, value)| value.then_some(name))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["err_fatal_or_nonfatal_received"], result);
+    }
+
+    #[test]
+    fn first_error_pointer() {
+        for bit in 0..32u8 {
+            assert_eq!(bit, u8::from(UncorrectableErrorKind::from(bit)));
+        }
+        let caps = AdvancedErrorCapabilitiesAndControl::from(0x0000_0094);
+        assert_eq!(20, caps.first_error_pointer);
+        assert!(caps.ecrc_check_capable);
+        assert_eq!(
+            UncorrectableErrorKind::UnsupportedRequestError,
+            caps.first_error()
+        );
+
+        // Unsupported Request and chip specific bit 8
+        let status = UncorrectableError::from(0x0010_0100);
+        assert!(status.is_set(UncorrectableErrorKind::UnsupportedRequestError));
+        assert!(status.is_set(UncorrectableErrorKind::Reserved(8)));
+        assert!(!status.is_set(UncorrectableErrorKind::CompletionTimeout));
+        // Pointer is 5 bits wide, but Reserved can hold any value
+        assert!(!status.is_set(UncorrectableErrorKind::Reserved(40)));
+    }
+
     #[test]
     fn correctable_error_status() {
         // CEMsk:  RxErr- BadTLP- BadDLLP- Rollover- Timeout- AdvNonFatalErr+
@@ -620,6 +937,7 @@ mod tests {
             advisory_non_fatal_error_status: true,
             corrected_internal_error_status: false,
             header_log_overflow_status: false,
+            reserved: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
     }
@@ -671,5 +989,12 @@ mod tests {
             advanced_error_interrupt_message_number: 0,
         };
         assert_eq!(sample, u32::from_le_bytes(data).into());
+
+        // RootSta: CERcvd+ MultCERcvd- UERcvd+ MultUERcvd- FirstFatal+ NonFatalMsg- FatalMsg+
+        //          IntMsg 31
+        let dword = 0xf800_0055;
+        let result = RootErrorStatus::from(dword);
+        assert_eq!(31, result.advanced_error_interrupt_message_number);
+        assert_eq!(dword, u32::from(result));
     }
 }
@@ -12,6 +12,13 @@ an ARI Device to support up to 256 Functions [0..255] instead of 8 Functions [0.
 - [AriCapability]
 - [AriControl]
 
+## Function Groups
+
+Functions of an ARI Device may be assigned to Function Groups for MFVC arbitration and for ACS
+P2P Egress Control. Function Group field of ARI Control register holds the assignment of each
+Function, MFVC and ACS Function Groups Enable bits are meaningful in Function 0 only.
+[FunctionGroups] collects the assignments of all Functions of a Device.
+
 ## Examples
 
 > ```text
@@ -39,6 +46,20 @@ let sample = AlternativeRoutingIdInterpretation {
 };
 assert_eq!(sample, result);
 ```
+
+Functions 0 and 1 in Group 0, Function 2 in Group 3, MFVC Function Groups are enabled:
+
+```rust
+# use pcics::extended_capabilities::alternative_routing_id_interpolation::*;
+let f0 = AlternativeRoutingIdInterpretation::try_from([0x01, 0x01, 0x01, 0x00].as_slice()).unwrap();
+let f1 = AlternativeRoutingIdInterpretation::try_from([0x00, 0x02, 0x00, 0x00].as_slice()).unwrap();
+let f2 = AlternativeRoutingIdInterpretation::try_from([0x00, 0x00, 0x30, 0x00].as_slice()).unwrap();
+let groups = FunctionGroups::new([(0, &f0), (1, &f1), (2, &f2)]);
+assert!(groups.mfvc_function_groups_enable);
+assert_eq!(Some(3), groups.group(2));
+assert_eq!(None, groups.group(3));
+assert_eq!(vec![0, 1], groups.functions(0).collect::<Vec<_>>());
+```
 */
 
 use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P2, P4, P5};
@@ -50,6 +71,10 @@ pub struct AlternativeRoutingIdInterpretation {
     pub ari_capability: AriCapability,
     pub ari_control: AriControl,
 }
+impl AlternativeRoutingIdInterpretation {
+    pub const SIZE: usize = 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for AlternativeRoutingIdInterpretation {
     type Error = ExtendedCapabilityDataError;
 
@@ -61,7 +86,7 @@ impl TryFrom<&[u8]> for AlternativeRoutingIdInterpretation {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Alternative Routing-ID Interpretation",
-                size: 4,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             ari_capability: From::<u16>::from(ari_capability),
@@ -119,3 +144,115 @@ impl From<u16> for AriControl {
         }
     }
 }
+
+impl From<AriControl> for u16 {
+    fn from(ctrl: AriControl) -> Self {
+        ctrl.mfvc_function_groups_enable as u16
+            | (ctrl.acs_function_groups_enable as u16) << 1
+            | ((ctrl.function_group & 0b111) as u16) << 4
+    }
+}
+
+/// Function Group assignments of the Functions of an ARI Device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionGroups {
+    /// MFVC Function Groups Enable of Function 0
+    pub mfvc_function_groups_enable: bool,
+    /// ACS Function Groups Enable of Function 0
+    pub acs_function_groups_enable: bool,
+    groups: [Option<u8>; FunctionGroups::FUNCTIONS],
+}
+
+impl FunctionGroups {
+    /// ARI Device has up to 256 Functions
+    pub const FUNCTIONS: usize = 256;
+
+    /// Collects assignments from ARI Capabilities of Functions listed with their Function Numbers
+    pub fn new<'a, I>(functions: I) -> Self
+    where
+        I: IntoIterator<Item = (u8, &'a AlternativeRoutingIdInterpretation)>,
+    {
+        let mut result = Self {
+            mfvc_function_groups_enable: false,
+            acs_function_groups_enable: false,
+            groups: [None; Self::FUNCTIONS],
+        };
+        for (function, ari) in functions {
+            let ctrl = &ari.ari_control;
+            if function == 0 {
+                result.mfvc_function_groups_enable = ctrl.mfvc_function_groups_enable;
+                result.acs_function_groups_enable = ctrl.acs_function_groups_enable;
+            }
+            if let Some(group) = result.groups.get_mut(function as usize) {
+                *group = Some(ctrl.function_group);
+            }
+        }
+        result
+    }
+    /// Function Group of the Function, `None` if the Function is not listed
+    pub fn group(&self, function: u8) -> Option<u8> {
+        self.groups.get(function as usize).copied().flatten()
+    }
+    /// Numbers of Functions assigned to the Function Group
+    pub fn functions(&self, group: u8) -> GroupFunctions<'_> {
+        GroupFunctions {
+            groups: self.groups.iter().enumerate(),
+            group,
+        }
+    }
+}
+
+/// Iterator over Function Numbers of a Function Group, see [FunctionGroups::functions]
+#[derive(Debug, Clone)]
+pub struct GroupFunctions<'a> {
+    groups: core::iter::Enumerate<core::slice::Iter<'a, Option<u8>>>,
+    group: u8,
+}
+
+impl<'a> Iterator for GroupFunctions<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let group = self.group;
+        self.groups
+            .find(|(_, g)| **g == Some(group))
+            .map(|(function, _)| function as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    fn ari(control: u16) -> AlternativeRoutingIdInterpretation {
+        let [lo, hi] = control.to_le_bytes();
+        [0x00, 0x00, lo, hi].as_slice().try_into().unwrap()
+    }
+
+    #[test]
+    fn ari_control() {
+        for word in [0x0000, 0x0001, 0x0002, 0x0073] {
+            assert_eq!(word, u16::from(AriControl::from(word)));
+        }
+        // Reserved bits are not kept
+        assert_eq!(0x0040, u16::from(AriControl::from(0xff4c)));
+    }
+
+    #[test]
+    fn function_groups() {
+        // ACS Function Groups Enable is set in Function 4, which is not Function 0
+        let functions = [(0, ari(0x0010)), (4, ari(0x0012)), (255, ari(0x0070))];
+        let groups = FunctionGroups::new(functions.iter().map(|(f, ari)| (*f, ari)));
+        assert!(!groups.mfvc_function_groups_enable);
+        assert!(!groups.acs_function_groups_enable);
+        assert_eq!(Some(1), groups.group(0));
+        assert_eq!(Some(1), groups.group(4));
+        assert_eq!(Some(7), groups.group(255));
+        assert_eq!(None, groups.group(1));
+        assert_eq!(vec![0, 4], groups.functions(1).collect::<Vec<_>>());
+        assert_eq!(vec![255], groups.functions(7).collect::<Vec<_>>());
+        assert_eq!(0, groups.functions(0).count());
+    }
+}
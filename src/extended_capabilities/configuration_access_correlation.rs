@@ -45,6 +45,8 @@ pub struct ConfigurationAccessCorrelation {
 impl ConfigurationAccessCorrelation {
     /// Size in bytes (with Extended Capability Header)
     pub const SIZE: usize = 0x8;
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl From<[u8; Self::SIZE]> for ConfigurationAccessCorrelation {
@@ -60,7 +62,7 @@ impl TryFrom<&[u8]> for ConfigurationAccessCorrelation {
     fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
         let Seq { head, .. } = slice.try_into().map_err(|_| ExtendedCapabilityDataError {
             name: "Configuration Access Correlation",
-            size: Self::SIZE,
+            size: Self::MIN_SIZE,
         })?;
         Ok(From::<[u8; Self::SIZE]>::from(head))
     }
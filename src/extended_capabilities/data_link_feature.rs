@@ -0,0 +1,188 @@
+/*!
+# Data Link Feature
+
+The Data Link Feature Capability is an optional Extended Capability required for Downstream
+Ports that support 16.0 GT/s or higher data rates. Link partners advertise their Data Link
+Features with Data Link Feature DLLPs during Link initialization, the features supported by both
+of them are active on the Link.
+
+## Struct diagram
+[DataLinkFeature]
+- [DataLinkFeatureCapabilities]
+- [DataLinkFeatureStatus]
+- [DataLinkFeatures]
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::data_link_feature::*;
+let data = [
+    0x25, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x80, 0x01, 0x00, 0x00, 0x80,
+];
+let sample = DataLinkFeature {
+    capabilities: DataLinkFeatureCapabilities {
+        local_data_link_feature_supported: DataLinkFeatures {
+            scaled_flow_control: true,
+            other: 0,
+        },
+        data_link_feature_exchange_enable: true,
+    },
+    status: DataLinkFeatureStatus {
+        remote_data_link_feature_supported: DataLinkFeatures {
+            scaled_flow_control: true,
+            other: 0,
+        },
+        remote_data_link_feature_supported_valid: true,
+    },
+};
+let result: DataLinkFeature = data[4..].try_into().unwrap();
+assert_eq!(sample, result);
+assert_eq!(Some(true), result.is_scaled_flow_control_active());
+```
+*/
+
+use heterob::{endianness::Le, Seq, P2};
+
+use super::ExtendedCapabilityDataError;
+use crate::register::{impl_u32_field, register};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLinkFeature {
+    /// Data Link Feature Capabilities
+    pub capabilities: DataLinkFeatureCapabilities,
+    /// Data Link Feature Status
+    pub status: DataLinkFeatureStatus,
+}
+impl DataLinkFeature {
+    pub const SIZE: usize = 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// Features supported by both Link partners, `None` while the remote features are not
+    /// received
+    ///
+    /// No features are active if Data Link Feature Exchange is disabled.
+    pub fn active_features(&self) -> Option<DataLinkFeatures> {
+        let local = &self.capabilities.local_data_link_feature_supported;
+        if !self.capabilities.data_link_feature_exchange_enable {
+            return Some(DataLinkFeatures::from(0));
+        }
+        if !self.status.remote_data_link_feature_supported_valid {
+            return None;
+        }
+        let remote = &self.status.remote_data_link_feature_supported;
+        Some(DataLinkFeatures {
+            scaled_flow_control: local.scaled_flow_control && remote.scaled_flow_control,
+            other: local.other & remote.other,
+        })
+    }
+    /// Scaled Flow Control is active on the Link, `None` while the remote features are not
+    /// received
+    pub fn is_scaled_flow_control_active(&self) -> Option<bool> {
+        self.active_features()
+            .map(|features| features.scaled_flow_control)
+    }
+}
+impl TryFrom<&[u8]> for DataLinkFeature {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((capabilities, status)),
+            ..
+        } = P2(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Data Link Feature",
+                size: Self::MIN_SIZE,
+            })?;
+        Ok(Self {
+            capabilities: From::<u32>::from(capabilities),
+            status: From::<u32>::from(status),
+        })
+    }
+}
+
+/// Data Link Features bits of Data Link Feature Capabilities and Status registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLinkFeatures {
+    /// Scaled Flow Control
+    pub scaled_flow_control: bool,
+    /// Bits 22:1 of the features field, reserved or defined by later specifications
+    pub other: u32,
+}
+register!(DataLinkFeatures: u32 {
+    scaled_flow_control[0],
+    other[1..23],
+});
+impl_u32_field!(DataLinkFeatures);
+
+/// Describes the Data Link Features supported by the Port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLinkFeatureCapabilities {
+    /// Local Data Link Feature Supported
+    pub local_data_link_feature_supported: DataLinkFeatures,
+    /// Data Link Feature Exchange Enable
+    pub data_link_feature_exchange_enable: bool,
+}
+register!(DataLinkFeatureCapabilities: u32 {
+    local_data_link_feature_supported[0..23],
+    data_link_feature_exchange_enable[31],
+});
+
+/// Data Link Features received from the Link partner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLinkFeatureStatus {
+    /// Remote Data Link Feature Supported
+    pub remote_data_link_feature_supported: DataLinkFeatures,
+    /// Remote Data Link Feature Supported Valid
+    pub remote_data_link_feature_supported_valid: bool,
+}
+register!(DataLinkFeatureStatus: u32 {
+    remote_data_link_feature_supported[0..23],
+    remote_data_link_feature_supported_valid[31],
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn dlf(capabilities: u32, status: u32) -> DataLinkFeature {
+        DataLinkFeature {
+            capabilities: capabilities.into(),
+            status: status.into(),
+        }
+    }
+
+    #[test]
+    fn active_features() {
+        // Remote features are not received yet
+        let result = dlf(0x8000_0001, 0x0000_0001);
+        assert_eq!(None, result.is_scaled_flow_control_active());
+        // Link partner does not support Scaled Flow Control
+        let result = dlf(0x8000_0007, 0x8000_0006);
+        let sample = DataLinkFeatures {
+            scaled_flow_control: false,
+            other: 0b11,
+        };
+        assert_eq!(Some(sample), result.active_features());
+        assert_eq!(Some(false), result.is_scaled_flow_control_active());
+        // Data Link Feature Exchange is disabled
+        let result = dlf(0x0000_0001, 0x0000_0001);
+        assert_eq!(Some(false), result.is_scaled_flow_control_active());
+        // Features field is 23 bits wide
+        let result = DataLinkFeatures::from(0xffff_ffff);
+        assert_eq!(
+            (true, 0x3f_ffff),
+            (result.scaled_flow_control, result.other)
+        );
+    }
+
+    #[test]
+    fn encode() {
+        // Reserved bits 30:23 are zero on encoding
+        let result = DataLinkFeatureCapabilities::from(0xffff_ffff);
+        assert_eq!(0x807f_ffff, u32::from(result));
+        let result = DataLinkFeatureStatus::from(0x8000_0005);
+        assert_eq!(0x8000_0005, u32::from(result));
+    }
+}
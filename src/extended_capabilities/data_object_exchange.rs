@@ -0,0 +1,433 @@
+/*!
+# Data Object Exchange (DOE)
+
+Data Object Exchange is an optional mechanism for system firmware/software to perform data
+object exchanges with a Function or RCRB through a mailbox. Protocols supported by a DOE
+instance (e.g. CMA/SPDM) are found with DOE Discovery data objects.
+
+## Struct diagram
+[DataObjectExchange]
+- [DoeCapabilities]
+- [DoeControl]
+- [DoeStatus]
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::data_object_exchange::*;
+use pretty_assertions::assert_eq;
+let data = [
+    0x2e, 0x00, 0x01, 0x00, 0x07, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x80,
+];
+let result: DataObjectExchange = data[4..].try_into().unwrap();
+let sample = DataObjectExchange {
+    doe_capabilities: DoeCapabilities {
+        doe_interrupt_support: true,
+        doe_interrupt_message_number: 3,
+    },
+    doe_control: DoeControl {
+        doe_abort: false,
+        doe_interrupt_enable: true,
+        doe_go: false,
+    },
+    doe_status: DoeStatus {
+        doe_busy: false,
+        doe_interrupt_status: false,
+        doe_error: false,
+        data_object_ready: true,
+    },
+};
+assert_eq!(sample, result);
+```
+
+## Discovery
+
+Mailbox access is out of scope of this crate. [DoeInstances] lists DOE instances of a function
+and [DoeInstance::discover] walks DOE Discovery with a caller supplied exchange of request and
+response data objects:
+
+```rust
+# use pcics::extended_capabilities::{data_object_exchange::*, ExtendedCapabilities};
+# use pcics::ConfigOffset;
+let ecs = [
+    0x2e, 0x00, 0x01, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00,
+];
+let doe = DoeInstances::new(ExtendedCapabilities::new(&ecs)).next().unwrap();
+assert_eq!((ConfigOffset(0x100), Some(3)), (doe.offset, doe.interrupt_message_number()));
+
+// DOE Discovery and CMA/SPDM protocols
+let responder = |_: ConfigOffset, request: [u32; 3]| {
+    let index = request[2] & 0xff;
+    let (protocol, next_index) = [(0x00, 1), (0x01, 0)][index as usize];
+    Some([0x0000_0001, 3, 0x0001 | protocol << 16 | next_index << 24])
+};
+let protocols = doe
+    .discover(responder)
+    .map(|response| response.map(|r| (r.vendor_id, r.data_object_protocol)))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(vec![(0x0001, 0x00), (0x0001, 0x01)], protocols);
+```
+*/
+
+use heterob::{endianness::Le, Seq, P3};
+use snafu::Snafu;
+
+use super::{
+    ExtendedCapabilities, ExtendedCapability, ExtendedCapabilityDataError, ExtendedCapabilityKind,
+};
+use crate::{register::register, ConfigOffset};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataObjectExchange {
+    /// DOE Capabilities
+    pub doe_capabilities: DoeCapabilities,
+    /// DOE Control
+    pub doe_control: DoeControl,
+    /// DOE Status
+    pub doe_status: DoeStatus,
+}
+impl DataObjectExchange {
+    pub const MIN_SIZE: usize = 4 + 4 + 4;
+    /// DOE Control register offset from the Extended Capability Header
+    pub const CONTROL_OFFSET: u16 = 0x08;
+    /// DOE Status register offset from the Extended Capability Header
+    pub const STATUS_OFFSET: u16 = 0x0c;
+    /// DOE Write Data Mailbox register offset from the Extended Capability Header
+    pub const WRITE_DATA_MAILBOX_OFFSET: u16 = 0x10;
+    /// DOE Read Data Mailbox register offset from the Extended Capability Header
+    pub const READ_DATA_MAILBOX_OFFSET: u16 = 0x14;
+}
+impl TryFrom<&[u8]> for DataObjectExchange {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((doe_capabilities, doe_control, doe_status)),
+            ..
+        } = P3(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Data Object Exchange",
+                size: Self::MIN_SIZE,
+            })?;
+        Ok(Self {
+            doe_capabilities: From::<u32>::from(doe_capabilities),
+            doe_control: From::<u32>::from(doe_control),
+            doe_status: From::<u32>::from(doe_status),
+        })
+    }
+}
+
+/// DOE Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoeCapabilities {
+    /// DOE Interrupt Support
+    pub doe_interrupt_support: bool,
+    /// DOE Interrupt Message Number
+    pub doe_interrupt_message_number: u16,
+}
+register!(DoeCapabilities: u32 {
+    doe_interrupt_support[0],
+    doe_interrupt_message_number[1..12],
+});
+
+/// DOE Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoeControl {
+    /// DOE Abort
+    pub doe_abort: bool,
+    /// DOE Interrupt Enable
+    pub doe_interrupt_enable: bool,
+    /// DOE Go
+    pub doe_go: bool,
+}
+register!(DoeControl: u32 {
+    doe_abort[0],
+    doe_interrupt_enable[1],
+    doe_go[31],
+});
+
+/// DOE Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoeStatus {
+    /// DOE Busy
+    pub doe_busy: bool,
+    /// DOE Interrupt Status
+    pub doe_interrupt_status: bool,
+    /// DOE Error
+    pub doe_error: bool,
+    /// Data Object Ready
+    pub data_object_ready: bool,
+}
+impl DoeStatus {
+    /// DOE Interrupt Status bit of DOE Status register
+    ///
+    /// Bit is RW1C, writing this value clears it
+    pub const DOE_INTERRUPT_STATUS: u32 = 1 << 1;
+}
+register!(DoeStatus: u32 {
+    doe_busy[0],
+    doe_interrupt_status[1],
+    doe_error[2],
+    data_object_ready[31],
+});
+
+/// First two DWORDs of every data object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataObjectHeader {
+    /// Vendor ID of the data object type owner
+    pub vendor_id: u16,
+    /// Data Object Type
+    pub data_object_type: u8,
+    /// Data object length in DWORDs including the header, from 2 to 2<sup>18</sup>
+    pub length: u32,
+}
+impl DataObjectHeader {
+    /// Vendor ID of PCI-SIG defined data objects
+    pub const PCI_SIG_VENDOR_ID: u16 = 0x0001;
+    /// DOE Discovery data object type
+    pub const DOE_DISCOVERY: u8 = 0x00;
+    /// Length field is 18 bits wide, 0 means 2<sup>18</sup> DWORDs
+    const LENGTH_MASK: u32 = (1 << 18) - 1;
+}
+impl From<[u32; 2]> for DataObjectHeader {
+    fn from([dw1, dw2]: [u32; 2]) -> Self {
+        let length = match dw2 & Self::LENGTH_MASK {
+            0 => Self::LENGTH_MASK + 1,
+            n => n,
+        };
+        Self {
+            vendor_id: dw1 as u16,
+            data_object_type: (dw1 >> 16) as u8,
+            length,
+        }
+    }
+}
+impl From<DataObjectHeader> for [u32; 2] {
+    fn from(header: DataObjectHeader) -> Self {
+        [
+            header.vendor_id as u32 | (header.data_object_type as u32) << 16,
+            header.length & DataObjectHeader::LENGTH_MASK,
+        ]
+    }
+}
+
+/// DOE Discovery response data object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryResponse {
+    /// Vendor ID of the data object protocol
+    pub vendor_id: u16,
+    /// Data Object Protocol
+    pub data_object_protocol: u8,
+    /// Index of the next entry, 0 if this is the last entry
+    pub next_index: u8,
+}
+register!(DiscoveryResponse: u32 {
+    vendor_id[0..16],
+    data_object_protocol[16..24],
+    next_index[24..32],
+});
+impl DiscoveryResponse {
+    /// DOE Discovery data object length in DWORDs
+    pub const LENGTH: u32 = 3;
+    /// DOE Discovery request data object for entry at `index`
+    pub fn request(index: u8) -> [u32; 3] {
+        let [dw1, dw2] = DataObjectHeader {
+            vendor_id: DataObjectHeader::PCI_SIG_VENDOR_ID,
+            data_object_type: DataObjectHeader::DOE_DISCOVERY,
+            length: Self::LENGTH,
+        }
+        .into();
+        [dw1, dw2, index as u32]
+    }
+}
+impl TryFrom<[u32; 3]> for DiscoveryResponse {
+    type Error = DiscoveryError;
+
+    fn try_from([dw1, dw2, dw3]: [u32; 3]) -> Result<Self, Self::Error> {
+        let header = DataObjectHeader::from([dw1, dw2]);
+        if header.vendor_id != DataObjectHeader::PCI_SIG_VENDOR_ID
+            || header.data_object_type != DataObjectHeader::DOE_DISCOVERY
+            || header.length != Self::LENGTH
+        {
+            return Err(DiscoveryError::Header { header });
+        }
+        Ok(dw3.into())
+    }
+}
+
+/// DOE Discovery errors
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DiscoveryError {
+    #[snafu(display("data object exchange of entry {index} failed"))]
+    Exchange { index: u8 },
+    #[snafu(display("unexpected DOE Discovery response header {header:?}"))]
+    Header { header: DataObjectHeader },
+}
+
+/// DOE instance of a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoeInstance {
+    /// Extended capability offset in configuration space
+    pub offset: ConfigOffset,
+    pub data_object_exchange: DataObjectExchange,
+}
+impl DoeInstance {
+    /// MSI/MSI-X vector used for DOE interrupts, `None` if interrupts are not supported
+    pub fn interrupt_message_number(&self) -> Option<u16> {
+        let caps = &self.data_object_exchange.doe_capabilities;
+        caps.doe_interrupt_support
+            .then_some(caps.doe_interrupt_message_number)
+    }
+    /// An iterator through DOE Discovery entries
+    ///
+    /// `exchange` writes the request data object to the mailbox of the instance at given offset
+    /// and returns the response data object, `None` if the exchange failed.
+    pub fn discover<F>(&self, exchange: F) -> Discovery<F>
+    where
+        F: FnMut(ConfigOffset, [u32; 3]) -> Option<[u32; 3]>,
+    {
+        Discovery {
+            offset: self.offset,
+            exchange,
+            index: Some(0),
+            remaining: u8::MAX as usize + 1,
+        }
+    }
+}
+
+/// An iterator through DOE instances of *Extended Capabilities List*
+///
+/// Broken extended capabilities are skipped.
+#[derive(Debug, Clone)]
+pub struct DoeInstances<'a> {
+    ecaps: ExtendedCapabilities<'a>,
+}
+impl<'a> DoeInstances<'a> {
+    pub fn new(ecaps: ExtendedCapabilities<'a>) -> Self {
+        Self { ecaps }
+    }
+}
+impl<'a> Iterator for DoeInstances<'a> {
+    type Item = DoeInstance;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ecaps.find_map(|ecap| match ecap.ok()? {
+            ExtendedCapability {
+                kind: ExtendedCapabilityKind::DataObjectExchange(data_object_exchange),
+                offset,
+                ..
+            } => Some(DoeInstance {
+                offset,
+                data_object_exchange,
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// An iterator through DOE Discovery entries, see [DoeInstance::discover]
+///
+/// Iteration stops after the entry with Next Index 0, after the first error and after 256
+/// entries.
+#[derive(Debug, Clone)]
+pub struct Discovery<F> {
+    offset: ConfigOffset,
+    exchange: F,
+    index: Option<u8>,
+    remaining: usize,
+}
+impl<F> Iterator for Discovery<F>
+where
+    F: FnMut(ConfigOffset, [u32; 3]) -> Option<[u32; 3]>,
+{
+    type Item = Result<DiscoveryResponse, DiscoveryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index.take().filter(|_| self.remaining > 0)?;
+        self.remaining -= 1;
+        let result = (self.exchange)(self.offset, DiscoveryResponse::request(index))
+            .ok_or(DiscoveryError::Exchange { index })
+            .and_then(DiscoveryResponse::try_from);
+        if let Ok(response) = &result {
+            self.index = Some(response.next_index).filter(|&index| index != 0);
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn data_object_header() {
+        let header = DataObjectHeader::from([0x0002_0001, 0]);
+        let sample = DataObjectHeader {
+            vendor_id: 0x0001,
+            data_object_type: 0x02,
+            length: 1 << 18,
+        };
+        assert_eq!(sample, header);
+        assert_eq!([0x0002_0001, 0], <[u32; 2]>::from(header));
+        assert_eq!([0x0000_0001, 3, 0x05], DiscoveryResponse::request(5));
+    }
+
+    #[test]
+    fn discovery() {
+        let offset = ConfigOffset(0x180);
+        let doe = DoeInstance {
+            offset,
+            data_object_exchange: [0u8; DataObjectExchange::MIN_SIZE]
+                .as_slice()
+                .try_into()
+                .unwrap(),
+        };
+        assert_eq!(None, doe.interrupt_message_number());
+
+        // Next Index loops back to the first entry
+        let mut requests = Vec::new();
+        let result = doe
+            .discover(|o, request| {
+                assert_eq!(offset, o);
+                requests.push(request[2]);
+                Some([0x0000_0001, 3, 0x0001 | ((request[2] + 1) % 3) << 24])
+            })
+            .count();
+        assert_eq!(3, result);
+        assert_eq!(vec![0, 1, 2], requests);
+
+        let result = doe
+            .discover(|_, _| Some([0x0000_0001, 3, 0x0001 | 1 << 24]))
+            .take(300)
+            .count();
+        assert_eq!(256, result);
+
+        let result = doe
+            .discover(|_, request| (request[2] == 0).then_some([0x0000_0001, 3, 1 << 24]))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            Ok(DiscoveryResponse {
+                vendor_id: 0,
+                data_object_protocol: 0,
+                next_index: 1,
+            }),
+            Err(DiscoveryError::Exchange { index: 1 }),
+        ];
+        assert_eq!(sample, result);
+
+        let result = doe.discover(|_, _| Some([0x0001_0001, 3, 0])).next();
+        let header = DataObjectHeader {
+            vendor_id: 0x0001,
+            data_object_type: 0x01,
+            length: 3,
+        };
+        assert_eq!(Some(Err(DiscoveryError::Header { header })), result);
+    }
+}
@@ -35,7 +35,12 @@ let sample = DesignatedVendorSpecificExtendedCapability {
 };
 
 assert_eq!(sample, result);
+assert!(result.is(0x8086, 0x23));
+assert_eq!(Some([0x00, 0x11, 0x22, 0x33, 0x44, 0x55].as_slice()), result.payload());
 ```
+
+All DVSEC structures of a function with their offsets are yielded by
+[ExtendedCapabilities::dvsecs](super::ExtendedCapabilities::dvsecs).
 */
 
 use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P3};
@@ -45,8 +50,9 @@ use super::ExtendedCapabilityHeaderPlaceholder;
 
 /// DVSEC Errors
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DesignatedVendorSpecificExtendedCapabilityError {
-    #[snafu(display("mandatory fields are unreadable"))]
+    #[snafu(display("mandatory fields ({} bytes) are unreadable", Dvsec::MIN_SIZE))]
     Mandatory,
     #[snafu(display(
         "Vendor-specific registers (VID: {:04x}, rev: {:02x}, ID: {:04x}) are unreadable. Length expected: {}, real: {}",
@@ -63,11 +69,26 @@ pub enum DesignatedVendorSpecificExtendedCapabilityError {
         dvsec_id: u16,
         real: usize,
     },
+    #[cfg(feature = "cxl")]
     #[snafu(display("Compute Express Link error: {source}"))]
     ComputeExpressLink {
         source: compute_express_link::ComputeExpressLinkError,
     },
 }
+impl DesignatedVendorSpecificExtendedCapabilityError {
+    /// Bytes from the start of the Extended Capability Header needed by the failed read,
+    /// `None` if DVSEC Length is shorter than DVSEC headers or CXL structure is invalid
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::Mandatory => Some(Dvsec::MIN_SIZE),
+            Self::VendorSpecificRegisters { dvsec_length, .. } => {
+                Some(usize::from(*dvsec_length)).filter(|&len| len >= Dvsec::MIN_SIZE)
+            }
+            #[cfg(feature = "cxl")]
+            Self::ComputeExpressLink { .. } => None,
+        }
+    }
+}
 
 /// [DesignatedVendorSpecificExtendedCapabilityError] alias
 pub type DvsecError = DesignatedVendorSpecificExtendedCapabilityError;
@@ -92,6 +113,7 @@ pub struct DesignatedVendorSpecificExtendedCapability<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DvsecType<'a> {
     Unspecified(&'a [u8]),
+    #[cfg(feature = "cxl")]
     ComputeExpressLink(ComputeExpressLink),
 }
 
@@ -103,6 +125,18 @@ impl<'a> Dvsec<'a> {
     pub const MIN_SIZE: usize = 0x0a;
     /// Max size in bytes (with Extended Capability Header)
     pub const MAX_SIZE: usize = 0xfff - 0x100;
+    /// Structure is defined by the vendor `dvsec_vendor_id` with `dvsec_id`
+    pub fn is(&self, dvsec_vendor_id: u16, dvsec_id: u16) -> bool {
+        self.dvsec_vendor_id == dvsec_vendor_id && self.dvsec_id == dvsec_id
+    }
+    /// DVSEC vendor-specific registers, `None` if the structure is decoded
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        match self.dvsec_type {
+            DvsecType::Unspecified(slice) => Some(slice),
+            #[cfg(feature = "cxl")]
+            _ => None,
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for Dvsec<'a> {
@@ -125,6 +159,7 @@ impl<'a> TryFrom<&'a [u8]> for Dvsec<'a> {
                 real: slice.len(),
             })?;
         let dvsec_type = match dvsec_vendor_id {
+            #[cfg(feature = "cxl")]
             0x1e98 => ComputeExpressLink::try_new(dvsec_vendor_specific_registers, dvsec_id)
                 .map(DvsecType::ComputeExpressLink)
                 .context(ComputeExpressLinkSnafu)?,
@@ -140,5 +175,7 @@ impl<'a> TryFrom<&'a [u8]> for Dvsec<'a> {
     }
 }
 
+#[cfg(feature = "cxl")]
 pub mod compute_express_link;
+#[cfg(feature = "cxl")]
 pub use compute_express_link::ComputeExpressLink;
@@ -49,6 +49,7 @@ use snafu::Snafu;
 
 /// Compute Express Link (CXL) Errors
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ComputeExpressLinkError {
     #[snafu(display("PCIe DVSEC for CXL Device"))]
     PcieDvsecForCxlDevice,
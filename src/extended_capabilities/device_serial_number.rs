@@ -37,6 +37,10 @@ pub struct DeviceSerialNumber {
     /// PCI Express Device Serial Number (2nd DW)
     pub upper_dword: u32,
 }
+impl DeviceSerialNumber {
+    pub const SIZE: usize = 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for DeviceSerialNumber {
     type Error = ExtendedCapabilityDataError;
 
@@ -48,7 +52,7 @@ impl TryFrom<&[u8]> for DeviceSerialNumber {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Device Serial Number",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             lower_dword,
@@ -16,6 +16,9 @@ which prevents TLPs subsequent to the error from propagating Upstream or Downstr
   - [HeaderLog]
   - [TlpPrefixLog]
 
+[DpcControl::software_trigger] and [DpcStatus::ack] compose register values for triggering
+DPC from software and for releasing the Port from containment after the error is handled.
+
 ## Examples
 
 > ```text
@@ -147,12 +150,18 @@ use heterob::{
 };
 use snafu::Snafu;
 
+use crate::register::{impl_u8_field, register};
+
 pub use super::advanced_error_reporting::{HeaderLog, TlpPrefixLog};
 
 /// Downstream Port Containment Error
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DownstreamPortContainmentError {
-    #[snafu(display("can't read manadatory registers (8 bytes)"))]
+    #[snafu(display(
+        "can't read manadatory registers ({} bytes)",
+        DownstreamPortContainment::MIN_SIZE
+    ))]
     Mandatory,
     #[snafu(display("can't read RP Extensions registers (36 bytes)"))]
     RpExtensions,
@@ -161,6 +170,19 @@ pub enum DownstreamPortContainmentError {
     #[snafu(display("can't read RP PIO TLP Prefix Log Register word#: {number}"))]
     RpPioTlpPrefixLog { number: usize },
 }
+impl DownstreamPortContainmentError {
+    /// Bytes after the Extended Capability Header needed by the failed read, RP PIO registers
+    /// follow the mandatory ones
+    pub fn needed(&self) -> Option<usize> {
+        let rp_extensions = DownstreamPortContainment::MIN_SIZE + 36;
+        Some(match self {
+            Self::Mandatory => DownstreamPortContainment::MIN_SIZE,
+            Self::RpExtensions => rp_extensions,
+            Self::RpPioImpspecLog => rp_extensions + 4,
+            Self::RpPioTlpPrefixLog { number } => rp_extensions + 4 + (number + 1) * 4,
+        })
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DownstreamPortContainment {
@@ -178,6 +200,9 @@ pub struct DownstreamPortContainment {
     /// are specific to Root Ports. Switch Downstream Ports must not Set this bit.
     pub rp_extensions: Option<RpExtensions>,
 }
+impl DownstreamPortContainment {
+    pub const MIN_SIZE: usize = 2 + 2 + 2 + 2;
+}
 impl TryFrom<&[u8]> for DownstreamPortContainment {
     type Error = DownstreamPortContainmentError;
 
@@ -341,27 +366,34 @@ pub struct DpcControl {
     pub dl_active_err_cor_enable: bool,
 }
 
-impl From<u16> for DpcControl {
-    fn from(word: u16) -> Self {
-        let Lsb((
-            dpc_trigger_enable,
-            dpc_completion_control,
-            dpc_interrupt_enable,
-            dpc_err_cor_enable,
-            poisoned_tlp_egress_blocking_enable,
-            dpc_software_trigger,
-            dl_active_err_cor_enable,
-            (),
-        )) = P8::<_, 2, 1, 1, 1, 1, 1, 1, 8>(word).into();
-        Self {
-            dpc_trigger_enable: From::<u8>::from(dpc_trigger_enable),
-            dpc_completion_control,
-            dpc_interrupt_enable,
-            dpc_err_cor_enable,
-            poisoned_tlp_egress_blocking_enable,
-            dpc_software_trigger,
-            dl_active_err_cor_enable,
+register!(DpcControl: u16 {
+    dpc_trigger_enable[0..2],
+    dpc_completion_control[2],
+    dpc_interrupt_enable[3],
+    dpc_err_cor_enable[4],
+    poisoned_tlp_egress_blocking_enable[5],
+    dpc_software_trigger[6],
+    dl_active_err_cor_enable[7],
+});
+
+impl DpcControl {
+    /// DPC Control register value triggering DPC from software, other fields keep their values
+    ///
+    /// `None` if the Port does not support software triggering or DPC is disabled, writing DPC
+    /// Software Trigger has no effect then.
+    pub fn software_trigger(&self, capability: &DpcCapability) -> Option<u16> {
+        let enabled = matches!(
+            self.dpc_trigger_enable,
+            DpcTrigger::ErrFatalMessage | DpcTrigger::ErrNonFatalMessage
+        );
+        if !capability.dpc_software_triggering_supported || !enabled {
+            return None;
         }
+        let control = Self {
+            dpc_software_trigger: true,
+            ..self.clone()
+        };
+        Some(control.into())
     }
 }
 
@@ -387,6 +419,17 @@ impl From<u8> for DpcTrigger {
         }
     }
 }
+impl From<DpcTrigger> for u8 {
+    fn from(trigger: DpcTrigger) -> Self {
+        match trigger {
+            DpcTrigger::Disabled => 0b00,
+            DpcTrigger::ErrFatalMessage => 0b01,
+            DpcTrigger::ErrNonFatalMessage => 0b10,
+            DpcTrigger::Reserved => 0b11,
+        }
+    }
+}
+impl_u8_field!(DpcTrigger);
 
 /// DPC Status
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -428,6 +471,49 @@ impl From<u16> for DpcStatus {
     }
 }
 
+impl From<DpcStatus> for u16 {
+    fn from(status: DpcStatus) -> Self {
+        let reason = status.dpc_trigger_reason.value() as u16;
+        let extension = status.dpc_trigger_reason.extension_value() as u16;
+        status.dpc_trigger_status as u16
+            | reason << 1
+            | (status.dpc_interrupt_status as u16) << 3
+            | (status.dpc_rp_busy as u16) << 4
+            | extension << 5
+            | (status.rp_pio_first_error_pointer as u16 & 0x1f) << 8
+    }
+}
+
+impl DpcStatus {
+    /// DPC Trigger Status bit of DPC Status register
+    ///
+    /// Bit is RW1C, clearing it releases the Downstream Port from DPC
+    pub const DPC_TRIGGER_STATUS: u16 = 1 << 0;
+    /// DPC Interrupt Status bit of DPC Status register
+    ///
+    /// Bit is RW1C, writing this value clears it
+    pub const DPC_INTERRUPT_STATUS: u16 = 1 << 3;
+
+    /// DPC Status register value clearing the set DPC Trigger Status and DPC Interrupt Status
+    /// bits after the error is handled
+    ///
+    /// `None` while DPC RP Busy is set, software must wait for the Root Port to complete
+    /// internal activity before clearing DPC Trigger Status.
+    pub fn ack(&self) -> Option<u16> {
+        if self.dpc_rp_busy {
+            return None;
+        }
+        let mut value = 0;
+        if self.dpc_trigger_status {
+            value |= Self::DPC_TRIGGER_STATUS;
+        }
+        if self.dpc_interrupt_status {
+            value |= Self::DPC_INTERRUPT_STATUS;
+        }
+        Some(value)
+    }
+}
+
 /// Indicates why DPC has been triggered
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DpcTriggerReason {
@@ -445,6 +531,8 @@ pub enum DpcTriggerReason {
 }
 
 impl DpcTriggerReason {
+    /// Decodes DPC Trigger Reason and DPC Trigger Reason Extension fields, the extension is
+    /// used only if the reason is 11b
     pub fn new(reason: u8, reason_extension: u8) -> Self {
         match (reason, reason_extension) {
             (0b00, _) => Self::UnmaskedUncorrectableError,
@@ -455,6 +543,7 @@ impl DpcTriggerReason {
             (_, v) => Self::Reserved(v),
         }
     }
+    /// DPC Trigger Reason field value
     pub fn value(&self) -> u8 {
         match self {
             Self::UnmaskedUncorrectableError => 0b00,
@@ -463,6 +552,7 @@ impl DpcTriggerReason {
             _ => 0b11,
         }
     }
+    /// DPC Trigger Reason Extension field value, 00b if the reason is not 11b
     pub fn extension_value(&self) -> u8 {
         match self {
             Self::RpPioError => 0b00,
@@ -524,3 +614,58 @@ impl From<u32> for RpPio {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dpc_trigger_reason() {
+        let reasons = [
+            (0b00, 0b00, DpcTriggerReason::UnmaskedUncorrectableError),
+            (0b01, 0b11, DpcTriggerReason::ReceivingAnErrNonFatal),
+            (0b10, 0b00, DpcTriggerReason::ReceivingAnErrFatal),
+            (0b11, 0b00, DpcTriggerReason::RpPioError),
+            (0b11, 0b01, DpcTriggerReason::DpcSoftwareTriggerBit),
+            (0b11, 0b10, DpcTriggerReason::Reserved(0b10)),
+        ];
+        for (reason, extension, sample) in reasons {
+            let status = DpcStatus::from(1 | reason << 1 | extension << 5);
+            assert_eq!(
+                sample, status.dpc_trigger_reason,
+                "{reason:02b} {extension:02b}"
+            );
+            let extension = if reason == 0b11 { extension } else { 0 };
+            assert_eq!(1 | reason << 1 | extension << 5, u16::from(status));
+        }
+    }
+
+    #[test]
+    fn software_trigger() {
+        // Trigger on ERR_FATAL, DPC Interrupt Enable, Software Triggering Supported
+        let control = DpcControl::from(0b1001);
+        let capability = DpcCapability::from(1 << 7);
+        assert_eq!(Some(0b100_1001), control.software_trigger(&capability));
+        assert_eq!(0b1001, u16::from(control.clone()));
+        assert_eq!(None, control.software_trigger(&DpcCapability::from(0)));
+        // DPC is disabled
+        let control = DpcControl::from(0b1000);
+        assert_eq!(None, control.software_trigger(&capability));
+    }
+
+    #[test]
+    fn ack() {
+        // Triggered by software, DPC Interrupt Status, RP PIO First Error Pointer 1fh
+        let status = DpcStatus::from(0x1f2f);
+        assert_eq!(
+            DpcTriggerReason::DpcSoftwareTriggerBit,
+            status.dpc_trigger_reason
+        );
+        assert_eq!(Some(0b1001), status.ack());
+        assert_eq!(0x1f2f, u16::from(status));
+        // DPC RP Busy
+        assert_eq!(None, DpcStatus::from(0b1_1001).ack());
+        assert_eq!(Some(0), DpcStatus::from(0).ack());
+    }
+}
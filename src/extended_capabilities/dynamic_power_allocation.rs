@@ -54,20 +54,72 @@ let sample = DynamicPowerAllocation {
 
 assert_eq!(sample, result);
 ```
+
+## Substate selection
+
+Software selects a substate by writing Substate Control, the Function reports the new substate
+in Substate Status within the Transition Latency of the substate:
+
+```rust
+# use pcics::extended_capabilities::dynamic_power_allocation::*;
+# use pcics::config_write::{AccessWidth, RegisterWrite};
+# use pcics::ConfigOffset;
+# let data = [
+#     0x16, 0x00, 0x01, 0x00, 0x03, 0x02, 0x05, 0x0a, 0x02, 0x00, 0x00, 0x00,
+#     0x03, 0x01, 0x03, 0x00, 0x00, 0x11, 0x22, 0x33,
+# ];
+let dpa: DynamicPowerAllocation = data.as_slice().try_into().unwrap();
+// Substate 1 uses Transition Latency Value 1: 10 x 100 ms
+let transition = dpa.select_substate(1).unwrap();
+assert_eq!(Some(1000), transition.latency_ms);
+// DPA Extended Capability at 200h, Substate Control is changed from 3 to 1
+let write = transition.write(ConfigOffset(0x200), 0x0003).unwrap();
+let sample = RegisterWrite {
+    offset: ConfigOffset(0x20e),
+    width: AccessWidth::Byte,
+    value: 0x01,
+};
+assert_eq!(sample, write);
+
+assert_eq!(
+    Err(DpaSubstateError::SubstateMax { substate: 4, substate_max: 3 }),
+    dpa.select_substate(4)
+);
+```
 */
 
 use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P2, P4, P8};
-use snafu::Snafu;
+use snafu::prelude::*;
 
 use super::ExtendedCapabilityHeader;
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor, RegisterWrite},
+    ConfigOffset,
+};
 
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DynamicPowerAllocationError {
-    #[snafu(display("capability, latency indicator, status and control fields are unreadable"))]
+    #[snafu(display(
+        "capability, latency indicator, status and control fields are unreadable ({} bytes)",
+        DynamicPowerAllocation::MIN_SIZE
+    ))]
     Mandatory,
     #[snafu(display("number of entries must be equal to the Substate_Max plus one (expected: {expected}, found: {found})"))]
     DpaAllocationArray { expected: usize, found: usize },
 }
+impl DynamicPowerAllocationError {
+    /// Bytes from the start of the Extended Capability Header needed by the failed read, the
+    /// DPA Power Allocation Array has one byte per substate
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::Mandatory => DynamicPowerAllocation::MIN_SIZE,
+            Self::DpaAllocationArray { expected, .. } => {
+                DynamicPowerAllocation::MIN_SIZE - 1 + expected
+            }
+        })
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DynamicPowerAllocation<'a> {
@@ -80,6 +132,66 @@ pub struct DynamicPowerAllocation<'a> {
     pub dpa_power_allocation_array: DpaPowerAllocationArray<'a>,
 }
 
+impl DynamicPowerAllocation<'_> {
+    /// Min size in bytes (with Extended Capability Header and one DPA Power Allocation Array
+    /// entry)
+    pub const MIN_SIZE: usize = ExtendedCapabilityHeader::SIZE + 4 + 4 + 2 + 2 + 1;
+    /// Maximum Transition Latency of `substate` in ms, `None` if the substate is not supported
+    /// or Transition Latency Unit is reserved
+    ///
+    /// DPA Latency Indicator bit selects Transition Latency Value 1 if set, Value 0 otherwise.
+    pub fn transition_latency_ms(&self, substate: u8) -> Option<u32> {
+        let DpaCapability {
+            substate_max,
+            ref transition_latency_unit,
+            transition_latency_value_0,
+            transition_latency_value_1,
+            ..
+        } = self.dpa_capability;
+        if substate > substate_max {
+            return None;
+        }
+        let value = if self.dpa_latency_indicator & (1 << substate) != 0 {
+            transition_latency_value_1
+        } else {
+            transition_latency_value_0
+        };
+        transition_latency_unit.ms().map(|unit| value as u32 * unit)
+    }
+    /// Checks that `substate` may be selected now
+    ///
+    /// A substate above Substate_Max can not be selected. Substate Control has no effect when
+    /// Substate Control Enabled is clear. A new substate should not be selected before the
+    /// Function reports the previously selected one, i.e. before its Transition Latency
+    /// elapses.
+    pub fn select_substate(&self, substate: u8) -> Result<DpaTransition, DpaSubstateError> {
+        let substate_max = self.dpa_capability.substate_max;
+        ensure!(
+            substate <= substate_max,
+            SubstateMaxSnafu {
+                substate,
+                substate_max
+            }
+        );
+        ensure!(
+            self.dpa_status.substate_control_enabled,
+            SubstateControlDisabledSnafu
+        );
+        let (status, control) = (
+            self.dpa_status.substate_status,
+            self.dpa_control.substate_control,
+        );
+        ensure!(
+            status == control,
+            TransitionInProgressSnafu { status, control }
+        );
+        Ok(DpaTransition {
+            substate,
+            latency_ms: self.transition_latency_ms(substate),
+        })
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for DynamicPowerAllocation<'a> {
     type Error = DynamicPowerAllocationError;
 
@@ -98,7 +210,7 @@ impl<'a> TryFrom<&'a [u8]> for DynamicPowerAllocation<'a> {
         let substate_max = substate_max as usize;
         tail.get(..substate_max + 1)
             .ok_or(DynamicPowerAllocationError::DpaAllocationArray {
-                expected: substate_max,
+                expected: substate_max + 1,
                 found: tail.len(),
             })
             .map(|slice| Self {
@@ -163,6 +275,18 @@ pub enum TransitionLatencyUnit {
     Reserved,
 }
 
+impl TransitionLatencyUnit {
+    /// Unit in ms, `None` if reserved
+    pub fn ms(&self) -> Option<u32> {
+        match self {
+            Self::Unit1ms => Some(1),
+            Self::Unit10ms => Some(10),
+            Self::Unit100ms => Some(100),
+            Self::Reserved => None,
+        }
+    }
+}
+
 impl From<u8> for TransitionLatencyUnit {
     fn from(byte: u8) -> Self {
         match byte {
@@ -210,6 +334,12 @@ pub struct DpaStatus {
     pub substate_control_enabled: bool,
 }
 
+impl DpaStatus {
+    /// DPA Status register, Substate Control Enabled is cleared by writing 1
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("DPA Status", 0x0c, AccessWidth::Word).rw1c(0x0100);
+}
+
 impl From<u16> for DpaStatus {
     fn from(word: u16) -> Self {
         let Lsb((substate_status, (), substate_control_enabled, ())) =
@@ -227,6 +357,14 @@ pub struct DpaControl {
     pub substate_control: u8,
 }
 
+impl DpaControl {
+    /// DPA Control register
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("DPA Control", 0x0e, AccessWidth::Word);
+    /// Substate Control field mask
+    pub const SUBSTATE_CONTROL: u16 = 0x1f;
+}
+
 impl From<u16> for DpaControl {
     fn from(word: u16) -> Self {
         let Lsb((substate_control, ())) = P2::<_, 5, 11>(word).into();
@@ -234,9 +372,110 @@ impl From<u16> for DpaControl {
     }
 }
 
+/// Substate can not be selected
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DpaSubstateError {
+    #[snafu(display("substate {substate} is above Substate_Max {substate_max}"))]
+    SubstateMax { substate: u8, substate_max: u8 },
+    #[snafu(display("Substate Control Enabled is clear"))]
+    SubstateControlDisabled,
+    #[snafu(display("transition from substate {status} to {control} is in progress"))]
+    TransitionInProgress { status: u8, control: u8 },
+}
+
+/// Validated substate change, see [DynamicPowerAllocation::select_substate]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DpaTransition {
+    pub substate: u8,
+    /// Maximum Transition Latency in ms, `None` if Transition Latency Unit is reserved
+    pub latency_ms: Option<u32>,
+}
+
+impl DpaTransition {
+    /// DPA Control register value selecting the substate, reserved bits of `current` value
+    /// are preserved
+    pub fn control(&self, current: u16) -> u16 {
+        current & !DpaControl::SUBSTATE_CONTROL | self.substate as u16
+    }
+    /// Write of DPA Control register of DPA Capability at `pointer`, `None` if the substate is
+    /// already selected
+    pub fn write(&self, pointer: ConfigOffset, current: u16) -> Option<RegisterWrite> {
+        let desired = self.control(current);
+        DpaControl::REGISTER.write(pointer, current as u32, desired as u32)
+    }
+    /// Function reports the selected substate in `status`
+    pub fn is_complete(&self, status: &DpaStatus) -> bool {
+        status.substate_status == self.substate
+    }
+}
+
 /// DPA Power Allocation Array
 ///
 /// Each Substate Power Allocation register indicates the power allocation
 /// value for its associated substate
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DpaPowerAllocationArray<'a>(pub &'a [u8]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn registers(capability: u32, latency_indicator: u32, status: u16, control: u16) -> [u8; 0x20] {
+        let mut data = [0; 0x20];
+        data[0x04..0x08].copy_from_slice(&capability.to_le_bytes());
+        data[0x08..0x0c].copy_from_slice(&latency_indicator.to_le_bytes());
+        data[0x0c..0x0e].copy_from_slice(&status.to_le_bytes());
+        data[0x0e..0x10].copy_from_slice(&control.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn transition_latency() {
+        // Substate_Max 7, 10 ms unit, Value 0 = 3, Value 1 = 20
+        let data = registers(0x1403_0107, 0b1000_0001, 0x0100, 0x0000);
+        let dpa = DynamicPowerAllocation::try_from(data.as_slice()).unwrap();
+        assert_eq!(Some(200), dpa.transition_latency_ms(0));
+        assert_eq!(Some(30), dpa.transition_latency_ms(1));
+        assert_eq!(Some(200), dpa.transition_latency_ms(7));
+        assert_eq!(None, dpa.transition_latency_ms(8));
+
+        // Reserved Transition Latency Unit
+        let data = registers(0x1403_0307, 0, 0x0100, 0x0000);
+        let dpa = DynamicPowerAllocation::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, dpa.transition_latency_ms(0));
+        assert_eq!(None, dpa.select_substate(2).unwrap().latency_ms);
+    }
+
+    #[test]
+    fn select_substate() {
+        let data = registers(0x1403_0107, 0, 0x0102, 0x0002);
+        let dpa = DynamicPowerAllocation::try_from(data.as_slice()).unwrap();
+        let transition = dpa.select_substate(5).unwrap();
+        // Reserved bits are preserved
+        assert_eq!(0xffe5, transition.control(0xfff2));
+        assert_eq!(None, transition.write(ConfigOffset(0x100), 0x0005));
+        assert!(!transition.is_complete(&dpa.dpa_status));
+        assert!(transition.is_complete(&DpaStatus::from(0x0105)));
+
+        // Previous transition is not completed
+        let data = registers(0x1403_0107, 0, 0x0102, 0x0004);
+        let dpa = DynamicPowerAllocation::try_from(data.as_slice()).unwrap();
+        assert_eq!(
+            Err(DpaSubstateError::TransitionInProgress {
+                status: 2,
+                control: 4
+            }),
+            dpa.select_substate(5)
+        );
+
+        // Substate Control Enabled is clear
+        let data = registers(0x1403_0107, 0, 0x0002, 0x0002);
+        let dpa = DynamicPowerAllocation::try_from(data.as_slice()).unwrap();
+        assert_eq!(
+            Err(DpaSubstateError::SubstateControlDisabled),
+            dpa.select_substate(5)
+        );
+    }
+}
@@ -12,6 +12,8 @@ capability.
  ├─ <a href="struct.FrsQueuingStatus.html">FrsQueuingStatus</a>
  ├─ <a href="struct.FrsQueuingControl.html">FrsQueuingControl</a>
  └─ <a href="struct.FrsMessageQueue.html">FrsMessageQueue</a>
+    └─ <a href="struct.FrsMessage.html">FrsMessage</a>
+       └─ <a href="enum.FrsReason.html">FrsReason</a>
 </pre>
 
 ## Examples
@@ -47,6 +49,10 @@ let sample = FrsQueuing {
 };
 
 assert_eq!(sample, result);
+
+// The oldest message in the queue
+let message = result.frs_message_queue.oldest_message().unwrap();
+assert_eq!((0x8001, FrsReason::Reserved(0xC)), (message.function_id, message.reason));
 ```
 */
 
@@ -70,6 +76,14 @@ impl FrsQueuing {
         + FrsQueuingStatus::SIZE
         + FrsQueuingControl::SIZE
         + FrsMessageQueue::SIZE;
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// FRS Message Queue Depth reached FRS Queue Max Depth, next FRS Message will set FRS
+    /// Message Overflow
+    pub fn is_full(&self) -> bool {
+        self.frs_message_queue.frs_message_queue_depth
+            >= self.frs_queuing_capability.frs_queue_max_depth
+    }
 }
 
 impl TryFrom<&[u8]> for FrsQueuing {
@@ -88,7 +102,7 @@ impl TryFrom<&[u8]> for FrsQueuing {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "FRS Queuing",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             frs_queuing_capability: From::<u32>::from(frs_queuing_capability),
@@ -189,6 +203,13 @@ pub struct FrsMessageQueue {
 
 impl FrsMessageQueue {
     pub const SIZE: usize = 4;
+    /// The oldest FRS Message still in the queue, `None` if the queue is empty
+    pub fn oldest_message(&self) -> Option<FrsMessage> {
+        (self.frs_message_queue_depth != 0).then(|| FrsMessage {
+            function_id: self.frs_message_queue_function_id,
+            reason: self.frs_message_queue_reason.into(),
+        })
+    }
 }
 
 impl From<u32> for FrsMessageQueue {
@@ -202,3 +223,105 @@ impl From<u32> for FrsMessageQueue {
         }
     }
 }
+
+/// FRS Message recorded in [FrsMessageQueue]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrsMessage {
+    /// Requester ID of the Function that sent the FRS Message
+    pub function_id: u16,
+    pub reason: FrsReason,
+}
+
+/// FRS Reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrsReason {
+    /// DRS Message Received
+    DrsMessageReceived,
+    /// D3Hot to D0 Transition Completed
+    D3HotToD0TransitionCompleted,
+    /// FLR Completed
+    FlrCompleted,
+    /// VF Enable Completed
+    VfEnableCompleted,
+    /// VF Disable Completed
+    VfDisableCompleted,
+    Reserved(u8),
+}
+
+impl From<u8> for FrsReason {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0b0001 => Self::DrsMessageReceived,
+            0b0010 => Self::D3HotToD0TransitionCompleted,
+            0b0011 => Self::FlrCompleted,
+            0b0100 => Self::VfEnableCompleted,
+            0b0101 => Self::VfDisableCompleted,
+            v => Self::Reserved(v),
+        }
+    }
+}
+
+impl From<FrsReason> for u8 {
+    fn from(reason: FrsReason) -> Self {
+        match reason {
+            FrsReason::DrsMessageReceived => 0b0001,
+            FrsReason::D3HotToD0TransitionCompleted => 0b0010,
+            FrsReason::FlrCompleted => 0b0011,
+            FrsReason::VfEnableCompleted => 0b0100,
+            FrsReason::VfDisableCompleted => 0b0101,
+            FrsReason::Reserved(v) => v,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const DATA: [u8; 16] = [
+        0x21, 0x00, 0x01, 0x00, // Capability header
+        0x04, 0x00, 0x0a, 0x00, // Max Depth 4, Interrupt Message Number 10
+        0x03, 0x00, 0x01, 0x00, // Received, Overflow; Interrupt Enable
+        0x08, 0x03, 0x33, 0x00, // 03:01.0, FLR Completed, Depth 3
+    ];
+
+    #[test]
+    fn parse() {
+        let result: FrsQueuing = DATA.as_slice().try_into().unwrap();
+        assert!(result.frs_queuing_status.frs_message_overflow);
+        assert!(result.frs_queuing_control.frs_interrupt_enable);
+        let capability = &result.frs_queuing_capability;
+        assert_eq!(4, capability.frs_queue_max_depth);
+        assert_eq!(10, capability.frs_interrupt_message_number);
+        let sample = Some(FrsMessage {
+            function_id: 0x0308,
+            reason: FrsReason::FlrCompleted,
+        });
+        assert_eq!(sample, result.frs_message_queue.oldest_message());
+        assert!(!result.is_full());
+
+        let mut data = DATA;
+        // Depth 4 with reserved reason
+        data[0x0f] = 0x00;
+        data[0x0e] = 0x4f;
+        let result = FrsQueuing::try_from(data.as_slice()).unwrap();
+        assert!(result.is_full());
+        let reason = result.frs_message_queue.oldest_message().unwrap().reason;
+        assert_eq!(0x0f, u8::from(reason));
+        // Empty queue
+        data[0x0e] = 0x05;
+        let result = FrsQueuing::try_from(data.as_slice()).unwrap();
+        assert_eq!(None, result.frs_message_queue.oldest_message());
+    }
+
+    #[test]
+    fn short_data() {
+        let result = FrsQueuing::try_from(&DATA[..FrsQueuing::MIN_SIZE - 1]);
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "FRS Queuing",
+            size: FrsQueuing::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+    }
+}
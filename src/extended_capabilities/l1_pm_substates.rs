@@ -18,11 +18,11 @@ all the Functions of the device.
 
 ## Examples
 > L1 PM Substates  
-    L1SubCap: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2+ ASPM_L1.1+ L1_PM_Substates+  
-              PortCommonModeRestoreTime=40us PortTPowerOnTime=44us  
-    L1SubCtl1: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2+ ASPM_L1.1+  
-               T_CommonMode=255us LTR1.2_Threshold=81920ns  
-    L1SubCtl2: T_PwrOn=44us  
+> L1SubCap: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2+ ASPM_L1.1+ L1_PM_Substates+  
+> PortCommonModeRestoreTime=40us PortTPowerOnTime=44us  
+> L1SubCtl1: PCI-PM_L1.2+ PCI-PM_L1.1+ ASPM_L1.2+ ASPM_L1.1+  
+> T_CommonMode=255us LTR1.2_Threshold=81920ns  
+> L1SubCtl2: T_PwrOn=44us  
   
 ```rust
 # use pcics::extended_capabilities::l1_pm_substates::*;
@@ -74,6 +74,10 @@ pub struct L1PmSubstates {
     /// L1 PM Substates Control 2
     pub l1_pm_substates_control_2: L1PmSubstatesControl2,
 }
+impl L1PmSubstates {
+    pub const SIZE: usize = 4 + 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for L1PmSubstates {
     type Error = ExtendedCapabilityDataError;
 
@@ -86,7 +90,7 @@ impl TryFrom<&[u8]> for L1PmSubstates {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "L1 PM Substates",
-                size: 12,
+                size: Self::MIN_SIZE,
             })?;
         let Lsb((
             pci_pm_l1_2_supported,
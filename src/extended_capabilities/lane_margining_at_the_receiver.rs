@@ -0,0 +1,291 @@
+/*!
+# Lane Margining at the Receiver
+
+The Lane Margining at the Receiver Extended Capability is required for Ports supporting 16.0
+GT/s or higher data rates. Software issues margin commands through per-Lane Margining Lane
+Control registers, Receivers report the command results in Margining Lane Status registers.
+
+## Struct diagram
+[LaneMarginingAtTheReceiver]
+- [MarginingPortCapabilities]
+- [MarginingPortStatus]
+- [MarginingLanes]
+  - [MarginingLane]
+    - [MarginingLaneRegister]
+
+## Examples
+
+```rust
+# use pcics::capabilities::pci_express::LinkWidth;
+# use pcics::extended_capabilities::lane_margining_at_the_receiver::*;
+let data = [
+    /* 00h */ 0x27, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0x00, 0x00, 0x03, 0x00, // Margining Port Capabilities and Status
+    /* 08h */ 0x09, 0x9c, 0x09, 0x9c, // Lane 0: Margining Lane Control and Status
+    /* 0Ch */ 0x09, 0x9c, 0x01, 0x00, // Lane 1: Margining Lane Control and Status
+];
+let result: LaneMarginingAtTheReceiver = data[4..].try_into().unwrap();
+assert!(result.is_ready());
+
+// Access Receiver 1 with Margin Type 1 (Report Margining Control Capabilities)
+let command = MarginingLaneRegister {
+    receiver_number: 1,
+    margin_type: 1,
+    usage_model: false,
+    margin_payload: 0x9c,
+};
+let lanes = result.lanes(LinkWidth::X2).collect::<Result<Vec<_>, _>>().unwrap();
+assert!(lanes[0].is_complete());
+assert_eq!(command, lanes[1].control);
+assert!(!lanes[1].is_complete());
+
+// Lane 1 registers are truncated to 2 bytes
+let result: LaneMarginingAtTheReceiver = data[4..14].try_into().unwrap();
+let error = MarginingLaneError { lane: 1, found: 2 };
+assert_eq!(Some(Err(error)), result.lanes(LinkWidth::X2).nth(1));
+```
+*/
+
+use core::{iter::Enumerate, slice::Chunks};
+
+use heterob::{endianness::Le, Seq, P2};
+use snafu::Snafu;
+
+use super::ExtendedCapabilityDataError;
+use crate::{capabilities::pci_express::LinkWidth, register::register};
+
+/// Lane Margining at the Receiver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneMarginingAtTheReceiver<'a> {
+    pub port_capabilities: MarginingPortCapabilities,
+    pub port_status: MarginingPortStatus,
+    lanes_data: &'a [u8],
+}
+
+impl<'a> LaneMarginingAtTheReceiver<'a> {
+    /// Margining Port Capabilities and Status registers
+    pub const MIN_SIZE: usize = 4;
+    /// Margining Lane Control and Status registers offset from the extended capability header
+    pub const LANES_OFFSET: usize = 0x08;
+    /// An iterator through Margining Lane registers of `link_width` Lanes
+    ///
+    /// `link_width` is usually Maximum Link Width of the PCI Express Capability, the iterator
+    /// stops at the end of the available data, a truncated last Lane is reported as
+    /// [MarginingLaneError].
+    pub fn lanes(&self, link_width: LinkWidth) -> MarginingLanes<'a> {
+        MarginingLanes::new(self.lanes_data, link_width)
+    }
+    /// Port is ready to accept margin commands: Margining Ready is set, and Margining Software
+    /// Ready is set if margining uses driver software
+    pub fn is_ready(&self) -> bool {
+        let status = &self.port_status;
+        status.margining_ready
+            && (status.margining_software_ready
+                || !self.port_capabilities.margining_uses_driver_software)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for LaneMarginingAtTheReceiver<'a> {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((port_capabilities, port_status)),
+            tail,
+        } = P2(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Lane Margining at the Receiver",
+                size: Self::MIN_SIZE,
+            })?;
+        Ok(Self {
+            port_capabilities: From::<u16>::from(port_capabilities),
+            port_status: From::<u16>::from(port_status),
+            lanes_data: tail,
+        })
+    }
+}
+
+/// Margining Port Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginingPortCapabilities {
+    /// Margining uses Driver Software
+    pub margining_uses_driver_software: bool,
+}
+
+register!(MarginingPortCapabilities: u16 {
+    margining_uses_driver_software[0],
+});
+
+/// Margining Port Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginingPortStatus {
+    /// Margining Ready
+    pub margining_ready: bool,
+    /// Margining Software Ready
+    pub margining_software_ready: bool,
+}
+
+register!(MarginingPortStatus: u16 {
+    margining_ready[0],
+    margining_software_ready[1],
+});
+
+/// Margining Lane Control and Margining Lane Status registers have same fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginingLaneRegister {
+    /// Receiver Number
+    pub receiver_number: u8,
+    /// Margin Type
+    pub margin_type: u8,
+    /// Usage Model, Lane Margining at the Receiver if cleared
+    pub usage_model: bool,
+    /// Margin Payload
+    pub margin_payload: u8,
+}
+
+register!(MarginingLaneRegister: u16 {
+    receiver_number[0..3],
+    margin_type[3..6],
+    usage_model[6],
+    margin_payload[8..16],
+});
+
+/// Margining Lane Control and Status registers of a Lane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginingLane {
+    /// Lane number
+    pub lane: u8,
+    /// Margining Lane Control
+    pub control: MarginingLaneRegister,
+    /// Margining Lane Status
+    pub status: MarginingLaneRegister,
+}
+
+impl MarginingLane {
+    /// Status reflects the command of the Control register
+    ///
+    /// Margin Payload Status is the command response, so only Receiver Number, Margin Type and
+    /// Usage Model are compared.
+    pub fn is_complete(&self) -> bool {
+        let (control, status) = (&self.control, &self.status);
+        control.receiver_number == status.receiver_number
+            && control.margin_type == status.margin_type
+            && control.usage_model == status.usage_model
+    }
+}
+
+/// An iterator through [MarginingLane]s
+#[derive(Debug, Clone)]
+pub struct MarginingLanes<'a> {
+    chunks: Enumerate<Chunks<'a, u8>>,
+}
+
+impl<'a> MarginingLanes<'a> {
+    /// Four byte entries of `link_width` Lanes from `data`
+    pub fn new(data: &'a [u8], link_width: LinkWidth) -> Self {
+        let end = link_width.value() as usize * 4;
+        Self {
+            chunks: data.get(..end).unwrap_or(data).chunks(4).enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for MarginingLanes<'a> {
+    type Item = Result<MarginingLane, MarginingLaneError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (lane, chunk) = self.chunks.next()?;
+        let lane = lane as u8;
+        let result = P2(chunk)
+            .try_into()
+            .map(
+                |Seq {
+                     head: Le((control, status)),
+                     ..
+                 }| MarginingLane {
+                    lane,
+                    control: From::<u16>::from(control),
+                    status: From::<u16>::from(status),
+                },
+            )
+            .map_err(|_| MarginingLaneError {
+                lane,
+                found: chunk.len(),
+            });
+        Some(result)
+    }
+}
+
+/// Margining Lane Control and Status registers are shorter than 4 bytes
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("Margining Lane {lane} registers are truncated to {found} bytes"))]
+pub struct MarginingLaneError {
+    pub lane: u8,
+    pub found: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn parse() {
+        let data = [
+            0x01, 0x00, 0x01, 0x00, 0x3a, 0x15, 0x3a, 0x80, 0xff, 0xff, 0x00, 0x00, 0x01, 0x00,
+        ];
+        let result: LaneMarginingAtTheReceiver = data.as_slice().try_into().unwrap();
+        assert!(result.port_capabilities.margining_uses_driver_software);
+        let sample = MarginingPortStatus {
+            margining_ready: true,
+            margining_software_ready: false,
+        };
+        assert_eq!(sample, result.port_status);
+        // Driver software is not ready
+        assert!(!result.is_ready());
+
+        let mut lanes = result.lanes(LinkWidth::X4).collect::<Vec<_>>();
+        // Last lane is truncated
+        assert_eq!(3, lanes.len());
+        let error = MarginingLaneError { lane: 2, found: 2 };
+        assert_eq!(Some(Err(error)), lanes.pop());
+        assert_eq!(
+            "Margining Lane 2 registers are truncated to 2 bytes",
+            error.to_string()
+        );
+        let lanes = lanes.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        let sample = MarginingLane {
+            lane: 0,
+            control: MarginingLaneRegister {
+                receiver_number: 2,
+                margin_type: 7,
+                usage_model: false,
+                margin_payload: 0x15,
+            },
+            status: MarginingLaneRegister {
+                receiver_number: 2,
+                margin_type: 7,
+                usage_model: false,
+                margin_payload: 0x80,
+            },
+        };
+        assert_eq!(sample, lanes[0]);
+        assert!(lanes[0].is_complete());
+        assert_eq!(0xff7f, u16::from(lanes[1].control.clone()));
+        assert!(!lanes[1].is_complete());
+
+        assert_eq!(1, result.lanes(LinkWidth::X1).count());
+    }
+
+    #[test]
+    fn short_data() {
+        let result = LaneMarginingAtTheReceiver::try_from([0x00; 3].as_slice());
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Lane Margining at the Receiver",
+            size: LaneMarginingAtTheReceiver::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+    }
+}
@@ -41,6 +41,10 @@ pub struct LatencyToleranceReporting {
     /// Max No-Snoop Latency
     pub max_no_snoop_latency: MaxLatency,
 }
+impl LatencyToleranceReporting {
+    pub const SIZE: usize = 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for LatencyToleranceReporting {
     type Error = ExtendedCapabilityDataError;
 
@@ -52,7 +56,7 @@ impl TryFrom<&[u8]> for LatencyToleranceReporting {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Latency Tolerance Reporting",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             max_snoop_latency: From::<u16>::from(max_snoop_latency),
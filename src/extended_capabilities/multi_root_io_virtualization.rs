@@ -0,0 +1,296 @@
+/*!
+# Multi-Root I/O Virtualization (MR-IOV)
+
+The MR-IOV Extended Capability is defined in the Multi-Root I/O Virtualization and Sharing
+Specification. It is implemented by Functions shared by several Virtual Hierarchies (VHs) of a
+multi-root topology. The Function Table assigning Functions to VHs is located in memory space
+through one of the Base Address Registers, like MSI-X Table.
+
+## Struct diagram
+[MultiRootIoVirtualization]
+- [MrIovCapabilities]
+- [MrIovControl]
+- [MrIovStatus]
+- [FunctionTable]
+  - [Bir]
+- [FunctionTableEntries]
+  - [FunctionTableEntry]
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::multi_root_io_virtualization::*;
+let data = [
+    /* 00h */ 0x11, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0x03, 0x00, 0x04, 0x00, // MR-IOV Capabilities
+    /* 08h */ 0x01, 0x00, 0x00, 0x00, // MR-IOV Control and Status
+    /* 0Ch */ 0x02, 0x10, 0x00, 0x00, // Function Table Offset/BIR
+];
+let result: MultiRootIoVirtualization = data[4..].try_into().unwrap();
+assert_eq!(4, result.capabilities.entries());
+assert!(result.control.mr_iov_enable);
+assert_eq!(FunctionTable { bir: Bir::Bar18h, offset: 0x1000 }, result.function_table);
+
+// Function Table read from BAR2 memory
+let table = [
+    0x00, 0x00, 0x00, 0x80, // VH 0, Function 0
+    0x01, 0x01, 0x00, 0x80, // VH 1, Function 1
+    0x00, 0x00, 0x00, 0x00, // Not assigned
+];
+let assigned = result
+    .function_table_entries(&table)
+    .flatten()
+    .filter(|entry| entry.valid)
+    .map(|entry| (entry.virtual_hierarchy, entry.function_number))
+    .collect::<Vec<_>>();
+assert_eq!(vec![(0, 0), (1, 1)], assigned);
+
+// Entry truncated to 2 bytes
+let mut entries = result.function_table_entries(&table[..6]);
+assert!(entries.next().unwrap().is_ok());
+assert_eq!(Some(Err(FunctionTableEntryError { found: 2 })), entries.next());
+```
+*/
+
+use core::slice::Chunks;
+
+use heterob::{endianness::Le, Seq, P4};
+use snafu::Snafu;
+
+use super::ExtendedCapabilityDataError;
+use crate::register::register;
+
+pub use crate::capabilities::msi_x::Bir;
+
+/// Multi-Root I/O Virtualization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiRootIoVirtualization {
+    /// MR-IOV Capabilities
+    pub capabilities: MrIovCapabilities,
+    /// MR-IOV Control
+    pub control: MrIovControl,
+    /// MR-IOV Status
+    pub status: MrIovStatus,
+    /// Function Table Offset/BIR
+    pub function_table: FunctionTable,
+}
+
+impl MultiRootIoVirtualization {
+    /// MR-IOV Capabilities, Control, Status and Function Table Offset/BIR registers
+    pub const SIZE: usize = 3 * 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// An iterator through Function Table entries read from `table`, the memory space pointed
+    /// by [function_table](Self::function_table)
+    ///
+    /// The iterator stops after the Function Table Size entries or at the end of `table`, a
+    /// truncated last entry is reported as [FunctionTableEntryError].
+    pub fn function_table_entries<'a>(&self, table: &'a [u8]) -> FunctionTableEntries<'a> {
+        let end = self.capabilities.entries() * FunctionTableEntry::SIZE;
+        FunctionTableEntries {
+            chunks: table
+                .get(..end)
+                .unwrap_or(table)
+                .chunks(FunctionTableEntry::SIZE),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for MultiRootIoVirtualization {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((capabilities, control, status, function_table)),
+            ..
+        } = P4(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Multi-Root I/O Virtualization",
+                size: Self::MIN_SIZE,
+            })?;
+        let _: (u16, u16) = (control, status);
+        Ok(Self {
+            capabilities: From::<u32>::from(capabilities),
+            control: From::<u16>::from(control),
+            status: From::<u16>::from(status),
+            function_table: From::<u32>::from(function_table),
+        })
+    }
+}
+
+/// MR-IOV Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MrIovCapabilities {
+    /// Function Table Size, encoded as N-1
+    pub function_table_size: u16,
+    /// Number of Virtual Hierarchies supported by the Function
+    pub virtual_hierarchies_supported: u8,
+}
+
+register!(MrIovCapabilities: u32 {
+    function_table_size[0..11],
+    virtual_hierarchies_supported[16..24],
+});
+
+impl MrIovCapabilities {
+    /// Number of Function Table entries
+    pub fn entries(&self) -> usize {
+        self.function_table_size as usize + 1
+    }
+}
+
+/// MR-IOV Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MrIovControl {
+    /// MR-IOV Enable
+    pub mr_iov_enable: bool,
+}
+
+register!(MrIovControl: u16 {
+    mr_iov_enable[0],
+});
+
+/// MR-IOV Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MrIovStatus {
+    /// Function Table Changed
+    pub function_table_changed: bool,
+}
+
+register!(MrIovStatus: u16 {
+    function_table_changed[0],
+});
+
+/// Function Table Offset/BIR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionTable {
+    pub bir: Bir,
+    /// Offset from the address contained by one of the Function's Base Address registers to
+    /// the base of the Function Table
+    pub offset: u32,
+}
+
+impl From<u32> for FunctionTable {
+    fn from(dword: u32) -> Self {
+        Self {
+            bir: From::<u8>::from(dword as u8 & 0b111),
+            offset: dword & !0b111,
+        }
+    }
+}
+
+/// Function Table entry assigning a Function to a Virtual Hierarchy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionTableEntry {
+    /// Virtual Hierarchy Number
+    pub virtual_hierarchy: u8,
+    /// Function Number in the Virtual Hierarchy
+    pub function_number: u8,
+    /// Entry Valid
+    pub valid: bool,
+}
+
+impl FunctionTableEntry {
+    pub const SIZE: usize = 4;
+}
+
+register!(FunctionTableEntry: u32 {
+    virtual_hierarchy[0..8],
+    function_number[8..16],
+    valid[31],
+});
+
+/// An iterator through [FunctionTableEntry]s
+#[derive(Debug, Clone)]
+pub struct FunctionTableEntries<'a> {
+    chunks: Chunks<'a, u8>,
+}
+
+impl<'a> Iterator for FunctionTableEntries<'a> {
+    type Item = Result<FunctionTableEntry, FunctionTableEntryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        let result = chunk
+            .try_into()
+            .map(|bytes| u32::from_le_bytes(bytes).into())
+            .map_err(|_| FunctionTableEntryError { found: chunk.len() });
+        Some(result)
+    }
+}
+
+/// Function Table entry is shorter than [FunctionTableEntry::SIZE]
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("Function Table entry is truncated to {found} bytes"))]
+pub struct FunctionTableEntryError {
+    pub found: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn parse() {
+        let data = [
+            0xff, 0xff, 0x20, 0xff, 0x01, 0x00, 0x01, 0x80, 0x0e, 0x40, 0x00, 0x00, 0xff,
+        ];
+        let result: MultiRootIoVirtualization = data.as_slice().try_into().unwrap();
+        let sample = MultiRootIoVirtualization {
+            capabilities: MrIovCapabilities {
+                function_table_size: 0x7ff,
+                virtual_hierarchies_supported: 0x20,
+            },
+            control: MrIovControl {
+                mr_iov_enable: true,
+            },
+            status: MrIovStatus {
+                function_table_changed: true,
+            },
+            function_table: FunctionTable {
+                bir: Bir::Reserved(6),
+                offset: 0x4008,
+            },
+        };
+        assert_eq!(sample, result);
+        assert_eq!(2048, result.capabilities.entries());
+
+        // Last entry is truncated
+        let table = [0x05, 0x07, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02];
+        let result = result.function_table_entries(&table).collect::<Vec<_>>();
+        let sample = vec![
+            Ok(FunctionTableEntry {
+                virtual_hierarchy: 5,
+                function_number: 7,
+                valid: true,
+            }),
+            Ok(FunctionTableEntry {
+                virtual_hierarchy: 0,
+                function_number: 0,
+                valid: false,
+            }),
+            Err(FunctionTableEntryError { found: 2 }),
+        ];
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn function_table_size() {
+        let data = [0x00; MultiRootIoVirtualization::SIZE];
+        let result: MultiRootIoVirtualization = data.as_slice().try_into().unwrap();
+        let table = [0x00; 3 * FunctionTableEntry::SIZE];
+        assert_eq!(1, result.function_table_entries(&table).count());
+    }
+
+    #[test]
+    fn short_data() {
+        let result = MultiRootIoVirtualization::try_from([0x00; 11].as_slice());
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Multi-Root I/O Virtualization",
+            size: MultiRootIoVirtualization::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+    }
+}
@@ -85,6 +85,8 @@ pub struct Multicast {
 
 impl Multicast {
     pub const SIZE: usize = 0x30;
+    /// Min size in bytes (with Extended Capability Header), MC Overlay BAR is optional
+    pub const MIN_SIZE: usize = 0x28;
 }
 
 impl TryFrom<&[u8]> for Multicast {
@@ -109,7 +111,7 @@ impl TryFrom<&[u8]> for Multicast {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Multicast",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })?;
         let _: (u32, u32, u32, u32, u32, u32) =
             (rcv_l, rcv_h, blk_all_l, blk_all_h, blk_untr_l, blk_untr_h);
@@ -16,132 +16,80 @@ arbitration for Upstream Requests.
 │  └─ <a href="../virtual_channel/struct.VcArbitrationCapability.html">VcArbitrationCapability</a>
 ├─ <a href="../virtual_channel/struct.PortVcControl.html">PortVcControl</a>
 │  └─ <a href="../virtual_channel/enum.VcArbitrationSelect.html">VcArbitrationSelect</a>
-├─ <a href="../virtual_channel/struct.PortVcStatus.html">PortVcStatus</a>
-├─ <a href="../virtual_channel/struct.VcArbitrationTable.html">VcArbitrationTable</a>
-│  └─ <a href="../virtual_channel/struct.VcArbitrationTableEntry.html">VcArbitrationTableEntry (0 .. N)</a>
-└─ <a href="struct.ExtendedVirtualChannels.html">ExtendedVirtualChannels</a>
-   └─ <a href="struct.ExtendedVirtualChannel.html">ExtendedVirtualChannel (0 .. N)</a>
-      ├─ <a href="struct.VcResourceCapability.html">VcResourceCapability</a>
-      │  └─ <a href="struct.FunctionArbitrationCapability.html">FunctionArbitrationCapability</a>
-      ├─ <a href="struct.VcResourceControl.html">VcResourceControl</a>
-      │  └─ <a href="enum.FunctionArbitrationSelect.html">FunctionArbitrationSelect</a>
-      ├─ <a href="struct.VcResourceStatus.html">VcResourceStatus</a>
-      └─ <a href="struct.FunctionArbitrationTable.html">FunctionArbitrationTable</a>
-         └─ <a href="struct.FunctionArbitrationTableEntry.html">FunctionArbitrationTableEntry (0 .. N)</a>
+└─ <a href="../virtual_channel/struct.PortVcStatus.html">PortVcStatus</a>
 </pre>
 
+VC resources, [VC Arbitration Table](MultifunctionVirtualChannel::vc_arbitration_table)
+and [Function Arbitration Tables](MultifunctionVirtualChannel::function_arbitration_table)
+are read on demand.
+
 ## Examples
 ```rust
 # use pcics::extended_capabilities::multifunction_virtual_channel::*;
-let data = [
+let mut data = [0u8; 0x40];
+data[..0x1c].copy_from_slice(&[
     /* 00h */ 0x08, 0x00, 0x01, 0x00,             // Capability header
-    /* 04h */ 0b0_000_0_001, 0b1_00, 0x00, 0x00,  // Port VC Capability Register 1
-    /* 08h */ 0b1111, 0x00, 0x00, 0x00,           // Port VC Capability Register 2
+    /* 04h */ 0x00, 0b1_00, 0x00, 0x00,           // Port VC Capability Register 1
+    /* 08h */ 0b1111, 0x00, 0x00, 0x02,           // Port VC Capability Register 2
     /* 0Ch */ 0b0010, 0x00,                       // Port VC Control Register
               0x00, 0x00,                         // Port VC Status Register
-    /* 10h */ 0b111, 0x00, 0x00, 0x00,            // VC Resource Capability Register (0)
+    /* 10h */ 0b111, 0x00, 0x00, 0x03,            // VC Resource Capability Register (0)
     /* 14h */ 0xff, 0x00, 0x04, 0x80,             // VC Resource Control Register (0)
     /* 18h */ 0x00, 0x00,                         // RsvdP
               0b10, 0x00,                         // VC Resource Status Register (0)
-];
-
-let result: MultifunctionVirtualChannel = data.as_slice().try_into().unwrap();
-
-let mut evcs_sample_data = [0u8; 0x1C];
-evcs_sample_data[..0x10].clone_from_slice(&data[..0x10]);
-evcs_sample_data[0x10..].clone_from_slice({
-    let evc = ExtendedVirtualChannel {
-        vc_resource_capability: VcResourceCapability {
-            function_arbitration_capability: FunctionArbitrationCapability {
-                hardware_fixed_arbitration: true,
-                wrr_32_phases: true,
-                wrr_64_phases: true,
-                wrr_128_phases: false,
-                time_based_wrr_128_phases: false,
-                wrr_256_phases: false,
-                reserved: 0,
-            },
-            maximum_time_slots: 0,
-            function_arbitration_table_offset: 0,
-        },
-        vc_resource_control: VcResourceControl {
-            tc_to_vc_map: 0xff,
-            load_function_arbitration_table: false,
-            function_arbitration_select: FunctionArbitrationSelect::Wrr64phases,
-            vc_id: 0,
-            vc_enable: true,
-        },
-        vc_resource_status: VcResourceStatus {
-            function_arbitration_table_status: false,
-            vc_negotiation_pending: true,
-        },
-        function_arbitration_table: None,
-    };
-    Into::<[u8; ExtendedVirtualChannel::SIZE]>::into(evc).as_slice()
-});
-
-let sample = MultifunctionVirtualChannel {
-    port_vc_capability_1: PortVcCapability1 {
-        extended_vc_count: 1,
-        low_priority_extended_vc_count: 0,
-        reference_clock: ReferenceClock::Rc100ns,
-        function_arbitration_table_entry_size: FunctionArbitrationTableEntrySize(0b01),
-    },
-    port_vc_capability_2: PortVcCapability2 {
-        vc_arbitration_capability: VcArbitrationCapability {
-            hardware_fixed_arbitration: true,
-            wrr_32_phases: true,
-            wrr_64_phases: true,
-            wrr_128_phases: true,
-            reserved: 0,
-        },
-        vc_arbitration_table_offset: 0,
-    },
-    port_vc_control: PortVcControl {
-        load_vc_arbitration_table: false,
-        vc_arbitration_select: VcArbitrationSelect::Wrr32phases,
-    },
-    port_vc_status: PortVcStatus {
-        vc_arbitration_table_status: false,
-    },
-    vc_arbitration_table: None,
-    extended_virtual_channels: ExtendedVirtualChannels::new(evcs_sample_data.as_slice(), 1, 2),
-};
-
-assert_eq!(sample, result);
+]);
+// VC Arbitration Table: WRR 32 phases, VC0 and VC1 in turn
+data[0x20..0x30].fill(0x10);
+// Function Arbitration Table: WRR 64 phases, Functions 0 - 3 in turn
+data[0x30..0x40].fill(0b11_10_01_00);
+
+let mfvc: MultifunctionVirtualChannel = data[4..].try_into().unwrap();
+assert_eq!(
+    FunctionArbitrationTableEntrySize(0b01),
+    mfvc.port_vc_capability_1.function_arbitration_table_entry_size
+);
+assert_eq!(
+    VcArbitrationSelect::Wrr32phases,
+    mfvc.port_vc_control.vc_arbitration_select
+);
+
+let vat = mfvc.vc_arbitration_table().unwrap();
+let vc_ids = vat.map(|VcArbitrationTableEntry { vc_id }| vc_id).collect::<Vec<_>>();
+assert_eq!([0, 1].repeat(16), vc_ids);
+
+let evcs = mfvc
+    .extended_virtual_channels()
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(1, evcs.len());
+assert_eq!(
+    FunctionArbitrationSelect::Wrr64phases,
+    evcs[0].vc_resource_control.function_arbitration_select
+);
+
+let fat = mfvc.function_arbitration_table(&evcs[0]).unwrap();
+let functions = fat.map(|FunctionArbitrationTableEntry(f)| f).collect::<Vec<_>>();
+assert_eq!([0, 1, 2, 3].repeat(16), functions);
 ```
 */
 
-use core::slice;
+use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P11, P3, P4, P7, P8};
 
-use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P11, P3, P4, P5, P7, P8};
-use snafu::Snafu;
+use super::{
+    virtual_channel::arbitration::{self, table_data, PackedEntries},
+    ExtendedCapabilityDataError, ECH_BYTES,
+};
 
 pub use super::virtual_channel::{
-    PortVcCapability2, PortVcControl, PortVcStatus, ReferenceClock, VcArbitrationCapability,
-    VcArbitrationSelect, VcArbitrationTable,
+    ArbitrationTableError, ExtendedVirtualChannelError, PortVcCapability2, PortVcControl,
+    PortVcStatus, ReferenceClock, VcArbitrationCapability, VcArbitrationSelect, VcArbitrationTable,
+    VcArbitrationTableEntry,
 };
 
-/// Numeral unit for VC Arbitration Table Offset and Function Arbitration Table Offset
-const DQWORD: usize = 0x10;
-
-#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
-pub enum MultifunctionVirtualChannelError {
-    #[snafu(display("capability 1, capability 2, control and status of Port VC are unreadable"))]
-    PortVcData,
-    #[snafu(display("VC Arbitration Table offset should be >= 2 * 10h"))]
-    VcArbitrationTableOffset,
-    #[snafu(display("VC Arbitration Table data ureadable"))]
-    VcArbitrationTableData,
-    #[snafu(display("VC Arbitration Table data too short: expected {expected}, real {real}"))]
-    VcArbitrationTableLength { expected: usize, real: usize },
-    #[snafu(display("at least one Function Arbitration Table should be readable"))]
-    FunctionArbitrationTable0,
-}
-
 /// Multi-Function Virtual Channel
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MultifunctionVirtualChannel<'a> {
+    data: &'a [u8],
     pub port_vc_capability_1: PortVcCapability1,
     /// Port VC Capability 2
     ///
@@ -150,69 +98,63 @@ pub struct MultifunctionVirtualChannel<'a> {
     pub port_vc_capability_2: PortVcCapability2,
     pub port_vc_control: PortVcControl,
     pub port_vc_status: PortVcStatus,
-    pub vc_arbitration_table: Option<VcArbitrationTable<'a>>,
-    pub extended_virtual_channels: ExtendedVirtualChannels<'a>,
+}
+
+impl<'a> MultifunctionVirtualChannel<'a> {
+    pub const MIN_SIZE: usize = 4 + 4 + 2 + 2;
+    pub fn extended_virtual_channels(&self) -> ExtendedVirtualChannels<'a> {
+        let count = self.port_vc_capability_1.extended_vc_count;
+        let start = 0x10 - ECH_BYTES;
+        ExtendedVirtualChannels::new(self.data.get(start..).unwrap_or_default(), count)
+    }
+    /// VC Arbitration Table for the currently selected VC Arbitration
+    pub fn vc_arbitration_table(&self) -> Result<VcArbitrationTable<'a>, ArbitrationTableError> {
+        arbitration::vc_arbitration_table(
+            self.data,
+            self.port_vc_capability_2.vc_arbitration_table_offset,
+            &self.port_vc_control.vc_arbitration_select,
+        )
+    }
+    /// Function Arbitration Table for the currently selected Function Arbitration of `evc`
+    /// resource
+    pub fn function_arbitration_table(
+        &self,
+        evc: &ExtendedVirtualChannel,
+    ) -> Result<FunctionArbitrationTable<'a>, ArbitrationTableError> {
+        let offset = evc.vc_resource_capability.function_arbitration_table_offset;
+        let entry_size_bits = self
+            .port_vc_capability_1
+            .function_arbitration_table_entry_size
+            .bits();
+        let select = &evc.vc_resource_control.function_arbitration_select;
+        if let FunctionArbitrationSelect::Reserved(value) = select {
+            return Err(ArbitrationTableError::ReservedSelect { value: *value });
+        }
+        let entries_number = select.function_arbitration_table_length();
+        table_data(self.data, offset, entries_number, entry_size_bits)
+            .map(|data| FunctionArbitrationTable::new(data, entry_size_bits))
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for MultifunctionVirtualChannel<'a> {
-    type Error = MultifunctionVirtualChannelError;
+    type Error = ExtendedCapabilityDataError;
 
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
         let Seq {
-            head:
-                Le((
-                    extended_capability_header,
-                    port_vc_capability_1,
-                    port_vc_capability_2,
-                    port_vc_control,
-                    port_vc_status,
-                )),
+            head: Le((port_vc_capability_1, port_vc_capability_2, port_vc_control, port_vc_status)),
             ..
-        } = P5(slice)
+        } = P4(slice)
             .try_into()
-            .map_err(|_| MultifunctionVirtualChannelError::PortVcData)?;
-        let _: u32 = extended_capability_header;
-        let port_vc_capability_1 @ PortVcCapability1 {
-            extended_vc_count,
-            function_arbitration_table_entry_size,
-            ..
-        } = From::<u32>::from(port_vc_capability_1);
-        let port_vc_capability_2: PortVcCapability2 = From::<u32>::from(port_vc_capability_2);
-        let port_vc_control: PortVcControl = From::<u16>::from(port_vc_control);
-        let port_vc_status: PortVcStatus = From::<u16>::from(port_vc_status);
-        let vc_arbitration_table = if port_vc_capability_2.vc_arbitration_table_offset == 0 {
-            None
-        } else if port_vc_capability_2.vc_arbitration_table_offset < 2 {
-            return Err(MultifunctionVirtualChannelError::VcArbitrationTableOffset);
-        } else {
-            let vat_offset = port_vc_capability_2.vc_arbitration_table_offset as usize * DQWORD;
-            let vat_slice = slice
-                .get(vat_offset..)
-                .ok_or(MultifunctionVirtualChannelError::VcArbitrationTableData)?;
-            let number_of_entries = port_vc_control
-                .vc_arbitration_select
-                .vc_arbitration_table_length();
-            // VAT entries is fixed 4 bits long
-            let length = number_of_entries * 4 / 8;
-            let vat_data = vat_slice.get(..length).ok_or(
-                MultifunctionVirtualChannelError::VcArbitrationTableLength {
-                    expected: length,
-                    real: vat_slice.len(),
-                },
-            )?;
-            Some(VcArbitrationTable::new(vat_data))
-        };
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Multi-Function Virtual Channel",
+                size: Self::MIN_SIZE,
+            })?;
         Ok(Self {
-            extended_virtual_channels: ExtendedVirtualChannels::new(
-                slice,
-                extended_vc_count,
-                function_arbitration_table_entry_size.bits(),
-            ),
-            port_vc_capability_1,
-            port_vc_capability_2,
-            port_vc_control,
-            port_vc_status,
-            vc_arbitration_table,
+            data: slice,
+            port_vc_capability_1: From::<u32>::from(port_vc_capability_1),
+            port_vc_capability_2: From::<u32>::from(port_vc_capability_2),
+            port_vc_control: From::<u16>::from(port_vc_control),
+            port_vc_status: From::<u16>::from(port_vc_status),
         })
     }
 }
@@ -280,64 +222,54 @@ impl From<u8> for FunctionArbitrationTableEntrySize {
 pub struct ExtendedVirtualChannels<'a> {
     data: &'a [u8],
     count: u8,
-    fat_entry_bits: usize,
     offset: usize,
 }
 impl<'a> ExtendedVirtualChannels<'a> {
-    pub fn new(data: &'a [u8], extended_vc_count: u8, fat_entry_bits: usize) -> Self {
+    pub fn new(data: &'a [u8], extended_vc_count: u8) -> Self {
         Self {
             data,
             // Default Extended Vc should always exists
             count: extended_vc_count + 1,
-            fat_entry_bits,
-            offset: 0x10,
+            offset: 0,
         }
     }
 }
 impl<'a> Iterator for ExtendedVirtualChannels<'a> {
-    type Item = ExtendedVirtualChannel<'a>;
+    type Item = Result<ExtendedVirtualChannel, ExtendedVirtualChannelError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.count == 0 {
             None
         } else {
-            let slice = self.data.get(self.offset..)?;
-            let Seq { head, .. } = slice.try_into().ok()?;
-            let mut evc: ExtendedVirtualChannel =
-                From::<[u8; ExtendedVirtualChannel::SIZE]>::from(head);
-            if let fat_offset @ 2.. = evc.vc_resource_capability.function_arbitration_table_offset {
-                let offset = fat_offset as usize * 0x10;
-                let number_of_entries = evc
-                    .vc_resource_control
-                    .function_arbitration_select
-                    .function_arbitration_table_length();
-                let length = self.fat_entry_bits * number_of_entries / 8;
-                evc.function_arbitration_table = self
-                    .data
-                    .get(offset..offset + length)
-                    .map(|data| FunctionArbitrationTable::new(data, self.fat_entry_bits))
-            }
-            self.count -= 1;
+            let slice = self.data.get(self.offset..).unwrap_or_default();
+            let result = slice
+                .try_into()
+                .map(|Seq { head, .. }| From::<[u8; ExtendedVirtualChannel::SIZE]>::from(head))
+                .map_err(|_| ExtendedVirtualChannelError {
+                    number: self.count,
+                    offset: self.offset,
+                });
+            // Resources after truncated data are not readable either
+            self.count = if result.is_ok() { self.count - 1 } else { 0 };
             self.offset += ExtendedVirtualChannel::SIZE;
-            Some(evc)
+            Some(result)
         }
     }
 }
 
 /// Extended Virtual Channel
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ExtendedVirtualChannel<'a> {
+pub struct ExtendedVirtualChannel {
     pub vc_resource_capability: VcResourceCapability,
     pub vc_resource_control: VcResourceControl,
     pub vc_resource_status: VcResourceStatus,
-    pub function_arbitration_table: Option<FunctionArbitrationTable<'a>>,
 }
-impl<'a> ExtendedVirtualChannel<'a> {
+impl ExtendedVirtualChannel {
     /// [VcResourceCapability] + [VcResourceControl] + RsvdP (1 Byte) + [VcResourceStatus]
     pub const SIZE: usize = 4 + 4 + 2 + 2;
 }
 
-impl<'a> From<[u8; ExtendedVirtualChannel::SIZE]> for ExtendedVirtualChannel<'a> {
+impl From<[u8; ExtendedVirtualChannel::SIZE]> for ExtendedVirtualChannel {
     fn from(data: [u8; ExtendedVirtualChannel::SIZE]) -> Self {
         let Le((vc_resource_capability, vc_resource_control, r, vc_resource_status)) =
             P4(data).into();
@@ -346,13 +278,12 @@ impl<'a> From<[u8; ExtendedVirtualChannel::SIZE]> for ExtendedVirtualChannel<'a>
             vc_resource_capability: From::<u32>::from(vc_resource_capability),
             vc_resource_control: From::<u32>::from(vc_resource_control),
             vc_resource_status: From::<u16>::from(vc_resource_status),
-            function_arbitration_table: None,
         }
     }
 }
 
-impl<'a> From<ExtendedVirtualChannel<'a>> for [u8; ExtendedVirtualChannel::SIZE] {
-    fn from(evc: ExtendedVirtualChannel<'a>) -> Self {
+impl From<ExtendedVirtualChannel> for [u8; ExtendedVirtualChannel::SIZE] {
+    fn from(evc: ExtendedVirtualChannel) -> Self {
         let cap = Into::<u32>::into(evc.vc_resource_capability).to_le_bytes();
         let ctrl = Into::<u32>::into(evc.vc_resource_control).to_le_bytes();
         let st = Into::<u16>::into(evc.vc_resource_status).to_le_bytes();
@@ -590,22 +521,14 @@ impl From<VcResourceStatus> for u16 {
 
 /// Function Arbitration Table is used to store the WRR or time-based WRR
 /// arbitration table for Function Arbitration for the VC resource
-#[derive(Debug, Clone)]
-pub struct FunctionArbitrationTable<'a> {
-    data: slice::Iter<'a, u8>,
-    entry_size_bits: usize,
-    shift: usize,
-    byte: u8,
-}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionArbitrationTable<'a>(PackedEntries<'a>);
 
 impl<'a> FunctionArbitrationTable<'a> {
+    /// Entries are packed into bytes, so `entry_size_bits` should be 1, 2, 4 or 8. Table with
+    /// any other entry size is empty.
     pub fn new(data: &'a [u8], entry_size_bits: usize) -> Self {
-        Self {
-            data: data.iter(),
-            entry_size_bits,
-            shift: 0,
-            byte: 0,
-        }
+        Self(PackedEntries::new(data, entry_size_bits))
     }
 }
 
@@ -613,40 +536,20 @@ impl<'a> Iterator for FunctionArbitrationTable<'a> {
     type Item = FunctionArbitrationTableEntry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.entry_size_bits == 8 {
-            self.data.next().map(|&v| FunctionArbitrationTableEntry(v))
-        } else {
-            if self.shift == 0 {
-                self.byte = *self.data.next()?;
-            }
-            let init_mask = !(u8::MAX << self.entry_size_bits);
-            let mask = init_mask << self.shift;
-            let result = (self.byte & mask) >> self.shift;
-            self.shift = (self.shift + self.entry_size_bits) % 8;
-            Some(FunctionArbitrationTableEntry(result))
-        }
-    }
-}
-
-impl<'a> PartialEq for FunctionArbitrationTable<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.data.clone().eq(other.data.clone())
-            && self.entry_size_bits == other.entry_size_bits
-            && self.shift == other.shift
-            && self.byte == other.byte
+        self.0.next().map(FunctionArbitrationTableEntry)
     }
 }
 
-impl<'a> Eq for FunctionArbitrationTable<'a> {}
-
+/// Function Number of the Function Arbitration Table phase
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct FunctionArbitrationTableEntry(u8);
+pub struct FunctionArbitrationTableEntry(pub u8);
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
-    use std::prelude::v1::*;
-    use pretty_assertions::assert_eq;
     use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
 
     #[test]
     fn function_arbitration_table_1_bit_entry() {
@@ -723,11 +626,6 @@ mod tests {
     fn extended_virtual_channels() {
         #[rustfmt::skip]
         let data = [
-            /* 00h */ 0x08, 0x00, 0x01, 0x00,             // Capability header
-            /* 04h */ 0b0_000_0_001, 0b1_00, 0x00, 0x00,  // Port VC Capability Register 1
-            /* 08h */ 0b1111, 0x00, 0x00, 0x00,           // Port VC Capability Register 2
-            /* 0Ch */ 0b0010, 0x00,                       // Port VC Control Register
-                      0x00, 0x00,                         // Port VC Status Register
             /* 10h */ 0b111, 0x00, 0x00, 0x04,            // VC Resource Capability Register (0)
             /* 14h */ 0x0f, 0x00, 0x04, 0x80,             // VC Resource Control Register (0)
             /* 18h */ 0x00, 0x00,                         // RsvdP
@@ -737,7 +635,7 @@ mod tests {
             /* 24h */ 0x00, 0x00,                         // RsvdP
                       0b01, 0x00,                         // VC Resource Status Register (1)
         ];
-        let result: Vec<_> = ExtendedVirtualChannels::new(data.as_slice(), 1, 0).collect();
+        let result: Result<Vec<_>, _> = ExtendedVirtualChannels::new(data.as_slice(), 1).collect();
 
         let sample = vec![
             ExtendedVirtualChannel {
@@ -765,7 +663,6 @@ mod tests {
                     function_arbitration_table_status: false,
                     vc_negotiation_pending: true,
                 },
-                function_arbitration_table: None,
             },
             ExtendedVirtualChannel {
                 vc_resource_capability: VcResourceCapability {
@@ -792,10 +689,116 @@ mod tests {
                     function_arbitration_table_status: true,
                     vc_negotiation_pending: false,
                 },
-                function_arbitration_table: None,
             },
         ];
 
-        assert_eq!(sample, result);
+        assert_eq!(Ok(sample), result);
+
+        // Extended VC Count is larger than the data
+        let result = ExtendedVirtualChannels::new(data.as_slice(), 2).last();
+        let error = ExtendedVirtualChannelError {
+            number: 1,
+            offset: 2 * ExtendedVirtualChannel::SIZE,
+        };
+        assert_eq!(Some(Err(error)), result);
+    }
+
+    #[test]
+    fn function_arbitration_table_location() {
+        #[rustfmt::skip]
+        let mut data = [
+            /* 00h */ 0x08, 0x00, 0x01, 0x00, // Capability header
+            /* 04h */ 0x00, 0x08, 0x00, 0x00, // Port VC Capability Register 1: 4 bits entry
+            /* 08h */ 0x00, 0x00, 0x00, 0x00, // Port VC Capability Register 2
+            /* 0Ch */ 0x00, 0x00, 0x00, 0x00, // Port VC Control/Status Registers
+            /* 10h */ 0x02, 0x00, 0x00, 0x02, // VC Resource Capability Register (0)
+            /* 14h */ 0xff, 0x00, 0x02, 0x80, // VC Resource Control Register (0): WRR32
+            /* 18h */ 0x00, 0x00, 0x00, 0x00, // VC Resource Status Register (0)
+            /* 1Ch */ 0x00, 0x00, 0x00, 0x00,
+            /* 20h */ 0x10, 0x32, 0x54, 0x76, 0x10, 0x32, 0x54, 0x76, // Function Arbitration
+            /* 28h */ 0x10, 0x32, 0x54, 0x76, 0x10, 0x32, 0x54, 0x76, // Table
+        ];
+        let function_arbitration_table = |data: &[u8]| {
+            let mfvc: MultifunctionVirtualChannel = data[4..].try_into().unwrap();
+            let evc = mfvc.extended_virtual_channels().next().unwrap().unwrap();
+            mfvc.function_arbitration_table(&evc).map(|fat| {
+                fat.map(|FunctionArbitrationTableEntry(v)| v)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let sample = (0..32).map(|i| i % 8).collect::<Vec<_>>();
+        assert_eq!(Ok(sample), function_arbitration_table(&data), "Valid");
+
+        assert_eq!(
+            Err(ArbitrationTableError::Data {
+                start: 0x20,
+                end: 0x30,
+                size: 0x2f
+            }),
+            function_arbitration_table(&data[..0x2f]),
+            "Truncated"
+        );
+
+        data[0x13] = 0;
+        assert_eq!(
+            Err(ArbitrationTableError::Offset { entries_number: 32 }),
+            function_arbitration_table(&data),
+            "Offset is 0"
+        );
+
+        // Hardware fixed arbitration does not need table
+        data[0x16] = 0x00;
+        assert_eq!(
+            Ok(vec![]),
+            function_arbitration_table(&data),
+            "Hardware fixed"
+        );
+
+        data[0x16] = 0b110 << 1;
+        assert_eq!(
+            Err(ArbitrationTableError::ReservedSelect { value: 0b110 }),
+            function_arbitration_table(&data),
+            "Reserved"
+        );
+    }
+
+    #[test]
+    fn vc_arbitration_table_location() {
+        #[rustfmt::skip]
+        let mut data = [
+            /* 00h */ 0x08, 0x00, 0x01, 0x00, // Capability header
+            /* 04h */ 0x00, 0x00, 0x00, 0x00, // Port VC Capability Register 1
+            /* 08h */ 0x02, 0x00, 0x00, 0x02, // Port VC Capability Register 2
+            /* 0Ch */ 0x02, 0x00, 0x00, 0x00, // Port VC Control/Status Registers: WRR32
+            /* 10h */ 0x00, 0x00, 0x00, 0x00, // VC Resource Capability Register (0)
+            /* 14h */ 0xff, 0x00, 0x00, 0x80, // VC Resource Control Register (0)
+            /* 18h */ 0x00, 0x00, 0x00, 0x00, // VC Resource Status Register (0)
+            /* 1Ch */ 0x00, 0x00, 0x00, 0x00,
+            /* 20h */ 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, // VC Arbitration
+            /* 28h */ 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, // Table
+        ];
+        let vc_arbitration_table = |data: &[u8]| {
+            let mfvc: MultifunctionVirtualChannel = data[4..].try_into().unwrap();
+            mfvc.vc_arbitration_table()
+                .map(|vat| vat.map(|e| e.vc_id).collect::<Vec<_>>())
+        };
+
+        assert_eq!(Ok([0, 1].repeat(16)), vc_arbitration_table(&data), "Valid");
+        assert_eq!(
+            Err(ArbitrationTableError::Data {
+                start: 0x20,
+                end: 0x30,
+                size: 0x20
+            }),
+            vc_arbitration_table(&data[..0x20]),
+            "Truncated"
+        );
+        data[0x0c] = 0b111 << 1;
+        assert_eq!(
+            Err(ArbitrationTableError::ReservedSelect { value: 0b111 }),
+            vc_arbitration_table(&data),
+            "Reserved"
+        );
     }
 }
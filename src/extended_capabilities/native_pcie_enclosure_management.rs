@@ -0,0 +1,301 @@
+/*!
+# Native PCIe Enclosure Management (NPEM)
+
+The NPEM Extended Capability provides mechanisms for enclosure management, e.g. storage
+backplane indicators (LEDs) associated with a Downstream Port or an Upstream Port. Software
+sets indicator states in NPEM Control, the enclosure reports the command completion in
+NPEM Status.
+
+## Struct diagram
+[NativePcieEnclosureManagement]
+- [NpemCapability]
+- [NpemControl]
+- [NpemStatus]
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::native_pcie_enclosure_management::*;
+let data = [
+    /* 00h */ 0x29, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0xff, 0x0f, 0x00, 0x00, // NPEM Capability
+    /* 08h */ 0x09, 0x00, 0x00, 0x00, // NPEM Control
+    /* 0Ch */ 0x01, 0x00, 0x00, 0x00, // NPEM Status
+];
+let result: NativePcieEnclosureManagement = data[4..].try_into().unwrap();
+assert!(result.capability.npem_capable && result.control.npem_enable);
+assert!(result.status.npem_command_completed);
+
+// Locate indicator is on
+let on = result
+    .control
+    .indicators()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect::<Vec<_>>();
+assert_eq!(vec!["locate_control"], on);
+```
+*/
+
+use heterob::{endianness::Le, Seq, P3};
+
+use super::ExtendedCapabilityDataError;
+use crate::register::{flags, register};
+
+/// Native PCIe Enclosure Management
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativePcieEnclosureManagement {
+    /// NPEM Capability
+    pub capability: NpemCapability,
+    /// NPEM Control
+    pub control: NpemControl,
+    /// NPEM Status
+    pub status: NpemStatus,
+}
+
+impl NativePcieEnclosureManagement {
+    /// NPEM Capability, Control and Status registers
+    pub const SIZE: usize = 3 * 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
+
+impl TryFrom<&[u8]> for NativePcieEnclosureManagement {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((capability, control, status)),
+            ..
+        } = P3(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Native PCIe Enclosure Management",
+                size: Self::MIN_SIZE,
+            })?;
+        Ok(Self {
+            capability: From::<u32>::from(capability),
+            control: From::<u32>::from(control),
+            status: From::<u32>::from(status),
+        })
+    }
+}
+
+/// NPEM Capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpemCapability {
+    /// NPEM Capable
+    pub npem_capable: bool,
+    /// NPEM Reset Capable
+    pub npem_reset_capable: bool,
+    /// NPEM OK Capable
+    pub ok_capable: bool,
+    /// NPEM Locate Capable
+    pub locate_capable: bool,
+    /// NPEM Fail Capable
+    pub fail_capable: bool,
+    /// NPEM Rebuild Capable
+    pub rebuild_capable: bool,
+    /// NPEM PFA (Predicted Failure Analysis) Capable
+    pub pfa_capable: bool,
+    /// NPEM Hot Spare Capable
+    pub hot_spare_capable: bool,
+    /// NPEM In A Critical Array Capable
+    pub in_a_critical_array_capable: bool,
+    /// NPEM In A Failed Array Capable
+    pub in_a_failed_array_capable: bool,
+    /// NPEM Invalid Device Type Capable
+    pub invalid_device_type_capable: bool,
+    /// NPEM Disabled Capable
+    pub disabled_capable: bool,
+    /// Enclosure-specific Capabilities
+    pub enclosure_specific_capabilities: u8,
+}
+
+register!(NpemCapability: u32 {
+    npem_capable[0],
+    npem_reset_capable[1],
+    ok_capable[2],
+    locate_capable[3],
+    fail_capable[4],
+    rebuild_capable[5],
+    pfa_capable[6],
+    hot_spare_capable[7],
+    in_a_critical_array_capable[8],
+    in_a_failed_array_capable[9],
+    invalid_device_type_capable[10],
+    disabled_capable[11],
+    enclosure_specific_capabilities[24..32],
+});
+
+flags!(
+    /// Indicator support bits as `(field name, value)` pairs
+    NpemCapability::indicators {
+        ok_capable,
+        locate_capable,
+        fail_capable,
+        rebuild_capable,
+        pfa_capable,
+        hot_spare_capable,
+        in_a_critical_array_capable,
+        in_a_failed_array_capable,
+        invalid_device_type_capable,
+        disabled_capable,
+    }
+);
+
+/// NPEM Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpemControl {
+    /// NPEM Enable
+    pub npem_enable: bool,
+    /// NPEM Initiate Reset
+    pub npem_initiate_reset: bool,
+    /// NPEM OK Control
+    pub ok_control: bool,
+    /// NPEM Locate Control
+    pub locate_control: bool,
+    /// NPEM Fail Control
+    pub fail_control: bool,
+    /// NPEM Rebuild Control
+    pub rebuild_control: bool,
+    /// NPEM PFA (Predicted Failure Analysis) Control
+    pub pfa_control: bool,
+    /// NPEM Hot Spare Control
+    pub hot_spare_control: bool,
+    /// NPEM In A Critical Array Control
+    pub in_a_critical_array_control: bool,
+    /// NPEM In A Failed Array Control
+    pub in_a_failed_array_control: bool,
+    /// NPEM Invalid Device Type Control
+    pub invalid_device_type_control: bool,
+    /// NPEM Disabled Control
+    pub disabled_control: bool,
+    /// Enclosure-specific Controls
+    pub enclosure_specific_controls: u8,
+}
+
+register!(NpemControl: u32 {
+    npem_enable[0],
+    npem_initiate_reset[1],
+    ok_control[2],
+    locate_control[3],
+    fail_control[4],
+    rebuild_control[5],
+    pfa_control[6],
+    hot_spare_control[7],
+    in_a_critical_array_control[8],
+    in_a_failed_array_control[9],
+    invalid_device_type_control[10],
+    disabled_control[11],
+    enclosure_specific_controls[24..32],
+});
+
+flags!(
+    /// Indicator control bits as `(field name, value)` pairs
+    NpemControl::indicators {
+        ok_control,
+        locate_control,
+        fail_control,
+        rebuild_control,
+        pfa_control,
+        hot_spare_control,
+        in_a_critical_array_control,
+        in_a_failed_array_control,
+        invalid_device_type_control,
+        disabled_control,
+    }
+);
+
+/// NPEM Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpemStatus {
+    /// NPEM Command Completed
+    pub npem_command_completed: bool,
+    /// Enclosure-specific Status
+    pub enclosure_specific_status: u8,
+}
+
+register!(NpemStatus: u32 {
+    npem_command_completed[0],
+    enclosure_specific_status[24..32],
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn parse() {
+        let data = [
+            0x4d, 0x05, 0x00, 0xa5, 0x15, 0x01, 0xf0, 0x5a, 0x01, 0x00, 0x00, 0x3c, 0xff,
+        ];
+        let result: NativePcieEnclosureManagement = data.as_slice().try_into().unwrap();
+        let sample = NativePcieEnclosureManagement {
+            capability: NpemCapability {
+                npem_capable: true,
+                npem_reset_capable: false,
+                ok_capable: true,
+                locate_capable: true,
+                fail_capable: false,
+                rebuild_capable: false,
+                pfa_capable: true,
+                hot_spare_capable: false,
+                in_a_critical_array_capable: true,
+                in_a_failed_array_capable: false,
+                invalid_device_type_capable: true,
+                disabled_capable: false,
+                enclosure_specific_capabilities: 0xa5,
+            },
+            control: NpemControl {
+                npem_enable: true,
+                npem_initiate_reset: false,
+                ok_control: true,
+                locate_control: false,
+                fail_control: true,
+                rebuild_control: false,
+                pfa_control: false,
+                hot_spare_control: false,
+                in_a_critical_array_control: true,
+                in_a_failed_array_control: false,
+                invalid_device_type_control: false,
+                disabled_control: false,
+                enclosure_specific_controls: 0x5a,
+            },
+            status: NpemStatus {
+                npem_command_completed: true,
+                enclosure_specific_status: 0x3c,
+            },
+        };
+        assert_eq!(sample, result);
+
+        let supported = result
+            .capability
+            .indicators()
+            .filter_map(|(name, supported)| supported.then_some(name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "ok_capable",
+                "locate_capable",
+                "pfa_capable",
+                "in_a_critical_array_capable",
+                "invalid_device_type_capable"
+            ],
+            supported
+        );
+        assert_eq!(10, result.control.indicators().count());
+        // Reserved bits are dropped
+        assert_eq!(0x5a000115, u32::from(result.control));
+    }
+
+    #[test]
+    fn short_data() {
+        let result = NativePcieEnclosureManagement::try_from([0x00; 11].as_slice());
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Native PCIe Enclosure Management",
+            size: NativePcieEnclosureManagement::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+    }
+}
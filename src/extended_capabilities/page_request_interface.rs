@@ -52,6 +52,10 @@ pub struct PageRequestInterface {
     /// Outstanding Page Request Allocation
     pub outstanding_page_request_allocation: u32,
 }
+impl PageRequestInterface {
+    pub const SIZE: usize = 2 + 2 + 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for PageRequestInterface {
     type Error = ExtendedCapabilityDataError;
 
@@ -69,7 +73,7 @@ impl TryFrom<&[u8]> for PageRequestInterface {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Page Request Interface",
-                size: 12,
+                size: Self::MIN_SIZE,
             })?;
         let Lsb((enable, reset, ())) = P3::<u16, 1, 1, 14>(control).into();
         let Lsb((
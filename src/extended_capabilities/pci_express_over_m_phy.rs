@@ -114,6 +114,8 @@ pub struct PciExpressOverMphy {
 impl PciExpressOverMphy {
     /// Size in bytes (with Extended Capability Header)
     pub const SIZE: usize = 0x1C;
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl TryFrom<&[u8]> for PciExpressOverMphy {
@@ -139,7 +141,7 @@ impl TryFrom<&[u8]> for PciExpressOverMphy {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "M-PCIe",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             mpcie_capabilities: From::<u32>::from(mpcie_capabilities),
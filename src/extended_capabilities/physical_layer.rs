@@ -0,0 +1,196 @@
+/*!
+# Physical Layer 16.0, 32.0 and 64.0 GT/s
+
+Physical Layer 16.0 GT/s, 32.0 GT/s and 64.0 GT/s Extended Capabilities share a layout:
+Capabilities, Control and Status registers, followed by rate-specific registers and an array of
+per-Lane Equalization Control registers sized by Maximum Link Width.
+
+- [EqualizationStatus] holds the Status register bits common to all three data rates
+- [LaneEqualizationControl] is a one byte entry of the per-Lane array
+- [PhysicalLayer] gives a uniform per-Lane access to these registers
+
+## Examples
+
+```rust
+# use pcics::capabilities::pci_express::{LinkWidth, TransmitterPreset};
+# use pcics::extended_capabilities::physical_layer::*;
+let status = EqualizationStatus::from(0x0000_000f);
+assert!(status.is_successful());
+
+// Downstream Port Transmitter Preset P4, Upstream Port Transmitter Preset P7
+let lanes = LaneEqualizationControls::new(&[0x74, 0x74, 0x74, 0x74], LinkWidth::X2);
+let sample = LaneEqualizationControl {
+    downstream_port_transmitter_preset: TransmitterPreset::P4,
+    upstream_port_transmitter_preset: TransmitterPreset::P7,
+};
+assert_eq!(vec![(0, sample.clone()), (1, sample)], lanes.collect::<Vec<_>>());
+```
+*/
+
+use core::{iter::Enumerate, slice::Iter};
+
+use crate::{
+    capabilities::pci_express::{LinkWidth, TransmitterPreset},
+    register::register,
+};
+
+/// Physical Layer Extended Capability of a data rate starting with 16.0 GT/s
+pub trait PhysicalLayer<'a> {
+    /// Data rate in GT/s
+    const DATA_RATE: u8;
+    /// Status register bits common to all data rates
+    fn equalization_status(&self) -> EqualizationStatus;
+    /// Lane Equalization Control array bytes following the fixed registers
+    fn lane_equalization_control_data(&self) -> &'a [u8];
+    /// An iterator through Lane Equalization Control entries of `link_width` Lanes
+    ///
+    /// `link_width` is usually Maximum Link Width of the PCI Express Capability, the iterator
+    /// stops at the end of the available data.
+    fn equalization_control_lanes(&self, link_width: LinkWidth) -> LaneEqualizationControls<'a> {
+        LaneEqualizationControls::new(self.lane_equalization_control_data(), link_width)
+    }
+}
+
+/// Equalization Status bits of the Physical Layer 16.0, 32.0 and 64.0 GT/s Status register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EqualizationStatus {
+    /// Equalization Complete
+    pub equalization_complete: bool,
+    /// Equalization Phase 1 Successful
+    pub equalization_phase_1_successful: bool,
+    /// Equalization Phase 2 Successful
+    pub equalization_phase_2_successful: bool,
+    /// Equalization Phase 3 Successful
+    pub equalization_phase_3_successful: bool,
+    /// Link Equalization Request
+    pub link_equalization_request: bool,
+}
+
+register!(EqualizationStatus: u32 {
+    equalization_complete[0],
+    equalization_phase_1_successful[1],
+    equalization_phase_2_successful[2],
+    equalization_phase_3_successful[3],
+    link_equalization_request[4],
+});
+
+impl EqualizationStatus {
+    /// Equalization procedure completed with all phases successful
+    pub fn is_successful(&self) -> bool {
+        self.equalization_complete
+            && self.equalization_phase_1_successful
+            && self.equalization_phase_2_successful
+            && self.equalization_phase_3_successful
+    }
+}
+
+/// Lane Equalization Control register of the Physical Layer 16.0, 32.0 and 64.0 GT/s
+/// Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneEqualizationControl {
+    /// Downstream Port Transmitter Preset
+    pub downstream_port_transmitter_preset: TransmitterPreset,
+    /// Upstream Port Transmitter Preset
+    pub upstream_port_transmitter_preset: TransmitterPreset,
+}
+
+register!(LaneEqualizationControl: u8 {
+    downstream_port_transmitter_preset[0..4],
+    upstream_port_transmitter_preset[4..8],
+});
+
+/// An iterator through `(lane, control)` pairs of Lane Equalization Control array
+#[derive(Debug, Clone)]
+pub struct LaneEqualizationControls<'a> {
+    iter: Enumerate<Iter<'a, u8>>,
+}
+
+impl<'a> LaneEqualizationControls<'a> {
+    /// One byte entries of `link_width` Lanes from `data`
+    pub fn new(data: &'a [u8], link_width: LinkWidth) -> Self {
+        let end = link_width.value() as usize;
+        Self {
+            iter: data.get(..end).unwrap_or(data).iter().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for LaneEqualizationControls<'a> {
+    type Item = (u8, LaneEqualizationControl);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (lane, &byte) = self.iter.next()?;
+        Some((lane as u8, byte.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    /// Minimal data rate with the Lane Equalization Control array right after the Status
+    struct Sample<'a> {
+        status: u32,
+        lanes: &'a [u8],
+    }
+
+    impl<'a> PhysicalLayer<'a> for Sample<'a> {
+        const DATA_RATE: u8 = 64;
+
+        fn equalization_status(&self) -> EqualizationStatus {
+            self.status.into()
+        }
+        fn lane_equalization_control_data(&self) -> &'a [u8] {
+            self.lanes
+        }
+    }
+
+    #[test]
+    fn equalization_status() {
+        let result = EqualizationStatus::from(0xffff_fff7);
+        let sample = EqualizationStatus {
+            equalization_complete: true,
+            equalization_phase_1_successful: true,
+            equalization_phase_2_successful: true,
+            equalization_phase_3_successful: false,
+            link_equalization_request: true,
+        };
+        assert_eq!(sample, result);
+        assert!(!result.is_successful());
+        assert_eq!(0x17, u32::from(result));
+    }
+
+    #[test]
+    fn lane_equalization_control() {
+        let result = LaneEqualizationControl::from(0xb2);
+        let sample = LaneEqualizationControl {
+            downstream_port_transmitter_preset: TransmitterPreset::P2,
+            upstream_port_transmitter_preset: TransmitterPreset::Reserved(0xb),
+        };
+        assert_eq!(sample, result);
+        assert_eq!(0xb2, u8::from(result));
+    }
+
+    #[test]
+    fn equalization_control_lanes() {
+        let data = [0x10, 0x32, 0x54, 0x76];
+        let phy = Sample {
+            status: 0,
+            lanes: &data,
+        };
+        let presets = |link_width| {
+            phy.equalization_control_lanes(link_width)
+                .map(|(lane, control)| (lane, u8::from(control)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![(0, 0x10), (1, 0x32)], presets(LinkWidth::X2));
+        // Short data
+        assert_eq!(
+            vec![(0, 0x10), (1, 0x32), (2, 0x54), (3, 0x76)],
+            presets(LinkWidth::X16)
+        );
+        assert!(!phy.equalization_status().equalization_complete);
+    }
+}
@@ -0,0 +1,201 @@
+/*!
+# Physical Layer 16.0 GT/s
+
+The Physical Layer 16.0 GT/s Extended Capability structure must be implemented in any Function
+or RCRB where the Supported Link Speeds Vector field indicates that the Link supports 16.0 GT/s
+or higher. It reports the 16.0 GT/s equalization results, Data Parity Mismatches detected by
+the Port and Retimers, and holds per-Lane 16.0 GT/s transmitter presets.
+
+## Struct diagram
+[PhysicalLayer16GTps]
+- [EqualizationStatus]
+- [DataParityMismatchStatus]
+- [LaneEqualizationControl](super::physical_layer::LaneEqualizationControl)
+
+## Examples
+
+```rust
+# use pcics::capabilities::pci_express::{LinkWidth, TransmitterPreset};
+# use pcics::extended_capabilities::physical_layer::{LaneEqualizationControl, PhysicalLayer};
+# use pcics::extended_capabilities::physical_layer_16_gtps::*;
+let data = [
+    /* 00h */ 0x26, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0x00, 0x00, 0x00, 0x00, // 16.0 GT/s Capabilities
+    /* 08h */ 0x00, 0x00, 0x00, 0x00, // 16.0 GT/s Control
+    /* 0Ch */ 0x0f, 0x00, 0x00, 0x00, // 16.0 GT/s Status
+    /* 10h */ 0x02, 0x00, 0x00, 0x00, // Local Data Parity Mismatch Status
+    /* 14h */ 0x00, 0x00, 0x00, 0x00, // First Retimer Data Parity Mismatch Status
+    /* 18h */ 0x00, 0x00, 0x00, 0x00, // Second Retimer Data Parity Mismatch Status
+    /* 1Ch */ 0x00, 0x00, 0x00, 0x00, // Reserved
+    /* 20h */ 0x47, 0x47, 0x00, 0x00, // 16.0 GT/s Lane Equalization Control
+];
+let result: PhysicalLayer16GTps = data[4..].try_into().unwrap();
+assert!(result.status.is_successful());
+assert_eq!(vec![1], result.local_data_parity_mismatch_status.lanes().collect::<Vec<_>>());
+
+let control = LaneEqualizationControl {
+    downstream_port_transmitter_preset: TransmitterPreset::P7,
+    upstream_port_transmitter_preset: TransmitterPreset::P4,
+};
+let lanes = result.equalization_control_lanes(LinkWidth::X2).collect::<Vec<_>>();
+assert_eq!(vec![(0, control.clone()), (1, control)], lanes);
+```
+*/
+
+use heterob::{endianness::Le, Seq, P7};
+
+use super::{
+    physical_layer::{EqualizationStatus, PhysicalLayer},
+    secondary_pci_express::{LaneErrorStatus, LaneErrors},
+    ExtendedCapabilityDataError,
+};
+
+/// Physical Layer 16.0 GT/s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalLayer16GTps<'a> {
+    /// 16.0 GT/s Capabilities, all bits are reserved
+    pub capabilities: u32,
+    /// 16.0 GT/s Control, all bits are reserved
+    pub control: u32,
+    /// 16.0 GT/s Status
+    pub status: EqualizationStatus,
+    /// Local Data Parity Mismatch Status
+    pub local_data_parity_mismatch_status: DataParityMismatchStatus,
+    /// First Retimer Data Parity Mismatch Status
+    pub first_retimer_data_parity_mismatch_status: DataParityMismatchStatus,
+    /// Second Retimer Data Parity Mismatch Status
+    pub second_retimer_data_parity_mismatch_status: DataParityMismatchStatus,
+    lane_equalization_control_data: &'a [u8],
+}
+
+impl<'a> PhysicalLayer16GTps<'a> {
+    /// Registers up to the 16.0 GT/s Lane Equalization Control array
+    pub const MIN_SIZE: usize = 7 * 4;
+}
+
+impl<'a> PhysicalLayer<'a> for PhysicalLayer16GTps<'a> {
+    const DATA_RATE: u8 = 16;
+
+    fn equalization_status(&self) -> EqualizationStatus {
+        self.status.clone()
+    }
+    fn lane_equalization_control_data(&self) -> &'a [u8] {
+        self.lane_equalization_control_data
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PhysicalLayer16GTps<'a> {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head:
+                Le((capabilities, control, status, local, first_retimer, second_retimer, _reserved)),
+            tail,
+        } = P7(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Physical Layer 16.0 GT/s",
+                size: Self::MIN_SIZE,
+            })?;
+        let _: [u8; 4] = _reserved;
+        Ok(Self {
+            capabilities,
+            control,
+            status: From::<u32>::from(status),
+            local_data_parity_mismatch_status: DataParityMismatchStatus(local),
+            first_retimer_data_parity_mismatch_status: DataParityMismatchStatus(first_retimer),
+            second_retimer_data_parity_mismatch_status: DataParityMismatchStatus(second_retimer),
+            lane_equalization_control_data: tail,
+        })
+    }
+}
+
+/// Data Parity Mismatch Status registers consist of a 32-bit vector, where each bit indicates
+/// if the Lane with the corresponding Lane number detected a Data Parity mismatch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataParityMismatchStatus(pub u32);
+
+impl DataParityMismatchStatus {
+    /// An iterator through numbers of Lanes with detected Data Parity mismatches
+    pub fn lanes(&self) -> LaneErrors {
+        LaneErrorStatus(self.0).lanes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::pci_express::LinkWidth;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const DATA: [u8; 0x24] = [
+        0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x17, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00,
+        0x80, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xaa, 0xaa, 0xaa, 0xaa, 0x10, 0x32,
+        0x54, 0x76, 0x98, 0xba, 0xdc, 0xfe,
+    ];
+
+    #[test]
+    fn parse() {
+        let result: PhysicalLayer16GTps = DATA.as_slice().try_into().unwrap();
+        let sample = PhysicalLayer16GTps {
+            capabilities: 0xffffffff,
+            control: 0,
+            status: EqualizationStatus {
+                equalization_complete: true,
+                equalization_phase_1_successful: true,
+                equalization_phase_2_successful: true,
+                equalization_phase_3_successful: false,
+                link_equalization_request: true,
+            },
+            local_data_parity_mismatch_status: DataParityMismatchStatus(0x80000005),
+            first_retimer_data_parity_mismatch_status: DataParityMismatchStatus(0x100),
+            second_retimer_data_parity_mismatch_status: DataParityMismatchStatus(0),
+            lane_equalization_control_data: &DATA[0x1c..],
+        };
+        assert_eq!(sample, result);
+        assert_eq!(
+            vec![0, 2, 31],
+            result
+                .local_data_parity_mismatch_status
+                .lanes()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            None,
+            result
+                .second_retimer_data_parity_mismatch_status
+                .lanes()
+                .next()
+        );
+        let presets = result
+            .equalization_control_lanes(LinkWidth::X16)
+            .map(|(lane, control)| (lane, u8::from(control)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (0, 0x10),
+                (1, 0x32),
+                (2, 0x54),
+                (3, 0x76),
+                (4, 0x98),
+                (5, 0xba)
+            ],
+            presets[..6]
+        );
+        assert_eq!(8, presets.len());
+    }
+
+    #[test]
+    fn short_data() {
+        let result = PhysicalLayer16GTps::try_from(&DATA[..PhysicalLayer16GTps::MIN_SIZE - 1]);
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Physical Layer 16.0 GT/s",
+            size: PhysicalLayer16GTps::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+        let result = PhysicalLayer16GTps::try_from(&DATA[..PhysicalLayer16GTps::MIN_SIZE]).unwrap();
+        assert_eq!(0, result.equalization_control_lanes(LinkWidth::X4).count());
+    }
+}
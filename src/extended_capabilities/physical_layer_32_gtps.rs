@@ -0,0 +1,383 @@
+/*!
+# Physical Layer 32.0 GT/s
+
+The Physical Layer 32.0 GT/s Extended Capability structure must be implemented in any Function
+or RCRB where the Supported Link Speeds Vector field indicates that the Link supports 32.0 GT/s
+or higher. It controls Equalization bypass and Modified TS1/TS2 Ordered Sets usage, reports the
+32.0 GT/s equalization results, Modified TS Data exchanged during Link training, and holds
+per-Lane 32.0 GT/s transmitter presets.
+
+## Struct diagram
+[PhysicalLayer32GTps]
+- [PhysicalLayer32GTpsCapabilities]
+  - [ModifiedTsUsageMode]
+- [PhysicalLayer32GTpsControl]
+- [PhysicalLayer32GTpsStatus]
+- [ModifiedTsData1]
+- [ModifiedTsData2]
+- [LaneEqualizationControl](super::physical_layer::LaneEqualizationControl)
+
+## Examples
+
+```rust
+# use pcics::capabilities::pci_express::{LinkWidth, TransmitterPreset};
+# use pcics::extended_capabilities::physical_layer::{LaneEqualizationControl, PhysicalLayer};
+# use pcics::extended_capabilities::physical_layer_32_gtps::*;
+let data = [
+    /* 00h */ 0x2a, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0x03, 0x07, 0x00, 0x00, // 32.0 GT/s Capabilities
+    /* 08h */ 0x00, 0x00, 0x00, 0x00, // 32.0 GT/s Control
+    /* 0Ch */ 0x0f, 0x00, 0x00, 0x00, // 32.0 GT/s Status
+    /* 10h */ 0x00, 0x00, 0x00, 0x00, // Received Modified TS Data 1
+    /* 14h */ 0x00, 0x00, 0x00, 0x00, // Received Modified TS Data 2
+    /* 18h */ 0x00, 0x00, 0x00, 0x00, // Transmitted Modified TS Data 1
+    /* 1Ch */ 0x00, 0x00, 0x00, 0x00, // Transmitted Modified TS Data 2
+    /* 20h */ 0x55, 0x55, 0x00, 0x00, // 32.0 GT/s Lane Equalization Control
+];
+let result: PhysicalLayer32GTps = data[4..].try_into().unwrap();
+assert!(result.capabilities.no_equalization_needed_supported);
+assert!(result.capabilities.is_usage_mode_supported(ModifiedTsUsageMode::AlternateProtocols));
+assert!(result.equalization_status().is_successful());
+
+let control = LaneEqualizationControl {
+    downstream_port_transmitter_preset: TransmitterPreset::P5,
+    upstream_port_transmitter_preset: TransmitterPreset::P5,
+};
+let lanes = result.equalization_control_lanes(LinkWidth::X2).collect::<Vec<_>>();
+assert_eq!(vec![(0, control.clone()), (1, control)], lanes);
+```
+*/
+
+use heterob::{endianness::Le, Seq, P7};
+
+use super::{
+    physical_layer::{EqualizationStatus, PhysicalLayer},
+    ExtendedCapabilityDataError,
+};
+use crate::register::{impl_u8_field, register};
+
+/// Physical Layer 32.0 GT/s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalLayer32GTps<'a> {
+    /// 32.0 GT/s Capabilities
+    pub capabilities: PhysicalLayer32GTpsCapabilities,
+    /// 32.0 GT/s Control
+    pub control: PhysicalLayer32GTpsControl,
+    /// 32.0 GT/s Status
+    pub status: PhysicalLayer32GTpsStatus,
+    /// Received Modified TS Data 1
+    pub received_modified_ts_data_1: ModifiedTsData1,
+    /// Received Modified TS Data 2
+    pub received_modified_ts_data_2: ModifiedTsData2,
+    /// Transmitted Modified TS Data 1
+    pub transmitted_modified_ts_data_1: ModifiedTsData1,
+    /// Transmitted Modified TS Data 2
+    pub transmitted_modified_ts_data_2: ModifiedTsData2,
+    lane_equalization_control_data: &'a [u8],
+}
+
+impl<'a> PhysicalLayer32GTps<'a> {
+    /// Registers up to the 32.0 GT/s Lane Equalization Control array
+    pub const MIN_SIZE: usize = 7 * 4;
+}
+
+impl<'a> PhysicalLayer<'a> for PhysicalLayer32GTps<'a> {
+    const DATA_RATE: u8 = 32;
+
+    fn equalization_status(&self) -> EqualizationStatus {
+        let status = &self.status;
+        EqualizationStatus {
+            equalization_complete: status.equalization_complete,
+            equalization_phase_1_successful: status.equalization_phase_1_successful,
+            equalization_phase_2_successful: status.equalization_phase_2_successful,
+            equalization_phase_3_successful: status.equalization_phase_3_successful,
+            link_equalization_request: status.link_equalization_request,
+        }
+    }
+    fn lane_equalization_control_data(&self) -> &'a [u8] {
+        self.lane_equalization_control_data
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PhysicalLayer32GTps<'a> {
+    type Error = ExtendedCapabilityDataError;
+
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let Seq {
+            head: Le((capabilities, control, status, rx_data_1, rx_data_2, tx_data_1, tx_data_2)),
+            tail,
+        } = P7(slice)
+            .try_into()
+            .map_err(|_| ExtendedCapabilityDataError {
+                name: "Physical Layer 32.0 GT/s",
+                size: Self::MIN_SIZE,
+            })?;
+        Ok(Self {
+            capabilities: From::<u32>::from(capabilities),
+            control: From::<u32>::from(control),
+            status: From::<u32>::from(status),
+            received_modified_ts_data_1: From::<u32>::from(rx_data_1),
+            received_modified_ts_data_2: From::<u32>::from(rx_data_2),
+            transmitted_modified_ts_data_1: From::<u32>::from(tx_data_1),
+            transmitted_modified_ts_data_2: From::<u32>::from(tx_data_2),
+            lane_equalization_control_data: tail,
+        })
+    }
+}
+
+/// 32.0 GT/s Capabilities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalLayer32GTpsCapabilities {
+    /// Equalization bypass to highest rate Supported
+    pub equalization_bypass_to_highest_rate_supported: bool,
+    /// No Equalization Needed Supported
+    pub no_equalization_needed_supported: bool,
+    /// Modified TS Usage Mode 0 Supported - PCI Express
+    pub modified_ts_usage_mode_0_supported: bool,
+    /// Modified TS Usage Mode 1 Supported - Training Set Message
+    pub modified_ts_usage_mode_1_supported: bool,
+    /// Modified TS Usage Mode 2 Supported - Alternate Protocol
+    pub modified_ts_usage_mode_2_supported: bool,
+}
+
+register!(PhysicalLayer32GTpsCapabilities: u32 {
+    equalization_bypass_to_highest_rate_supported[0],
+    no_equalization_needed_supported[1],
+    modified_ts_usage_mode_0_supported[8],
+    modified_ts_usage_mode_1_supported[9],
+    modified_ts_usage_mode_2_supported[10],
+});
+
+impl PhysicalLayer32GTpsCapabilities {
+    /// Modified TS Usage Mode Supported bit of `mode`, reserved modes are not supported
+    pub fn is_usage_mode_supported(&self, mode: ModifiedTsUsageMode) -> bool {
+        match mode {
+            ModifiedTsUsageMode::PciExpress => self.modified_ts_usage_mode_0_supported,
+            ModifiedTsUsageMode::TrainingSetMessages => self.modified_ts_usage_mode_1_supported,
+            ModifiedTsUsageMode::AlternateProtocols => self.modified_ts_usage_mode_2_supported,
+            ModifiedTsUsageMode::Reserved(_) => false,
+        }
+    }
+}
+
+/// 32.0 GT/s Control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalLayer32GTpsControl {
+    /// Equalization bypass to highest rate Disable
+    pub equalization_bypass_to_highest_rate_disable: bool,
+    /// No Equalization Needed Disable
+    pub no_equalization_needed_disable: bool,
+    /// Modified TS Usage Mode Selected
+    pub modified_ts_usage_mode_selected: ModifiedTsUsageMode,
+}
+
+register!(PhysicalLayer32GTpsControl: u32 {
+    equalization_bypass_to_highest_rate_disable[0],
+    no_equalization_needed_disable[1],
+    modified_ts_usage_mode_selected[8..11],
+});
+
+/// 32.0 GT/s Status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalLayer32GTpsStatus {
+    /// Equalization 32.0 GT/s Complete
+    pub equalization_complete: bool,
+    /// Equalization 32.0 GT/s Phase 1 Successful
+    pub equalization_phase_1_successful: bool,
+    /// Equalization 32.0 GT/s Phase 2 Successful
+    pub equalization_phase_2_successful: bool,
+    /// Equalization 32.0 GT/s Phase 3 Successful
+    pub equalization_phase_3_successful: bool,
+    /// Link Equalization Request 32.0 GT/s
+    pub link_equalization_request: bool,
+    /// Modified TS Received
+    pub modified_ts_received: bool,
+    /// Received Enhanced Link Behavior Control
+    pub received_enhanced_link_behavior_control: u8,
+    /// Transmitter Precoding On
+    pub transmitter_precoding_on: bool,
+    /// Transmitter Precode Request
+    pub transmitter_precode_request: bool,
+    /// No Equalization Needed Received
+    pub no_equalization_needed_received: bool,
+}
+
+register!(PhysicalLayer32GTpsStatus: u32 {
+    equalization_complete[0],
+    equalization_phase_1_successful[1],
+    equalization_phase_2_successful[2],
+    equalization_phase_3_successful[3],
+    link_equalization_request[4],
+    modified_ts_received[5],
+    received_enhanced_link_behavior_control[6..8],
+    transmitter_precoding_on[8],
+    transmitter_precode_request[9],
+    no_equalization_needed_received[10],
+});
+
+/// Modified TS Data 1 register, Received or Transmitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModifiedTsData1 {
+    /// Modified TS Usage Mode
+    pub usage_mode: ModifiedTsUsageMode,
+    /// Modified TS Information 1
+    pub information_1: u16,
+    /// Modified TS Vendor ID
+    pub vendor_id: u16,
+}
+
+register!(ModifiedTsData1: u32 {
+    usage_mode[0..3],
+    information_1[3..16],
+    vendor_id[16..32],
+});
+
+/// Modified TS Data 2 register, Received or Transmitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModifiedTsData2 {
+    /// Modified TS Information 2
+    pub information_2: u32,
+    /// Alternate Protocol Negotiation Status
+    pub alternate_protocol_negotiation_status: u8,
+}
+
+register!(ModifiedTsData2: u32 {
+    information_2[0..24],
+    alternate_protocol_negotiation_status[24..26],
+});
+
+/// Modified TS Usage Mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifiedTsUsageMode {
+    /// PCI Express
+    PciExpress,
+    /// Training Set Messages
+    TrainingSetMessages,
+    /// Alternate Protocols
+    AlternateProtocols,
+    Reserved(u8),
+}
+
+impl From<u8> for ModifiedTsUsageMode {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0b000 => Self::PciExpress,
+            0b001 => Self::TrainingSetMessages,
+            0b010 => Self::AlternateProtocols,
+            v => Self::Reserved(v),
+        }
+    }
+}
+
+impl From<ModifiedTsUsageMode> for u8 {
+    fn from(mode: ModifiedTsUsageMode) -> Self {
+        match mode {
+            ModifiedTsUsageMode::PciExpress => 0b000,
+            ModifiedTsUsageMode::TrainingSetMessages => 0b001,
+            ModifiedTsUsageMode::AlternateProtocols => 0b010,
+            ModifiedTsUsageMode::Reserved(v) => v,
+        }
+    }
+}
+
+impl_u8_field!(ModifiedTsUsageMode);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::pci_express::LinkWidth;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const DATA: [u8; 0x24] = [
+        0x02, 0x03, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x3f, 0x07, 0x00, 0x00, 0x0a, 0x80, 0x86,
+        0x80, 0x34, 0x12, 0x00, 0x02, 0x02, 0x00, 0x86, 0x80, 0x00, 0x00, 0x00, 0x01, 0x10, 0x32,
+        0x54, 0x76, 0x98, 0xba, 0xdc, 0xfe,
+    ];
+
+    #[test]
+    fn parse() {
+        let result: PhysicalLayer32GTps = DATA.as_slice().try_into().unwrap();
+        let sample = PhysicalLayer32GTps {
+            capabilities: PhysicalLayer32GTpsCapabilities {
+                equalization_bypass_to_highest_rate_supported: false,
+                no_equalization_needed_supported: true,
+                modified_ts_usage_mode_0_supported: true,
+                modified_ts_usage_mode_1_supported: true,
+                modified_ts_usage_mode_2_supported: false,
+            },
+            control: PhysicalLayer32GTpsControl {
+                equalization_bypass_to_highest_rate_disable: true,
+                no_equalization_needed_disable: false,
+                modified_ts_usage_mode_selected: ModifiedTsUsageMode::AlternateProtocols,
+            },
+            status: PhysicalLayer32GTpsStatus {
+                equalization_complete: true,
+                equalization_phase_1_successful: true,
+                equalization_phase_2_successful: true,
+                equalization_phase_3_successful: true,
+                link_equalization_request: true,
+                modified_ts_received: true,
+                received_enhanced_link_behavior_control: 0,
+                transmitter_precoding_on: true,
+                transmitter_precode_request: true,
+                no_equalization_needed_received: true,
+            },
+            received_modified_ts_data_1: ModifiedTsData1 {
+                usage_mode: ModifiedTsUsageMode::AlternateProtocols,
+                information_1: 0x1001,
+                vendor_id: 0x8086,
+            },
+            received_modified_ts_data_2: ModifiedTsData2 {
+                information_2: 0x1234,
+                alternate_protocol_negotiation_status: 0b10,
+            },
+            transmitted_modified_ts_data_1: ModifiedTsData1 {
+                usage_mode: ModifiedTsUsageMode::AlternateProtocols,
+                information_1: 0,
+                vendor_id: 0x8086,
+            },
+            transmitted_modified_ts_data_2: ModifiedTsData2 {
+                information_2: 0,
+                alternate_protocol_negotiation_status: 0b01,
+            },
+            lane_equalization_control_data: &DATA[0x1c..],
+        };
+        assert_eq!(sample, result);
+        assert!(result.equalization_status().is_successful());
+        assert!(result
+            .capabilities
+            .is_usage_mode_supported(ModifiedTsUsageMode::TrainingSetMessages));
+        assert!(!result
+            .capabilities
+            .is_usage_mode_supported(ModifiedTsUsageMode::Reserved(0b111)));
+
+        let presets = result
+            .equalization_control_lanes(LinkWidth::X16)
+            .map(|(lane, control)| (lane, u8::from(control)))
+            .collect::<Vec<_>>();
+        assert_eq!(8, presets.len());
+        assert_eq!([(0, 0x10), (1, 0x32)], presets[..2]);
+        assert_eq!((7, 0xfe), presets[7]);
+    }
+
+    #[test]
+    fn modified_ts_data_round_trip() {
+        for dword in [0x8086_1002, 0xffff_fffd, 0x0000_0007] {
+            assert_eq!(dword, u32::from(ModifiedTsData1::from(dword)));
+        }
+        assert_eq!(0x03ff_ffff, u32::from(ModifiedTsData2::from(0xffff_ffff)));
+    }
+
+    #[test]
+    fn short_data() {
+        let result = PhysicalLayer32GTps::try_from(&DATA[..PhysicalLayer32GTps::MIN_SIZE - 1]);
+        let sample = Err(ExtendedCapabilityDataError {
+            name: "Physical Layer 32.0 GT/s",
+            size: PhysicalLayer32GTps::MIN_SIZE,
+        });
+        assert_eq!(sample, result);
+        let result = PhysicalLayer32GTps::try_from(&DATA[..PhysicalLayer32GTps::MIN_SIZE]).unwrap();
+        assert_eq!(0, result.equalization_control_lanes(LinkWidth::X4).count());
+    }
+}
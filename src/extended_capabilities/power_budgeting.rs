@@ -53,6 +53,10 @@ pub struct PowerBudgeting {
     pub data: Data,
     pub power_budget_capability: PowerBudgetCapability,
 }
+impl PowerBudgeting {
+    pub const SIZE: usize = 1 + 3 + 4 + 1 + 3;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for PowerBudgeting {
     type Error = ExtendedCapabilityDataError;
 
@@ -64,7 +68,7 @@ impl TryFrom<&[u8]> for PowerBudgeting {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Power Budgeting",
-                size: 12,
+                size: Self::MIN_SIZE,
             })?;
         let _: ([u8; 3], [u8; 3]) = (rsvdp_0, rsvdp_1);
         let Lsb((
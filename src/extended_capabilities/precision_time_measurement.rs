@@ -52,6 +52,10 @@ pub struct PrecisionTimeMeasurement {
     /// PTM Control
     pub ptm_control: PtmControl,
 }
+impl PrecisionTimeMeasurement {
+    pub const SIZE: usize = 4 + 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for PrecisionTimeMeasurement {
     type Error = ExtendedCapabilityDataError;
 
@@ -63,7 +67,7 @@ impl TryFrom<&[u8]> for PrecisionTimeMeasurement {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Precision Time Measurement",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             ptm_capability: From::<u32>::from(ptm_capability),
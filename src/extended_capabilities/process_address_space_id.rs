@@ -42,6 +42,10 @@ pub struct ProcessAddressSpaceId {
     pub pacid_capability: PacidCapability,
     pub pacid_control: PacidControl,
 }
+impl ProcessAddressSpaceId {
+    pub const SIZE: usize = 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+}
 impl TryFrom<&[u8]> for ProcessAddressSpaceId {
     type Error = ExtendedCapabilityDataError;
 
@@ -53,7 +57,7 @@ impl TryFrom<&[u8]> for ProcessAddressSpaceId {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Process Address Space ID",
-                size: 4,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             pacid_capability: From::<u16>::from(pacid_capability),
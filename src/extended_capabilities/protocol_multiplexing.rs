@@ -80,6 +80,11 @@ let sample = ProtocolMultiplexing {
 };
 
 assert_eq!(sample, result);
+
+// Entry truncated to 2 bytes
+let mut array = PmuxProtocolArray::new(&[0x12, 0x34, 0x56, 0x78, 0x11, 0x22]);
+assert!(array.next().unwrap().is_ok());
+assert_eq!(Some(Err(PmuxProtocolArrayEntryError { found: 2 })), array.next());
 ```
 */
 
@@ -91,14 +96,27 @@ use snafu::Snafu;
 use super::ExtendedCapabilityHeader;
 
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ProtocolMultiplexingError {
-    #[snafu(display("capability, control and status fields are unreadable"))]
+    #[snafu(display(
+        "capability, control and status fields ({} bytes) are unreadable",
+        ProtocolMultiplexing::MIN_SIZE
+    ))]
     Mandatory,
     #[snafu(display(
         "ureadable bytes for PMUX Protocol Array (expected: {expected}, found: {found})"
     ))]
     PmuxProtocolArray { expected: usize, found: usize },
 }
+impl ProtocolMultiplexingError {
+    /// Bytes from the start of the Extended Capability Header needed by the failed read
+    pub fn needed(&self) -> Option<usize> {
+        Some(match self {
+            Self::Mandatory => ProtocolMultiplexing::MIN_SIZE,
+            Self::PmuxProtocolArray { expected, .. } => ProtocolMultiplexing::MIN_SIZE + expected,
+        })
+    }
+}
 
 /// Protocol Multiplexing
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -109,6 +127,11 @@ pub struct ProtocolMultiplexing<'a> {
     pub pmux_protocol_array: PmuxProtocolArray<'a>,
 }
 
+impl ProtocolMultiplexing<'_> {
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = ExtendedCapabilityHeader::SIZE + 4 + 4 + 4;
+}
+
 impl<'a> TryFrom<&'a [u8]> for ProtocolMultiplexing<'a> {
     type Error = ProtocolMultiplexingError;
 
@@ -314,21 +337,33 @@ impl<'a> PartialEq for PmuxProtocolArray<'a> {
 impl<'a> Eq for PmuxProtocolArray<'a> {}
 
 impl<'a> Iterator for PmuxProtocolArray<'a> {
-    type Item = PmuxProtocolArrayEntry;
+    type Item = Result<PmuxProtocolArrayEntry, PmuxProtocolArrayEntryError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let slice = self.0.next()?;
-        let Seq {
-            head: Le((protocol_id, authority_id)),
-            ..
-        } = P2(slice).try_into().ok()?;
-        Some(PmuxProtocolArrayEntry {
-            protocol_id,
-            authority_id,
-        })
+        let result = P2(slice)
+            .try_into()
+            .map(
+                |Seq {
+                     head: Le((protocol_id, authority_id)),
+                     ..
+                 }| PmuxProtocolArrayEntry {
+                    protocol_id,
+                    authority_id,
+                },
+            )
+            .map_err(|_| PmuxProtocolArrayEntryError { found: slice.len() });
+        Some(result)
     }
 }
 
+/// PMUX Protocol Array entry is shorter than [PmuxProtocolArrayEntry::SIZE]
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("PMUX Protocol Array entry is truncated to {found} bytes"))]
+pub struct PmuxProtocolArrayEntryError {
+    pub found: usize,
+}
+
 /// PMUX Protocol Array entry
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PmuxProtocolArrayEntry {
@@ -42,6 +42,9 @@ let sample = ReadinessTimeReporting {
 };
 
 assert_eq!(sample, result);
+
+// 0x132 x 32ns
+assert_eq!(Some(9792), result.dl_up_time.nanoseconds());
 ```
 */
 
@@ -72,6 +75,22 @@ pub struct ReadinessTimeReporting {
 impl ReadinessTimeReporting {
     /// Size in bytes (with Extended Capability Header)
     pub const SIZE: usize = 0x0c;
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// Name of the first time field with a reserved scale, only [valid](Self::valid) times
+    /// are checked
+    pub fn reserved_scale(&self) -> Option<&'static str> {
+        [
+            ("Reset Time", &self.reset_time),
+            ("DL_Up Time", &self.dl_up_time),
+            ("FLR Time", &self.flr_time),
+            ("D3hot to D0 Time", &self.d3hot_to_d0_time),
+        ]
+        .into_iter()
+        .filter(|_| self.valid)
+        .find(|(_, time)| time.nanoseconds().is_none())
+        .map(|(field, _)| field)
+    }
 }
 
 impl TryFrom<&[u8]> for ReadinessTimeReporting {
@@ -88,7 +107,7 @@ impl TryFrom<&[u8]> for ReadinessTimeReporting {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Readiness Time Reporting",
-                size: Self::SIZE,
+                size: Self::MIN_SIZE,
             })?;
         let Lsb((reset_time, dl_up_time, (), valid)) = P4::<u32, 12, 12, 7, 1>(r1).into();
         let Lsb((flr_time, d3hot_to_d0_time, ())) = P3::<u32, 12, 12, 8>(r2).into();
@@ -114,6 +133,10 @@ impl ReadinessTime {
     pub fn actual_time_value(&self) -> u64 {
         (self.value as u64) << (self.scale * 5)
     }
+    /// Time in nanoseconds, `None` for reserved [scale](Self::scale) encodings 110b and 111b
+    pub fn nanoseconds(&self) -> Option<u64> {
+        (self.scale <= 0b101).then(|| self.actual_time_value())
+    }
 }
 
 impl From<u16> for ReadinessTime {
@@ -122,3 +145,39 @@ impl From<u16> for ReadinessTime {
         Self { value, scale }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn nanoseconds() {
+        let data = [
+            0x22, 0x00, 0x01, 0x00, // Capability header
+            0xff, 0x05, 0x00, 0x80, // Reset Time 511 x 1024ns, DL_Up Time 0ns, Valid
+            0x01, 0xfa, 0x00, 0x00, // FLR Time 33554432ns, D3hot to D0 Time 15 x 1ns
+        ];
+        let result: ReadinessTimeReporting = data.as_slice().try_into().unwrap();
+        let times = [
+            &result.reset_time,
+            &result.dl_up_time,
+            &result.flr_time,
+            &result.d3hot_to_d0_time,
+        ]
+        .map(ReadinessTime::nanoseconds);
+        assert_eq!([Some(511 << 10), Some(0), Some(1 << 25), Some(15)], times);
+        assert_eq!(None, result.reserved_scale());
+
+        // Reserved DL_Up Time scale
+        let mut data = data;
+        data[6] = 0xc0;
+        let result: ReadinessTimeReporting = data.as_slice().try_into().unwrap();
+        assert_eq!(None, result.dl_up_time.nanoseconds());
+        assert_eq!(Some("DL_Up Time"), result.reserved_scale());
+        // Times are not valid
+        data[7] = 0x00;
+        let result: ReadinessTimeReporting = data.as_slice().try_into().unwrap();
+        assert_eq!(None, result.reserved_scale());
+    }
+}
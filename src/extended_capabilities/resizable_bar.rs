@@ -13,6 +13,12 @@ size back to the hardware.
    └─ <a href="struct.ResizableBarControl.html">ResizableBarControl</a>
 </pre>
 
+[ResizableBar::resize] composes the Resizable BAR Control register value selecting a new BAR
+size, checked against sizes advertised by the Function.
+
+[ResizableBar::views] combines each entry with the Base Address Register of the header it
+refers to and reports entries referring to a missing, I/O Space or upper half of a 64-bit BAR.
+
 ## Examples
 ```rust
 # use pcics::extended_capabilities::resizable_bar::*;
@@ -25,7 +31,7 @@ let data = [
 ];
 
 let mut rebar: ResizableBar = data.as_slice().try_into().unwrap();
-let result = rebar.clone().collect::<Vec<_>>();
+let result = rebar.clone().collect::<Result<Vec<_>, _>>().unwrap();
 
 let sample = vec![
     ResizableBarEntry {
@@ -55,7 +61,7 @@ let sample = vec![
 assert_eq!(sample, result);
 
 
-let second_entry = rebar.nth(1).unwrap();
+let second_entry = rebar.nth(1).unwrap().unwrap();
 let supported_sizes = ResizableBarEntry::BAR_SIZES
     .iter()
     .enumerate()
@@ -76,20 +82,55 @@ use core::slice;
 use heterob::{
     bit_numbering::Lsb,
     endianness::{Le, LeBytesTryInto},
-    Seq, P2, P3, P6,
+    Seq, P2, P3,
 };
-use snafu::Snafu;
+use snafu::prelude::*;
 
-use super::ExtendedCapabilityHeader;
+use super::{ExtendedCapabilityHeader, SingleRootIoVirtualization};
+use crate::{
+    header::{BaseAddress, BaseAddressType, BaseAddresses, Bridge, Header, HeaderType, Normal},
+    register::register,
+};
 
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ResizableBarError {
-    #[snafu(display("should have at least one entry"))]
+    #[snafu(display("should have at least one entry ({} bytes)", ResizableBar::MIN_SIZE))]
     FirstEntry,
     #[snafu(display("should have 1..=6 number of entries"))]
     NumberOfResizableBars { value: usize },
-    #[snafu(display("entries data too short"))]
-    ShortData,
+    #[snafu(display("entries data too short (expected: {expected}, found: {found})"))]
+    ShortData { expected: usize, found: usize },
+}
+impl ResizableBarError {
+    /// Bytes from the start of the Extended Capability Header needed by the failed read,
+    /// `None` for invalid Number of Resizable BARs
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::FirstEntry => Some(ResizableBar::MIN_SIZE),
+            Self::NumberOfResizableBars { .. } => None,
+            Self::ShortData { expected, .. } => Some(*expected),
+        }
+    }
+}
+
+/// Resizable BAR Control register value can't be composed
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResizableBarResizeError {
+    #[snafu(display("BAR {bar_index} has no Resizable BAR entry"))]
+    NotResizable { bar_index: u8 },
+    #[snafu(display("BAR size {size:#x} is not advertised by the Function"))]
+    UnsupportedSize { size: u64 },
+    #[snafu(display("Resizable BAR entries are unreadable"))]
+    Entry { source: ResizableBarEntryError },
+}
+
+/// Resizable BAR entry is shorter than [ResizableBar::ENTRY_SIZE]
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("Resizable BAR entry is truncated to {found} bytes"))]
+pub struct ResizableBarEntryError {
+    pub found: usize,
 }
 
 /// An iterator through [Resible BAR Entries](ResizableBarEntry)
@@ -99,6 +140,99 @@ pub struct ResizableBar<'a>(pub slice::Chunks<'a, u8>);
 impl<'a> ResizableBar<'a> {
     /// Entry size = Resizable Bar Capability size + Resizable Bar Control size
     pub const ENTRY_SIZE: usize = 4 + 4;
+    /// Min size in bytes (with Extended Capability Header and first entry)
+    pub const MIN_SIZE: usize = ExtendedCapabilityHeader::SIZE + Self::ENTRY_SIZE;
+    /// Resizable BAR Control register offset from the Extended Capability Header and its value
+    /// selecting `size` bytes for BAR `bar_index`
+    ///
+    /// Only the Control register of the entry with matching BAR Index is returned, registers of
+    /// other BARs are left as is. The offset is counted from the entry returned first, so the
+    /// iterator should not be advanced.
+    pub fn resize(
+        &self,
+        bar_index: u8,
+        size: u64,
+    ) -> Result<(usize, u32), ResizableBarResizeError> {
+        let (n, entry) = self
+            .clone()
+            .enumerate()
+            .find_map(|(n, entry)| match entry {
+                Ok(entry) if entry.control.bar_index != bar_index => None,
+                entry => Some(entry.map(|entry| (n, entry))),
+            })
+            .context(NotResizableSnafu { bar_index })?
+            .context(EntrySnafu)?;
+        let offset = ExtendedCapabilityHeader::SIZE + n * Self::ENTRY_SIZE + 4;
+        Ok((offset, entry.resize(size)?))
+    }
+    /// Bitmap of BAR Indices having a readable entry, bit `n` is set for BAR `n`
+    pub fn bar_indices(&self) -> u8 {
+        self.clone()
+            .flatten()
+            .fold(0, |bitmap, entry| bitmap | 1 << entry.control.bar_index)
+    }
+    /// Entries combined with the Base Address Registers of the `header`
+    ///
+    /// `sizes` holds BAR sizes found by sizing the header BARs, indexed by BAR, zero if the
+    /// size is unknown.
+    pub fn views<'s>(&self, header: &Header, sizes: &'s [u64]) -> ResizableBarViews<'a, 's> {
+        let (bars, count) = match &header.header_type {
+            HeaderType::Normal(Normal { base_addresses, .. }) => (base_addresses.orig(), 6),
+            HeaderType::Bridge(Bridge { base_addresses, .. }) => {
+                let [bar0, bar1] = base_addresses.orig();
+                ([bar0, bar1, 0, 0, 0, 0], 2)
+            }
+            _ => ([0; 6], 0),
+        };
+        self.views_of(bars, count, sizes)
+    }
+    /// Entries of a [VF Resizable BAR](super::VfResizableBar) combined with the VF Base
+    /// Address Registers of the SR-IOV capability
+    ///
+    /// `sizes` holds per-VF BAR sizes found by sizing the VF BARs, indexed by VF BAR, zero if
+    /// the size is unknown.
+    pub fn vf_views<'s>(
+        &self,
+        sriov: &SingleRootIoVirtualization,
+        sizes: &'s [u64],
+    ) -> ResizableBarViews<'a, 's> {
+        self.views_of(sriov.base_addresses.orig(), 6, sizes)
+    }
+    fn views_of<'s>(
+        &self,
+        bars: [u32; 6],
+        count: usize,
+        sizes: &'s [u64],
+    ) -> ResizableBarViews<'a, 's> {
+        ResizableBarViews {
+            entries: self.clone(),
+            bars,
+            count,
+            sizes,
+            seen: 0,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> ResizableBar<'a> {
+    /// Copies data of remaining entries
+    pub fn into_owned(self) -> OwnedResizableBar {
+        OwnedResizableBar(self.0.flatten().copied().collect())
+    }
+}
+
+/// [ResizableBar] owning entries data
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedResizableBar(alloc::vec::Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl OwnedResizableBar {
+    /// An iterator through owned entries
+    pub fn as_borrowed(&self) -> ResizableBar<'_> {
+        ResizableBar(self.0.chunks(ResizableBar::ENTRY_SIZE))
+    }
 }
 
 impl<'a> PartialEq for ResizableBar<'a> {
@@ -125,7 +259,10 @@ impl<'a> TryFrom<&'a [u8]> for ResizableBar<'a> {
             let end = start + num_bars * ResizableBar::ENTRY_SIZE;
             let chunks = slice
                 .get(start..end)
-                .ok_or(ResizableBarError::ShortData)?
+                .ok_or(ResizableBarError::ShortData {
+                    expected: end,
+                    found: slice.len(),
+                })?
                 .chunks(ResizableBar::ENTRY_SIZE);
             Ok(ResizableBar(chunks))
         } else {
@@ -135,18 +272,131 @@ impl<'a> TryFrom<&'a [u8]> for ResizableBar<'a> {
 }
 
 impl<'a> Iterator for ResizableBar<'a> {
-    type Item = ResizableBarEntry;
+    type Item = Result<ResizableBarEntry, ResizableBarEntryError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let chunk = self.0.next()?;
-        let Seq {
-            head: Le((cap, ctrl)),
-            ..
-        } = P2(chunk).try_into().ok()?;
-        Some(ResizableBarEntry {
-            capability: From::<u32>::from(cap),
-            control: From::<u32>::from(ctrl),
-        })
+        let result = P2(chunk)
+            .try_into()
+            .map(
+                |Seq {
+                     head: Le((cap, ctrl)),
+                     ..
+                 }| ResizableBarEntry {
+                    capability: From::<u32>::from(cap),
+                    control: From::<u32>::from(ctrl),
+                },
+            )
+            .map_err(|_| ResizableBarEntryError { found: chunk.len() });
+        Some(result)
+    }
+}
+
+/// BAR Index of a Resizable BAR entry does not match the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizableBarConflict {
+    /// Header has no BAR with the index, e.g. BAR 2 of a Type 1 header
+    NoSuchBar,
+    /// Entry with the same BAR Index precedes
+    Duplicate,
+    /// Base Address Register reads as zero, it is not implemented or not assigned
+    Unimplemented,
+    /// BAR is the upper half of the 64-bit BAR `lower`
+    UpperHalf { lower: u8 },
+    /// I/O Space BAR is not resizable
+    IoSpace,
+    /// Size found by sizing the header BAR differs from the BAR Size of the entry
+    SizeMismatch { sized: u64 },
+}
+
+/// Resizable BAR entry combined with the Base Address Register of the header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResizableBarView {
+    pub bar_index: u8,
+    /// Decoded Base Address Register, `None` if there is no BAR starting at the index
+    pub base_address: Option<BaseAddress>,
+    /// Current size in bytes selected by the BAR Size field, `None` for reserved encodings
+    pub size: Option<u64>,
+    /// Size in bytes found by sizing the header BAR, `None` if unknown
+    pub sized: Option<u64>,
+    /// Bit `n` is set if the Function supports the BAR sized to 2ⁿ bytes
+    pub supported_sizes: u64,
+    /// The first mismatch with the header found
+    pub conflict: Option<ResizableBarConflict>,
+}
+
+/// An iterator through [ResizableBarView]s, truncated entries are reported as errors
+#[derive(Debug, Clone)]
+pub struct ResizableBarViews<'a, 's> {
+    entries: ResizableBar<'a>,
+    bars: [u32; 6],
+    count: usize,
+    sizes: &'s [u64],
+    seen: u8,
+}
+
+impl<'a, 's> ResizableBarViews<'a, 's> {
+    /// BAR starting at `index` or the reason there is no such memory BAR
+    fn base_address(&self, index: u8) -> Result<BaseAddress, ResizableBarConflict> {
+        let index = index as usize;
+        if index >= self.count {
+            return Err(ResizableBarConflict::NoSuchBar);
+        }
+        let mut bars = BaseAddresses::new(self.bars);
+        let bar = bars.find(|ba| match ba.base_address_type {
+            BaseAddressType::MemorySpace64 { .. } => ba.region + 1 >= index,
+            _ => ba.region >= index,
+        });
+        match bar {
+            Some(ba) if ba.region + 1 == index => Err(ResizableBarConflict::UpperHalf {
+                lower: ba.region as u8,
+            }),
+            Some(ba) if ba.region == index => match ba.base_address_type {
+                BaseAddressType::IoSpace { .. } => Err(ResizableBarConflict::IoSpace),
+                _ => Ok(ba),
+            },
+            _ => Err(ResizableBarConflict::Unimplemented),
+        }
+    }
+}
+
+impl<'a, 's> Iterator for ResizableBarViews<'a, 's> {
+    type Item = Result<ResizableBarView, ResizableBarEntryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = match self.entries.next()? {
+            Ok(entry) => entry,
+            Err(error) => return Some(Err(error)),
+        };
+        let bar_index = entry.control.bar_index;
+        let duplicate = self.seen & 1 << bar_index != 0;
+        self.seen |= 1 << bar_index;
+        let size = 1u64.checked_shl(entry.control.bar_size as u32 + 20);
+        let sized = self
+            .sizes
+            .get(bar_index as usize)
+            .copied()
+            .filter(|&size| size != 0);
+        let supported_sizes = (20..64)
+            .filter(|&power| entry.is_function_supports_power_of_two(power))
+            .fold(0, |bitmap, power| bitmap | 1 << power);
+        let base_address = self.base_address(bar_index);
+        let conflict = match base_address {
+            Err(ResizableBarConflict::NoSuchBar) => Some(ResizableBarConflict::NoSuchBar),
+            _ if duplicate => Some(ResizableBarConflict::Duplicate),
+            Err(conflict) => Some(conflict),
+            Ok(_) => sized
+                .filter(|&sized| Some(sized) != size)
+                .map(|sized| ResizableBarConflict::SizeMismatch { sized }),
+        };
+        Some(Ok(ResizableBarView {
+            bar_index,
+            base_address: base_address.ok(),
+            size,
+            sized,
+            supported_sizes,
+            conflict,
+        }))
     }
 }
 
@@ -174,6 +424,22 @@ impl ResizableBarEntry {
             _ => false,
         }
     }
+    /// Resizable BAR Control register value selecting `size` bytes, other fields keep their
+    /// values
+    ///
+    /// `size` must be a power of two advertised by the Function.
+    pub fn resize(&self, size: u64) -> Result<u32, ResizableBarResizeError> {
+        let power = size.trailing_zeros() as usize;
+        ensure!(
+            size.is_power_of_two() && self.is_function_supports_power_of_two(power),
+            UnsupportedSizeSnafu { size }
+        );
+        let control = ResizableBarControl {
+            bar_size: (power - 20) as u8,
+            ..self.control.clone()
+        };
+        Ok(control.into())
+    }
 }
 
 /// Resizable BAR Capability
@@ -199,21 +465,176 @@ pub struct ResizableBarControl {
     pub support_map_from_256tb_to_8eb: u16,
 }
 
-impl From<u32> for ResizableBarControl {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            bar_index,
-            (),
-            number_of_resizable_bars,
-            bar_size,
-            (),
-            support_map_from_256tb_to_8eb,
-        )) = P6::<_, 3, 2, 3, 6, 2, 16>(dword).into();
-        Self {
-            bar_index,
-            number_of_resizable_bars,
-            bar_size,
-            support_map_from_256tb_to_8eb,
+register!(ResizableBarControl: u32 {
+    bar_index[0..3],
+    number_of_resizable_bars[5..8],
+    bar_size[8..14],
+    support_map_from_256tb_to_8eb[16..32],
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const DATA: [u8; 28] = [
+        0x15, 0x00, 0x01, 0x00, // Capability header
+        0x30, 0x00, 0x00, 0x00, // BAR 0: 1MB, 2MB
+        0x40, 0x00, 0x00, 0x00, // BAR 0: 2 entries, 1MB
+        0x00, 0x00, 0x00, 0x80, // BAR 2: 128TB
+        0x02, 0x00, 0x00, 0x80, // BAR 2: 128TB, 8EB
+        0xff, 0xff, 0xff, 0xff, // Not an entry
+        0xff, 0xff, 0xff, 0xff,
+    ];
+
+    #[test]
+    fn resize() {
+        let rebar = ResizableBar::try_from(DATA.as_slice()).unwrap();
+        assert_eq!(Ok((0x08, 0x0000_0140)), rebar.resize(0, 1 << 21));
+        assert_eq!(Ok((0x10, 0x8000_1b02)), rebar.resize(2, 1 << 47));
+        assert_eq!(Ok((0x10, 0x8000_2b02)), rebar.resize(2, 1 << 63));
+        assert_eq!(
+            Err(ResizableBarResizeError::NotResizable { bar_index: 1 }),
+            rebar.resize(1, 1 << 20)
+        );
+        assert_eq!(
+            Err(ResizableBarResizeError::UnsupportedSize { size: 1 << 22 }),
+            rebar.resize(0, 1 << 22)
+        );
+        assert_eq!(
+            Err(ResizableBarResizeError::UnsupportedSize { size: 3 << 20 }),
+            rebar.resize(0, 3 << 20)
+        );
+        assert!(rebar.resize(0, 0).is_err());
+    }
+
+    #[test]
+    fn views() {
+        let mut header = [0u8; Header::TOTAL_SIZE];
+        let bars: [u32; 6] = [0xe000_000c, 0x0000_0001, 0x0000_3001, 0, 0xd000_0000, 0];
+        for (n, bar) in bars.iter().enumerate() {
+            header[0x10 + n * 4..][..4].copy_from_slice(&bar.to_le_bytes());
+        }
+        let entries: [(u32, u32); 6] = [
+            (0x0000_1ff0, 0x0000_08c0), // BAR 0: 6 entries, 256MB of 1MB..256MB
+            (0x0000_0010, 0x0000_0001), // BAR 1: 1MB
+            (0x0000_0010, 0x0000_0002), // BAR 2: 1MB
+            (0x0000_0010, 0x0000_0003), // BAR 3: 1MB
+            (0x0000_0030, 0x0000_0004), // BAR 4: 1MB of 1MB, 2MB
+            (0x0000_0010, 0x0000_0000), // BAR 0: 1MB
+        ];
+        let mut data = [0u8; 4 + 6 * 8];
+        for (n, (cap, ctrl)) in entries.iter().enumerate() {
+            data[4 + n * 8..][..4].copy_from_slice(&cap.to_le_bytes());
+            data[8 + n * 8..][..4].copy_from_slice(&ctrl.to_le_bytes());
         }
+        let rebar = ResizableBar::try_from(data.as_slice()).unwrap();
+        assert_eq!(0b0001_1111, rebar.bar_indices());
+
+        let header = Header::from(header);
+        let result = rebar
+            .views(&header, &[1 << 28, 0, 0, 0, 2 << 20])
+            .collect::<Result<Vec<_>, _>>();
+        let view = |bar_index, size, supported_sizes, conflict| ResizableBarView {
+            bar_index,
+            base_address: None,
+            size: Some(size),
+            sized: None,
+            supported_sizes,
+            conflict,
+        };
+        let sample = vec![
+            ResizableBarView {
+                base_address: Some(BaseAddress {
+                    region: 0,
+                    base_address_type: BaseAddressType::MemorySpace64 {
+                        prefetchable: true,
+                        base_address: 0x1_e000_0000,
+                    },
+                }),
+                sized: Some(1 << 28),
+                ..view(0, 1 << 28, 0x1ff << 20, None)
+            },
+            view(
+                1,
+                1 << 20,
+                1 << 20,
+                Some(ResizableBarConflict::UpperHalf { lower: 0 }),
+            ),
+            view(2, 1 << 20, 1 << 20, Some(ResizableBarConflict::IoSpace)),
+            view(
+                3,
+                1 << 20,
+                1 << 20,
+                Some(ResizableBarConflict::Unimplemented),
+            ),
+            ResizableBarView {
+                base_address: Some(BaseAddress {
+                    region: 4,
+                    base_address_type: BaseAddressType::MemorySpace32 {
+                        prefetchable: false,
+                        base_address: 0xd000_0000,
+                    },
+                }),
+                sized: Some(2 << 20),
+                ..view(
+                    4,
+                    1 << 20,
+                    3 << 20,
+                    Some(ResizableBarConflict::SizeMismatch { sized: 2 << 20 }),
+                )
+            },
+            ResizableBarView {
+                base_address: Some(BaseAddress {
+                    region: 0,
+                    base_address_type: BaseAddressType::MemorySpace64 {
+                        prefetchable: true,
+                        base_address: 0x1_e000_0000,
+                    },
+                }),
+                sized: Some(1 << 28),
+                ..view(0, 1 << 20, 1 << 20, Some(ResizableBarConflict::Duplicate))
+            },
+        ];
+        assert_eq!(Ok(sample), result);
+
+        // Type 1 header has two BARs
+        let mut header = [0u8; Header::TOTAL_SIZE];
+        header[0x0e] = 0x01;
+        let header = Header::from(header);
+        let result = rebar
+            .views(&header, &[])
+            .map(|view| view.map(|view| view.conflict))
+            .collect::<Result<Vec<_>, _>>();
+        let sample = vec![
+            Some(ResizableBarConflict::Unimplemented),
+            Some(ResizableBarConflict::Unimplemented),
+            Some(ResizableBarConflict::NoSuchBar),
+            Some(ResizableBarConflict::NoSuchBar),
+            Some(ResizableBarConflict::NoSuchBar),
+            Some(ResizableBarConflict::Duplicate),
+        ];
+        assert_eq!(Ok(sample), result);
+    }
+
+    #[test]
+    fn truncated_entry() {
+        let mut rebar = ResizableBar(DATA[4..18].chunks(ResizableBar::ENTRY_SIZE));
+        assert!(rebar.clone().next().unwrap().is_ok());
+        assert_eq!(0b0001, rebar.bar_indices());
+        let error = ResizableBarEntryError { found: 6 };
+        assert_eq!(
+            Err(ResizableBarResizeError::Entry { source: error }),
+            rebar.resize(2, 1 << 47)
+        );
+        assert_eq!(Ok((0x08, 0x0000_0140)), rebar.resize(0, 1 << 21));
+        let header = Header::from([0u8; Header::TOTAL_SIZE]);
+        assert_eq!(Some(Err(error)), rebar.views(&header, &[]).nth(1));
+        assert_eq!(Some(Err(error)), rebar.nth(1));
+        assert_eq!(
+            "Resizable BAR entry is truncated to 6 bytes",
+            error.to_string()
+        );
     }
 }
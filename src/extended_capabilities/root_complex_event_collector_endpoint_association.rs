@@ -44,6 +44,7 @@ pub struct RootComplexEventCollectorEndpointAssociation {
 
 impl RootComplexEventCollectorEndpointAssociation {
     pub const SIZE: usize = 4;
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl From<[u8; Self::SIZE]> for RootComplexEventCollectorEndpointAssociation {
@@ -61,7 +62,7 @@ impl TryFrom<&[u8]> for RootComplexEventCollectorEndpointAssociation {
     fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
         let Seq { head, .. } = slice.try_into().map_err(|_| ExtendedCapabilityDataError {
             name: "Root Complex Event Collector Endpoint Association",
-            size: Self::SIZE,
+            size: Self::MIN_SIZE,
         })?;
         Ok(From::<[u8; Self::SIZE]>::from(head))
     }
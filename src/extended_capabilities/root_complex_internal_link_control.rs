@@ -23,6 +23,10 @@ the Element Self-Description register of the Root Complex Link Declaration Capab
    └─ <a href="../../capabilities/pci_express/enum.LinkWidth.html">LinkWidth</a>
 </pre>
 
+Registers use [pci_express](crate::capabilities::pci_express) Link field types, so ASPM of an
+internal link is checked and encoded the same way as of an external one, e.g. with
+[ActiveStatePowerManagement::supports].
+
 ## Examples
 ```rust
 # use pcics::extended_capabilities::root_complex_internal_link_control::*;
@@ -64,11 +68,14 @@ assert_eq!(sample, result);
 ```
 */
 
-use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P3, P4, P7};
+use heterob::{endianness::Le, Seq, P3};
 
-use crate::capabilities::pci_express::{
-    ActiveStatePowerManagement, L0sExitLatency, L1ExitLatency, LinkSpeed, LinkWidth,
-    SupportedLinkSpeedsVector,
+use crate::{
+    capabilities::pci_express::{
+        ActiveStatePowerManagement, L0sExitLatency, L1ExitLatency, LinkSpeed, LinkWidth,
+        SupportedLinkSpeedsVector,
+    },
+    register::register,
 };
 
 use super::ExtendedCapabilityDataError;
@@ -86,6 +93,17 @@ pub struct RootComplexInternalLinkControl {
 
 impl RootComplexInternalLinkControl {
     pub const SIZE: usize = 4 + 2 + 2;
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// ASPM Control enables only states reported in ASPM Support
+    pub fn is_aspm_control_supported(&self) -> bool {
+        self.root_complex_link_capabilities
+            .active_state_power_management_support
+            .supports(
+                &self
+                    .root_complex_link_control
+                    .active_state_power_management_control,
+            )
+    }
 }
 
 impl From<[u8; Self::SIZE]> for RootComplexInternalLinkControl {
@@ -103,13 +121,26 @@ impl From<[u8; Self::SIZE]> for RootComplexInternalLinkControl {
     }
 }
 
+impl From<RootComplexInternalLinkControl> for [u8; RootComplexInternalLinkControl::SIZE] {
+    fn from(rcilc: RootComplexInternalLinkControl) -> Self {
+        let mut bytes = [0; RootComplexInternalLinkControl::SIZE];
+        let capabilities = u32::from(rcilc.root_complex_link_capabilities);
+        bytes[..4].copy_from_slice(&capabilities.to_le_bytes());
+        let control = u16::from(rcilc.root_complex_link_control);
+        bytes[4..6].copy_from_slice(&control.to_le_bytes());
+        let status = u16::from(rcilc.root_complex_link_status);
+        bytes[6..].copy_from_slice(&status.to_le_bytes());
+        bytes
+    }
+}
+
 impl TryFrom<&[u8]> for RootComplexInternalLinkControl {
     type Error = ExtendedCapabilityDataError;
 
     fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
         let Seq { head, .. } = slice.try_into().map_err(|_| ExtendedCapabilityDataError {
             name: "Root Complex Internal Link Control",
-            size: Self::SIZE,
+            size: Self::MIN_SIZE,
         })?;
         Ok(From::<[u8; Self::SIZE]>::from(head))
     }
@@ -118,73 +149,88 @@ impl TryFrom<&[u8]> for RootComplexInternalLinkControl {
 /// Root Complex Link Capabilities
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RootComplexLinkCapabilities {
+    /// Max Link Speed
     pub max_link_speed: LinkSpeed,
+    /// Maximum Link Width
     pub maximum_link_width: LinkWidth,
+    /// ASPM Support
     pub active_state_power_management_support: ActiveStatePowerManagement,
+    /// L0s Exit Latency
     pub l0s_exit_latency: L0sExitLatency,
+    /// L1 Exit Latency
     pub l1_exit_latency: L1ExitLatency,
+    /// Supported Link Speeds Vector
     pub supported_link_speeds_vector: SupportedLinkSpeedsVector,
 }
 
-impl From<u32> for RootComplexLinkCapabilities {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            max_link_speed,
-            maximum_link_width,
-            active_state_power_management_support,
-            l0s_exit_latency,
-            l1_exit_latency,
-            supported_link_speeds_vector,
-            (),
-        )) = P7::<_, 4, 6, 2, 3, 3, 7, 7>(dword).into();
-        Self {
-            max_link_speed: From::<u8>::from(max_link_speed),
-            maximum_link_width: From::<u8>::from(maximum_link_width),
-            active_state_power_management_support: From::<u8>::from(
-                active_state_power_management_support,
-            ),
-            l0s_exit_latency: From::<u8>::from(l0s_exit_latency),
-            l1_exit_latency: From::<u8>::from(l1_exit_latency),
-            supported_link_speeds_vector: From::<u8>::from(supported_link_speeds_vector),
-        }
-    }
-}
+register!(RootComplexLinkCapabilities: u32 {
+    max_link_speed[0..4],
+    maximum_link_width[4..10],
+    active_state_power_management_support[10..12],
+    l0s_exit_latency[12..15],
+    l1_exit_latency[15..18],
+    supported_link_speeds_vector[18..25],
+});
 
 /// Root Complex Link Control
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RootComplexLinkControl {
+    /// ASPM Control
     pub active_state_power_management_control: ActiveStatePowerManagement,
     /// Forces the transmission of additional Ordered Sets when exiting the L0s
     /// state and when in the Recovery state
     pub extended_synch: bool,
 }
 
-impl From<u16> for RootComplexLinkControl {
-    fn from(word: u16) -> Self {
-        let Lsb((active_state_power_management_control, (), extended_synch, ())) =
-            P4::<_, 2, 5, 1, 8>(word).into();
-        Self {
-            active_state_power_management_control: From::<u8>::from(
-                active_state_power_management_control,
-            ),
-            extended_synch,
-        }
-    }
-}
+register!(RootComplexLinkControl: u16 {
+    active_state_power_management_control[0..2],
+    extended_synch[7],
+});
 
 /// Root Complex Link Status
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RootComplexLinkStatus {
+    /// Current Link Speed
     pub current_link_speed: LinkSpeed,
+    /// Negotiated Link Width
     pub negotiated_link_width: LinkWidth,
 }
 
-impl From<u16> for RootComplexLinkStatus {
-    fn from(word: u16) -> Self {
-        let Lsb((current_link_speed, negotiated_link_width, ())) = P3::<_, 4, 6, 6>(word).into();
-        Self {
-            current_link_speed: From::<u8>::from(current_link_speed),
-            negotiated_link_width: From::<u8>::from(negotiated_link_width),
-        }
+register!(RootComplexLinkStatus: u16 {
+    current_link_speed[0..4],
+    negotiated_link_width[4..10],
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn registers() {
+        let caps = RootComplexLinkCapabilities::from(0xffff_ffff);
+        assert_eq!(0x01ff_ffff, u32::from(caps));
+        let control = RootComplexLinkControl::from(0xffff);
+        assert_eq!(0x0083, u16::from(control));
+        let status = RootComplexLinkStatus::from(0x0042);
+        assert_eq!(LinkSpeed::Rate5GTps, status.current_link_speed);
+        assert_eq!(LinkWidth::X4, status.negotiated_link_width);
+        assert_eq!(0x0042, u16::from(status));
+    }
+
+    #[test]
+    fn aspm_control_supported() {
+        // ASPM L1 Support, ASPM L0s and L1 Control
+        let data = [0x00, 0x08, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00];
+        let mut rcilc = RootComplexInternalLinkControl::from(data);
+        assert!(!rcilc.is_aspm_control_supported());
+        rcilc
+            .root_complex_link_control
+            .active_state_power_management_control = ActiveStatePowerManagement::L1;
+        assert!(rcilc.is_aspm_control_supported());
+        assert_eq!(
+            [0x00, 0x08, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00],
+            <[u8; RootComplexInternalLinkControl::SIZE]>::from(rcilc)
+        );
     }
 }
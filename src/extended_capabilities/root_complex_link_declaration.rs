@@ -42,7 +42,7 @@ let rcld_sample = RootComplexLinkDeclaration {
     link_entries: LinkEntries::new(&data[0x10..0x20], 1),
 };
 
-let le_result = rcld_result.link_entries.next().unwrap();
+let le_result = rcld_result.link_entries.next().unwrap().unwrap();
 let le_sample = LinkEntry {
     link_description: LinkDescription {
         target_port_number: 0,
@@ -54,6 +54,7 @@ let le_sample = LinkEntry {
     link_address: LinkAddress::MemoryMappedSpace(0xfed19000),
 };
 assert_eq!(le_sample, le_result);
+assert_eq!(Ok(0xfed19000), le_result.link_address.base_address());
 ```
 */
 
@@ -93,13 +94,12 @@ impl TryFrom<&[u8]> for DeviceSerialNumber {
     }
 }
 
-use core::slice::Chunks;
-
-use heterob::{bit_numbering::Lsb, endianness::FromLeBytes, P4, P5, P6};
+use heterob::{bit_numbering::Lsb, P4, P5, P6};
 use snafu::prelude::*;
 
 /// Root Complex Link Declaration Error
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RootComplexLinkDeclarationError {
     #[snafu(display("can't read Element Self Description (4 bytes) from slice"))]
     ElementSelfDescription,
@@ -107,15 +107,37 @@ pub enum RootComplexLinkDeclarationError {
     NumberOfLinkEntries {
         element_self_description: ElementSelfDescription,
     },
-    #[snafu(display("reserved space should be readable"))]
+    #[snafu(display("reserved space (8 bytes) should be readable"))]
     ReservedSpace {
         element_self_description: ElementSelfDescription,
     },
-    #[snafu(display("there must be at least one LinkEntry"))]
+    #[snafu(display(
+        "there must be at least one LinkEntry ({} bytes)",
+        RootComplexLinkDeclaration::MIN_SIZE
+    ))]
     LinkEntry1 {
         element_self_description: ElementSelfDescription,
     },
 }
+impl RootComplexLinkDeclarationError {
+    /// Bytes after the Extended Capability Header needed by the failed read, `None` for
+    /// invalid Number of Link Entries
+    pub fn needed(&self) -> Option<usize> {
+        match self {
+            Self::ElementSelfDescription => Some(4),
+            Self::NumberOfLinkEntries { .. } => None,
+            Self::ReservedSpace { .. } => Some(LinkEntries::FIRST_ENTRY_OFFSET),
+            Self::LinkEntry1 { .. } => Some(RootComplexLinkDeclaration::MIN_SIZE),
+        }
+    }
+}
+
+/// Memory-Mapped Space link address is not aligned to [LinkAddress::ALIGNMENT]
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[snafu(display("link address {address:#x} is not 4 KiB aligned"))]
+pub struct MisalignedAddress {
+    pub address: u64,
+}
 
 /// Root Complex Link Declaration
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -123,6 +145,10 @@ pub struct RootComplexLinkDeclaration<'a> {
     pub element_self_description: ElementSelfDescription,
     pub link_entries: LinkEntries<'a>,
 }
+impl RootComplexLinkDeclaration<'_> {
+    /// Element Self Description, reserved space and first Link Entry
+    pub const MIN_SIZE: usize = LinkEntries::FIRST_ENTRY_OFFSET + LinkEntry::SIZE;
+}
 impl<'a> TryFrom<&'a [u8]> for RootComplexLinkDeclaration<'a> {
     type Error = RootComplexLinkDeclarationError;
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
@@ -196,11 +222,6 @@ impl From<u32> for ElementSelfDescription {
         }
     }
 }
-impl FromLeBytes<4> for ElementSelfDescription {
-    fn from_le_bytes(bytes: [u8; 4]) -> Self {
-        u32::from_le_bytes(bytes).into()
-    }
-}
 
 /// Indicates the type of the Root Complex Element
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -235,10 +256,15 @@ impl From<ElementType> for u8 {
     }
 }
 
-/// Link Entries
+/// An iterator through [Link Entries](LinkEntry)
+///
+/// Entries missing from the data are reported by [LinkEntryError], the iteration stops after
+/// it.
 #[derive(Debug, Clone)]
 pub struct LinkEntries<'a> {
-    chunks: Chunks<'a, u8>,
+    data: &'a [u8],
+    count: u8,
+    offset: usize,
     pub state: LinkEntriesState,
 }
 impl<'a> LinkEntries<'a> {
@@ -246,10 +272,12 @@ impl<'a> LinkEntries<'a> {
     pub fn new(slice: &'a [u8], number_of_link_entries: u8) -> Self {
         let length = (number_of_link_entries as usize) * LinkEntry::SIZE;
         Self {
-            chunks: slice[..slice.len().min(length)].chunks(LinkEntry::SIZE),
+            data: slice,
+            count: number_of_link_entries,
+            offset: 0,
             state: if slice.len() >= length {
                 LinkEntriesState::Valid
-            } else if slice.len() % LinkEntry::SIZE == 0 {
+            } else if slice.len().is_multiple_of(LinkEntry::SIZE) {
                 LinkEntriesState::Incomplete
             } else {
                 LinkEntriesState::Invalid
@@ -265,24 +293,50 @@ impl<'a> PartialEq for LinkEntries<'a> {
 }
 impl<'a> Eq for LinkEntries<'a> {}
 impl<'a> Iterator for LinkEntries<'a> {
-    type Item = LinkEntry;
+    type Item = Result<LinkEntry, LinkEntryError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let slice = self.chunks.next()?;
-        let Seq {
-            head: Le((link_description, _reserved, addr_low, addr_high)),
-            ..
-        } = P4(slice).try_into().ok()?;
-        let link_description @ LinkDescription { link_type, .. } =
-            From::<u32>::from(link_description);
-        let _: [u8; 4] = _reserved;
-        Some(LinkEntry {
-            link_description,
-            link_address: LinkAddress::new(link_type, addr_low, addr_high),
-        })
+        if self.count == 0 {
+            return None;
+        }
+        let slice = self.data.get(self.offset..).unwrap_or_default();
+        let result = P4(slice)
+            .try_into()
+            .map(
+                |Seq {
+                     head: Le((link_description, _reserved, addr_low, addr_high)),
+                     ..
+                 }| {
+                    let link_description @ LinkDescription { link_type, .. } =
+                        From::<u32>::from(link_description);
+                    let _: [u8; 4] = _reserved;
+                    LinkEntry {
+                        link_description,
+                        link_address: LinkAddress::new(link_type, addr_low, addr_high),
+                    }
+                },
+            )
+            .map_err(|_| LinkEntryError {
+                number: self.count,
+                offset: self.offset,
+            });
+        // Entries after the truncated one are not readable either
+        self.count = if result.is_ok() { self.count - 1 } else { 0 };
+        self.offset += LinkEntry::SIZE;
+        Some(result)
     }
 }
 
+/// Link Entry is beyond the end of data
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[snafu(display("Link Entry at {offset:#x} is unreadable ({number} entries remaining)"))]
+pub struct LinkEntryError {
+    /// Number of entries not read, including the failed one
+    pub number: u8,
+    /// Offset from the first Link Entry
+    pub offset: usize,
+}
+
 /// Link Entries State depends on bytes number
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LinkEntriesState {
@@ -340,11 +394,6 @@ impl From<u32> for LinkDescription {
         }
     }
 }
-impl FromLeBytes<4> for LinkDescription {
-    fn from_le_bytes(bytes: [u8; 4]) -> Self {
-        u32::from_le_bytes(bytes).into()
-    }
-}
 
 /// Identifies the target element for the Link entry
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -369,6 +418,23 @@ pub enum LinkAddress {
 }
 impl LinkAddress {
     const DWORDS: usize = 2;
+    /// RCRB and configuration space base addresses are 4 KiB aligned
+    pub const ALIGNMENT: u64 = 1 << 12;
+    /// RCRB base address or PCI Express Configuration Space base address
+    ///
+    /// Bits 11:0 of the Memory-Mapped Space address are reserved, they must be zero for the
+    /// address to be 4 KiB aligned. Configuration Space address is always aligned.
+    pub fn base_address(&self) -> Result<u64, MisalignedAddress> {
+        let address = match *self {
+            Self::MemoryMappedSpace(address) => address,
+            Self::ConfigurationSpace { address, .. } => address,
+        };
+        ensure!(
+            address % Self::ALIGNMENT == 0,
+            MisalignedAddressSnafu { address }
+        );
+        Ok(address)
+    }
 }
 impl LinkAddress {
     #[must_use]
@@ -422,6 +488,7 @@ impl From<LinkAddress> for [u32; LinkAddress::DWORDS] {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -448,6 +515,24 @@ mod tests {
         assert_eq!(sample, result);
     }
 
+    #[test]
+    fn link_address_base_address() {
+        let result = LinkAddress::new(0, 0xfed19000, 0x1).base_address();
+        assert_eq!(Ok(0x1_fed19000), result);
+        let result = LinkAddress::new(0, 0xfed19004, 0).base_address();
+        let sample = MisalignedAddress {
+            address: 0xfed19004,
+        };
+        assert_eq!(Err(sample), result);
+        assert_eq!(
+            "link address 0xfed19004 is not 4 KiB aligned",
+            sample.to_string()
+        );
+        // Bits below Bus Number are not the part of the address
+        let result = LinkAddress::new(1, 0xe0000fff, 0).base_address();
+        assert_eq!(Ok(0xe0000000), result);
+    }
+
     #[test]
     fn link_address_configuration_space() {
         //           address    bus  dev   fn  reserved  N
@@ -496,7 +581,7 @@ mod tests {
 
         let result = le.collect::<Vec<_>>();
 
-        let sample = vec![LinkEntry {
+        let sample = vec![Ok(LinkEntry {
             link_description: LinkDescription {
                 link_valid: true,
                 link_type: 0,
@@ -505,10 +590,28 @@ mod tests {
                 target_port_number: 0x00,
             },
             link_address: LinkAddress::MemoryMappedSpace(0x00000000fed19000),
-        }];
+        })];
         assert_eq!(sample, result, "Entry");
     }
 
+    #[test]
+    fn truncated_link_entries() {
+        let mut data = [0u8; 0x18];
+        data[..4].copy_from_slice(&[0x01, 0x00, 0x01, 0x00]);
+        let le = LinkEntries::new(data.as_slice(), 3);
+        assert_eq!(LinkEntriesState::Invalid, le.state, "State");
+        let result = le.map(|entry| entry.map(|_| ())).collect::<Vec<_>>();
+        let error = LinkEntryError {
+            number: 2,
+            offset: 0x10,
+        };
+        assert_eq!(vec![Ok(()), Err(error.clone())], result);
+        assert_eq!(
+            "Link Entry at 0x10 is unreadable (2 entries remaining)",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn parse_full_struct() {
         // Desc:   PortNumber=02 ComponentID=01 EltType=Config
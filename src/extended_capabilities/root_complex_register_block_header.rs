@@ -66,6 +66,8 @@ pub struct RootComplexRegisterBlockHeader {
 impl RootComplexRegisterBlockHeader {
     /// Size in bytes (with Extended Capability Header)
     pub const SIZE: usize = 0x14;
+    /// Min size in bytes (with Extended Capability Header)
+    pub const MIN_SIZE: usize = Self::SIZE;
 }
 
 impl From<[u8; Self::SIZE]> for RootComplexRegisterBlockHeader {
@@ -94,7 +96,7 @@ impl TryFrom<&[u8]> for RootComplexRegisterBlockHeader {
     fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
         let Seq { head, .. } = slice.try_into().map_err(|_| ExtendedCapabilityDataError {
             name: "RCRB Header",
-            size: Self::SIZE,
+            size: Self::MIN_SIZE,
         })?;
         Ok(From::<[u8; Self::SIZE]>::from(head))
     }
@@ -13,6 +13,7 @@ RCRB where any of the following are true:
 - [LinkControl3]
   - [SupportedLinkSpeedsVector]
 - [LaneErrorStatus]
+  - [LaneErrors]
 
 ## Examples
 
@@ -50,17 +51,21 @@ sample.link_control_3 = LinkControl3 {
 sample.lane_error_status = LaneErrorStatus(0x0f);
 
 assert_eq!(sample, result);
+assert_eq!(vec![0, 1, 2, 3], result.lane_error_status.lanes().collect::<Vec<_>>());
 ```
 */
 
-use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P11, P2, P6};
+use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P2, P6};
 
 use super::ExtendedCapabilityDataError;
 
 use core::slice::Chunks;
 
-use crate::capabilities::pci_express::{
-    LinkWidth, ReceiverPresetHint, SupportedLinkSpeedsVector, TransmitterPreset,
+use crate::{
+    capabilities::pci_express::{
+        LinkWidth, ReceiverPresetHint, SupportedLinkSpeedsVector, TransmitterPreset,
+    },
+    register::register,
 };
 
 /// Lane Equalization Control offset
@@ -73,13 +78,14 @@ pub struct SecondaryPciExpress<'a> {
     pub lane_error_status: LaneErrorStatus,
 }
 impl<'a> SecondaryPciExpress<'a> {
+    pub const MIN_SIZE: usize = 4 + 4;
     pub fn equalization_control_lanes(
         &self,
         link_width: LinkWidth,
     ) -> EqualizationControlLanes<'a> {
         // One Lane Equalization Control 2 bytes width
-        let end = (u8::from(link_width) as usize) * 2;
-        EqualizationControlLanes::new(&self.ecl_data[..end])
+        let end = link_width.value() as usize * 2;
+        EqualizationControlLanes::new(self.ecl_data.get(..end).unwrap_or(self.ecl_data))
     }
 }
 impl<'a> TryFrom<&'a [u8]> for SecondaryPciExpress<'a> {
@@ -93,7 +99,7 @@ impl<'a> TryFrom<&'a [u8]> for SecondaryPciExpress<'a> {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Secondary PCI Express",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             ecl_data: tail,
@@ -115,43 +121,54 @@ pub struct LinkControl3 {
     pub lower_skp_os_generation_vector: SupportedLinkSpeedsVector,
 }
 
-impl From<u32> for LinkControl3 {
-    fn from(dword: u32) -> Self {
-        let Lsb((
-            perform_equalization,
-            link_equalization_request_interrupt_enable,
-            (),
-            speed_2_5_gtps,
-            speed_5_0_gtps,
-            speed_8_0_gtps,
-            speed_16_0_gtps,
-            speed_32_0_gtps,
-            speed_64_0_gtps,
-            reserved,
-            (),
-        )) = P11::<_, 1, 1, 7, 1, 1, 1, 1, 1, 1, 1, 16>(dword).into();
-        Self {
-            perform_equalization,
-            link_equalization_request_interrupt_enable,
-            lower_skp_os_generation_vector: SupportedLinkSpeedsVector {
-                speed_2_5_gtps,
-                speed_5_0_gtps,
-                speed_8_0_gtps,
-                speed_16_0_gtps,
-                speed_32_0_gtps,
-                speed_64_0_gtps,
-                reserved,
-            },
-        }
-    }
-}
+register!(LinkControl3: u32 {
+    perform_equalization[0],
+    link_equalization_request_interrupt_enable[1],
+    lower_skp_os_generation_vector[9..16],
+});
 
 /// The Lane Error Status register consists of a 32-bit vector, where each bit indicates if the
 /// Lane with the corresponding Lane number detected an error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LaneErrorStatus(pub u32);
 
+impl LaneErrorStatus {
+    /// An iterator through numbers of Lanes with detected errors
+    pub fn lanes(&self) -> LaneErrors {
+        LaneErrors(self.0)
+    }
+    /// Lane Error Status register value clearing errors of `lanes`
+    ///
+    /// Bits are RW1C, errors of other Lanes are not affected. Lane numbers above 31 are
+    /// ignored.
+    pub fn ack(lanes: impl IntoIterator<Item = u8>) -> u32 {
+        lanes
+            .into_iter()
+            .filter_map(|lane| 1u32.checked_shl(lane as u32))
+            .fold(0, |value, bit| value | bit)
+    }
+}
+
+/// An iterator through Lane numbers set in [LaneErrorStatus]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneErrors(u32);
+
+impl Iterator for LaneErrors {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let lane = self.0.trailing_zeros();
+        // Clear the lowest set bit
+        self.0 &= self.0 - 1;
+        Some(lane as u8)
+    }
+}
+
 /// An iterator through Lane Equalization Controls
+#[derive(Debug, Clone)]
 pub struct EqualizationControlLanes<'a> {
     chunks: Chunks<'a, u8>,
 }
@@ -176,13 +193,13 @@ impl<'a> Iterator for EqualizationControlLanes<'a> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LaneEqualizationControl {
     /// Downstream Port 8.0 GT/s Transmitter Preset
-    downstream_port_transmitter_preset: TransmitterPreset,
+    pub downstream_port_transmitter_preset: TransmitterPreset,
     /// Downstream Port 8.0 GT/s Receiver Preset Hint
-    downstream_port_receiver_preset_hint: ReceiverPresetHint,
+    pub downstream_port_receiver_preset_hint: ReceiverPresetHint,
     /// Upstream Port 8.0 GT/s Transmitter Preset
-    upstream_port_transmitter_preset: TransmitterPreset,
+    pub upstream_port_transmitter_preset: TransmitterPreset,
     /// Upstream Port 8.0 GT/s Receiver Preset Hint
-    upstream_port_receiver_preset_hint: ReceiverPresetHint,
+    pub upstream_port_receiver_preset_hint: ReceiverPresetHint,
 }
 
 impl From<u16> for LaneEqualizationControl {
@@ -247,6 +264,33 @@ mod tests {
             lane_error_status: LaneErrorStatus(0b1100_1111_1011),
         };
         assert_eq!(sample, result);
+        let lanes = sample.lane_error_status.lanes().collect::<Vec<_>>();
+        assert_eq!(vec![0, 1, 3, 4, 5, 6, 7, 10, 11], lanes);
+        assert_eq!(0b1100_1111_1011, LaneErrorStatus::ack(lanes));
+        assert_eq!(1 << 31 | 1 << 2, LaneErrorStatus::ack([2, 31, 32]));
+        assert_eq!(0, LaneErrorStatus(0).lanes().count());
+    }
+
+    #[test]
+    fn link_control_3() {
+        // Perform Equalization, lower SKP OS generation at 8.0 and 16.0 GT/s
+        let value = 1 << 0 | 0b1100 << 9;
+        let result = LinkControl3::from(value | 0xffff_0000);
+        let sample = LinkControl3 {
+            perform_equalization: true,
+            link_equalization_request_interrupt_enable: false,
+            lower_skp_os_generation_vector: SupportedLinkSpeedsVector {
+                speed_2_5_gtps: false,
+                speed_5_0_gtps: false,
+                speed_8_0_gtps: true,
+                speed_16_0_gtps: true,
+                speed_32_0_gtps: false,
+                speed_64_0_gtps: false,
+                reserved: false,
+            },
+        };
+        assert_eq!(sample, result);
+        assert_eq!(value, u32::from(result));
     }
 
     #[test]
@@ -255,14 +299,13 @@ mod tests {
         let result = spe
             .equalization_control_lanes(LinkWidth::X8)
             .collect::<Vec<_>>();
-        let sample = std::iter::repeat(LaneEqualizationControl {
+        let lane = LaneEqualizationControl {
             downstream_port_transmitter_preset: TransmitterPreset::P7,
             downstream_port_receiver_preset_hint: ReceiverPresetHint::Reserved,
             upstream_port_transmitter_preset: TransmitterPreset::P7,
             upstream_port_receiver_preset_hint: ReceiverPresetHint::Minus8dB,
-        })
-        .take(8)
-        .collect::<Vec<_>>();
+        };
+        let sample = core::iter::repeat_n(lane, 8).collect::<Vec<_>>();
         assert_eq!(sample, result);
     }
 }
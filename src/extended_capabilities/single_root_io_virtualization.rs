@@ -7,7 +7,7 @@
 use core::mem::size_of;
 
 use snafu::prelude::*;
-use heterob::{P16,P5,P6,P1, endianness::{FromLeBytes, LeBytesInto}, bit_numbering::Lsb};
+use heterob::{P16,P5,P6,P1, endianness::LeBytesInto, bit_numbering::Lsb};
 
 use crate::header::BaseAddresses;
 use super::ExtendedCapabilityDataError;
@@ -15,6 +15,7 @@ use super::ExtendedCapabilityDataError;
 
 /// Single Root I/O Virtualization Error
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SingleRootIoVirtualizationError {
     #[snafu(display("can't read Element Self Description (4 bytes) from slice"))]
     ElementSelfDescription,
@@ -52,22 +53,61 @@ pub struct SingleRootIoVirtualization {
     pub vf_migration_state_array_offset: u32,
 }
 impl SingleRootIoVirtualization {
-    pub const BYTES: usize = 0x40 - super::ECH_BYTES;
+    pub const SIZE: usize = 0x40 - super::ECH_BYTES;
+    pub const MIN_SIZE: usize = Self::SIZE;
+    /// Same as [SIZE](Self::SIZE)
+    pub const BYTES: usize = Self::SIZE;
+    /// Routing ID of the VF `vf_number` (counted from 1) of the PF with `pf_routing_id`
+    ///
+    /// First VF Offset and VF Stride are valid for the current NumVFs, so `None` is returned
+    /// for a VF number beyond NumVFs or a Routing ID beyond the last bus.
+    pub fn vf_routing_id(&self, pf_routing_id: u16, vf_number: u16) -> Option<u16> {
+        let offset = self.vf_offset(vf_number)?;
+        u16::try_from(pf_routing_id as u32 + offset).ok()
+    }
+    /// Number (counted from 1) of the VF with `vf_routing_id` if it belongs to the PF with
+    /// `pf_routing_id`
+    pub fn vf_number(&self, pf_routing_id: u16, vf_routing_id: u16) -> Option<u16> {
+        let offset = (vf_routing_id as u32)
+            .checked_sub(pf_routing_id as u32 + self.first_vf_offset as u32)?;
+        let index = match self.vf_stride {
+            0 if offset == 0 => 0,
+            0 => return None,
+            stride if offset % stride as u32 == 0 => offset / stride as u32,
+            _ => return None,
+        };
+        u16::try_from(index + 1)
+            .ok()
+            .filter(|&vf_number| vf_number <= self.num_vfs)
+    }
+    /// Routing ID of the PF owning the VF `vf_number` (counted from 1) with `vf_routing_id`
+    pub fn pf_routing_id(&self, vf_routing_id: u16, vf_number: u16) -> Option<u16> {
+        let offset = self.vf_offset(vf_number)?;
+        u16::try_from((vf_routing_id as u32).checked_sub(offset)?).ok()
+    }
+    /// Routing ID offset of the VF `vf_number` from its PF
+    fn vf_offset(&self, vf_number: u16) -> Option<u32> {
+        if vf_number == 0 || vf_number > self.num_vfs {
+            return None;
+        }
+        Some(self.first_vf_offset as u32 + (vf_number as u32 - 1) * self.vf_stride as u32)
+    }
 }
 
 
-impl From<[u8; SingleRootIoVirtualization::BYTES]> for SingleRootIoVirtualization {
-    fn from(bytes: [u8; Self::BYTES]) -> Self {
+impl From<[u8; SingleRootIoVirtualization::SIZE]> for SingleRootIoVirtualization {
+    fn from(bytes: [u8; Self::SIZE]) -> Self {
         let P16((
             sriov_capabilities, sriov_control, sriov_status, initial_vfs, total_vfs, num_vfs,
             function_dependency_link, rsvdp_0, first_vf_offset, vf_stride, rsvdp_1, vf_device_id,
             supported_page_sizes, system_page_sizes, base_addresses, vf_migration_state_array_offset,
         )) = bytes.le_bytes_into();
         let _: (u8, u16, [u8; size_of::<u32>() * 6]) = (rsvdp_0, rsvdp_1, base_addresses);
+        let _: (u32, u16, u16) = (sriov_capabilities, sriov_control, sriov_status);
         Self {
-            sriov_capabilities,
-            sriov_control,
-            sriov_status,
+            sriov_capabilities: sriov_capabilities.into(),
+            sriov_control: sriov_control.into(),
+            sriov_status: sriov_status.into(),
             initial_vfs,
             total_vfs,
             num_vfs,
@@ -88,11 +128,11 @@ impl From<[u8; SingleRootIoVirtualization::BYTES]> for SingleRootIoVirtualizatio
 impl<'a> TryFrom<&'a [u8]> for SingleRootIoVirtualization {
     type Error = ExtendedCapabilityDataError;
     fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
-        slice.get(..Self::BYTES)
-            .and_then(|slice| <[u8; Self::BYTES]>::try_from(slice).ok())
+        slice.get(..Self::SIZE)
+            .and_then(|slice| <[u8; Self::SIZE]>::try_from(slice).ok())
             .ok_or(ExtendedCapabilityDataError {
                 name: "Single Root I/O Virtualization",
-                size: Self::BYTES
+                size: Self::MIN_SIZE,
             })
             .map(Self::from)
     }
@@ -127,9 +167,6 @@ impl From<u32> for SriovCapabilities {
         }
     }
 }
-impl FromLeBytes<4> for SriovCapabilities {
-    fn from_le_bytes(bytes: [u8;4]) -> Self { u32::from_le_bytes(bytes).into() }
-}
 
 /// SR-IOV Control
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -167,9 +204,6 @@ impl From<u16> for SriovControl {
         }
     }
 }
-impl FromLeBytes<2> for SriovControl {
-    fn from_le_bytes(bytes: [u8;2]) -> Self { u16::from_le_bytes(bytes).into() }
-}
 
 /// SR-IOV Status
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -187,9 +221,6 @@ impl From<u16> for SriovStatus {
         }
     }
 }
-impl FromLeBytes<2> for SriovStatus {
-    fn from_le_bytes(bytes: [u8;2]) -> Self { u16::from_le_bytes(bytes).into() }
-}
 
 
 /// Defines the page size the system will use to map the VFs’ memory addresses
@@ -275,4 +306,34 @@ mod tests {
         
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn routing_ids() {
+        let mut sriov: SingleRootIoVirtualization = DATA.into();
+        // VFs are not enabled
+        assert_eq!(None, sriov.vf_routing_id(0x0100, 1));
+        sriov.num_vfs = 8;
+        // 01:00.0 PF, VFs 02:80.0, 02:80.4, ..., 02:81.4
+        assert_eq!(Some(0x0280), sriov.vf_routing_id(0x0100, 1));
+        assert_eq!(Some(0x029c), sriov.vf_routing_id(0x0100, 8));
+        assert_eq!(None, sriov.vf_routing_id(0x0100, 0));
+        assert_eq!(None, sriov.vf_routing_id(0x0100, 9));
+        assert_eq!(None, sriov.vf_routing_id(0xff00, 8));
+
+        assert_eq!(Some(1), sriov.vf_number(0x0100, 0x0280));
+        assert_eq!(Some(8), sriov.vf_number(0x0100, 0x029c));
+        // Between VFs, beyond NumVFs, before First VF
+        assert_eq!(None, sriov.vf_number(0x0100, 0x0282));
+        assert_eq!(None, sriov.vf_number(0x0100, 0x02a0));
+        assert_eq!(None, sriov.vf_number(0x0100, 0x0100));
+
+        assert_eq!(Some(0x0100), sriov.pf_routing_id(0x029c, 8));
+        assert_eq!(None, sriov.pf_routing_id(0x0010, 1));
+
+        // Single VF with zero stride
+        sriov.num_vfs = 1;
+        sriov.vf_stride = 0;
+        assert_eq!(Some(1), sriov.vf_number(0x0100, 0x0280));
+        assert_eq!(None, sriov.vf_number(0x0100, 0x0281));
+    }
 }
@@ -53,6 +53,9 @@ pub struct TphRequester<'a> {
     pub tph_requester_control: TphRequesterControl,
 }
 
+impl<'a> TphRequester<'a> {
+    pub const MIN_SIZE: usize = 4 + 4;
+}
 impl<'a> TryFrom<&'a [u8]> for TphRequester<'a> {
     type Error = ExtendedCapabilityDataError;
 
@@ -64,7 +67,7 @@ impl<'a> TryFrom<&'a [u8]> for TphRequester<'a> {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "TPH Requester",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         let Lsb((
             no_st_mode_supported,
@@ -114,6 +117,43 @@ impl<'a> TryFrom<&'a [u8]> for TphRequester<'a> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<'a> TphRequester<'a> {
+    /// Copies ST Table data
+    pub fn into_owned(self) -> OwnedTphRequester {
+        let TphRequesterCapability {
+            no_st_mode_supported,
+            interrupt_vector_mode_supported,
+            device_specific_mode_supported,
+            extended_tph_requester_supported,
+            st_table,
+        } = self.tph_requester_capability;
+        let st_table = match st_table {
+            StTable::NotPresent => OwnedStTable::NotPresent,
+            StTable::Valid { size, data } => OwnedStTable::Valid {
+                size,
+                data: data.to_vec(),
+            },
+            StTable::Invalid { size, data } => OwnedStTable::Invalid {
+                size,
+                data: data.to_vec(),
+            },
+            StTable::MsiXTable { size } => OwnedStTable::MsiXTable { size },
+            StTable::Reserved => OwnedStTable::Reserved,
+        };
+        OwnedTphRequester {
+            tph_requester_capability: OwnedTphRequesterCapability {
+                no_st_mode_supported,
+                interrupt_vector_mode_supported,
+                device_specific_mode_supported,
+                extended_tph_requester_supported,
+                st_table,
+            },
+            tph_requester_control: self.tph_requester_control,
+        }
+    }
+}
+
 /// TPH Requester Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TphRequesterCapability<'a> {
@@ -146,6 +186,85 @@ pub enum StTable<'a> {
     Reserved,
 }
 
+/// [TphRequester] owning ST Table data
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedTphRequester {
+    /// TPH Requester Capability
+    pub tph_requester_capability: OwnedTphRequesterCapability,
+    /// TPH Requester Control
+    pub tph_requester_control: TphRequesterControl,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedTphRequester {
+    /// [TphRequester] borrowing owned ST Table data
+    pub fn as_borrowed(&self) -> TphRequester<'_> {
+        let OwnedTphRequesterCapability {
+            no_st_mode_supported,
+            interrupt_vector_mode_supported,
+            device_specific_mode_supported,
+            extended_tph_requester_supported,
+            ref st_table,
+        } = self.tph_requester_capability;
+        let st_table = match *st_table {
+            OwnedStTable::NotPresent => StTable::NotPresent,
+            OwnedStTable::Valid { size, ref data } => StTable::Valid { size, data },
+            OwnedStTable::Invalid { size, ref data } => StTable::Invalid { size, data },
+            OwnedStTable::MsiXTable { size } => StTable::MsiXTable { size },
+            OwnedStTable::Reserved => StTable::Reserved,
+        };
+        TphRequester {
+            tph_requester_capability: TphRequesterCapability {
+                no_st_mode_supported,
+                interrupt_vector_mode_supported,
+                device_specific_mode_supported,
+                extended_tph_requester_supported,
+                st_table,
+            },
+            tph_requester_control: self.tph_requester_control.clone(),
+        }
+    }
+}
+
+/// [TphRequesterCapability] owning ST Table data
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedTphRequesterCapability {
+    /// No ST Mode Supported
+    pub no_st_mode_supported: bool,
+    /// Interrupt Vector Mode Supported
+    pub interrupt_vector_mode_supported: bool,
+    /// Device Specific Mode Supported
+    pub device_specific_mode_supported: bool,
+    /// Extended TPH Requester Supported
+    pub extended_tph_requester_supported: bool,
+    /// ST Table
+    pub st_table: OwnedStTable,
+}
+
+/// [StTable] owning its data
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnedStTable {
+    /// ST Table is not present
+    NotPresent,
+    /// ST Table is located in the TPH Requester Capability structure and all bytes are readable
+    Valid {
+        size: u16,
+        data: alloc::vec::Vec<u8>,
+    },
+    /// ST Table is located in the TPH Requester Capability structure, but has invalid size
+    Invalid {
+        size: u16,
+        data: alloc::vec::Vec<u8>,
+    },
+    /// ST Table is located in the MSI-X Table
+    MsiXTable { size: u16 },
+    /// Reserved
+    Reserved,
+}
+
 /// Each implemented ST Entry is 16 bits
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TphStTableEntry {
@@ -9,6 +9,18 @@ permitted to be implemented by any PCI Express Function or RCRB.
 - [VsecHeader]
 - [VsecRegisters]
 
+[VendorSpecificExtendedCapability::bounded_payload] returns registers clipped to VSEC Length
+and checks that the structure does not run into the next extended capability.
+
+## Vendor decoders
+
+With `vendor-decoders` feature enabled [VendorSpecificExtendedCapability::decode] interprets
+registers of known VSEC layouts. Layout is selected by Vendor ID of the function, VSEC ID and
+VSEC Rev:
+- [Intel](intel): Platform Monitoring Technology discovery structures, Xeon root port VSEC ID
+  0002h and 0003h (e.g. `8086:2030`)
+- [NVIDIA](nvidia): GPU VSEC ID 0001h
+
 ## Examples
 
 > ```text
@@ -34,8 +46,26 @@ assert_eq!(sample, result);
 */
 
 use heterob::{bit_numbering::Lsb, endianness::LeBytesTryInto, Seq, P3};
+use snafu::prelude::*;
 
 use super::ExtendedCapabilityDataError;
+use crate::ConfigOffset;
+
+/// Vendor-Specific Registers can't be located
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VsecPayloadError {
+    #[snafu(display("VSEC Length {length:#x} is shorter than VSEC headers (8 bytes)"))]
+    InvalidLength { length: u16 },
+    #[snafu(display("VSEC Length {length:#x} exceeds available data ({available} bytes)"))]
+    Incomplete { length: u16, available: usize },
+    #[snafu(display("VSEC at {offset} with length {length:#x} crosses the structure end {end}"))]
+    Overlap {
+        offset: ConfigOffset,
+        length: u16,
+        end: ConfigOffset,
+    },
+}
 
 /// Vendor-Specific Extended Capability
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -43,6 +73,46 @@ pub struct VendorSpecificExtendedCapability<'a> {
     pub header: VsecHeader,
     pub registers: VsecRegisters<'a>,
 }
+impl<'a> VendorSpecificExtendedCapability<'a> {
+    /// Vendor-Specific Header
+    pub const MIN_SIZE: usize = 4;
+    /// Vendor-Specific Registers clipped to VSEC Length
+    pub fn payload(&self) -> Result<&'a [u8], VsecPayloadError> {
+        let length = self.header.vsec_length;
+        match self.registers {
+            VsecRegisters::Valid(slice) => Ok(slice),
+            VsecRegisters::InvalidLength(_) => InvalidLengthSnafu { length }.fail(),
+            VsecRegisters::Incomplete(slice) => IncompleteSnafu {
+                length,
+                // Extended Capability Header and Vendor-Specific Header are available
+                available: 8 + slice.len(),
+            }
+            .fail(),
+        }
+    }
+    /// [Payload](Self::payload) of the VSEC located at `offset`, the structure must not cross
+    /// `end`
+    ///
+    /// `end` is usually the next structure offset, see
+    /// [ExtendedCapabilities::structure_end](super::ExtendedCapabilities::structure_end).
+    pub fn bounded_payload(
+        &self,
+        offset: ConfigOffset,
+        end: ConfigOffset,
+    ) -> Result<&'a [u8], VsecPayloadError> {
+        let length = self.header.vsec_length;
+        ensure!(length >= 8, InvalidLengthSnafu { length });
+        ensure!(
+            offset.checked_add(length).is_some_and(|e| e <= end),
+            OverlapSnafu {
+                offset,
+                length,
+                end
+            }
+        );
+        self.payload()
+    }
+}
 impl<'a> TryFrom<&'a [u8]> for VendorSpecificExtendedCapability<'a> {
     type Error = ExtendedCapabilityDataError;
 
@@ -52,7 +122,7 @@ impl<'a> TryFrom<&'a [u8]> for VendorSpecificExtendedCapability<'a> {
                 .le_bytes_try_into()
                 .map_err(|_| ExtendedCapabilityDataError {
                     name: "Vendor-Specific Extended Capability",
-                    size: 4,
+                    size: Self::MIN_SIZE,
                 })?;
         let Lsb((vsec_id, vsec_rev, vsec_length)) = P3::<u32, 16, 4, 12>(header).into();
         let header = VsecHeader {
@@ -94,3 +164,84 @@ pub enum VsecRegisters<'a> {
     /// Available data shorter than length in header
     Incomplete(&'a [u8]),
 }
+
+#[cfg(feature = "vendor-decoders")]
+pub mod intel;
+#[cfg(feature = "vendor-decoders")]
+pub mod nvidia;
+
+#[cfg(feature = "vendor-decoders")]
+impl<'a> VendorSpecificExtendedCapability<'a> {
+    /// Decode vendor-specific registers based on function `vendor_id` (from
+    /// [Header](crate::Header)), VSEC ID and VSEC Rev
+    pub fn decode(&self, vendor_id: u16) -> Option<KnownVsec> {
+        match vendor_id {
+            intel::VENDOR_ID => intel::IntelVsec::decode(self).map(KnownVsec::Intel),
+            nvidia::VENDOR_ID => nvidia::NvidiaVsec::decode(self).map(KnownVsec::Nvidia),
+            _ => None,
+        }
+    }
+}
+
+/// Decoded vendor-specific registers
+#[cfg(feature = "vendor-decoders")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownVsec {
+    Intel(intel::IntelVsec),
+    Nvidia(nvidia::NvidiaVsec),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExtendedCapabilities;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn payload() {
+        // VSEC (000Bh) at 100h with VSEC Length 10h followed by LTR (0018h) at 110h
+        let mut ecs = [0u8; 0x20];
+        ecs[..12].copy_from_slice(&[0x0b, 0x00, 0x01, 0x11, 0x02, 0x00, 0x00, 0x01, 1, 2, 3, 4]);
+        ecs[0x10..0x14].copy_from_slice(&[0x18, 0x00, 0x01, 0x00]);
+        let offset = ConfigOffset(0x100);
+        let end = ExtendedCapabilities::new(&ecs).structure_end(offset);
+        assert_eq!(ConfigOffset(0x110), end);
+
+        let vsec = VendorSpecificExtendedCapability::try_from(&ecs[4..]).unwrap();
+        let sample = [1, 2, 3, 4, 0, 0, 0, 0].as_slice();
+        assert_eq!(Ok(sample), vsec.payload());
+        assert_eq!(Ok(sample), vsec.bounded_payload(offset, end));
+
+        // VSEC Length 14h
+        ecs[6] = 0x40;
+        ecs[7] = 0x01;
+        let vsec = VendorSpecificExtendedCapability::try_from(&ecs[4..]).unwrap();
+        assert!(vsec.payload().is_ok());
+        assert_eq!(
+            Err(VsecPayloadError::Overlap {
+                offset,
+                length: 0x14,
+                end
+            }),
+            vsec.bounded_payload(offset, end)
+        );
+
+        // Data ends before VSEC Length
+        let vsec = VendorSpecificExtendedCapability::try_from(&ecs[4..0x10]).unwrap();
+        assert_eq!(
+            Err(VsecPayloadError::Incomplete {
+                length: 0x14,
+                available: 0x10
+            }),
+            vsec.payload()
+        );
+
+        // VSEC Length 4
+        ecs[6] = 0x40;
+        ecs[7] = 0x00;
+        let vsec = VendorSpecificExtendedCapability::try_from(&ecs[4..]).unwrap();
+        let error = Err(VsecPayloadError::InvalidLength { length: 4 });
+        assert_eq!(error, vsec.payload());
+        assert_eq!(error, vsec.bounded_payload(offset, end));
+    }
+}
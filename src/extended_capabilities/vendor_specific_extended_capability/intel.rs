@@ -0,0 +1,300 @@
+/*!
+# Intel Vendor-Specific Extended Capabilities
+
+Intel reuses low VSEC IDs and revisions across device generations, so a layout is selected by
+VSEC ID, VSEC Rev and VSEC Length:
+
+| VSEC ID | Rev | Length | Layout |
+|---------|-----|--------|--------|
+| 0002h-0004h, 0041h, 0042h | 1 | 10h | [Platform Monitoring Technology](PlatformMonitoringTechnology) discovery structure, discovery table BIR selects BAR0-BAR5 |
+| 0002h | 0 | 0Ch | Xeon root port [REUT](Reut) engine header (e.g. `8086:2030` at 100h) |
+| 0003h | 1 | 0Ah | Xeon root port [VSEC 0003h](RootPortId3) (e.g. `8086:2030` at 1D0h) |
+
+Xeon root port VSECs are not described beyond register names and sizes, their registers are
+kept raw.
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::vendor_specific_extended_capability::{
+#     VendorSpecificExtendedCapability, KnownVsec, intel::*,
+# };
+let data = [
+    /* 04h */ 0x02, 0x00, 0x01, 0x01, // Vendor-Specific Header
+    /* 08h */ 0x00, 0x00,             // Unused
+              0x01,                   // Number of entries
+              0x04,                   // Entry size
+    /* 0Ch */ 0x00 | 0x02, 0x40, 0x00, 0x00, // Discovery table offset and BIR
+];
+let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+let sample = IntelVsec::PlatformMonitoringTechnology(PlatformMonitoringTechnology {
+    feature: PmtFeature::Telemetry,
+    number_of_entries: 1,
+    entry_size: 4,
+    table_bar_index: 2,
+    table_offset: 0x4000,
+});
+assert_eq!(Some(KnownVsec::Intel(sample)), vsec.decode(0x8086));
+```
+*/
+
+use heterob::{bit_numbering::Lsb, endianness::Le, Seq, P2, P3};
+
+use super::{VendorSpecificExtendedCapability, VsecRegisters};
+
+/// Intel Vendor ID
+pub const VENDOR_ID: u16 = 0x8086;
+
+/// Known Intel VSEC layouts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntelVsec {
+    PlatformMonitoringTechnology(PlatformMonitoringTechnology),
+    Reut(Reut),
+    RootPortId3(RootPortId3),
+}
+
+impl IntelVsec {
+    pub fn decode(vsec: &VendorSpecificExtendedCapability) -> Option<Self> {
+        let registers = match vsec.registers {
+            VsecRegisters::Valid(registers) => registers,
+            _ => return None,
+        };
+        match (vsec.header.vsec_id, vsec.header.vsec_rev) {
+            (id, 1) if registers.len() == PlatformMonitoringTechnology::SIZE => {
+                PmtFeature::try_from(id)
+                    .ok()
+                    .and_then(|feature| PlatformMonitoringTechnology::try_new(registers, feature))
+                    .map(Self::PlatformMonitoringTechnology)
+            }
+            (0x0002, 0) => registers.try_into().ok().map(Self::Reut),
+            (0x0003, 1) => registers.try_into().ok().map(Self::RootPortId3),
+            _ => None,
+        }
+    }
+}
+
+/// Platform Monitoring Technology discovery structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlatformMonitoringTechnology {
+    pub feature: PmtFeature,
+    /// Number of entries in discovery table
+    pub number_of_entries: u8,
+    /// Discovery table entry size in DWORDs
+    pub entry_size: u8,
+    /// BAR containing discovery table
+    pub table_bar_index: u8,
+    /// Discovery table offset from the BAR base (QWORD aligned)
+    pub table_offset: u32,
+}
+
+impl PlatformMonitoringTechnology {
+    /// Vendor-specific registers size, VSEC Length is 10h
+    pub const SIZE: usize = 8;
+
+    fn try_new(registers: &[u8], feature: PmtFeature) -> Option<Self> {
+        let Seq {
+            head: Le((_unused, [number_of_entries, entry_size], table)),
+            ..
+        } = P3(registers).try_into().ok()?;
+        let _: [u8; 2] = _unused;
+        let Lsb((table_bar_index, table_offset)) = P2::<u32, 3, 29>(table).into();
+        let _: u32 = table_offset;
+        if table_bar_index > 5 {
+            return None;
+        }
+        Some(Self {
+            feature,
+            number_of_entries,
+            entry_size,
+            table_bar_index,
+            table_offset: table_offset << 3,
+        })
+    }
+}
+
+/// Robust Electrical Unified Testing (REUT) engine header of Xeon PCI Express root ports
+///
+/// VSEC ID 0002h, VSEC Rev 0, VSEC Length 0Ch. The VSEC headers are XPREUT_HDR_EXT and
+/// XPREUT_HDR_CAP registers, followed by the only vendor-specific register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reut {
+    /// REUT Header Leaf Capability (XPREUT_HDR_LEF)
+    pub leaf_capability: u32,
+}
+
+impl Reut {
+    /// Vendor-specific registers size, VSEC Length is 0Ch
+    pub const SIZE: usize = 4;
+}
+
+impl TryFrom<&[u8]> for Reut {
+    type Error = usize;
+
+    /// Error is the registers size if it is not [SIZE](Self::SIZE)
+    fn try_from(registers: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; Self::SIZE] = registers.try_into().map_err(|_| registers.len())?;
+        Ok(Self {
+            leaf_capability: u32::from_le_bytes(bytes),
+        })
+    }
+}
+
+/// VSEC ID 0003h of Xeon PCI Express root ports
+///
+/// VSEC Rev 1, VSEC Length 0Ah: a single 16-bit register follows the VSEC headers. The ID and
+/// Rev match the [PMT Watcher](PmtFeature::Watcher) discovery structure, the length tells them
+/// apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootPortId3 {
+    pub register: u16,
+}
+
+impl RootPortId3 {
+    /// Vendor-specific registers size, VSEC Length is 0Ah
+    pub const SIZE: usize = 2;
+}
+
+impl TryFrom<&[u8]> for RootPortId3 {
+    type Error = usize;
+
+    /// Error is the registers size if it is not [SIZE](Self::SIZE)
+    fn try_from(registers: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; Self::SIZE] = registers.try_into().map_err(|_| registers.len())?;
+        Ok(Self {
+            register: u16::from_le_bytes(bytes),
+        })
+    }
+}
+
+/// Feature described by PMT discovery structure (VSEC ID)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PmtFeature {
+    /// Telemetry aggregator
+    Telemetry,
+    /// Watcher
+    Watcher,
+    /// Crash log
+    Crashlog,
+    /// Intel On Demand (Software Defined Silicon)
+    OnDemand,
+    /// Topology Aware Register and PM Capsule Interface
+    Tpmi,
+}
+
+impl TryFrom<u16> for PmtFeature {
+    type Error = u16;
+
+    fn try_from(vsec_id: u16) -> Result<Self, Self::Error> {
+        match vsec_id {
+            0x02 => Ok(Self::Telemetry),
+            0x03 => Ok(Self::Watcher),
+            0x04 => Ok(Self::Crashlog),
+            0x41 => Ok(Self::OnDemand),
+            0x42 => Ok(Self::Tpmi),
+            v => Err(v),
+        }
+    }
+}
+
+impl From<PmtFeature> for u16 {
+    fn from(feature: PmtFeature) -> Self {
+        match feature {
+            PmtFeature::Telemetry => 0x02,
+            PmtFeature::Watcher => 0x03,
+            PmtFeature::Crashlog => 0x04,
+            PmtFeature::OnDemand => 0x41,
+            PmtFeature::Tpmi => 0x42,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extended_capabilities::{vendor_specific_extended_capability::KnownVsec, *},
+        ECS_OFFSET,
+    };
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn skylake_root_port_vsecs() {
+        let data = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/device/8086_2030/config"
+        ));
+        let result = ExtendedCapabilities::new(&data[ECS_OFFSET..])
+            .filter_map(|ecap| match ecap {
+                Ok(ExtendedCapability {
+                    kind: ExtendedCapabilityKind::VendorSpecificExtendedCapability(vsec),
+                    ..
+                }) => Some((
+                    vsec.header.vsec_id,
+                    vsec.header.vsec_rev,
+                    vsec.decode(VENDOR_ID),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // IDs are shared with PMT features, registers are not PMT discovery structures
+        let sample = vec![
+            (
+                0x0002,
+                0,
+                Some(KnownVsec::Intel(IntelVsec::Reut(Reut {
+                    leaf_capability: 0x0000_3807,
+                }))),
+            ),
+            (
+                0x0003,
+                1,
+                Some(KnownVsec::Intel(IntelVsec::RootPortId3(RootPortId3 {
+                    register: 0x0001,
+                }))),
+            ),
+            (0x0005, 3, None),
+            (0x0007, 0, None),
+            (0x0008, 0, None),
+        ];
+        assert_eq!(sample, result);
+    }
+
+    #[test]
+    fn root_port_vsec_length() {
+        // REUT header with VSEC Length 10h
+        let data = [0x02, 0x00, 0x00, 0x01, 0x07, 0x38, 0, 0, 0, 0, 0, 0];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+        assert_eq!(Err(8), Reut::try_from([0u8; 8].as_slice()));
+        // VSEC ID 0003h with VSEC Length 0Ch
+        let data = [0x03, 0x00, 0xc1, 0x00, 0x01, 0x00, 0, 0];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+    }
+
+    #[test]
+    fn other_vendor() {
+        let data = [0x02, 0x00, 0x01, 0x01, 0, 0, 1, 4, 0x02, 0x40, 0, 0];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert!(vsec.decode(VENDOR_ID).is_some());
+        assert_eq!(None, vsec.decode(0x10de));
+    }
+
+    #[test]
+    fn not_pmt_discovery() {
+        // Watcher ID and Rev with VSEC Length 14h
+        let data = [
+            0x03, 0x00, 0x41, 0x01, 0, 0, 1, 4, 0x02, 0x40, 0, 0, 0, 0, 0, 0,
+        ];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+        // Discovery table BIR 6
+        let data = [0x03, 0x00, 0x01, 0x01, 0, 0, 1, 4, 0x06, 0x40, 0, 0];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+        let data = [0x03, 0x00, 0x01, 0x01, 0, 0, 1, 4, 0x05, 0x40, 0, 0];
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert!(vsec.decode(VENDOR_ID).is_some());
+    }
+}
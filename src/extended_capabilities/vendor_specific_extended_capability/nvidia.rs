@@ -0,0 +1,125 @@
+/*!
+# NVIDIA Vendor-Specific Extended Capabilities
+
+NVIDIA GPUs implement a VSEC with VSEC ID 0001h, VSEC Rev 1 and VSEC Length 24h:
+
+> ```text
+> Vendor Specific Information: ID=0001 Rev=1 Len=024 <?>
+> ```
+
+Seven DWORD registers follow the VSEC headers. There is no public description of them, so
+they are kept raw.
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::vendor_specific_extended_capability::{
+#     VendorSpecificExtendedCapability, KnownVsec, nvidia::*,
+# };
+let mut data = [0u8; 0x20];
+data[..4].copy_from_slice(&[0x01, 0x00, 0x41, 0x02]);
+data[4..8].copy_from_slice(&[0x78, 0x56, 0x34, 0x12]);
+let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+let sample = NvidiaVsec::Gpu(Gpu {
+    registers: [0x1234_5678, 0, 0, 0, 0, 0, 0],
+});
+assert_eq!(Some(KnownVsec::Nvidia(sample)), vsec.decode(0x10de));
+```
+*/
+
+use heterob::{endianness::Le, P7};
+
+use super::{VendorSpecificExtendedCapability, VsecRegisters};
+
+/// NVIDIA Vendor ID
+pub const VENDOR_ID: u16 = 0x10de;
+
+/// Known NVIDIA VSEC layouts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NvidiaVsec {
+    Gpu(Gpu),
+}
+
+impl NvidiaVsec {
+    pub fn decode(vsec: &VendorSpecificExtendedCapability) -> Option<Self> {
+        let registers = match vsec.registers {
+            VsecRegisters::Valid(registers) => registers,
+            _ => return None,
+        };
+        match (vsec.header.vsec_id, vsec.header.vsec_rev) {
+            (0x0001, 1) => registers.try_into().ok().map(Self::Gpu),
+            _ => None,
+        }
+    }
+}
+
+/// GPU VSEC (VSEC ID 0001h, VSEC Rev 1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpu {
+    pub registers: [u32; 7],
+}
+
+impl Gpu {
+    /// Vendor-specific registers size, VSEC Length is 24h
+    pub const SIZE: usize = 0x1c;
+}
+
+impl TryFrom<&[u8]> for Gpu {
+    type Error = usize;
+
+    /// Error is the registers size if it is not [SIZE](Self::SIZE)
+    fn try_from(registers: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; Self::SIZE] = registers.try_into().map_err(|_| registers.len())?;
+        let Le((r0, r1, r2, r3, r4, r5, r6)) = P7(bytes).into();
+        Ok(Self {
+            registers: [r0, r1, r2, r3, r4, r5, r6],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extended_capabilities::vendor_specific_extended_capability::KnownVsec;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn gpu() {
+        let mut data = [0u8; 0x20];
+        data[..4].copy_from_slice(&[0x01, 0x00, 0x41, 0x02]);
+        for (n, byte) in data[4..].iter_mut().enumerate() {
+            *byte = n as u8;
+        }
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        let sample = Gpu {
+            registers: [
+                0x0302_0100,
+                0x0706_0504,
+                0x0b0a_0908,
+                0x0f0e_0d0c,
+                0x1312_1110,
+                0x1716_1514,
+                0x1b1a_1918,
+            ],
+        };
+        assert_eq!(
+            Some(KnownVsec::Nvidia(NvidiaVsec::Gpu(sample))),
+            vsec.decode(VENDOR_ID)
+        );
+        assert_eq!(None, vsec.decode(0x8086));
+    }
+
+    #[test]
+    fn gpu_vsec_length() {
+        // VSEC Length 20h
+        let mut data = [0u8; 0x1c];
+        data[..4].copy_from_slice(&[0x01, 0x00, 0x01, 0x02]);
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+        // VSEC Rev 2
+        let mut data = [0u8; 0x20];
+        data[..4].copy_from_slice(&[0x01, 0x00, 0x42, 0x02]);
+        let vsec: VendorSpecificExtendedCapability = data.as_slice().try_into().unwrap();
+        assert_eq!(None, vsec.decode(VENDOR_ID));
+    }
+}
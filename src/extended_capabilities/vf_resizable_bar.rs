@@ -0,0 +1,129 @@
+/*!
+# VF Resizable BAR
+
+The VF Resizable BAR capability permits hardware to communicate the
+resource sizes that are acceptable for operation via the VF Resizable BAR Capability and Control
+registers and system software to communicate the optimal size back to the hardware via the VF
+BAR Size field of the VF Resizable BAR Control register.
+
+## Struct diagram
+
+VF Resizable BAR has same fields as [ResizableBar](super::ResizableBar): it is an iterator
+through VF Resizable BAR Capability and Control register pairs. VF BAR Index refers to the VF
+BARs of the [SR-IOV](super::single_root_io_virtualization) capability, so entries are combined
+with them by [ResizableBar::vf_views](super::resizable_bar::ResizableBar::vf_views).
+
+## Examples
+
+```rust
+# use pcics::extended_capabilities::{
+#     single_root_io_virtualization::SingleRootIoVirtualization,
+#     vf_resizable_bar::*,
+# };
+let data = [
+    /* 00h */ 0x24, 0x00, 0x01, 0x00, // Capability header
+    /* 04h */ 0x30, 0x00, 0x00, 0x00, // VF Resizable BAR Capability (0)
+    /* 08h */ 0x40, 0x01, 0x00, 0x00, // VF Resizable BAR Control (0)
+    /* 0Ch */ 0x00, 0x01, 0x00, 0x00, // VF Resizable BAR Capability (1)
+    /* 10h */ 0x02, 0x04, 0x00, 0x00, // VF Resizable BAR Control (1)
+];
+let vf_rebar: VfResizableBar = data.as_slice().try_into().unwrap();
+let entries = vf_rebar
+    .clone()
+    .flatten()
+    .map(|VfResizableBarEntry { capability, control }| {
+        (control.bar_index, control.bar_size, capability.support_map_from_1mb_to_128tb)
+    })
+    .collect::<Vec<_>>();
+assert_eq!(vec![(0, 1, 0x30), (2, 4, 0x100)], entries);
+
+// VF BAR0 is a 64-bit memory BAR, VF BAR2 is not implemented
+let mut sriov = [0u8; SingleRootIoVirtualization::SIZE];
+sriov[0x20] = 0x0c;
+let sriov = SingleRootIoVirtualization::from(sriov);
+let conflicts = vf_rebar
+    .vf_views(&sriov, &[2 << 20])
+    .map(|view| view.map(|view| view.conflict))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(vec![None, Some(ResizableBarConflict::Unimplemented)], conflicts);
+```
+*/
+
+pub use super::resizable_bar::{ResizableBarConflict, ResizableBarView};
+
+/// VF Resizable BAR
+pub type VfResizableBar<'a> = super::ResizableBar<'a>;
+
+/// VF Resizable BAR Capability and Control register pair
+pub type VfResizableBarEntry = super::resizable_bar::ResizableBarEntry;
+
+/// VF Resizable BAR Error
+pub type VfResizableBarError = super::resizable_bar::ResizableBarError;
+
+/// Truncated VF Resizable BAR Capability and Control register pair
+pub type VfResizableBarEntryError = super::resizable_bar::ResizableBarEntryError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extended_capabilities::{
+        resizable_bar::{ResizableBarCapability, ResizableBarControl},
+        SingleRootIoVirtualization,
+    };
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn entries() {
+        let data = [
+            0x24, 0x00, 0x01, 0x00, // Capability header
+            0xf0, 0xff, 0x0f, 0x00, // VF BAR 1: 1MB..2GB
+            0x61, 0x05, 0x01, 0x00, // VF BAR 1: 3 entries, 32MB, 256TB
+            0x10, 0x00, 0x00, 0x00, // VF BAR 3: 1MB
+            0x03, 0x00, 0x00, 0x00, // VF BAR 3: 1MB
+            0x10, 0x00, 0x00, 0x00, // VF BAR 5: 1MB
+            0x05, 0x00, 0x00, 0x00, // VF BAR 5: 1MB
+        ];
+        let vf_rebar = VfResizableBar::try_from(data.as_slice()).unwrap();
+        let result = vf_rebar.clone().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(3, result.len());
+        let sample = VfResizableBarEntry {
+            capability: ResizableBarCapability {
+                support_map_from_1mb_to_128tb: 0x000f_fff0,
+            },
+            control: ResizableBarControl {
+                bar_index: 1,
+                number_of_resizable_bars: 3,
+                bar_size: 5,
+                support_map_from_256tb_to_8eb: 0x0001,
+            },
+        };
+        assert_eq!(sample, result[0]);
+        assert!(result[0].is_function_supports_power_of_two(48));
+        assert_eq!(0b0010_1010, vf_rebar.bar_indices());
+
+        // VF BAR0 64-bit: 1..2, VF BAR3 I/O, VF BAR5 32-bit
+        let mut sriov = [0u8; SingleRootIoVirtualization::SIZE];
+        let bars: [u32; 6] = [0x8000_000c, 0, 0, 0x0000_1001, 0, 0x9000_0000];
+        for (n, bar) in bars.iter().enumerate() {
+            sriov[0x20 + n * 4..][..4].copy_from_slice(&bar.to_le_bytes());
+        }
+        let sriov = SingleRootIoVirtualization::from(sriov);
+        let result = vf_rebar
+            .vf_views(&sriov, &[0, 0, 0, 0, 0, 1 << 20])
+            .map(|view| view.map(|view| (view.bar_index, view.size, view.conflict)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let sample = vec![
+            (
+                1,
+                Some(32 << 20),
+                Some(ResizableBarConflict::UpperHalf { lower: 0 }),
+            ),
+            (3, Some(1 << 20), Some(ResizableBarConflict::IoSpace)),
+            (5, Some(1 << 20), None),
+        ];
+        assert_eq!(sample, result);
+    }
+}
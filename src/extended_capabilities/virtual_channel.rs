@@ -90,8 +90,9 @@ use core::slice;
 
 use super::ECH_BYTES;
 
-/// Numeral unit for VC Arbitration Table Offset and Port Arbitration Table Offset
-const DQWORD: usize = 16;
+pub(super) mod arbitration;
+pub use arbitration::ArbitrationTableError;
+use arbitration::{table_data, PackedEntries};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VirtualChannel<'a> {
@@ -106,41 +107,52 @@ pub struct VirtualChannel<'a> {
     pub port_vc_status: PortVcStatus,
 }
 impl<'a> VirtualChannel<'a> {
+    pub const MIN_SIZE: usize = 4 + 4 + 2 + 2;
     pub fn extended_virtual_channels(&self) -> ExtendedVirtualChannels<'a> {
         let count = self.port_vc_capability_1.extended_vc_count;
         let start = 0x10 - ECH_BYTES;
-        let data = &self.data[start..];
+        let data = self.data.get(start..).unwrap_or_default();
         ExtendedVirtualChannels::new(data, count)
     }
-    pub fn vc_arbitration_table(&self) -> VcArbitrationTable<'a> {
-        let offset = self.port_vc_capability_2.vc_arbitration_table_offset;
-        let entries_number = self
-            .port_vc_control
-            .vc_arbitration_select
-            .vc_arbitration_table_length();
-        let start = offset as usize * DQWORD - ECH_BYTES;
-        // VC Arbitration Table entry length is 4 bits, so there are 2 entries in one byte
-        let end = start + entries_number / 2;
-        let data = &self.data[start..end];
-        VcArbitrationTable::new(data)
+    /// VC Arbitration Table for the currently selected VC Arbitration
+    pub fn vc_arbitration_table(&self) -> Result<VcArbitrationTable<'a>, ArbitrationTableError> {
+        arbitration::vc_arbitration_table(
+            self.data,
+            self.port_vc_capability_2.vc_arbitration_table_offset,
+            &self.port_vc_control.vc_arbitration_select,
+        )
     }
+    /// Port Arbitration Table for the currently selected Port Arbitration of `evc` resource
     pub fn port_arbitration_table(
         &'a self,
         evc: &'a ExtendedVirtualChannel,
-    ) -> PortArbitrationTable<'a> {
+    ) -> Result<PortArbitrationTable<'a>, ArbitrationTableError> {
         let offset = evc.vc_resource_capability.port_arbitration_table_offset;
         let entry_size_bits = self
             .port_vc_capability_1
             .port_arbitration_table_entry_size
             .bits();
-        let entries_number = evc
-            .vc_resource_control
-            .port_arbitration_select
-            .port_arbitration_table_length();
-        let start = offset as usize * DQWORD - ECH_BYTES;
-        let end = start + entry_size_bits * entries_number / 8;
-        let data = &self.data[start..end];
-        PortArbitrationTable::new(data, entry_size_bits)
+        let select = &evc.vc_resource_control.port_arbitration_select;
+        if let PortArbitrationSelect::Reserved(value) = select {
+            return Err(ArbitrationTableError::ReservedSelect { value: *value });
+        }
+        let entries_number = select.port_arbitration_table_length();
+        table_data(self.data, offset, entries_number, entry_size_bits)
+            .map(|data| PortArbitrationTable::new(data, entry_size_bits))
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a> VirtualChannel<'a> {
+    /// Copies borrowed data, tables remain accessible through
+    /// [as_borrowed](OwnedVirtualChannel::as_borrowed)
+    pub fn into_owned(self) -> OwnedVirtualChannel {
+        OwnedVirtualChannel {
+            data: self.data.to_vec(),
+            port_vc_capability_1: self.port_vc_capability_1,
+            port_vc_capability_2: self.port_vc_capability_2,
+            port_vc_control: self.port_vc_control,
+            port_vc_status: self.port_vc_status,
+        }
     }
 }
 impl<'a> TryFrom<&'a [u8]> for VirtualChannel<'a> {
@@ -154,7 +166,7 @@ impl<'a> TryFrom<&'a [u8]> for VirtualChannel<'a> {
             .try_into()
             .map_err(|_| ExtendedCapabilityDataError {
                 name: "Virtual Channel",
-                size: 8,
+                size: Self::MIN_SIZE,
             })?;
         Ok(Self {
             data: slice,
@@ -166,6 +178,35 @@ impl<'a> TryFrom<&'a [u8]> for VirtualChannel<'a> {
     }
 }
 
+/// [VirtualChannel] owning its data
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedVirtualChannel {
+    data: alloc::vec::Vec<u8>,
+    /// Port VC Capability Register 1
+    pub port_vc_capability_1: PortVcCapability1,
+    /// Port VC Capability Register 2
+    pub port_vc_capability_2: PortVcCapability2,
+    /// Por VC Control Register
+    pub port_vc_control: PortVcControl,
+    /// Port VC Status Register
+    pub port_vc_status: PortVcStatus,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedVirtualChannel {
+    /// [VirtualChannel] borrowing owned data
+    pub fn as_borrowed(&self) -> VirtualChannel<'_> {
+        VirtualChannel {
+            data: &self.data,
+            port_vc_capability_1: self.port_vc_capability_1.clone(),
+            port_vc_capability_2: self.port_vc_capability_2.clone(),
+            port_vc_control: self.port_vc_control.clone(),
+            port_vc_status: self.port_vc_status.clone(),
+        }
+    }
+}
+
 /// The Port VC Capability register 1 describes the configuration of the Virtual Channels
 /// associated with a PCI Express Port.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -221,6 +262,16 @@ impl From<u8> for ReferenceClock {
     }
 }
 
+/// Port Arbitration Table Entry Size
+///
+/// Two bits field, all encodings are defined:
+///
+/// | Value | Entry size |
+/// | :---: | :--------: |
+/// | 00b   | 1 bit      |
+/// | 01b   | 2 bits     |
+/// | 10b   | 4 bits     |
+/// | 11b   | 8 bits     |
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PortArbitrationTableEntrySize(u8);
 impl PortArbitrationTableEntrySize {
@@ -230,7 +281,7 @@ impl PortArbitrationTableEntrySize {
 }
 impl From<u8> for PortArbitrationTableEntrySize {
     fn from(byte: u8) -> Self {
-        Self(byte)
+        Self(byte & 0b11)
     }
 }
 
@@ -261,9 +312,7 @@ impl<'a> Iterator for ExtendedVirtualChannels<'a> {
         if self.count == 0 {
             None
         } else {
-            let slice = self
-                .data
-                .get(self.offset..self.offset + ExtendedVirtualChannel::SIZE)?;
+            let slice = self.data.get(self.offset..).unwrap_or_default();
             let result = slice
                 .try_into()
                 .map(|Seq { head, .. }| From::<[u8; ExtendedVirtualChannel::SIZE]>::from(head))
@@ -271,18 +320,24 @@ impl<'a> Iterator for ExtendedVirtualChannels<'a> {
                     number: self.count,
                     offset: self.offset,
                 });
-            self.count -= 1;
+            // Resources after truncated data are not readable either
+            self.count = if result.is_ok() { self.count - 1 } else { 0 };
             self.offset += ExtendedVirtualChannel::SIZE;
             Some(result)
         }
     }
 }
 
-/// Extended Virtual Channel Error
+/// VC Resource registers are beyond the end of data
 #[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[snafu(display(
+    "VC resource registers at {offset:#x} are unreadable ({number} resources remaining)"
+))]
 pub struct ExtendedVirtualChannelError {
-    number: u8,
-    offset: usize,
+    /// Number of resources not read, including the failed one
+    pub number: u8,
+    /// Offset from the first VC Resource Capability register
+    pub offset: usize,
 }
 
 /// Virtual Channel resources
@@ -384,49 +439,22 @@ pub struct PortArbitrationCapability {
     pub reserved: u8,
 }
 
-#[derive(Debug, Clone)]
-pub struct PortArbitrationTable<'a> {
-    data: slice::Iter<'a, u8>,
-    entry_size_bits: usize,
-    shift: usize,
-    byte: u8,
-}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortArbitrationTable<'a>(PackedEntries<'a>);
 impl<'a> PortArbitrationTable<'a> {
+    /// Entries are packed into bytes, so `entry_size_bits` should be 1, 2, 4 or 8. Table with
+    /// any other entry size is empty.
     pub fn new(data: &'a [u8], entry_size_bits: usize) -> Self {
-        Self {
-            data: data.iter(),
-            entry_size_bits,
-            shift: 0,
-            byte: 0,
-        }
+        Self(PackedEntries::new(data, entry_size_bits))
     }
 }
 impl<'a> Iterator for PortArbitrationTable<'a> {
     type Item = PortArbitrationTableEntry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.entry_size_bits == 8 {
-            self.data.next().map(|&v| PortArbitrationTableEntry(v))
-        } else {
-            if self.shift == 0 {
-                self.byte = *self.data.next()?;
-            }
-            let init_mask = !(u8::MAX << self.entry_size_bits);
-            let mask = init_mask << self.shift;
-            let result = (self.byte & mask) >> self.shift;
-            self.shift = (self.shift + self.entry_size_bits) % 8;
-            Some(PortArbitrationTableEntry(result))
-        }
+        self.0.next().map(PortArbitrationTableEntry)
     }
 }
-impl<'a> PartialEq for PortArbitrationTable<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.data.clone().eq(other.data.clone())
-            && self.entry_size_bits == other.entry_size_bits
-            && self.shift == other.shift
-    }
-}
-impl<'a> Eq for PortArbitrationTable<'a> {}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PortArbitrationTableEntry(u8);
@@ -734,6 +762,7 @@ impl From<u16> for PortVcStatus {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -850,6 +879,72 @@ mod tests {
         assert_eq!(sample, result);
     }
 
+    #[test]
+    fn port_arbitration_table_location() {
+        #[rustfmt::skip]
+        let mut data = [
+            /* 00h */ 0x02, 0x00, 0x01, 0x00, // Capability header
+            /* 04h */ 0x00, 0x04, 0x00, 0x00, // Port VC Capability Register 1: 2 bits entry
+            /* 08h */ 0x00, 0x00, 0x00, 0x00, // Port VC Capability Register 2
+            /* 0Ch */ 0x00, 0x00, 0x00, 0x00, // Port VC Control/Status Registers
+            /* 10h */ 0x02, 0x00, 0x00, 0x03, // VC Resource Capability Register (0)
+            /* 14h */ 0xff, 0x00, 0x02, 0x80, // VC Resource Control Register (0): WRR32
+            /* 18h */ 0x00, 0x00, 0x00, 0x00, // VC Resource Status Register (0)
+            /* 1Ch */ 0x00, 0x00, 0x00, 0x00,
+            /* 20h */ 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            /* 28h */ 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            /* 30h */ 0x55, 0x55, 0x55, 0x55, 0xaa, 0xaa, 0xaa, 0xaa, // Port Arbitration Table
+        ];
+        let port_arbitration_table = |data: &[u8]| {
+            let vc: VirtualChannel = data[4..].try_into().unwrap();
+            let evc = vc.extended_virtual_channels().next().unwrap().unwrap();
+            vc.port_arbitration_table(&evc).map(|pat| {
+                pat.map(|PortArbitrationTableEntry(v)| v)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let mut sample = vec![1; 16];
+        sample.extend([2; 16]);
+        assert_eq!(Ok(sample), port_arbitration_table(&data), "Valid");
+
+        assert_eq!(
+            Err(ArbitrationTableError::Data {
+                start: 0x30,
+                end: 0x38,
+                size: 0x37
+            }),
+            port_arbitration_table(&data[..0x37]),
+            "Truncated"
+        );
+
+        data[0x13] = 0;
+        assert_eq!(
+            Err(ArbitrationTableError::Offset { entries_number: 32 }),
+            port_arbitration_table(&data),
+            "Offset is 0"
+        );
+
+        // Hardware fixed arbitration does not need table
+        data[0x16] = 0x00;
+        assert_eq!(Ok(vec![]), port_arbitration_table(&data), "Hardware fixed");
+
+        data[0x16] = 0b111 << 1;
+        assert_eq!(
+            Err(ArbitrationTableError::ReservedSelect { value: 0b111 }),
+            port_arbitration_table(&data),
+            "Reserved"
+        );
+    }
+
+    #[test]
+    fn port_arbitration_table_invalid_entry_size() {
+        let data = [0xff; 4];
+        assert_eq!(0, PortArbitrationTable::new(&data, 0).count());
+        assert_eq!(0, PortArbitrationTable::new(&data, 3).count());
+        assert_eq!(0, PortArbitrationTable::new(&data, 16).count());
+    }
+
     #[test]
     fn port_arbitration_table_entry_size() {
         use PortArbitrationTableEntry as E;
@@ -1081,6 +1176,11 @@ mod tests {
                     vc_negotiation_pending: false,
                 },
             }),
+            // Extended VC Count is larger than the data
+            Err(ExtendedVirtualChannelError {
+                number: 1,
+                offset: 2 * ExtendedVirtualChannel::SIZE,
+            }),
         ];
         assert_eq!(sample, result);
     }
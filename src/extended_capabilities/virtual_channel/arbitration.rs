@@ -0,0 +1,162 @@
+//! Arbitration tables common to Virtual Channel and Multi-Function Virtual Channel
+//!
+//! Both capabilities locate their tables by offset in DQWORDs from the Extended Capability
+//! Header, while parsers receive capability data after the header.
+
+use core::slice;
+
+use snafu::Snafu;
+
+use super::{VcArbitrationSelect, VcArbitrationTable};
+use crate::extended_capabilities::ECH_BYTES;
+
+/// Numeral unit for VC Arbitration Table Offset and Port/Function Arbitration Table Offset
+const DQWORD: usize = 16;
+
+/// VC Arbitration Table, Port Arbitration Table and Function Arbitration Table errors
+#[derive(Snafu, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArbitrationTableError {
+    #[snafu(display("arbitration select {value:#05b} is reserved"))]
+    ReservedSelect { value: u8 },
+    #[snafu(display("table offset is 0, but {entries_number} entries are expected"))]
+    Offset { entries_number: usize },
+    #[snafu(display("table [{start:#x}..{end:#x}] is out of capability data ({size} bytes)"))]
+    Data {
+        start: usize,
+        end: usize,
+        size: usize,
+    },
+}
+
+/// Table data of `entries_number` entries located at `offset` DQWORDs from the header
+///
+/// `data` is capability data after the Extended Capability Header, error positions are
+/// reported from the header.
+pub(crate) fn table_data(
+    data: &[u8],
+    offset: u8,
+    entries_number: usize,
+    entry_size_bits: usize,
+) -> Result<&[u8], ArbitrationTableError> {
+    // Hardware fixed arbitration does not use table
+    if entries_number == 0 {
+        return Ok(&[]);
+    }
+    // Offset is counted from the Extended Capability Header, 0 means table is absent
+    let start = (offset as usize * DQWORD)
+        .checked_sub(ECH_BYTES)
+        .ok_or(ArbitrationTableError::Offset { entries_number })?;
+    let end = start + entry_size_bits * entries_number / 8;
+    data.get(start..end).ok_or(ArbitrationTableError::Data {
+        start: start + ECH_BYTES,
+        end: end + ECH_BYTES,
+        size: data.len() + ECH_BYTES,
+    })
+}
+
+/// VC Arbitration Table for the currently selected VC Arbitration
+pub(crate) fn vc_arbitration_table<'a>(
+    data: &'a [u8],
+    offset: u8,
+    select: &VcArbitrationSelect,
+) -> Result<VcArbitrationTable<'a>, ArbitrationTableError> {
+    if let VcArbitrationSelect::Reserved(value) = select {
+        return Err(ArbitrationTableError::ReservedSelect { value: *value });
+    }
+    let entries_number = select.vc_arbitration_table_length();
+    // VC Arbitration Table entry length is 4 bits, so there are 2 entries in one byte
+    table_data(data, offset, entries_number, 4).map(VcArbitrationTable::new)
+}
+
+/// Iterator through entries packed into bytes starting from the least significant bits
+#[derive(Debug, Clone)]
+pub(crate) struct PackedEntries<'a> {
+    data: slice::Iter<'a, u8>,
+    entry_size_bits: usize,
+    shift: usize,
+    byte: u8,
+}
+
+impl<'a> PackedEntries<'a> {
+    /// `entry_size_bits` should be 1, 2, 4 or 8, there are no entries of any other size
+    pub fn new(data: &'a [u8], entry_size_bits: usize) -> Self {
+        let data = if matches!(entry_size_bits, 1 | 2 | 4 | 8) {
+            data
+        } else {
+            &[]
+        };
+        Self {
+            data: data.iter(),
+            entry_size_bits,
+            shift: 0,
+            byte: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for PackedEntries<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.entry_size_bits == 8 {
+            self.data.next().copied()
+        } else {
+            if self.shift == 0 {
+                self.byte = *self.data.next()?;
+            }
+            let init_mask = !(u8::MAX << self.entry_size_bits);
+            let mask = init_mask << self.shift;
+            let result = (self.byte & mask) >> self.shift;
+            self.shift = (self.shift + self.entry_size_bits) % 8;
+            Some(result)
+        }
+    }
+}
+
+impl<'a> PartialEq for PackedEntries<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data.clone().eq(other.data.clone())
+            && self.entry_size_bits == other.entry_size_bits
+            && self.shift == other.shift
+            && (self.shift == 0 || self.byte == other.byte)
+    }
+}
+
+impl<'a> Eq for PackedEntries<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn table_data_bounds() {
+        let data = [0u8; 0x1c];
+        assert_eq!(Ok(&[][..]), table_data(&data, 0, 0, 8), "No entries");
+        assert_eq!(
+            Err(ArbitrationTableError::Offset { entries_number: 32 }),
+            table_data(&data, 0, 32, 1)
+        );
+        assert_eq!(Ok(&data[0x0c..0x10]), table_data(&data, 1, 32, 1));
+        assert_eq!(
+            Err(ArbitrationTableError::Data {
+                start: 0x10,
+                end: 0x30,
+                size: 0x20
+            }),
+            table_data(&data, 1, 32, 8)
+        );
+    }
+
+    #[test]
+    fn packed_entries() {
+        let data = [0b1110_0100, 0xff];
+        assert_eq!(
+            vec![0, 1, 2, 3, 3, 3, 3, 3],
+            PackedEntries::new(&data, 2).collect::<Vec<_>>()
+        );
+        assert_eq!(0, PackedEntries::new(&data, 3).count());
+    }
+}
@@ -0,0 +1,408 @@
+/*!
+## Plain-old-data export
+
+Decoded structures of the crate are rich Rust enums, which are not usable across a C boundary.
+This module mirrors the most often needed results with `#[repr(C)]` structures of plain
+integers:
+- [HeaderSummary]: identification and class of the function
+- [LinkSummary]: PCI Express Link capabilities and status
+- [CapabilityEntry]: an entry of the capabilities or extended capabilities list
+
+Mirrors are built with [From] conversions from the decoded structures and never change layout
+between crate versions with the same major number. Boolean values are encoded as `0`/`1`,
+enumerations as their register encodings.
+
+## C interface
+
+The module is enabled with `ffi` feature and exports `extern "C"` functions decoding a raw
+configuration space image:
+- [pcics_header_summary]
+- [pcics_link_summary]
+- [pcics_capability_list]
+
+Functions return a negative value on invalid arguments or data. The crate itself is `no_std`
+and has no panic handler, so it can't be built as a static library directly. The `pcics-ffi`
+wrapper crate in `ffi/` directory links the functions with `std` into a static library:
+`cargo build -p pcics-ffi --release` produces `target/release/libpcics_ffi.a`.
+
+## Examples
+
+```rust
+# use pcics::{ffi::*, ConfigurationSpace};
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+
+let header = HeaderSummary::from(&cs.header);
+assert_eq!((0x8086, 0x2030), (header.vendor_id, header.device_id));
+assert_eq!((0x06, 0x04, 1), (header.class_base, header.class_sub, header.header_type));
+
+let entries = capability_entries(&cs).collect::<Vec<_>>();
+assert_eq!(CapabilityEntry { offset: 0x40, id: 0x0d, version: 0, extended: 0 }, entries[0]);
+assert_eq!(CapabilityEntry { offset: 0x100, id: 0x0b, version: 1, extended: 1 }, entries[4]);
+```
+*/
+
+use crate::{
+    capabilities::{pci_express::Link, Capability, CapabilityKind},
+    extended_capabilities::{ExtendedCapability, ExtendedCapabilityKind},
+    header::HeaderType,
+    ConfigurationSpace, Header,
+};
+
+/// Identification and class of the function
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderSummary {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Subsystem Vendor ID of Type 00h header, `0` for other header types
+    pub sub_vendor_id: u16,
+    /// Subsystem ID of Type 00h header, `0` for other header types
+    pub sub_device_id: u16,
+    pub command: u16,
+    pub status: u16,
+    pub class_base: u8,
+    pub class_sub: u8,
+    pub class_interface: u8,
+    pub revision_id: u8,
+    /// Header Type without the Multi-Function Device bit
+    pub header_type: u8,
+    pub is_multi_function: u8,
+    pub capabilities_pointer: u8,
+    pub interrupt_line: u8,
+    pub interrupt_pin: u8,
+}
+
+impl From<&Header> for HeaderSummary {
+    fn from(header: &Header) -> Self {
+        let (sub_vendor_id, sub_device_id) = match &header.header_type {
+            HeaderType::Normal(normal) => (normal.sub_vendor_id, normal.sub_device_id),
+            _ => (0, 0),
+        };
+        Self {
+            vendor_id: header.vendor_id,
+            device_id: header.device_id,
+            sub_vendor_id,
+            sub_device_id,
+            command: header.command.clone().into(),
+            status: header.status.clone().into(),
+            class_base: header.class_code.base,
+            class_sub: header.class_code.sub,
+            class_interface: header.class_code.interface,
+            revision_id: header.revision_id,
+            header_type: (&header.header_type).into(),
+            is_multi_function: header.is_multi_function.into(),
+            capabilities_pointer: header.capabilities_pointer,
+            interrupt_line: header.interrupt_line,
+            interrupt_pin: header.interrupt_pin.into(),
+        }
+    }
+}
+
+/// PCI Express Link capabilities and status
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LinkSummary {
+    /// Max Link Speed encoding
+    pub max_link_speed: u8,
+    /// Maximum Link Width in lanes
+    pub max_link_width: u8,
+    /// Current Link Speed encoding
+    pub current_link_speed: u8,
+    /// Negotiated Link Width in lanes
+    pub negotiated_link_width: u8,
+    pub port_number: u8,
+    pub link_training: u8,
+    pub data_link_layer_link_active: u8,
+    pub link_disable: u8,
+    /// Scaled Flow Control of the Data Link Feature Capability is active, `2` if unknown:
+    /// there is no Data Link Feature Capability or the remote features are not received
+    pub scaled_flow_control: u8,
+}
+
+impl LinkSummary {
+    /// Unknown [scaled_flow_control](Self::scaled_flow_control)
+    pub const UNKNOWN: u8 = 2;
+}
+
+impl From<&Link> for LinkSummary {
+    fn from(link: &Link) -> Self {
+        let capabilities = &link.capabilities;
+        let status = &link.status;
+        Self {
+            max_link_speed: capabilities.max_link_speed.into(),
+            max_link_width: capabilities.maximum_link_width.into(),
+            current_link_speed: status.current_link_speed.into(),
+            negotiated_link_width: status.negotiated_link_width.into(),
+            port_number: capabilities.port_number,
+            link_training: status.link_training.into(),
+            data_link_layer_link_active: status.data_link_layer_link_active.into(),
+            link_disable: link.control.link_disable.into(),
+            scaled_flow_control: Self::UNKNOWN,
+        }
+    }
+}
+
+/// An entry of the capabilities or extended capabilities list
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityEntry {
+    /// Offset in configuration space
+    pub offset: u16,
+    /// Capability ID or Extended Capability ID
+    pub id: u16,
+    /// Extended Capability Version, `0` for capabilities
+    pub version: u8,
+    /// `1` for extended capabilities
+    pub extended: u8,
+}
+
+impl From<&Capability<'_>> for CapabilityEntry {
+    fn from(cap: &Capability<'_>) -> Self {
+        Self {
+            offset: cap.pointer.get(),
+            id: cap.id().into(),
+            version: 0,
+            extended: 0,
+        }
+    }
+}
+
+impl From<&ExtendedCapability<'_>> for CapabilityEntry {
+    fn from(ecap: &ExtendedCapability<'_>) -> Self {
+        Self {
+            offset: ecap.offset.get(),
+            id: ecap.id(),
+            version: ecap.version,
+            extended: 1,
+        }
+    }
+}
+
+/// Decoded capabilities followed by decoded extended capabilities of the function, broken
+/// entries are skipped
+pub fn capability_entries<'a>(
+    cs: &'a ConfigurationSpace<'a>,
+) -> impl Iterator<Item = CapabilityEntry> + 'a {
+    let caps = cs
+        .capabilities()
+        .filter_map(Result::ok)
+        .map(|cap| CapabilityEntry::from(&cap));
+    let ecaps = cs
+        .extended_capabilities()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|ecap| CapabilityEntry::from(&ecap));
+    caps.chain(ecaps)
+}
+
+/// Link of the PCI Express Capability, `None` if there is no PCI Express Capability or the
+/// function has no Link
+///
+/// Scaled Flow Control state is taken from the Data Link Feature Capability if present.
+pub fn link_summary(cs: &ConfigurationSpace<'_>) -> Option<LinkSummary> {
+    let mut summary = cs
+        .capabilities()
+        .filter_map(Result::ok)
+        .find_map(|cap| match cap.kind {
+            CapabilityKind::PciExpress(pcie) => {
+                Some(pcie.device_type.link().map(LinkSummary::from))
+            }
+            _ => None,
+        })
+        .flatten()?;
+    let scaled_flow_control = cs
+        .extended_capabilities()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .find_map(|ecap| match ecap.kind {
+            ExtendedCapabilityKind::DataLinkFeature(dlf) => dlf.is_scaled_flow_control_active(),
+            _ => None,
+        });
+    if let Some(active) = scaled_flow_control {
+        summary.scaled_flow_control = active.into();
+    }
+    Some(summary)
+}
+
+unsafe fn configuration_space<'a>(data: *const u8, len: usize) -> Option<ConfigurationSpace<'a>> {
+    if data.is_null() {
+        return None;
+    }
+    let data = core::slice::from_raw_parts(data, len);
+    ConfigurationSpace::try_from(data).ok()
+}
+
+/// Fills `out` with the header summary of configuration space image `data` of `len` bytes
+///
+/// Returns `0` on success, `-1` if a pointer is null or the image is shorter than the header.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes, `out` must be valid for a write of
+/// [HeaderSummary].
+#[no_mangle]
+pub unsafe extern "C" fn pcics_header_summary(
+    data: *const u8,
+    len: usize,
+    out: *mut HeaderSummary,
+) -> i32 {
+    match configuration_space(data, len) {
+        Some(cs) if !out.is_null() => {
+            out.write(HeaderSummary::from(&cs.header));
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Fills `out` with the PCI Express Link summary of configuration space image `data` of `len`
+/// bytes
+///
+/// Returns `0` on success, `1` if the function has no PCI Express Link, `-1` if a pointer is
+/// null or the image is shorter than the header.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes, `out` must be valid for a write of
+/// [LinkSummary].
+#[no_mangle]
+pub unsafe extern "C" fn pcics_link_summary(
+    data: *const u8,
+    len: usize,
+    out: *mut LinkSummary,
+) -> i32 {
+    match configuration_space(data, len) {
+        Some(cs) if !out.is_null() => match link_summary(&cs) {
+            Some(link) => {
+                out.write(link);
+                0
+            }
+            None => 1,
+        },
+        _ => -1,
+    }
+}
+
+/// Writes at most `capacity` capability list entries of configuration space image `data` of
+/// `len` bytes into `out`
+///
+/// Returns the total number of entries, which is larger than `capacity` if `out` is too small,
+/// or `-1` if a pointer is null or the image is shorter than the header. `out` may be null if
+/// `capacity` is `0`.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes, `out` must be valid for writes of
+/// `capacity` [CapabilityEntry]s.
+#[no_mangle]
+pub unsafe extern "C" fn pcics_capability_list(
+    data: *const u8,
+    len: usize,
+    out: *mut CapabilityEntry,
+    capacity: usize,
+) -> isize {
+    let cs = match configuration_space(data, len) {
+        Some(cs) if !out.is_null() || capacity == 0 => cs,
+        _ => return -1,
+    };
+    let mut count = 0;
+    for entry in capability_entries(&cs) {
+        if count < capacity {
+            out.add(count).write(entry);
+        }
+        count += 1;
+    }
+    count as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    const DATA: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/device/8086_2030/config"
+    ));
+
+    #[test]
+    fn summaries() {
+        let cs = ConfigurationSpace::try_from(DATA).unwrap();
+        let header = HeaderSummary::from(&cs.header);
+        assert_eq!(0x0010, header.status & 0x0010, "Capabilities List");
+        assert_eq!(0x40, header.capabilities_pointer);
+        let link = link_summary(&cs).unwrap();
+        assert_eq!(1, link.data_link_layer_link_active);
+        assert_eq!(LinkSummary::UNKNOWN, link.scaled_flow_control);
+        let result = capability_entries(&cs)
+            .map(|e| (e.offset, e.id, e.extended))
+            .collect::<Vec<_>>();
+        let sample = vec![
+            (0x40, 0x0d, 0),
+            (0x60, 0x05, 0),
+            (0x90, 0x10, 0),
+            (0xe0, 0x01, 0),
+        ];
+        assert_eq!(sample, result[..4]);
+        assert_eq!(12, result.len());
+
+        // Root Port with Data Link Feature Capability, remote features are not received yet
+        let mut data = crate::synthetic::root_port(false);
+        data[0x100..0x10c].copy_from_slice(&[
+            0x25, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let link = link_summary(&cs).unwrap();
+        assert_eq!(LinkSummary::UNKNOWN, link.scaled_flow_control);
+        // Remote Port does not support Scaled Flow Control
+        data[0x10b] = 0x80;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(0, link_summary(&cs).unwrap().scaled_flow_control);
+        data[0x108] = 0x01;
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert_eq!(1, link_summary(&cs).unwrap().scaled_flow_control);
+    }
+
+    #[test]
+    fn extern_functions() {
+        let mut header = HeaderSummary::default();
+        let rc = unsafe { pcics_header_summary(DATA.as_ptr(), DATA.len(), &mut header) };
+        assert_eq!(0, rc);
+        let cs = ConfigurationSpace::try_from(DATA).unwrap();
+        assert_eq!(HeaderSummary::from(&cs.header), header);
+
+        let mut link = LinkSummary::default();
+        let rc = unsafe { pcics_link_summary(DATA.as_ptr(), DATA.len(), &mut link) };
+        assert_eq!((0, link_summary(&cs).unwrap()), (rc, link));
+        // Header only, no capabilities
+        let rc = unsafe { pcics_link_summary(DATA.as_ptr(), 0x40, &mut link) };
+        assert_eq!(1, rc);
+
+        let count =
+            unsafe { pcics_capability_list(DATA.as_ptr(), DATA.len(), core::ptr::null_mut(), 0) };
+        assert_eq!(12, count);
+        let mut entries = [CapabilityEntry::default(); 2];
+        let count = unsafe {
+            pcics_capability_list(
+                DATA.as_ptr(),
+                DATA.len(),
+                entries.as_mut_ptr(),
+                entries.len(),
+            )
+        };
+        assert_eq!(12, count);
+        assert_eq!(capability_entries(&cs).take(2).collect::<Vec<_>>(), entries);
+
+        let rc = unsafe { pcics_header_summary(core::ptr::null(), 0, &mut header) };
+        assert_eq!(-1, rc);
+        let rc = unsafe { pcics_header_summary(DATA.as_ptr(), 0x20, &mut header) };
+        assert_eq!(-1, rc);
+    }
+}
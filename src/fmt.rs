@@ -0,0 +1,261 @@
+/*!
+## Formatted output without allocation
+
+Display implementations of the crate write straight into [core::fmt::Formatter] and never
+allocate, so any of them can be written to a [Write] sink like a UART with `write!`. The
+helpers of this module render lspci-like summaries of a function and guarantee the size of the
+output: [BoundedWriter] passes at most `limit` bytes to the sink and cuts the rest off at a
+character boundary.
+
+## Examples
+
+```rust
+# use pcics::{fmt::*, ConfigurationSpace};
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+// String stands for any core::fmt::Write sink
+let mut out = String::new();
+let written = write_function(&mut out, &cs, 1024).unwrap();
+assert_eq!(written, out.len());
+assert!(out.starts_with("0604: 8086:2030 (rev 04)\n\tCapabilities: [40] 0d\n"));
+assert!(out.ends_with("\tCapabilities: [300 v1] 000b\n"));
+
+// Output does not fit into 16 bytes
+let mut out = String::new();
+let result = write_function(&mut out, &cs, 16);
+assert_eq!(Err(FmtError::Truncated { written: 16 }), result);
+assert_eq!("0604: 8086:2030 ", out);
+```
+*/
+
+use core::fmt::{self, Write};
+
+use snafu::prelude::*;
+
+use crate::ConfigurationSpace;
+
+/// Output did not complete
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FmtError {
+    #[snafu(display("output truncated after {written} bytes"))]
+    Truncated { written: usize },
+    #[snafu(display("writer failed after {written} bytes"))]
+    Writer { written: usize },
+}
+
+/// [Write] adapter passing at most `limit` bytes to the inner writer
+///
+/// A string which does not fit is cut at the last character boundary within the limit, the
+/// write fails and every following write fails without output.
+pub struct BoundedWriter<'w> {
+    inner: &'w mut dyn Write,
+    limit: usize,
+    written: usize,
+    truncated: bool,
+}
+
+impl<'w> BoundedWriter<'w> {
+    pub fn new(inner: &'w mut dyn Write, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            written: 0,
+            truncated: false,
+        }
+    }
+    /// Number of bytes passed to the inner writer
+    pub fn written(&self) -> usize {
+        self.written
+    }
+    /// Some output was cut off by the limit
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+    /// Outcome of writing with `result`, number of written bytes on success
+    fn finish(&self, result: fmt::Result) -> Result<usize, FmtError> {
+        let written = self.written;
+        match result {
+            Ok(()) => Ok(written),
+            Err(_) if self.truncated => TruncatedSnafu { written }.fail(),
+            Err(_) => WriterSnafu { written }.fail(),
+        }
+    }
+}
+
+impl fmt::Debug for BoundedWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedWriter")
+            .field("limit", &self.limit)
+            .field("written", &self.written)
+            .field("truncated", &self.truncated)
+            .finish()
+    }
+}
+
+impl Write for BoundedWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Err(fmt::Error);
+        }
+        let remaining = self.limit - self.written;
+        let end = if s.len() <= remaining {
+            s.len()
+        } else {
+            self.truncated = true;
+            (0..=remaining)
+                .rev()
+                .find(|&end| s.is_char_boundary(end))
+                .unwrap_or_default()
+        };
+        let head = s.get(..end).unwrap_or_default();
+        self.inner.write_str(head)?;
+        self.written += end;
+        if self.truncated {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Writes at most `limit` bytes of `value` to `out`, returns the number of written bytes
+pub fn write_display(
+    out: &mut dyn Write,
+    value: &dyn fmt::Display,
+    limit: usize,
+) -> Result<usize, FmtError> {
+    let mut w = BoundedWriter::new(out, limit);
+    let result = write!(w, "{}", value);
+    w.finish(result)
+}
+
+/// Writes at most `limit` bytes of lspci-like summary of the function to `out`, returns the
+/// number of written bytes
+///
+/// The first line holds the address if it is known, the class, the IDs and the revision, the
+/// next lines list the capabilities with their IDs.
+pub fn write_function(
+    out: &mut dyn Write,
+    cs: &ConfigurationSpace,
+    limit: usize,
+) -> Result<usize, FmtError> {
+    let mut w = BoundedWriter::new(out, limit);
+    let result = function(&mut w, cs);
+    w.finish(result)
+}
+
+fn function(w: &mut dyn Write, cs: &ConfigurationSpace) -> fmt::Result {
+    let header = &cs.header;
+    let class = &header.class_code;
+    let ids = format_args!(
+        "{:02x}{:02x}: {:04x}:{:04x}",
+        class.base, class.sub, header.vendor_id, header.device_id
+    );
+    write!(w, "{}", cs.located(ids))?;
+    if header.revision_id != 0 {
+        write!(w, " (rev {:02x})", header.revision_id)?;
+    }
+    writeln!(w)?;
+    for cap in cs.capabilities() {
+        match cap {
+            Ok(cap) => writeln!(w, "\tCapabilities: [{}] {:02x}", cap.pointer, cap.id())?,
+            Err(e) => writeln!(w, "\tCapabilities: <{}>", e)?,
+        }
+    }
+    for ecap in cs.extended_capabilities().into_iter().flatten() {
+        match ecap {
+            Ok(ecap) => writeln!(
+                w,
+                "\tCapabilities: [{} v{}] {:04x}",
+                ecap.offset,
+                ecap.version,
+                ecap.id()
+            )?,
+            Err(e) => writeln!(w, "\tCapabilities: <{}>", e)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration_space::FunctionAddress;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    /// Sink of a fixed capacity, as a UART buffer
+    struct Buffer<const N: usize> {
+        data: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> Write for Buffer<N> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bounded_writer() {
+        let mut out = String::new();
+        let mut w = BoundedWriter::new(&mut out, 5);
+        assert!(w.write_str("ab").is_ok());
+        // Multi-byte character does not fit
+        assert!(w.write_str("cd\u{00b5}").is_err());
+        assert!(w.is_truncated());
+        assert!(w.write_str("e").is_err());
+        assert_eq!(4, w.written());
+        assert_eq!("abcd", out);
+
+        assert_eq!(Ok(3), write_display(&mut out, &0x123, 8));
+        assert_eq!(
+            Err(FmtError::Truncated { written: 2 }),
+            write_display(&mut out, &4567, 2)
+        );
+        assert_eq!("abcd29145", out);
+    }
+
+    #[test]
+    fn function_summary() {
+        let data = crate::synthetic::root_port(false);
+        let address = FunctionAddress::new(0, 0, 0x1c, 0).unwrap();
+        let cs = ConfigurationSpace::try_from(data.as_slice())
+            .unwrap()
+            .with_address(address);
+        let mut out = String::new();
+        let written = write_function(&mut out, &cs, 1024).unwrap();
+        assert_eq!(out.len(), written);
+        assert!(out.starts_with("0000:00:1c.0 0604: "), "{}", out);
+        assert!(out.contains("\tCapabilities: ["));
+
+        // Writer failure is not a truncation
+        let mut buffer = Buffer::<2> {
+            data: [0; 2],
+            len: 0,
+        };
+        let result = write_function(&mut buffer, &cs, 1024);
+        assert!(
+            matches!(result, Err(FmtError::Writer { .. })),
+            "{:?}",
+            result
+        );
+        let mut buffer = Buffer::<8> {
+            data: [0; 8],
+            len: 0,
+        };
+        let result = write_function(&mut buffer, &cs, 8);
+        assert_eq!(Err(FmtError::Truncated { written: 8 }), result);
+        assert_eq!(b"0000:00:", &buffer.data);
+    }
+}
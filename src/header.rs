@@ -11,7 +11,7 @@ supports.
 <pre>
 <a href="struct.Header.html">Header</a>
 ├─ <a href="struct.Command.html">Command</a>
-├─ <a href="struct.Status.html">Status</a>
+├─ <a href="type.PrimaryStatus.html">PrimaryStatus</a>
 │  └─ <a href="enum.DevselTiming.html">DevselTiming</a>
 ├─ <a href="struct.ClassCode.html">ClassCode</a>
 ├─ <a href="struct.BuiltInSelfTest.html">BuiltInSelfTest</a>
@@ -26,7 +26,7 @@ supports.
 │   │  │  └─ <a href="struct.BaseAddress.html">BaseAddress (0 .. 2)</a>
 │   │  │     └─ <a href="enum.BaseAddressType.html">BaseAddressType</a>
 │   │  ├─ <a href="enum.BridgeIoAddressRange.html">BridgeIoAddressRange</a>
-│   │  ├─ <a href="struct.Status.html">Status</a>
+│   │  ├─ <a href="type.SecondaryStatus.html">SecondaryStatus</a>
 │   │  │  └─ <a href="enum.DevselTiming.html">DevselTiming</a>
 │   │  ├─ <a href="enum.BridgePrefetchableMemory.html">BridgePrefetchableMemory</a>
 │   │  ├─ <a href="struct.ExpansionRom.html">ExpansionRom</a>
@@ -35,7 +35,7 @@ supports.
 │      ├─ <a href="struct.BaseAddresses.html">BaseAddresses<1></a>
 │      │  └─ <a href="struct.BaseAddress.html">BaseAddress (0 .. 1)</a>
 │      │     └─ <a href="enum.BaseAddressType.html">BaseAddressType</a>
-│      ├─ <a href="struct.Status.html">Status</a>
+│      ├─ <a href="type.CardbusStatus.html">CardbusStatus</a>
 │      │  └─ <a href="enum.DevselTiming.html">DevselTiming</a>
 │      ├─ <a href="enum.IoAccessAddressRange.html">IoAccessAddressRange x 2</a>
 │      └─ <a href="struct.CardbusBridgeControl.html">CardbusBridgeControl</a>
@@ -111,18 +111,24 @@ let sample = Header {
 };
 assert_eq!(sample, result);
 
+// Header is serialized back into the same bytes
+assert_eq!(data, <[u8; Header::TOTAL_SIZE]>::from(&result));
 ```
 */
 
 
-use core::array::TryFromSliceError;
+use core::{array::TryFromSliceError, ops::RangeInclusive, time::Duration};
+
+use crate::{bdf::Bus, register::register};
 
 mod command;
-pub use command::Command;
+pub use command::{Command, IntxState};
 
 mod status;
-use heterob::{endianness::{Le, LeBytesInto}, P11, P22, P17, Seq, P4, bit_numbering::Lsb, P3};
-pub use status::{Status, DevselTiming};
+use heterob::{endianness::{Le, LeBytesInto}, P11, P22, Seq, bit_numbering::Lsb, P3};
+#[cfg(feature = "cardbus")]
+use heterob::{P17, P4};
+pub use status::{Status, PrimaryStatus, SecondaryStatus, CardbusStatus, DevselTiming};
 
 mod class_code;
 pub use class_code::ClassCode;
@@ -137,7 +143,9 @@ pub use bar::{
 mod bridge_control;
 pub use bridge_control::BridgeControl;
 
+#[cfg(feature = "cardbus")]
 mod cardbus_bridge_control;
+#[cfg(feature = "cardbus")]
 pub use cardbus_bridge_control::CardbusBridgeControl;
 
 
@@ -151,7 +159,7 @@ pub struct Header {
     /// Identifies the particular device. Where valid IDs are allocated by the vendor
     pub device_id: u16,
     pub command: Command,
-    pub status: Status<'P'>,
+    pub status: PrimaryStatus,
     /// Device specific revision identifier.
     pub revision_id: u8,
     pub class_code: ClassCode,
@@ -263,9 +271,9 @@ impl From<[u8; Header::TOTAL_SIZE]> for Header {
                         interrupt_pin,
                         HeaderType::Bridge(Bridge {
                             base_addresses: BaseAddresses::new(base_addresses.le_bytes_into()),
-                            primary_bus_number,
-                            secondary_bus_number,
-                            subordinate_bus_number,
+                            primary_bus_number: Bus(primary_bus_number),
+                            secondary_bus_number: Bus(secondary_bus_number),
+                            subordinate_bus_number: Bus(subordinate_bus_number),
                             secondary_latency_timer,
                             io_address_range: BridgeIoAddressRange::new(
                                 io_base,
@@ -287,6 +295,7 @@ impl From<[u8; Header::TOTAL_SIZE]> for Header {
                         }),
                     )
                 }
+                #[cfg(feature = "cardbus")]
                 0x02 => {
                     let Le((
                         base_addresses,
@@ -319,9 +328,9 @@ impl From<[u8; Header::TOTAL_SIZE]> for Header {
                         HeaderType::Cardbus(Cardbus {
                             base_addresses: BaseAddresses::new(base_addresses.le_bytes_into()),
                             secondary_status: From::<u16>::from(secondary_status),
-                            pci_bus_number,
-                            cardbus_bus_number,
-                            subordinate_bus_number,
+                            pci_bus_number: Bus(pci_bus_number),
+                            cardbus_bus_number: Bus(cardbus_bus_number),
+                            subordinate_bus_number: Bus(subordinate_bus_number),
                             cardbus_latency_timer,
                             memory_base_address_0,
                             memory_limit_address_0,
@@ -373,6 +382,141 @@ impl TryFrom<&[u8]> for Header {
     }
 }
 
+/// Packs the header back into registers
+///
+/// Reserved bits and registers are zero. Cardbus optional registers located after the
+/// predefined header are not written, same as they are not parsed.
+impl From<&Header> for [u8; Header::TOTAL_SIZE] {
+    fn from(header: &Header) -> Self {
+        let mut b = HeaderBytes([0; Header::TOTAL_SIZE]);
+        b.set(0x00, &header.vendor_id.to_le_bytes());
+        b.set(0x02, &header.device_id.to_le_bytes());
+        b.set(0x04, &u16::from(header.command.clone()).to_le_bytes());
+        b.set(0x06, &u16::from(header.status.clone()).to_le_bytes());
+        b.set(0x08, &[header.revision_id]);
+        b.set(0x09, &<[u8; 3]>::from(header.class_code.clone()));
+        b.set(0x0c, &[header.cache_line_size, header.latency_timer]);
+        let htype = u8::from(&header.header_type) | (header.is_multi_function as u8) << 7;
+        b.set(0x0e, &[htype, u8::from(header.bist.clone())]);
+        let capabilities_pointer = header.capabilities_pointer & !0b11;
+        let interrupt = [header.interrupt_line, u8::from(header.interrupt_pin)];
+        match &header.header_type {
+            HeaderType::Normal(normal) => {
+                b.set_dwords(0x10, &normal.base_addresses.orig());
+                b.set(0x28, &normal.cardbus_cis_pointer.to_le_bytes());
+                b.set(0x2c, &normal.sub_vendor_id.to_le_bytes());
+                b.set(0x2e, &normal.sub_device_id.to_le_bytes());
+                b.set(0x30, &u32::from(normal.expansion_rom.clone()).to_le_bytes());
+                b.set(0x34, &[capabilities_pointer]);
+                b.set(0x3c, &interrupt);
+                b.set(0x3e, &[normal.min_grant, normal.max_latency]);
+            }
+            HeaderType::Bridge(bridge) => {
+                b.set_dwords(0x10, &bridge.base_addresses.orig());
+                b.set(
+                    0x18,
+                    &[
+                        bridge.primary_bus_number.get(),
+                        bridge.secondary_bus_number.get(),
+                        bridge.subordinate_bus_number.get(),
+                        bridge.secondary_latency_timer,
+                    ],
+                );
+                let (io_base, io_limit, io_base_upper, io_limit_upper) =
+                    bridge.io_address_range.registers();
+                b.set(0x1c, &[io_base, io_limit]);
+                b.set(
+                    0x1e,
+                    &u16::from(bridge.secondary_status.clone()).to_le_bytes(),
+                );
+                b.set(0x20, &bridge.memory_base.to_le_bytes());
+                b.set(0x22, &bridge.memory_limit.to_le_bytes());
+                let (base, limit, base_upper, limit_upper) = bridge.prefetchable_memory.registers();
+                b.set(0x24, &base.to_le_bytes());
+                b.set(0x26, &limit.to_le_bytes());
+                b.set(0x28, &base_upper.to_le_bytes());
+                b.set(0x2c, &limit_upper.to_le_bytes());
+                b.set(0x30, &io_base_upper.to_le_bytes());
+                b.set(0x32, &io_limit_upper.to_le_bytes());
+                b.set(0x34, &[capabilities_pointer]);
+                b.set(0x38, &u32::from(bridge.expansion_rom.clone()).to_le_bytes());
+                b.set(0x3c, &interrupt);
+                b.set(
+                    0x3e,
+                    &u16::from(bridge.bridge_control.clone()).to_le_bytes(),
+                );
+            }
+            #[cfg(feature = "cardbus")]
+            HeaderType::Cardbus(cardbus) => {
+                b.set_dwords(0x10, &cardbus.base_addresses.orig());
+                b.set(0x14, &[capabilities_pointer]);
+                b.set(
+                    0x16,
+                    &u16::from(cardbus.secondary_status.clone()).to_le_bytes(),
+                );
+                b.set(
+                    0x18,
+                    &[
+                        cardbus.pci_bus_number.get(),
+                        cardbus.cardbus_bus_number.get(),
+                        cardbus.subordinate_bus_number.get(),
+                        cardbus.cardbus_latency_timer,
+                    ],
+                );
+                b.set_dwords(
+                    0x1c,
+                    &[
+                        cardbus.memory_base_address_0,
+                        cardbus.memory_limit_address_0,
+                        cardbus.memory_base_address_1,
+                        cardbus.memory_limit_address_1,
+                    ],
+                );
+                let [[base_0, base_upper_0], [limit_0, limit_upper_0]] =
+                    From::from(cardbus.io_access_address_range_0.clone());
+                let [[base_1, base_upper_1], [limit_1, limit_upper_1]] =
+                    From::from(cardbus.io_access_address_range_1.clone());
+                let io = [
+                    base_0,
+                    base_upper_0,
+                    limit_0,
+                    limit_upper_0,
+                    base_1,
+                    base_upper_1,
+                    limit_1,
+                    limit_upper_1,
+                ];
+                for (n, word) in io.iter().enumerate() {
+                    b.set(0x2c + n * 2, &word.to_le_bytes());
+                }
+                b.set(0x3c, &interrupt);
+                b.set(
+                    0x3e,
+                    &u16::from(cardbus.bridge_control.clone()).to_le_bytes(),
+                );
+            }
+            HeaderType::Reserved(_) => (),
+        }
+        b.0
+    }
+}
+
+/// Header image under construction
+struct HeaderBytes([u8; Header::TOTAL_SIZE]);
+
+impl HeaderBytes {
+    fn set(&mut self, offset: usize, bytes: &[u8]) {
+        if let Some(dst) = self.0.get_mut(offset..offset + bytes.len()) {
+            dst.copy_from_slice(bytes);
+        }
+    }
+    fn set_dwords(&mut self, offset: usize, dwords: &[u32]) {
+        for (n, dword) in dwords.iter().enumerate() {
+            self.set(offset + n * 4, &dword.to_le_bytes());
+        }
+    }
+}
+
 
 
 
@@ -384,6 +528,7 @@ impl TryFrom<&[u8]> for Header {
 pub enum HeaderType {
     Normal(Normal),
     Bridge(Bridge),
+    #[cfg(feature = "cardbus")]
     Cardbus(Cardbus),
     Reserved(u8),
 }
@@ -408,6 +553,7 @@ impl<'a> From<&'a HeaderType> for u8 {
         match data {
             HeaderType::Normal(_) => 0,
             HeaderType::Bridge(_) => 1,
+            #[cfg(feature = "cardbus")]
             HeaderType::Cardbus(_) => 2,
             HeaderType::Reserved(v) => *v,
         }
@@ -440,16 +586,16 @@ pub struct Bridge {
     /// Base Address Registers
     pub base_addresses: BaseAddresses<2>,
     /// Primary Bus Number
-    pub primary_bus_number: u8,
+    pub primary_bus_number: Bus,
     /// Secondary Bus Number
-    pub secondary_bus_number: u8,
+    pub secondary_bus_number: Bus,
     /// Subordinate Bus Numbe
-    pub subordinate_bus_number: u8,
+    pub subordinate_bus_number: Bus,
     /// Secondary Latency Timer
     pub secondary_latency_timer: u8,
     pub io_address_range: BridgeIoAddressRange,
     /// Secondary Status
-    pub secondary_status: Status<'B'>,
+    pub secondary_status: SecondaryStatus,
     /// Memory Base
     pub memory_base: u16,
     /// Memory Limit
@@ -459,6 +605,14 @@ pub struct Bridge {
     pub expansion_rom: ExpansionRom,
     pub bridge_control: BridgeControl,
 }
+impl Bridge {
+    /// Buses below the bridge, from Secondary to Subordinate Bus Number
+    ///
+    /// The range is empty if the bridge is not configured.
+    pub fn bus_range(&self) -> RangeInclusive<Bus> {
+        self.secondary_bus_number..=self.subordinate_bus_number
+    }
+}
 
 /// The I/O Base and I/O Limit registers define an address range that is used by the bridge to
 /// determine when to forward I/O transactions from one interface to the other.
@@ -513,6 +667,23 @@ impl BridgeIoAddressRange {
             },
         }
     }
+    /// I/O Base, I/O Limit, I/O Base Upper 16 Bits and I/O Limit Upper 16 Bits registers, the
+    /// inverse of [new](Self::new)
+    pub fn registers(&self) -> (u8, u8, u16, u16) {
+        match *self {
+            Self::NotImplemented => (0, 0, 0, 0),
+            Self::IoAddr16 { base, limit } => {
+                ((base >> 8) as u8 & !0xf, (limit >> 8) as u8 & !0xf, 0, 0)
+            }
+            Self::IoAddr32 { base, limit } => (
+                (base >> 8) as u8 & !0xf | 0x01,
+                (limit >> 8) as u8 & !0xf | 0x01,
+                (base >> 16) as u16,
+                (limit >> 16) as u16,
+            ),
+            Self::Malformed { base, limit } | Self::Reserved { base, limit } => (base, limit, 0, 0),
+        }
+    }
 }
 
 /// The Prefetchable Memory Base and Prefetchable Memory Limit registers define a prefetchable
@@ -570,20 +741,41 @@ impl BridgePrefetchableMemory {
             },
         }
     }
+    /// Prefetchable Memory Base, Prefetchable Memory Limit, Prefetchable Base Upper 32 Bits and
+    /// Prefetchable Limit Upper 32 Bits registers, the inverse of [new](Self::new)
+    pub fn registers(&self) -> (u16, u16, u32, u32) {
+        match *self {
+            Self::NotImplemented => (0, 0, 0, 0),
+            Self::MemAddr32 { base, limit } => (
+                (base >> 16) as u16 & !0xf,
+                (limit >> 16) as u16 & !0xf,
+                0,
+                0,
+            ),
+            Self::MemAddr64 { base, limit } => (
+                (base >> 16) as u16 & !0xf | 0x01,
+                (limit >> 16) as u16 & !0xf | 0x01,
+                (base >> 32) as u32,
+                (limit >> 32) as u32,
+            ),
+            Self::Malformed { base, limit } | Self::Reserved { base, limit } => (base, limit, 0, 0),
+        }
+    }
 }
 
 /// PCI-to-CardBus bridge (Type 02h)
+#[cfg(feature = "cardbus")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cardbus {
     pub base_addresses: BaseAddresses<1>,
     /// Secondary status
-    pub secondary_status: Status<'C'>,
+    pub secondary_status: CardbusStatus,
     /// PCI Bus Number
-    pub pci_bus_number: u8,
+    pub pci_bus_number: Bus,
     /// CardBus Bus Number
-    pub cardbus_bus_number: u8,
+    pub cardbus_bus_number: Bus,
     /// Subordinate Bus Number
-    pub subordinate_bus_number: u8,
+    pub subordinate_bus_number: Bus,
     /// CardBus Latency Timer
     pub cardbus_latency_timer: u8,
     /// Memory Base #0
@@ -606,6 +798,7 @@ pub struct Cardbus {
     /// Reserved
     pub reserved: Option<[u8; Self::RESERVED_SIZE]>,
 }
+#[cfg(feature = "cardbus")]
 impl Cardbus {
     const RESERVED_SIZE: usize = 0x80 - 0x48;
     pub fn try_set_optional_registers(&mut self, slice: &[u8]) -> Result<(), TryFromSliceError> {
@@ -636,32 +829,53 @@ pub struct BuiltInSelfTest {
     /// Will return 0, after BIST execution, if the test completed successfully.
     pub completion_code: u8,
 }
-impl From<u8> for BuiltInSelfTest {
-    fn from(data: u8) -> Self {
-        Self {
-            is_capable: data & 0b1000_0000 != 0,
-            is_running: data & 0b0100_0000 != 0,
-            completion_code: data & 0b1111,
-        }
+register!(BuiltInSelfTest: u8 {
+    completion_code[0..4],
+    is_running[6],
+    is_capable[7],
+});
+impl BuiltInSelfTest {
+    /// System software should fail the device if BIST does not complete within 2 seconds
+    pub const TIMEOUT: Duration = Duration::from_secs(2);
+    /// Start BIST bit
+    pub const START: u8 = 0b0100_0000;
+    /// BIST register value starting the test, `None` if the device is not BIST capable
+    ///
+    /// Completion code is read-only, so the written value contains only Start BIST bit. The
+    /// test is complete when [is_running](Self::is_running) is reset, which should happen in
+    /// [TIMEOUT](Self::TIMEOUT).
+    pub fn start(&self) -> Option<u8> {
+        self.is_capable.then_some(Self::START)
     }
-}
-impl From<BuiltInSelfTest> for u8 {
-    fn from(bist: BuiltInSelfTest) -> Self {
-        let mut result = bist.completion_code & 0b1111;
-        if bist.is_capable {
-            result |= 0b1000_0000;
-        }
-        if bist.is_running {
-            result |= 0b0100_0000;
+    /// Interprets register value read after BIST was started
+    pub fn outcome(&self) -> BistOutcome {
+        match (self.is_capable, self.is_running, self.completion_code) {
+            (false, _, _) => BistOutcome::NotCapable,
+            (_, true, _) => BistOutcome::Running,
+            (_, _, 0) => BistOutcome::Passed,
+            (_, _, code) => BistOutcome::Failed(code),
         }
-        result
     }
 }
 
+/// BIST result according to Completion Code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BistOutcome {
+    /// Device does not support BIST
+    NotCapable,
+    /// BIST is not completed yet
+    Running,
+    /// Completion Code 0, the test completed successfully
+    Passed,
+    /// Non-zero device-specific failure Completion Code
+    Failed(u8),
+}
+
 
 /// Specifies which interrupt pin the device uses.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum InterruptPin {
+    #[default]
     Unused,
     IntA,
     IntB,
@@ -669,9 +883,6 @@ pub enum InterruptPin {
     IntD,
     Reserved(u8),
 }
-impl Default for InterruptPin {
-    fn default() -> Self { Self::Unused }
-}
 
 impl From<u8> for InterruptPin {
     fn from(data: u8) -> Self {
@@ -756,22 +967,30 @@ impl From<[u16; 4]> for IoAccessAddressRange {
         [[a,b],[c,d]].into()
 }
     }
-impl From<IoAccessAddressRange > for [[u16;2]; 2] {
+impl From<IoAccessAddressRange> for [[u16; 2]; 2] {
     fn from(data: IoAccessAddressRange) -> Self {
+        let cap = IoAccessAddressRange::IO_CAP_MASK;
         match data {
             IoAccessAddressRange::Addr16Bit { base, limit } => {
-                [[base & !0b11, 0], [limit, 0]]
-            },
-            IoAccessAddressRange::Addr32Bit { base, limit } => {
-                let base = base.to_le_bytes();
-                let base_upper = u16::from_le_bytes([base[0] & !0b11, base[1]]);
-                let base_lower = u16::from_le_bytes([base[2], base[3]]);
-                let limit = limit.to_le_bytes();
-                let limit_upper = u16::from_le_bytes([limit[0], limit[1]]);
-                let limit_lower = u16::from_le_bytes([limit[2], limit[3]]);
-                [[base_upper, base_lower], [limit_upper, limit_lower]]
-            },
-            _ => unreachable!(),
+                [[base & !cap, 0], [limit & !cap, 0]]
+            }
+            IoAccessAddressRange::Addr32Bit { base, limit } => [
+                [base as u16 & !cap | 0x01, (base >> 16) as u16],
+                [limit as u16 & !cap, (limit >> 16) as u16],
+            ],
+            IoAccessAddressRange::Unknown {
+                io_address_capability,
+                base_lower,
+                base_upper,
+                limit_lower,
+                limit_upper,
+            } => [
+                [
+                    base_lower & !cap | io_address_capability as u16 & cap,
+                    base_upper,
+                ],
+                [limit_lower & !cap, limit_upper],
+            ],
         }
     }
 }
@@ -819,6 +1038,20 @@ mod tests {
     use pretty_assertions::assert_eq;
     use super::*;
 
+    #[test]
+    fn bist() {
+        let bist = BuiltInSelfTest::from(0x00);
+        assert_eq!(None, bist.start());
+        assert_eq!(BistOutcome::NotCapable, bist.outcome());
+        let bist = BuiltInSelfTest::from(0x80);
+        assert_eq!(Some(0x40), bist.start());
+        assert_eq!(BistOutcome::Passed, bist.outcome());
+        assert_eq!(BistOutcome::Running, BuiltInSelfTest::from(0xc0).outcome());
+        assert_eq!(BistOutcome::Failed(0x5), BuiltInSelfTest::from(0x85).outcome());
+        // Reserved bits are not preserved
+        assert_eq!(0x85, u8::from(BuiltInSelfTest::from(0xb5)));
+    }
+
     #[test]
     fn io_access_address_range() {
         let zeros = [[ 0x00, 0x00 ], [ 0x00, 0x00 ]];
@@ -850,6 +1083,39 @@ mod tests {
             },
             unkn.into(), "Unknown"
         );
+
+        // Capability bits are restored
+        for data in [zeros, a16, a32, unkn] {
+            let range: IoAccessAddressRange = data.into();
+            assert_eq!(data, <[[u16; 2]; 2]>::from(range));
+        }
+    }
+
+    #[test]
+    fn bridge_ranges_registers() {
+        for (io_base, io_limit, io_base_upper, io_limit_upper) in [
+            (0x00, 0x00, 0x0000, 0x0000),
+            (0x20, 0x30, 0x0000, 0x0000),
+            (0xf1, 0x01, 0x0001, 0x0002),
+            (0x01, 0x00, 0x0000, 0x0000),
+            (0x22, 0x32, 0x0000, 0x0000),
+        ] {
+            let range = BridgeIoAddressRange::new(io_base, io_limit, io_base_upper, io_limit_upper);
+            assert_eq!(
+                (io_base, io_limit, io_base_upper, io_limit_upper),
+                range.registers()
+            );
+        }
+        for (base, limit, base_upper, limit_upper) in [
+            (0x0000, 0x0000, 0, 0),
+            (0x9100, 0x91f0, 0, 0),
+            (0x9101, 0x91f1, 0x1, 0x2),
+            (0x9101, 0x91f0, 0, 0),
+            (0x9102, 0x91f2, 0, 0),
+        ] {
+            let memory = BridgePrefetchableMemory::new(base, limit, base_upper, limit_upper);
+            assert_eq!((base, limit, base_upper, limit_upper), memory.registers());
+        }
     }
 
     #[test]
@@ -880,7 +1146,7 @@ mod tests {
             0x21, 0x30, 0x00, 0x00, 0x00, 0x60, 0x01, 0x93, 0x00, 0x00, 0x00, 0x00, 0x28, 0x10, 0xa5, 0x06,
             0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x01, 0x00, 0x00,
         ];
-        let result: Header = data.try_into().unwrap();
+        let result: Header = data.into();
         let sample = Header {
             vendor_id: 0x8086,
             device_id: 0xa102,
@@ -921,6 +1187,7 @@ mod tests {
             interrupt_pin: InterruptPin::IntA,
         };
         assert_eq!(sample, result);
+        assert_eq!(data, <[u8; Header::TOTAL_SIZE]>::from(&result));
     }
 
     #[test]
@@ -976,7 +1243,7 @@ mod tests {
             0x00, 0x92, 0x90, 0x92, 0x01, 0x91, 0xf1, 0x91, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x1b, 0x00,
         ];
-        let result: Header = data.try_into().unwrap();
+        let result: Header = data.into();
         // println!("{:#04X?}", &result);
         let sample = Header {
             vendor_id: 0x1912,
@@ -1000,9 +1267,9 @@ mod tests {
             is_multi_function: false,
             header_type: HeaderType::Bridge(Bridge {
                 base_addresses: BaseAddresses::new([0; 2]),
-                primary_bus_number: 0x04,
-                secondary_bus_number: 0x05,
-                subordinate_bus_number: 0x08,
+                primary_bus_number: Bus(0x04),
+                secondary_bus_number: Bus(0x05),
+                subordinate_bus_number: Bus(0x08),
                 secondary_latency_timer: 0x00,
                 io_address_range: BridgeIoAddressRange::IoAddr32 {
                     base: 0xf000,
@@ -1022,8 +1289,24 @@ mod tests {
             interrupt_pin: InterruptPin::Unused,
         };
         assert_eq!(sample, result);
+        assert_eq!(data, <[u8; Header::TOTAL_SIZE]>::from(&result));
+        if let HeaderType::Bridge(bridge) = &result.header_type {
+            assert_eq!(Bus(0x05)..=Bus(0x08), bridge.bus_range());
+            assert!(!bridge.bus_range().contains(&bridge.primary_bus_number));
+        }
+    }
+
+    #[cfg(not(feature = "cardbus"))]
+    #[test]
+    fn header_type_cardbus_disabled() {
+        let mut data = [0u8; Header::TOTAL_SIZE];
+        data[0x0e] = 0x82;
+        let result = Header::from(data);
+        assert_eq!(HeaderType::Reserved(0x02), result.header_type);
+        assert!(result.is_multi_function);
     }
 
+    #[cfg(feature = "cardbus")]
     #[test]
     fn header_type_cardbus() {
         // Random data with some bytes fixed
@@ -1078,9 +1361,9 @@ mod tests {
             header_type: HeaderType::Cardbus(Cardbus {
                 base_addresses: BaseAddresses::new([0x35f88000]),
                 secondary_status: 0x0000.into(),
-                pci_bus_number: 0x6d,
-                cardbus_bus_number: 0xba,
-                subordinate_bus_number: 0xfe,
+                pci_bus_number: Bus(0x6d),
+                cardbus_bus_number: Bus(0xba),
+                subordinate_bus_number: Bus(0xfe),
                 cardbus_latency_timer: 252,
                 memory_base_address_0: 0x11f54000,
                 memory_limit_address_0: 0x22475fff - 0xfff,
@@ -1111,5 +1394,9 @@ mod tests {
             interrupt_pin: InterruptPin::Reserved(0x1a),
         };
         assert_eq!(sample, result);
+        assert_eq!(
+            data[..Header::TOTAL_SIZE],
+            <[u8; Header::TOTAL_SIZE]>::from(&result)
+        );
     }
 }
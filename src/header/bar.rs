@@ -28,12 +28,9 @@ impl<const N: usize> Iterator for BaseAddresses<N> {
     fn next(&mut self) -> Option<Self::Item> {
         let mut next = || -> Option<(usize, u32)> {
             let region = self.region;
-            if region < N {
-                self.region += 1;
-                Some((region, self.data[region]))
-            } else {
-                None
-            }
+            let dword = *self.data.get(region)?;
+            self.region += 1;
+            Some((region, dword))
         };
         loop {
             let (region, dword) = next()?;
@@ -88,24 +85,36 @@ impl<const N: usize> FromIterator<BaseAddress> for [u32; N] {
     fn from_iter<I: IntoIterator<Item = BaseAddress>>(iter: I) -> Self {
         let mut dwords = [0; N];
         for ba in iter.into_iter() {
-            let i = ba.region.min(N - 1);
-            match ba.base_address_type {
-                BaseAddressType::MemorySpace32 { prefetchable, base_address, } => {
-                    dwords[i] = base_address & !0b1111 | ((prefetchable as u32) << 3);
-                },
-                BaseAddressType::MemorySpaceBelow1M { prefetchable, base_address, } => {
-                    dwords[i] = (base_address as u32) & !0b1111 | 0b010 | ((prefetchable as u32) << 3);
-                },
-                BaseAddressType::MemorySpace64 { prefetchable, base_address, } => {
-                    dwords[i] = (base_address as u32) & !0b1111 | 0b100 | ((prefetchable as u32) << 3);
-                    dwords[i + 1] = (base_address >> 32) as u32;
-                },
-                BaseAddressType::IoSpace { base_address, } => {
-                    dwords[i] = base_address & !0b11 | 0b01;
-                },
-                _ => (),
+            let i = ba.region.min(N.saturating_sub(1));
+            let (lo, hi) = match ba.base_address_type {
+                BaseAddressType::MemorySpace32 {
+                    prefetchable,
+                    base_address,
+                } => (base_address & !0b1111 | ((prefetchable as u32) << 3), None),
+                BaseAddressType::MemorySpaceBelow1M {
+                    prefetchable,
+                    base_address,
+                } => (
+                    base_address & !0b1111 | 0b010 | ((prefetchable as u32) << 3),
+                    None,
+                ),
+                BaseAddressType::MemorySpace64 {
+                    prefetchable,
+                    base_address,
+                } => {
+                    let lo = (base_address as u32) & !0b1111 | 0b100 | ((prefetchable as u32) << 3);
+                    (lo, Some((base_address >> 32) as u32))
+                }
+                BaseAddressType::IoSpace { base_address } => (base_address & !0b11 | 0b01, None),
+                _ => continue,
+            };
+            if let Some(dword) = dwords.get_mut(i) {
+                *dword = lo;
+            }
+            // Upper half of the last region does not fit
+            if let (Some(hi), Some(dword)) = (hi, dwords.get_mut(i + 1)) {
+                *dword = hi;
             }
-
         }
         dwords
     }
@@ -316,4 +325,21 @@ mod tests {
 
         assert_eq!(BaseAddresses::new(dwords), BaseAddresses::new(result));
     }
+
+    #[test]
+    fn base_address_64bit_last_region_from_iterator() {
+        let bas = [
+            BaseAddress {
+                region: 1,
+                base_address_type: BaseAddressType::MemorySpace64 {
+                    prefetchable: true, base_address: 0x1_2345_0000
+                },
+            },
+        ];
+        // Upper dword does not fit, no out of bounds write
+        let result: [u32; 2] = bas.iter().collect();
+        assert_eq!([0, 0x2345_000c], result);
+        let result: [u32; 0] = bas.iter().collect();
+        assert_eq!([0u32; 0], result);
+    }
 }
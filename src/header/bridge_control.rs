@@ -5,7 +5,7 @@
 //! interface that are provided by the Command register for the primary interface. There are some
 //! bits that affect the operation of both interfaces of the bridge.
 
-use heterob::{bit_numbering::Lsb, P13};
+use crate::register::register;
 
 /// Bridge Control Register
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -43,39 +43,20 @@ pub struct BridgeControl {
     pub discard_timer_serr_enable: bool,
 }
 
-impl From<u16> for BridgeControl {
-    fn from(word: u16) -> Self {
-        let Lsb((
-            parity_error_response_enable,
-            serr_enable,
-            isa_enable,
-            vga_enable,
-            vga_16_enable,
-            master_abort_mode,
-            secondary_bus_reset,
-            fast_back_to_back_enable,
-            primary_discard_timer,
-            secondary_discard_timer,
-            discard_timer_status,
-            discard_timer_serr_enable,
-            (),
-        )) = P13::<_, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 4>(word).into();
-        Self {
-            parity_error_response_enable,
-            serr_enable,
-            isa_enable,
-            vga_enable,
-            vga_16_enable,
-            master_abort_mode,
-            secondary_bus_reset,
-            fast_back_to_back_enable,
-            primary_discard_timer,
-            secondary_discard_timer,
-            discard_timer_status,
-            discard_timer_serr_enable,
-        }
-    }
-}
+register!(BridgeControl: u16 {
+    parity_error_response_enable[0],
+    serr_enable[1],
+    isa_enable[2],
+    vga_enable[3],
+    vga_16_enable[4],
+    master_abort_mode[5],
+    secondary_bus_reset[6],
+    fast_back_to_back_enable[7],
+    primary_discard_timer[8],
+    secondary_discard_timer[9],
+    discard_timer_status[10],
+    discard_timer_serr_enable[11],
+});
 
 #[cfg(test)]
 mod tests {
@@ -100,5 +81,6 @@ mod tests {
             discard_timer_serr_enable: true,
         };
         assert_eq!(sample, result);
+        assert_eq!(0x0AAA, u16::from(sample));
     }
 }
@@ -1,7 +1,7 @@
 //! The Bridge Control register provides extensions of the Command Register that are specific to
 //! PCI to PCI and PCI-to-CardBus bridges.
 
-use heterob::{bit_numbering::Lsb, P12};
+use crate::register::register;
 
 /// Bridge Control Register (Offset = 3EH)
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -32,36 +32,18 @@ pub struct CardbusBridgeControl {
     pub write_posting_enable: bool,
 }
 
-impl From<u16> for CardbusBridgeControl {
-    fn from(word: u16) -> Self {
-        let Lsb((
-            parity_error_response_enable,
-            serr_enable,
-            isa_enable,
-            vga_enable,
-            (),
-            master_abort_mode,
-            cardbus_reset,
-            ireq_int_enable,
-            memory_0_prefetch_enable,
-            memory_1_prefetch_enable,
-            write_posting_enable,
-            (),
-        )) = P12::<_, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 5>(word).into();
-        Self {
-            parity_error_response_enable,
-            serr_enable,
-            isa_enable,
-            vga_enable,
-            master_abort_mode,
-            cardbus_reset,
-            ireq_int_enable,
-            memory_0_prefetch_enable,
-            memory_1_prefetch_enable,
-            write_posting_enable,
-        }
-    }
-}
+register!(CardbusBridgeControl: u16 {
+    parity_error_response_enable[0],
+    serr_enable[1],
+    isa_enable[2],
+    vga_enable[3],
+    master_abort_mode[5],
+    cardbus_reset[6],
+    ireq_int_enable[7],
+    memory_0_prefetch_enable[8],
+    memory_1_prefetch_enable[9],
+    write_posting_enable[10],
+});
 
 #[cfg(test)]
 mod tests {
@@ -84,5 +66,6 @@ mod tests {
             write_posting_enable: false,
         };
         assert_eq!(sample, result);
+        assert_eq!(0x02AA, u16::from(sample));
     }
 }
@@ -23,6 +23,12 @@ impl From<[u8; 3]> for ClassCode {
     }
 }
 
+impl From<ClassCode> for [u8; 3] {
+    fn from(class_code: ClassCode) -> Self {
+        [class_code.interface, class_code.sub, class_code.base]
+    }
+}
+
 impl ClassCode {
     pub fn meaning(&self) -> (&str, Option<&str>, Option<&str>) {
         match (self.base, self.sub, self.interface) {
@@ -242,7 +248,7 @@ mod tests {
     #[test]
     fn meaning() {
         let data = [0x00, 0x00, 0x05];
-        let result: ClassCode = data.try_into().unwrap();
+        let result: ClassCode = data.into();
         let result = result.meaning();
         assert_eq!(("Memory controller", Some("RAM memory"), None), result);
     }
@@ -1,4 +1,8 @@
-use heterob::{bit_numbering::Lsb, P12};
+use super::PrimaryStatus;
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor},
+    register::register,
+};
 
 /// Provides control over a device's ability to generate and respond to PCI cycles.
 ///
@@ -21,37 +25,83 @@ pub struct Command {
     pub reserved: u8,
 }
 
-impl From<u16> for Command {
-    fn from(word: u16) -> Self {
-        let Lsb((
-            io_space,
-            memory_space,
-            bus_master,
-            special_cycles,
-            memory_write_and_invalidate_enable,
-            vga_palette_snoop,
-            parity_error_response,
-            stepping,
-            serr_enable,
-            fast_back_to_back_enable,
-            interrupt_disable,
-            reserved,
-        )) = P12::<_, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 5>(word).into();
+register!(Command: u16 {
+    io_space[0],
+    memory_space[1],
+    bus_master[2],
+    special_cycles[3],
+    memory_write_and_invalidate_enable[4],
+    vga_palette_snoop[5],
+    parity_error_response[6],
+    stepping[7],
+    serr_enable[8],
+    fast_back_to_back_enable[9],
+    interrupt_disable[10],
+    reserved[11..16],
+});
+
+impl Command {
+    /// Register location in the header
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Command", 0x04, AccessWidth::Word);
+    /// Command with Bus Master Enable set to `enabled`
+    pub fn with_bus_master(self, enabled: bool) -> Self {
+        Self {
+            bus_master: enabled,
+            ..self
+        }
+    }
+    /// Command with Interrupt Disable set to `disabled`
+    pub fn with_intx_disabled(self, disabled: bool) -> Self {
         Self {
-            io_space,
-            memory_space,
-            bus_master,
-            special_cycles,
-            memory_write_and_invalidate_enable,
-            vga_palette_snoop,
-            parity_error_response,
-            stepping,
-            serr_enable,
-            fast_back_to_back_enable,
-            interrupt_disable,
-            reserved,
+            interrupt_disable: disabled,
+            ..self
         }
     }
+    /// Command with SERR# Enable set to `enabled`
+    pub fn with_serr_enabled(self, enabled: bool) -> Self {
+        Self {
+            serr_enable: enabled,
+            ..self
+        }
+    }
+    /// Legacy INTx interrupt state of the function
+    ///
+    /// Interrupt Status reflects the internal interrupt state regardless of Interrupt Disable,
+    /// INTx is asserted only if both Interrupt Status is set and Interrupt Disable is clear.
+    /// Functions using MSI or MSI-X should have INTx disabled.
+    pub fn effective_interrupts(&self, status: &PrimaryStatus) -> IntxState {
+        match (status.interrupt_status, self.interrupt_disable) {
+            (false, _) => IntxState::Idle,
+            (true, true) => IntxState::Masked,
+            (true, false) => IntxState::Asserted,
+        }
+    }
+    /// Function may report errors by SERR# (ERR_NONFATAL/ERR_FATAL Messages on PCI Express)
+    ///
+    /// Non-fatal and fatal errors detected by the function are reported only if SERR# Enable
+    /// is set, Signaled System Error in Status register records that this happened.
+    pub fn system_errors_reported(&self) -> bool {
+        self.serr_enable
+    }
+}
+
+/// Legacy INTx interrupt state combined from Command and Status registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntxState {
+    /// Function has no pending interrupt
+    Idle,
+    /// Interrupt is pending, but INTx is not asserted because of Interrupt Disable
+    Masked,
+    /// Interrupt is pending and INTx is asserted
+    Asserted,
+}
+
+impl IntxState {
+    /// INTx is asserted and delivered to the interrupt controller
+    pub fn is_deliverable(&self) -> bool {
+        matches!(self, Self::Asserted)
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +128,37 @@ mod tests {
         };
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn builders() {
+        let command = Command::from(0x0002)
+            .with_bus_master(true)
+            .with_intx_disabled(true)
+            .with_serr_enabled(true);
+        assert_eq!(0x0506, u16::from(command.clone()));
+        assert_eq!(0xAAAA, u16::from(Command::from(0xAAAA)));
+        assert!(command.system_errors_reported());
+        assert_eq!(
+            0x0002,
+            u16::from(
+                command
+                    .with_bus_master(false)
+                    .with_intx_disabled(false)
+                    .with_serr_enabled(false)
+            )
+        );
+    }
+
+    #[test]
+    fn effective_interrupts() {
+        let pending: PrimaryStatus = 0x0008.into();
+        let idle: PrimaryStatus = 0x0000.into();
+        let command = Command::default();
+        assert_eq!(IntxState::Asserted, command.effective_interrupts(&pending));
+        assert_eq!(IntxState::Idle, command.effective_interrupts(&idle));
+        let command = command.with_intx_disabled(true);
+        let state = command.effective_interrupts(&pending);
+        assert_eq!(IntxState::Masked, state);
+        assert!(!state.is_deliverable());
+    }
 }
@@ -1,4 +1,7 @@
-use heterob::{bit_numbering::Lsb, P13};
+use crate::{
+    config_write::{AccessWidth, RegisterDescriptor},
+    register::{impl_u8_field, register},
+};
 
 /// The Status register is used to record status information for PCI bus related events.
 ///
@@ -9,7 +12,8 @@ use heterob::{bit_numbering::Lsb, P13};
 /// 2. Secondary PCI-to-PCI Bridge
 /// 3. Secondary CardBus
 ///
-/// Status type selected by generic constant [char] 'P', 'B' or 'C'
+/// Status type selected by generic constant [char] 'P', 'B' or 'C', use [PrimaryStatus],
+/// [SecondaryStatus] and [CardbusStatus] aliases to name them
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Status<const T: char> {
     pub reserved: u8,
@@ -30,40 +34,50 @@ pub struct Status<const T: char> {
     pub detected_parity_error: bool,
 }
 
-impl<const T: char> From<u16> for Status<T> {
-    fn from(word: u16) -> Self {
-        let Lsb((
-            reserved,
-            interrupt_status,
-            capabilities_list,
-            is_66mhz_capable,
-            user_definable_features,
-            fast_back_to_back_capable,
-            master_data_parity_error,
-            devsel_timing,
-            signaled_target_abort,
-            received_target_abort,
-            received_master_abort,
-            system_error,
-            detected_parity_error,
-        )) = P13::<_, 3, 1, 1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1>(word).into();
-        Self {
-            reserved,
-            interrupt_status,
-            capabilities_list,
-            is_66mhz_capable,
-            user_definable_features,
-            fast_back_to_back_capable,
-            master_data_parity_error,
-            devsel_timing: From::<u8>::from(devsel_timing),
-            signaled_target_abort,
-            received_target_abort,
-            received_master_abort,
-            system_error,
-            detected_parity_error,
-        }
-    }
+/// Primary Status register (identical for all device types)
+pub type PrimaryStatus = Status<'P'>;
+/// Secondary Status register of PCI-to-PCI Bridge
+pub type SecondaryStatus = Status<'B'>;
+/// Secondary Status register of CardBus Bridge
+pub type CardbusStatus = Status<'C'>;
+
+/// Master Data Parity Error, Signaled / Received Target Abort, Received Master Abort,
+/// System Error and Detected Parity Error
+const STATUS_RW1C: u32 = 0xf900;
+/// 66 MHz Capable, Fast Back-to-Back Transactions Capable and DEVSEL Timing
+const STATUS_READ_ONLY: u32 = 0x06a0;
+
+impl PrimaryStatus {
+    /// Register location in the header
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Status", 0x06, AccessWidth::Word)
+            // Interrupt Status and Capabilities List
+            .read_only(STATUS_READ_ONLY | 0x0018)
+            .rw1c(STATUS_RW1C);
 }
+impl SecondaryStatus {
+    /// Register location in the Type 01h header
+    pub const REGISTER: RegisterDescriptor =
+        RegisterDescriptor::new("Secondary Status", 0x1e, AccessWidth::Word)
+            .read_only(STATUS_READ_ONLY)
+            .rw1c(STATUS_RW1C);
+}
+
+register!(Status<const T: char>: u16 {
+    reserved[0..3],
+    interrupt_status[3],
+    capabilities_list[4],
+    is_66mhz_capable[5],
+    user_definable_features[6],
+    fast_back_to_back_capable[7],
+    master_data_parity_error[8],
+    devsel_timing[9..11],
+    signaled_target_abort[11],
+    received_target_abort[12],
+    received_master_abort[13],
+    system_error[14],
+    detected_parity_error[15],
+});
 
 /// Specifies allowable timings for assertion of DEVSEL#
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -88,6 +102,12 @@ impl From<u8> for DevselTiming {
         }
     }
 }
+impl From<DevselTiming> for u8 {
+    fn from(timing: DevselTiming) -> Self {
+        timing as u8
+    }
+}
+impl_u8_field!(DevselTiming);
 
 #[cfg(test)]
 mod tests {
@@ -96,7 +116,7 @@ mod tests {
 
     #[test]
     fn from_word() {
-        let result: Status<'P'> = 0xAAAA.into();
+        let result: PrimaryStatus = 0xAAAA.into();
         let sample = Status {
             reserved: 0b010,
             interrupt_status: true,
@@ -113,5 +133,6 @@ mod tests {
             detected_parity_error: true,
         };
         assert_eq!(sample, result);
+        assert_eq!(0xAAAA, u16::from(sample));
     }
 }
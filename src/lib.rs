@@ -1,11 +1,18 @@
 #![doc = include_str!("../README.md")]
 
 #![no_std]
+// Parsed data is untrusted, see Panic safety in README
+#![cfg_attr(not(test), deny(clippy::indexing_slicing))]
 
 #[cfg(test)]
 #[macro_use]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+
+mod register;
 
 pub mod header;
 pub use header::Header;
@@ -16,6 +23,44 @@ pub use capabilities::Capabilities;
 pub mod extended_capabilities;
 pub use extended_capabilities::ExtendedCapabilities;
 
+pub mod config_offset;
+pub use config_offset::ConfigOffset;
+
+pub mod bdf;
+
+pub mod configuration_space;
+pub use configuration_space::ConfigurationSpace;
+
+pub mod config_access;
+pub use config_access::ConfigAccess;
+
+pub mod config_write;
+
+pub mod ecam;
+
+pub mod editor;
+pub use editor::ConfigSpaceEditor;
+
+pub mod analysis;
+
+pub mod sysfs;
+
+pub mod lspci;
+
+#[cfg(feature = "fmt")]
+pub mod fmt;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "alloc")]
+pub mod topology;
+
+#[cfg(any(test, feature = "synthetic"))]
+// Images are built at constant offsets, not from parsed data
+#[allow(clippy::indexing_slicing)]
+pub mod synthetic;
+
 
 /// Device dependent region starts at 0x40 offset
 pub const DDR_OFFSET: usize = 0x40;
@@ -24,4 +69,47 @@ pub const ECS_OFFSET: usize = 0x100;
 /// Device dependent region length
 pub const DDR_LENGTH: usize = ECS_OFFSET - DDR_OFFSET;
 /// Extended configuration space length
-pub const ECS_LENGTH: usize = 4096 - ECS_OFFSET;
+pub const ECS_LENGTH: usize = EXTENDED_CONFIG_SPACE_SIZE - ECS_OFFSET;
+/// Conventional PCI configuration space size
+pub const CONFIG_SPACE_SIZE: usize = ECS_OFFSET;
+/// PCI Express extended configuration space size, including conventional configuration space
+pub const EXTENDED_CONFIG_SPACE_SIZE: usize = 4096;
+
+/// Largest of `sizes`, usable in const context to size a buffer for any of several structures
+///
+/// ```rust
+/// # use pcics::{capabilities::{MsiX, PciExpress}, max_size};
+/// const BUFFER_SIZE: usize = max_size(&[MsiX::MIN_SIZE, PciExpress::MIN_SIZE]);
+/// static BUFFER: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+/// assert_eq!(PciExpress::MIN_SIZE, BUFFER.len());
+/// ```
+pub const fn max_size(sizes: &[usize]) -> usize {
+    let mut max = 0;
+    let mut rest = sizes;
+    while let [size, tail @ ..] = rest {
+        if *size > max {
+            max = *size;
+        }
+        rest = tail;
+    }
+    max
+}
+
+/// Checks that a buffer of `buffer` bytes holds a structure of `size` bytes, returns `buffer`
+///
+/// Evaluated in const context the check fails at compile time:
+///
+/// ```rust
+/// # use pcics::{assert_buffer_size, capabilities::PciExpress, CONFIG_SPACE_SIZE};
+/// const PCIE_BUFFER: usize = assert_buffer_size(0x40, PciExpress::MIN_SIZE);
+/// const CS_BUFFER: usize = assert_buffer_size(0x100, CONFIG_SPACE_SIZE);
+/// ```
+///
+/// ```compile_fail
+/// # use pcics::{assert_buffer_size, EXTENDED_CONFIG_SPACE_SIZE};
+/// const BUFFER: usize = assert_buffer_size(0x100, EXTENDED_CONFIG_SPACE_SIZE);
+/// ```
+pub const fn assert_buffer_size(buffer: usize, size: usize) -> usize {
+    assert!(buffer >= size, "buffer is too small for the structure");
+    buffer
+}
@@ -0,0 +1,605 @@
+/*!
+## lspci names
+
+`lspci -vvv` prints capabilities by their titles, e.g. `Express (v2) Endpoint, MSI 00` or
+`Access Control Services`, and PCI Express Capability registers by abbreviations, e.g.
+`LnkCap:` or `DevCtl2:`. [lookup] and [lookup_extended] map such names to the items of this
+crate, so tools ingesting lspci text can find the same structure in freshly parsed binary data
+and report values that differ.
+
+Titles are matched by their leading words, the details lspci prints after them are ignored.
+Capabilities and extended capabilities lists share some titles, e.g. `Vendor Specific
+Information`, so titles of `[100 v1]` lines are looked up with [lookup_extended].
+
+## Examples
+
+```rust
+# use pcics::{lspci::*, capabilities::pci_express::Register, ConfigurationSpace};
+let name = lookup("Express (v2) Root Port (Slot+), MSI 00").unwrap();
+assert_eq!(LspciItem::PciExpress { version: 2, device_type: 0b0100 }, name.item);
+assert_eq!("capabilities::PciExpress", name.type_path);
+
+let name = lookup("LnkCap:").unwrap();
+assert_eq!(LspciItem::PciExpressRegister(Register::LinkCapabilities), name.item);
+assert_eq!("capabilities::pci_express::LinkCapabilities", name.type_path);
+
+let name = lookup_extended("Vendor Specific Information: ID=0004 Rev=1 Len=024 <?>").unwrap();
+assert_eq!(LspciItem::ExtendedCapability(0x000b), name.item);
+
+// Cross-reference with parsed data
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let item = lookup("Express (v2) Root Port (Slot+), MSI 00").unwrap().item;
+assert!(cs.capabilities().flatten().any(|cap| item.matches_capability(&cap)));
+let item = lookup_extended("Advanced Error Reporting").unwrap().item;
+assert!(cs
+    .extended_capabilities()
+    .unwrap()
+    .flatten()
+    .any(|ecap| item.matches_extended_capability(&ecap)));
+```
+*/
+
+use crate::{
+    capabilities::{pci_express::Register, Capability, CapabilityKind},
+    extended_capabilities::ExtendedCapability,
+};
+
+/// Item of this crate named by lspci
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LspciItem {
+    /// Capability with the Capability ID
+    Capability(u8),
+    /// Extended capability with the Capability ID
+    ExtendedCapability(u16),
+    /// PCI Express Capability of the version and Device/Port Type
+    PciExpress { version: u8, device_type: u8 },
+    /// Register of the PCI Express Capability
+    PciExpressRegister(Register),
+}
+
+impl LspciItem {
+    /// The capability is the named item, or holds the named register
+    pub fn matches_capability(&self, cap: &Capability) -> bool {
+        match (*self, &cap.kind) {
+            (Self::Capability(id), _) => cap.id() == id,
+            (
+                Self::PciExpress {
+                    version,
+                    device_type,
+                },
+                CapabilityKind::PciExpress(pcie),
+            ) => pcie.version == version && pcie.device_type.id() == device_type,
+            (Self::PciExpressRegister(register), CapabilityKind::PciExpress(pcie)) => {
+                pcie.implements(register)
+            }
+            _ => false,
+        }
+    }
+    /// The extended capability is the named item
+    pub fn matches_extended_capability(&self, ecap: &ExtendedCapability) -> bool {
+        matches!(*self, Self::ExtendedCapability(id) if ecap.id() == id)
+    }
+}
+
+/// lspci name with the item of this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LspciName {
+    /// Title or abbreviation as printed by lspci
+    pub name: &'static str,
+    pub item: LspciItem,
+    /// Path of the type holding the item, relative to the crate root
+    pub type_path: &'static str,
+}
+
+const fn cap(name: &'static str, id: u8, type_path: &'static str) -> LspciName {
+    LspciName {
+        name,
+        item: LspciItem::Capability(id),
+        type_path,
+    }
+}
+
+const fn ecap(name: &'static str, id: u16, type_path: &'static str) -> LspciName {
+    LspciName {
+        name,
+        item: LspciItem::ExtendedCapability(id),
+        type_path,
+    }
+}
+
+const fn reg(name: &'static str, register: Register, type_path: &'static str) -> LspciName {
+    LspciName {
+        name,
+        item: LspciItem::PciExpressRegister(register),
+        type_path,
+    }
+}
+
+const CAPABILITIES: [LspciName; 20] = [
+    cap(
+        "Power Management version",
+        0x01,
+        "capabilities::PowerManagementInterface",
+    ),
+    cap("AGP version", 0x02, "capabilities::AcceleratedGraphicsPort"),
+    cap("Vital Product Data", 0x03, "capabilities::VitalProductData"),
+    cap("Slot ID", 0x04, "capabilities::SlotIdentification"),
+    cap("MSI", 0x05, "capabilities::MessageSignaledInterrups"),
+    cap(
+        "CompactPCI hot-swap",
+        0x06,
+        "capabilities::CompactPciHotSwap",
+    ),
+    cap("PCI-X", 0x07, "capabilities::PciX"),
+    cap("HyperTransport", 0x08, "capabilities::Hypertransport"),
+    cap(
+        "Vendor Specific Information",
+        0x09,
+        "capabilities::VendorSpecific",
+    ),
+    cap("Debug port", 0x0a, "capabilities::DebugPort"),
+    cap(
+        "CompactPCI central resource control",
+        0x0b,
+        "capabilities::CompactPciResourceControl",
+    ),
+    cap("Hot-plug capable", 0x0c, "capabilities::PciHotPlug"),
+    cap("Subsystem", 0x0d, "capabilities::BridgeSubsystemVendorId"),
+    cap("AGP3", 0x0e, "capabilities::Agp8x"),
+    cap("Secure device", 0x0f, "capabilities::SecureDevice"),
+    cap("MSI-X", 0x11, "capabilities::MsiX"),
+    cap("SATA HBA", 0x12, "capabilities::Sata"),
+    cap(
+        "PCI Advanced Features",
+        0x13,
+        "capabilities::AdvancedFeatures",
+    ),
+    cap(
+        "Enhanced Allocation (EA)",
+        0x14,
+        "capabilities::EnhancedAllocation",
+    ),
+    cap(
+        "Flattening Portal Bridge (FPB)",
+        0x15,
+        "capabilities::FlatteningPortalBridge",
+    ),
+];
+
+/// Device/Port Type names following `Express (vN) `
+const DEVICE_TYPES: [(&str, u8); 9] = [
+    ("Endpoint", 0b0000),
+    ("Legacy Endpoint", 0b0001),
+    ("Root Port", 0b0100),
+    ("Upstream Port", 0b0101),
+    ("Downstream Port", 0b0110),
+    ("PCI-Express to PCI/PCI-X Bridge", 0b0111),
+    ("PCI/PCI-X to PCI-Express Bridge", 0b1000),
+    ("Root Complex Integrated Endpoint", 0b1001),
+    ("Root Complex Event Collector", 0b1010),
+];
+
+const REGISTERS: [LspciName; 21] = [
+    reg(
+        "DevCap",
+        Register::DeviceCapabilities,
+        "capabilities::pci_express::DeviceCapabilities",
+    ),
+    reg(
+        "DevCtl",
+        Register::DeviceControl,
+        "capabilities::pci_express::DeviceControl",
+    ),
+    reg(
+        "DevSta",
+        Register::DeviceStatus,
+        "capabilities::pci_express::DeviceStatus",
+    ),
+    reg(
+        "LnkCap",
+        Register::LinkCapabilities,
+        "capabilities::pci_express::LinkCapabilities",
+    ),
+    reg(
+        "LnkCtl",
+        Register::LinkControl,
+        "capabilities::pci_express::LinkControl",
+    ),
+    reg(
+        "LnkSta",
+        Register::LinkStatus,
+        "capabilities::pci_express::LinkStatus",
+    ),
+    reg(
+        "SltCap",
+        Register::SlotCapabilities,
+        "capabilities::pci_express::SlotCapabilities",
+    ),
+    reg(
+        "SltCtl",
+        Register::SlotControl,
+        "capabilities::pci_express::SlotControl",
+    ),
+    reg(
+        "SltSta",
+        Register::SlotStatus,
+        "capabilities::pci_express::SlotStatus",
+    ),
+    reg(
+        "RootCtl",
+        Register::RootControl,
+        "capabilities::pci_express::RootControl",
+    ),
+    reg(
+        "RootCap",
+        Register::RootCapabilities,
+        "capabilities::pci_express::RootCapabilities",
+    ),
+    reg(
+        "RootSta",
+        Register::RootStatus,
+        "capabilities::pci_express::RootStatus",
+    ),
+    reg(
+        "DevCap2",
+        Register::DeviceCapabilities2,
+        "capabilities::pci_express::DeviceCapabilities2",
+    ),
+    reg(
+        "DevCtl2",
+        Register::DeviceControl2,
+        "capabilities::pci_express::DeviceControl2",
+    ),
+    reg(
+        "DevSta2",
+        Register::DeviceStatus2,
+        "capabilities::pci_express::DeviceStatus2",
+    ),
+    reg(
+        "LnkCap2",
+        Register::LinkCapabilities2,
+        "capabilities::pci_express::LinkCapabilities2",
+    ),
+    reg(
+        "LnkCtl2",
+        Register::LinkControl2,
+        "capabilities::pci_express::LinkControl2",
+    ),
+    reg(
+        "LnkSta2",
+        Register::LinkStatus2,
+        "capabilities::pci_express::LinkStatus2",
+    ),
+    reg(
+        "SltCap2",
+        Register::SlotCapabilities2,
+        "capabilities::pci_express::SlotCapabilities2",
+    ),
+    reg(
+        "SltCtl2",
+        Register::SlotControl2,
+        "capabilities::pci_express::SlotControl2",
+    ),
+    reg(
+        "SltSta2",
+        Register::SlotStatus2,
+        "capabilities::pci_express::SlotStatus2",
+    ),
+];
+
+/// Virtual Channel titles both VC (0002h) and VC9 (0009h) structures, the first one is listed
+const EXTENDED_CAPABILITIES: [LspciName; 41] = [
+    ecap(
+        "Advanced Error Reporting",
+        0x0001,
+        "extended_capabilities::AdvancedErrorReporting",
+    ),
+    ecap(
+        "Virtual Channel",
+        0x0002,
+        "extended_capabilities::VirtualChannel",
+    ),
+    ecap(
+        "Device Serial Number",
+        0x0003,
+        "extended_capabilities::DeviceSerialNumber",
+    ),
+    ecap(
+        "Power Budgeting",
+        0x0004,
+        "extended_capabilities::PowerBudgeting",
+    ),
+    ecap(
+        "Root Complex Link",
+        0x0005,
+        "extended_capabilities::RootComplexLinkDeclaration",
+    ),
+    ecap(
+        "Root Complex Internal Link",
+        0x0006,
+        "extended_capabilities::RootComplexInternalLinkControl",
+    ),
+    ecap(
+        "Root Complex Event Collector",
+        0x0007,
+        "extended_capabilities::RootComplexEventCollectorEndpointAssociation",
+    ),
+    ecap(
+        "Multi-Function Virtual Channel",
+        0x0008,
+        "extended_capabilities::MultifunctionVirtualChannel",
+    ),
+    ecap(
+        "RCRB Header",
+        0x000a,
+        "extended_capabilities::RootComplexRegisterBlockHeader",
+    ),
+    ecap(
+        "Vendor Specific Information",
+        0x000b,
+        "extended_capabilities::VendorSpecificExtendedCapability",
+    ),
+    ecap(
+        "Configuration Access Correlation",
+        0x000c,
+        "extended_capabilities::ConfigurationAccessCorrelation",
+    ),
+    ecap(
+        "Access Control Services",
+        0x000d,
+        "extended_capabilities::AccessControlServices",
+    ),
+    ecap(
+        "Alternative Routing-ID Interpretation (ARI)",
+        0x000e,
+        "extended_capabilities::AlternativeRoutingIdInterpretation",
+    ),
+    ecap(
+        "Address Translation Service (ATS)",
+        0x000f,
+        "extended_capabilities::AddressTranslationServices",
+    ),
+    ecap(
+        "Single Root I/O Virtualization (SR-IOV)",
+        0x0010,
+        "extended_capabilities::SingleRootIoVirtualization",
+    ),
+    ecap(
+        "Multi-Root I/O Virtualization (MR-IOV)",
+        0x0011,
+        "extended_capabilities::MultiRootIoVirtualization",
+    ),
+    ecap("Multicast", 0x0012, "extended_capabilities::Multicast"),
+    ecap(
+        "Page Request Interface (PRI)",
+        0x0013,
+        "extended_capabilities::PageRequestInterface",
+    ),
+    ecap(
+        "Physical Resizable BAR",
+        0x0015,
+        "extended_capabilities::ResizableBar",
+    ),
+    ecap(
+        "Dynamic Power Allocation",
+        0x0016,
+        "extended_capabilities::DynamicPowerAllocation",
+    ),
+    ecap(
+        "Transaction Processing Hints",
+        0x0017,
+        "extended_capabilities::TphRequester",
+    ),
+    ecap(
+        "Latency Tolerance Reporting",
+        0x0018,
+        "extended_capabilities::LatencyToleranceReporting",
+    ),
+    ecap(
+        "Secondary PCI Express",
+        0x0019,
+        "extended_capabilities::SecondaryPciExpress",
+    ),
+    ecap(
+        "Protocol Multiplexing",
+        0x001a,
+        "extended_capabilities::ProtocolMultiplexing",
+    ),
+    ecap(
+        "Process Address Space ID (PASID)",
+        0x001b,
+        "extended_capabilities::ProcessAddressSpaceId",
+    ),
+    ecap("LN Requester", 0x001c, "extended_capabilities::LnRequester"),
+    ecap(
+        "Downstream Port Containment",
+        0x001d,
+        "extended_capabilities::DownstreamPortContainment",
+    ),
+    ecap(
+        "L1 PM Substates",
+        0x001e,
+        "extended_capabilities::L1PmSubstates",
+    ),
+    ecap(
+        "Precision Time Measurement",
+        0x001f,
+        "extended_capabilities::PrecisionTimeMeasurement",
+    ),
+    ecap(
+        "PCI Express over M_PHY",
+        0x0020,
+        "extended_capabilities::PciExpressOverMphy",
+    ),
+    ecap("FRS Queueing", 0x0021, "extended_capabilities::FrsQueuing"),
+    ecap(
+        "Readiness Time Reporting",
+        0x0022,
+        "extended_capabilities::ReadinessTimeReporting",
+    ),
+    ecap(
+        "Designated Vendor-Specific",
+        0x0023,
+        "extended_capabilities::DesignatedVendorSpecificExtendedCapability",
+    ),
+    ecap(
+        "VF Resizable BAR",
+        0x0024,
+        "extended_capabilities::VfResizableBar",
+    ),
+    ecap(
+        "Data Link Feature",
+        0x0025,
+        "extended_capabilities::DataLinkFeature",
+    ),
+    ecap(
+        "Physical Layer 16.0 GT/s",
+        0x0026,
+        "extended_capabilities::PhysicalLayer16GTps",
+    ),
+    ecap(
+        "Lane Margining at the Receiver",
+        0x0027,
+        "extended_capabilities::LaneMarginingAtTheReceiver",
+    ),
+    ecap("Hierarchy ID", 0x0028, "extended_capabilities::HierarchyId"),
+    ecap(
+        "Native PCIe Enclosure Management (NPEM)",
+        0x0029,
+        "extended_capabilities::NativePcieEnclosureManagement",
+    ),
+    ecap(
+        "Physical Layer 32.0 GT/s",
+        0x002a,
+        "extended_capabilities::PhysicalLayer32GTps",
+    ),
+    ecap(
+        "Data Object Exchange",
+        0x002e,
+        "extended_capabilities::DataObjectExchange",
+    ),
+];
+
+/// Looks up a register abbreviation or a title of the capabilities list entry
+pub fn lookup(name: &str) -> Option<LspciName> {
+    let name = name.trim();
+    let abbreviation = name.strip_suffix(':').unwrap_or(name);
+    if let Some(name) = REGISTERS.iter().find(|reg| reg.name == abbreviation) {
+        return Some(*name);
+    }
+    if let Some(express) = pci_express(name) {
+        return Some(express);
+    }
+    find_title(&CAPABILITIES, name)
+}
+
+/// Looks up a title of the extended capabilities list entry
+pub fn lookup_extended(name: &str) -> Option<LspciName> {
+    find_title(&EXTENDED_CAPABILITIES, name.trim())
+}
+
+/// Longest title that `text` starts with as whole words
+fn find_title(names: &[LspciName], text: &str) -> Option<LspciName> {
+    names
+        .iter()
+        .filter(|name| starts_with_title(text, name.name))
+        .max_by_key(|name| name.name.len())
+        .copied()
+}
+
+fn starts_with_title(text: &str, title: &str) -> bool {
+    matches!(
+        text.strip_prefix(title),
+        Some(rest) if rest.is_empty() || rest.starts_with([' ', ':', ','])
+    )
+}
+
+/// `Express (v2) Root Port (Slot+), MSI 00`
+fn pci_express(text: &str) -> Option<LspciName> {
+    let rest = text.strip_prefix("Express (v")?;
+    let (version, rest) = rest.split_once(") ")?;
+    let version = version.parse().ok()?;
+    let (_, device_type) = DEVICE_TYPES
+        .iter()
+        .filter(|(name, _)| starts_with_title(rest, name))
+        .max_by_key(|(name, _)| name.len())?;
+    Some(LspciName {
+        name: "Express",
+        item: LspciItem::PciExpress {
+            version,
+            device_type: *device_type,
+        },
+        type_path: "capabilities::PciExpress",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn titles() {
+        // MSI-X is not MSI
+        assert_eq!(
+            Some(LspciItem::Capability(0x11)),
+            lookup("MSI-X: Enable+ Count=1 Masked-").map(|name| name.item)
+        );
+        assert_eq!(
+            Some(LspciItem::Capability(0x05)),
+            lookup("MSI: Enable+ Count=1/1 Maskable- 64bit-").map(|name| name.item)
+        );
+        assert_eq!(
+            Some(LspciItem::Capability(0x01)),
+            lookup("Power Management version 3").map(|name| name.item)
+        );
+        // Longest title wins
+        assert_eq!(
+            Some(LspciItem::ExtendedCapability(0x0006)),
+            lookup_extended("Root Complex Internal Link <?>").map(|name| name.item)
+        );
+        assert_eq!(
+            Some(LspciItem::ExtendedCapability(0x0005)),
+            lookup_extended("Root Complex Link").map(|name| name.item)
+        );
+        // Word prefix is not a title
+        assert_eq!(None, lookup("Subsystemx"));
+        assert_eq!(None, lookup_extended("Express (v2) Endpoint"));
+    }
+
+    #[test]
+    fn pci_express() {
+        let sample = LspciName {
+            name: "Express",
+            item: LspciItem::PciExpress {
+                version: 1,
+                device_type: 0b0001,
+            },
+            type_path: "capabilities::PciExpress",
+        };
+        assert_eq!(Some(sample), lookup("Express (v1) Legacy Endpoint, MSI 00"));
+        assert_eq!(
+            Some(LspciItem::PciExpress {
+                version: 2,
+                device_type: 0b0000,
+            }),
+            lookup("Express (v2) Endpoint, MSI 00").map(|name| name.item)
+        );
+        assert_eq!(None, lookup("Express (v2) Unknown type 3"));
+    }
+
+    #[test]
+    fn registers() {
+        for name in REGISTERS {
+            assert_eq!(Some(name), lookup(name.name));
+        }
+        assert_eq!(
+            Some(LspciItem::PciExpressRegister(Register::DeviceControl2)),
+            lookup("DevCtl2:").map(|name| name.item)
+        );
+    }
+}
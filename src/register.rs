@@ -0,0 +1,277 @@
+//! Register bit fields conversions
+//!
+//! [register!] generates both `From<uN> for T` and `From<T> for uN` from a single field layout,
+//! so decoding and encoding of a register can not diverge. Fields are listed with their bit
+//! positions as half-open ranges, a single number is a one bit field. Bits not covered by any
+//! field are reserved: ignored on decoding and zero on encoding. A field named after the layout
+//! keeps reserved bits as read instead, in place.
+//!
+//! ```text
+//! register!(RevisionId: u8 {
+//!     minor[0..5],
+//!     major[5..8],
+//! });
+//! register!(ErrorStatus: u32 {
+//!     receiver_error[0],
+//!     bad_tlp[6],
+//! }, reserved);
+//! ```
+//!
+//! [flags!] adds a method yielding `(name, value)` pairs of one bit fields, names are the field
+//! names. Metrics exporters iterate status bits with it instead of matching every field.
+//!
+//! ```text
+//! flags!(
+//!     /// Error bits
+//!     ErrorStatus::error_bits { receiver_error, bad_tlp }
+//! );
+//! ```
+
+/// Register field value type
+pub(crate) trait Field: Sized {
+    fn from_bits(bits: u64) -> Self;
+    fn into_bits(self) -> u64;
+}
+
+impl Field for bool {
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+    fn into_bits(self) -> u64 {
+        self as u64
+    }
+}
+
+macro_rules! impl_uint_field {
+    ($($t:ty),+) => {$(
+        impl Field for $t {
+            fn from_bits(bits: u64) -> Self {
+                bits as $t
+            }
+            fn into_bits(self) -> u64 {
+                self as u64
+            }
+        }
+    )+};
+}
+impl_uint_field!(u8, u16, u32, u64);
+
+/// Implements [Field] for a type with `From<u8>` and `From<T> for u8` conversions
+macro_rules! impl_u8_field {
+    ($($t:ty),+ $(,)?) => {$(
+        impl $crate::register::Field for $t {
+            fn from_bits(bits: u64) -> Self {
+                From::<u8>::from(bits as u8)
+            }
+            fn into_bits(self) -> u64 {
+                u8::from(self) as u64
+            }
+        }
+    )+};
+}
+pub(crate) use impl_u8_field;
+
+/// Implements [Field] for a type with `From<u32>` and `From<T> for u32` conversions, nested
+/// registers are fields of their containing register
+macro_rules! impl_u32_field {
+    ($($t:ty),+ $(,)?) => {$(
+        impl $crate::register::Field for $t {
+            fn from_bits(bits: u64) -> Self {
+                From::<u32>::from(bits as u32)
+            }
+            fn into_bits(self) -> u64 {
+                u32::from(self) as u64
+            }
+        }
+    )+};
+}
+pub(crate) use impl_u32_field;
+
+/// Mask of `width` least significant bits
+pub(crate) const fn mask(width: u32) -> u64 {
+    if width >= u64::BITS {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+macro_rules! register {
+    (
+        $name:ident : $ty:ty {
+            $($field:ident [$lsb:literal $(.. $end:literal)?]),+ $(,)?
+        }, $rest:ident
+    ) => {
+        impl From<$ty> for $name {
+            fn from(value: $ty) -> Self {
+                let value = value as u64;
+                let defined = $crate::register::register!(@defined $($lsb $(.. $end)?),+);
+                Self {
+                    $($field: $crate::register::register!(@get value, $lsb $(.. $end)?),)+
+                    $rest: $crate::register::Field::from_bits(value & !defined),
+                }
+            }
+        }
+        impl From<$name> for $ty {
+            fn from(register: $name) -> Self {
+                let defined = $crate::register::register!(@defined $($lsb $(.. $end)?),+);
+                let mut value = $crate::register::Field::into_bits(register.$rest) & !defined;
+                $(
+                    let bits = $crate::register::Field::into_bits(register.$field);
+                    let mask = $crate::register::mask($crate::register::register!(
+                        @width $lsb $(.. $end)?
+                    ));
+                    value |= (bits & mask) << $lsb;
+                )+
+                value as $ty
+            }
+        }
+    };
+    (
+        $name:ident $(<const $g:ident: $gt:ty>)? : $ty:ty {
+            $($field:ident [$lsb:literal $(.. $end:literal)?]),+ $(,)?
+        }
+    ) => {
+        impl$(<const $g: $gt>)? From<$ty> for $name$(<$g>)? {
+            fn from(value: $ty) -> Self {
+                let value = value as u64;
+                Self {
+                    $($field: $crate::register::Field::from_bits(
+                        (value >> $lsb)
+                            & $crate::register::mask($crate::register::register!(
+                                @width $lsb $(.. $end)?
+                            )),
+                    ),)+
+                }
+            }
+        }
+        impl$(<const $g: $gt>)? From<$name$(<$g>)?> for $ty {
+            fn from(register: $name$(<$g>)?) -> Self {
+                let mut value = 0u64;
+                $(
+                    let bits = $crate::register::Field::into_bits(register.$field);
+                    let mask = $crate::register::mask($crate::register::register!(
+                        @width $lsb $(.. $end)?
+                    ));
+                    value |= (bits & mask) << $lsb;
+                )+
+                value as $ty
+            }
+        }
+    };
+    (@width $lsb:literal) => {
+        1
+    };
+    (@width $lsb:literal .. $end:literal) => {
+        $end - $lsb
+    };
+    (@get $value:ident, $lsb:literal $(.. $end:literal)?) => {
+        $crate::register::Field::from_bits(
+            ($value >> $lsb)
+                & $crate::register::mask($crate::register::register!(@width $lsb $(.. $end)?)),
+        )
+    };
+    (@defined $($lsb:literal $(.. $end:literal)?),+) => {
+        0u64 $(| $crate::register::mask($crate::register::register!(@width $lsb $(.. $end)?)) << $lsb)+
+    };
+}
+pub(crate) use register;
+
+macro_rules! flags {
+    (
+        $(#[$meta:meta])*
+        $name:ident :: $method:ident { $($field:ident),+ $(,)? }
+    ) => {
+        impl $name {
+            $(#[$meta])*
+            pub fn $method(
+                &self,
+            ) -> core::array::IntoIter<(&'static str, bool), { $crate::register::flags!(@count $($field)+) }> {
+                [$((stringify!($field), self.$field)),+].into_iter()
+            }
+        }
+    };
+    (@count $($field:ident)+) => {
+        0 $(+ $crate::register::flags!(@one $field))+
+    };
+    (@one $field:ident) => {
+        1
+    };
+}
+pub(crate) use flags;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Sample {
+        flag: bool,
+        value: u8,
+        high: u16,
+    }
+
+    register!(Sample: u32 {
+        flag[0],
+        value[4..8],
+        high[16..32],
+    });
+
+    #[test]
+    fn round_trip() {
+        let sample = Sample::from(0xbeef_ff31);
+        assert_eq!(
+            Sample {
+                flag: true,
+                value: 0x3,
+                high: 0xbeef
+            },
+            sample
+        );
+        assert_eq!(0xbeef_0031, u32::from(sample));
+        let overflow = Sample {
+            flag: false,
+            value: 0xff,
+            high: 0,
+        };
+        assert_eq!(0xf0, u32::from(overflow));
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct WithReserved {
+        flag: bool,
+        value: u8,
+        reserved: u16,
+    }
+
+    register!(WithReserved: u16 {
+        flag[0],
+        value[4..8],
+    }, reserved);
+
+    #[test]
+    fn reserved_bits() {
+        let sample = WithReserved::from(0xbe3f);
+        assert_eq!(
+            WithReserved {
+                flag: true,
+                value: 0x3,
+                reserved: 0xbe0e
+            },
+            sample
+        );
+        assert_eq!(0xbe3f, u16::from(sample));
+    }
+
+    flags!(Sample::bits { flag });
+    flags!(WithReserved::bits { flag });
+
+    #[test]
+    fn flag_pairs() {
+        let sample = Sample::from(0x0000_0001);
+        assert_eq!(vec![("flag", true)], sample.bits().collect::<Vec<_>>());
+        let sample = WithReserved::from(0xfffe);
+        assert_eq!(Some(("flag", false)), sample.bits().next());
+    }
+}
@@ -0,0 +1,476 @@
+/*!
+## Synthetic configuration spaces
+
+Ready-made configuration space images of typical PCI Express functions for tests. Every
+builder returns a full 4 KiB buffer that can be fed to any parser of this crate. Available
+with the `synthetic` feature.
+
+Layout of the generated functions:
+
+| Offset | Endpoint                          | Root Port                          |
+|--------|-----------------------------------|------------------------------------|
+| 00h    | Type 00h header, 64-bit BAR0      | Type 01h header, buses 00:01-01    |
+| 40h    | PCI Express v2, Endpoint          | PCI Express v2, Root Port          |
+| 80h    | MSI-X, table and PBA in BAR0      | MSI                                |
+| 100h   | -                                 | -                                  |
+
+[endpoint_with_extended_capabilities] and [root_port_with_extended_capabilities] extend these
+functions with Extended Capabilities listed in [ENDPOINT_EXTENDED_CAPABILITIES] and
+[ROOT_PORT_EXTENDED_CAPABILITIES] respectively.
+
+## Examples
+
+```rust
+# use pcics::{capabilities::CapabilityKind, synthetic, ConfigurationSpace};
+let data = synthetic::endpoint_with_msix(16);
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let msix = cs
+    .capabilities()
+    .find_map(|cap| match cap.ok()?.kind {
+        CapabilityKind::MsiX(msix) => Some(msix),
+        _ => None,
+    })
+    .unwrap();
+assert_eq!(15, msix.message_control.table_size);
+```
+*/
+
+use crate::{
+    capabilities::Capability, configuration_space::ConfigurationSpace,
+    extended_capabilities::ECH_BYTES,
+};
+
+/// Vendor ID of synthetic functions (Red Hat, Inc.)
+pub const VENDOR_ID: u16 = 0x1b36;
+/// Device ID of [endpoint_with_msix]
+pub const ENDPOINT_DEVICE_ID: u16 = 0xe001;
+/// Device ID of [root_port]
+pub const ROOT_PORT_DEVICE_ID: u16 = 0xe002;
+
+/// PCI Express Capability pointer
+pub const PCIE_PTR: u8 = 0x40;
+/// Interrupt (MSI-X or MSI) Capability pointer
+pub const INTERRUPT_PTR: u8 = 0x80;
+
+/// Maximum number of MSI-X vectors (Table Size is 11 bits wide)
+pub const MSIX_MAX_VECTORS: u16 = 2048;
+
+/// Extended Capabilities of [endpoint_with_extended_capabilities] as `(offset, id)` pairs
+pub const ENDPOINT_EXTENDED_CAPABILITIES: [(u16, u16); 16] = [
+    // Advanced Error Reporting
+    (0x100, 0x0001),
+    // Alternative Routing-ID Interpretation
+    (0x148, 0x000e),
+    // Address Translation Services
+    (0x150, 0x000f),
+    // Page Request Interface
+    (0x158, 0x0013),
+    // Process Address Space ID
+    (0x168, 0x001b),
+    // Single Root I/O Virtualization
+    (0x170, 0x0010),
+    // Latency Tolerance Reporting
+    (0x1b0, 0x0018),
+    // TPH Requester
+    (0x1b8, 0x0017),
+    // Resizable BAR
+    (0x1c8, 0x0015),
+    // Device Serial Number
+    (0x1d8, 0x0003),
+    // Precision Time Measurement
+    (0x1e8, 0x001f),
+    // Multicast
+    (0x1f8, 0x0012),
+    // Data Object Exchange
+    (0x228, 0x002e),
+    // Readiness Time Reporting
+    (0x240, 0x0022),
+    // VF Resizable BAR
+    (0x250, 0x0024),
+    // Multi-Root I/O Virtualization
+    (0x260, 0x0011),
+];
+
+/// Extended Capabilities of [root_port_with_extended_capabilities] as `(offset, id)` pairs
+pub const ROOT_PORT_EXTENDED_CAPABILITIES: [(u16, u16); 12] = [
+    // Advanced Error Reporting
+    (0x100, 0x0001),
+    // Access Control Services
+    (0x148, 0x000d),
+    // Secondary PCI Express
+    (0x150, 0x0019),
+    // Downstream Port Containment
+    (0x168, 0x001d),
+    // Precision Time Measurement
+    (0x180, 0x001f),
+    // L1 PM Substates
+    (0x190, 0x001e),
+    // Native PCIe Enclosure Management
+    (0x1a0, 0x0029),
+    // Physical Layer 16.0 GT/s
+    (0x1b0, 0x0026),
+    // Physical Layer 32.0 GT/s
+    (0x1d8, 0x002a),
+    // Lane Margining at the Receiver
+    (0x200, 0x0027),
+    // Data Link Feature
+    (0x288, 0x0025),
+    // FRS Queuing
+    (0x298, 0x0021),
+];
+
+/// Device ID of Virtual Functions of [endpoint_with_extended_capabilities]
+pub const VF_DEVICE_ID: u16 = 0xe003;
+
+/// Function configuration space image
+type Image = [u8; ConfigurationSpace::PCIE_SIZE];
+
+struct Builder(Image);
+
+impl Builder {
+    fn new(device_id: u16, header_type: u8, class_code: [u8; 3]) -> Self {
+        let mut b = Self([0; ConfigurationSpace::PCIE_SIZE]);
+        b.u16(0x00, VENDOR_ID);
+        b.u16(0x02, device_id);
+        // Command: Memory Space, Bus Master
+        b.u16(0x04, 0x0006);
+        // Status: Capabilities List
+        b.u16(0x06, 0x0010);
+        // Revision ID, Class Code
+        b.u8(0x08, 0x01);
+        b.0[0x09..0x0c].copy_from_slice(&class_code);
+        b.u8(0x0e, header_type);
+        b.u8(0x34, PCIE_PTR);
+        // Interrupt Pin: INTA#
+        b.u8(0x3d, 0x01);
+        b
+    }
+    fn u8(&mut self, offset: usize, value: u8) {
+        self.0[offset] = value;
+    }
+    fn u16(&mut self, offset: usize, value: u16) {
+        self.0[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+    fn u32(&mut self, offset: usize, value: u32) {
+        self.0[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    /// Capability header, returns offset of capability data
+    fn cap(&mut self, ptr: u8, id: u8, next: u8) -> usize {
+        self.u8(ptr as usize, id);
+        self.u8(ptr as usize + 1, next);
+        ptr as usize + Capability::HEADER_SIZE
+    }
+    /// PCI Express Capability version 2 with Device/Port Type `device_type`
+    fn pcie(&mut self, device_type: u8, slot: bool, next: u8) -> usize {
+        let data = self.cap(PCIE_PTR, 0x10, next);
+        self.u16(
+            data,
+            0x0002 | (device_type as u16) << 4 | (slot as u16) << 8,
+        );
+        // Device Capabilities: Max_Payload_Size 256 bytes, Role-Based Error Reporting
+        self.u32(data + 0x02, 0x0000_8001);
+        // Link Capabilities: 8.0 GT/s, x4
+        self.u32(data + 0x0a, 0x0000_0043);
+        // Link Status: 8.0 GT/s, x4
+        self.u16(data + 0x10, 0x0043);
+        data
+    }
+    /// Extended Capabilities list, returns offsets of capability data in `ecaps` order
+    fn ecaps<const N: usize>(&mut self, ecaps: &[(u16, u16); N]) -> [usize; N] {
+        let mut data = [0; N];
+        for (n, &(offset, id)) in ecaps.iter().enumerate() {
+            let next = ecaps.get(n + 1).map(|&(offset, _)| offset).unwrap_or(0);
+            // Capability Version 1 for all but AER
+            let version = if id == 0x0001 { 2 } else { 1 };
+            self.u32(
+                offset as usize,
+                id as u32 | version << 16 | (next as u32) << 20,
+            );
+            data[n] = offset as usize + ECH_BYTES;
+        }
+        data
+    }
+    fn build(self) -> Image {
+        self.0
+    }
+}
+
+/// PCI Express Endpoint with MSI-X Capability supporting `vectors` vectors
+///
+/// MSI-X Table is located at the start of 64-bit BAR0, Pending Bit Array follows the table
+///
+/// # Panics
+///
+/// Panics if `vectors` is not in `1..=2048` range
+pub fn endpoint_with_msix(vectors: u16) -> Image {
+    endpoint(vectors).build()
+}
+
+fn endpoint(vectors: u16) -> Builder {
+    assert!(
+        (1..=MSIX_MAX_VECTORS).contains(&vectors),
+        "MSI-X vectors should be in 1..=2048 range"
+    );
+    // Ethernet controller
+    let mut b = Builder::new(ENDPOINT_DEVICE_ID, 0x00, [0x00, 0x00, 0x02]);
+    // BAR0: 64-bit non-prefetchable memory at fe00_0000
+    b.u32(0x10, 0xfe00_0004);
+    b.u16(0x2c, VENDOR_ID);
+    b.u16(0x2e, ENDPOINT_DEVICE_ID);
+    b.pcie(0b0000, false, INTERRUPT_PTR);
+
+    let data = b.cap(INTERRUPT_PTR, 0x11, 0x00);
+    // MSI-X Table entry is 16 bytes, PBA is QWORD aligned
+    let table_size = vectors as u32 * 16;
+    b.u16(data, vectors - 1);
+    b.u32(data + 2, 0);
+    b.u32(data + 6, table_size);
+    b
+}
+
+/// PCI Express Root Port with MSI Capability, optionally connected to a hot-plug capable slot
+pub fn root_port(slot: bool) -> Image {
+    root_port_builder(slot).build()
+}
+
+fn root_port_builder(slot: bool) -> Builder {
+    // PCI-to-PCI bridge
+    let mut b = Builder::new(ROOT_PORT_DEVICE_ID, 0x01, [0x00, 0x04, 0x06]);
+    // Primary, Secondary and Subordinate Bus Numbers
+    b.0[0x18..0x1b].copy_from_slice(&[0x00, 0x01, 0x01]);
+    let data = b.pcie(0b0100, slot, INTERRUPT_PTR);
+    // Link Capabilities: Data Link Layer Link Active Reporting Capable, Port Number 1
+    b.u32(data + 0x0a, 0x0110_0043);
+    if slot {
+        // Slot Capabilities: Attention Button, Power Indicator, Hot-Plug Capable,
+        // 25 W Slot Power Limit, Physical Slot Number 1
+        let spl = 250 << 7 | 0b01 << 15;
+        b.u32(data + 0x12, 0b101_0001 | spl | 1 << 19);
+    }
+
+    // MSI: 64-bit address capable
+    let data = b.cap(INTERRUPT_PTR, 0x05, 0x00);
+    b.u16(data, 0x0080);
+    b
+}
+
+/// [endpoint_with_msix] with 8 vectors and [ENDPOINT_EXTENDED_CAPABILITIES]
+///
+/// The Physical Function supports up to 8 Virtual Functions with 64-bit VF BAR0, BAR0 is
+/// resizable up to 256 MB, VF BAR0 is resizable up to 16 MB.
+pub fn endpoint_with_extended_capabilities() -> Image {
+    let mut b = endpoint(8);
+    let [aer, ari, ats, pri, pasid, sriov, ltr, tph, rebar, dsn, ptm, mc, doe, rtr, vf_rebar, mriov] =
+        b.ecaps(&ENDPOINT_EXTENDED_CAPABILITIES);
+    // AER: Uncorrectable Error Severity defaults, ECRC Generation Capable
+    b.u32(aer + 0x08, 0x0046_2030);
+    b.u32(aer + 0x10, 0x0000_2000);
+    b.u32(aer + 0x14, 0x0000_0020);
+    // ARI: Next Function Number 0
+    b.u16(ari, 0x0000);
+    // ATS: Page Aligned Request, Enable
+    b.u16(ats, 0x0020);
+    b.u16(ats + 0x02, 0x8000);
+    // PRI: Stopped, Outstanding Page Request Capacity 512
+    b.u16(pri + 0x02, 0x0100);
+    b.u32(pri + 0x04, 0x200);
+    // PASID: Execute and Privileged Mode Permission Supported, Max PASID Width 20
+    b.u16(pasid, 0x1406);
+    // SR-IOV: VF Migration Capable off, ARI Capable Hierarchy Preserved
+    b.u32(sriov, 0x0000_0002);
+    // InitialVFs, TotalVFs
+    b.u16(sriov + 0x08, 8);
+    b.u16(sriov + 0x0a, 8);
+    // First VF Offset, VF Stride, VF Device ID
+    b.u16(sriov + 0x10, 0x0080);
+    b.u16(sriov + 0x12, 0x0001);
+    b.u16(sriov + 0x16, VF_DEVICE_ID);
+    // Supported Page Sizes: 4K, 8K, 64K, 256K, 1M, 4M; System Page Size: 4K
+    b.u32(sriov + 0x18, 0x0000_0553);
+    b.u32(sriov + 0x1c, 0x0000_0001);
+    // VF BAR0: 64-bit non-prefetchable memory
+    b.u32(sriov + 0x20, 0x0000_0004);
+    // LTR: Max Snoop/No-Snoop Latency 3 * 1024 ns
+    b.u16(ltr, 0x0803);
+    b.u16(ltr + 0x02, 0x0803);
+    // TPH Requester: No ST Mode Supported, ST Table is not present
+    b.u32(tph, 0x0000_0001);
+    // Resizable BAR: BAR0 supports 1 MB - 256 MB, 1 Resizable BAR, 256 MB BAR Size
+    b.u32(rebar, 0x0000_1ff0);
+    b.u32(rebar + 0x04, 0x0000_0820);
+    // DSN: EUI-64 based on Red Hat OUI
+    b.u32(dsn, 0x00e0_0100);
+    b.u32(dsn + 0x04, 0x001b_3600);
+    // PTM: Requester Capable, Enable
+    b.u32(ptm, 0x0000_0001);
+    b.u32(ptm + 0x04, 0x0000_0001);
+    // Multicast: MC_Max_Group 64, MC_Window_Size_Requested 2^12
+    b.u16(mc, 0x0c3f);
+    // DOE: Interrupt Support, Interrupt Message Number 1
+    b.u32(doe, 0x0000_0003);
+    // Readiness Time Reporting: Valid, Reset, DL_Up and FLR Time 96 x 32^4 ns (about 100 ms),
+    // D3hot to D0 Time 10 x 32^4 ns
+    b.u32(rtr, 0x8086_0860);
+    b.u32(rtr + 0x04, 0x0080_a860);
+    // VF Resizable BAR: VF BAR0 supports 1 MB - 16 MB, 1 VF Resizable BAR, 16 MB VF BAR Size
+    b.u32(vf_rebar, 0x0000_01f0);
+    b.u32(vf_rebar + 0x04, 0x0000_0420);
+    // MR-IOV: 4 Function Table entries, 2 Virtual Hierarchies, MR-IOV Enable, Function Table
+    // at 4 KB offset of BAR0
+    b.u32(mriov, 0x0002_0003);
+    b.u16(mriov + 0x04, 0x0001);
+    b.u32(mriov + 0x08, 0x0000_1000);
+    b.build()
+}
+
+/// [root_port] with a slot and [ROOT_PORT_EXTENDED_CAPABILITIES]
+///
+/// The Link supports up to 32.0 GT/s and is trained at 8.0 GT/s. Downstream Port Containment
+/// has no Root Port Extensions, Secondary PCI Express and Physical Layer 16.0/32.0 GT/s have
+/// Lane Equalization Control registers for all 4 lanes.
+pub fn root_port_with_extended_capabilities() -> Image {
+    let mut b = root_port_builder(true);
+    let pcie = PCIE_PTR as usize + Capability::HEADER_SIZE;
+    // Link Capabilities: 32.0 GT/s, x4; Link Capabilities 2: 2.5 - 32.0 GT/s
+    b.u32(pcie + 0x0a, 0x0110_0045);
+    b.u32(pcie + 0x2a, 0x0000_003e);
+    let [aer, acs, spcie, dpc, ptm, l1pm, npem, pl16, pl32, lmr, dlf, frs] =
+        b.ecaps(&ROOT_PORT_EXTENDED_CAPABILITIES);
+    // AER: Uncorrectable Error Severity defaults, Root Error Command: all errors reporting
+    b.u32(aer + 0x08, 0x0046_2030);
+    b.u32(aer + 0x28, 0x0000_0007);
+    // ACS: Source Validation, Translation Blocking, P2P Request/Completion Redirect,
+    // Upstream Forwarding and their enables
+    b.u16(acs, 0x001f);
+    b.u16(acs + 0x02, 0x001f);
+    // Secondary PCI Express: Lane Equalization Control, Transmitter Preset 4
+    for lane in 0..4 {
+        b.u16(spcie + 0x08 + lane * 2, 0x0404);
+    }
+    // DPC: Interrupt Message Number 0, DPC Software Triggering Supported,
+    // DL_Active ERR_COR Signaling Supported, DPC Trigger Enable on ERR_FATAL
+    b.u16(dpc, 0x1080);
+    b.u16(dpc + 0x02, 0x0001);
+    // PTM: Requester, Responder and Root Capable, Local Clock Granularity 16 ns
+    b.u32(ptm, 0x0000_1007);
+    b.u32(ptm + 0x04, 0x0000_0003);
+    // L1 PM Substates: PCI-PM and ASPM L1.1/L1.2 Supported, Port Common_Mode_Restore_Time
+    // 10 us, Port T_POWER_ON 10 us
+    b.u32(l1pm, 0x0009_0a1f);
+    // NPEM: Reset, OK, Locate and Fail Capable, NPEM Enable, OK indication
+    b.u32(npem, 0x0000_001f);
+    b.u32(npem + 0x04, 0x0000_0005);
+    // Physical Layer 16.0 GT/s: Equalization Complete, all Phases Successful, Downstream and
+    // Upstream Port Transmitter Preset 4 for all 4 lanes
+    b.u32(pl16 + 0x08, 0x0000_000f);
+    for lane in 0..4 {
+        b.u8(pl16 + 0x1c + lane, 0x44);
+    }
+    // Physical Layer 32.0 GT/s: Equalization bypass to highest rate and No Equalization
+    // Needed Supported, Modified TS Usage Mode 0 Supported, Equalization Complete, all Phases
+    // Successful, Transmitter Preset 4 for all 4 lanes
+    b.u32(pl32, 0x0000_0103);
+    b.u32(pl32 + 0x08, 0x0000_000f);
+    for lane in 0..4 {
+        b.u8(pl32 + 0x1c + lane, 0x44);
+    }
+    // Lane Margining at the Receiver: Margining Ready, No Command completed on all 4 lanes
+    b.u16(lmr + 0x02, 0x0001);
+    for lane in 0..4 {
+        b.u16(lmr + 0x04 + lane * 4, 0x9c38);
+        b.u16(lmr + 0x06 + lane * 4, 0x9c38);
+    }
+    // Data Link Feature: Scaled Flow Control supported by both Link partners, Data Link
+    // Feature Exchange Enable
+    b.u32(dlf, 0x8000_0001);
+    b.u32(dlf + 0x04, 0x8000_0001);
+    // FRS Queuing: FRS Queue Max Depth 16, FRS Message Received, FRS Interrupt Enable, FLR
+    // Completed message from 01:00.0 in the queue
+    b.u32(frs, 0x0000_0010);
+    b.u16(frs + 0x04, 0x0001);
+    b.u16(frs + 0x06, 0x0001);
+    b.u32(frs + 0x08, 0x0013_0100);
+    b.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capabilities::{pci_express::DeviceType, CapabilityKind},
+        header::HeaderType,
+    };
+    use pretty_assertions::assert_eq;
+    use std::vec::Vec;
+
+    fn kinds<'a>(cs: &'a ConfigurationSpace) -> Vec<CapabilityKind<'a>> {
+        assert_eq!(0, cs.extended_capabilities().unwrap().count());
+        cs.capabilities().map(|cap| cap.unwrap().kind).collect()
+    }
+
+    fn ecaps(data: &Image) -> Vec<(u16, u16)> {
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        cs.extended_capabilities()
+            .unwrap()
+            .map(|ecap| {
+                let ecap = ecap.unwrap();
+                (ecap.offset.into(), ecap.id())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn endpoint() {
+        let data = endpoint_with_msix(2048);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        assert!(matches!(cs.header.header_type, HeaderType::Normal(_)));
+        match kinds(&cs).as_slice() {
+            [CapabilityKind::PciExpress(pcie), CapabilityKind::MsiX(msix)] => {
+                assert!(matches!(pcie.device_type, DeviceType::Endpoint { .. }));
+                assert_eq!(2047, msix.message_control.table_size);
+                assert_eq!(0, msix.table.offset);
+                assert_eq!(2048 * 16, msix.pending_bit_array.offset);
+            }
+            v => panic!("{:?}", v),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn endpoint_without_vectors() {
+        endpoint_with_msix(0);
+    }
+
+    #[test]
+    fn root_port_slot() {
+        for slot in [false, true] {
+            let data = root_port(slot);
+            let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+            match kinds(&cs).as_slice() {
+                [CapabilityKind::PciExpress(pcie), CapabilityKind::MessageSignaledInterrups(_)] => {
+                    assert_eq!(slot, pcie.slot_implemented);
+                    match &pcie.device_type {
+                        DeviceType::RootPort { slot: s, link, .. } => {
+                            assert_eq!(slot, s.capabilities.hot_plug_capable);
+                            assert_eq!(1, link.capabilities.port_number);
+                        }
+                        v => panic!("{:?}", v),
+                    }
+                }
+                v => panic!("{:?}", v),
+            }
+        }
+    }
+
+    #[test]
+    fn extended_capabilities() {
+        assert_eq!(
+            ENDPOINT_EXTENDED_CAPABILITIES.as_slice(),
+            ecaps(&endpoint_with_extended_capabilities())
+        );
+        assert_eq!(
+            ROOT_PORT_EXTENDED_CAPABILITIES.as_slice(),
+            ecaps(&root_port_with_extended_capabilities())
+        );
+    }
+}
@@ -0,0 +1,401 @@
+/*!
+## Linux sysfs textual formats
+
+Driver binding tools on Linux identify functions by strings exposed through sysfs:
+- `modalias` attribute, e.g. `pci:v00008086d00002030sv00008086sd00000000bc06sc04i00`, is matched
+  against driver aliases to find a module to load
+- `new_id` / `remove_id` driver attributes take hex vendor, device, subvendor, subdevice,
+  class and class mask values separated by spaces, e.g. `0x8086 0x2030`
+
+[Modalias] and [NewId] are built from parsed registers and can be parsed back from the strings.
+
+## Examples
+
+```rust
+# use pcics::{sysfs::*, ConfigurationSpace};
+let data = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+let modalias = Modalias::from(&cs);
+let sample = "pci:v00008086d00002030sv00008086sd00000000bc06sc04i00";
+assert_eq!(sample, modalias.to_string());
+assert_eq!(Ok(modalias.clone()), sample.parse());
+
+assert_eq!("0x8086 0x2030", NewId::new(0x8086, 0x2030).to_string());
+let new_id = NewId::from(&modalias);
+assert_eq!("0x8086 0x2030 0x8086 0x0000 0x060400 0xffffff", new_id.to_string());
+assert_eq!(Ok(new_id), "8086 2030 8086 0 60400 ffffff".parse());
+```
+*/
+
+use core::{fmt, str::FromStr};
+
+use snafu::prelude::*;
+
+use crate::{
+    capabilities::CapabilityKind,
+    header::{ClassCode, HeaderType, Normal},
+    ConfigurationSpace, Header,
+};
+
+/// Matches any Subsystem Vendor ID / Subsystem ID in [NewId]
+pub const PCI_ANY_ID: u32 = !0;
+
+/// sysfs string parse error
+#[derive(Snafu, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SysfsError {
+    #[snafu(display("modalias {field} field is missing or malformed"))]
+    Modalias { field: &'static str },
+    #[snafu(display("new_id {field} field is missing or malformed"))]
+    NewId { field: &'static str },
+}
+
+/// PCI device modalias in the `pci:vVdDsvSVsdSDbcBCscSCiI` form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modalias {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Subsystem Vendor ID, 0 if the function does not report it
+    pub subsystem_vendor_id: u16,
+    /// Subsystem ID, 0 if the function does not report it
+    pub subsystem_device_id: u16,
+    pub class_code: ClassCode,
+}
+
+impl Modalias {
+    pub const PREFIX: &'static str = "pci:";
+}
+
+/// Subsystem IDs are taken from the header, they are 0 for PCI-to-PCI bridges
+impl From<&Header> for Modalias {
+    fn from(header: &Header) -> Self {
+        let (subsystem_vendor_id, subsystem_device_id) = match &header.header_type {
+            HeaderType::Normal(Normal {
+                sub_vendor_id,
+                sub_device_id,
+                ..
+            }) => (*sub_vendor_id, *sub_device_id),
+            #[cfg(feature = "cardbus")]
+            HeaderType::Cardbus(cardbus) => (
+                cardbus.subsystem_vendor_id.unwrap_or(0),
+                cardbus.subsystem_device_id.unwrap_or(0),
+            ),
+            _ => (0, 0),
+        };
+        Self {
+            vendor_id: header.vendor_id,
+            device_id: header.device_id,
+            subsystem_vendor_id,
+            subsystem_device_id,
+            class_code: header.class_code.clone(),
+        }
+    }
+}
+
+/// Subsystem IDs of PCI-to-PCI bridges are taken from the PCI Bridge Subsystem Vendor ID
+/// Capability the same way Linux does
+impl From<&ConfigurationSpace<'_>> for Modalias {
+    fn from(cs: &ConfigurationSpace<'_>) -> Self {
+        let mut modalias = Self::from(&cs.header);
+        if let HeaderType::Bridge(_) = cs.header.header_type {
+            let ssvid = cs.capabilities().find_map(|cap| match cap.ok()?.kind {
+                CapabilityKind::BridgeSubsystemVendorId(ssvid) => Some(ssvid),
+                _ => None,
+            });
+            if let Some(ssvid) = ssvid {
+                modalias.subsystem_vendor_id = ssvid.subsystem_vendor_id;
+                modalias.subsystem_device_id = ssvid.subsystem_id;
+            }
+        }
+        modalias
+    }
+}
+
+impl fmt::Display for Modalias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}v{:08X}d{:08X}sv{:08X}sd{:08X}bc{:02X}sc{:02X}i{:02X}",
+            Self::PREFIX,
+            self.vendor_id,
+            self.device_id,
+            self.subsystem_vendor_id,
+            self.subsystem_device_id,
+            self.class_code.base,
+            self.class_code.sub,
+            self.class_code.interface,
+        )
+    }
+}
+
+impl FromStr for Modalias {
+    type Err = SysfsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        /// Takes `prefix` followed by `digits` hex digits
+        fn field<'a, T: TryFrom<u32>>(
+            s: &'a str,
+            prefix: &str,
+            digits: usize,
+            field: &'static str,
+        ) -> Result<(T, &'a str), SysfsError> {
+            s.strip_prefix(prefix)
+                .filter(|s| s.get(..digits).is_some_and(is_hex))
+                .and_then(|s| {
+                    let value = u32::from_str_radix(&s[..digits], 16).ok()?;
+                    Some((value.try_into().ok()?, &s[digits..]))
+                })
+                .context(ModaliasSnafu { field })
+        }
+        let s = s.trim_end();
+        let s = s
+            .strip_prefix(Self::PREFIX)
+            .context(ModaliasSnafu { field: "prefix" })?;
+        let (vendor_id, s) = field(s, "v", 8, "vendor")?;
+        let (device_id, s) = field(s, "d", 8, "device")?;
+        let (subsystem_vendor_id, s) = field(s, "sv", 8, "subvendor")?;
+        let (subsystem_device_id, s) = field(s, "sd", 8, "subdevice")?;
+        let (base, s) = field(s, "bc", 2, "base class")?;
+        let (sub, s) = field(s, "sc", 2, "subclass")?;
+        let (interface, s) = field(s, "i", 2, "interface")?;
+        ensure!(s.is_empty(), ModaliasSnafu { field: "interface" });
+        Ok(Self {
+            vendor_id,
+            device_id,
+            subsystem_vendor_id,
+            subsystem_device_id,
+            class_code: ClassCode {
+                interface,
+                sub,
+                base,
+            },
+        })
+    }
+}
+
+/// Dynamic ID written to `new_id` or `remove_id` attribute of a PCI driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewId {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Subsystem Vendor ID and Subsystem ID, [PCI_ANY_ID] matches any value
+    pub subsystem: Option<(u32, u32)>,
+    /// 24-bit Class Code and mask of the bits to compare
+    pub class: Option<(u32, u32)>,
+}
+
+impl NewId {
+    /// ID matching any function with `vendor_id` and `device_id`
+    pub const fn new(vendor_id: u16, device_id: u16) -> Self {
+        Self {
+            vendor_id,
+            device_id,
+            subsystem: None,
+            class: None,
+        }
+    }
+}
+
+impl From<&Header> for NewId {
+    fn from(header: &Header) -> Self {
+        Self::from(&Modalias::from(header))
+    }
+}
+
+/// ID matching exactly the subsystem and the class code of the function
+impl From<&Modalias> for NewId {
+    fn from(modalias: &Modalias) -> Self {
+        let ClassCode {
+            interface,
+            sub,
+            base,
+        } = modalias.class_code;
+        Self {
+            vendor_id: modalias.vendor_id,
+            device_id: modalias.device_id,
+            subsystem: Some((
+                modalias.subsystem_vendor_id as u32,
+                modalias.subsystem_device_id as u32,
+            )),
+            class: Some((
+                (base as u32) << 16 | (sub as u32) << 8 | interface as u32,
+                0xff_ffff,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for NewId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x} {:#06x}", self.vendor_id, self.device_id)?;
+        // Class fields follow the subsystem fields
+        let subsystem = self
+            .subsystem
+            .or(self.class.map(|_| (PCI_ANY_ID, PCI_ANY_ID)));
+        if let Some((vendor, device)) = subsystem {
+            write!(f, " {:#06x} {:#06x}", vendor, device)?;
+        }
+        if let Some((class, mask)) = self.class {
+            write!(f, " {:#08x} {:#08x}", class, mask)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NewId {
+    type Err = SysfsError;
+
+    /// Parses whitespace separated hex values with optional `0x` prefix, driver data is ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = s.split_whitespace().map(|s| {
+            let s = s
+                .strip_prefix("0x")
+                .or_else(|| s.strip_prefix("0X"))
+                .unwrap_or(s);
+            u32::from_str_radix(s, 16).ok()
+        });
+        let mut next = |field: &'static str, required: bool| match values.next() {
+            Some(Some(value)) => Ok(Some(value)),
+            None if !required => Ok(None),
+            _ => NewIdSnafu { field }.fail(),
+        };
+        let id = |value: Option<u32>, field| {
+            value
+                .and_then(|v| u16::try_from(v).ok())
+                .context(NewIdSnafu { field })
+        };
+        let vendor_id = id(next("vendor", true)?, "vendor")?;
+        let device_id = id(next("device", true)?, "device")?;
+        let subsystem = match next("subvendor", false)? {
+            Some(vendor) => Some((vendor, next("subdevice", true)?.unwrap_or_default())),
+            None => None,
+        };
+        let class = match next("class", false)? {
+            Some(class) => Some((class, next("class mask", true)?.unwrap_or_default())),
+            None => None,
+        };
+        Ok(Self {
+            vendor_id,
+            device_id,
+            subsystem,
+            class,
+        })
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn modalias() {
+        let data = crate::synthetic::endpoint_with_extended_capabilities();
+        let header = Header::try_from(data.as_slice()).unwrap();
+        let result = Modalias::from(&header);
+        let parsed: Modalias = result.to_string().parse().unwrap();
+        assert_eq!(result, parsed);
+
+        let sample = Modalias {
+            vendor_id: 0x8086,
+            device_id: 0xa102,
+            subsystem_vendor_id: 0x1028,
+            subsystem_device_id: 0x06e4,
+            class_code: ClassCode {
+                interface: 0x01,
+                sub: 0x06,
+                base: 0x01,
+            },
+        };
+        let s = "pci:v00008086d0000A102sv00001028sd000006E4bc01sc06i01";
+        assert_eq!(s, sample.to_string());
+        assert_eq!(Ok(sample), format!("{}\n", s).parse());
+
+        let error = |field| Err(SysfsError::Modalias { field });
+        assert_eq!(error("prefix"), "usb:v8086".parse::<Modalias>());
+        // Wildcards of driver aliases do not describe a function
+        let s = "pci:v00008086d*sv*sd*bc*sc*i*";
+        assert_eq!(error("device"), s.parse::<Modalias>());
+        // Vendor ID is 16-bit wide
+        let s = "pci:v00018086d0000A102sv00001028sd000006E4bc01sc06i01";
+        assert_eq!(error("vendor"), s.parse::<Modalias>());
+        let s = "pci:v00008086d0000A102sv00001028sd000006E4bc01sc06i011";
+        assert_eq!(error("interface"), s.parse::<Modalias>());
+    }
+
+    #[test]
+    fn bridge_modalias() {
+        let data = crate::synthetic::root_port(true);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = Modalias::from(&cs);
+        assert_eq!(
+            (0, 0),
+            (result.subsystem_vendor_id, result.subsystem_device_id)
+        );
+
+        // PCI Bridge Subsystem Vendor ID Capability appended to the list
+        let mut data = data;
+        let last = cs.capabilities().offsets().last().unwrap().0;
+        data[last.get() as usize + 1] = 0xf0;
+        data[0xf0..0xf8].copy_from_slice(&[0x0d, 0x00, 0x00, 0x00, 0x28, 0x10, 0xe4, 0x06]);
+        let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+        let result = Modalias::from(&cs);
+        assert_eq!(
+            (0x1028, 0x06e4),
+            (result.subsystem_vendor_id, result.subsystem_device_id)
+        );
+        assert_eq!(
+            (0, 0),
+            (
+                Modalias::from(&cs.header).subsystem_vendor_id,
+                Modalias::from(&cs.header).subsystem_device_id
+            )
+        );
+    }
+
+    #[test]
+    fn new_id() {
+        let sample = NewId {
+            vendor_id: 0x8086,
+            device_id: 0xa102,
+            subsystem: None,
+            class: Some((0x010601, 0xffff00)),
+        };
+        let s = "0x8086 0xa102 0xffffffff 0xffffffff 0x010601 0xffff00";
+        assert_eq!(s, sample.to_string());
+        let sample = NewId {
+            subsystem: Some((PCI_ANY_ID, PCI_ANY_ID)),
+            ..sample
+        };
+        assert_eq!(Ok(sample), s.parse());
+        // Driver data
+        assert_eq!(Ok(sample), format!("{} 0x1\n", s).parse());
+
+        let sample = NewId {
+            subsystem: Some((0x1028, 0)),
+            class: None,
+            ..sample
+        };
+        assert_eq!("0x8086 0xa102 0x1028 0x0000", sample.to_string());
+        assert_eq!(Ok(sample), "8086 A102 1028 0".parse());
+
+        let error = |field| Err(SysfsError::NewId { field });
+        assert_eq!(error("vendor"), "".parse::<NewId>());
+        assert_eq!(error("device"), "8086".parse::<NewId>());
+        assert_eq!(error("device"), "8086 1a102".parse::<NewId>());
+        assert_eq!(error("subdevice"), "8086 a102 1028".parse::<NewId>());
+        assert_eq!(
+            error("class mask"),
+            "8086 a102 1028 0 10601".parse::<NewId>()
+        );
+        assert_eq!(error("class"), "8086 a102 1028 0 xyz".parse::<NewId>());
+    }
+}
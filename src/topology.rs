@@ -0,0 +1,372 @@
+/*!
+## PCI Express hierarchy
+
+Functions found by an enumeration (e.g. [Ecam](crate::ecam::Ecam) or sysfs) are a flat list.
+[Topology] links them into a tree: a bridge (Root Port, Switch Upstream or Downstream Port,
+PCI-to-PCI bridge) is the parent of every function located on its Secondary Bus in the same
+PCI Segment Group. Functions without such bridge, e.g. Root Ports and Root Complex Integrated
+Endpoints, are the roots of the tree.
+
+Bus numbers are taken from the header and are valid only after the bridges are configured by
+the enumeration software. A bridge with Secondary Bus Number not greater than its own Bus
+Number is treated as not configured and has no children.
+
+Each [Node] holds the parsed configuration space and the [Device/Port
+Type](crate::capabilities::pci_express::DeviceType) of the PCI Express Capability, so a Switch
+is an Upstream Port node with Downstream Port children. Available with the `alloc` feature.
+
+## Examples
+
+```rust
+# use pcics::{configuration_space::FunctionAddress, topology::*, ConfigurationSpace};
+// Root Port at ae:00.0 with Secondary Bus afh
+let root_port = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+let endpoint = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_9dc8/config"
+));
+let functions = [
+    (FunctionAddress::new(0, 0xaf, 0, 0).unwrap(), endpoint.as_slice()),
+    (FunctionAddress::new(0, 0xae, 0, 0).unwrap(), root_port.as_slice()),
+];
+let topology = functions
+    .into_iter()
+    .map(|(address, data)| (address, ConfigurationSpace::try_from(data).unwrap()))
+    .collect::<Topology>();
+
+let root = topology.roots().next().unwrap();
+assert_eq!("0000:ae:00.0", root.address.to_string());
+assert!(root.device_type.as_ref().unwrap().is_root());
+let child = root.children().next().unwrap();
+assert_eq!("0000:af:00.0", child.address.to_string());
+assert_eq!(Some(root.address), child.parent().map(|parent| parent.address));
+assert_eq!(1, child.depth());
+```
+*/
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{ops::Deref, slice};
+
+use crate::{
+    bdf::Bus,
+    capabilities::{pci_express::DeviceType, CapabilityKind},
+    configuration_space::FunctionAddress,
+    header::HeaderType,
+    ConfigurationSpace,
+};
+
+/// Function of the hierarchy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node<'a> {
+    pub address: FunctionAddress,
+    /// Configuration space with attached [address](Self::address)
+    pub configuration_space: ConfigurationSpace<'a>,
+    /// Device/Port Type, `None` for conventional PCI functions
+    pub device_type: Option<DeviceType>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+impl Node<'_> {
+    /// Secondary Bus Number if the function is a configured bridge
+    pub fn secondary_bus(&self) -> Option<Bus> {
+        let secondary = match &self.configuration_space.header.header_type {
+            HeaderType::Bridge(bridge) => bridge.secondary_bus_number,
+            #[cfg(feature = "cardbus")]
+            HeaderType::Cardbus(cardbus) => cardbus.cardbus_bus_number,
+            _ => return None,
+        };
+        (secondary > self.address.bus).then_some(secondary)
+    }
+}
+
+/// Tree of functions, see [module](self) documentation
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Topology<'a> {
+    /// Nodes sorted by address
+    nodes: Vec<Node<'a>>,
+    roots: Vec<usize>,
+}
+
+impl<'a> Topology<'a> {
+    /// Builds the tree of `functions`, a function repeated at the same address is ignored
+    pub fn new<I>(functions: I) -> Self
+    where
+        I: IntoIterator<Item = (FunctionAddress, ConfigurationSpace<'a>)>,
+    {
+        let mut nodes = functions
+            .into_iter()
+            .map(|(address, cs)| {
+                let device_type = cs.capabilities().find_map(|cap| match cap.ok()?.kind {
+                    CapabilityKind::PciExpress(pcie) => Some(pcie.device_type),
+                    _ => None,
+                });
+                Node {
+                    address,
+                    configuration_space: cs.with_address(address),
+                    device_type,
+                    parent: None,
+                    children: Vec::new(),
+                }
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.address);
+        nodes.dedup_by_key(|node| node.address);
+        // The first bridge wins if secondary buses overlap
+        let mut bridges = BTreeMap::new();
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(secondary) = node.secondary_bus() {
+                bridges
+                    .entry((node.address.segment, secondary))
+                    .or_insert(index);
+            }
+        }
+        let mut roots = Vec::new();
+        let mut links = Vec::new();
+        for (index, node) in nodes.iter().enumerate() {
+            let FunctionAddress { segment, bus, .. } = node.address;
+            match bridges.get(&(segment, bus)) {
+                Some(&parent) => links.push((parent, index)),
+                None => roots.push(index),
+            }
+        }
+        for (parent, child) in links {
+            if let Some(node) = nodes.get_mut(child) {
+                node.parent = Some(parent);
+            }
+            if let Some(node) = nodes.get_mut(parent) {
+                node.children.push(child);
+            }
+        }
+        Self { nodes, roots }
+    }
+    /// Number of functions
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Functions without parent bridge
+    pub fn roots(&self) -> Nodes<'_, 'a> {
+        Nodes {
+            topology: self,
+            indices: self.roots.iter(),
+        }
+    }
+    /// Function at `address`
+    pub fn get(&self, address: FunctionAddress) -> Option<NodeRef<'_, 'a>> {
+        self.nodes
+            .binary_search_by_key(&address, |node| node.address)
+            .ok()
+            .and_then(|index| self.node(index))
+    }
+    /// An iterator through all functions in address order
+    pub fn iter(&self) -> impl Iterator<Item = NodeRef<'_, 'a>> {
+        self.nodes.iter().map(move |node| NodeRef {
+            topology: self,
+            node,
+        })
+    }
+    fn node(&self, index: usize) -> Option<NodeRef<'_, 'a>> {
+        self.nodes.get(index).map(|node| NodeRef {
+            topology: self,
+            node,
+        })
+    }
+}
+
+impl<'a> FromIterator<(FunctionAddress, ConfigurationSpace<'a>)> for Topology<'a> {
+    fn from_iter<T: IntoIterator<Item = (FunctionAddress, ConfigurationSpace<'a>)>>(
+        iter: T,
+    ) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// [Node] with access to its relatives
+#[derive(Debug, Clone, Copy)]
+pub struct NodeRef<'t, 'a> {
+    topology: &'t Topology<'a>,
+    node: &'t Node<'a>,
+}
+
+impl<'t, 'a> NodeRef<'t, 'a> {
+    /// Bridge the function is located below
+    pub fn parent(&self) -> Option<Self> {
+        self.parent.and_then(|index| self.topology.node(index))
+    }
+    /// Functions on the Secondary Bus of the bridge
+    pub fn children(&self) -> Nodes<'t, 'a> {
+        Nodes {
+            topology: self.topology,
+            indices: self.node.children.iter(),
+        }
+    }
+    /// Number of bridges above the function
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut node = *self;
+        while let Some(parent) = node.parent() {
+            depth += 1;
+            node = parent;
+        }
+        depth
+    }
+    /// Parsed function data living as long as the topology
+    pub fn node(&self) -> &'t Node<'a> {
+        self.node
+    }
+}
+
+impl<'a> Deref for NodeRef<'_, 'a> {
+    type Target = Node<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.node()
+    }
+}
+
+/// An iterator through roots or children of a node
+#[derive(Debug, Clone)]
+pub struct Nodes<'t, 'a> {
+    topology: &'t Topology<'a>,
+    indices: slice::Iter<'t, usize>,
+}
+
+impl<'t, 'a> Iterator for Nodes<'t, 'a> {
+    type Item = NodeRef<'t, 'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let topology = self.topology;
+        self.indices.find_map(|&index| topology.node(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bdf, synthetic};
+    use pretty_assertions::assert_eq;
+    use std::prelude::v1::*;
+
+    /// Root Port image turned into `device_type` with the bus numbers
+    fn bridge(device_type: u8, buses: [u8; 3]) -> Vec<u8> {
+        let mut data = synthetic::root_port(false).to_vec();
+        data[0x18..0x1b].copy_from_slice(&buses);
+        let ptr = synthetic::PCIE_PTR as usize + 2;
+        data[ptr] = data[ptr] & 0x0f | device_type << 4;
+        data
+    }
+
+    type Function = ((u16, u8, u8, u8), Vec<u8>);
+
+    fn topology(functions: &[Function]) -> Topology<'_> {
+        functions
+            .iter()
+            .map(|((s, b, d, f), data)| {
+                let address = FunctionAddress::new(*s, *b, *d, *f).unwrap();
+                let cs = ConfigurationSpace::try_from(data.as_slice()).unwrap();
+                (address, cs)
+            })
+            .collect()
+    }
+
+    fn addresses<'t, 'a: 't>(nodes: impl Iterator<Item = NodeRef<'t, 'a>>) -> Vec<String> {
+        nodes.map(|node| node.address.to_string()).collect()
+    }
+
+    #[test]
+    fn switch() {
+        let endpoint = synthetic::endpoint_with_msix(1).to_vec();
+        let functions = [
+            // Root Ports
+            ((0, 0x00, 0x1c, 0), bridge(0b0100, [0x00, 0x01, 0x04])),
+            ((0, 0x00, 0x1d, 0), bridge(0b0100, [0x00, 0x05, 0x05])),
+            // Switch
+            ((0, 0x01, 0x00, 0), bridge(0b0101, [0x01, 0x02, 0x04])),
+            ((0, 0x02, 0x00, 0), bridge(0b0110, [0x02, 0x03, 0x03])),
+            ((0, 0x02, 0x01, 0), bridge(0b0110, [0x02, 0x04, 0x04])),
+            // Endpoints
+            ((0, 0x03, 0x00, 0), endpoint.clone()),
+            ((0, 0x03, 0x00, 1), endpoint.clone()),
+            ((0, 0x05, 0x00, 0), endpoint.clone()),
+            // Root Complex Integrated Endpoint
+            ((0, 0x00, 0x02, 0), endpoint.clone()),
+            // Same bus in other segment
+            ((1, 0x03, 0x00, 0), endpoint.clone()),
+        ];
+        let topology = topology(&functions);
+        assert_eq!(functions.len(), topology.len());
+
+        let sample = [
+            "0000:00:02.0",
+            "0000:00:1c.0",
+            "0000:00:1d.0",
+            "0001:03:00.0",
+        ];
+        assert_eq!(sample.to_vec(), addresses(topology.roots()));
+
+        let address = FunctionAddress::from_bdf(
+            0,
+            Bus(0),
+            bdf::Device::new(0x1c).unwrap(),
+            bdf::Function::new(0).unwrap(),
+        );
+        let root_port = topology.get(address).unwrap();
+        assert!(root_port.device_type.as_ref().unwrap().is_root());
+        assert_eq!(Some(Bus(1)), root_port.secondary_bus());
+        let upstream = root_port.children().next().unwrap();
+        assert!(matches!(
+            upstream.device_type,
+            Some(DeviceType::UpstreamPort { .. })
+        ));
+        assert_eq!(
+            vec!["0000:02:00.0", "0000:02:01.0"],
+            addresses(upstream.children())
+        );
+        let downstream = upstream.children().next().unwrap();
+        assert_eq!(
+            vec!["0000:03:00.0", "0000:03:00.1"],
+            addresses(downstream.children())
+        );
+        let endpoint = downstream.children().nth(1).unwrap();
+        assert_eq!(3, endpoint.depth());
+        assert_eq!(0, endpoint.children().count());
+        assert_eq!(
+            Some(endpoint.address),
+            endpoint.configuration_space.address()
+        );
+        // Empty Downstream Port
+        let downstream = upstream.children().nth(1).unwrap();
+        assert_eq!(0, downstream.children().count());
+        let endpoint = topology
+            .get(FunctionAddress::new(0, 5, 0, 0).unwrap())
+            .unwrap();
+        assert_eq!(
+            FunctionAddress::new(0, 0, 0x1d, 0),
+            endpoint.parent().map(|node| node.address)
+        );
+    }
+
+    #[test]
+    fn not_configured() {
+        let endpoint = synthetic::endpoint_with_msix(1).to_vec();
+        // Bus numbers are not assigned yet, bridges pointing to each other
+        let functions = [
+            ((0, 0x00, 0x1c, 0), bridge(0b0100, [0x00, 0x00, 0x00])),
+            ((0, 0x01, 0x00, 0), bridge(0b0101, [0x00, 0x00, 0x00])),
+            ((0, 0x02, 0x00, 0), bridge(0b0110, [0x00, 0x01, 0x01])),
+            ((0, 0x02, 0x00, 0), endpoint),
+        ];
+        let topology = topology(&functions);
+        // Repeated address is ignored
+        assert_eq!(3, topology.len());
+        assert_eq!(3, topology.roots().count());
+        assert!(topology.iter().all(|node| node.secondary_bus().is_none()));
+        assert!(Topology::new([]).is_empty());
+    }
+}
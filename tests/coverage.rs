@@ -0,0 +1,67 @@
+//! Extended Capabilities parse coverage
+//!
+//! Every device dump from `tests/data/device` and every synthetic function should be parsed
+//! completely: all capabilities are decoded without errors and none of extended capabilities
+//! falls back to Reserved or not yet decoded placeholder kinds.
+//!
+//! ```sh
+//! cargo test --features synthetic --test coverage
+//! ```
+
+use std::{fs, path::Path};
+
+use pcics::{
+    extended_capabilities::{ExtendedCapability, ExtendedCapabilityKind as Kind},
+    synthetic, ConfigurationSpace,
+};
+
+/// Fully parsed extended capabilities as `(offset, id)` pairs
+fn parse(name: &str, data: &[u8]) -> Vec<(u16, u16)> {
+    let cs = ConfigurationSpace::try_from(data).unwrap();
+    for cap in cs.capabilities() {
+        assert!(cap.is_ok(), "{}: {:?}", name, cap);
+    }
+    let ecaps = match cs.extended_capabilities() {
+        Some(ecaps) => ecaps,
+        None => return Vec::new(),
+    };
+    ecaps
+        .map(|ecap| {
+            let ecap: ExtendedCapability = ecap.unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert!(
+                !ecap.kind.is_placeholder() && !matches!(ecap.kind, Kind::Reserved(_)),
+                "{}: {:?}",
+                name,
+                ecap
+            );
+            (ecap.offset.into(), ecap.id())
+        })
+        .collect()
+}
+
+#[test]
+fn device_dumps() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/device");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path().join("config");
+        parse(&path.display().to_string(), &fs::read(&path).unwrap());
+    }
+}
+
+#[test]
+fn synthetic_endpoint() {
+    let data = synthetic::endpoint_with_extended_capabilities();
+    assert_eq!(
+        synthetic::ENDPOINT_EXTENDED_CAPABILITIES.as_slice(),
+        parse("synthetic endpoint", &data)
+    );
+}
+
+#[test]
+fn synthetic_root_port() {
+    let data = synthetic::root_port_with_extended_capabilities();
+    assert_eq!(
+        synthetic::ROOT_PORT_EXTENDED_CAPABILITIES.as_slice(),
+        parse("synthetic root port", &data)
+    );
+}
@@ -0,0 +1,739 @@
+//! Golden decode of a device dump
+//!
+//! `tests/data/device/8086_2030` is a Root Port of Intel Xeon Scalable processor (Sky Lake-E).
+//! Every structure of the dump is decoded and compared field by field with the complete
+//! sample, so the test is an executable specification of the decoding and catches
+//! regressions in any of the modules the dump touches.
+//!
+//! ```sh
+//! cargo test --test golden
+//! ```
+
+use pcics::{
+    bdf::Bus,
+    capabilities::{Capability, CapabilityKind},
+    extended_capabilities::{ExtendedCapability, ExtendedCapabilityKind},
+    ConfigOffset, ConfigurationSpace,
+};
+use pretty_assertions::assert_eq;
+
+const DATA: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/device/8086_2030/config"
+));
+
+fn configuration_space() -> ConfigurationSpace<'static> {
+    ConfigurationSpace::try_from(DATA).unwrap()
+}
+
+fn capability<'a>(cs: &'a ConfigurationSpace, pointer: u16) -> CapabilityKind<'a> {
+    let pointer = ConfigOffset(pointer);
+    cs.capabilities()
+        .map(Result::unwrap)
+        .find(|cap| cap.pointer == pointer)
+        .map(|Capability { kind, .. }| kind)
+        .unwrap()
+}
+
+fn extended_capability(offset: u16) -> ExtendedCapabilityKind<'static> {
+    let offset = ConfigOffset(offset);
+    configuration_space()
+        .extended_capabilities()
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|ecap| ecap.offset == offset)
+        .map(|ExtendedCapability { kind, .. }| kind)
+        .unwrap()
+}
+
+#[test]
+fn lists() {
+    let cs = configuration_space();
+    let result = cs
+        .capabilities()
+        .map(|cap| cap.map(|cap| (cap.pointer.get(), cap.id())))
+        .collect::<Vec<_>>();
+    let sample = vec![
+        Ok((0x40, 0x0d)),
+        Ok((0x60, 0x05)),
+        Ok((0x90, 0x10)),
+        Ok((0xe0, 0x01)),
+    ];
+    assert_eq!(sample, result);
+    let result = cs
+        .extended_capabilities()
+        .unwrap()
+        .map(|ecap| ecap.map(|ecap| (ecap.offset.get(), ecap.version, ecap.id())))
+        .collect::<Vec<_>>();
+    let sample = vec![
+        Ok((0x100, 1, 0x000b)),
+        Ok((0x110, 1, 0x000d)),
+        Ok((0x148, 1, 0x0001)),
+        Ok((0x1d0, 1, 0x000b)),
+        Ok((0x250, 1, 0x0019)),
+        Ok((0x280, 1, 0x000b)),
+        Ok((0x298, 1, 0x000b)),
+        Ok((0x300, 1, 0x000b)),
+    ];
+    assert_eq!(sample, result);
+}
+
+#[test]
+fn header() {
+    use pcics::header::*;
+    let sample = Header {
+        vendor_id: 0x8086,
+        device_id: 0x2030,
+        command: Command {
+            io_space: true,
+            memory_space: true,
+            bus_master: true,
+            special_cycles: false,
+            memory_write_and_invalidate_enable: false,
+            vga_palette_snoop: false,
+            parity_error_response: true,
+            stepping: false,
+            serr_enable: true,
+            fast_back_to_back_enable: false,
+            interrupt_disable: true,
+            reserved: 0,
+        },
+        status: PrimaryStatus {
+            reserved: 0,
+            interrupt_status: false,
+            capabilities_list: true,
+            is_66mhz_capable: false,
+            user_definable_features: false,
+            fast_back_to_back_capable: false,
+            master_data_parity_error: false,
+            devsel_timing: DevselTiming::Fast,
+            signaled_target_abort: false,
+            received_target_abort: false,
+            received_master_abort: false,
+            system_error: false,
+            detected_parity_error: false,
+        },
+        revision_id: 0x04,
+        class_code: ClassCode {
+            interface: 0x00,
+            sub: 0x04,
+            base: 0x06,
+        },
+        cache_line_size: 0,
+        latency_timer: 0,
+        is_multi_function: false,
+        header_type: HeaderType::Bridge(Bridge {
+            base_addresses: BaseAddresses::new([0; 2]),
+            primary_bus_number: Bus(0xae),
+            secondary_bus_number: Bus(0xaf),
+            subordinate_bus_number: Bus(0xaf),
+            secondary_latency_timer: 0,
+            io_address_range: BridgeIoAddressRange::IoAddr16 {
+                base: 0xf000,
+                limit: 0x0000,
+            },
+            secondary_status: SecondaryStatus {
+                reserved: 0,
+                interrupt_status: false,
+                capabilities_list: false,
+                is_66mhz_capable: false,
+                user_definable_features: false,
+                fast_back_to_back_capable: false,
+                master_data_parity_error: false,
+                devsel_timing: DevselTiming::Fast,
+                signaled_target_abort: false,
+                received_target_abort: false,
+                received_master_abort: true,
+                system_error: false,
+                detected_parity_error: false,
+            },
+            memory_base: 0xe1a0,
+            memory_limit: 0xe1a0,
+            prefetchable_memory: BridgePrefetchableMemory::MemAddr64 {
+                base: 0xe100_0000,
+                limit: 0xe180_0000,
+            },
+            expansion_rom: ExpansionRom {
+                address: 0,
+                reserved: 0,
+                is_enabled: false,
+            },
+            bridge_control: BridgeControl {
+                parity_error_response_enable: true,
+                serr_enable: true,
+                isa_enable: false,
+                vga_enable: false,
+                vga_16_enable: false,
+                master_abort_mode: false,
+                secondary_bus_reset: false,
+                fast_back_to_back_enable: false,
+                primary_discard_timer: false,
+                secondary_discard_timer: false,
+                discard_timer_status: false,
+                discard_timer_serr_enable: false,
+            },
+        }),
+        bist: BuiltInSelfTest {
+            is_capable: false,
+            is_running: false,
+            completion_code: 0,
+        },
+        capabilities_pointer: 0x40,
+        interrupt_line: 0xff,
+        interrupt_pin: InterruptPin::IntA,
+    };
+    assert_eq!(sample, configuration_space().header);
+}
+
+#[test]
+fn bridge_subsystem_vendor_id() {
+    use pcics::capabilities::BridgeSubsystemVendorId;
+    let sample = CapabilityKind::BridgeSubsystemVendorId(BridgeSubsystemVendorId {
+        reserved: 0,
+        subsystem_vendor_id: 0x8086,
+        subsystem_id: 0x0000,
+    });
+    assert_eq!(sample, capability(&configuration_space(), 0x40));
+}
+
+#[test]
+fn message_signaled_interrupts() {
+    use pcics::capabilities::message_signaled_interrups::*;
+    let sample = CapabilityKind::MessageSignaledInterrups(MessageSignaledInterrups {
+        message_control: MessageControl {
+            msi_enable: true,
+            multiple_message_capable: MultipleMessage(1),
+            multiple_message_enable: MultipleMessage(0),
+            a_64_bit_address_capable: false,
+            per_vector_masking_capable: true,
+            extended_message_data_capable: false,
+            extended_message_data_enable: false,
+            raw: 0x0103,
+        },
+        message_address: MessageAddress::Dword(0xfee0_0038),
+        message_data: 0x0000,
+        extended_message_data: 0x0000,
+        mask_bits: Some(0x0000_0002),
+        pending_bits: Some(0x0000_0000),
+    });
+    assert_eq!(sample, capability(&configuration_space(), 0x60));
+}
+
+#[test]
+fn pci_express() {
+    use pcics::capabilities::pci_express::*;
+    let link = Link {
+        capabilities: LinkCapabilities {
+            max_link_speed: LinkSpeed::Rate8GTps,
+            maximum_link_width: LinkWidth::X16,
+            active_state_power_management_support: ActiveStatePowerManagement::L1,
+            l0s_exit_latency: L0sExitLatency::Ge256nsAndLt512ns,
+            l1_exit_latency: L1ExitLatency::Ge8usAndLt16us,
+            clock_power_management: false,
+            surprise_down_error_reporting_capable: true,
+            data_link_layer_link_active_reporting_capable: true,
+            link_bandwidth_notification_capability: true,
+            aspm_optionality_compliance: true,
+            port_number: 5,
+        },
+        control: LinkControl {
+            active_state_power_management_control: ActiveStatePowerManagement::NoAspm,
+            read_completion_boundary: ReadCompletionBoundary::B64,
+            link_disable: false,
+            retrain_link: false,
+            common_clock_configuration: true,
+            extended_synch: false,
+            enable_clock_power_management: false,
+            hardware_autonomous_width_disable: false,
+            link_bandwidth_management_interrupt_enable: false,
+            link_autonomous_bandwidth_interrupt_enable: false,
+        },
+        status: LinkStatus {
+            current_link_speed: LinkSpeed::Rate8GTps,
+            negotiated_link_width: LinkWidth::X4,
+            link_training_error: false,
+            link_training: false,
+            slot_clock_configuration: true,
+            data_link_layer_link_active: true,
+            link_bandwidth_management_status: false,
+            link_autonomous_bandwidth_status: false,
+        },
+    };
+    let no_speeds = SupportedLinkSpeedsVector {
+        speed_2_5_gtps: false,
+        speed_5_0_gtps: false,
+        speed_8_0_gtps: false,
+        speed_16_0_gtps: false,
+        speed_32_0_gtps: false,
+        speed_64_0_gtps: false,
+        reserved: false,
+    };
+    let link_2 = Link2 {
+        capabilities: LinkCapabilities2 {
+            supported_link_speeds_vector: SupportedLinkSpeedsVector {
+                speed_2_5_gtps: true,
+                speed_5_0_gtps: true,
+                speed_8_0_gtps: true,
+                ..no_speeds.clone()
+            },
+            crosslink_supported: false,
+            lower_skp_os_generation_supported_speeds_vector: no_speeds.clone(),
+            lower_skp_os_reception_supported_speeds_vector: no_speeds,
+            retimer_presence_detect_supported: false,
+            two_retimers_presence_detect_supported: false,
+            drs_supported: false,
+        },
+        control: LinkControl2 {
+            target_link_speed: LinkSpeed::Rate8GTps,
+            enter_compliance: false,
+            hardware_autonomous_speed_disable: false,
+            selectable_de_emphasis: DeEmphasis::Minus3_5dB,
+            transmit_margin: TransmitMargin(0),
+            enter_modified_compliance: false,
+            compliance_sos: false,
+            compliance_preset_or_de_emphasis: CompliancePresetOrDeEmphasis(0),
+        },
+        status: LinkStatus2 {
+            current_de_emphasis_level: DeEmphasis::Minus3_5dB,
+            equalization_complete: true,
+            equalization_phase_1_successful: true,
+            equalization_phase_2_successful: true,
+            equalization_phase_3_successful: true,
+            link_equalization_request: false,
+            retimer_presence_detected: false,
+            two_retimers_presence_detected: false,
+            crosslink_resolution: CrosslinkResolution::NotSupported,
+            downstream_component_presence: DownstreamComponentPresence::DownNotDetermined,
+            drs_message_received: false,
+        },
+    };
+    let slot = Slot {
+        capabilities: SlotCapabilities {
+            attention_button_present: false,
+            power_controller_present: false,
+            mrl_sensor_present: false,
+            attention_indicator_present: false,
+            power_indicator_present: false,
+            hot_plug_surprise: false,
+            hot_plug_capable: false,
+            slot_power_limit: SlotPowerLimit {
+                value: 75,
+                scale: 1.0,
+            },
+            electromechanical_interlock_present: false,
+            no_command_completed_support: false,
+            physical_slot_number: 4,
+        },
+        control: SlotControl {
+            attention_button_pressed_enable: false,
+            power_fault_detected_enable: false,
+            mrl_sensor_changed_enable: false,
+            presence_detect_changed_enable: false,
+            command_completed_interrupt_enable: false,
+            hot_plug_interrupt_enable: false,
+            attention_indicator_control: IndicatorControl::Off,
+            power_indicator_control: IndicatorControl::Off,
+            power_controller_control: false,
+            electromechanical_interlock_control: false,
+            data_link_layer_state_changed_enable: false,
+        },
+        status: SlotStatus {
+            attention_button_pressed: false,
+            power_fault_detected: false,
+            mrl_sensor_changed: false,
+            presence_detect_changed: true,
+            command_completed: false,
+            mrl_sensor_state: false,
+            presence_detect_state: true,
+            electromechanical_interlock_status: false,
+            data_link_layer_state_changed: true,
+        },
+    };
+    let root = Root {
+        control: RootControl {
+            system_error_on_correctable_error_enable: false,
+            system_error_on_non_fatal_error_enable: true,
+            system_error_on_fatal_error_enable: true,
+            pme_interrupt_enable: true,
+            crs_software_visibility_enable: true,
+            no_nfm_subtree_below_this_root_port: false,
+        },
+        capabilities: RootCapabilities {
+            crs_software_visibility: true,
+        },
+        status: RootStatus {
+            pme_requester_id: 0,
+            pme_status: false,
+            pme_pending: false,
+        },
+    };
+    let device = Device {
+        capabilities: DeviceCapabilities {
+            max_payload_size_supported: MaxSize::B256,
+            phantom_functions_supported: PhantomFunctionsSupported::NoBits,
+            extended_tag_field_supported: ExtendedTagFieldSupported::Eight,
+            endpoint_l0s_acceptable_latency: EndpointL0sAcceptableLatency::Max64ns,
+            endpoint_l1_acceptable_latency: EndpointL1AcceptableLatency::Max1us,
+            attention_button_present: false,
+            attention_indicator_present: false,
+            power_indicator_present: false,
+            role_based_error_reporting: true,
+            captured_slot_power_limit: SlotPowerLimit {
+                value: 0,
+                scale: 1.0,
+            },
+            function_level_reset_capability: false,
+        },
+        control: DeviceControl {
+            correctable_error_reporting_enable: false,
+            non_fatal_error_reporting_enable: false,
+            fatal_error_reporting_enable: true,
+            unsupported_request_reporting_enable: false,
+            enable_relaxed_ordering: false,
+            max_payload_size: MaxSize::B256,
+            extended_tag_field_enable: true,
+            phantom_functions_enable: false,
+            aux_power_pm_enable: false,
+            enable_no_snoop: false,
+            max_read_request_size: MaxSize::B128,
+            bcre_or_flreset: false,
+        },
+        status: DeviceStatus {
+            correctable_error_detected: false,
+            non_fatal_error_detected: false,
+            fatal_error_detected: false,
+            unsupported_request_detected: false,
+            aux_power_detected: false,
+            transactions_pending: false,
+            emergency_power_reduction_detected: false,
+        },
+    };
+    let device_2 = Device2 {
+        capabilities: DeviceCapabilities2 {
+            completion_timeout_ranges_supported: CompletionTimeoutRanges::RangesBCD,
+            completion_timeout_disable_supported: true,
+            ari_forwarding_supported: true,
+            atomic_op_routing_supported: false,
+            u32_atomicop_completer_supported: true,
+            u64_atomicop_completer_supported: true,
+            u128_cas_completer_supported: true,
+            no_ro_enabled_pr_pr_passing: false,
+            ltr_mechanism_supported: false,
+            tph_completer_supported: TphCompleter::Tph,
+            ln_system_cls: LnSystemCls::NotSupported,
+            support_10bit_tag_completer: false,
+            support_10bit_tag_requester: false,
+            obff_supported: Obff::NotSupported,
+            extended_fmt_field_supported: false,
+            end_end_tlp_prefix_supported: false,
+            max_end_end_tlp_prefixes: MaxEndEndTlpPrefixes::Max4,
+            emergency_power_reduction_supported: EmergencyPowerReduction::NotSupported,
+            emergency_power_reduction_initialization_required: false,
+            frs_supported: false,
+        },
+        control: DeviceControl2 {
+            completion_timeout_value: CompletionTimeoutValue::RangeB65msTo210ms,
+            completion_timeout_disable: false,
+            ari_forwarding_enable: true,
+            atomic_op_requester_enable: false,
+            atomic_op_egress_blocking: false,
+            ido_request_enable: false,
+            ido_completion_enable: false,
+            ltr_mechanism_enable: false,
+            emergency_power_reduction_request: false,
+            enable_10bit_tag_requester: false,
+            obff_enable: ObffEnable::Disabled,
+            end_end_tlp_prefix_blocking: EndEndTlpPrefixBlocking::ForwardingEnabled,
+        },
+        status: DeviceStatus2 {},
+    };
+    let sample = CapabilityKind::PciExpress(PciExpress {
+        version: 2,
+        device_type: DeviceType::RootPort {
+            link,
+            link_2: Some(link_2),
+            slot,
+            slot_2: Some(Slot2 {
+                capabilities: SlotCapabilities2 {},
+                control: SlotControl2 {},
+                status: SlotStatus2 {},
+            }),
+            root,
+        },
+        slot_implemented: true,
+        interrupt_message_number: 0,
+        tcs_routing_support: false,
+        device,
+        device_2: Some(device_2),
+        register_blocks: RegisterBlocks {
+            root: RegisterBlock::Present,
+            device_2: RegisterBlock::Present,
+            link_2: RegisterBlock::Present,
+            slot_2: RegisterBlock::Present,
+        },
+    });
+    assert_eq!(sample, capability(&configuration_space(), 0x90));
+}
+
+#[test]
+fn power_management_interface() {
+    use pcics::capabilities::power_management_interface::*;
+    let sample = CapabilityKind::PowerManagementInterface(PowerManagementInterface {
+        capabilities: Capabilities {
+            version: 3,
+            pme_clock: false,
+            immediate_readiness_on_return_to_d0: false,
+            device_specific_initialization: false,
+            aux_current: AuxCurrent::SelfPowered,
+            d1_support: false,
+            d2_support: false,
+            pme_support: PmeSupport {
+                d0: true,
+                d1: false,
+                d2: false,
+                d3_hot: true,
+                d3_cold: true,
+            },
+        },
+        control: Control {
+            power_state: PowerState::D0,
+            no_soft_reset: true,
+            pme_enabled: false,
+            data_select: DataSelect::PowerConsumedD0,
+            data_scale: DataScale::Unknown,
+            pme_status: false,
+        },
+        bridge: Bridge {
+            reserved: 0,
+            b2_b3: false,
+            bpcc_enabled: false,
+        },
+        data: 0,
+    });
+    assert_eq!(sample, capability(&configuration_space(), 0xe0));
+}
+
+#[test]
+fn vendor_specific() {
+    use pcics::extended_capabilities::vendor_specific_extended_capability::*;
+    let vsec = |offset| match extended_capability(offset) {
+        ExtendedCapabilityKind::VendorSpecificExtendedCapability(vsec) => vsec,
+        kind => panic!("{:?}", kind),
+    };
+    let result = [0x100, 0x1d0, 0x280, 0x298, 0x300].map(vsec);
+    let header = |vsec_id, vsec_rev, vsec_length| VsecHeader {
+        vsec_id,
+        vsec_rev,
+        vsec_length,
+    };
+    let sample = [
+        VendorSpecificExtendedCapability {
+            header: header(0x0002, 0, 0x00c),
+            registers: VsecRegisters::Valid(&[0x07, 0x38, 0x00, 0x00]),
+        },
+        VendorSpecificExtendedCapability {
+            header: header(0x0003, 1, 0x00a),
+            registers: VsecRegisters::Valid(&[0x01, 0x00]),
+        },
+        VendorSpecificExtendedCapability {
+            header: header(0x0005, 3, 0x018),
+            registers: VsecRegisters::Valid(&[
+                0x05, 0x00, 0x00, 0x40, 0x30, 0xe0, 0x3f, 0x00, 0x78, 0x00, 0x00, 0x00, 0x60, 0x00,
+                0x00, 0x00,
+            ]),
+        },
+        VendorSpecificExtendedCapability {
+            header: header(0x0007, 0, 0x024),
+            registers: VsecRegisters::Valid(&[
+                0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xc7, 0x3f, 0x07, 0x00, 0x00, 0x00,
+                0x00, 0x0a, 0x04, 0x20, 0x00, 0xaf, 0x00, 0x00, 0x00, 0xae, 0x00, 0x00, 0x00, 0x00,
+            ]),
+        },
+        VendorSpecificExtendedCapability {
+            header: header(0x0008, 0, 0x038),
+            registers: VsecRegisters::Valid(&[
+                0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ]),
+        },
+    ];
+    assert_eq!(sample, result);
+}
+
+#[test]
+fn access_control_services() {
+    use pcics::extended_capabilities::access_control_services::*;
+    let acs = match extended_capability(0x110) {
+        ExtendedCapabilityKind::AccessControlServices(acs) => acs,
+        kind => panic!("{:?}", kind),
+    };
+    let sample = AcsCapability {
+        acs_source_validation: true,
+        acs_translation_blocking: true,
+        acs_p2p_request_redirect: true,
+        acs_p2p_completion_redirect: true,
+        acs_upstream_forwarding: true,
+        acs_p2p_egress_control: false,
+        acs_direct_translated_p2p: false,
+        egress_control_vector_size: 0,
+    };
+    assert_eq!(sample, acs.acs_capability);
+    let sample = AcsControl {
+        acs_source_validation_enable: false,
+        acs_translation_blocking_enable: false,
+        acs_p2p_request_redirect_enable: false,
+        acs_p2p_completion_redirect_enable: false,
+        acs_upstream_forwarding_enable: false,
+        acs_p2p_egress_control_enable: false,
+        acs_direct_translated_p2p_enable: false,
+    };
+    assert_eq!(sample, acs.acs_control);
+    assert_eq!(0, acs.egress_control_vectors().count());
+}
+
+#[test]
+fn advanced_error_reporting() {
+    use pcics::extended_capabilities::advanced_error_reporting::*;
+    let no_errors = UncorrectableError {
+        link_training_error: false,
+        data_link_protocol_error_status: false,
+        surprise_down_error_status: false,
+        poisoned_tlp_received_status: false,
+        flow_control_protocol_error_status: false,
+        completion_timeout_status: false,
+        completer_abort_status: false,
+        unexpected_completion_status: false,
+        receiver_overflow_status: false,
+        malformed_tlp_status: false,
+        ecrc_error_status: false,
+        unsupported_request_error_status: false,
+        acs_violation_status: false,
+        uncorrectable_internal_error_status: false,
+        mc_blocked_tlp_status: false,
+        atomicop_egress_blocked_status: false,
+        tlp_prefix_blocked_error_status: false,
+        poisoned_tlp_egress_blocked_status: false,
+        dmwr_request_egress_blocked_status: false,
+        ide_check_failed_status: false,
+        misrouted_ide_tlp_status: false,
+        pcrc_check_failed_status: false,
+        tlp_translation_egress_blocked_status: false,
+        reserved: 0,
+    };
+    let sample = ExtendedCapabilityKind::AdvancedErrorReporting(AdvancedErrorReporting {
+        uncorrectable_error_status: no_errors.clone(),
+        uncorrectable_error_mask: UncorrectableError {
+            unexpected_completion_status: true,
+            unsupported_request_error_status: true,
+            acs_violation_status: true,
+            ..no_errors.clone()
+        },
+        uncorrectable_error_severity: UncorrectableError {
+            data_link_protocol_error_status: true,
+            surprise_down_error_status: true,
+            poisoned_tlp_received_status: true,
+            flow_control_protocol_error_status: true,
+            completion_timeout_status: true,
+            completer_abort_status: true,
+            receiver_overflow_status: true,
+            malformed_tlp_status: true,
+            ecrc_error_status: true,
+            ..no_errors
+        },
+        correctable_error_status: CorrectableError {
+            receiver_error_status: false,
+            bad_tlp_status: false,
+            bad_dllp_status: false,
+            replay_num_rollover_status: false,
+            replay_timer_timeout_status: false,
+            advisory_non_fatal_error_status: false,
+            corrected_internal_error_status: false,
+            header_log_overflow_status: false,
+            reserved: 0,
+        },
+        correctable_error_mask: CorrectableError {
+            receiver_error_status: true,
+            bad_tlp_status: true,
+            bad_dllp_status: true,
+            replay_num_rollover_status: true,
+            replay_timer_timeout_status: true,
+            advisory_non_fatal_error_status: true,
+            corrected_internal_error_status: false,
+            header_log_overflow_status: false,
+            reserved: 0,
+        },
+        advanced_error_capabilities_and_control: AdvancedErrorCapabilitiesAndControl {
+            first_error_pointer: 0,
+            ecrc_generation_capable: true,
+            ecrc_generation_enable: true,
+            ecrc_check_capable: true,
+            ecrc_check_enable: true,
+            multiple_header_recording_capable: false,
+            multiple_header_recording_enable: false,
+            tlp_prefix_log_present: false,
+            completion_timeout_prefix_or_header_log_capable: false,
+        },
+        header_log: HeaderLog([0; 4]),
+        root_error_command: Some(RootErrorCommand {
+            correctable_error_reporting_enable: false,
+            non_fatal_error_reporting_enable: false,
+            fatal_error_reporting_enable: false,
+        }),
+        root_error_status: Some(RootErrorStatus {
+            err_cor_received: false,
+            multiple_err_cor_received: false,
+            err_fatal_or_nonfatal_received: false,
+            multiple_err_fatal_or_nonfatal_received: false,
+            first_uncorrectable_fatal: false,
+            non_fatal_error_messages_received: false,
+            fatal_error_messages_received: false,
+            advanced_error_interrupt_message_number: 0,
+        }),
+        error_source_identification: Some(ErrorSourceIdentification {
+            err_cor_source_identification: 0,
+            err_fatal_or_nonfatal_source_identification: 0,
+        }),
+        tlp_prefix_log: None,
+    });
+    assert_eq!(sample, extended_capability(0x148));
+}
+
+#[test]
+fn secondary_pci_express() {
+    use pcics::capabilities::pci_express::{
+        LinkWidth, ReceiverPresetHint, SupportedLinkSpeedsVector, TransmitterPreset,
+    };
+    use pcics::extended_capabilities::secondary_pci_express::*;
+    let spe = match extended_capability(0x250) {
+        ExtendedCapabilityKind::SecondaryPciExpress(spe) => spe,
+        kind => panic!("{:?}", kind),
+    };
+    let sample = LinkControl3 {
+        perform_equalization: false,
+        link_equalization_request_interrupt_enable: false,
+        lower_skp_os_generation_vector: SupportedLinkSpeedsVector {
+            speed_2_5_gtps: false,
+            speed_5_0_gtps: false,
+            speed_8_0_gtps: false,
+            speed_16_0_gtps: false,
+            speed_32_0_gtps: false,
+            speed_64_0_gtps: false,
+            reserved: false,
+        },
+    };
+    assert_eq!(sample, spe.link_control_3);
+    assert_eq!(LaneErrorStatus(0), spe.lane_error_status);
+    // Every Lane of x16 Link: 2778h
+    let lane = LaneEqualizationControl {
+        downstream_port_transmitter_preset: TransmitterPreset::P8,
+        downstream_port_receiver_preset_hint: ReceiverPresetHint::Reserved,
+        upstream_port_transmitter_preset: TransmitterPreset::P7,
+        upstream_port_receiver_preset_hint: ReceiverPresetHint::Minus8dB,
+    };
+    let result = spe
+        .equalization_control_lanes(LinkWidth::X16)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![lane; 16], result);
+}
@@ -0,0 +1,442 @@
+//! Minimal port of lspci capability decoding (pciutils ls-caps.c)
+//!
+//! Only raw field extraction is ported, output formatting is omitted. Register offsets and
+//! masks follow pciutils lib/header.h. Known lspci quirks are kept as is, so this module must
+//! not be "fixed" to match pcics.
+
+use std::collections::BTreeMap;
+
+use super::{Fields, Walk, WalkEnd};
+
+const PCI_STATUS: usize = 0x06;
+const PCI_STATUS_CAP_LIST: u16 = 0x10;
+const PCI_HEADER_TYPE: usize = 0x0e;
+const PCI_CAPABILITY_LIST: usize = 0x34;
+const PCI_CB_CAPABILITY_LIST: usize = 0x14;
+
+const PCI_CAP_ID_PM: u8 = 0x01;
+const PCI_CAP_ID_MSI: u8 = 0x05;
+const PCI_CAP_ID_HT: u8 = 0x08;
+const PCI_CAP_ID_DBG: u8 = 0x0a;
+const PCI_CAP_ID_EXP: u8 = 0x10;
+
+const PCI_HT_CMD_TYP_HI: u16 = 0xe000;
+const PCI_HT_CMD_TYP_HI_PRI: u16 = 0x0000;
+const PCI_HT_CMD_TYP_HI_SEC: u16 = 0x2000;
+const PCI_HT_PRI_LCTR0: usize = 4;
+const PCI_HT_PRI_LCNF0: usize = 6;
+const PCI_HT_PRI_LCTR1: usize = 8;
+const PCI_HT_PRI_LCNF1: usize = 10;
+const PCI_HT_PRI_RID: usize = 12;
+const PCI_HT_PRI_LFRER0: usize = 13;
+const PCI_HT_PRI_LFCAP0: usize = 14;
+const PCI_HT_PRI_FTR: usize = 16;
+const PCI_HT_PRI_LFRER1: usize = 17;
+const PCI_HT_PRI_LFCAP1: usize = 18;
+const PCI_HT_PRI_MBU: usize = 24;
+const PCI_HT_PRI_MLU: usize = 25;
+const PCI_HT_PRI_BN: usize = 26;
+const PCI_HT_PRI_SIZEOF: usize = 28;
+const PCI_HT_SEC_LCTR: usize = 4;
+const PCI_HT_SEC_LCNF: usize = 6;
+const PCI_HT_SEC_RID: usize = 8;
+const PCI_HT_SEC_LFRER: usize = 9;
+const PCI_HT_SEC_LFCAP: usize = 10;
+const PCI_HT_SEC_FTR: usize = 12;
+const PCI_HT_SEC_MBU: usize = 20;
+const PCI_HT_SEC_MLU: usize = 21;
+const PCI_HT_SEC_SIZEOF: usize = 24;
+
+const PCI_EXP_DEVCAP: usize = 0x04;
+const PCI_EXP_DEVCTL: usize = 0x08;
+const PCI_EXP_LNKCAP: usize = 0x0c;
+const PCI_EXP_LNKSTA: usize = 0x12;
+const PCI_EXP_SLTCAP: usize = 0x14;
+const PCI_EXP_TYPE_ENDPOINT: u16 = 0x0;
+const PCI_EXP_TYPE_LEG_END: u16 = 0x1;
+const PCI_EXP_TYPE_ROOT_PORT: u16 = 0x4;
+const PCI_EXP_TYPE_UPSTREAM: u16 = 0x5;
+const PCI_EXP_TYPE_DOWNSTREAM: u16 = 0x6;
+const PCI_EXP_TYPE_PCI_BRIDGE: u16 = 0x7;
+const PCI_EXP_TYPE_PCIE_BRIDGE: u16 = 0x8;
+const PCI_EXP_TYPE_ROOT_INT_EP: u16 = 0x9;
+const PCI_EXP_TYPE_ROOT_EC: u16 = 0xa;
+
+struct Config<'a>(&'a [u8]);
+
+impl Config<'_> {
+    fn byte(&self, pos: usize) -> u8 {
+        self.0[pos]
+    }
+    fn word(&self, pos: usize) -> u16 {
+        u16::from_le_bytes([self.0[pos], self.0[pos + 1]])
+    }
+    fn long(&self, pos: usize) -> u32 {
+        u32::from_le_bytes([
+            self.0[pos],
+            self.0[pos + 1],
+            self.0[pos + 2],
+            self.0[pos + 3],
+        ])
+    }
+    /// `config_fetch()`: dump is able to provide `len` bytes at `pos`
+    fn fetch(&self, pos: usize, len: usize) -> bool {
+        pos + len <= self.0.len()
+    }
+}
+
+/// `show_caps()`
+pub fn decode(data: &[u8]) -> (Walk, Fields) {
+    let d = Config(data);
+    let mut walk = Walk::default();
+    let mut fields = BTreeMap::new();
+    if d.word(PCI_STATUS) & PCI_STATUS_CAP_LIST == 0 {
+        return (walk, fields);
+    }
+    let cap_ptr = if d.byte(PCI_HEADER_TYPE) & 0x7f == 2 {
+        PCI_CB_CAPABILITY_LIST
+    } else {
+        PCI_CAPABILITY_LIST
+    };
+    let mut been_there = [false; 256];
+    let mut r#where = d.byte(cap_ptr) & !3;
+    while r#where != 0 {
+        let pos = r#where as usize;
+        if !d.fetch(pos, 4) {
+            walk.end = WalkEnd::AccessDenied;
+            break;
+        }
+        let id = d.byte(pos);
+        let next = d.byte(pos + 1) & !3;
+        if been_there[pos] {
+            walk.end = WalkEnd::Looped;
+            break;
+        }
+        been_there[pos] = true;
+        if id == 0xff {
+            walk.end = WalkEnd::Broken;
+            break;
+        }
+        walk.caps.push((r#where, id));
+        let prefix = format!("cap[{:02x}]", r#where);
+        let mut set = |name: &str, value: u64| {
+            fields.insert(format!("{}.{}", prefix, name), value);
+        };
+        match id {
+            PCI_CAP_ID_PM => cap_pm(&d, pos, &mut set),
+            PCI_CAP_ID_MSI => cap_msi(&d, pos, &mut set),
+            PCI_CAP_ID_HT => cap_ht(&d, pos, &mut set),
+            PCI_CAP_ID_DBG => cap_debug_port(&d, pos, &mut set),
+            PCI_CAP_ID_EXP => cap_express(&d, pos, &mut set),
+            _ => (),
+        }
+        r#where = next;
+    }
+    (walk, fields)
+}
+
+fn cap_pm(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let pmc = d.word(pos + 2);
+    set("pm.version", (pmc & 0x0007) as u64);
+    set("pm.pme_clock", (pmc & 0x0008 != 0) as u64);
+    set("pm.dsi", (pmc & 0x0020 != 0) as u64);
+    set("pm.aux_current", ((pmc >> 6) & 7) as u64);
+    set("pm.d1", (pmc & 0x0200 != 0) as u64);
+    set("pm.d2", (pmc & 0x0400 != 0) as u64);
+    set("pm.pme_support", (pmc >> 11) as u64);
+    if !d.fetch(pos + 4, 2) {
+        return;
+    }
+    let pmcsr = d.word(pos + 4);
+    set("pm.power_state", (pmcsr & 0x0003) as u64);
+    set("pm.no_soft_reset", (pmcsr & 0x0008 != 0) as u64);
+    set("pm.pme_enable", (pmcsr & 0x0100 != 0) as u64);
+    set("pm.data_select", ((pmcsr >> 9) & 15) as u64);
+    set("pm.data_scale", ((pmcsr >> 13) & 3) as u64);
+    set("pm.pme_status", (pmcsr & 0x8000 != 0) as u64);
+}
+
+fn cap_msi(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let ctrl = d.word(pos + 2);
+    let is64 = ctrl & 0x0080 != 0;
+    set("msi.enable", (ctrl & 0x0001) as u64);
+    set("msi.count_capable", 1 << ((ctrl & 0x000e) >> 1));
+    set("msi.count_enabled", 1 << ((ctrl & 0x0070) >> 4));
+    set("msi.maskable", (ctrl & 0x0100 != 0) as u64);
+    set("msi.64bit", is64 as u64);
+    if !d.fetch(pos + 4, if is64 { 10 } else { 6 }) {
+        return;
+    }
+    let mut address = d.long(pos + 4) as u64;
+    let data = if is64 {
+        address |= (d.long(pos + 8) as u64) << 32;
+        d.word(pos + 12)
+    } else {
+        d.word(pos + 8)
+    };
+    set("msi.address", address);
+    set("msi.data", data as u64);
+}
+
+fn cap_debug_port(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let base = d.word(pos + 2);
+    set("debug_port.bar", (base >> 13) as u64);
+    set("debug_port.offset", (base & 0x1fff) as u64);
+}
+
+/// `cap_ht()`: only Slave/Primary and Host/Secondary Interface blocks are decoded
+fn cap_ht(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let cmd = d.word(pos + 2);
+    match cmd & PCI_HT_CMD_TYP_HI {
+        PCI_HT_CMD_TYP_HI_PRI => show_ht_pri(d, pos, cmd, set),
+        PCI_HT_CMD_TYP_HI_SEC => show_ht_sec(d, pos, cmd, set),
+        _ => (),
+    }
+}
+
+fn show_ht_pri(d: &Config, pos: usize, cmd: u16, set: &mut impl FnMut(&str, u64)) {
+    if !d.fetch(pos + PCI_HT_PRI_LCTR0, PCI_HT_PRI_SIZEOF - PCI_HT_PRI_LCTR0) {
+        return;
+    }
+    set("ht.base_unitid", (cmd & 0x001f) as u64);
+    set("ht.unit_count", ((cmd >> 5) & 0x1f) as u64);
+    set("ht.master_host", (cmd & 0x0400 != 0) as u64);
+    set("ht.default_direction", (cmd & 0x0800 != 0) as u64);
+    set("ht.dul", (cmd & 0x1000 != 0) as u64);
+    show_ht_lctr("ht.lctr0", d.word(pos + PCI_HT_PRI_LCTR0), set);
+    show_ht_lcnf("ht.lcnf0", d.word(pos + PCI_HT_PRI_LCNF0), set);
+    show_ht_lctr("ht.lctr1", d.word(pos + PCI_HT_PRI_LCTR1), set);
+    show_ht_lcnf("ht.lcnf1", d.word(pos + PCI_HT_PRI_LCNF1), set);
+    let rid = d.byte(pos + PCI_HT_PRI_RID);
+    show_ht_rid(rid, set);
+    if rid < 0x22 {
+        return;
+    }
+    show_ht_lfrer("ht.lfrer0", d.byte(pos + PCI_HT_PRI_LFRER0), set);
+    // lfcap0 is declared as u8
+    let lfcap0 = d.word(pos + PCI_HT_PRI_LFCAP0) as u8;
+    set("ht.lfcap0", lfcap0 as u64);
+    show_ht_ftr(d.byte(pos + PCI_HT_PRI_FTR) as u16, set);
+    show_ht_lfrer("ht.lfrer1", d.byte(pos + PCI_HT_PRI_LFRER1), set);
+    // lfcap1 is declared as u8
+    let lfcap1 = d.word(pos + PCI_HT_PRI_LFCAP1) as u8;
+    set("ht.lfcap1", lfcap1 as u64);
+    set("ht.mbu", d.byte(pos + PCI_HT_PRI_MBU) as u64);
+    set("ht.mlu", d.byte(pos + PCI_HT_PRI_MLU) as u64);
+    set("ht.bn", d.byte(pos + PCI_HT_PRI_BN) as u64);
+}
+
+fn show_ht_sec(d: &Config, pos: usize, cmd: u16, set: &mut impl FnMut(&str, u64)) {
+    if !d.fetch(pos + PCI_HT_SEC_LCTR, PCI_HT_SEC_SIZEOF - PCI_HT_SEC_LCTR) {
+        return;
+    }
+    set("ht.warm_reset", (cmd & 0x0001 != 0) as u64);
+    set("ht.double_ended", (cmd & 0x0002 != 0) as u64);
+    set("ht.device_number", ((cmd >> 2) & 0x1f) as u64);
+    set("ht.chain_side", (cmd & 0x0080 != 0) as u64);
+    set("ht.host_hide", (cmd & 0x0100 != 0) as u64);
+    set("ht.slave", (cmd & 0x0400 != 0) as u64);
+    set("ht.eoc_error", (cmd & 0x0800 != 0) as u64);
+    set("ht.dul", (cmd & 0x1000 != 0) as u64);
+    show_ht_lctr("ht.lctr", d.word(pos + PCI_HT_SEC_LCTR), set);
+    show_ht_lcnf("ht.lcnf", d.word(pos + PCI_HT_SEC_LCNF), set);
+    let rid = d.byte(pos + PCI_HT_SEC_RID);
+    show_ht_rid(rid, set);
+    if rid < 0x22 {
+        return;
+    }
+    show_ht_lfrer("ht.lfrer", d.byte(pos + PCI_HT_SEC_LFRER), set);
+    // lfcap is declared as u8
+    let lfcap = d.word(pos + PCI_HT_SEC_LFCAP) as u8;
+    set("ht.lfcap", lfcap as u64);
+    let ftr = d.word(pos + PCI_HT_SEC_FTR);
+    show_ht_ftr(ftr, set);
+    set("ht.ftr.extrs", (ftr & 0x0100 != 0) as u64);
+    set("ht.ftr.ucnfe", (ftr & 0x0200 != 0) as u64);
+    set("ht.mbu", d.byte(pos + PCI_HT_SEC_MBU) as u64);
+    set("ht.mlu", d.byte(pos + PCI_HT_SEC_MLU) as u64);
+}
+
+fn show_ht_lctr(name: &str, lctr: u16, set: &mut impl FnMut(&str, u64)) {
+    let flags = [
+        ("cfle", 0x0002),
+        ("cst", 0x0004),
+        ("cfe", 0x0008),
+        ("lkfail", 0x0010),
+        ("init", 0x0020),
+        ("eoc", 0x0040),
+        ("txo", 0x0080),
+        ("isocen", 0x1000),
+        ("lsen", 0x2000),
+        ("extctl", 0x4000),
+        ("64b", 0x8000),
+    ];
+    for (flag, mask) in flags {
+        set(&format!("{}.{}", name, flag), (lctr & mask != 0) as u64);
+    }
+    set(&format!("{}.crcerr", name), ((lctr >> 8) & 0xf) as u64);
+}
+
+fn show_ht_lcnf(name: &str, lcnf: u16, set: &mut impl FnMut(&str, u64)) {
+    set(&format!("{}.mlwi", name), (lcnf & 0x0007) as u64);
+    set(&format!("{}.dwfcin", name), (lcnf & 0x0008 != 0) as u64);
+    set(&format!("{}.mlwo", name), ((lcnf >> 4) & 0x7) as u64);
+    set(&format!("{}.dwfcout", name), (lcnf & 0x0080 != 0) as u64);
+    set(&format!("{}.lwi", name), ((lcnf >> 8) & 0x7) as u64);
+    set(&format!("{}.dwfcinen", name), (lcnf & 0x0800 != 0) as u64);
+    set(&format!("{}.lwo", name), ((lcnf >> 12) & 0x7) as u64);
+    set(&format!("{}.dwfcouten", name), (lcnf & 0x8000 != 0) as u64);
+}
+
+fn show_ht_rid(rid: u8, set: &mut impl FnMut(&str, u64)) {
+    set("ht.rid.major", ((rid & 0xe0) >> 5) as u64);
+    set("ht.rid.minor", (rid & 0x1f) as u64);
+}
+
+fn show_ht_lfrer(name: &str, lfrer: u8, set: &mut impl FnMut(&str, u64)) {
+    set(&format!("{}.freq", name), (lfrer & 0x0f) as u64);
+    set(&format!("{}.prot", name), (lfrer & 0x10 != 0) as u64);
+    set(&format!("{}.ovfl", name), (lfrer & 0x20 != 0) as u64);
+    set(&format!("{}.eoc", name), (lfrer & 0x40 != 0) as u64);
+    set(&format!("{}.ctltm", name), (lfrer & 0x80 != 0) as u64);
+}
+
+fn show_ht_ftr(ftr: u16, set: &mut impl FnMut(&str, u64)) {
+    set("ht.ftr.isocfc", (ftr & 0x0001 != 0) as u64);
+    set("ht.ftr.ldtstop", (ftr & 0x0002 != 0) as u64);
+    set("ht.ftr.crctm", (ftr & 0x0004 != 0) as u64);
+    set("ht.ftr.ectlt", (ftr & 0x0008 != 0) as u64);
+    set("ht.ftr.64ba", (ftr & 0x0010 != 0) as u64);
+    set("ht.ftr.uidrd", (ftr & 0x0020 != 0) as u64);
+}
+
+fn cap_express(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let cap = d.word(pos + 2);
+    let r#type = (cap & 0x00f0) >> 4;
+    set("express.version", (cap & 0x000f) as u64);
+    set("express.type", r#type as u64);
+    set("express.slot", (cap & 0x0100 != 0) as u64);
+    set("express.irq", ((cap & 0x3e00) >> 9) as u64);
+    let slot = matches!(
+        r#type,
+        PCI_EXP_TYPE_ROOT_PORT | PCI_EXP_TYPE_DOWNSTREAM | PCI_EXP_TYPE_PCIE_BRIDGE
+    ) && cap & 0x0100 != 0;
+    let link = !matches!(r#type, PCI_EXP_TYPE_ROOT_INT_EP | PCI_EXP_TYPE_ROOT_EC);
+    let size = match r#type {
+        PCI_EXP_TYPE_ROOT_PORT | PCI_EXP_TYPE_ROOT_EC => 32,
+        _ if slot => 24,
+        _ => 16,
+    };
+    if !d.fetch(pos + PCI_EXP_DEVCAP, size) {
+        return;
+    }
+    cap_express_dev(d, pos, r#type, set);
+    if link {
+        cap_express_link(d, pos, set);
+    }
+    if slot {
+        cap_express_slot(d, pos, set);
+    }
+}
+
+fn cap_express_dev(d: &Config, pos: usize, r#type: u16, set: &mut impl FnMut(&str, u64)) {
+    let t = d.long(pos + PCI_EXP_DEVCAP);
+    set("express.devcap.payload", (t & 0x07) as u64);
+    set("express.devcap.phantom", ((t & 0x18) >> 3) as u64);
+    if matches!(r#type, PCI_EXP_TYPE_ENDPOINT | PCI_EXP_TYPE_LEG_END) {
+        set("express.devcap.l0s", ((t & 0x1c0) >> 6) as u64);
+        set("express.devcap.l1", ((t & 0xe00) >> 9) as u64);
+    }
+    set("express.devcap.ext_tag", (t & 0x0020 != 0) as u64);
+    if matches!(
+        r#type,
+        PCI_EXP_TYPE_ENDPOINT
+            | PCI_EXP_TYPE_LEG_END
+            | PCI_EXP_TYPE_UPSTREAM
+            | PCI_EXP_TYPE_PCI_BRIDGE
+    ) {
+        set("express.devcap.atn_but", (t & 0x1000 != 0) as u64);
+        set("express.devcap.atn_ind", (t & 0x2000 != 0) as u64);
+        set("express.devcap.pwr_ind", (t & 0x4000 != 0) as u64);
+    }
+    set("express.devcap.rbe", (t & 0x8000 != 0) as u64);
+    let flreset = matches!(
+        r#type,
+        PCI_EXP_TYPE_ENDPOINT | PCI_EXP_TYPE_LEG_END | PCI_EXP_TYPE_ROOT_INT_EP
+    );
+    if flreset {
+        set("express.devcap.flreset", (t & 0x1000_0000 != 0) as u64);
+    }
+    if matches!(
+        r#type,
+        PCI_EXP_TYPE_ENDPOINT | PCI_EXP_TYPE_UPSTREAM | PCI_EXP_TYPE_PCI_BRIDGE
+    ) {
+        set("express.devcap.pwr_val", ((t & 0x03fc_0000) >> 18) as u64);
+        set("express.devcap.pwr_scl", ((t & 0x0c00_0000) >> 26) as u64);
+    }
+
+    let w = d.word(pos + PCI_EXP_DEVCTL);
+    set("express.devctl.cere", (w & 0x0001 != 0) as u64);
+    set("express.devctl.nfere", (w & 0x0002 != 0) as u64);
+    set("express.devctl.fere", (w & 0x0004 != 0) as u64);
+    set("express.devctl.urre", (w & 0x0008 != 0) as u64);
+    set("express.devctl.relax_en", (w & 0x0010 != 0) as u64);
+    set("express.devctl.ext_tag", (w & 0x0100 != 0) as u64);
+    set("express.devctl.phantom", (w & 0x0200 != 0) as u64);
+    set("express.devctl.aux_pme", (w & 0x0400 != 0) as u64);
+    set("express.devctl.nosnp_en", (w & 0x0800 != 0) as u64);
+    if r#type == PCI_EXP_TYPE_PCI_BRIDGE {
+        set("express.devctl.bcre", (w & 0x8000 != 0) as u64);
+    }
+    if flreset && t & 0x1000_0000 != 0 {
+        set("express.devctl.flreset", (w & 0x8000 != 0) as u64);
+    }
+    set("express.devctl.payload", ((w & 0x00e0) >> 5) as u64);
+    set("express.devctl.readrq", ((w & 0x7000) >> 12) as u64);
+}
+
+fn cap_express_link(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let t = d.long(pos + PCI_EXP_LNKCAP);
+    let aspm = (t & 0x0c00) >> 10;
+    set("express.lnkcap.port", (t >> 24) as u64);
+    set("express.lnkcap.speed", (t & 0x000f) as u64);
+    set("express.lnkcap.width", ((t & 0x03f0) >> 4) as u64);
+    set("express.lnkcap.aspm", aspm as u64);
+    if aspm & 1 != 0 {
+        set("express.lnkcap.l0s", ((t & 0x7000) >> 12) as u64);
+    }
+    if aspm & 2 != 0 {
+        set("express.lnkcap.l1", ((t & 0x38000) >> 15) as u64);
+    }
+    set("express.lnkcap.clock_pm", (t & 0x0004_0000 != 0) as u64);
+    set("express.lnkcap.surprise", (t & 0x0008_0000 != 0) as u64);
+    set("express.lnkcap.dll_a", (t & 0x0010_0000 != 0) as u64);
+    set("express.lnkcap.lbnc", (t & 0x0020_0000 != 0) as u64);
+    set("express.lnkcap.aoc", (t & 0x0040_0000 != 0) as u64);
+
+    let w = d.word(pos + PCI_EXP_LNKSTA);
+    set("express.lnksta.speed", (w & 0x000f) as u64);
+    set("express.lnksta.width", ((w & 0x03f0) >> 4) as u64);
+    set("express.lnksta.tr_err", (w & 0x0400 != 0) as u64);
+    set("express.lnksta.train", (w & 0x0800 != 0) as u64);
+    set("express.lnksta.sl_clk", (w & 0x1000 != 0) as u64);
+    set("express.lnksta.dl_act", (w & 0x2000 != 0) as u64);
+    set("express.lnksta.bwmgmt", (w & 0x4000 != 0) as u64);
+    set("express.lnksta.autbw", (w & 0x8000 != 0) as u64);
+}
+
+fn cap_express_slot(d: &Config, pos: usize, set: &mut impl FnMut(&str, u64)) {
+    let t = d.long(pos + PCI_EXP_SLTCAP);
+    set("express.sltcap.atnb", (t & 0x0001 != 0) as u64);
+    set("express.sltcap.pwrc", (t & 0x0002 != 0) as u64);
+    set("express.sltcap.mrl", (t & 0x0004 != 0) as u64);
+    set("express.sltcap.atni", (t & 0x0008 != 0) as u64);
+    set("express.sltcap.pwri", (t & 0x0010 != 0) as u64);
+    set("express.sltcap.hpc", (t & 0x0040 != 0) as u64);
+    set("express.sltcap.hps", (t & 0x0020 != 0) as u64);
+    set("express.sltcap.pwr_val", ((t & 0x0000_7f80) >> 7) as u64);
+    set("express.sltcap.pwr_scl", ((t & 0x0001_8000) >> 15) as u64);
+    set("express.sltcap.interlock", (t & 0x0002_0000 != 0) as u64);
+    set("express.sltcap.nocmdcomp", (t & 0x0004_0000 != 0) as u64);
+    set("express.sltcap.psn", (t >> 19) as u64);
+}
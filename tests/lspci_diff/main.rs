@@ -0,0 +1,749 @@
+//! Differential comparison of capability decoding against lspci
+//!
+//! Every dump from the fuzz roots (`tests/data`) and a fixed number of its mutants is decoded
+//! twice: by pcics and by a minimal port of lspci decoding ([lspci]). Both sides flatten decoded
+//! registers to `cap[<pointer>].<field>` keys, then values are compared field by field.
+//!
+//! Capability list walks are compared separately: lspci masks reserved pointer bits and stops on
+//! looped or broken chains, while pcics reports such lists as is. Walk divergences are printed,
+//! field divergences of capabilities found by both sides fail the test, unless they are known
+//! lspci bugs ([QUIRKS]).
+//!
+//! ```sh
+//! cargo test --features lspci-diff --test lspci_diff -- --nocapture
+//! ```
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use pcics::{
+    capabilities::{
+        hypertransport::{
+            FeatureCapability, HostOrSecondaryInterface, LinkConfiguration, LinkControl, LinkError,
+            LinkFrequencyCapability, LinkWidth, RevisionId, SlaveOrPrimaryInterface,
+        },
+        message_signaled_interrups::MessageAddress,
+        pci_express::{DeviceType, Link, Slot},
+        CapabilityKind, DebugPort, Hypertransport, MessageSignaledInterrups, PciExpress,
+        PowerManagementInterface,
+    },
+    synthetic, Capabilities, Header, DDR_OFFSET, ECS_OFFSET,
+};
+
+mod lspci;
+
+/// Flattened decoded registers
+pub type Fields = BTreeMap<String, u64>;
+
+/// Capabilities list as `(pointer, id)` pairs
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Walk {
+    pub caps: Vec<(u8, u8)>,
+    pub end: WalkEnd,
+}
+
+/// Reason the capabilities list walk stopped
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WalkEnd {
+    /// Null next pointer
+    #[default]
+    Null,
+    /// Capability header is out of dump
+    AccessDenied,
+    /// Capability already visited
+    Looped,
+    /// Capability ID is FFh
+    Broken,
+    /// Parser error
+    Error,
+}
+
+/// Same field decoded differently
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Disagreement {
+    field: String,
+    pcics: Option<u64>,
+    lspci: Option<u64>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: pcics={:x?} lspci={:x?}",
+            self.field, self.pcics, self.lspci
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    walks: Vec<(Walk, Walk)>,
+    fields: Vec<Disagreement>,
+    quirks: Vec<Disagreement>,
+}
+
+/// Known lspci decoding bugs: field name part and the bits of the pcics value lspci keeps
+///
+/// `show_ht_pri()` and `show_ht_sec()` keep HyperTransport Link Frequency Capability registers
+/// in `u8` variables, so 1.4 GHz and faster rates are never reported.
+const QUIRKS: [(&str, u64); 1] = [(".ht.lfcap", 0xff)];
+
+impl Disagreement {
+    fn is_quirk(&self) -> bool {
+        QUIRKS.iter().any(|&(name, mask)| {
+            self.field.contains(name) && self.pcics.map(|v| v & mask) == self.lspci
+        })
+    }
+}
+
+/// Upper bound of walked capabilities, every dword of config space visited once
+const MAX_CAPS: usize = 256 / 4;
+
+fn decode(data: &[u8]) -> (Walk, Fields) {
+    let mut walk = Walk::default();
+    let mut fields = Fields::new();
+    let header: Header = match data.try_into() {
+        Ok(header) => header,
+        Err(_) => {
+            walk.end = WalkEnd::Error;
+            return (walk, fields);
+        }
+    };
+    if !header.status.capabilities_list {
+        return (walk, fields);
+    }
+    let ddr = &data[DDR_OFFSET..ECS_OFFSET.min(data.len())];
+    for (n, cap) in Capabilities::new(ddr, &header).enumerate() {
+        if n == MAX_CAPS {
+            walk.end = WalkEnd::Looped;
+            break;
+        }
+        let cap = match cap {
+            Ok(cap) => cap,
+            Err(_) => {
+                walk.end = WalkEnd::Error;
+                break;
+            }
+        };
+        let ptr = u16::from(cap.pointer) as u8;
+        walk.caps.push((ptr, data[ptr as usize]));
+        let prefix = format!("cap[{:02x}]", ptr);
+        let mut set = |name: &str, value: u64| {
+            fields.insert(format!("{}.{}", prefix, name), value);
+        };
+        match cap.kind {
+            CapabilityKind::PowerManagementInterface(pm) => pm_fields(&pm, &mut set),
+            CapabilityKind::MessageSignaledInterrups(msi) => msi_fields(&msi, &mut set),
+            CapabilityKind::Hypertransport(ht) => ht_fields(&ht, &mut set),
+            CapabilityKind::DebugPort(dp) => debug_port_fields(&dp, &mut set),
+            CapabilityKind::PciExpress(pcie) => express_fields(&pcie, &mut set),
+            _ => (),
+        }
+    }
+    (walk, fields)
+}
+
+fn pm_fields(pm: &PowerManagementInterface, set: &mut impl FnMut(&str, u64)) {
+    let caps = &pm.capabilities;
+    let pme = &caps.pme_support;
+    set("pm.version", caps.version as u64);
+    set("pm.pme_clock", caps.pme_clock as u64);
+    set("pm.dsi", caps.device_specific_initialization as u64);
+    set("pm.aux_current", caps.aux_current as u64);
+    set("pm.d1", caps.d1_support as u64);
+    set("pm.d2", caps.d2_support as u64);
+    set(
+        "pm.pme_support",
+        [pme.d0, pme.d1, pme.d2, pme.d3_hot, pme.d3_cold]
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &b)| acc | (b as u64) << i),
+    );
+    let ctrl = &pm.control;
+    set("pm.power_state", ctrl.power_state as u64);
+    set("pm.no_soft_reset", ctrl.no_soft_reset as u64);
+    set("pm.pme_enable", ctrl.pme_enabled as u64);
+    set("pm.data_select", u8::from(ctrl.data_select) as u64);
+    set("pm.data_scale", ctrl.data_scale as u64);
+    set("pm.pme_status", ctrl.pme_status as u64);
+}
+
+fn msi_fields(msi: &MessageSignaledInterrups, set: &mut impl FnMut(&str, u64)) {
+    let ctrl = &msi.message_control;
+    set("msi.enable", ctrl.msi_enable as u64);
+    set(
+        "msi.count_capable",
+        ctrl.multiple_message_capable.number_of_vectors() as u64,
+    );
+    set(
+        "msi.count_enabled",
+        ctrl.multiple_message_enable.number_of_vectors() as u64,
+    );
+    set("msi.maskable", ctrl.per_vector_masking_capable as u64);
+    set("msi.64bit", ctrl.a_64_bit_address_capable as u64);
+    let address = match msi.message_address {
+        MessageAddress::Dword(a) => a as u64,
+        MessageAddress::Qword(a) => a,
+    };
+    set("msi.address", address);
+    set("msi.data", msi.message_data as u64);
+}
+
+fn debug_port_fields(dp: &DebugPort, set: &mut impl FnMut(&str, u64)) {
+    set("debug_port.bar", dp.bar_number as u64);
+    set("debug_port.offset", dp.offset as u64);
+}
+
+fn ht_fields(ht: &Hypertransport, set: &mut impl FnMut(&str, u64)) {
+    match ht {
+        Hypertransport::SlaveOrPrimaryInterface(pri) => ht_pri_fields(pri, set),
+        Hypertransport::HostOrSecondaryInterface(sec) => ht_sec_fields(sec, set),
+        _ => (),
+    }
+}
+
+fn ht_pri_fields(pri: &SlaveOrPrimaryInterface, set: &mut impl FnMut(&str, u64)) {
+    let cmd = &pri.command;
+    set("ht.base_unitid", cmd.base_unitid as u64);
+    set("ht.unit_count", cmd.unit_count as u64);
+    set("ht.master_host", cmd.master_host as u64);
+    set("ht.default_direction", cmd.default_direction as u64);
+    set("ht.dul", cmd.drop_on_uninitialized_link as u64);
+    ht_link_control_fields("ht.lctr0", &pri.link_control_0, set);
+    ht_link_config_fields("ht.lcnf0", &pri.link_config_0, set);
+    ht_link_control_fields("ht.lctr1", &pri.link_control_1, set);
+    ht_link_config_fields("ht.lcnf1", &pri.link_config_1, set);
+    if !ht_revision_fields(&pri.revision_id, set) {
+        return;
+    }
+    ht_link_error_fields("ht.lfrer0", pri.link_freq_0, &pri.link_error_0, set);
+    set("ht.lfcap0", ht_link_freq_cap(&pri.link_freq_cap_0));
+    ht_feature_fields(&pri.feature, set);
+    ht_link_error_fields("ht.lfrer1", pri.link_freq_1, &pri.link_error_1, set);
+    set("ht.lfcap1", ht_link_freq_cap(&pri.link_freq_cap_1));
+    set("ht.mbu", pri.mem_base_upper as u64);
+    set("ht.mlu", pri.mem_limit_upper as u64);
+    set("ht.bn", pri.bus_number as u64);
+}
+
+fn ht_sec_fields(sec: &HostOrSecondaryInterface, set: &mut impl FnMut(&str, u64)) {
+    let cmd = &sec.command;
+    set("ht.warm_reset", cmd.warm_reset as u64);
+    set("ht.double_ended", cmd.double_ended as u64);
+    set("ht.device_number", cmd.device_number as u64);
+    set("ht.chain_side", cmd.chain_side as u64);
+    set("ht.host_hide", cmd.host_hide as u64);
+    set("ht.slave", cmd.act_as_slave as u64);
+    set("ht.eoc_error", cmd.host_inbound_end_of_chain_error as u64);
+    set("ht.dul", cmd.drop_on_uninitialized_link as u64);
+    ht_link_control_fields("ht.lctr", &sec.link_control, set);
+    ht_link_config_fields("ht.lcnf", &sec.link_config, set);
+    if !ht_revision_fields(&sec.revision_id, set) {
+        return;
+    }
+    ht_link_error_fields("ht.lfrer", sec.link_freq, &sec.link_error, set);
+    set("ht.lfcap", ht_link_freq_cap(&sec.link_freq_cap));
+    ht_feature_fields(&sec.feature, set);
+    set("ht.ftr.extrs", sec.feature.extended_register_set as u64);
+    set(
+        "ht.ftr.ucnfe",
+        sec.feature.upstream_configuration_enable as u64,
+    );
+    set("ht.mbu", sec.mem_base_upper as u64);
+    set("ht.mlu", sec.mem_limit_upper as u64);
+}
+
+fn ht_link_control_fields(name: &str, lctr: &LinkControl, set: &mut impl FnMut(&str, u64)) {
+    let flags = [
+        ("cfle", lctr.crc_flood_enable),
+        ("cst", lctr.crc_start_test),
+        ("cfe", lctr.crc_force_error),
+        ("lkfail", lctr.link_failure),
+        ("init", lctr.initialization_complete),
+        ("eoc", lctr.end_of_chain),
+        ("txo", lctr.transmitter_off),
+        ("isocen", lctr.isochronous_flow_control_enable),
+        ("lsen", lctr.ldtstop_tristate_enable),
+        ("extctl", lctr.extended_ctl_time),
+        ("64b", lctr.enable_64_bit_addressing),
+    ];
+    for (flag, value) in flags {
+        set(&format!("{}.{}", name, flag), value as u64);
+    }
+    set(&format!("{}.crcerr", name), lctr.crc_error as u64);
+}
+
+fn ht_link_config_fields(name: &str, lcnf: &LinkConfiguration, set: &mut impl FnMut(&str, u64)) {
+    let width = |width: &LinkWidth| match width {
+        LinkWidth::Width8bits => 0b000,
+        LinkWidth::Width16bits => 0b001,
+        LinkWidth::Width32bits => 0b011,
+        LinkWidth::Width2bits => 0b100,
+        LinkWidth::Width4bits => 0b101,
+        LinkWidth::NotConnected => 0b111,
+        LinkWidth::Reserved(v) => *v as u64,
+    };
+    set(&format!("{}.mlwi", name), width(&lcnf.max_link_width_in));
+    set(
+        &format!("{}.dwfcin", name),
+        lcnf.doubleword_flow_control_in as u64,
+    );
+    set(&format!("{}.mlwo", name), width(&lcnf.max_link_width_out));
+    set(
+        &format!("{}.dwfcout", name),
+        lcnf.doubleword_flow_control_out as u64,
+    );
+    set(&format!("{}.lwi", name), width(&lcnf.link_width_in));
+    set(
+        &format!("{}.dwfcinen", name),
+        lcnf.doubleword_flow_control_in_enable as u64,
+    );
+    set(&format!("{}.lwo", name), width(&lcnf.link_width_out));
+    set(
+        &format!("{}.dwfcouten", name),
+        lcnf.doubleword_flow_control_out_enable as u64,
+    );
+}
+
+/// Whether lspci decodes registers following Revision ID (revision 1.02 and later)
+fn ht_revision_fields(rid: &RevisionId, set: &mut impl FnMut(&str, u64)) -> bool {
+    set("ht.rid.major", rid.major as u64);
+    set("ht.rid.minor", rid.minor as u64);
+    (rid.major, rid.minor) >= (1, 2)
+}
+
+fn ht_link_error_fields(name: &str, freq: u8, error: &LinkError, set: &mut impl FnMut(&str, u64)) {
+    set(&format!("{}.freq", name), freq as u64);
+    set(&format!("{}.prot", name), error.protocol_error as u64);
+    set(&format!("{}.ovfl", name), error.overflow_error as u64);
+    set(&format!("{}.eoc", name), error.end_of_chain_error as u64);
+    set(&format!("{}.ctltm", name), error.ctl_timeout as u64);
+}
+
+fn ht_link_freq_cap(lfcap: &LinkFrequencyCapability) -> u64 {
+    [
+        lfcap.supports_200mhz,
+        lfcap.supports_300mhz,
+        lfcap.supports_400mhz,
+        lfcap.supports_500mhz,
+        lfcap.supports_600mhz,
+        lfcap.supports_800mhz,
+        lfcap.supports_1000mhz,
+        lfcap.supports_1200mhz,
+        lfcap.supports_1400mhz,
+        lfcap.supports_1600mhz,
+        lfcap.supports_1800mhz,
+        lfcap.supports_2000mhz,
+        lfcap.supports_2200mhz,
+        lfcap.supports_2400mhz,
+        lfcap.supports_2600mhz,
+        lfcap.supports_vendor_specific,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |acc, (i, &b)| acc | (b as u64) << i)
+}
+
+fn ht_feature_fields(ftr: &FeatureCapability, set: &mut impl FnMut(&str, u64)) {
+    set("ht.ftr.isocfc", ftr.isochronous_flow_control_mode as u64);
+    set("ht.ftr.ldtstop", ftr.ldtstop as u64);
+    set("ht.ftr.crctm", ftr.crc_test_mode as u64);
+    set("ht.ftr.ectlt", ftr.extended_ctl_time_required as u64);
+    set("ht.ftr.64ba", ftr.qword_addressing as u64);
+    set("ht.ftr.uidrd", ftr.unitid_reorder_disable as u64);
+}
+
+fn express_fields(pcie: &PciExpress, set: &mut impl FnMut(&str, u64)) {
+    set("express.version", pcie.version as u64);
+    set("express.type", pcie.device_type.id() as u64);
+    set("express.slot", pcie.slot_implemented as u64);
+    set("express.irq", pcie.interrupt_message_number as u64);
+    express_device_fields(pcie, set);
+    if let Some(link) = pcie.device_type.link() {
+        express_link_fields(link, set);
+    }
+    // lspci decodes Slot registers only if Slot Implemented is set
+    match pcie.device_type.slot() {
+        Some(slot) if pcie.slot_implemented && pcie.device_type.is_downstream_port() => {
+            express_slot_fields(slot, set)
+        }
+        _ => (),
+    }
+}
+
+/// Device/Port Type dependent fields are set for the types lspci shows them for
+fn express_device_fields(pcie: &PciExpress, set: &mut impl FnMut(&str, u64)) {
+    let device_type = &pcie.device_type;
+    let caps = &pcie.device.capabilities;
+    set(
+        "express.devcap.payload",
+        u8::from(caps.max_payload_size_supported) as u64,
+    );
+    set(
+        "express.devcap.phantom",
+        u8::from(caps.phantom_functions_supported) as u64,
+    );
+    let (endpoint, legacy_endpoint) = (
+        matches!(device_type, DeviceType::Endpoint { .. }),
+        matches!(device_type, DeviceType::LegacyEndpoint { .. }),
+    );
+    if endpoint || legacy_endpoint {
+        set(
+            "express.devcap.l0s",
+            u8::from(caps.endpoint_l0s_acceptable_latency) as u64,
+        );
+        set(
+            "express.devcap.l1",
+            u8::from(caps.endpoint_l1_acceptable_latency) as u64,
+        );
+    }
+    set(
+        "express.devcap.ext_tag",
+        bool::from(caps.extended_tag_field_supported.clone()) as u64,
+    );
+    if device_type.receives_set_slot_power_limit() {
+        set(
+            "express.devcap.atn_but",
+            caps.attention_button_present as u64,
+        );
+        set(
+            "express.devcap.atn_ind",
+            caps.attention_indicator_present as u64,
+        );
+        set(
+            "express.devcap.pwr_ind",
+            caps.power_indicator_present as u64,
+        );
+    }
+    set("express.devcap.rbe", caps.role_based_error_reporting as u64);
+    let flreset = device_type.is_endpoint();
+    if flreset {
+        set(
+            "express.devcap.flreset",
+            caps.function_level_reset_capability as u64,
+        );
+    }
+    // Legacy Endpoint is missing from the lspci list
+    if device_type.receives_set_slot_power_limit() && !legacy_endpoint {
+        let cspl = &caps.captured_slot_power_limit;
+        set("express.devcap.pwr_val", cspl.value as u64);
+        set("express.devcap.pwr_scl", cspl.scale_encoding() as u64);
+    }
+
+    let ctrl = &pcie.device.control;
+    set(
+        "express.devctl.cere",
+        ctrl.correctable_error_reporting_enable as u64,
+    );
+    set(
+        "express.devctl.nfere",
+        ctrl.non_fatal_error_reporting_enable as u64,
+    );
+    set(
+        "express.devctl.fere",
+        ctrl.fatal_error_reporting_enable as u64,
+    );
+    set(
+        "express.devctl.urre",
+        ctrl.unsupported_request_reporting_enable as u64,
+    );
+    set(
+        "express.devctl.relax_en",
+        ctrl.enable_relaxed_ordering as u64,
+    );
+    set(
+        "express.devctl.ext_tag",
+        ctrl.extended_tag_field_enable as u64,
+    );
+    set(
+        "express.devctl.phantom",
+        ctrl.phantom_functions_enable as u64,
+    );
+    set("express.devctl.aux_pme", ctrl.aux_power_pm_enable as u64);
+    set("express.devctl.nosnp_en", ctrl.enable_no_snoop as u64);
+    if matches!(device_type, DeviceType::PcieToPciBridge { .. }) {
+        set("express.devctl.bcre", ctrl.bcre_or_flreset as u64);
+    }
+    if flreset && caps.function_level_reset_capability {
+        set("express.devctl.flreset", ctrl.bcre_or_flreset as u64);
+    }
+    set(
+        "express.devctl.payload",
+        u8::from(ctrl.max_payload_size) as u64,
+    );
+    set(
+        "express.devctl.readrq",
+        u8::from(ctrl.max_read_request_size) as u64,
+    );
+}
+
+fn express_link_fields(link: &Link, set: &mut impl FnMut(&str, u64)) {
+    let caps = &link.capabilities;
+    let aspm = u8::from(caps.active_state_power_management_support.clone());
+    set("express.lnkcap.port", caps.port_number as u64);
+    set("express.lnkcap.speed", u8::from(caps.max_link_speed) as u64);
+    set(
+        "express.lnkcap.width",
+        u8::from(caps.maximum_link_width) as u64,
+    );
+    set("express.lnkcap.aspm", aspm as u64);
+    if aspm & 1 != 0 {
+        set("express.lnkcap.l0s", u8::from(caps.l0s_exit_latency) as u64);
+    }
+    if aspm & 2 != 0 {
+        set("express.lnkcap.l1", u8::from(caps.l1_exit_latency) as u64);
+    }
+    set(
+        "express.lnkcap.clock_pm",
+        caps.clock_power_management as u64,
+    );
+    set(
+        "express.lnkcap.surprise",
+        caps.surprise_down_error_reporting_capable as u64,
+    );
+    set(
+        "express.lnkcap.dll_a",
+        caps.data_link_layer_link_active_reporting_capable as u64,
+    );
+    set(
+        "express.lnkcap.lbnc",
+        caps.link_bandwidth_notification_capability as u64,
+    );
+    set(
+        "express.lnkcap.aoc",
+        caps.aspm_optionality_compliance as u64,
+    );
+
+    let sta = &link.status;
+    set(
+        "express.lnksta.speed",
+        u8::from(sta.current_link_speed) as u64,
+    );
+    set(
+        "express.lnksta.width",
+        u8::from(sta.negotiated_link_width) as u64,
+    );
+    set("express.lnksta.tr_err", sta.link_training_error as u64);
+    set("express.lnksta.train", sta.link_training as u64);
+    set("express.lnksta.sl_clk", sta.slot_clock_configuration as u64);
+    set(
+        "express.lnksta.dl_act",
+        sta.data_link_layer_link_active as u64,
+    );
+    set(
+        "express.lnksta.bwmgmt",
+        sta.link_bandwidth_management_status as u64,
+    );
+    set(
+        "express.lnksta.autbw",
+        sta.link_autonomous_bandwidth_status as u64,
+    );
+}
+
+fn express_slot_fields(slot: &Slot, set: &mut impl FnMut(&str, u64)) {
+    let caps = &slot.capabilities;
+    set("express.sltcap.atnb", caps.attention_button_present as u64);
+    set("express.sltcap.pwrc", caps.power_controller_present as u64);
+    set("express.sltcap.mrl", caps.mrl_sensor_present as u64);
+    set(
+        "express.sltcap.atni",
+        caps.attention_indicator_present as u64,
+    );
+    set("express.sltcap.pwri", caps.power_indicator_present as u64);
+    set("express.sltcap.hpc", caps.hot_plug_capable as u64);
+    set("express.sltcap.hps", caps.hot_plug_surprise as u64);
+    set("express.sltcap.pwr_val", caps.slot_power_limit.value as u64);
+    set(
+        "express.sltcap.pwr_scl",
+        caps.slot_power_limit.scale_encoding() as u64,
+    );
+    set(
+        "express.sltcap.interlock",
+        caps.electromechanical_interlock_present as u64,
+    );
+    set(
+        "express.sltcap.nocmdcomp",
+        caps.no_command_completed_support as u64,
+    );
+    set("express.sltcap.psn", caps.physical_slot_number as u64);
+}
+
+fn compare(data: &[u8], report: &mut Report) {
+    let (walk, fields) = decode(data);
+    let (lspci_walk, lspci_fields) = lspci::decode(data);
+    let same_walk = walk == lspci_walk;
+    if !same_walk {
+        report.walks.push((walk, lspci_walk));
+    }
+    let keys = fields
+        .keys()
+        .chain(lspci_fields.keys())
+        .collect::<std::collections::BTreeSet<_>>();
+    for key in keys {
+        let (pcics, lspci) = (fields.get(key).copied(), lspci_fields.get(key).copied());
+        // Capability found by only one side is a walk divergence
+        let both = pcics.is_some() && lspci.is_some();
+        if pcics != lspci && (both || same_walk) {
+            let disagreement = Disagreement {
+                field: key.clone(),
+                pcics,
+                lspci,
+            };
+            if disagreement.is_quirk() {
+                report.quirks.push(disagreement);
+            } else {
+                report.fields.push(disagreement);
+            }
+        }
+    }
+}
+
+/// Fuzz roots: real device dumps, synthetic functions and random data
+fn roots() -> Vec<(String, Vec<u8>)> {
+    let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut roots = fs::read_dir(data.join("device"))
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path().join("config");
+            (path.display().to_string(), fs::read(&path).unwrap())
+        })
+        .collect::<Vec<_>>();
+    roots.push((
+        "synthetic endpoint".into(),
+        synthetic::endpoint_with_msix(32).to_vec(),
+    ));
+    for slot in [false, true] {
+        let name = format!("synthetic root port (slot: {})", slot);
+        roots.push((name, synthetic::root_port(slot).to_vec()));
+    }
+    roots.push((
+        "synthetic HyperTransport bridge".into(),
+        hypertransport_bridge(),
+    ));
+    let random = data.join("random/4k");
+    roots.push((random.display().to_string(), fs::read(&random).unwrap()));
+    roots.sort();
+    roots
+}
+
+/// Host bridge with HyperTransport Slave/Primary (40h) and Host/Secondary (60h) Interface
+/// blocks of revision 3.00, both links are capable of 200 MHz - 2.6 GHz rates
+fn hypertransport_bridge() -> Vec<u8> {
+    let mut data = vec![0; DDR_OFFSET + 0xc0];
+    // AMD Host bridge, Status: Capabilities List
+    data[..4].copy_from_slice(&[0x22, 0x10, 0x00, 0x12]);
+    data[0x06] = 0x10;
+    data[0x0a..0x0c].copy_from_slice(&[0x00, 0x06]);
+    data[0x34] = 0x40;
+    #[rustfmt::skip]
+    let pri = [
+        0x08, 0x60, 0x21, 0x00, // Base UnitID 1, Unit Count 1
+        0x20, 0x00, 0x11, 0x11, // Link 0: Initialization Complete, 16 bits
+        0x40, 0x00, 0x77, 0x77, // Link 1: End of Chain, not connected
+        0x60, 0x0e, 0xf5, 0x7f, // Revision 3.00, Link 0: 2.6 GHz
+        0x13, 0x00, 0xf5, 0x7f, // LDTSTOP#, 64 Bit Addressing
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x01, 0x00, // Bus Number 1
+    ];
+    #[rustfmt::skip]
+    let sec = [
+        0x08, 0x00, 0x01, 0x20, // Warm Reset
+        0x20, 0x00, 0x11, 0x11, // Initialization Complete, 16 bits
+        0x60, 0x0e, 0xf5, 0x7f, // Revision 3.00, 2.6 GHz
+        0x13, 0x02, 0x00, 0x00, // LDTSTOP#, 64 Bit Addressing, Upstream Configuration Enable
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+    data[0x40..0x40 + pri.len()].copy_from_slice(&pri);
+    data[0x60..0x60 + sec.len()].copy_from_slice(&sec);
+    data
+}
+
+/// Deterministic xorshift64 mutator
+struct Mutator(u64);
+
+impl Mutator {
+    /// Bytes walked by both decoders: Status, Capabilities Pointers and device dependent region
+    const TARGETS: [usize; 4] = [0x06, 0x0e, 0x14, 0x34];
+    const MUTANTS: usize = 1024;
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn mutate(&mut self, root: &[u8]) -> Vec<u8> {
+        let mut data = root.to_vec();
+        for _ in 0..1 + self.next() % 4 {
+            let pos = match self.next() % 8 {
+                n @ 0..=3 => Self::TARGETS[n as usize],
+                _ => DDR_OFFSET + (self.next() as usize % (ECS_OFFSET - DDR_OFFSET)),
+            };
+            data[pos] = self.next() as u8;
+        }
+        data
+    }
+}
+
+#[test]
+fn known_good_roots() {
+    for (name, data) in roots()
+        .into_iter()
+        .filter(|(name, _)| !name.contains("random"))
+    {
+        let mut report = Report::default();
+        compare(&data, &mut report);
+        assert!(report.walks.is_empty(), "{}: {:?}", name, report.walks);
+        assert!(report.fields.is_empty(), "{}: {:?}", name, report.fields);
+    }
+}
+
+#[test]
+fn hypertransport_link_frequency_capability() {
+    let mut report = Report::default();
+    compare(&hypertransport_bridge(), &mut report);
+    let quirks = report
+        .quirks
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "cap[40].ht.lfcap0: pcics=Some(7ff5) lspci=Some(f5)",
+            "cap[40].ht.lfcap1: pcics=Some(7ff5) lspci=Some(f5)",
+            "cap[60].ht.lfcap: pcics=Some(7ff5) lspci=Some(f5)",
+        ],
+        quirks
+    );
+}
+
+#[test]
+fn mutated_roots() {
+    let mut mutator = Mutator(0x9e37_79b9_7f4a_7c15);
+    let mut report = Report::default();
+    for (_, root) in roots() {
+        compare(&root, &mut report);
+        for _ in 0..Mutator::MUTANTS {
+            compare(&mutator.mutate(&root), &mut report);
+        }
+    }
+    let mut walk_ends = BTreeMap::new();
+    for (pcics, lspci) in &report.walks {
+        *walk_ends.entry((pcics.end, lspci.end)).or_insert(0) += 1;
+    }
+    let fields = report
+        .fields
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert!(
+        fields.is_empty(),
+        "{} field disagreements:\n{}\ncapabilities list walk divergences (pcics, lspci): {:?}\n\
+         known lspci quirks: {}",
+        fields.len(),
+        fields.join("\n"),
+        walk_ends,
+        report.quirks.len()
+    );
+}
@@ -0,0 +1,212 @@
+//! Panic-free parsing regression
+//!
+//! Every file of `tests/data/fuzz` is a configuration space image which once made the library
+//! panic or which is built to reach a bounds check: capabilities lists loops, counts pointing
+//! past the end of data, truncated structures. Each image, its truncations and a fixed set of
+//! pseudo-random mutations are parsed through all public entry points; errors are fine, panics
+//! are not.
+//!
+//! ```sh
+//! cargo test --test panic_free
+//! ```
+
+use std::{fs, panic, path::Path};
+
+use pcics::{
+    analysis::{
+        budget::ByteBudget,
+        interrupts::{AdvancedErrorInterrupt, InterruptVectors},
+        lanes::Lanes,
+        link_declaration::MisalignedLinks,
+        routing::RoutingIdUsage,
+        tph::TphInconsistencies,
+    },
+    capabilities::pci_express::LinkWidth,
+    config_access::ConfigReader,
+    extended_capabilities::{physical_layer::PhysicalLayer, ExtendedCapabilityKind},
+    sysfs::Modalias,
+    ConfigSpaceEditor, ConfigurationSpace, Header, DDR_OFFSET,
+};
+
+/// Mutations per corpus image
+const MUTATIONS: usize = 128;
+/// Sizes of BARs combined with Resizable BAR entries
+const BAR_SIZES: [u64; 6] = [0x1000, 1 << 20, 0, 1 << 30, 0x10, 1 << 40];
+
+fn corpus() -> Vec<(String, Vec<u8>)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let mut files = vec![root.join("random/4k")];
+    for entry in fs::read_dir(root.join("fuzz")).unwrap() {
+        files.push(entry.unwrap().path());
+    }
+    files
+        .into_iter()
+        .map(|path| (path.display().to_string(), fs::read(&path).unwrap()))
+        .collect()
+}
+
+/// Walks every structure reachable from `data`
+fn parse(data: &[u8]) {
+    if let Some(header) = data.get(..DDR_OFFSET) {
+        let _ = format!("{:?}", Header::try_from(header));
+    }
+    if let Ok(reader) = ConfigReader::new(data) {
+        reader.for_each_capability(|cap| {
+            let _ = format!("{:?}", cap);
+        });
+        let mut window = [0; 64];
+        reader.for_each_extended_capability(&mut window, |ecap| {
+            let _ = format!("{:?}", ecap);
+        });
+    }
+    let cs = match ConfigurationSpace::try_from(data) {
+        Ok(cs) => cs,
+        Err(_) => return,
+    };
+    let mut cap_ids = Vec::new();
+    for cap in cs.capabilities() {
+        let _ = format!("{:?}", cap);
+        if let Ok(cap) = cap {
+            cap_ids.push(cap.id());
+        }
+    }
+    let sriov = cs
+        .extended_capabilities()
+        .into_iter()
+        .flatten()
+        .find_map(|ecap| match ecap.ok()?.kind {
+            ExtendedCapabilityKind::SingleRootIoVirtualization(sriov) => Some(sriov),
+            _ => None,
+        });
+    let mut ecap_ids = Vec::new();
+    let mut offsets = Vec::new();
+    for ecap in cs.extended_capabilities().into_iter().flatten() {
+        let ecap = match ecap {
+            Ok(ecap) => ecap,
+            Err(e) => {
+                let _ = e.to_string();
+                continue;
+            }
+        };
+        // Looped list, every capability is already visited
+        if offsets.contains(&ecap.offset) {
+            break;
+        }
+        offsets.push(ecap.offset);
+        let _ = format!("{:?}", ecap);
+        ecap_ids.push(ecap.id());
+        match ecap.kind {
+            ExtendedCapabilityKind::VirtualChannel(vc) => {
+                let _ = vc.extended_virtual_channels().count();
+                let _ = format!("{:?}", vc.vc_arbitration_table());
+            }
+            #[cfg(feature = "mfvc")]
+            ExtendedCapabilityKind::MultifunctionVirtualChannel(mfvc) => {
+                let _ = mfvc.extended_virtual_channels().count();
+            }
+            ExtendedCapabilityKind::MultiRootIoVirtualization(mriov) => {
+                let _ = format!("{:?}", mriov.function_table_entries(data).last());
+            }
+            ExtendedCapabilityKind::ResizableBar(rebar) => {
+                let _ = format!("{:?}", rebar.views(&cs.header, &BAR_SIZES).last());
+            }
+            ExtendedCapabilityKind::VfResizableBar(rebar) => {
+                if let Some(sriov) = &sriov {
+                    let _ = format!("{:?}", rebar.vf_views(sriov, &BAR_SIZES).last());
+                }
+            }
+            ExtendedCapabilityKind::PhysicalLayer16GTps(pl) => {
+                let lanes = pl.equalization_control_lanes(LinkWidth::X32);
+                let _ = format!("{:?}", lanes.last());
+            }
+            ExtendedCapabilityKind::PhysicalLayer32GTps(pl) => {
+                let lanes = pl.equalization_control_lanes(LinkWidth::X32);
+                let _ = format!("{:?}", lanes.last());
+            }
+            ExtendedCapabilityKind::LaneMarginingAtTheReceiver(lmr) => {
+                let _ = format!("{:?}", lmr.lanes(LinkWidth::X32).last());
+            }
+            _ => (),
+        }
+    }
+    let _ = format!("{:?}", cs.parse_report());
+    let _ = format!("{:?}", InterruptVectors::from_configuration_space(&cs));
+    let _ = format!(
+        "{:?}",
+        AdvancedErrorInterrupt::from_configuration_space(&cs)
+    );
+    let _ = Lanes::from_configuration_space(&cs).map(|lanes| lanes.count());
+    let _ = MisalignedLinks::from_configuration_space(&cs).count();
+    let _ = format!("{:?}", RoutingIdUsage::from_configuration_space(&cs));
+    let _ = format!("{:?}", TphInconsistencies::from_configuration_space(&cs));
+    let _ = format!("{:?}", ByteBudget::from(cs.capabilities()));
+    if let Some(ecaps) = cs.extended_capabilities() {
+        let _ = ByteBudget::from(ecaps).first_fit(0x40);
+    }
+    let _ = Modalias::from(&cs).to_string();
+
+    let mut image = data.to_vec();
+    let mut editor = ConfigSpaceEditor::new(&mut image);
+    for id in cap_ids {
+        editor.hide_capability(id);
+    }
+    for id in ecap_ids {
+        editor.hide_extended_capability(id);
+    }
+}
+
+/// Parses `data` reporting `name` of the image if parsing panics
+fn check(name: &str, data: &[u8]) {
+    let result = panic::catch_unwind(|| parse(data));
+    assert!(result.is_ok(), "{} panicked", name);
+}
+
+/// Deterministic xorshift sequence, keeps failures reproducible
+struct XorShift(u64);
+
+impl Iterator for XorShift {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        Some(self.0 as usize)
+    }
+}
+
+#[test]
+fn corpus_images() {
+    for (name, data) in corpus() {
+        check(&name, &data);
+    }
+}
+
+#[test]
+fn truncated_images() {
+    for (name, data) in corpus() {
+        for len in (0..data.len())
+            .step_by(0x10)
+            .chain([1, 2, 3, DDR_OFFSET + 1, 0x102, 0xffe])
+        {
+            let name = format!("{} truncated to {} bytes", name, len);
+            check(&name, data.get(..len).unwrap_or(&data));
+        }
+    }
+}
+
+#[test]
+fn mutated_images() {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    for (name, data) in corpus() {
+        for n in 0..MUTATIONS {
+            let mut data = data.clone();
+            let count = rng.next().unwrap() % 16 + 1;
+            for _ in 0..count {
+                let offset = rng.next().unwrap() % data.len();
+                data[offset] = rng.next().unwrap() as u8;
+            }
+            check(&format!("{} mutation {}", name, n), &data);
+        }
+    }
+}